@@ -0,0 +1,5 @@
+-- Structured reporting period for datasets (e.g. 2024 / "Semester I")
+ALTER TABLE datasets ADD COLUMN period_year INTEGER;
+ALTER TABLE datasets ADD COLUMN period_label TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_datasets_period ON datasets(period_year, period_label);
@@ -0,0 +1,68 @@
+-- Full-text index over employees, datasets and summaries, kept in sync by triggers
+CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5(
+    kind UNINDEXED,
+    ref_id UNINDEXED,
+    title,
+    body,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+INSERT INTO search_index (kind, ref_id, title, body)
+SELECT 'employee', id, name,
+       trim(ifnull(nip, '') || ' ' || ifnull(jabatan, '') || ' ' || ifnull(sub_jabatan, ''))
+FROM employees;
+
+INSERT INTO search_index (kind, ref_id, title, body)
+SELECT 'dataset', id, name, ifnull(description, '')
+FROM datasets;
+
+INSERT INTO search_index (kind, ref_id, title, body)
+SELECT 'summary', id, '', content
+FROM summaries;
+
+CREATE TRIGGER IF NOT EXISTS search_employees_insert AFTER INSERT ON employees BEGIN
+    INSERT INTO search_index (kind, ref_id, title, body)
+    VALUES ('employee', new.id, new.name,
+            trim(ifnull(new.nip, '') || ' ' || ifnull(new.jabatan, '') || ' ' || ifnull(new.sub_jabatan, '')));
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_employees_update AFTER UPDATE ON employees BEGIN
+    DELETE FROM search_index WHERE kind = 'employee' AND ref_id = old.id;
+    INSERT INTO search_index (kind, ref_id, title, body)
+    VALUES ('employee', new.id, new.name,
+            trim(ifnull(new.nip, '') || ' ' || ifnull(new.jabatan, '') || ' ' || ifnull(new.sub_jabatan, '')));
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_employees_delete AFTER DELETE ON employees BEGIN
+    DELETE FROM search_index WHERE kind = 'employee' AND ref_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_datasets_insert AFTER INSERT ON datasets BEGIN
+    INSERT INTO search_index (kind, ref_id, title, body)
+    VALUES ('dataset', new.id, new.name, ifnull(new.description, ''));
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_datasets_update AFTER UPDATE ON datasets BEGIN
+    DELETE FROM search_index WHERE kind = 'dataset' AND ref_id = old.id;
+    INSERT INTO search_index (kind, ref_id, title, body)
+    VALUES ('dataset', new.id, new.name, ifnull(new.description, ''));
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_datasets_delete AFTER DELETE ON datasets BEGIN
+    DELETE FROM search_index WHERE kind = 'dataset' AND ref_id = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_summaries_insert AFTER INSERT ON summaries BEGIN
+    INSERT INTO search_index (kind, ref_id, title, body)
+    VALUES ('summary', new.id, '', new.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_summaries_update AFTER UPDATE ON summaries BEGIN
+    DELETE FROM search_index WHERE kind = 'summary' AND ref_id = old.id;
+    INSERT INTO search_index (kind, ref_id, title, body)
+    VALUES ('summary', new.id, '', new.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_summaries_delete AFTER DELETE ON summaries BEGIN
+    DELETE FROM search_index WHERE kind = 'summary' AND ref_id = old.id;
+END;
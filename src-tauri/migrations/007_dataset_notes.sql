@@ -0,0 +1,11 @@
+-- Timestamped notes / changelog entries attached to a dataset
+CREATE TABLE IF NOT EXISTS dataset_notes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    dataset_id INTEGER NOT NULL,
+    content TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (dataset_id) REFERENCES datasets(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_dataset_notes_dataset ON dataset_notes(dataset_id, created_at);
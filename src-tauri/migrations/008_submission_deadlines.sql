@@ -0,0 +1,3 @@
+-- Submission deadline per dataset and the time each score's response was submitted
+ALTER TABLE datasets ADD COLUMN submission_deadline DATETIME;
+ALTER TABLE scores ADD COLUMN submitted_at DATETIME;
@@ -0,0 +1,20 @@
+-- Reference data from the organizational structure (SOTK) document
+CREATE TABLE IF NOT EXISTS org_units (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL COLLATE NOCASE UNIQUE,
+    code TEXT,
+    parent_id INTEGER,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (parent_id) REFERENCES org_units(id) ON DELETE SET NULL
+);
+
+CREATE TABLE IF NOT EXISTS jabatan_reference (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL COLLATE NOCASE,
+    unit_id INTEGER,
+    eselon TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (unit_id) REFERENCES org_units(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_jabatan_reference_name ON jabatan_reference(name);
@@ -0,0 +1,2 @@
+-- Archived datasets stay queryable but are hidden from the default dataset list
+ALTER TABLE datasets ADD COLUMN archived_at DATETIME;
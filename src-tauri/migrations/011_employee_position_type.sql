@@ -0,0 +1,2 @@
+-- Explicit Eselon/Staff classification; NULL falls back to the jabatan keyword heuristics
+ALTER TABLE employees ADD COLUMN position_type TEXT CHECK (position_type IN ('eselon', 'staff'));
@@ -0,0 +1,18 @@
+-- One row per committed import or append, so a dataset's build-up can be traced
+CREATE TABLE IF NOT EXISTS import_runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    dataset_id INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    source_file TEXT,
+    started_at DATETIME NOT NULL,
+    finished_at DATETIME NOT NULL,
+    row_count INTEGER NOT NULL,
+    inserted_count INTEGER NOT NULL,
+    updated_count INTEGER NOT NULL,
+    skipped_count INTEGER NOT NULL,
+    validation_errors INTEGER,
+    validation_warnings INTEGER,
+    FOREIGN KEY (dataset_id) REFERENCES datasets(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_import_runs_dataset ON import_runs(dataset_id, started_at);
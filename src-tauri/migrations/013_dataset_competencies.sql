@@ -0,0 +1,14 @@
+-- Competency order as it appeared in each dataset's source form; overrides the global order
+CREATE TABLE IF NOT EXISTS dataset_competencies (
+    dataset_id INTEGER NOT NULL,
+    competency_id INTEGER NOT NULL,
+    display_order INTEGER NOT NULL,
+    PRIMARY KEY (dataset_id, competency_id),
+    FOREIGN KEY (dataset_id) REFERENCES datasets(id) ON DELETE CASCADE,
+    FOREIGN KEY (competency_id) REFERENCES competencies(id) ON DELETE CASCADE
+);
+
+INSERT OR IGNORE INTO dataset_competencies (dataset_id, competency_id, display_order)
+SELECT DISTINCT s.dataset_id, c.id, c.display_order
+FROM scores s
+JOIN competencies c ON c.id = s.competency_id;
@@ -0,0 +1,2 @@
+-- Number of form responses behind a score; NULL when the source was already one value per cell
+ALTER TABLE scores ADD COLUMN respondent_count INTEGER;
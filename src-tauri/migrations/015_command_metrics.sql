@@ -0,0 +1,10 @@
+-- Round-trip timings of IPC commands as measured by the frontend invoke wrapper
+CREATE TABLE IF NOT EXISTS command_metrics (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    command TEXT NOT NULL,
+    duration_ms REAL NOT NULL,
+    row_count INTEGER,
+    recorded_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_command_metrics_command ON command_metrics(command);
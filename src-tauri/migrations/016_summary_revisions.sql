@@ -0,0 +1,15 @@
+-- Every saved version of an employee summary, newest last
+CREATE TABLE IF NOT EXISTS summary_revisions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    employee_id INTEGER NOT NULL,
+    content TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (employee_id) REFERENCES employees(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_summary_revisions_employee ON summary_revisions(employee_id, created_at);
+
+-- Existing summaries become the first revision
+INSERT INTO summary_revisions (employee_id, content, created_at)
+SELECT employee_id, content, updated_at
+FROM summaries;
@@ -0,0 +1,68 @@
+-- Summaries belong to an employee within a dataset so each period keeps its own narrative.
+-- Existing summaries move to the latest dataset the employee has scores in (or the latest
+-- dataset overall); summaries that cannot be placed in any dataset are dropped.
+CREATE TABLE summaries_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    employee_id INTEGER NOT NULL,
+    dataset_id INTEGER NOT NULL,
+    content TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (employee_id) REFERENCES employees(id) ON DELETE CASCADE,
+    FOREIGN KEY (dataset_id) REFERENCES datasets(id) ON DELETE CASCADE,
+    UNIQUE (employee_id, dataset_id)
+);
+
+INSERT INTO summaries_new (id, employee_id, dataset_id, content, created_at, updated_at)
+SELECT id, employee_id, dataset_id, content, created_at, updated_at
+FROM (
+    SELECT s.id, s.employee_id, s.content, s.created_at, s.updated_at,
+           COALESCE(
+               (SELECT sc.dataset_id
+                FROM scores sc
+                JOIN datasets d ON d.id = sc.dataset_id
+                WHERE sc.employee_id = s.employee_id
+                ORDER BY d.created_at DESC, d.id DESC
+                LIMIT 1),
+               (SELECT id FROM datasets ORDER BY created_at DESC, id DESC LIMIT 1)
+           ) AS dataset_id
+    FROM summaries s
+    JOIN employees e ON e.id = s.employee_id
+)
+WHERE dataset_id IS NOT NULL;
+
+DROP TABLE summaries;
+ALTER TABLE summaries_new RENAME TO summaries;
+
+CREATE INDEX IF NOT EXISTS idx_summaries_dataset ON summaries(dataset_id);
+
+DELETE FROM search_index
+WHERE kind = 'summary' AND ref_id NOT IN (SELECT id FROM summaries);
+
+CREATE TRIGGER IF NOT EXISTS search_summaries_insert AFTER INSERT ON summaries BEGIN
+    INSERT INTO search_index (kind, ref_id, title, body)
+    VALUES ('summary', new.id, '', new.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_summaries_update AFTER UPDATE ON summaries BEGIN
+    DELETE FROM search_index WHERE kind = 'summary' AND ref_id = old.id;
+    INSERT INTO search_index (kind, ref_id, title, body)
+    VALUES ('summary', new.id, '', new.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS search_summaries_delete AFTER DELETE ON summaries BEGIN
+    DELETE FROM search_index WHERE kind = 'summary' AND ref_id = old.id;
+END;
+
+-- Revisions follow their summary into its dataset
+ALTER TABLE summary_revisions ADD COLUMN dataset_id INTEGER REFERENCES datasets(id) ON DELETE CASCADE;
+
+UPDATE summary_revisions
+SET dataset_id = (
+    SELECT s.dataset_id FROM summaries s WHERE s.employee_id = summary_revisions.employee_id
+);
+
+DELETE FROM summary_revisions WHERE dataset_id IS NULL;
+
+DROP INDEX IF EXISTS idx_summary_revisions_employee;
+CREATE INDEX IF NOT EXISTS idx_summary_revisions_employee ON summary_revisions(employee_id, dataset_id, created_at);
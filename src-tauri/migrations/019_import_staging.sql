@@ -0,0 +1,18 @@
+-- Scores of a running performance import, staged in batches so large files are never held
+-- in memory at once. Rows only live inside the import transaction.
+CREATE TABLE IF NOT EXISTS import_staging (
+    seq INTEGER PRIMARY KEY,
+    employee_name TEXT NOT NULL,
+    normalized_name TEXT NOT NULL,
+    competency TEXT NOT NULL,
+    raw_value TEXT NOT NULL,
+    numeric_value REAL,
+    submitted_at DATETIME,
+    timestamped INTEGER NOT NULL DEFAULT 0,
+    employee_id INTEGER,
+    competency_id INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_import_staging_employee ON import_staging(normalized_name);
+CREATE INDEX IF NOT EXISTS idx_import_staging_competency ON import_staging(competency);
+CREATE INDEX IF NOT EXISTS idx_import_staging_pair ON import_staging(employee_id, competency_id);
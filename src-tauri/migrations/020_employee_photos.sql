@@ -0,0 +1,3 @@
+-- Resized profile photo stored in the photos directory next to the database; photo_path
+-- is relative to the database directory.
+ALTER TABLE employees ADD COLUMN photo_path TEXT;
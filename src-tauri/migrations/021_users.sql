@@ -0,0 +1,8 @@
+-- Local accounts. Until the first account exists every caller is treated as an admin.
+CREATE TABLE IF NOT EXISTS users (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    username TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    password_hash TEXT NOT NULL,
+    role TEXT NOT NULL CHECK (role IN ('admin', 'editor', 'viewer')),
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,9 @@
+-- Free-form labels for grouping and filtering datasets, e.g. "semester-1" or "pilot".
+CREATE TABLE IF NOT EXISTS dataset_tags (
+    dataset_id INTEGER NOT NULL,
+    tag TEXT NOT NULL COLLATE NOCASE,
+    PRIMARY KEY (dataset_id, tag),
+    FOREIGN KEY (dataset_id) REFERENCES datasets(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_dataset_tags_tag ON dataset_tags(tag);
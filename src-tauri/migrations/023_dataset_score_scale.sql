@@ -0,0 +1,3 @@
+-- Maximum raw score of a dataset, used to normalise scores to 0-100.
+-- NULL keeps detecting the scale from the highest score.
+ALTER TABLE datasets ADD COLUMN score_scale REAL;
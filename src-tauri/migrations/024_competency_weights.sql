@@ -0,0 +1,10 @@
+-- Explicit mapping of a competency onto a report parameter, per position type. Parameters
+-- without a mapping keep matching competency names by their aliases.
+CREATE TABLE IF NOT EXISTS competency_weights (
+    competency_id INTEGER NOT NULL,
+    position_type TEXT NOT NULL CHECK (position_type IN ('eselon', 'staff')),
+    parameter TEXT NOT NULL,
+    weight REAL NOT NULL,
+    PRIMARY KEY (competency_id, position_type),
+    FOREIGN KEY (competency_id) REFERENCES competencies(id) ON DELETE CASCADE
+);
@@ -0,0 +1,12 @@
+-- Rating predicates of one dataset. A dataset without rows uses the bands in the UI config.
+CREATE TABLE IF NOT EXISTS rating_bands (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    dataset_id INTEGER NOT NULL,
+    label TEXT NOT NULL,
+    min_score REAL NOT NULL,
+    max_score REAL,
+    color TEXT NOT NULL,
+    FOREIGN KEY (dataset_id) REFERENCES datasets(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_rating_bands_dataset ON rating_bands(dataset_id);
@@ -0,0 +1,8 @@
+-- Where an imported score came from, to trace a suspicious value back to its source cell.
+-- NULL for scores entered by hand or imported before provenance was recorded.
+ALTER TABLE scores ADD COLUMN source_row INTEGER;
+ALTER TABLE scores ADD COLUMN source_column TEXT;
+ALTER TABLE scores ADD COLUMN import_run_id INTEGER REFERENCES import_runs(id) ON DELETE SET NULL;
+
+ALTER TABLE import_staging ADD COLUMN source_row INTEGER;
+ALTER TABLE import_staging ADD COLUMN source_column TEXT;
@@ -0,0 +1,3 @@
+-- Staged rows now carry how many responses they stand for, which is more than one for
+-- form answers aggregated per employee before the import.
+ALTER TABLE import_staging RENAME COLUMN timestamped TO respondents;
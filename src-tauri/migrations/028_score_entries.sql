@@ -0,0 +1,35 @@
+-- Individual values behind a score, one per rater or import source. The score itself holds
+-- the dataset's aggregation of its entries, see `datasets.score_aggregation`.
+CREATE TABLE IF NOT EXISTS score_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    dataset_id INTEGER NOT NULL,
+    employee_id INTEGER NOT NULL,
+    competency_id INTEGER NOT NULL,
+    rater TEXT NOT NULL,
+    raw_value TEXT NOT NULL,
+    numeric_value REAL,
+    import_run_id INTEGER REFERENCES import_runs(id) ON DELETE SET NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (dataset_id) REFERENCES datasets(id) ON DELETE CASCADE,
+    FOREIGN KEY (employee_id) REFERENCES employees(id) ON DELETE CASCADE,
+    FOREIGN KEY (competency_id) REFERENCES competencies(id) ON DELETE CASCADE,
+    UNIQUE(dataset_id, employee_id, competency_id, rater)
+);
+
+CREATE INDEX IF NOT EXISTS idx_score_entries_pair ON score_entries(dataset_id, employee_id, competency_id);
+
+-- mean, median or mode; NULL means mean
+ALTER TABLE datasets ADD COLUMN score_aggregation TEXT;
+
+-- Scores written before entries existed count as one rater, so a later import is combined
+-- with them instead of replacing them
+INSERT OR IGNORE INTO score_entries (dataset_id, employee_id, competency_id, rater, raw_value, numeric_value, import_run_id, created_at)
+SELECT dataset_id, employee_id, competency_id, 'legacy', raw_value, numeric_value, import_run_id, created_at
+FROM scores;
+
+-- A removed score takes its entries with it, so they cannot resurface in a later aggregation
+CREATE TRIGGER IF NOT EXISTS score_entries_follow_scores AFTER DELETE ON scores BEGIN
+    DELETE FROM score_entries
+    WHERE dataset_id = old.dataset_id AND employee_id = old.employee_id
+      AND competency_id = old.competency_id;
+END;
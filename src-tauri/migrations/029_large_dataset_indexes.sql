@@ -0,0 +1,12 @@
+-- Indexes for datasets with 100k+ scores. Per-dataset score lookups by employee are
+-- already served by the UNIQUE (dataset_id, employee_id, competency_id) index of scores.
+CREATE INDEX IF NOT EXISTS idx_scores_dataset_competency ON scores(dataset_id, competency_id);
+CREATE INDEX IF NOT EXISTS idx_employees_lower_name ON employees(lower(name));
+CREATE INDEX IF NOT EXISTS idx_dataset_employees_employee_dataset ON dataset_employees(employee_id, dataset_id);
+
+-- Covered by the indexes above or by a primary key / unique constraint prefix
+DROP INDEX IF EXISTS idx_scores_dataset;
+DROP INDEX IF EXISTS idx_dataset_employees_dataset;
+DROP INDEX IF EXISTS idx_dataset_employees_employee;
+
+ANALYZE;
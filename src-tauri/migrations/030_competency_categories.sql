@@ -0,0 +1,12 @@
+-- Groups of competencies (e.g. Perilaku, Kualitas, Teknis) averaged together in analytics.
+CREATE TABLE IF NOT EXISTS competency_categories (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT,
+    display_order INTEGER NOT NULL DEFAULT 0
+);
+
+ALTER TABLE competencies ADD COLUMN category_id INTEGER
+    REFERENCES competency_categories(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_competencies_category ON competencies(category_id);
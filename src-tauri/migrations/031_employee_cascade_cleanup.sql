@@ -0,0 +1,8 @@
+-- Every table holding employee rows declares ON DELETE CASCADE; deleting an employee now relies
+-- on it. Remove rows orphaned while enforcement was off so PRAGMA foreign_key_check is clean.
+DELETE FROM dataset_employees WHERE employee_id NOT IN (SELECT id FROM employees);
+DELETE FROM scores WHERE employee_id NOT IN (SELECT id FROM employees);
+DELETE FROM score_entries WHERE employee_id NOT IN (SELECT id FROM employees);
+DELETE FROM summaries WHERE employee_id NOT IN (SELECT id FROM employees);
+DELETE FROM summary_revisions WHERE employee_id NOT IN (SELECT id FROM employees);
+DELETE FROM attachments WHERE employee_id NOT IN (SELECT id FROM employees);
@@ -0,0 +1,12 @@
+-- Penilaian Pimpinan of one employee in a dataset, imported from the leadership sheet.
+-- Employees without a row use the default leadership score.
+CREATE TABLE IF NOT EXISTS leadership_scores (
+    dataset_id INTEGER NOT NULL,
+    employee_id INTEGER NOT NULL,
+    score REAL NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (dataset_id, employee_id),
+    FOREIGN KEY (dataset_id) REFERENCES datasets(id) ON DELETE CASCADE,
+    FOREIGN KEY (employee_id) REFERENCES employees(id) ON DELETE CASCADE
+);
@@ -0,0 +1,15 @@
+-- Monthly attendance per employee from the attendance system; `month` is `YYYY-MM`.
+CREATE TABLE IF NOT EXISTS attendance (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    employee_id INTEGER NOT NULL,
+    month TEXT NOT NULL,
+    working_days INTEGER NOT NULL,
+    present_days INTEGER NOT NULL,
+    late_days INTEGER NOT NULL DEFAULT 0,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (employee_id) REFERENCES employees(id) ON DELETE CASCADE,
+    UNIQUE (employee_id, month)
+);
+
+CREATE INDEX IF NOT EXISTS idx_attendance_month ON attendance(month);
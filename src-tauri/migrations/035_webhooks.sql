@@ -0,0 +1,11 @@
+-- Intranet endpoints notified when an import or merge finishes
+CREATE TABLE IF NOT EXISTS webhooks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    url TEXT NOT NULL UNIQUE,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    -- Outcome of the latest delivery: 'delivered' or 'failed'
+    last_status TEXT,
+    last_error TEXT,
+    last_attempt_at DATETIME,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,34 @@
+-- NIP identifies an employee across name changes. It is stored as digits only, the form every
+-- lookup uses, so "19850101 201001 1 001" and "198501012010011001" are one key.
+UPDATE employees SET nip = NULL WHERE TRIM(nip) = '';
+WITH RECURSIVE nip_digits(id, rest, digits) AS (
+    SELECT id, nip, '' FROM employees WHERE nip IS NOT NULL
+    UNION ALL
+    SELECT id,
+           SUBSTR(rest, 2),
+           digits || CASE WHEN SUBSTR(rest, 1, 1) GLOB '[0-9]' THEN SUBSTR(rest, 1, 1) ELSE '' END
+    FROM nip_digits
+    WHERE rest <> ''
+)
+UPDATE employees
+SET nip = (
+    SELECT NULLIF(d.digits, '') FROM nip_digits d WHERE d.id = employees.id AND d.rest = ''
+)
+WHERE nip IS NOT NULL;
+
+-- Where several records share a NIP the newer ones keep it but are flagged, so the unique
+-- index can be built without losing data. They show up as duplicate suspects in the data
+-- quality report and are listed in the audit log until merged or corrected.
+ALTER TABLE employees ADD COLUMN nip_conflict INTEGER NOT NULL DEFAULT 0;
+UPDATE employees SET nip_conflict = 1
+WHERE nip IS NOT NULL
+  AND EXISTS (
+      SELECT 1 FROM employees older
+      WHERE older.nip = employees.nip AND older.id < employees.id
+  );
+INSERT INTO audit_log (action, details)
+SELECT 'employee_nip_conflict', json_object('employee_id', id, 'nip', nip)
+FROM employees
+WHERE nip_conflict = 1;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_employees_nip ON employees(nip) WHERE nip_conflict = 0;
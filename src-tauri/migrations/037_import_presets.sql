@@ -0,0 +1,15 @@
+-- Column and rating mapping choices reused from one semester's import to the next
+CREATE TABLE IF NOT EXISTS import_presets (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    -- Empty detects the delimiter from the file
+    delimiter TEXT,
+    -- JSON array of {csv_column, db_field}
+    column_mapping TEXT NOT NULL DEFAULT '[]',
+    -- JSON array of {text_value, numeric_value}
+    rating_mappings TEXT NOT NULL DEFAULT '[]',
+    -- Raw score that normalises to 100; NULL detects it from the scores
+    score_scale REAL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,3 @@
+-- none, z_score or min_max; evens out raters before analytics and weighting without
+-- changing the stored scores. NULL means none
+ALTER TABLE datasets ADD COLUMN score_normalization TEXT;
@@ -0,0 +1,10 @@
+-- Canonical spellings of competency names (numbering stripped, case and punctuation folded)
+-- mapped onto the competency they stand for, so renamed form questions keep one identity
+CREATE TABLE IF NOT EXISTS competency_aliases (
+    alias_key TEXT PRIMARY KEY,
+    competency_id INTEGER NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (competency_id) REFERENCES competencies(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_competency_aliases_competency ON competency_aliases(competency_id);
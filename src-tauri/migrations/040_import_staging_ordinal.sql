@@ -0,0 +1,2 @@
+-- Question number split off a score header; orders the dataset's competencies
+ALTER TABLE import_staging ADD COLUMN ordinal INTEGER;
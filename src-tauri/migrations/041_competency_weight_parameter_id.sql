@@ -0,0 +1,4 @@
+-- Competency weights point at a weighting parameter by its stable id instead of its name, so
+-- renaming the parameter keeps its competencies. Ids start out as the lowercased name.
+ALTER TABLE competency_weights RENAME COLUMN parameter TO parameter_id;
+UPDATE competency_weights SET parameter_id = LOWER(TRIM(parameter_id));
@@ -0,0 +1,11 @@
+-- Shared secret each payload is signed with, so a receiver can check it came from this app
+ALTER TABLE webhooks ADD COLUMN secret TEXT NOT NULL DEFAULT '';
+UPDATE webhooks SET secret = lower(hex(randomblob(32)));
+
+-- Deliveries still in flight; whatever is left when the app exits is resent on the next start
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    webhook_id INTEGER NOT NULL REFERENCES webhooks(id) ON DELETE CASCADE,
+    payload TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
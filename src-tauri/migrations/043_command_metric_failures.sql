@@ -0,0 +1,2 @@
+-- Failed runs are timed too, so the slow command list can tell them apart
+ALTER TABLE command_metrics ADD COLUMN failed INTEGER NOT NULL DEFAULT 0;
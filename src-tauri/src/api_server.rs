@@ -0,0 +1,382 @@
+use crate::commands::analytics::{
+    compute_dataset_comparison, compute_dataset_stats, DatasetComparison, DatasetStats,
+};
+use crate::commands::dataset::{
+    fetch_dataset, fetch_dataset_members, fetch_datasets, DatasetMember,
+};
+use crate::db::audit::record_task_failure;
+use crate::db::models::{Dataset, Employee};
+use crate::db::settings::{get_setting, set_setting};
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::net::IpAddr;
+use subtle::ConstantTimeEq;
+
+const API_SERVER_KEY: &str = "api_server";
+const DEFAULT_PORT: u16 = 8470;
+const API_SERVER_TASK: &str = "api_server";
+
+/// The read-only LAN API. Changes take effect the next time the app starts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiServerSettings {
+    pub enabled: bool,
+    /// Interface to listen on; anything but a loopback address makes the API reachable
+    /// from the LAN and requires an access token
+    pub bind_address: String,
+    pub port: u16,
+    /// When set, requests must send `Authorization: Bearer <token>`. On save, `None` keeps
+    /// the current token and an empty string clears it.
+    pub access_token: Option<String>,
+}
+
+/// The settings as shown to the settings screen; the token never leaves the backend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiServerSettingsView {
+    pub enabled: bool,
+    pub bind_address: String,
+    pub port: u16,
+    pub has_access_token: bool,
+}
+
+impl From<ApiServerSettings> for ApiServerSettingsView {
+    fn from(settings: ApiServerSettings) -> Self {
+        Self {
+            enabled: settings.enabled,
+            bind_address: settings.bind_address,
+            port: settings.port,
+            has_access_token: settings.access_token.is_some(),
+        }
+    }
+}
+
+impl Default for ApiServerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: "127.0.0.1".to_string(),
+            port: DEFAULT_PORT,
+            access_token: None,
+        }
+    }
+}
+
+pub async fn load_settings(pool: &SqlitePool) -> Result<ApiServerSettings, String> {
+    Ok(get_setting(pool, API_SERVER_KEY)
+        .await
+        .map_err(|e| format!("Failed to load API server settings: {}", e))?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default())
+}
+
+/// Refuses to expose the API beyond this computer without an access token.
+fn validate_exposure(settings: &ApiServerSettings) -> Result<(), String> {
+    let address = settings
+        .bind_address
+        .parse::<IpAddr>()
+        .map_err(|_| format!("{} is not a valid IP address", settings.bind_address))?;
+    if !address.is_loopback() && settings.access_token.is_none() {
+        return Err(format!(
+            "Listening on {} makes the API reachable from the network; set an access token first",
+            address
+        ));
+    }
+    Ok(())
+}
+
+pub async fn save_settings(
+    pool: &SqlitePool,
+    settings: ApiServerSettings,
+) -> Result<ApiServerSettings, String> {
+    if settings.port == 0 {
+        return Err("The API server needs a port between 1 and 65535".to_string());
+    }
+    let access_token = match settings.access_token {
+        Some(token) => Some(token.trim().to_string()).filter(|token| !token.is_empty()),
+        None => load_settings(pool).await?.access_token,
+    };
+    let settings = ApiServerSettings {
+        bind_address: settings.bind_address.trim().to_string(),
+        access_token,
+        ..settings
+    };
+    validate_exposure(&settings)?;
+
+    let value = serde_json::to_string(&settings)
+        .map_err(|e| format!("Failed to serialize API server settings: {}", e))?;
+    set_setting(pool, API_SERVER_KEY, &value)
+        .await
+        .map_err(|e| format!("Failed to save API server settings: {}", e))?;
+    Ok(settings)
+}
+
+#[derive(Clone)]
+struct ApiState {
+    pool: SqlitePool,
+    access_token: Option<String>,
+}
+
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+impl From<String> for ApiError {
+    fn from(message: String) -> Self {
+        Self(StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+async fn require_token(
+    State(state): State<ApiState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    if let Some(token) = &state.access_token {
+        let presented = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        let valid = presented
+            .is_some_and(|presented| bool::from(presented.as_bytes().ct_eq(token.as_bytes())));
+        if !valid {
+            return Err(ApiError(
+                StatusCode::UNAUTHORIZED,
+                "Missing or invalid access token".to_string(),
+            ));
+        }
+    }
+    Ok(next.run(request).await)
+}
+
+#[derive(Deserialize)]
+struct DatasetQuery {
+    period_year: Option<i32>,
+    period_label: Option<String>,
+    include_archived: Option<bool>,
+    tag: Option<String>,
+}
+
+async fn datasets(
+    State(state): State<ApiState>,
+    Query(query): Query<DatasetQuery>,
+) -> ApiResult<Vec<Dataset>> {
+    Ok(Json(
+        fetch_datasets(
+            &state.pool,
+            query.period_year,
+            query.period_label.as_deref(),
+            query.include_archived.unwrap_or(false),
+            query.tag.as_deref(),
+        )
+        .await?,
+    ))
+}
+
+async fn dataset(State(state): State<ApiState>, Path(id): Path<i64>) -> ApiResult<Dataset> {
+    fetch_dataset(&state.pool, id)
+        .await
+        .map(Json)
+        .map_err(|_| ApiError(StatusCode::NOT_FOUND, format!("Dataset {} not found", id)))
+}
+
+async fn dataset_employees(
+    State(state): State<ApiState>,
+    Path(id): Path<i64>,
+) -> ApiResult<Vec<DatasetMember>> {
+    Ok(Json(fetch_dataset_members(&state.pool, id).await?))
+}
+
+async fn dataset_stats(
+    State(state): State<ApiState>,
+    Path(id): Path<i64>,
+) -> ApiResult<DatasetStats> {
+    compute_dataset_stats(&state.pool, id)
+        .await
+        .map(Json)
+        .map_err(|e| format!("Failed to compute dataset stats: {}", e).into())
+}
+
+async fn employees(State(state): State<ApiState>) -> ApiResult<Vec<Employee>> {
+    sqlx::query_as::<_, Employee>("SELECT * FROM employees ORDER BY LOWER(name)")
+        .fetch_all(&state.pool)
+        .await
+        .map(Json)
+        .map_err(|e| format!("Failed to list employees: {}", e).into())
+}
+
+#[derive(Deserialize)]
+struct ComparisonQuery {
+    base: i64,
+    comparison: i64,
+    order_by_period: Option<bool>,
+}
+
+async fn comparison(
+    State(state): State<ApiState>,
+    Query(query): Query<ComparisonQuery>,
+) -> ApiResult<DatasetComparison> {
+    Ok(Json(
+        compute_dataset_comparison(
+            &state.pool,
+            query.base,
+            query.comparison,
+            query.order_by_period.unwrap_or(false),
+        )
+        .await?,
+    ))
+}
+
+fn router(state: ApiState) -> Router {
+    Router::new()
+        .route("/api/datasets", get(datasets))
+        .route("/api/datasets/{id}", get(dataset))
+        .route("/api/datasets/{id}/employees", get(dataset_employees))
+        .route("/api/datasets/{id}/stats", get(dataset_stats))
+        .route("/api/employees", get(employees))
+        .route("/api/comparisons", get(comparison))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .with_state(state)
+}
+
+/// Serves the read-only JSON API in the background when it is enabled in the settings.
+pub fn spawn_api_server(pool: SqlitePool) {
+    tauri::async_runtime::spawn(async move {
+        let settings = match load_settings(&pool).await {
+            Ok(settings) if !settings.enabled => return,
+            Ok(settings) => validate_exposure(&settings).map(|_| settings),
+            Err(error) => Err(error),
+        };
+        let settings = match settings {
+            Ok(settings) => settings,
+            Err(error) => {
+                let error = format!("API server not started: {}", error);
+                record_task_failure(&pool, API_SERVER_TASK, &error).await;
+                return;
+            }
+        };
+        let address = format!("{}:{}", settings.bind_address, settings.port);
+        let listener = match tokio::net::TcpListener::bind(&address).await {
+            Ok(listener) => listener,
+            Err(error) => {
+                let error = format!("API server could not listen on {}: {}", address, error);
+                record_task_failure(&pool, API_SERVER_TASK, &error).await;
+                return;
+            }
+        };
+        let app = router(ApiState {
+            pool: pool.clone(),
+            access_token: settings.access_token,
+        });
+        if let Err(error) = axum::serve(listener, app).await {
+            let error = format!("API server stopped: {}", error);
+            record_task_failure(&pool, API_SERVER_TASK, &error).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::analytics::ANONYMITY_FLOOR_KEY;
+    use crate::db::test_pool;
+    use axum::body::{to_bytes, Body};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_save_settings_validates_and_trims() {
+        let pool = test_pool().await;
+
+        assert!(!load_settings(&pool).await.unwrap().enabled);
+        let invalid = ApiServerSettings {
+            bind_address: "lan".to_string(),
+            ..ApiServerSettings::default()
+        };
+        assert!(save_settings(&pool, invalid).await.is_err());
+
+        let saved = save_settings(
+            &pool,
+            ApiServerSettings {
+                enabled: true,
+                access_token: Some("  ".to_string()),
+                ..ApiServerSettings::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(saved.access_token, None);
+        assert!(load_settings(&pool).await.unwrap().enabled);
+
+        let lan = ApiServerSettings {
+            enabled: true,
+            bind_address: "0.0.0.0".to_string(),
+            ..ApiServerSettings::default()
+        };
+        assert!(save_settings(&pool, lan.clone()).await.is_err());
+        let with_token = ApiServerSettings {
+            access_token: Some("rahasia".to_string()),
+            ..lan.clone()
+        };
+        save_settings(&pool, with_token).await.unwrap();
+        // Leaving the token out keeps it; an empty one clears it again
+        let kept = save_settings(&pool, lan.clone()).await.unwrap();
+        assert_eq!(kept.access_token.as_deref(), Some("rahasia"));
+        let cleared = ApiServerSettings {
+            access_token: Some(String::new()),
+            ..lan
+        };
+        assert!(save_settings(&pool, cleared).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_router_requires_the_token_and_applies_the_respondent_floor() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO datasets (id, name, created_at, updated_at)
+                VALUES (1, 'Ganjil 2024', datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, created_at, updated_at)
+                VALUES (1, 'Budi', datetime('now'), datetime('now'));
+             INSERT INTO competencies (id, name, display_order) VALUES
+                (1, 'Kerjasama', 0), (2, 'Inisiatif', 1);
+             INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, respondent_count, created_at) VALUES
+                (1, 1, 1, 'Baik', 75, 5, datetime('now')),
+                (1, 1, 2, 'Sangat Baik', 85, 2, datetime('now'));",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        set_setting(&pool, ANONYMITY_FLOOR_KEY, "3").await.unwrap();
+        let app = router(ApiState {
+            pool,
+            access_token: Some("rahasia".to_string()),
+        });
+        let request = |token: &str| {
+            axum::http::Request::get("/api/datasets/1/stats")
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let rejected = app.clone().oneshot(request("salah")).await.unwrap();
+        assert_eq!(rejected.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app.oneshot(request("rahasia")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let stats: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(stats["total_scores"], 1);
+        assert_eq!(stats["competency_stats"].as_array().unwrap().len(), 1);
+    }
+}
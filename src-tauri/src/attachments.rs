@@ -0,0 +1,198 @@
+use crate::db::data_directory;
+use crate::db::models::Attachment;
+use chrono::Utc;
+use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
+
+const ATTACHMENT_DIR: &str = "attachments";
+const MAX_FILENAME_CHARS: usize = 100;
+
+async fn attachment_directory(pool: &SqlitePool) -> Result<PathBuf, String> {
+    Ok(data_directory(pool).await?.join(ATTACHMENT_DIR))
+}
+
+/// Keeps letters, digits, `-`, `_` and `.`; anything else becomes `_` so the stored name
+/// is safe on every platform and cannot escape the attachments directory.
+fn sanitize_filename(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.') {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let trimmed = sanitized.trim_matches(|ch| ch == '.' || ch == '_');
+    // Keep the tail so the extension survives truncation
+    let excess = trimmed.chars().count().saturating_sub(MAX_FILENAME_CHARS);
+    let truncated: String = trimmed.chars().skip(excess).collect();
+    if truncated.is_empty() {
+        "attachment".to_string()
+    } else {
+        truncated
+    }
+}
+
+fn guess_mime(name: &str) -> &'static str {
+    let extension = Path::new(name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    match extension.as_str() {
+        "pdf" => "application/pdf",
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "txt" => "text/plain",
+        "doc" => "application/msword",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xls" => "application/vnd.ms-excel",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Copies `source` into the attachments directory as `<employee>/<timestamp>-<sanitized name>`.
+/// The copy is made before the row is written, so no transaction waits on the disk, and is
+/// removed again when the row can't be saved.
+pub async fn add_attachment(
+    pool: &SqlitePool,
+    employee_id: i64,
+    source: &Path,
+) -> Result<Attachment, String> {
+    let metadata =
+        std::fs::metadata(source).map_err(|e| format!("Failed to read attachment: {}", e))?;
+    if !metadata.is_file() {
+        return Err(format!("{} is not a file", source.display()));
+    }
+    let filename = source
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| "Attachment has no file name".to_string())?;
+
+    let exists: Option<i64> = sqlx::query_scalar("SELECT id FROM employees WHERE id = ?")
+        .bind(employee_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to load employee: {}", e))?;
+    if exists.is_none() {
+        return Err(format!("Employee {} not found", employee_id));
+    }
+
+    let stored_path = format!(
+        "{}/{}-{}",
+        employee_id,
+        Utc::now().timestamp_micros(),
+        sanitize_filename(&filename)
+    );
+    let target = attachment_directory(pool).await?.join(&stored_path);
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create attachment directory: {}", e))?;
+    }
+    std::fs::copy(source, &target).map_err(|e| format!("Failed to store attachment: {}", e))?;
+
+    sqlx::query_as::<_, Attachment>(
+        "INSERT INTO attachments (employee_id, filename, mime, stored_path, size_bytes, created_at)
+         VALUES (?, ?, ?, ?, ?, datetime('now'))
+         RETURNING *",
+    )
+    .bind(employee_id)
+    .bind(&filename)
+    .bind(guess_mime(&filename))
+    .bind(&stored_path)
+    .bind(metadata.len() as i64)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| {
+        let _ = std::fs::remove_file(&target);
+        format!("Failed to save attachment: {}", e)
+    })
+}
+
+pub async fn list_attachments(
+    pool: &SqlitePool,
+    employee_id: i64,
+) -> Result<Vec<Attachment>, String> {
+    sqlx::query_as::<_, Attachment>(
+        "SELECT * FROM attachments WHERE employee_id = ? ORDER BY created_at DESC, id DESC",
+    )
+    .bind(employee_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to load attachments: {}", e))
+}
+
+async fn load_attachment(pool: &SqlitePool, attachment_id: i64) -> Result<Attachment, String> {
+    sqlx::query_as::<_, Attachment>("SELECT * FROM attachments WHERE id = ?")
+        .bind(attachment_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to load attachment: {}", e))?
+        .ok_or_else(|| format!("Attachment {} not found", attachment_id))
+}
+
+/// Absolute path of the stored copy; errors when the file has gone missing.
+pub async fn attachment_path(pool: &SqlitePool, attachment_id: i64) -> Result<PathBuf, String> {
+    let attachment = load_attachment(pool, attachment_id).await?;
+    let path = attachment_directory(pool)
+        .await?
+        .join(&attachment.stored_path);
+    if !path.is_file() {
+        return Err(format!("The file for {} is missing", attachment.filename));
+    }
+    Ok(path)
+}
+
+pub async fn delete_attachment(pool: &SqlitePool, attachment_id: i64) -> Result<(), String> {
+    let attachment = load_attachment(pool, attachment_id).await?;
+    sqlx::query("DELETE FROM attachments WHERE id = ?")
+        .bind(attachment_id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to delete attachment: {}", e))?;
+
+    let path = attachment_directory(pool)
+        .await?
+        .join(&attachment.stored_path);
+    match std::fs::remove_file(&path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(format!("Failed to remove attachment file: {}", e))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Removes the stored files of deleted employees; their rows go with the employee.
+pub(crate) async fn remove_employee_files(
+    pool: &SqlitePool,
+    employee_ids: &[i64],
+) -> Result<(), String> {
+    let directory = attachment_directory(pool).await?;
+    for id in employee_ids {
+        let path = directory.join(id.to_string());
+        if path.is_dir() {
+            std::fs::remove_dir_all(&path)
+                .map_err(|e| format!("Failed to remove attachment files: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_filename_strips_path_and_unsafe_characters() {
+        assert_eq!(
+            sanitize_filename("SK Kenaikan (2024).pdf"),
+            "SK_Kenaikan__2024_.pdf"
+        );
+        assert_eq!(sanitize_filename("../../etc/passwd"), "etc_passwd");
+        assert_eq!(sanitize_filename("..."), "attachment");
+        assert_eq!(guess_mime("scan.JPG"), "image/jpeg");
+    }
+}
@@ -0,0 +1,284 @@
+use crate::db::audit::record_audit;
+use crate::db::models::User;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock};
+
+const MIN_PASSWORD_CHARS: usize = 8;
+
+/// Account roles, lowest first: viewers only read, editors import and change data, admins
+/// also delete and manage accounts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Viewer,
+    Editor,
+    Admin,
+}
+
+impl Role {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Viewer => "viewer",
+            Self::Editor => "editor",
+            Self::Admin => "admin",
+        }
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "viewer" => Ok(Self::Viewer),
+            "editor" => Ok(Self::Editor),
+            "admin" => Ok(Self::Admin),
+            other => Err(format!("Unknown role: {}", other)),
+        }
+    }
+}
+
+/// The signed-in account, shared by every command through `AppState`.
+#[derive(Debug, Clone, Default)]
+pub struct Session {
+    user: Arc<Mutex<Option<User>>>,
+}
+
+impl Session {
+    pub fn current(&self) -> Option<User> {
+        self.user.lock().ok().and_then(|user| user.clone())
+    }
+
+    fn set(&self, user: Option<User>) {
+        if let Ok(mut current) = self.user.lock() {
+            *current = user;
+        }
+    }
+}
+
+fn hash_password(password: &str) -> Result<String, String> {
+    if password.chars().count() < MIN_PASSWORD_CHARS {
+        return Err(format!(
+            "The password needs at least {} characters",
+            MIN_PASSWORD_CHARS
+        ));
+    }
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| format!("Failed to hash password: {}", e))
+}
+
+/// Checked when the username does not exist, so an unknown account takes as long to
+/// reject as a wrong password.
+fn dummy_password_hash() -> &'static str {
+    static HASH: OnceLock<String> = OnceLock::new();
+    HASH.get_or_init(|| {
+        let salt = SaltString::generate(&mut OsRng);
+        Argon2::default()
+            .hash_password(b"no such account", &salt)
+            .map(|hash| hash.to_string())
+            .unwrap_or_default()
+    })
+}
+
+/// Errors unless the signed-in user has at least `role`. The role is read from the
+/// database so changes and deleted accounts apply at once. Until the first account is
+/// created every caller counts as an admin, so single-user installs keep working.
+pub async fn require_role(pool: &SqlitePool, session: &Session, role: Role) -> Result<(), String> {
+    let (has_users, user_role): (bool, Option<String>) = sqlx::query_as(
+        "SELECT EXISTS (SELECT 1 FROM users), (SELECT role FROM users WHERE id = ?)",
+    )
+    .bind(session.current().map(|user| user.id))
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Failed to load user: {}", e))?;
+
+    let user_role = match user_role {
+        Some(user_role) => Role::from_str(&user_role)?,
+        None if has_users => return Err("Sign in to continue".to_string()),
+        None => Role::Admin,
+    };
+    if user_role < role {
+        return Err(format!("This action needs the {} role", role.as_str()));
+    }
+    Ok(())
+}
+
+pub async fn login(
+    pool: &SqlitePool,
+    session: &Session,
+    username: &str,
+    password: &str,
+) -> Result<User, String> {
+    let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE username = ?")
+        .bind(username.trim())
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to load user: {}", e))?;
+    let stored_hash = match &user {
+        Some(user) => user.password_hash.as_str(),
+        None => dummy_password_hash(),
+    };
+    let verified = PasswordHash::new(stored_hash).is_ok_and(|hash| {
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok()
+    });
+    let user = user
+        .filter(|_| verified)
+        .ok_or_else(|| "Incorrect username or password".to_string())?;
+
+    record_audit(
+        pool,
+        "user_logged_in",
+        None,
+        &serde_json::json!({ "user_id": user.id, "username": user.username }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    session.set(Some(user.clone()));
+    Ok(user)
+}
+
+pub fn logout(session: &Session) {
+    session.set(None);
+}
+
+/// Creates an account; the first one has to be an admin so the app cannot lock itself out.
+pub async fn create_user(
+    pool: &SqlitePool,
+    username: &str,
+    password: &str,
+    role: Role,
+) -> Result<User, String> {
+    let username = username.trim();
+    if username.is_empty() {
+        return Err("Username cannot be empty".to_string());
+    }
+    let password_hash = hash_password(password)?;
+
+    // The first-admin rule is part of the insert so two concurrent signups cannot both
+    // create a non-admin first account
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let user = sqlx::query_as::<_, User>(
+        "INSERT INTO users (username, password_hash, role)
+         SELECT ?1, ?2, ?3 WHERE ?3 = 'admin' OR EXISTS (SELECT 1 FROM users)
+         RETURNING *",
+    )
+    .bind(username)
+    .bind(password_hash)
+    .bind(role.as_str())
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| match e.as_database_error() {
+        Some(db_error) if db_error.is_unique_violation() => {
+            format!("User {} already exists", username)
+        }
+        _ => format!("Failed to create user: {}", e),
+    })?
+    .ok_or_else(|| "The first account must be an admin".to_string())?;
+
+    record_audit(
+        &mut *tx,
+        "user_created",
+        None,
+        &serde_json::json!({ "user_id": user.id, "username": user.username, "role": user.role }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    Ok(user)
+}
+
+pub async fn list_users(pool: &SqlitePool) -> Result<Vec<User>, String> {
+    sqlx::query_as::<_, User>("SELECT * FROM users ORDER BY LOWER(username)")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to load users: {}", e))
+}
+
+/// Deletes an account other than the caller's own; the last admin cannot be removed.
+pub async fn delete_user(pool: &SqlitePool, session: &Session, user_id: i64) -> Result<(), String> {
+    if session.current().is_some_and(|user| user.id == user_id) {
+        return Err("You cannot delete your own account".to_string());
+    }
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = ?")
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to load user: {}", e))?
+        .ok_or_else(|| format!("User {} not found", user_id))?;
+    // Checked by the delete itself so a concurrent delete cannot remove the other admin
+    let deleted = sqlx::query(
+        "DELETE FROM users WHERE id = ?
+         AND (role != 'admin' OR (SELECT COUNT(*) FROM users WHERE role = 'admin') > 1)",
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to delete user: {}", e))?
+    .rows_affected();
+    if deleted == 0 {
+        return Err("The last admin account cannot be deleted".to_string());
+    }
+    record_audit(
+        &mut *tx,
+        "user_deleted",
+        None,
+        &serde_json::json!({ "user_id": user.id, "username": user.username }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    #[tokio::test]
+    async fn test_roles_apply_once_the_first_admin_exists() {
+        let pool = test_pool().await;
+        let session = Session::default();
+
+        require_role(&pool, &session, Role::Admin).await.unwrap();
+        assert!(create_user(&pool, "staf", "rahasia123", Role::Viewer)
+            .await
+            .is_err());
+        create_user(&pool, "admin", "rahasia123", Role::Admin)
+            .await
+            .unwrap();
+        create_user(&pool, "staf", "rahasia123", Role::Viewer)
+            .await
+            .unwrap();
+        assert!(require_role(&pool, &session, Role::Viewer).await.is_err());
+
+        assert!(login(&pool, &session, "staf", "salah-sandi").await.is_err());
+        assert!(login(&pool, &session, "tamu", "rahasia123").await.is_err());
+        login(&pool, &session, "STAF", "rahasia123").await.unwrap();
+        require_role(&pool, &session, Role::Viewer).await.unwrap();
+        assert!(require_role(&pool, &session, Role::Editor).await.is_err());
+
+        logout(&session);
+        assert!(session.current().is_none());
+
+        let admin_id: i64 = sqlx::query_scalar("SELECT id FROM users WHERE username = 'admin'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(delete_user(&pool, &session, admin_id).await.is_err());
+    }
+}
@@ -0,0 +1,471 @@
+use crate::db::audit::{record_audit, record_task_failure};
+use crate::db::data_directory;
+use crate::db::settings::{get_setting_or, set_setting};
+use crate::encryption::is_encrypted;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{ConnectOptions, Connection, SqliteConnection, SqlitePool};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const BACKUP_DIR: &str = "backups";
+const SCHEDULED_LABEL: &str = "scheduled";
+const BACKUP_TASK: &str = "scheduled_backup";
+
+const ENABLED_KEY: &str = "backup.enabled";
+const INTERVAL_KEY: &str = "backup.interval_hours";
+const KEEP_LAST_KEY: &str = "backup.keep_last";
+const DEFAULT_INTERVAL_HOURS: i64 = 24;
+const MIN_INTERVAL_HOURS: i64 = 1;
+const MAX_INTERVAL_HOURS: i64 = 24 * 30;
+const DEFAULT_KEEP_LAST: i64 = 7;
+const MIN_KEEP_LAST: i64 = 1;
+const MAX_KEEP_LAST: i64 = 100;
+/// How often the background task wakes up to check whether a backup is due.
+const CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+/// Attached name of the backup being restored.
+const RESTORE_SCHEMA: &str = "restore_source";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupSettings {
+    pub enabled: bool,
+    pub interval_hours: i64,
+    /// Scheduled backups kept; manual and pre-reset snapshots are never pruned.
+    pub keep_last: i64,
+    pub last_backup_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupFile {
+    pub file_name: String,
+    pub size_bytes: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupRestore {
+    pub restored: BackupFile,
+    /// Snapshot of the data that was replaced
+    pub safety_backup: String,
+}
+
+pub async fn load_settings(pool: &SqlitePool) -> Result<BackupSettings, String> {
+    let read_error = |e: sqlx::Error| format!("Failed to load backup settings: {}", e);
+    Ok(BackupSettings {
+        enabled: get_setting_or(pool, ENABLED_KEY, true)
+            .await
+            .map_err(read_error)?,
+        interval_hours: get_setting_or(pool, INTERVAL_KEY, DEFAULT_INTERVAL_HOURS)
+            .await
+            .map_err(read_error)?
+            .clamp(MIN_INTERVAL_HOURS, MAX_INTERVAL_HOURS),
+        keep_last: get_setting_or(pool, KEEP_LAST_KEY, DEFAULT_KEEP_LAST)
+            .await
+            .map_err(read_error)?
+            .clamp(MIN_KEEP_LAST, MAX_KEEP_LAST),
+        last_backup_at: list_backups(pool)
+            .await?
+            .into_iter()
+            .find(|backup| is_scheduled(&backup.file_name))
+            .map(|backup| backup.created_at),
+    })
+}
+
+pub async fn save_settings(
+    pool: &SqlitePool,
+    enabled: bool,
+    interval_hours: i64,
+    keep_last: i64,
+) -> Result<BackupSettings, String> {
+    if !(MIN_INTERVAL_HOURS..=MAX_INTERVAL_HOURS).contains(&interval_hours) {
+        return Err(format!(
+            "Backup interval must be between {} and {} hours",
+            MIN_INTERVAL_HOURS, MAX_INTERVAL_HOURS
+        ));
+    }
+    if !(MIN_KEEP_LAST..=MAX_KEEP_LAST).contains(&keep_last) {
+        return Err(format!(
+            "Backups to keep must be between {} and {}",
+            MIN_KEEP_LAST, MAX_KEEP_LAST
+        ));
+    }
+
+    for (key, value) in [
+        (ENABLED_KEY, enabled.to_string()),
+        (INTERVAL_KEY, interval_hours.to_string()),
+        (KEEP_LAST_KEY, keep_last.to_string()),
+    ] {
+        set_setting(pool, key, &value)
+            .await
+            .map_err(|e| format!("Failed to save backup settings: {}", e))?;
+    }
+
+    load_settings(pool).await
+}
+
+/// `backups/` next to the database file, i.e. inside the app data directory.
+pub(crate) async fn backup_directory(pool: &SqlitePool) -> Result<PathBuf, String> {
+    Ok(data_directory(pool).await?.join(BACKUP_DIR))
+}
+
+fn is_scheduled(file_name: &str) -> bool {
+    file_name.starts_with(&format!("epa-{}-", SCHEDULED_LABEL))
+}
+
+/// Snapshots the database with `VACUUM INTO`; `label` becomes part of the file name.
+pub async fn create_backup(pool: &SqlitePool, label: &str) -> Result<PathBuf, String> {
+    let directory = backup_directory(pool).await?;
+    std::fs::create_dir_all(&directory)
+        .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+
+    // `VACUUM INTO` refuses an existing file, so a backup within the same millisecond
+    // gets a counter
+    let stamp = Utc::now().format("%Y%m%d-%H%M%S%3f").to_string();
+    let mut path = directory.join(format!("epa-{}-{}.db", label, stamp));
+    let mut copy = 1;
+    while path.exists() {
+        copy += 1;
+        path = directory.join(format!("epa-{}-{}-{}.db", label, stamp, copy));
+    }
+    sqlx::query("VACUUM INTO ?")
+        .bind(path.to_string_lossy().to_string())
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to back up database: {}", e))?;
+    Ok(path)
+}
+
+/// Backups on disk, newest first.
+pub async fn list_backups(pool: &SqlitePool) -> Result<Vec<BackupFile>, String> {
+    let directory = backup_directory(pool).await?;
+    if !directory.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups = Vec::new();
+    for entry in std::fs::read_dir(&directory)
+        .map_err(|e| format!("Failed to read backup directory: {}", e))?
+    {
+        let entry = entry.map_err(|e| format!("Failed to read backup directory: {}", e))?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if !file_name.starts_with("epa-") || !file_name.ends_with(".db") {
+            continue;
+        }
+        let metadata = entry
+            .metadata()
+            .map_err(|e| format!("Failed to read backup {}: {}", file_name, e))?;
+        backups.push(BackupFile {
+            file_name,
+            size_bytes: metadata.len(),
+            created_at: metadata
+                .modified()
+                .map_err(|e| format!("Failed to read backup time: {}", e))?
+                .into(),
+        });
+    }
+    backups.sort_by(|a, b| {
+        b.created_at
+            .cmp(&a.created_at)
+            .then_with(|| b.file_name.cmp(&a.file_name))
+    });
+    Ok(backups)
+}
+
+/// Deletes scheduled backups beyond the newest `keep_last`.
+async fn prune_scheduled(pool: &SqlitePool, keep_last: i64) -> Result<(), String> {
+    let directory = backup_directory(pool).await?;
+    for backup in list_backups(pool)
+        .await?
+        .into_iter()
+        .filter(|backup| is_scheduled(&backup.file_name))
+        .skip(keep_last as usize)
+    {
+        std::fs::remove_file(directory.join(&backup.file_name))
+            .map_err(|e| format!("Failed to remove backup {}: {}", backup.file_name, e))?;
+    }
+    Ok(())
+}
+
+/// Tables holding application data; FTS shadow tables follow their virtual table.
+async fn data_tables(conn: &mut SqliteConnection) -> Result<Vec<String>, String> {
+    sqlx::query_scalar(
+        "SELECT name FROM main.sqlite_master
+         WHERE type = 'table'
+           AND name NOT LIKE 'sqlite_%'
+           AND name NOT LIKE 'search_index_%'
+           AND name <> '_sqlx_migrations'
+         ORDER BY name",
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to list tables: {}", e))
+}
+
+async fn ensure_same_schema(conn: &mut SqliteConnection) -> Result<(), String> {
+    let mut versions = Vec::new();
+    for schema in ["main", RESTORE_SCHEMA] {
+        let version: Option<i64> = sqlx::query_scalar(&format!(
+            "SELECT MAX(version) FROM {}._sqlx_migrations",
+            schema
+        ))
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to read schema version: {}", e))?;
+        versions.push(version);
+    }
+    if versions[0] != versions[1] {
+        return Err(
+            "The backup was made with a different database version and cannot be restored"
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// Empties every data table, refills them from the attached restore source when one is
+/// given, and records `action` in the audit log, all in one transaction.
+async fn rewrite_tables(
+    conn: &mut SqliteConnection,
+    restore: bool,
+    action: &str,
+    details: &serde_json::Value,
+) -> Result<Vec<String>, String> {
+    let tables = data_tables(conn).await?;
+    let mut tx = conn.begin().await.map_err(|e| e.to_string())?;
+    for table in &tables {
+        sqlx::query(&format!("DELETE FROM main.\"{}\"", table))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to clear {}: {}", table, e))?;
+    }
+    sqlx::query("DELETE FROM main.sqlite_sequence")
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to reset id sequences: {}", e))?;
+
+    if restore {
+        // search_index is rebuilt by the triggers on the restored rows
+        for table in tables.iter().filter(|table| *table != "search_index") {
+            sqlx::query(&format!(
+                "INSERT INTO main.\"{table}\" SELECT * FROM {schema}.\"{table}\"",
+                table = table,
+                schema = RESTORE_SCHEMA
+            ))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to restore {}: {}", table, e))?;
+        }
+        sqlx::query(&format!(
+            "INSERT INTO main.sqlite_sequence SELECT * FROM {}.sqlite_sequence",
+            RESTORE_SCHEMA
+        ))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to restore id sequences: {}", e))?;
+    }
+
+    record_audit(&mut *tx, action, None, details)
+        .await
+        .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    Ok(tables)
+}
+
+/// Replaces all data, optionally with the contents of `source`. Foreign keys are off for
+/// the duration because every table is rewritten, so delete order does not matter. The
+/// work runs on a dedicated connection that is closed afterwards, so neither the attached
+/// backup nor the pragma can leak to other users of the pool.
+pub(crate) async fn replace_all_data(
+    pool: &SqlitePool,
+    source: Option<&Path>,
+    action: &str,
+    details: &serde_json::Value,
+) -> Result<Vec<String>, String> {
+    let mut conn = pool
+        .connect_options()
+        .connect()
+        .await
+        .map_err(|e| format!("Failed to connect to database: {}", e))?;
+    let result = rewrite_on_connection(&mut conn, source, action, details).await;
+    if source.is_some() {
+        // Harmless when the attach itself failed; the connection is closed either way
+        let _ = sqlx::query(&format!("DETACH DATABASE {}", RESTORE_SCHEMA))
+            .execute(&mut conn)
+            .await;
+    }
+    conn.close()
+        .await
+        .map_err(|e| format!("Failed to close connection: {}", e))?;
+    result
+}
+
+async fn rewrite_on_connection(
+    conn: &mut SqliteConnection,
+    source: Option<&Path>,
+    action: &str,
+    details: &serde_json::Value,
+) -> Result<Vec<String>, String> {
+    if let Some(source) = source {
+        // Without a key an attached file shares the main database's key; a plain backup
+        // needs the empty key
+        let key = if is_encrypted(source)? { "" } else { " KEY ''" };
+        sqlx::query(&format!("ATTACH DATABASE ? AS {}{}", RESTORE_SCHEMA, key))
+            .bind(source.to_string_lossy().to_string())
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to open backup: {}", e))?;
+        ensure_same_schema(conn).await?;
+    }
+    sqlx::query("PRAGMA foreign_keys = OFF")
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to disable foreign keys: {}", e))?;
+    rewrite_tables(conn, source.is_some(), action, details).await
+}
+
+/// Restores a backup listed by `list_backups` after snapshotting the current data.
+pub async fn restore_backup(pool: &SqlitePool, file_name: &str) -> Result<BackupRestore, String> {
+    let restored = list_backups(pool)
+        .await?
+        .into_iter()
+        .find(|backup| backup.file_name == file_name)
+        .ok_or_else(|| format!("Backup not found: {}", file_name))?;
+    let source = backup_directory(pool).await?.join(&restored.file_name);
+
+    let safety_backup = create_backup(pool, "before-restore").await?;
+    let safety_backup = safety_backup
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    replace_all_data(
+        pool,
+        Some(&source),
+        "backup_restored",
+        &serde_json::json!({ "file_name": restored.file_name, "safety_backup": safety_backup }),
+    )
+    .await?;
+
+    Ok(BackupRestore {
+        restored,
+        safety_backup,
+    })
+}
+
+async fn run_if_due(pool: &SqlitePool) -> Result<(), String> {
+    let settings = load_settings(pool).await?;
+    if !settings.enabled {
+        return Ok(());
+    }
+
+    let due = settings.last_backup_at.is_none_or(|last_backup| {
+        Utc::now() - last_backup >= ChronoDuration::hours(settings.interval_hours)
+    });
+    if due {
+        create_backup(pool, SCHEDULED_LABEL).await?;
+        prune_scheduled(pool, settings.keep_last).await?;
+    }
+    Ok(())
+}
+
+/// Starts the background task that snapshots the database when a backup is due.
+pub fn spawn_backup_task(pool: SqlitePool) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(error) = run_if_due(&pool).await {
+                record_task_failure(&pool, BACKUP_TASK, &error).await;
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_restore_brings_back_data_and_prunes_scheduled() {
+        let directory = tempfile::tempdir().unwrap();
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(&format!(
+                "sqlite:{}?mode=rwc",
+                directory.path().join("epa.db").display()
+            ))
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        sqlx::query("INSERT INTO employees (name) VALUES ('Budi Santoso')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let backup = create_backup(&pool, "manual").await.unwrap();
+        let again = create_backup(&pool, "manual").await.unwrap();
+        assert_ne!(again, backup);
+        sqlx::query("DELETE FROM employees")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let file_name = backup.file_name().unwrap().to_string_lossy().to_string();
+        restore_backup(&pool, &file_name).await.unwrap();
+        let names: Vec<String> = sqlx::query_scalar("SELECT name FROM employees")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(names, vec!["Budi Santoso".to_string()]);
+        let indexed: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM search_index WHERE kind = 'employee'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(indexed, 1);
+
+        let foreign = directory.path().join("foreign.db");
+        std::fs::write(&foreign, b"").unwrap();
+        assert!(replace_all_data(
+            &pool,
+            Some(&foreign),
+            "backup_restored",
+            &serde_json::json!({})
+        )
+        .await
+        .is_err());
+        let attached: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM pragma_database_list WHERE name = 'restore_source'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(attached, 0);
+        let foreign_keys: i64 = sqlx::query_scalar("PRAGMA foreign_keys")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(foreign_keys, 1);
+
+        let backup_dir = backup_directory(&pool).await.unwrap();
+        for stamp in ["20240101-000000", "20240102-000000"] {
+            std::fs::copy(
+                &backup,
+                backup_dir.join(format!("epa-scheduled-{}.db", stamp)),
+            )
+            .unwrap();
+        }
+        prune_scheduled(&pool, 1).await.unwrap();
+        let scheduled = list_backups(&pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|backup| is_scheduled(&backup.file_name))
+            .count();
+        assert_eq!(scheduled, 1);
+
+        pool.close().await;
+    }
+}
@@ -1,8 +1,23 @@
-use crate::db::models::{Competency, Dataset, Employee, Score};
+use crate::auth::Role;
+use crate::commands::competency::load_competency_categories;
+use crate::commands::dataset::dataset_period_key;
+use crate::data_quality::{
+    applicable_checks, assess_employees, failing_condition, DataQualityIssue, EmployeeDataQuality,
+};
+use crate::db::attendance::load_attendance_percentages;
+use crate::db::audit::record_audit;
+use crate::db::models::{Competency, CompetencyCategory, Dataset, Employee, Score};
+use crate::db::score_entries::load_normalized_values;
+use crate::db::settings::{get_setting_or, set_setting};
+use crate::scoring::position::{load_position_keywords, PositionKeywords};
+use crate::scoring::weighting::load_weighting_scheme;
+use crate::scoring::{compute_weighted_score, PositionType};
+use crate::ui_config::{load_dataset_ui_config, load_ui_config, UiConfig};
 use crate::AppState;
 use serde::{Deserialize, Serialize};
-use sqlx::{QueryBuilder, SqlitePool};
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
 use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 use tauri::State;
 use unicode_normalization::UnicodeNormalization;
@@ -11,15 +26,149 @@ use unicode_normalization::UnicodeNormalization;
 pub struct ScoreDistribution {
     pub range: String,
     pub count: i64,
+    pub lower_bound: Option<f64>,
+    pub upper_bound: Option<f64>,
+}
+
+/// Number of scores whose raw text matches one of the dataset's rating mapping labels.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RatingLabelCount {
+    pub label: String,
+    pub numeric_value: f64,
+    pub count: i64,
+}
+
+/// Bucket count used when no rating mappings are available to derive boundaries from.
+const FALLBACK_DISTRIBUTION_BUCKETS: usize = 5;
+
+fn format_bound(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+/// Buckets scores by the given lower edges. With fewer than two distinct edges the
+/// range is split evenly between the observed min and max instead, so the histogram
+/// follows whatever scale the dataset actually uses.
+fn build_score_distribution(values: &[f64], edges: &[f64]) -> Vec<ScoreDistribution> {
+    let mut edges: Vec<f64> = edges.iter().copied().filter(|v| v.is_finite()).collect();
+    edges.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    edges.dedup();
+
+    if edges.len() < 2 {
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        if !min.is_finite() {
+            return Vec::new();
+        }
+        let width = (max - min) / FALLBACK_DISTRIBUTION_BUCKETS as f64;
+        edges = if width > 0.0 {
+            (0..FALLBACK_DISTRIBUTION_BUCKETS)
+                .map(|index| min + width * index as f64)
+                .collect()
+        } else {
+            vec![min]
+        };
+    }
+
+    let mut counts = vec![0i64; edges.len()];
+    let mut below = 0i64;
+    for value in values {
+        match edges.iter().rposition(|edge| value >= edge) {
+            Some(index) => counts[index] += 1,
+            None => below += 1,
+        }
+    }
+
+    let mut distribution = Vec::with_capacity(edges.len() + 1);
+    if below > 0 {
+        distribution.push(ScoreDistribution {
+            range: format!("<{}", format_bound(edges[0])),
+            count: below,
+            lower_bound: None,
+            upper_bound: Some(edges[0]),
+        });
+    }
+    for (index, count) in counts.into_iter().enumerate() {
+        let lower = edges[index];
+        let upper = edges.get(index + 1).copied();
+        let range = match upper {
+            Some(upper) => format!("{}-{}", format_bound(lower), format_bound(upper)),
+            None => format!("{}+", format_bound(lower)),
+        };
+        distribution.push(ScoreDistribution {
+            range,
+            count,
+            lower_bound: Some(lower),
+            upper_bound: upper,
+        });
+    }
+    distribution
+}
+
+/// Five-number summary and population standard deviation of a set of scores.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreSpread {
+    pub min: f64,
+    pub q1: f64,
+    pub median: f64,
+    pub q3: f64,
+    pub max: f64,
+    pub std_dev: f64,
+}
+
+impl ScoreSpread {
+    pub fn from_values(values: &[f64]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        let mut sorted = values.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        // Linear interpolation between the closest ranks
+        let quantile = |q: f64| {
+            let position = q * (sorted.len() - 1) as f64;
+            let lower = sorted[position.floor() as usize];
+            let upper = sorted[position.ceil() as usize];
+            lower + (upper - lower) * position.fract()
+        };
+        let mean = sorted.iter().sum::<f64>() / sorted.len() as f64;
+        let variance = sorted
+            .iter()
+            .map(|value| (value - mean).powi(2))
+            .sum::<f64>()
+            / sorted.len() as f64;
+
+        Some(Self {
+            min: sorted[0],
+            q1: quantile(0.25),
+            median: quantile(0.5),
+            q3: quantile(0.75),
+            max: sorted[sorted.len() - 1],
+            std_dev: variance.sqrt(),
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompetencyStats {
     pub competency: Competency,
     pub average_score: f64,
+    pub std_dev: f64,
     pub employee_count: i64,
 }
 
+/// Scores of all competencies in one category, pooled together.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryStats {
+    pub category: CompetencyCategory,
+    pub average_score: f64,
+    pub std_dev: f64,
+    pub employee_count: i64,
+    pub competency_count: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DatasetStats {
     pub dataset: Dataset,
@@ -27,8 +176,14 @@ pub struct DatasetStats {
     pub total_competencies: i64,
     pub total_scores: i64,
     pub average_score: f64,
+    /// `None` when the dataset has no numeric scores yet.
+    pub score_spread: Option<ScoreSpread>,
     pub score_distribution: Vec<ScoreDistribution>,
+    pub rating_distribution: Vec<RatingLabelCount>,
     pub competency_stats: Vec<CompetencyStats>,
+    /// Only categories with at least one numeric score in the dataset.
+    #[serde(default)]
+    pub category_stats: Vec<CategoryStats>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,36 +223,85 @@ pub struct EmployeeWithStats {
     pub position_status: String,
     pub average_score: f64,
     pub score_count: i64,
+    pub percentile: Option<f64>,
+    pub weighted_score: Option<f64>,
+    pub completeness: Option<f64>,
+    pub data_quality: Option<EmployeeDataQuality>,
+}
+
+/// Optional, more expensive aggregates that `list_employees` only computes on request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmployeeListColumn {
+    Percentile,
+    WeightedScore,
+    Completeness,
+    DataQuality,
+}
+
+impl FromStr for EmployeeListColumn {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "percentile" => Ok(Self::Percentile),
+            "weighted_score" => Ok(Self::WeightedScore),
+            "completeness" => Ok(Self::Completeness),
+            "data_quality" => Ok(Self::DataQuality),
+            other => Err(format!("Unknown employee list column: {}", other)),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmployeeListResult {
     pub employees: Vec<EmployeeWithStats>,
     pub total_count: i64,
+    pub page_size: i64,
+    pub default_page_size: i64,
+    pub max_page_size: i64,
+    pub columns: Vec<EmployeeListColumn>,
 }
 
-const STAFF_KEYWORDS: [&str; 2] = ["staff", "staf"];
-const ESELON_KEYWORDS: [&str; 14] = [
-    "eselon",
-    "kepala",
-    "sekretaris",
-    "kabid",
-    "kabag",
-    "kasubag",
-    "kepala seksi",
-    "kasi",
-    "koordinator",
-    "pengawas",
-    "sub bagian",
-    "subbagian",
-    "subbidang",
-    "sub bidang",
-];
+const DEFAULT_EMPLOYEE_PAGE_SIZE: i64 = 50;
+const MAX_EMPLOYEE_PAGE_SIZE: i64 = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmployeeRanking {
+    pub rank: i64,
+    pub employee: Employee,
+    pub position_type: PositionType,
+    pub score: f64,
+    pub average_score: f64,
+    pub weighted_score: f64,
+    /// Subtotals of the weighted components, in the order of `WeightedScore::sections`
+    pub section_subtotals: Vec<f64>,
+    pub percentile: f64,
+    pub rating: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum RankingBasis {
+    Average,
+    Weighted,
+}
+
+impl FromStr for RankingBasis {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "average" => Ok(Self::Average),
+            "weighted" => Ok(Self::Weighted),
+            other => Err(format!("Unknown ranking basis: {}", other)),
+        }
+    }
+}
 
 const ROLE_ORDER_EXPR: &str =
     "LOWER(REPLACE(REPLACE(REPLACE(TRIM(IFNULL(e.jabatan, '') || ' ' || IFNULL(e.sub_jabatan, '')), '.', ' '), ',', ' '), '/', ' '))";
 
-fn sanitize_text(value: &str) -> String {
+pub(crate) fn sanitize_text(value: &str) -> String {
     let decomposed: String = value
         .nfkd()
         .filter(|ch| !matches!(ch, '\u{0300}'..='\u{036f}'))
@@ -119,43 +323,8 @@ fn sanitize_text(value: &str) -> String {
         .join(" ")
 }
 
-fn derive_position_status(
-    jabatan: Option<&str>,
-    sub_jabatan: Option<&str>,
-    gol: Option<&str>,
-) -> String {
-    let combined = format!(
-        "{} {}",
-        jabatan.unwrap_or_default(),
-        sub_jabatan.unwrap_or_default()
-    );
-    let normalized = sanitize_text(&combined);
-
-    if !normalized.is_empty() {
-        if STAFF_KEYWORDS
-            .iter()
-            .any(|keyword| normalized.contains(keyword))
-        {
-            return "Staff".to_string();
-        }
-        if ESELON_KEYWORDS
-            .iter()
-            .any(|keyword| normalized.contains(keyword))
-        {
-            return "Eselon".to_string();
-        }
-    }
-
-    let gol_value = gol.unwrap_or_default().trim().to_uppercase();
-    if gol_value.starts_with("IV") {
-        "Eselon".to_string()
-    } else {
-        "Staff".to_string()
-    }
-}
-
 #[derive(Debug, Clone, Copy)]
-enum EmployeeSortField {
+pub(crate) enum EmployeeSortField {
     Name,
     Nip,
     Jabatan,
@@ -202,13 +371,34 @@ pub struct ScoreWithCompetency {
     pub competency: Competency,
 }
 
+/// An employee's mean numeric score over the competencies of one category.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryAverage {
+    pub category: CompetencyCategory,
+    pub average_score: f64,
+    pub competency_count: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmployeePerformance {
     pub employee: Employee,
+    pub position_type: PositionType,
     pub scores: Vec<ScoreWithCompetency>,
     pub average_score: f64,
     pub strengths: Vec<String>,
     pub gaps: Vec<String>,
+    /// The dataset's fixed normalisation scale, if any; see `Dataset::score_scale`
+    #[serde(default)]
+    pub score_scale: Option<f64>,
+    /// Categories in display order; uncategorised competencies only count towards `average_score`.
+    #[serde(default)]
+    pub category_averages: Vec<CategoryAverage>,
+    /// Imported Penilaian Pimpinan; `None` falls back to the default leadership score.
+    #[serde(default)]
+    pub leadership_score: Option<f64>,
+    /// Present days over working days in the dataset's reporting year, as a percentage.
+    #[serde(default)]
+    pub attendance_percentage: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -227,6 +417,62 @@ pub struct DatasetComparison {
     pub average_delta: f64,
 }
 
+/// Competencies listed in the dashboard overview, best average first.
+const COMPETENCY_OVERVIEW_LIMIT: usize = 8;
+
+/// A score's numeric value after its dataset's normalization.
+pub(crate) struct NumericScore {
+    pub dataset_id: i64,
+    pub employee_id: i64,
+    pub competency_id: i64,
+    pub value: f64,
+}
+
+/// Numeric scores of a dataset that pass the anonymity `floor`, normalized as the dataset
+/// asks, so stats and comparisons agree with performances and rankings.
+pub(crate) async fn load_numeric_scores(
+    pool: &SqlitePool,
+    dataset_id: i64,
+    floor: i64,
+) -> Result<Vec<NumericScore>, sqlx::Error> {
+    let rows: Vec<(i64, i64, f64)> = sqlx::query_as(&format!(
+        "SELECT s.employee_id, s.competency_id, s.numeric_value FROM scores s
+         WHERE s.dataset_id = ? AND s.numeric_value IS NOT NULL AND {floor}",
+        floor = ANONYMITY_FLOOR_FILTER,
+    ))
+    .bind(dataset_id)
+    .bind(floor)
+    .fetch_all(pool)
+    .await?;
+    let normalized = load_normalized_values(&mut *pool.acquire().await?, dataset_id).await?;
+    Ok(rows
+        .into_iter()
+        .map(|(employee_id, competency_id, value)| NumericScore {
+            dataset_id,
+            employee_id,
+            competency_id,
+            value: normalized
+                .get(&(employee_id, competency_id))
+                .copied()
+                .unwrap_or(value),
+        })
+        .collect())
+}
+
+/// Mean and population standard deviation; zero for no values.
+fn mean_and_std_dev(values: &[f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let variance = values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / values.len() as f64;
+    (mean, variance.sqrt())
+}
+
 pub async fn compute_dataset_stats(
     pool: &SqlitePool,
     dataset_id: i64,
@@ -248,87 +494,164 @@ pub async fn compute_dataset_stats(
     .fetch_one(pool)
     .await?;
 
-    let total_competencies: i64 =
-        sqlx::query_scalar("SELECT COUNT(DISTINCT competency_id) FROM scores WHERE dataset_id = ?")
-            .bind(dataset_id)
-            .fetch_one(pool)
-            .await?;
-
-    let score_stats: (i64, Option<f64>) = sqlx::query_as(
-        "SELECT COUNT(*), AVG(numeric_value) FROM scores
-         WHERE dataset_id = ? AND numeric_value IS NOT NULL",
-    )
+    let floor = load_anonymity_floor(pool).await?;
+    let total_competencies: i64 = sqlx::query_scalar(&format!(
+        "SELECT COUNT(DISTINCT s.competency_id) FROM scores s
+         WHERE s.dataset_id = ? AND {floor}",
+        floor = ANONYMITY_FLOOR_FILTER,
+    ))
     .bind(dataset_id)
+    .bind(floor)
     .fetch_one(pool)
     .await?;
 
-    let total_scores = score_stats.0;
-    let average_score = score_stats.1.unwrap_or(0.0);
+    // Everything below is on the dataset's fixed scale when it has one
+    let mut numeric_scores = load_numeric_scores(pool, dataset_id, floor).await?;
+    for score in &mut numeric_scores {
+        score.value = apply_score_scale(score.value, dataset.score_scale);
+    }
+    let numeric_values: Vec<f64> = numeric_scores.iter().map(|score| score.value).collect();
+    let mut competency_values: HashMap<i64, Vec<f64>> = HashMap::new();
+    for score in &numeric_scores {
+        competency_values
+            .entry(score.competency_id)
+            .or_default()
+            .push(score.value);
+    }
 
-    let distribution_rows: Vec<(i64, i64)> = sqlx::query_as(
-        "SELECT
-            CASE
-                WHEN numeric_value < 1 THEN 0
-                WHEN numeric_value < 2 THEN 1
-                WHEN numeric_value < 3 THEN 2
-                WHEN numeric_value < 4 THEN 3
-                ELSE 4
-            END as range_key,
-            COUNT(*) as count
-        FROM scores
-        WHERE dataset_id = ? AND numeric_value IS NOT NULL
-        GROUP BY range_key
-        ORDER BY range_key",
+    let total_scores = numeric_values.len() as i64;
+    let average_score = if numeric_values.is_empty() {
+        0.0
+    } else {
+        numeric_values.iter().sum::<f64>() / numeric_values.len() as f64
+    };
+
+    let mapping_values: Vec<f64> = sqlx::query_scalar(
+        "SELECT DISTINCT numeric_value FROM rating_mappings WHERE dataset_id = ? ORDER BY numeric_value",
     )
     .bind(dataset_id)
     .fetch_all(pool)
-    .await?;
-
-    let score_distribution: Vec<ScoreDistribution> = distribution_rows
+    .await?
+    .into_iter()
+    .map(|value| apply_score_scale(value, dataset.score_scale))
+    .collect();
+
+    let score_distribution = build_score_distribution(&numeric_values, &mapping_values);
+
+    let rating_distribution: Vec<RatingLabelCount> =
+        sqlx::query_as::<_, (String, f64, i64)>(&format!(
+            "SELECT rm.text_value, rm.numeric_value, COUNT(s.id)
+                FROM rating_mappings rm
+                LEFT JOIN scores s ON s.dataset_id = rm.dataset_id
+                    AND LOWER(TRIM(s.raw_value)) = LOWER(TRIM(rm.text_value))
+                    AND {floor}
+                WHERE rm.dataset_id = ?
+                GROUP BY rm.id, rm.text_value, rm.numeric_value
+                ORDER BY rm.numeric_value DESC, rm.text_value",
+            floor = ANONYMITY_FLOOR_FILTER,
+        ))
+        .bind(floor)
+        .bind(dataset_id)
+        .fetch_all(pool)
+        .await?
         .into_iter()
-        .map(|(range_key, count)| {
-            let range = match range_key {
-                0 => "0-1",
-                1 => "1-2",
-                2 => "2-3",
-                3 => "3-4",
-                _ => "4+",
-            };
-            ScoreDistribution {
-                range: range.to_string(),
-                count,
-            }
+        .map(|(label, numeric_value, count)| RatingLabelCount {
+            label,
+            numeric_value: apply_score_scale(numeric_value, dataset.score_scale),
+            count,
         })
         .collect();
 
-    let competency_stats_rows: Vec<(i64, String, Option<String>, i32, Option<f64>, i64)> =
-        sqlx::query_as(
-            "SELECT
-                c.id, c.name, c.description, c.display_order,
-                AVG(s.numeric_value) as avg_score,
+    let score_spread = ScoreSpread::from_values(&numeric_values);
+
+    type CompetencyStatsRow = (i64, String, Option<String>, i32, Option<i64>, i64);
+    let competency_stats_rows: Vec<CompetencyStatsRow> = sqlx::query_as(&format!(
+        "SELECT
+                c.id, c.name, c.description, {order} AS display_order, c.category_id,
                 COUNT(DISTINCT s.employee_id) as employee_count
             FROM competencies c
             JOIN scores s ON c.id = s.competency_id
-            WHERE s.dataset_id = ? AND s.numeric_value IS NOT NULL
-            GROUP BY c.id, c.name, c.description, c.display_order
-            ORDER BY c.display_order, c.name",
-        )
-        .bind(dataset_id)
-        .fetch_all(pool)
-        .await?;
+            {join}
+            WHERE s.dataset_id = ? AND s.numeric_value IS NOT NULL AND {floor}
+            GROUP BY c.id
+            ORDER BY display_order, c.name",
+        join = DATASET_COMPETENCY_JOIN,
+        order = DATASET_COMPETENCY_ORDER,
+        floor = ANONYMITY_FLOOR_FILTER,
+    ))
+    .bind(dataset_id)
+    .bind(floor)
+    .fetch_all(pool)
+    .await?;
 
+    let mut category_values: HashMap<i64, Vec<f64>> = HashMap::new();
     let competency_stats: Vec<CompetencyStats> = competency_stats_rows
         .into_iter()
         .map(
-            |(id, name, description, display_order, avg_score, employee_count)| CompetencyStats {
-                competency: Competency {
-                    id,
-                    name,
-                    description,
-                    display_order,
-                },
-                average_score: avg_score.unwrap_or(0.0),
-                employee_count,
+            |(id, name, description, display_order, category_id, employee_count)| {
+                let values = competency_values.get(&id).map_or(&[][..], Vec::as_slice);
+                if let Some(category_id) = category_id {
+                    category_values
+                        .entry(category_id)
+                        .or_default()
+                        .extend_from_slice(values);
+                }
+                let (average_score, std_dev) = mean_and_std_dev(values);
+                CompetencyStats {
+                    competency: Competency {
+                        id,
+                        name,
+                        description,
+                        display_order,
+                        category_id,
+                    },
+                    average_score,
+                    std_dev,
+                    employee_count,
+                }
+            },
+        )
+        .collect();
+
+    type CategoryStatsRow = (i64, String, Option<String>, i32, i64, i64);
+    let category_stats_rows: Vec<CategoryStatsRow> = sqlx::query_as(&format!(
+        "SELECT
+                cat.id, cat.name, cat.description, cat.display_order,
+                COUNT(DISTINCT s.employee_id) as employee_count,
+                COUNT(DISTINCT c.id) as competency_count
+            FROM competency_categories cat
+            JOIN competencies c ON c.category_id = cat.id
+            JOIN scores s ON s.competency_id = c.id
+            {join}
+            WHERE s.dataset_id = ? AND s.numeric_value IS NOT NULL AND {floor}
+            GROUP BY cat.id, cat.name, cat.description, cat.display_order
+            ORDER BY cat.display_order, cat.name",
+        join = DATASET_COMPETENCY_JOIN,
+        floor = ANONYMITY_FLOOR_FILTER,
+    ))
+    .bind(dataset_id)
+    .bind(floor)
+    .fetch_all(pool)
+    .await?;
+
+    let category_stats: Vec<CategoryStats> = category_stats_rows
+        .into_iter()
+        .map(
+            |(id, name, description, display_order, employee_count, competency_count)| {
+                let (average_score, std_dev) =
+                    mean_and_std_dev(category_values.get(&id).map_or(&[][..], Vec::as_slice));
+                CategoryStats {
+                    category: CompetencyCategory {
+                        id,
+                        name,
+                        description,
+                        display_order,
+                    },
+                    average_score,
+                    std_dev,
+                    employee_count,
+                    competency_count,
+                }
             },
         )
         .collect();
@@ -339,89 +662,162 @@ pub async fn compute_dataset_stats(
         total_competencies,
         total_scores,
         average_score,
+        score_spread,
         score_distribution,
+        rating_distribution,
         competency_stats,
+        category_stats,
     })
 }
 
-pub async fn compute_employee_performance(
-    pool: &SqlitePool,
-    dataset_id: i64,
-    employee_id: i64,
-) -> Result<EmployeePerformance, sqlx::Error> {
-    let employee = sqlx::query_as::<_, Employee>(
-        "SELECT e.* FROM employees e
-         JOIN dataset_employees de ON de.employee_id = e.id
-         WHERE e.id = ? AND de.dataset_id = ?",
-    )
-    .bind(employee_id)
-    .bind(dataset_id)
-    .fetch_one(pool)
-    .await?;
+pub(crate) type ScoreRow = (
+    i64,
+    i64,
+    i64,
+    i64,
+    String,
+    Option<f64>,
+    String,
+    i64,
+    String,
+    Option<String>,
+    i32,
+    Option<i64>,
+    Option<i64>,
+    Option<String>,
+    Option<i64>,
+);
+
+/// Joins a score's competency to its position in that score's dataset; pair with
+/// `DATASET_COMPETENCY_ORDER`, which falls back to the global order.
+pub(crate) const DATASET_COMPETENCY_JOIN: &str =
+    "LEFT JOIN dataset_competencies dc ON dc.dataset_id = s.dataset_id AND dc.competency_id = c.id";
+pub(crate) const DATASET_COMPETENCY_ORDER: &str = "COALESCE(dc.display_order, c.display_order)";
+
+pub(crate) const ANONYMITY_FLOOR_KEY: &str = "analytics.anonymity_floor";
+const MAX_ANONYMITY_FLOOR: i64 = 50;
+
+/// Hides scores aggregated from fewer form responses than the bound floor; scores from
+/// sources without respondent rows (`respondent_count` NULL) always pass.
+pub(crate) const ANONYMITY_FLOOR_FILTER: &str =
+    "(s.respondent_count IS NULL OR s.respondent_count >= ?)";
+
+/// Minimum respondents a per-competency score needs before it is shown; 0 disables it.
+pub(crate) async fn load_anonymity_floor(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
+    Ok(get_setting_or(pool, ANONYMITY_FLOOR_KEY, 0i64)
+        .await?
+        .clamp(0, MAX_ANONYMITY_FLOOR))
+}
 
-    let score_rows: Vec<(
-        i64,
-        i64,
-        i64,
-        i64,
-        String,
-        Option<f64>,
-        String,
-        i64,
-        String,
-        Option<String>,
-        i32,
-    )> = sqlx::query_as(
-        "SELECT
-                s.id, s.employee_id, s.dataset_id, s.competency_id, s.raw_value, s.numeric_value, s.created_at,
-                c.id, c.name, c.description, c.display_order
-            FROM scores s
-            JOIN competencies c ON s.competency_id = c.id
-            WHERE s.employee_id = ? AND s.dataset_id = ?
-            ORDER BY c.display_order, c.name",
+#[tauri::command]
+pub async fn get_anonymity_floor(state: State<'_, AppState>) -> Result<i64, String> {
+    load_anonymity_floor(&state.pool)
+        .await
+        .map_err(|e| format!("Failed to load anonymity floor: {}", e))
+}
+
+#[tauri::command]
+pub async fn update_anonymity_floor(
+    state: State<'_, AppState>,
+    min_respondents: i64,
+) -> Result<i64, String> {
+    state.require_role(Role::Admin).await?;
+    save_anonymity_floor(&state.pool, min_respondents).await
+}
+
+/// Stores the anonymity floor and its audit entry in one transaction.
+async fn save_anonymity_floor(pool: &SqlitePool, min_respondents: i64) -> Result<i64, String> {
+    if !(0..=MAX_ANONYMITY_FLOOR).contains(&min_respondents) {
+        return Err(format!(
+            "Minimum respondents must be between 0 and {}",
+            MAX_ANONYMITY_FLOOR
+        ));
+    }
+    let old_floor = load_anonymity_floor(pool)
+        .await
+        .map_err(|e| format!("Failed to load anonymity floor: {}", e))?;
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    set_setting(&mut *tx, ANONYMITY_FLOOR_KEY, &min_respondents.to_string())
+        .await
+        .map_err(|e| format!("Failed to save anonymity floor: {}", e))?;
+    record_audit(
+        &mut *tx,
+        "anonymity_floor_updated",
+        None,
+        &serde_json::json!({ "old_min_respondents": old_floor, "new_min_respondents": min_respondents }),
     )
-    .bind(employee_id)
-    .bind(dataset_id)
-    .fetch_all(pool)
-    .await?;
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    Ok(min_respondents)
+}
 
-    let scores: Vec<ScoreWithCompetency> = score_rows
-        .into_iter()
-        .map(
-            |(
-                score_id,
-                emp_id,
-                score_dataset_id,
-                comp_id,
-                raw_value,
-                numeric_value,
-                created_at,
-                c_id,
-                c_name,
-                c_desc,
-                c_order,
-            )| {
-                ScoreWithCompetency {
-                    score: Score {
-                        id: score_id,
-                        employee_id: emp_id,
-                        dataset_id: score_dataset_id,
-                        competency_id: comp_id,
-                        raw_value,
-                        numeric_value,
-                        created_at: created_at.parse().unwrap_or_default(),
-                    },
-                    competency: Competency {
-                        id: c_id,
-                        name: c_name,
-                        description: c_desc,
-                        display_order: c_order,
-                    },
-                }
-            },
-        )
-        .collect();
+pub(crate) const SCORE_WITH_COMPETENCY_COLUMNS: &str =
+    "s.id, s.employee_id, s.dataset_id, s.competency_id, s.raw_value, s.numeric_value, s.created_at,
+     c.id, c.name, c.description, COALESCE(dc.display_order, c.display_order), c.category_id,
+     s.source_row, s.source_column, s.import_run_id";
+
+pub(crate) fn score_row_to_entry(row: ScoreRow) -> ScoreWithCompetency {
+    let (
+        score_id,
+        emp_id,
+        score_dataset_id,
+        comp_id,
+        raw_value,
+        numeric_value,
+        created_at,
+        c_id,
+        c_name,
+        c_desc,
+        c_order,
+        c_category_id,
+        source_row,
+        source_column,
+        import_run_id,
+    ) = row;
+    ScoreWithCompetency {
+        score: Score {
+            id: score_id,
+            employee_id: emp_id,
+            dataset_id: score_dataset_id,
+            competency_id: comp_id,
+            raw_value,
+            numeric_value,
+            created_at: created_at.parse().unwrap_or_default(),
+            source_row,
+            source_column,
+            import_run_id,
+        },
+        competency: Competency {
+            id: c_id,
+            name: c_name,
+            description: c_desc,
+            display_order: c_order,
+            category_id: c_category_id,
+        },
+    }
+}
 
+/// Replaces the numeric value with the dataset's normalized one, when it has one.
+fn apply_normalization(entry: &mut ScoreWithCompetency, normalized: &HashMap<(i64, i64), f64>) {
+    let key = (entry.score.employee_id, entry.score.competency_id);
+    if let (Some(_), Some(value)) = (entry.score.numeric_value, normalized.get(&key)) {
+        entry.score.numeric_value = Some(*value);
+    }
+}
+
+pub(crate) fn build_employee_performance(
+    employee: Employee,
+    scores: Vec<ScoreWithCompetency>,
+    keywords: &PositionKeywords,
+    score_scale: Option<f64>,
+    categories: &[CompetencyCategory],
+    leadership_score: Option<f64>,
+    attendance_percentage: Option<f64>,
+) -> EmployeePerformance {
     let numeric_scores: Vec<f64> = scores
         .iter()
         .filter_map(|s| s.score.numeric_value)
@@ -429,7 +825,10 @@ pub async fn compute_employee_performance(
     let average_score = if numeric_scores.is_empty() {
         0.0
     } else {
-        numeric_scores.iter().sum::<f64>() / numeric_scores.len() as f64
+        apply_score_scale(
+            numeric_scores.iter().sum::<f64>() / numeric_scores.len() as f64,
+            score_scale,
+        )
     };
 
     let mut sorted_scores = scores.clone();
@@ -462,13 +861,194 @@ pub async fn compute_employee_performance(
         .map(|s| s.competency.name.clone())
         .collect();
 
-    Ok(EmployeePerformance {
+    let category_averages = categories
+        .iter()
+        .filter_map(|category| {
+            let values: Vec<f64> = scores
+                .iter()
+                .filter(|s| s.competency.category_id == Some(category.id))
+                .filter_map(|s| s.score.numeric_value)
+                .collect();
+            if values.is_empty() {
+                return None;
+            }
+            Some(CategoryAverage {
+                category: category.clone(),
+                average_score: apply_score_scale(
+                    values.iter().sum::<f64>() / values.len() as f64,
+                    score_scale,
+                ),
+                competency_count: values.len() as i64,
+            })
+        })
+        .collect();
+
+    EmployeePerformance {
+        position_type: keywords.classify(&employee),
         employee,
         scores,
         average_score,
         strengths,
         gaps,
-    })
+        score_scale,
+        category_averages,
+        leadership_score,
+        attendance_percentage,
+    }
+}
+
+/// `value` as 0-100 when the dataset has a fixed score scale, so averages and rankings
+/// match its weighted scores; unchanged otherwise.
+pub(crate) fn apply_score_scale(value: f64, score_scale: Option<f64>) -> f64 {
+    score_scale.map_or(value, |scale| value / scale * 100.0)
+}
+
+async fn load_score_scale(pool: &SqlitePool, dataset_id: i64) -> Result<Option<f64>, sqlx::Error> {
+    Ok(
+        sqlx::query_scalar::<_, Option<f64>>("SELECT score_scale FROM datasets WHERE id = ?")
+            .bind(dataset_id)
+            .fetch_optional(pool)
+            .await?
+            .flatten(),
+    )
+}
+
+pub async fn compute_employee_performance(
+    pool: &SqlitePool,
+    dataset_id: i64,
+    employee_id: i64,
+) -> Result<EmployeePerformance, sqlx::Error> {
+    let employee = sqlx::query_as::<_, Employee>(
+        "SELECT e.* FROM employees e
+         JOIN dataset_employees de ON de.employee_id = e.id
+         WHERE e.id = ? AND de.dataset_id = ?",
+    )
+    .bind(employee_id)
+    .bind(dataset_id)
+    .fetch_one(pool)
+    .await?;
+
+    let score_rows: Vec<ScoreRow> = sqlx::query_as(&format!(
+        "SELECT {columns}
+            FROM scores s
+            JOIN competencies c ON s.competency_id = c.id
+            {join}
+            WHERE s.employee_id = ? AND s.dataset_id = ? AND {floor}
+            ORDER BY {order}, c.name",
+        columns = SCORE_WITH_COMPETENCY_COLUMNS,
+        join = DATASET_COMPETENCY_JOIN,
+        order = DATASET_COMPETENCY_ORDER,
+        floor = ANONYMITY_FLOOR_FILTER,
+    ))
+    .bind(employee_id)
+    .bind(dataset_id)
+    .bind(load_anonymity_floor(pool).await?)
+    .fetch_all(pool)
+    .await?;
+
+    let mut scores: Vec<ScoreWithCompetency> =
+        score_rows.into_iter().map(score_row_to_entry).collect();
+    let normalized = load_normalized_values(&mut *pool.acquire().await?, dataset_id).await?;
+    for entry in &mut scores {
+        apply_normalization(entry, &normalized);
+    }
+
+    let keywords = load_position_keywords(pool).await?;
+    let score_scale = load_score_scale(pool, dataset_id).await?;
+    let categories = load_competency_categories(pool).await?;
+    let leadership_score: Option<f64> = sqlx::query_scalar(
+        "SELECT score FROM leadership_scores WHERE dataset_id = ? AND employee_id = ?",
+    )
+    .bind(dataset_id)
+    .bind(employee_id)
+    .fetch_optional(pool)
+    .await?;
+    let attendance_percentage = load_attendance_percentages(pool, dataset_id, Some(employee_id))
+        .await?
+        .remove(&employee_id);
+    Ok(build_employee_performance(
+        employee,
+        scores,
+        &keywords,
+        score_scale,
+        &categories,
+        leadership_score,
+        attendance_percentage,
+    ))
+}
+
+/// Performance of every employee linked to a dataset, loaded with two queries.
+pub async fn compute_dataset_performances(
+    pool: &SqlitePool,
+    dataset_id: i64,
+) -> Result<Vec<EmployeePerformance>, sqlx::Error> {
+    let employees = sqlx::query_as::<_, Employee>(
+        "SELECT e.* FROM employees e
+         JOIN dataset_employees de ON de.employee_id = e.id
+         WHERE de.dataset_id = ?
+         ORDER BY e.name COLLATE NOCASE",
+    )
+    .bind(dataset_id)
+    .fetch_all(pool)
+    .await?;
+
+    let score_rows: Vec<ScoreRow> = sqlx::query_as(&format!(
+        "SELECT {columns}
+            FROM scores s
+            JOIN competencies c ON s.competency_id = c.id
+            {join}
+            WHERE s.dataset_id = ? AND {floor}
+            ORDER BY s.employee_id, {order}, c.name",
+        columns = SCORE_WITH_COMPETENCY_COLUMNS,
+        join = DATASET_COMPETENCY_JOIN,
+        order = DATASET_COMPETENCY_ORDER,
+        floor = ANONYMITY_FLOOR_FILTER,
+    ))
+    .bind(dataset_id)
+    .bind(load_anonymity_floor(pool).await?)
+    .fetch_all(pool)
+    .await?;
+
+    let normalized = load_normalized_values(&mut *pool.acquire().await?, dataset_id).await?;
+    let mut scores_by_employee: HashMap<i64, Vec<ScoreWithCompetency>> = HashMap::new();
+    for row in score_rows {
+        let mut entry = score_row_to_entry(row);
+        apply_normalization(&mut entry, &normalized);
+        scores_by_employee
+            .entry(entry.score.employee_id)
+            .or_default()
+            .push(entry);
+    }
+
+    let keywords = load_position_keywords(pool).await?;
+    let score_scale = load_score_scale(pool, dataset_id).await?;
+    let categories = load_competency_categories(pool).await?;
+    let leadership_scores: HashMap<i64, f64> = sqlx::query_as::<_, (i64, f64)>(
+        "SELECT employee_id, score FROM leadership_scores WHERE dataset_id = ?",
+    )
+    .bind(dataset_id)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .collect();
+    let attendance = load_attendance_percentages(pool, dataset_id, None).await?;
+    Ok(employees
+        .into_iter()
+        .map(|employee| {
+            let scores = scores_by_employee.remove(&employee.id).unwrap_or_default();
+            let leadership_score = leadership_scores.get(&employee.id).copied();
+            let attendance_percentage = attendance.get(&employee.id).copied();
+            build_employee_performance(
+                employee,
+                scores,
+                &keywords,
+                score_scale,
+                &categories,
+                leadership_score,
+                attendance_percentage,
+            )
+        })
+        .collect())
 }
 
 #[tauri::command]
@@ -490,54 +1070,10 @@ pub async fn get_overview_stats(state: State<'_, AppState>) -> Result<DashboardO
         .await
         .map_err(|e| format!("Failed to count scores: {}", e))?;
 
-    let total_competencies: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM competencies")
-        .fetch_one(&pool)
-        .await
-        .map_err(|e| format!("Failed to count competencies: {}", e))?;
-
-    let average_score: f64 = sqlx::query_scalar::<_, Option<f64>>(
-        "SELECT AVG(numeric_value) FROM scores WHERE numeric_value IS NOT NULL",
-    )
-    .fetch_one(&pool)
-    .await
-    .map_err(|e| format!("Failed to compute average score: {}", e))?
-    .unwrap_or(0.0);
-
-    let distribution_rows: Vec<(i64, i64)> = sqlx::query_as(
-        "SELECT
-            CASE
-                WHEN numeric_value < 1 THEN 0
-                WHEN numeric_value < 2 THEN 1
-                WHEN numeric_value < 3 THEN 2
-                WHEN numeric_value < 4 THEN 3
-                ELSE 4
-            END as range_key,
-            COUNT(*) as count
-        FROM scores
-        WHERE numeric_value IS NOT NULL
-        GROUP BY range_key
-        ORDER BY range_key",
-    )
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| format!("Failed to compute score distribution: {}", e))?;
-
-    let score_distribution: Vec<ScoreDistribution> = distribution_rows
-        .into_iter()
-        .map(|(range_key, count)| {
-            let range = match range_key {
-                0 => "0-1",
-                1 => "1-2",
-                2 => "2-3",
-                3 => "3-4",
-                _ => "4+",
-            };
-            ScoreDistribution {
-                range: range.to_string(),
-                count,
-            }
-        })
-        .collect();
+    let total_competencies: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM competencies")
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| format!("Failed to count competencies: {}", e))?;
 
     let dataset_ids: Vec<i64> =
         sqlx::query_scalar("SELECT id FROM datasets ORDER BY created_at DESC")
@@ -545,8 +1081,25 @@ pub async fn get_overview_stats(state: State<'_, AppState>) -> Result<DashboardO
             .await
             .map_err(|e| format!("Failed to list datasets: {}", e))?;
 
+    // Each dataset's own normalization applies, as in its stats
+    let floor = load_anonymity_floor(&pool)
+        .await
+        .map_err(|e| format!("Failed to load anonymity floor: {}", e))?;
+    let mut numeric_scores = Vec::new();
+    for &dataset_id in &dataset_ids {
+        numeric_scores.extend(
+            load_numeric_scores(&pool, dataset_id, floor)
+                .await
+                .map_err(|e| format!("Failed to load scores: {}", e))?,
+        );
+    }
+    let numeric_values: Vec<f64> = numeric_scores.iter().map(|score| score.value).collect();
+    let (average_score, _) = mean_and_std_dev(&numeric_values);
+    // Datasets may use different rating scales, so the overview splits the observed range
+    let score_distribution = build_score_distribution(&numeric_values, &[]);
+
     let mut dataset_stats: Vec<DatasetStats> = Vec::new();
-    for dataset_id in dataset_ids {
+    for &dataset_id in &dataset_ids {
         let stats = compute_dataset_stats(&pool, dataset_id)
             .await
             .map_err(|e| format!("Failed to compute dataset stats: {}", e))?;
@@ -576,45 +1129,33 @@ pub async fn get_overview_stats(state: State<'_, AppState>) -> Result<DashboardO
     recent_datasets.sort_by(|a, b| b.dataset.created_at.cmp(&a.dataset.created_at));
     recent_datasets.truncate(5);
 
-    let competency_rows: Vec<(i64, String, Option<String>, i32, Option<f64>, i64, i64)> =
-        sqlx::query_as(
-            "SELECT
-            c.id,
-            c.name,
-            c.description,
-            c.display_order,
-            AVG(s.numeric_value) as avg_score,
-            COUNT(DISTINCT s.dataset_id) as dataset_count,
-            COUNT(s.id) as score_count
-        FROM competencies c
-        JOIN scores s ON s.competency_id = c.id
-        WHERE s.numeric_value IS NOT NULL
-        GROUP BY c.id, c.name, c.description, c.display_order
-        ORDER BY avg_score DESC
-        LIMIT 8",
-        )
-        .fetch_all(&pool)
-        .await
-        .map_err(|e| format!("Failed to compute competency overview: {}", e))?;
-
-    let competency_overview: Vec<CompetencyOverview> = competency_rows
+    let competencies: HashMap<i64, Competency> =
+        sqlx::query_as::<_, Competency>("SELECT * FROM competencies")
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| format!("Failed to load competencies: {}", e))?
+            .into_iter()
+            .map(|competency| (competency.id, competency))
+            .collect();
+    let mut by_competency: HashMap<i64, (Vec<f64>, HashSet<i64>)> = HashMap::new();
+    for score in &numeric_scores {
+        let (values, datasets) = by_competency.entry(score.competency_id).or_default();
+        values.push(score.value);
+        datasets.insert(score.dataset_id);
+    }
+    let mut competency_overview: Vec<CompetencyOverview> = by_competency
         .into_iter()
-        .map(
-            |(id, name, description, display_order, avg, dataset_count, score_count)| {
-                CompetencyOverview {
-                    competency: Competency {
-                        id,
-                        name,
-                        description,
-                        display_order,
-                    },
-                    average_score: avg.unwrap_or(0.0),
-                    dataset_count,
-                    score_count,
-                }
-            },
-        )
+        .filter_map(|(id, (values, datasets))| {
+            Some(CompetencyOverview {
+                competency: competencies.get(&id)?.clone(),
+                average_score: mean_and_std_dev(&values).0,
+                dataset_count: datasets.len() as i64,
+                score_count: values.len() as i64,
+            })
+        })
         .collect();
+    competency_overview.sort_by(|a, b| b.average_score.total_cmp(&a.average_score));
+    competency_overview.truncate(COMPETENCY_OVERVIEW_LIMIT);
 
     Ok(DashboardOverview {
         total_datasets,
@@ -641,6 +1182,7 @@ pub async fn get_dataset_stats(
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn list_employees(
     state: State<'_, AppState>,
     dataset_id: i64,
@@ -649,21 +1191,64 @@ pub async fn list_employees(
     offset: Option<i64>,
     sort_by: Option<String>,
     sort_direction: Option<String>,
+    columns: Option<Vec<String>>,
+    data_quality_issue: Option<String>,
 ) -> Result<EmployeeListResult, String> {
     let pool = state.pool.clone();
-    let limit = limit.unwrap_or(50).clamp(1, 500);
+    let limit = limit
+        .unwrap_or(DEFAULT_EMPLOYEE_PAGE_SIZE)
+        .clamp(1, MAX_EMPLOYEE_PAGE_SIZE);
     let offset = offset.unwrap_or(0).max(0);
 
+    let mut columns = columns
+        .unwrap_or_default()
+        .iter()
+        .map(|value| EmployeeListColumn::from_str(value))
+        .collect::<Result<Vec<_>, _>>()?;
+    columns.sort_by_key(|column| *column as u8);
+    columns.dedup();
+
+    // `any` keeps employees failing at least one data quality check
+    let quality_filter = match data_quality_issue.as_deref() {
+        None => None,
+        Some(value) => {
+            let issue = match value {
+                "any" => None,
+                other => Some(DataQualityIssue::from_str(other)?),
+            };
+            Some(failing_condition(&applicable_checks(&pool).await?, issue))
+        }
+    };
+
+    let config = load_ui_config(&pool)
+        .await
+        .map_err(|e| format!("Failed to load UI config: {}", e))?;
     let sort_field = sort_by
         .as_deref()
+        .or(Some(config.default_employee_sort.column.as_str()))
         .and_then(|value| EmployeeSortField::from_str(value).ok())
         .unwrap_or(EmployeeSortField::Name);
+    let sort_direction = sort_direction.or_else(|| {
+        sort_by
+            .is_none()
+            .then(|| config.default_employee_sort.direction.clone())
+    });
     let sort_direction_str = match sort_direction.as_deref() {
         Some(direction) if direction.eq_ignore_ascii_case("desc") => "DESC",
         _ => "ASC",
     };
 
-    let staff_condition = STAFF_KEYWORDS
+    let keywords = load_position_keywords(&pool)
+        .await
+        .map_err(|e| format!("Failed to load position keywords: {}", e))?;
+    let anonymity_floor = load_anonymity_floor(&pool)
+        .await
+        .map_err(|e| format!("Failed to load anonymity floor: {}", e))?;
+    let score_scale = load_score_scale(&pool, dataset_id)
+        .await
+        .map_err(|e| format!("Failed to load score scale: {}", e))?;
+    let staff_condition = keywords
+        .staff
         .iter()
         .map(|keyword| {
             format!(
@@ -680,7 +1265,8 @@ pub async fn list_employees(
         staff_condition
     };
 
-    let eselon_condition = ESELON_KEYWORDS
+    let eselon_condition = keywords
+        .eselon
         .iter()
         .map(|keyword| {
             format!(
@@ -699,6 +1285,8 @@ pub async fn list_employees(
 
     let position_case = format!(
         "CASE
+            WHEN e.position_type = 'eselon' THEN 'Eselon'
+            WHEN e.position_type = 'staff' THEN 'Staff'
             WHEN {staff} THEN 'Staff'
             WHEN {eselon} THEN 'Eselon'
             WHEN UPPER(IFNULL(e.gol, '')) LIKE 'IV%' THEN 'Eselon'
@@ -716,39 +1304,44 @@ pub async fn list_employees(
             e.gol,
             e.jabatan,
             e.sub_jabatan,
+            e.position_type,
+            e.photo_path,
             e.created_at,
             e.updated_at,
             {position_case},
-            COALESCE(AVG(s.numeric_value), 0.0) as average_score,
-            COUNT(s.id) as score_count
+            COALESCE(a.average_score, 0.0) as average_score,
+            COALESCE(a.score_count, 0) as score_count
         FROM employees e
-        LEFT JOIN scores s ON s.employee_id = e.id AND s.dataset_id = ",
+        LEFT JOIN (
+            SELECT CAST(key AS INTEGER) AS employee_id,
+                   json_extract(value, '$[0]') AS average_score,
+                   json_extract(value, '$[1]') AS score_count
+            FROM json_each(",
         position_case = position_case,
     );
 
-    let mut employees_query = QueryBuilder::new(select_clause);
-    employees_query.push_bind(dataset_id);
-    employees_query.push(" AND s.numeric_value IS NOT NULL");
+    // Averaged from normalized values like stats and rankings, and handed to SQL so sorting
+    // and paging use them too
+    let averages = employee_averages(&pool, dataset_id, anonymity_floor)
+        .await
+        .map_err(|e| format!("Failed to compute averages: {}", e))?;
+    let averages_json: serde_json::Map<String, serde_json::Value> = averages
+        .iter()
+        .map(|(employee_id, (average, count))| {
+            (employee_id.to_string(), serde_json::json!([average, count]))
+        })
+        .collect();
 
-    if let Some(search_term) = &search {
-        let normalized = search_term.trim().to_lowercase();
-        if !normalized.is_empty() {
-            employees_query.push(" WHERE (");
-            employees_query.push("LOWER(e.name) LIKE ");
-            employees_query.push_bind(format!("%{}%", normalized));
-            employees_query.push(" OR LOWER(IFNULL(e.nip, '')) LIKE ");
-            employees_query.push_bind(format!("%{}%", normalized));
-            employees_query.push(" OR LOWER(IFNULL(e.jabatan, '')) LIKE ");
-            employees_query.push_bind(format!("%{}%", normalized));
-            employees_query.push(" OR LOWER(IFNULL(e.sub_jabatan, '')) LIKE ");
-            employees_query.push_bind(format!("%{}%", normalized));
-            employees_query.push(")");
-        }
-    }
+    let mut employees_query = QueryBuilder::new(select_clause);
+    employees_query.push_bind(serde_json::Value::Object(averages_json).to_string());
+    employees_query.push(")) a ON a.employee_id = e.id");
 
-    employees_query.push(
-        " GROUP BY e.id, e.name, e.nip, e.gol, e.jabatan, e.sub_jabatan, e.created_at, e.updated_at, position_status",
+    push_employee_filters(
+        &mut employees_query,
+        search.as_deref(),
+        quality_filter.as_deref(),
     );
+
     employees_query.push(" ORDER BY ");
     employees_query.push(sort_field.order_expression());
     employees_query.push(" ");
@@ -765,6 +1358,8 @@ pub async fn list_employees(
         Option<String>,
         Option<String>,
         Option<String>,
+        Option<String>,
+        Option<String>,
         String,
         String,
         String,
@@ -786,6 +1381,8 @@ pub async fn list_employees(
                 gol,
                 jabatan,
                 sub_jabatan,
+                position_type,
+                photo_path,
                 created_at,
                 updated_at,
                 position_status,
@@ -795,11 +1392,14 @@ pub async fn list_employees(
                 let status = if matches!(position_status.as_str(), "Staff" | "Eselon") {
                     position_status
                 } else {
-                    derive_position_status(
+                    match keywords.classify_role(
                         jabatan.as_deref(),
                         sub_jabatan.as_deref(),
                         gol.as_deref(),
-                    )
+                    ) {
+                        PositionType::Eselon => "Eselon".to_string(),
+                        PositionType::Staff => "Staff".to_string(),
+                    }
                 };
 
                 EmployeeWithStats {
@@ -810,34 +1410,44 @@ pub async fn list_employees(
                         gol,
                         jabatan,
                         sub_jabatan,
+                        position_type,
+                        photo_path,
                         created_at: created_at.parse().unwrap_or_default(),
                         updated_at: updated_at.parse().unwrap_or_default(),
                     },
                     position_status: status,
-                    average_score: avg,
+                    average_score: apply_score_scale(avg, score_scale),
                     score_count: count,
+                    percentile: None,
+                    weighted_score: None,
+                    completeness: None,
+                    data_quality: None,
                 }
             },
         )
         .collect();
 
+    let mut employees_with_stats = employees_with_stats;
+    if !columns.is_empty() && !employees_with_stats.is_empty() {
+        fill_optional_columns(
+            &pool,
+            &config,
+            dataset_id,
+            score_scale,
+            &averages,
+            &columns,
+            &mut employees_with_stats,
+        )
+        .await?;
+    }
+
     let mut count_query = QueryBuilder::new("SELECT COUNT(*) FROM employees e");
 
-    if let Some(search_term) = &search {
-        let normalized = search_term.trim().to_lowercase();
-        if !normalized.is_empty() {
-            count_query.push(" WHERE (");
-            count_query.push("LOWER(e.name) LIKE ");
-            count_query.push_bind(format!("%{}%", normalized));
-            count_query.push(" OR LOWER(IFNULL(e.nip, '')) LIKE ");
-            count_query.push_bind(format!("%{}%", normalized));
-            count_query.push(" OR LOWER(IFNULL(e.jabatan, '')) LIKE ");
-            count_query.push_bind(format!("%{}%", normalized));
-            count_query.push(" OR LOWER(IFNULL(e.sub_jabatan, '')) LIKE ");
-            count_query.push_bind(format!("%{}%", normalized));
-            count_query.push(")");
-        }
-    }
+    push_employee_filters(
+        &mut count_query,
+        search.as_deref(),
+        quality_filter.as_deref(),
+    );
 
     let total_count: i64 = count_query
         .build_query_scalar()
@@ -848,9 +1458,142 @@ pub async fn list_employees(
     Ok(EmployeeListResult {
         employees: employees_with_stats,
         total_count,
+        page_size: limit,
+        default_page_size: DEFAULT_EMPLOYEE_PAGE_SIZE,
+        max_page_size: MAX_EMPLOYEE_PAGE_SIZE,
+        columns,
     })
 }
 
+fn push_employee_filters(
+    query: &mut QueryBuilder<'_, Sqlite>,
+    search: Option<&str>,
+    quality_filter: Option<&str>,
+) {
+    let mut keyword = " WHERE ";
+    if let Some(search_term) = search {
+        let normalized = search_term.trim().to_lowercase();
+        if !normalized.is_empty() {
+            query.push(" WHERE (");
+            query.push("LOWER(e.name) LIKE ");
+            query.push_bind(format!("%{}%", normalized));
+            query.push(" OR LOWER(IFNULL(e.nip, '')) LIKE ");
+            query.push_bind(format!("%{}%", normalized));
+            query.push(" OR LOWER(IFNULL(e.jabatan, '')) LIKE ");
+            query.push_bind(format!("%{}%", normalized));
+            query.push(" OR LOWER(IFNULL(e.sub_jabatan, '')) LIKE ");
+            query.push_bind(format!("%{}%", normalized));
+            query.push(")");
+            keyword = " AND ";
+        }
+    }
+    if let Some(condition) = quality_filter {
+        query.push(keyword);
+        query.push(condition);
+    }
+}
+
+/// Each employee's mean normalized score over the scores that pass the anonymity `floor`,
+/// with the number of those scores.
+async fn employee_averages(
+    pool: &SqlitePool,
+    dataset_id: i64,
+    floor: i64,
+) -> Result<HashMap<i64, (f64, i64)>, sqlx::Error> {
+    let mut totals: HashMap<i64, (f64, i64)> = HashMap::new();
+    for score in load_numeric_scores(pool, dataset_id, floor).await? {
+        let total = totals.entry(score.employee_id).or_default();
+        total.0 += score.value;
+        total.1 += 1;
+    }
+    Ok(totals
+        .into_iter()
+        .map(|(employee_id, (sum, count))| (employee_id, (sum / count as f64, count)))
+        .collect())
+}
+
+async fn fill_optional_columns(
+    pool: &SqlitePool,
+    config: &UiConfig,
+    dataset_id: i64,
+    score_scale: Option<f64>,
+    averages: &HashMap<i64, (f64, i64)>,
+    columns: &[EmployeeListColumn],
+    employees: &mut [EmployeeWithStats],
+) -> Result<(), String> {
+    let floor = load_anonymity_floor(pool)
+        .await
+        .map_err(|e| format!("Failed to load anonymity floor: {}", e))?;
+
+    if columns.contains(&EmployeeListColumn::Percentile) {
+        let mut averages: Vec<f64> = averages
+            .values()
+            .map(|(average, _)| apply_score_scale(*average, score_scale))
+            .collect();
+        averages.sort_by(f64::total_cmp);
+
+        for entry in employees.iter_mut().filter(|entry| entry.score_count > 0) {
+            entry.percentile = percentile_rank(&averages, entry.average_score);
+        }
+    }
+
+    if columns.contains(&EmployeeListColumn::WeightedScore) {
+        let scheme = load_weighting_scheme(pool)
+            .await
+            .map_err(|e| format!("Failed to load weighting scheme: {}", e))?;
+        let weighted: HashMap<i64, f64> = compute_dataset_performances(pool, dataset_id)
+            .await
+            .map_err(|e| format!("Failed to compute weighted scores: {}", e))?
+            .iter()
+            .map(|performance| {
+                (
+                    performance.employee.id,
+                    compute_weighted_score(performance, config, &scheme).total_score,
+                )
+            })
+            .collect();
+
+        for entry in employees.iter_mut() {
+            entry.weighted_score = weighted.get(&entry.employee.id).copied();
+        }
+    }
+
+    if columns.contains(&EmployeeListColumn::DataQuality) {
+        let ids: Vec<i64> = employees.iter().map(|entry| entry.employee.id).collect();
+        let mut assessed: HashMap<i64, EmployeeDataQuality> = assess_employees(pool, Some(&ids))
+            .await?
+            .into_iter()
+            .map(|(employee, quality)| (employee.id, quality))
+            .collect();
+        for entry in employees.iter_mut() {
+            entry.data_quality = assessed.remove(&entry.employee.id);
+        }
+    }
+
+    if columns.contains(&EmployeeListColumn::Completeness) {
+        // Counted like `score_count`, so scores below the floor count on neither side
+        let competency_count: i64 = sqlx::query_scalar(&format!(
+            "SELECT COUNT(DISTINCT s.competency_id) FROM scores s
+             WHERE s.dataset_id = ? AND {floor}",
+            floor = ANONYMITY_FLOOR_FILTER,
+        ))
+        .bind(dataset_id)
+        .bind(floor)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to compute completeness: {}", e))?;
+
+        if competency_count > 0 {
+            for entry in employees.iter_mut() {
+                entry.completeness =
+                    Some(entry.score_count as f64 / competency_count as f64 * 100.0);
+            }
+        }
+    }
+
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn get_employee_performance(
     state: State<'_, AppState>,
@@ -864,22 +1607,268 @@ pub async fn get_employee_performance(
         .map_err(|e| format!("Failed to load employee performance: {}", e))
 }
 
+/// Ranks employees of a dataset by raw average or by the weighted report score.
+/// Tied scores share a rank; employees without numeric scores are left out.
+#[tauri::command]
+pub async fn rank_employees(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    basis: Option<String>,
+) -> Result<Vec<EmployeeRanking>, String> {
+    compute_rankings(&state.pool, dataset_id, basis.as_deref()).await
+}
+
+pub(crate) async fn compute_rankings(
+    pool: &SqlitePool,
+    dataset_id: i64,
+    basis: Option<&str>,
+) -> Result<Vec<EmployeeRanking>, String> {
+    let config = load_dataset_ui_config(pool, dataset_id)
+        .await
+        .map_err(|e| format!("Failed to load UI config: {}", e))?;
+    let basis = RankingBasis::from_str(basis.unwrap_or(&config.default_ranking_basis))?;
+    let scheme = load_weighting_scheme(pool)
+        .await
+        .map_err(|e| format!("Failed to load weighting scheme: {}", e))?;
+
+    let performances = compute_dataset_performances(pool, dataset_id)
+        .await
+        .map_err(|e| format!("Failed to load employee performance: {}", e))?;
+
+    let mut rankings: Vec<EmployeeRanking> = performances
+        .into_iter()
+        .filter(|performance| {
+            performance
+                .scores
+                .iter()
+                .any(|entry| entry.score.numeric_value.is_some())
+        })
+        .map(|performance| {
+            let weighted = compute_weighted_score(&performance, &config, &scheme);
+            let score = match basis {
+                RankingBasis::Average => performance.average_score,
+                RankingBasis::Weighted => weighted.total_score,
+            };
+            EmployeeRanking {
+                rank: 0,
+                position_type: weighted.position_type,
+                score,
+                average_score: performance.average_score,
+                weighted_score: weighted.total_score,
+                section_subtotals: weighted
+                    .sections
+                    .iter()
+                    .map(|section| section.subtotal)
+                    .collect(),
+                percentile: 0.0,
+                rating: weighted.rating,
+                employee: performance.employee,
+            }
+        })
+        .collect();
+
+    rankings.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.employee.name.cmp(&b.employee.name))
+    });
+
+    let mut sorted_scores: Vec<f64> = rankings.iter().map(|ranking| ranking.score).collect();
+    sorted_scores.sort_by(f64::total_cmp);
+    for index in 0..rankings.len() {
+        let rank = if index > 0 && rankings[index].score == rankings[index - 1].score {
+            rankings[index - 1].rank
+        } else {
+            index as i64 + 1
+        };
+        rankings[index].rank = rank;
+        rankings[index].percentile =
+            percentile_rank(&sorted_scores, rankings[index].score).unwrap_or(0.0);
+    }
+
+    Ok(rankings)
+}
+
+/// Share of `sorted` (ascending) at or below `value`, from 0 to 100; `None` for an empty
+/// group.
+fn percentile_rank(sorted: &[f64], value: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let at_or_below = sorted.partition_point(|other| *other <= value);
+    Some(at_or_below as f64 / sorted.len() as f64 * 100.0)
+}
+
+/// Employee attribute `get_group_stats` splits a dataset by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum GroupBy {
+    Gol,
+    Jabatan,
+    PositionStatus,
+    /// SOTK unit of the employee's jabatan, falling back to the sub jabatan
+    Department,
+}
+
+impl FromStr for GroupBy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "gol" => Ok(Self::Gol),
+            "jabatan" => Ok(Self::Jabatan),
+            "position_status" => Ok(Self::PositionStatus),
+            "department" => Ok(Self::Department),
+            other => Err(format!("Unknown group: {}", other)),
+        }
+    }
+}
+
+const UNGROUPED_LABEL: &str = "Tidak diketahui";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RatingBandCount {
+    pub label: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupStats {
+    pub group: String,
+    pub employee_count: usize,
+    pub average_score: f64,
+    pub average_weighted_score: f64,
+    /// One entry per configured rating band, highest first
+    pub rating_distribution: Vec<RatingBandCount>,
+}
+
+/// Golongan without spaces and with a consistent case, so "III / C" and "III/c" match.
+fn normalize_gol(gol: &str) -> String {
+    let compact: String = gol.chars().filter(|ch| !ch.is_whitespace()).collect();
+    match compact.split_once('/') {
+        Some((rank, grade)) => format!("{}/{}", rank.to_uppercase(), grade.to_lowercase()),
+        None => gol.trim().to_string(),
+    }
+}
+
+/// Average and weighted scores plus the rating spread of each group of scored employees.
+#[tauri::command]
+pub async fn get_group_stats(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    group_by: String,
+) -> Result<Vec<GroupStats>, String> {
+    let pool = state.pool.clone();
+    let group_by = GroupBy::from_str(&group_by)?;
+
+    let config = load_dataset_ui_config(&pool, dataset_id)
+        .await
+        .map_err(|e| format!("Failed to load UI config: {}", e))?;
+    let units: HashMap<String, String> = if group_by == GroupBy::Department {
+        sqlx::query_as::<_, (String, String)>(
+            "SELECT LOWER(jr.name), MIN(u.name) FROM jabatan_reference jr
+             JOIN org_units u ON u.id = jr.unit_id
+             GROUP BY LOWER(jr.name)",
+        )
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| format!("Failed to load org units: {}", e))?
+        .into_iter()
+        .collect()
+    } else {
+        HashMap::new()
+    };
+
+    let mut groups: Vec<(String, Vec<EmployeeRanking>)> = Vec::new();
+    let mut group_index: HashMap<String, usize> = HashMap::new();
+    for ranking in compute_rankings(&pool, dataset_id, None).await? {
+        let employee = &ranking.employee;
+        let label = match group_by {
+            GroupBy::Gol => employee.gol.as_deref().map(normalize_gol),
+            GroupBy::Jabatan => employee.jabatan.as_deref().map(|j| j.trim().to_string()),
+            GroupBy::PositionStatus => Some(ranking.position_type.label().to_string()),
+            GroupBy::Department => employee
+                .jabatan
+                .as_deref()
+                .and_then(|jabatan| units.get(&jabatan.trim().to_lowercase()).cloned())
+                .or_else(|| {
+                    employee
+                        .sub_jabatan
+                        .as_deref()
+                        .map(|s| s.trim().to_string())
+                }),
+        }
+        .filter(|label| !label.is_empty())
+        .unwrap_or_else(|| UNGROUPED_LABEL.to_string());
+
+        let index = *group_index.entry(label.to_lowercase()).or_insert_with(|| {
+            groups.push((label, Vec::new()));
+            groups.len() - 1
+        });
+        groups[index].1.push(ranking);
+    }
+    groups.sort_by_key(|(label, _)| label.to_lowercase());
+
+    Ok(groups
+        .into_iter()
+        .map(|(group, members)| {
+            let count = members.len() as f64;
+            GroupStats {
+                employee_count: members.len(),
+                average_score: members.iter().map(|m| m.average_score).sum::<f64>() / count,
+                average_weighted_score: members.iter().map(|m| m.weighted_score).sum::<f64>()
+                    / count,
+                rating_distribution: config
+                    .rating_bands
+                    .iter()
+                    .map(|band| RatingBandCount {
+                        label: band.label.clone(),
+                        count: members.iter().filter(|m| m.rating == band.label).count(),
+                    })
+                    .collect(),
+                group,
+            }
+        })
+        .collect())
+}
+
 #[tauri::command]
 pub async fn compare_datasets(
     state: State<'_, AppState>,
     base_dataset_id: i64,
     comparison_dataset_id: i64,
+    order_by_period: Option<bool>,
 ) -> Result<DatasetComparison, String> {
-    let pool = state.pool.clone();
+    compute_dataset_comparison(
+        &state.pool,
+        base_dataset_id,
+        comparison_dataset_id,
+        order_by_period.unwrap_or(false),
+    )
+    .await
+}
 
-    let base_stats = compute_dataset_stats(&pool, base_dataset_id)
+pub(crate) async fn compute_dataset_comparison(
+    pool: &SqlitePool,
+    base_dataset_id: i64,
+    comparison_dataset_id: i64,
+    order_by_period: bool,
+) -> Result<DatasetComparison, String> {
+    let mut base_stats = compute_dataset_stats(pool, base_dataset_id)
         .await
         .map_err(|e| format!("Failed to compute base dataset stats: {}", e))?;
-    let comparison_stats = compute_dataset_stats(&pool, comparison_dataset_id)
+    let mut comparison_stats = compute_dataset_stats(pool, comparison_dataset_id)
         .await
         .map_err(|e| format!("Failed to compute comparison dataset stats: {}", e))?;
 
-    let mut competency_map = std::collections::HashMap::new();
+    // Optionally compare chronologically: the earlier period always becomes the base
+    if order_by_period
+        && dataset_period_key(&comparison_stats.dataset) < dataset_period_key(&base_stats.dataset)
+    {
+        std::mem::swap(&mut base_stats, &mut comparison_stats);
+    }
+
+    let mut competency_map = HashMap::new();
     for stat in &base_stats.competency_stats {
         competency_map.insert(stat.competency.id, stat.clone());
     }
@@ -930,3 +1919,378 @@ pub async fn compare_datasets(
         average_delta,
     })
 }
+
+/// Employees needed with scores on both competencies before a coefficient is reported.
+const MIN_CORRELATION_PAIRS: usize = 3;
+
+/// Pearson correlations between the dataset's competencies across employees. Pairs close
+/// to 1 point at redundant questions or a halo effect in the instrument.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompetencyCorrelations {
+    pub competencies: Vec<Competency>,
+    /// `matrix[i][j]` correlates `competencies[i]` with `competencies[j]`; `None` when fewer
+    /// than `MIN_CORRELATION_PAIRS` employees have both scores or either score never varies
+    pub matrix: Vec<Vec<Option<f64>>>,
+    /// Employees scored on both competencies of each cell
+    pub pair_counts: Vec<Vec<usize>>,
+}
+
+/// Pearson's r of the pairs, `None` when a side has no variance.
+fn pearson(pairs: &[(f64, f64)]) -> Option<f64> {
+    let n = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let (mut covariance, mut variance_x, mut variance_y) = (0.0, 0.0, 0.0);
+    for (x, y) in pairs {
+        covariance += (x - mean_x) * (y - mean_y);
+        variance_x += (x - mean_x).powi(2);
+        variance_y += (y - mean_y).powi(2);
+    }
+    if variance_x == 0.0 || variance_y == 0.0 {
+        return None;
+    }
+    Some((covariance / (variance_x * variance_y).sqrt()).clamp(-1.0, 1.0))
+}
+
+/// Correlation matrix over `competency_ids` from each employee's scores by competency,
+/// using every employee scored on both competencies of a cell.
+fn correlation_matrix(
+    competency_ids: &[i64],
+    employee_scores: &HashMap<i64, HashMap<i64, f64>>,
+) -> (Vec<Vec<Option<f64>>>, Vec<Vec<usize>>) {
+    let size = competency_ids.len();
+    let mut matrix = vec![vec![None; size]; size];
+    let mut pair_counts = vec![vec![0; size]; size];
+    for i in 0..size {
+        for j in i..size {
+            let pairs: Vec<(f64, f64)> = employee_scores
+                .values()
+                .filter_map(|scores| {
+                    Some((
+                        *scores.get(&competency_ids[i])?,
+                        *scores.get(&competency_ids[j])?,
+                    ))
+                })
+                .collect();
+            let coefficient = if pairs.len() >= MIN_CORRELATION_PAIRS {
+                pearson(&pairs)
+            } else {
+                None
+            };
+            matrix[i][j] = coefficient;
+            matrix[j][i] = coefficient;
+            pair_counts[i][j] = pairs.len();
+            pair_counts[j][i] = pairs.len();
+        }
+    }
+    (matrix, pair_counts)
+}
+
+#[tauri::command]
+pub async fn get_competency_correlations(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+) -> Result<CompetencyCorrelations, String> {
+    compute_competency_correlations(&state.pool, dataset_id)
+        .await
+        .map_err(|e| format!("Failed to compute correlations: {}", e))
+}
+
+/// Correlations of the dataset's scores that pass the anonymity floor, with the competencies
+/// in the dataset's own order.
+async fn compute_competency_correlations(
+    pool: &SqlitePool,
+    dataset_id: i64,
+) -> Result<CompetencyCorrelations, sqlx::Error> {
+    let floor = load_anonymity_floor(pool).await?;
+    let competencies = sqlx::query_as::<_, Competency>(&format!(
+        "SELECT c.id, c.name, c.description, {order} AS display_order, c.category_id
+         FROM competencies c
+         JOIN (SELECT DISTINCT s.dataset_id, s.competency_id FROM scores s
+               WHERE s.dataset_id = ? AND s.numeric_value IS NOT NULL AND {floor}) s
+           ON s.competency_id = c.id
+         {join}
+         ORDER BY display_order, c.name",
+        join = DATASET_COMPETENCY_JOIN,
+        order = DATASET_COMPETENCY_ORDER,
+        floor = ANONYMITY_FLOOR_FILTER,
+    ))
+    .bind(dataset_id)
+    .bind(floor)
+    .fetch_all(pool)
+    .await?;
+
+    let mut employee_scores: HashMap<i64, HashMap<i64, f64>> = HashMap::new();
+    for score in load_numeric_scores(pool, dataset_id, floor).await? {
+        employee_scores
+            .entry(score.employee_id)
+            .or_default()
+            .insert(score.competency_id, score.value);
+    }
+
+    let competency_ids: Vec<i64> = competencies.iter().map(|c| c.id).collect();
+    let (matrix, pair_counts) = correlation_matrix(&competency_ids, &employee_scores);
+    Ok(CompetencyCorrelations {
+        competencies,
+        matrix,
+        pair_counts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    #[test]
+    fn test_score_distribution_uses_mapping_edges_and_falls_back_to_range() {
+        let mapped = build_score_distribution(&[65.0, 75.0, 75.0, 85.0, 50.0], &[85.0, 65.0, 75.0]);
+        let ranges: Vec<(&str, i64)> = mapped.iter().map(|d| (d.range.as_str(), d.count)).collect();
+        assert_eq!(
+            ranges,
+            vec![("<65", 1), ("65-75", 1), ("75-85", 2), ("85+", 1)]
+        );
+
+        let fallback = build_score_distribution(&[60.0, 70.0, 80.0, 90.0, 100.0], &[]);
+        assert_eq!(fallback.len(), FALLBACK_DISTRIBUTION_BUCKETS);
+        assert_eq!(fallback[0].range, "60-68");
+        assert_eq!(fallback.last().unwrap().count, 1);
+    }
+
+    #[test]
+    fn test_score_spread_interpolates_quartiles() {
+        let spread = ScoreSpread::from_values(&[90.0, 60.0, 80.0, 70.0, 100.0]).unwrap();
+        assert_eq!(
+            (spread.min, spread.q1, spread.median, spread.q3, spread.max),
+            (60.0, 70.0, 80.0, 90.0, 100.0)
+        );
+        assert!((spread.std_dev - 200.0_f64.sqrt()).abs() < 1e-9);
+        assert!(ScoreSpread::from_values(&[]).is_none());
+    }
+
+    #[test]
+    fn test_percentile_rank_counts_ties_and_skips_empty_groups() {
+        let sorted = [50.0, 70.0, 70.0, 90.0];
+        assert_eq!(percentile_rank(&sorted, 70.0), Some(75.0));
+        assert_eq!(percentile_rank(&sorted, 90.0), Some(100.0));
+        assert_eq!(percentile_rank(&sorted, 40.0), Some(0.0));
+        assert_eq!(percentile_rank(&[], 70.0), None);
+    }
+
+    #[test]
+    fn test_normalize_gol_ignores_spacing_and_case() {
+        assert_eq!(normalize_gol("III / C"), "III/c");
+        assert_eq!(normalize_gol("iv/a"), "IV/a");
+        assert_eq!(normalize_gol(" Golongan 3 "), "Golongan 3");
+    }
+
+    #[test]
+    fn test_correlation_matrix_uses_employees_scored_on_both() {
+        let employee_scores: HashMap<i64, HashMap<i64, f64>> = [
+            (1, vec![(10, 60.0), (20, 65.0), (30, 80.0)]),
+            (2, vec![(10, 70.0), (20, 75.0), (30, 80.0)]),
+            (3, vec![(10, 80.0), (20, 85.0), (30, 60.0)]),
+            (4, vec![(10, 90.0), (30, 70.0)]),
+        ]
+        .into_iter()
+        .map(|(employee, scores)| (employee, scores.into_iter().collect()))
+        .collect();
+
+        let (matrix, pair_counts) = correlation_matrix(&[10, 20, 30, 40], &employee_scores);
+        assert!((matrix[0][1].unwrap() - 1.0).abs() < 1e-9);
+        assert_eq!(matrix[0][1], matrix[1][0]);
+        assert_eq!(pair_counts[0][1], 3);
+        assert_eq!(pair_counts[0][2], 4);
+        assert!(matrix[0][2].unwrap() < 0.0);
+        // Nobody was scored on competency 40
+        assert_eq!((matrix[0][3], pair_counts[3][3]), (None, 0));
+        assert_eq!(pearson(&[(1.0, 5.0), (2.0, 5.0), (3.0, 5.0)]), None);
+    }
+
+    #[tokio::test]
+    async fn test_dataset_stats_leave_out_scores_below_the_respondent_floor() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO datasets (id, name, created_at, updated_at)
+                VALUES (1, 'Ganjil 2024', datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, created_at, updated_at) VALUES
+                (1, 'Budi', datetime('now'), datetime('now')),
+                (2, 'Sari', datetime('now'), datetime('now'));
+             INSERT INTO competency_categories (id, name, display_order) VALUES (1, 'Perilaku', 0);
+             INSERT INTO competencies (id, name, display_order, category_id) VALUES
+                (1, 'Kerjasama', 0, 1), (2, 'Inisiatif', 1, 1);
+             INSERT INTO rating_mappings (dataset_id, text_value, numeric_value) VALUES
+                (1, 'Baik', 75), (1, 'Sangat Baik', 85);
+             INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, respondent_count, created_at) VALUES
+                (1, 1, 1, 'Baik', 75, 5, datetime('now')),
+                (2, 1, 1, 'Baik', 75, NULL, datetime('now')),
+                (1, 1, 2, 'Sangat Baik', 85, 2, datetime('now'));",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        set_setting(&pool, ANONYMITY_FLOOR_KEY, "3").await.unwrap();
+
+        let stats = compute_dataset_stats(&pool, 1).await.unwrap();
+        assert_eq!((stats.total_competencies, stats.total_scores), (1, 2));
+        assert_eq!(stats.average_score, 75.0);
+        let ratings: Vec<(&str, i64)> = stats
+            .rating_distribution
+            .iter()
+            .map(|r| (r.label.as_str(), r.count))
+            .collect();
+        assert_eq!(ratings, vec![("Sangat Baik", 0), ("Baik", 2)]);
+        let competencies: Vec<&str> = stats
+            .competency_stats
+            .iter()
+            .map(|c| c.competency.name.as_str())
+            .collect();
+        assert_eq!(competencies, vec!["Kerjasama"]);
+        assert_eq!(stats.category_stats.len(), 1);
+        assert_eq!(
+            (
+                stats.category_stats[0].average_score,
+                stats.category_stats[0].competency_count
+            ),
+            (75.0, 1)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_correlations_follow_the_floor_and_the_dataset_order() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO datasets (id, name, created_at, updated_at)
+                VALUES (1, 'Ganjil 2024', datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, created_at, updated_at) VALUES
+                (1, 'Budi', datetime('now'), datetime('now')),
+                (2, 'Sari', datetime('now'), datetime('now')),
+                (3, 'Dewi', datetime('now'), datetime('now'));
+             INSERT INTO competencies (id, name, display_order) VALUES
+                (1, 'Kerjasama', 0), (2, 'Inisiatif', 1), (3, 'Integritas', 2);
+             INSERT INTO dataset_competencies (dataset_id, competency_id, display_order) VALUES
+                (1, 2, 0), (1, 1, 1), (1, 3, 2);
+             INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, respondent_count, created_at) VALUES
+                (1, 1, 1, '70', 70, NULL, datetime('now')),
+                (2, 1, 1, '80', 80, NULL, datetime('now')),
+                (3, 1, 1, '90', 90, NULL, datetime('now')),
+                (1, 1, 2, '60', 60, NULL, datetime('now')),
+                (2, 1, 2, '75', 75, NULL, datetime('now')),
+                (3, 1, 2, '85', 85, NULL, datetime('now')),
+                (1, 1, 3, '80', 80, 2, datetime('now')),
+                (2, 1, 3, '70', 70, 2, datetime('now'));",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        set_setting(&pool, ANONYMITY_FLOOR_KEY, "3").await.unwrap();
+
+        let correlations = compute_competency_correlations(&pool, 1).await.unwrap();
+        let names: Vec<&str> = correlations
+            .competencies
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Inisiatif", "Kerjasama"]);
+        assert_eq!(correlations.pair_counts[0][1], 3);
+    }
+
+    #[tokio::test]
+    async fn test_anonymity_floor_changes_are_audited() {
+        let pool = test_pool().await;
+
+        assert!(save_anonymity_floor(&pool, MAX_ANONYMITY_FLOOR + 1)
+            .await
+            .is_err());
+        assert_eq!(save_anonymity_floor(&pool, 3).await.unwrap(), 3);
+        assert_eq!(load_anonymity_floor(&pool).await.unwrap(), 3);
+        let details: Vec<String> = sqlx::query_scalar(
+            "SELECT details FROM audit_log WHERE action = 'anonymity_floor_updated'",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(details.len(), 1);
+        let details: serde_json::Value = serde_json::from_str(&details[0]).unwrap();
+        assert_eq!(details["old_min_respondents"], 0);
+        assert_eq!(details["new_min_respondents"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_dataset_stats_use_normalized_values_but_keep_manual_edits() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO datasets (id, name, score_normalization, created_at, updated_at)
+                VALUES (1, 'Ganjil 2024', 'min_max', datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, created_at, updated_at) VALUES
+                (1, 'Budi', datetime('now'), datetime('now')),
+                (2, 'Sari', datetime('now'), datetime('now')),
+                (3, 'Dewi', datetime('now'), datetime('now')),
+                (4, 'Rudi', datetime('now'), datetime('now'));
+             INSERT INTO dataset_employees (dataset_id, employee_id, created_at, updated_at) VALUES
+                (1, 1, datetime('now'), datetime('now')), (1, 2, datetime('now'), datetime('now')),
+                (1, 3, datetime('now'), datetime('now')), (1, 4, datetime('now'), datetime('now'));
+             INSERT INTO competencies (id, name, display_order) VALUES (1, 'Kerjasama', 0);
+             INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, created_at) VALUES
+                (1, 1, 1, '60', 60, datetime('now')),
+                (2, 1, 1, '70', 70, datetime('now')),
+                (3, 1, 1, '50', 50, datetime('now')),
+                (4, 1, 1, '90', 90, datetime('now'));
+             INSERT INTO score_entries (dataset_id, employee_id, competency_id, rater, raw_value, numeric_value) VALUES
+                (1, 1, 1, 'Atasan', '60', 60),
+                (1, 2, 1, 'Atasan', '70', 70),
+                (1, 3, 1, 'manual', '50', 50),
+                (1, 4, 1, 'Rekan', '90', 90);",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Atasan's 60..70 stretch over the dataset's 50..90, Rekan's single value lands in the
+        // middle and Dewi's hand-edited 50 stays
+        let stats = compute_dataset_stats(&pool, 1).await.unwrap();
+        assert_eq!(stats.average_score, 65.0);
+        assert_eq!(stats.competency_stats[0].average_score, 65.0);
+        let performance = compute_employee_performance(&pool, 1, 3).await.unwrap();
+        assert_eq!(performance.average_score, 50.0);
+        // The employee list averages the same values
+        let averages = employee_averages(&pool, 1, 0).await.unwrap();
+        assert_eq!(averages[&1], (50.0, 1));
+        assert_eq!(averages[&2], (90.0, 1));
+        assert_eq!(averages[&4], (70.0, 1));
+    }
+
+    #[tokio::test]
+    async fn test_fixed_score_scale_applies_to_averages_and_rankings() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO datasets (id, name, score_scale, created_at, updated_at)
+                VALUES (1, 'Ganjil 2024', 4, datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, created_at, updated_at) VALUES
+                (1, 'Budi', datetime('now'), datetime('now')),
+                (2, 'Sari', datetime('now'), datetime('now'));
+             INSERT INTO dataset_employees (dataset_id, employee_id, created_at, updated_at) VALUES
+                (1, 1, datetime('now'), datetime('now')), (1, 2, datetime('now'), datetime('now'));
+             INSERT INTO competencies (id, name, display_order) VALUES (1, 'Kerjasama', 0);
+             INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, created_at) VALUES
+                (1, 1, 1, '3', 3, datetime('now')),
+                (2, 1, 1, '2', 2, datetime('now'));",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let stats = compute_dataset_stats(&pool, 1).await.unwrap();
+        let rankings = compute_rankings(&pool, 1, Some("average")).await.unwrap();
+
+        assert_eq!(stats.average_score, 62.5);
+        assert_eq!(stats.competency_stats[0].average_score, 62.5);
+        let averages: Vec<(&str, f64)> = rankings
+            .iter()
+            .map(|ranking| (ranking.employee.name.as_str(), ranking.score))
+            .collect();
+        assert_eq!(averages, vec![("Budi", 75.0), ("Sari", 50.0)]);
+        let percentiles: Vec<f64> = rankings.iter().map(|ranking| ranking.percentile).collect();
+        assert_eq!(percentiles, vec![100.0, 50.0]);
+    }
+}
@@ -0,0 +1,24 @@
+use crate::api_server::{self, ApiServerSettings, ApiServerSettingsView};
+use crate::auth::Role;
+use crate::AppState;
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_api_server_settings(
+    state: State<'_, AppState>,
+) -> Result<ApiServerSettingsView, String> {
+    state.require_role(Role::Admin).await?;
+    api_server::load_settings(&state.pool).await.map(Into::into)
+}
+
+/// Saves the LAN API settings; the server picks them up when the app restarts.
+#[tauri::command]
+pub async fn update_api_server_settings(
+    state: State<'_, AppState>,
+    settings: ApiServerSettings,
+) -> Result<ApiServerSettingsView, String> {
+    state.require_role(Role::Admin).await?;
+    api_server::save_settings(&state.pool, settings)
+        .await
+        .map(Into::into)
+}
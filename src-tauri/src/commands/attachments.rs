@@ -0,0 +1,41 @@
+use crate::attachments;
+use crate::auth::Role;
+use crate::db::models::Attachment;
+use crate::AppState;
+use std::path::Path;
+use tauri::State;
+
+#[tauri::command]
+pub async fn add_employee_attachment(
+    state: State<'_, AppState>,
+    employee_id: i64,
+    file_path: String,
+) -> Result<Attachment, String> {
+    state.require_role(Role::Editor).await?;
+    attachments::add_attachment(&state.pool, employee_id, Path::new(&file_path)).await
+}
+
+#[tauri::command]
+pub async fn list_employee_attachments(
+    state: State<'_, AppState>,
+    employee_id: i64,
+) -> Result<Vec<Attachment>, String> {
+    attachments::list_attachments(&state.pool, employee_id).await
+}
+
+/// Opens the stored copy with the system's default application.
+#[tauri::command]
+pub async fn open_attachment(state: State<'_, AppState>, attachment_id: i64) -> Result<(), String> {
+    let path = attachments::attachment_path(&state.pool, attachment_id).await?;
+    tauri_plugin_opener::open_path(path, None::<&str>)
+        .map_err(|e| format!("Failed to open attachment: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_attachment(
+    state: State<'_, AppState>,
+    attachment_id: i64,
+) -> Result<(), String> {
+    state.require_role(Role::Editor).await?;
+    attachments::delete_attachment(&state.pool, attachment_id).await
+}
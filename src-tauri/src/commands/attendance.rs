@@ -0,0 +1,128 @@
+use crate::auth::Role;
+use crate::commands::csv::read_options;
+use crate::commands::employee::normalize_nip;
+use crate::csv_parser::attendance::parse_attendance;
+use crate::csv_parser::CsvParser;
+use crate::db::audit::record_audit;
+use crate::db::models::Attendance;
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::State;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttendanceImportResult {
+    pub imported: usize,
+    /// Names (or NIPs) that match no employee; their rows are skipped.
+    pub unmatched: Vec<String>,
+}
+
+/// Imports a monthly attendance export. Rows are matched to employees by NIP, then by name;
+/// a month imported again replaces the earlier record.
+#[tauri::command]
+pub async fn import_attendance(
+    state: State<'_, AppState>,
+    file_path: String,
+    delimiter: Option<String>,
+    encoding: Option<String>,
+) -> Result<AttendanceImportResult, String> {
+    state.require_role(Role::Editor).await?;
+    let options = read_options(delimiter, encoding)?;
+    let records = parse_attendance(Path::new(&file_path), options)
+        .map_err(|e| format!("Failed to parse attendance: {}", e))?;
+    if records.is_empty() {
+        return Err("Attendance file has no rows".to_string());
+    }
+
+    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+    let employees: Vec<(i64, String, Option<String>)> =
+        sqlx::query_as("SELECT id, name, nip FROM employees")
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to load employees: {}", e))?;
+    let by_nip: HashMap<String, i64> = employees
+        .iter()
+        .filter_map(|(id, _, nip)| Some((normalize_nip(nip.as_deref())?, *id)))
+        .collect();
+    let by_name: HashMap<String, i64> = employees
+        .iter()
+        .map(|(id, name, _)| (CsvParser::clean_field(name).to_lowercase(), *id))
+        .collect();
+
+    let mut imported = 0;
+    let mut unmatched = Vec::new();
+    for record in &records {
+        let employee_id = normalize_nip(record.nip.as_deref())
+            .and_then(|nip| by_nip.get(&nip))
+            .or_else(|| {
+                record
+                    .employee_name
+                    .as_deref()
+                    .and_then(|name| by_name.get(&name.to_lowercase()))
+            });
+        let Some(employee_id) = employee_id else {
+            if let Some(label) = record.employee_name.clone().or_else(|| record.nip.clone()) {
+                if !unmatched.contains(&label) {
+                    unmatched.push(label);
+                }
+            }
+            continue;
+        };
+
+        sqlx::query(
+            "INSERT INTO attendance (employee_id, month, working_days, present_days, late_days, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, datetime('now'), datetime('now'))
+             ON CONFLICT(employee_id, month) DO UPDATE SET
+                working_days = excluded.working_days,
+                present_days = excluded.present_days,
+                late_days = excluded.late_days,
+                updated_at = excluded.updated_at",
+        )
+        .bind(employee_id)
+        .bind(&record.month)
+        .bind(record.working_days)
+        .bind(record.present_days)
+        .bind(record.late_days)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to save attendance: {}", e))?;
+        imported += 1;
+    }
+
+    record_audit(
+        &mut *tx,
+        "attendance_imported",
+        None,
+        &serde_json::json!({
+            "file_path": file_path,
+            "imported": imported,
+            "unmatched": unmatched.len(),
+        }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(AttendanceImportResult {
+        imported,
+        unmatched,
+    })
+}
+
+/// Monthly attendance of one employee, latest month first.
+#[tauri::command]
+pub async fn list_employee_attendance(
+    state: State<'_, AppState>,
+    employee_id: i64,
+) -> Result<Vec<Attendance>, String> {
+    sqlx::query_as::<_, Attendance>(
+        "SELECT * FROM attendance WHERE employee_id = ? ORDER BY month DESC",
+    )
+    .bind(employee_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| format!("Failed to load attendance: {}", e))
+}
@@ -0,0 +1,48 @@
+use crate::auth::{self, Role};
+use crate::db::models::User;
+use crate::AppState;
+use tauri::State;
+
+#[tauri::command]
+pub async fn login(
+    state: State<'_, AppState>,
+    username: String,
+    password: String,
+) -> Result<User, String> {
+    auth::login(&state.pool, &state.session, &username, &password).await
+}
+
+#[tauri::command]
+pub async fn logout(state: State<'_, AppState>) -> Result<(), String> {
+    auth::logout(&state.session);
+    Ok(())
+}
+
+/// The signed-in account, `None` when nobody is signed in.
+#[tauri::command]
+pub async fn get_current_user(state: State<'_, AppState>) -> Result<Option<User>, String> {
+    Ok(state.session.current())
+}
+
+#[tauri::command]
+pub async fn list_users(state: State<'_, AppState>) -> Result<Vec<User>, String> {
+    state.require_role(Role::Admin).await?;
+    auth::list_users(&state.pool).await
+}
+
+#[tauri::command]
+pub async fn create_user(
+    state: State<'_, AppState>,
+    username: String,
+    password: String,
+    role: Role,
+) -> Result<User, String> {
+    state.require_role(Role::Admin).await?;
+    auth::create_user(&state.pool, &username, &password, role).await
+}
+
+#[tauri::command]
+pub async fn delete_user(state: State<'_, AppState>, user_id: i64) -> Result<(), String> {
+    state.require_role(Role::Admin).await?;
+    auth::delete_user(&state.pool, &state.session, user_id).await
+}
@@ -0,0 +1,34 @@
+use crate::auth::Role;
+use crate::backup::{self, BackupFile, BackupRestore, BackupSettings};
+use crate::AppState;
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_backup_settings(state: State<'_, AppState>) -> Result<BackupSettings, String> {
+    backup::load_settings(&state.pool).await
+}
+
+#[tauri::command]
+pub async fn update_backup_settings(
+    state: State<'_, AppState>,
+    enabled: bool,
+    interval_hours: i64,
+    keep_last: i64,
+) -> Result<BackupSettings, String> {
+    state.require_role(Role::Admin).await?;
+    backup::save_settings(&state.pool, enabled, interval_hours, keep_last).await
+}
+
+#[tauri::command]
+pub async fn list_backups(state: State<'_, AppState>) -> Result<Vec<BackupFile>, String> {
+    backup::list_backups(&state.pool).await
+}
+
+#[tauri::command]
+pub async fn restore_backup(
+    state: State<'_, AppState>,
+    file_name: String,
+) -> Result<BackupRestore, String> {
+    state.require_role(Role::Admin).await?;
+    backup::restore_backup(&state.pool, &file_name).await
+}
@@ -0,0 +1,591 @@
+use crate::auth::Role;
+use crate::commands::analytics::{
+    compute_dataset_performances, load_anonymity_floor, ANONYMITY_FLOOR_FILTER,
+    DATASET_COMPETENCY_JOIN, DATASET_COMPETENCY_ORDER,
+};
+use crate::commands::dataset::normalize_period;
+use crate::commands::employee::{normalize_nip, EmployeeIdentityResolver};
+use crate::db::audit::record_audit;
+use crate::db::competency_aliases::{record_alias, resolve_competency};
+use crate::db::models::{AuditLogEntry, Competency, Dataset, Employee, RatingMapping, Score};
+use crate::db::score_entries::BUNDLE_RATER;
+use crate::scoring::weighting::{
+    load_weighting_scheme, WeightingConfigFile, WEIGHTING_CONFIG_FORMAT,
+};
+use crate::scoring::{self, WeightedScore};
+use crate::ui_config::load_dataset_ui_config;
+use crate::AppState;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use tauri::State;
+
+const BUNDLE_FORMAT: &str = "employee-performance-dataset";
+const BUNDLE_VERSION: u32 = 1;
+const AUDIT_SNAPSHOT_FORMAT: &str = "employee-performance-audit-snapshot";
+
+/// Self-contained copy of one dataset for moving it between installations.
+/// Ids inside the bundle are only meaningful within the bundle itself.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DatasetBundle {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub dataset: Dataset,
+    pub employees: Vec<Employee>,
+    pub competencies: Vec<Competency>,
+    pub rating_mappings: Vec<RatingMapping>,
+    pub scores: Vec<Score>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DatasetBundleImportResult {
+    pub dataset: Dataset,
+    pub employees_matched: usize,
+    pub employees_created: usize,
+    pub competencies_created: usize,
+    pub score_count: usize,
+}
+
+/// Everything an inspector needs to recompute a dataset's published totals; `totals` are
+/// the figures the app produced at export time.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuditSnapshot {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub data: DatasetBundle,
+    pub weighting: WeightingConfigFile,
+    pub totals: Vec<EmployeeTotal>,
+    pub audit_log: Vec<AuditLogEntry>,
+}
+
+/// Where the snapshot was written and the SHA-256 of its contents, also kept in the audit
+/// log so a later copy can be checked against it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuditSnapshotFile {
+    pub path: String,
+    pub sha256: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmployeeTotal {
+    pub employee_id: i64,
+    pub employee_name: String,
+    pub average_score: f64,
+    pub weighted: WeightedScore,
+}
+
+async fn load_dataset_bundle(pool: &SqlitePool, dataset_id: i64) -> Result<DatasetBundle, String> {
+    let dataset = sqlx::query_as::<_, Dataset>("SELECT * FROM datasets WHERE id = ?")
+        .bind(dataset_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to load dataset: {}", e))?;
+
+    // Scores can outlive the dataset link, and the import needs every employee they reference
+    let employees = sqlx::query_as::<_, Employee>(
+        "SELECT * FROM employees
+         WHERE id IN (SELECT employee_id FROM dataset_employees WHERE dataset_id = ?
+                      UNION SELECT employee_id FROM scores WHERE dataset_id = ?)
+         ORDER BY id",
+    )
+    .bind(dataset_id)
+    .bind(dataset_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to load employees: {}", e))?;
+
+    let floor = load_anonymity_floor(pool)
+        .await
+        .map_err(|e| format!("Failed to load anonymity floor: {}", e))?;
+    let competencies = sqlx::query_as::<_, Competency>(&format!(
+        "SELECT DISTINCT c.id, c.name, c.description, {order} AS display_order, c.category_id
+         FROM competencies c
+         JOIN scores s ON s.competency_id = c.id
+         {join}
+         WHERE s.dataset_id = ? AND {floor}
+         ORDER BY display_order, c.name",
+        join = DATASET_COMPETENCY_JOIN,
+        order = DATASET_COMPETENCY_ORDER,
+        floor = ANONYMITY_FLOOR_FILTER,
+    ))
+    .bind(dataset_id)
+    .bind(floor)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to load competencies: {}", e))?;
+
+    let rating_mappings = sqlx::query_as::<_, RatingMapping>(
+        "SELECT * FROM rating_mappings WHERE dataset_id = ? ORDER BY id",
+    )
+    .bind(dataset_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to load rating mappings: {}", e))?;
+
+    let scores = sqlx::query_as::<_, Score>(&format!(
+        "SELECT s.* FROM scores s WHERE s.dataset_id = ? AND {floor} ORDER BY s.id",
+        floor = ANONYMITY_FLOOR_FILTER,
+    ))
+    .bind(dataset_id)
+    .bind(floor)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to load scores: {}", e))?;
+
+    Ok(DatasetBundle {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        exported_at: Utc::now(),
+        dataset,
+        employees,
+        competencies,
+        rating_mappings,
+        scores,
+    })
+}
+
+#[tauri::command]
+pub async fn export_dataset_bundle(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    file_path: String,
+) -> Result<(), String> {
+    state.require_role(Role::Admin).await?;
+    let bundle = load_dataset_bundle(&state.pool, dataset_id).await?;
+
+    let json = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| format!("Failed to serialize dataset bundle: {}", e))?;
+    std::fs::write(&file_path, json).map_err(|e| format!("Failed to write bundle: {}", e))
+}
+
+/// Writes a read-only audit snapshot of one dataset. The dataset stays locked while the
+/// snapshot is taken so no import can change it halfway.
+#[tauri::command]
+pub async fn export_audit_snapshot(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    path: String,
+) -> Result<AuditSnapshotFile, String> {
+    state.require_role(Role::Admin).await?;
+    let pool = state.pool.clone();
+    let _lock = state
+        .dataset_locks
+        .acquire("audit-snapshot", &[dataset_id])?;
+
+    let data = load_dataset_bundle(&pool, dataset_id).await?;
+    let config = load_dataset_ui_config(&pool, dataset_id)
+        .await
+        .map_err(|e| format!("Failed to load UI config: {}", e))?;
+    let scheme = load_weighting_scheme(&pool)
+        .await
+        .map_err(|e| format!("Failed to load weighting scheme: {}", e))?;
+
+    let totals = compute_dataset_performances(&pool, dataset_id)
+        .await
+        .map_err(|e| format!("Failed to load employee performance: {}", e))?
+        .into_iter()
+        .map(|performance| EmployeeTotal {
+            weighted: scoring::compute_weighted_score(&performance, &config, &scheme),
+            employee_id: performance.employee.id,
+            employee_name: performance.employee.name,
+            average_score: performance.average_score,
+        })
+        .collect();
+
+    let audit_log = sqlx::query_as::<_, AuditLogEntry>(
+        "SELECT * FROM audit_log WHERE dataset_id = ? ORDER BY created_at, id",
+    )
+    .bind(dataset_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to load audit log: {}", e))?;
+
+    let snapshot = AuditSnapshot {
+        format: AUDIT_SNAPSHOT_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        exported_at: data.exported_at,
+        data,
+        weighting: WeightingConfigFile {
+            format: WEIGHTING_CONFIG_FORMAT.to_string(),
+            weighting: scheme,
+            rating_bands: config.rating_bands,
+        },
+        totals,
+        audit_log,
+    };
+
+    let json = serde_json::to_string_pretty(&snapshot)
+        .map_err(|e| format!("Failed to serialize audit snapshot: {}", e))?;
+    std::fs::write(&path, &json).map_err(|e| format!("Failed to write audit snapshot: {}", e))?;
+    let sha256 = format!("{:x}", Sha256::digest(json.as_bytes()));
+
+    record_audit(
+        &pool,
+        "audit_snapshot_exported",
+        Some(dataset_id),
+        &serde_json::json!({
+            "path": path,
+            "sha256": sha256,
+            "scores": snapshot.data.scores.len(),
+            "employees": snapshot.totals.len(),
+        }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+
+    Ok(AuditSnapshotFile { path, sha256 })
+}
+
+#[tauri::command]
+pub async fn import_dataset_bundle(
+    state: State<'_, AppState>,
+    file_path: String,
+) -> Result<DatasetBundleImportResult, String> {
+    state.require_role(Role::Editor).await?;
+    let content =
+        std::fs::read_to_string(&file_path).map_err(|e| format!("Failed to read bundle: {}", e))?;
+    let bundle: DatasetBundle = serde_json::from_str(&content)
+        .map_err(|e| format!("File is not a valid dataset bundle: {}", e))?;
+    import_bundle(&state.pool, &bundle, &file_path).await
+}
+
+/// Writes `bundle` as a new dataset, matching its employees and competencies to local ones.
+async fn import_bundle(
+    pool: &SqlitePool,
+    bundle: &DatasetBundle,
+    file_path: &str,
+) -> Result<DatasetBundleImportResult, String> {
+    if bundle.format != BUNDLE_FORMAT {
+        return Err(format!("Unsupported bundle format: {}", bundle.format));
+    }
+    if bundle.version > BUNDLE_VERSION {
+        return Err(format!(
+            "Bundle version {} is newer than supported version {}",
+            bundle.version, BUNDLE_VERSION
+        ));
+    }
+
+    let period = normalize_period(
+        bundle.dataset.period_year,
+        bundle.dataset.period_label.as_deref(),
+    )?;
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let dataset = sqlx::query_as::<_, Dataset>(
+        r#"
+        INSERT INTO datasets (name, description, source_file, period_year, period_label, submission_deadline, score_scale, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'))
+        RETURNING *
+        "#,
+    )
+    .bind(&bundle.dataset.name)
+    .bind(&bundle.dataset.description)
+    .bind(&bundle.dataset.source_file)
+    .bind(period.period_year)
+    .bind(&period.period_label)
+    .bind(bundle.dataset.submission_deadline)
+    .bind(bundle.dataset.score_scale)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to create dataset: {}", e))?;
+
+    // Reconcile bundle employees with local master data by NIP, then by name
+    let existing = sqlx::query_as::<_, Employee>("SELECT * FROM employees ORDER BY id")
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to load employees: {}", e))?;
+    let mut resolver = EmployeeIdentityResolver::default();
+    for employee in &existing {
+        resolver.resolve(employee.id, employee.nip.as_deref(), &employee.name);
+    }
+
+    // bundle employee id -> local employee id, and placeholder id -> local employee id
+    let mut employee_map: HashMap<i64, i64> = HashMap::new();
+    let mut placeholder_map: HashMap<i64, i64> = HashMap::new();
+    let mut employees_matched = 0usize;
+    let mut employees_created = 0usize;
+    for (index, employee) in bundle.employees.iter().enumerate() {
+        // Bundle employees get negative placeholder ids so they never collide with local ids
+        let placeholder = -(index as i64) - 1;
+        let resolved = resolver.resolve(placeholder, employee.nip.as_deref(), &employee.name);
+
+        let local_id = if resolved > 0 {
+            employees_matched += 1;
+            resolved
+        } else if let Some(local_id) = placeholder_map.get(&resolved) {
+            *local_id
+        } else {
+            employees_created += 1;
+            let local_id = sqlx::query_scalar::<_, i64>(
+                r#"
+                INSERT INTO employees (name, nip, gol, jabatan, sub_jabatan, position_type, created_at, updated_at)
+                VALUES (?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'))
+                RETURNING id
+                "#,
+            )
+            .bind(&employee.name)
+            .bind(normalize_nip(employee.nip.as_deref()))
+            .bind(&employee.gol)
+            .bind(&employee.jabatan)
+            .bind(&employee.sub_jabatan)
+            .bind(&employee.position_type)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to create employee {}: {}", employee.name, e))?;
+            placeholder_map.insert(resolved, local_id);
+            local_id
+        };
+        employee_map.insert(employee.id, local_id);
+
+        sqlx::query(
+            r#"
+            INSERT INTO dataset_employees (dataset_id, employee_id, created_at, updated_at)
+            VALUES (?, ?, datetime('now'), datetime('now'))
+            ON CONFLICT(dataset_id, employee_id) DO NOTHING
+            "#,
+        )
+        .bind(dataset.id)
+        .bind(local_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to link employee {}: {}", employee.name, e))?;
+    }
+
+    // Competencies are global and matched by name or alias
+    let mut competency_map: HashMap<i64, i64> = HashMap::new();
+    let mut competencies_created = 0usize;
+    for competency in &bundle.competencies {
+        let existing_id = resolve_competency(&mut tx, &competency.name)
+            .await
+            .map_err(|e| format!("Failed to fetch competency: {}", e))?;
+
+        let local_id = match existing_id {
+            Some(id) => id,
+            None => {
+                competencies_created += 1;
+                sqlx::query_scalar::<_, i64>(
+                    "INSERT INTO competencies (name, description, display_order) VALUES (?, ?, ?) RETURNING id",
+                )
+                .bind(&competency.name)
+                .bind(&competency.description)
+                .bind(competency.display_order)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to insert competency {}: {}", competency.name, e))?
+            }
+        };
+        record_alias(&mut tx, &competency.name, local_id)
+            .await
+            .map_err(|e| {
+                format!(
+                    "Failed to record competency alias {}: {}",
+                    competency.name, e
+                )
+            })?;
+        competency_map.insert(competency.id, local_id);
+
+        sqlx::query(
+            "INSERT OR IGNORE INTO dataset_competencies (dataset_id, competency_id, display_order) VALUES (?, ?, ?)",
+        )
+        .bind(dataset.id)
+        .bind(local_id)
+        .bind(competency.display_order)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to order competency {}: {}", competency.name, e))?;
+    }
+
+    for mapping in &bundle.rating_mappings {
+        sqlx::query(
+            r#"
+            INSERT INTO rating_mappings (dataset_id, text_value, numeric_value)
+            VALUES (?, ?, ?)
+            ON CONFLICT(dataset_id, text_value) DO UPDATE SET numeric_value = excluded.numeric_value
+            "#,
+        )
+        .bind(dataset.id)
+        .bind(&mapping.text_value)
+        .bind(mapping.numeric_value)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to insert rating mapping: {}", e))?;
+    }
+
+    let mut score_count = 0usize;
+    for score in &bundle.scores {
+        let employee_id = employee_map.get(&score.employee_id).ok_or_else(|| {
+            format!(
+                "Bundle score references unknown employee {}",
+                score.employee_id
+            )
+        })?;
+        let competency_id = competency_map.get(&score.competency_id).ok_or_else(|| {
+            format!(
+                "Bundle score references unknown competency {}",
+                score.competency_id
+            )
+        })?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, created_at)
+            VALUES (?, ?, ?, ?, ?, datetime('now'))
+            ON CONFLICT(dataset_id, employee_id, competency_id) DO UPDATE
+            SET raw_value = excluded.raw_value,
+                numeric_value = excluded.numeric_value
+            "#,
+        )
+        .bind(employee_id)
+        .bind(dataset.id)
+        .bind(competency_id)
+        .bind(&score.raw_value)
+        .bind(score.numeric_value)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to insert score: {}", e))?;
+        // An entry of its own, so a later append is combined with the bundled value
+        sqlx::query(
+            r#"
+            INSERT INTO score_entries (dataset_id, employee_id, competency_id, rater, raw_value, numeric_value, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, datetime('now'))
+            ON CONFLICT(dataset_id, employee_id, competency_id, rater) DO UPDATE
+            SET raw_value = excluded.raw_value,
+                numeric_value = excluded.numeric_value
+            "#,
+        )
+        .bind(dataset.id)
+        .bind(employee_id)
+        .bind(competency_id)
+        .bind(BUNDLE_RATER)
+        .bind(&score.raw_value)
+        .bind(score.numeric_value)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to insert score entry: {}", e))?;
+        score_count += 1;
+    }
+
+    record_audit(
+        &mut *tx,
+        "dataset_bundle_imported",
+        Some(dataset.id),
+        &serde_json::json!({
+            "file_path": file_path,
+            "exported_at": bundle.exported_at,
+            "employees_matched": employees_matched,
+            "employees_created": employees_created,
+            "score_count": score_count,
+        }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(DatasetBundleImportResult {
+        dataset,
+        employees_matched,
+        employees_created,
+        competencies_created,
+        score_count,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::analytics::ANONYMITY_FLOOR_KEY;
+    use crate::commands::import::write_dataset_scores;
+    use crate::csv_parser::ParsedScore;
+    use crate::db::settings::set_setting;
+    use crate::db::test_pool;
+
+    #[tokio::test]
+    async fn test_bundle_leaves_out_scores_below_the_respondent_floor() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO datasets (id, name, created_at, updated_at)
+                VALUES (1, 'Ganjil 2024', datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, created_at, updated_at)
+                VALUES (1, 'Budi', datetime('now'), datetime('now')),
+                       (2, 'Sari', datetime('now'), datetime('now'));
+             INSERT INTO dataset_employees (dataset_id, employee_id, created_at, updated_at)
+                VALUES (1, 1, datetime('now'), datetime('now'));
+             INSERT INTO competencies (id, name, display_order) VALUES
+                (1, 'Kerjasama', 0), (2, 'Inisiatif', 1);
+             INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, respondent_count, created_at) VALUES
+                (1, 1, 1, 'Baik', 75, 5, datetime('now')),
+                (1, 1, 2, 'Sangat Baik', 85, 2, datetime('now')),
+                (2, 1, 1, 'Baik', 70, 5, datetime('now'));",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        set_setting(&pool, ANONYMITY_FLOOR_KEY, "3").await.unwrap();
+
+        let bundle = load_dataset_bundle(&pool, 1).await.unwrap();
+        let competencies: Vec<&str> = bundle
+            .competencies
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(competencies, vec!["Kerjasama"]);
+        assert_eq!(bundle.scores.len(), 2);
+        assert_eq!(bundle.scores[0].numeric_value, Some(75.0));
+        // Sari has a score but no dataset link and is still exported
+        let employees: Vec<&str> = bundle.employees.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(employees, vec!["Budi", "Sari"]);
+    }
+
+    #[tokio::test]
+    async fn test_append_after_bundle_import_combines_both_values() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO datasets (id, name, created_at, updated_at)
+                VALUES (1, 'Ganjil 2024', datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, created_at, updated_at)
+                VALUES (1, 'Budi', datetime('now'), datetime('now'));
+             INSERT INTO dataset_employees (dataset_id, employee_id, created_at, updated_at)
+                VALUES (1, 1, datetime('now'), datetime('now'));
+             INSERT INTO competencies (id, name, display_order) VALUES (1, 'Kerjasama', 0);
+             INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, created_at)
+                VALUES (1, 1, 1, '75', 75, datetime('now'));",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let bundle = load_dataset_bundle(&pool, 1).await.unwrap();
+        let imported = import_bundle(&pool, &bundle, "ganjil.json").await.unwrap();
+        let scores = vec![ParsedScore {
+            employee_name: "Budi".to_string(),
+            competency: "Kerjasama".to_string(),
+            value: "85".to_string(),
+            ordinal: None,
+            submitted_at: None,
+            source_row: None,
+            source_column: None,
+            respondent_count: None,
+        }];
+        let mut tx = pool.begin().await.unwrap();
+        write_dataset_scores(&mut tx, imported.dataset.id, &scores, &[])
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+
+        let value: Option<f64> =
+            sqlx::query_scalar("SELECT numeric_value FROM scores WHERE dataset_id = ?")
+                .bind(imported.dataset.id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(value, Some(80.0));
+    }
+}
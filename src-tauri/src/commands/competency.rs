@@ -0,0 +1,426 @@
+use crate::auth::Role;
+use crate::db::audit::record_audit;
+use crate::db::competency_aliases::{self, CompetencyAlias};
+use crate::db::models::{Competency, CompetencyCategory};
+use crate::AppState;
+use sqlx::SqlitePool;
+use tauri::State;
+
+pub(crate) async fn load_competency_categories(
+    pool: &SqlitePool,
+) -> Result<Vec<CompetencyCategory>, sqlx::Error> {
+    sqlx::query_as::<_, CompetencyCategory>(
+        "SELECT * FROM competency_categories ORDER BY display_order, name",
+    )
+    .fetch_all(pool)
+    .await
+}
+
+fn normalize_category_name(name: &str) -> Result<String, String> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err("Category name cannot be empty".to_string());
+    }
+    Ok(trimmed.to_string())
+}
+
+fn category_write_error(e: sqlx::Error, action: &str) -> String {
+    match e.as_database_error() {
+        Some(db_error) if db_error.is_unique_violation() => {
+            "A category with this name already exists".to_string()
+        }
+        _ if matches!(e, sqlx::Error::RowNotFound) => "Category not found".to_string(),
+        _ => format!("Failed to {} category: {}", action, e),
+    }
+}
+
+#[tauri::command]
+pub async fn list_competency_categories(
+    state: State<'_, AppState>,
+) -> Result<Vec<CompetencyCategory>, String> {
+    load_competency_categories(&state.pool)
+        .await
+        .map_err(|e| format!("Failed to load competency categories: {}", e))
+}
+
+/// New categories are placed after the existing ones.
+#[tauri::command]
+pub async fn create_competency_category(
+    state: State<'_, AppState>,
+    name: String,
+    description: Option<String>,
+) -> Result<CompetencyCategory, String> {
+    state.require_role(Role::Editor).await?;
+    insert_category(&state.pool, &name, description).await
+}
+
+async fn insert_category(
+    pool: &SqlitePool,
+    name: &str,
+    description: Option<String>,
+) -> Result<CompetencyCategory, String> {
+    let name = normalize_category_name(name)?;
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let category = sqlx::query_as::<_, CompetencyCategory>(
+        "INSERT INTO competency_categories (name, description, display_order)
+         SELECT ?, ?, COALESCE(MAX(display_order) + 1, 0) FROM competency_categories
+         RETURNING *",
+    )
+    .bind(name)
+    .bind(description)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| category_write_error(e, "create"))?;
+    record_audit(
+        &mut *tx,
+        "competency_category_created",
+        None,
+        &serde_json::json!({ "category_id": category.id, "name": category.name }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    Ok(category)
+}
+
+#[tauri::command]
+pub async fn update_competency_category(
+    state: State<'_, AppState>,
+    id: i64,
+    name: String,
+    description: Option<String>,
+    display_order: Option<i32>,
+) -> Result<CompetencyCategory, String> {
+    state.require_role(Role::Editor).await?;
+    update_category(&state.pool, id, &name, description, display_order).await
+}
+
+async fn update_category(
+    pool: &SqlitePool,
+    id: i64,
+    name: &str,
+    description: Option<String>,
+    display_order: Option<i32>,
+) -> Result<CompetencyCategory, String> {
+    let name = normalize_category_name(name)?;
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let previous =
+        sqlx::query_as::<_, CompetencyCategory>("SELECT * FROM competency_categories WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to load category: {}", e))?
+            .ok_or_else(|| "Category not found".to_string())?;
+    let category = sqlx::query_as::<_, CompetencyCategory>(
+        "UPDATE competency_categories
+         SET name = ?, description = ?, display_order = COALESCE(?, display_order)
+         WHERE id = ?
+         RETURNING *",
+    )
+    .bind(name)
+    .bind(description)
+    .bind(display_order)
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| category_write_error(e, "update"))?;
+    record_audit(
+        &mut *tx,
+        "competency_category_updated",
+        None,
+        &serde_json::json!({
+            "category_id": id,
+            "old_name": previous.name,
+            "new_name": category.name,
+            "old_description": previous.description,
+            "new_description": category.description,
+            "old_display_order": previous.display_order,
+            "new_display_order": category.display_order,
+        }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    Ok(category)
+}
+
+/// Competencies of a deleted category become uncategorised.
+#[tauri::command]
+pub async fn delete_competency_category(state: State<'_, AppState>, id: i64) -> Result<(), String> {
+    state.require_role(Role::Editor).await?;
+    delete_category(&state.pool, id).await
+}
+
+async fn delete_category(pool: &SqlitePool, id: i64) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let name: Option<String> =
+        sqlx::query_scalar("DELETE FROM competency_categories WHERE id = ? RETURNING name")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to delete category: {}", e))?;
+    if let Some(name) = name {
+        record_audit(
+            &mut *tx,
+            "competency_category_deleted",
+            None,
+            &serde_json::json!({ "category_id": id, "name": name }),
+        )
+        .await
+        .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    }
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))
+}
+
+/// Moves a competency into a category, or out of any category when `category_id` is `None`.
+#[tauri::command]
+pub async fn set_competency_category(
+    state: State<'_, AppState>,
+    competency_id: i64,
+    category_id: Option<i64>,
+) -> Result<Competency, String> {
+    state.require_role(Role::Editor).await?;
+    assign_category(&state.pool, competency_id, category_id).await
+}
+
+async fn assign_category(
+    pool: &SqlitePool,
+    competency_id: i64,
+    category_id: Option<i64>,
+) -> Result<Competency, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    if let Some(category_id) = category_id {
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM competency_categories WHERE id = ?)")
+                .bind(category_id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to load category: {}", e))?;
+        if !exists {
+            return Err(format!("Category {} not found", category_id));
+        }
+    }
+
+    let previous: Option<i64> =
+        sqlx::query_scalar::<_, Option<i64>>("SELECT category_id FROM competencies WHERE id = ?")
+            .bind(competency_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to load competency: {}", e))?
+            .ok_or_else(|| format!("Competency {} not found", competency_id))?;
+    let competency = sqlx::query_as::<_, Competency>(
+        "UPDATE competencies SET category_id = ? WHERE id = ? RETURNING *",
+    )
+    .bind(category_id)
+    .bind(competency_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to update competency: {}", e))?;
+    record_audit(
+        &mut *tx,
+        "competency_category_set",
+        None,
+        &serde_json::json!({
+            "competency_id": competency_id,
+            "old_category_id": previous,
+            "new_category_id": category_id,
+        }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    Ok(competency)
+}
+
+#[tauri::command]
+pub async fn list_competency_aliases(
+    state: State<'_, AppState>,
+) -> Result<Vec<CompetencyAlias>, String> {
+    sqlx::query_as::<_, CompetencyAlias>(
+        "SELECT alias_key, competency_id FROM competency_aliases ORDER BY competency_id, alias_key",
+    )
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| format!("Failed to load competency aliases: {}", e))
+}
+
+/// Folds competency `source_id` into `target_id`: its scores, dataset order and weights move
+/// to the target and its name becomes an alias, so later imports land on the target. Rows
+/// the target already held are dropped and counted in the audit entry.
+#[tauri::command]
+pub async fn merge_competencies(
+    state: State<'_, AppState>,
+    source_id: i64,
+    target_id: i64,
+) -> Result<Competency, String> {
+    state.require_role(Role::Editor).await?;
+    if source_id == target_id {
+        return Err("A competency cannot be merged into itself".to_string());
+    }
+
+    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+    let target = sqlx::query_as::<_, Competency>("SELECT * FROM competencies WHERE id = ?")
+        .bind(target_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to load competency: {}", e))?
+        .ok_or_else(|| format!("Competency {} not found", target_id))?;
+    let source_name: String = sqlx::query_scalar("SELECT name FROM competencies WHERE id = ?")
+        .bind(source_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to load competency: {}", e))?
+        .ok_or_else(|| format!("Competency {} not found", source_id))?;
+
+    let outcome = competency_aliases::merge_competencies(&mut tx, source_id, target_id)
+        .await
+        .map_err(|e| format!("Failed to merge competencies: {}", e))?;
+    record_audit(
+        &mut *tx,
+        "competencies_merged",
+        None,
+        &serde_json::json!({
+            "source_id": source_id,
+            "source_name": source_name,
+            "target_id": target_id,
+            "target_name": target.name,
+            "dropped_entries": outcome.dropped_entries,
+            "dropped_scores": outcome.dropped_scores,
+        }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::analytics::{
+        compute_dataset_stats, compute_employee_performance, ANONYMITY_FLOOR_KEY,
+    };
+    use crate::db::settings::set_setting;
+    use crate::db::test_pool;
+
+    #[tokio::test]
+    async fn test_category_averages_pool_their_competencies() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO datasets (id, name, created_at, updated_at)
+             VALUES (1, 'Penilaian 2024', datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, created_at, updated_at)
+             VALUES (1, 'Budi', datetime('now'), datetime('now')),
+                    (2, 'Sari', datetime('now'), datetime('now'));
+             INSERT INTO dataset_employees (dataset_id, employee_id, created_at, updated_at)
+             VALUES (1, 1, datetime('now'), datetime('now')),
+                    (1, 2, datetime('now'), datetime('now'));
+             INSERT INTO competency_categories (id, name, display_order)
+             VALUES (1, 'Teknis', 1), (2, 'Perilaku', 0), (3, 'Kualitas', 2);
+             INSERT INTO competencies (id, name, display_order, category_id)
+             VALUES (1, 'Kerjasama', 0, 2), (2, 'Integritas', 1, 2),
+                    (3, 'Analisis', 2, 1), (4, 'Inisiatif', 3, NULL);
+             INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, created_at)
+             VALUES (1, 1, 1, '80', 80.0, datetime('now')),
+                    (1, 1, 2, '70', 70.0, datetime('now')),
+                    (1, 1, 3, '90', 90.0, datetime('now')),
+                    (1, 1, 4, '60', 60.0, datetime('now')),
+                    (2, 1, 1, '60', 60.0, datetime('now')),
+                    (2, 1, 3, '70', 70.0, datetime('now'));
+             INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, respondent_count, created_at)
+             VALUES (2, 1, 2, '10', 10.0, 1, datetime('now'))",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        // The score from a single respondent stays out of every average
+        set_setting(&pool, ANONYMITY_FLOOR_KEY, "3").await.unwrap();
+
+        let stats = compute_dataset_stats(&pool, 1).await.unwrap();
+        let categories: Vec<(&str, f64, i64, i64)> = stats
+            .category_stats
+            .iter()
+            .map(|c| {
+                (
+                    c.category.name.as_str(),
+                    c.average_score,
+                    c.employee_count,
+                    c.competency_count,
+                )
+            })
+            .collect();
+        assert_eq!(
+            categories,
+            vec![("Perilaku", 70.0, 2, 2), ("Teknis", 80.0, 2, 1)]
+        );
+
+        let performance = compute_employee_performance(&pool, 1, 1).await.unwrap();
+        let averages: Vec<(&str, f64, i64)> = performance
+            .category_averages
+            .iter()
+            .map(|c| {
+                (
+                    c.category.name.as_str(),
+                    c.average_score,
+                    c.competency_count,
+                )
+            })
+            .collect();
+        assert_eq!(averages, vec![("Perilaku", 75.0, 2), ("Teknis", 90.0, 1)]);
+        assert_eq!(performance.average_score, 75.0);
+    }
+
+    #[tokio::test]
+    async fn test_category_changes_are_audited() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO competencies (id, name, display_order) VALUES (1, 'Kerjasama', 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let category = insert_category(&pool, " Perilaku ", None).await.unwrap();
+        assert_eq!(category.name, "Perilaku");
+        update_category(&pool, category.id, "Perilaku Kerja", None, Some(4))
+            .await
+            .unwrap();
+        assign_category(&pool, 1, Some(category.id)).await.unwrap();
+        assert!(assign_category(&pool, 1, Some(99)).await.is_err());
+        delete_category(&pool, category.id).await.unwrap();
+        delete_category(&pool, category.id).await.unwrap();
+
+        let entries: Vec<(String, String)> =
+            sqlx::query_as("SELECT action, details FROM audit_log ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        let actions: Vec<&str> = entries.iter().map(|(action, _)| action.as_str()).collect();
+        assert_eq!(
+            actions,
+            vec![
+                "competency_category_created",
+                "competency_category_updated",
+                "competency_category_set",
+                "competency_category_deleted",
+            ]
+        );
+        let updated: serde_json::Value = serde_json::from_str(&entries[1].1).unwrap();
+        assert_eq!(updated["old_name"], "Perilaku");
+        assert_eq!(updated["new_name"], "Perilaku Kerja");
+        assert_eq!(updated["new_display_order"], 4);
+    }
+}
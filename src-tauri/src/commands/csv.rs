@@ -1,23 +1,154 @@
-use crate::csv_parser::{CsvParser, CsvPreview, ParsedEmployee, ParsedScore};
+use crate::csv_parser::formats::{
+    aggregate_respondents, FormatCandidate, FormatDetection, FormatRegistry, SourceTable,
+};
+use crate::csv_parser::leadership::{parse_leadership_scores, ParsedLeadershipScore};
+use crate::csv_parser::{
+    CsvParser, CsvPreview, ParsedEmployee, ParsedScore, ReadOptions, RespondentAggregation,
+};
+use crate::db::models::CreateRatingMapping;
+use encoding_rs::Encoding;
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::str::FromStr;
+
+/// An explicit delimiter from the UI; empty means detect, `tab` or `\t` a tab.
+pub(crate) fn parse_delimiter(delimiter: Option<String>) -> Result<Option<char>, String> {
+    match delimiter.as_deref() {
+        None | Some("") => Ok(None),
+        Some("\\t") | Some("tab") => Ok(Some('\t')),
+        Some(value) => {
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => Ok(Some(ch)),
+                _ => Err(format!("Delimiter must be a single character: {}", value)),
+            }
+        }
+    }
+}
+
+/// Overrides from the UI; `encoding` is a WHATWG label such as `windows-1252` or `utf-16le`.
+pub(crate) fn read_options(
+    delimiter: Option<String>,
+    encoding: Option<String>,
+) -> Result<ReadOptions, String> {
+    let encoding = match encoding.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(label) => Some(
+            Encoding::for_label(label.as_bytes())
+                .ok_or_else(|| format!("Unknown encoding: {}", label))?,
+        ),
+    };
+    Ok(ReadOptions {
+        delimiter: parse_delimiter(delimiter)?,
+        encoding,
+    })
+}
 
 #[tauri::command]
-pub async fn preview_csv(file_path: String, max_rows: usize) -> Result<CsvPreview, String> {
+pub async fn preview_csv(
+    file_path: String,
+    max_rows: usize,
+    delimiter: Option<String>,
+    encoding: Option<String>,
+) -> Result<CsvPreview, String> {
     let path = PathBuf::from(file_path);
 
-    CsvParser::preview(&path, max_rows).map_err(|e| e.to_string())
+    CsvParser::preview(&path, max_rows, read_options(delimiter, encoding)?)
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub async fn parse_employee_csv(file_path: String) -> Result<Vec<ParsedEmployee>, String> {
+pub async fn parse_employee_csv(
+    file_path: String,
+    format: Option<String>,
+    delimiter: Option<String>,
+    encoding: Option<String>,
+) -> Result<Vec<ParsedEmployee>, String> {
     let path = PathBuf::from(file_path);
 
-    CsvParser::parse_employee_csv(&path).map_err(|e| e.to_string())
+    FormatRegistry::default()
+        .parse_employees(&path, format.as_deref(), read_options(delimiter, encoding)?)
+        .map_err(|e| e.to_string())
 }
 
+/// `aggregation` (`mean`, `median` or `latest`) combines the respondent rows of a Google
+/// Forms export per employee and question, rating answers through `rating_mappings`.
 #[tauri::command]
-pub async fn parse_scores_csv(file_path: String) -> Result<Vec<ParsedScore>, String> {
+pub async fn parse_scores_csv(
+    file_path: String,
+    format: Option<String>,
+    delimiter: Option<String>,
+    encoding: Option<String>,
+    aggregation: Option<String>,
+    rating_mappings: Option<Vec<CreateRatingMapping>>,
+) -> Result<Vec<ParsedScore>, String> {
     let path = PathBuf::from(file_path);
+    let aggregation = aggregation
+        .as_deref()
+        .map(RespondentAggregation::from_str)
+        .transpose()?;
+
+    let scores = FormatRegistry::default()
+        .parse_scores(&path, format.as_deref(), read_options(delimiter, encoding)?)
+        .map_err(|e| e.to_string())?;
+    Ok(match aggregation {
+        Some(aggregation) => {
+            let rating_map: HashMap<String, f64> = rating_mappings
+                .unwrap_or_default()
+                .into_iter()
+                .map(|mapping| (mapping.text_value, mapping.numeric_value))
+                .collect();
+            aggregate_respondents(scores, aggregation, &rating_map)
+        }
+        None => scores,
+    })
+}
 
-    CsvParser::parse_scores_csv(&path).map_err(|e| e.to_string())
+/// Penilaian Pimpinan scores from a name / score sheet; store them with
+/// `import_leadership_scores`.
+#[tauri::command]
+pub async fn parse_leadership_csv(
+    file_path: String,
+    delimiter: Option<String>,
+    encoding: Option<String>,
+) -> Result<Vec<ParsedLeadershipScore>, String> {
+    let options = read_options(delimiter, encoding)?;
+    parse_leadership_scores(&PathBuf::from(file_path), options).map_err(|e| e.to_string())
+}
+
+/// Employees from pasted text, e.g. rows copied out of a spreadsheet
+#[tauri::command]
+pub async fn parse_employee_text(
+    content: String,
+    delimiter: Option<String>,
+) -> Result<Vec<ParsedEmployee>, String> {
+    SourceTable::from_text(&content, parse_delimiter(delimiter)?)
+        .and_then(|table| table.employees())
+        .map_err(|e| e.to_string())
+}
+
+/// Scores from pasted text in either the wide or the long layout
+#[tauri::command]
+pub async fn parse_scores_text(content: String) -> Result<Vec<ParsedScore>, String> {
+    SourceTable::from_text(&content, None)
+        .and_then(|table| table.detected_scores())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn detect_format(
+    file_path: String,
+    delimiter: Option<String>,
+    encoding: Option<String>,
+) -> Result<FormatDetection, String> {
+    let path = PathBuf::from(file_path);
+
+    FormatRegistry::default()
+        .detect(&path, read_options(delimiter, encoding)?)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_import_formats() -> Result<Vec<FormatCandidate>, String> {
+    Ok(FormatRegistry::default().formats())
 }
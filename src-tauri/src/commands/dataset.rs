@@ -1,25 +1,116 @@
-use crate::db::models::{CreateDataset, Dataset};
+use crate::auth::Role;
+use crate::commands::employee::EmployeeIdentityResolver;
+use crate::csv_parser::CsvParser;
+use crate::data_quality::{assess_dataset, DatasetQuality};
+use crate::db::audit::record_audit;
+use crate::db::models::{
+    CreateDataset, Dataset, DatasetNote, DatasetPeriod, Employee, ValidationIssue,
+};
+use crate::db::score_entries::{refresh_aggregated_scores, ScoreAggregation, ScoreNormalization};
+use crate::webhooks::{self, WebhookPayload, MERGE_COMPLETED};
 use crate::AppState;
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, QueryBuilder, Sqlite, SqlitePool};
+use std::collections::HashMap;
+use std::str::FromStr;
 use tauri::State;
 
+const MIN_PERIOD_YEAR: i32 = 2000;
+const MAX_PERIOD_YEAR: i32 = 2100;
+const MAX_PERIOD_LABEL_LEN: usize = 50;
+const MAX_TAG_LEN: usize = 40;
+
+/// Chronological ordering shared by every dataset listing: structured period first,
+/// falling back to the creation year for datasets imported before periods existed.
+pub(crate) const DATASET_PERIOD_ORDER: &str =
+    "COALESCE(period_year, CAST(strftime('%Y', created_at) AS INTEGER)) DESC, IFNULL(period_label, '') DESC, created_at DESC";
+
+/// Validate and canonicalize period metadata ("semester 1" becomes "Semester I").
+pub(crate) fn normalize_period(
+    period_year: Option<i32>,
+    period_label: Option<&str>,
+) -> Result<DatasetPeriod, String> {
+    if let Some(year) = period_year {
+        if !(MIN_PERIOD_YEAR..=MAX_PERIOD_YEAR).contains(&year) {
+            return Err(format!(
+                "Period year must be between {} and {}",
+                MIN_PERIOD_YEAR, MAX_PERIOD_YEAR
+            ));
+        }
+    }
+
+    let period_label = match period_label
+        .map(str::trim)
+        .filter(|label| !label.is_empty())
+    {
+        None => None,
+        Some(label) => {
+            if label.chars().count() > MAX_PERIOD_LABEL_LEN {
+                return Err(format!(
+                    "Period label cannot exceed {} characters",
+                    MAX_PERIOD_LABEL_LEN
+                ));
+            }
+            let canonical = match label.to_lowercase().split_whitespace().collect::<Vec<_>>()[..] {
+                ["semester", "1" | "i"] => "Semester I".to_string(),
+                ["semester", "2" | "ii"] => "Semester II".to_string(),
+                _ => label.split_whitespace().collect::<Vec<_>>().join(" "),
+            };
+            Some(canonical)
+        }
+    };
+
+    if period_label.is_some() && period_year.is_none() {
+        return Err("Period year is required when a period label is set".to_string());
+    }
+
+    Ok(DatasetPeriod {
+        period_year,
+        period_label,
+    })
+}
+
+/// Year used when reporting a dataset, preferring the structured period over created_at.
+pub(crate) fn dataset_period_year(dataset: &Dataset) -> i32 {
+    use chrono::Datelike;
+    dataset
+        .period_year
+        .unwrap_or_else(|| dataset.created_at.with_timezone(&chrono::Local).year())
+}
+
+pub(crate) fn dataset_period_key(
+    dataset: &Dataset,
+) -> (i32, String, chrono::DateTime<chrono::Utc>) {
+    (
+        dataset_period_year(dataset),
+        dataset.period_label.clone().unwrap_or_default(),
+        dataset.created_at,
+    )
+}
+
 #[tauri::command]
 pub async fn create_dataset(
     state: State<'_, AppState>,
     dataset: CreateDataset,
 ) -> Result<Dataset, String> {
+    state.require_role(Role::Editor).await?;
     let pool = state.pool.clone();
 
+    let period = normalize_period(dataset.period_year, dataset.period_label.as_deref())?;
+
     let result = sqlx::query_as::<_, Dataset>(
         r#"
-        INSERT INTO datasets (name, description, source_file, created_at, updated_at)
-        VALUES (?, ?, ?, datetime('now'), datetime('now'))
+        INSERT INTO datasets (name, description, source_file, period_year, period_label, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, datetime('now'), datetime('now'))
         RETURNING *
         "#,
     )
     .bind(&dataset.name)
     .bind(&dataset.description)
     .bind(&dataset.source_file)
+    .bind(period.period_year)
+    .bind(&period.period_label)
     .fetch_one(&pool)
     .await
     .map_err(|e| e.to_string())?;
@@ -28,32 +119,108 @@ pub async fn create_dataset(
 }
 
 #[tauri::command]
-pub async fn list_datasets(state: State<'_, AppState>) -> Result<Vec<Dataset>, String> {
-    let pool = state.pool.clone();
+pub async fn list_datasets(
+    state: State<'_, AppState>,
+    period_year: Option<i32>,
+    period_label: Option<String>,
+    include_archived: Option<bool>,
+    tag: Option<String>,
+) -> Result<Vec<Dataset>, String> {
+    fetch_datasets(
+        &state.pool,
+        period_year,
+        period_label.as_deref(),
+        include_archived.unwrap_or(false),
+        tag.as_deref(),
+    )
+    .await
+}
 
-    let datasets = sqlx::query_as::<_, Dataset>("SELECT * FROM datasets ORDER BY created_at DESC")
-        .fetch_all(&pool)
+/// Datasets in period order with their tags, filtered like `list_datasets`.
+pub(crate) async fn fetch_datasets(
+    pool: &SqlitePool,
+    period_year: Option<i32>,
+    period_label: Option<&str>,
+    include_archived: bool,
+    tag: Option<&str>,
+) -> Result<Vec<Dataset>, String> {
+    let mut query = QueryBuilder::<Sqlite>::new("SELECT * FROM datasets WHERE 1 = 1");
+    if !include_archived {
+        query.push(" AND archived_at IS NULL");
+    }
+    if let Some(year) = period_year {
+        query.push(" AND period_year = ").push_bind(year);
+    }
+    if let Some(label) = period_label
+        .map(str::trim)
+        .filter(|label| !label.is_empty())
+    {
+        query
+            .push(" AND LOWER(period_label) = ")
+            .push_bind(label.to_lowercase());
+    }
+    if let Some(tag) = tag.map(str::trim).filter(|tag| !tag.is_empty()) {
+        query
+            .push(" AND id IN (SELECT dataset_id FROM dataset_tags WHERE tag = ")
+            .push_bind(tag)
+            .push(")");
+    }
+    query.push(" ORDER BY ").push(DATASET_PERIOD_ORDER);
+
+    let mut datasets = query
+        .build_query_as::<Dataset>()
+        .fetch_all(pool)
         .await
         .map_err(|e| e.to_string())?;
+    attach_tags(pool, &mut datasets).await?;
 
     Ok(datasets)
 }
 
+async fn attach_tags(pool: &SqlitePool, datasets: &mut [Dataset]) -> Result<(), String> {
+    if datasets.is_empty() {
+        return Ok(());
+    }
+    let ids: Vec<i64> = datasets.iter().map(|dataset| dataset.id).collect();
+    let mut query =
+        QueryBuilder::<Sqlite>::new("SELECT dataset_id, tag FROM dataset_tags WHERE dataset_id IN");
+    push_id_list(&mut query, &ids);
+    query.push(" ORDER BY tag COLLATE NOCASE");
+    let tags: Vec<(i64, String)> = query
+        .build_query_as()
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to load dataset tags: {}", e))?;
+
+    let mut tags_by_dataset: HashMap<i64, Vec<String>> = HashMap::new();
+    for (dataset_id, tag) in tags {
+        tags_by_dataset.entry(dataset_id).or_default().push(tag);
+    }
+    for dataset in datasets.iter_mut() {
+        dataset.tags = tags_by_dataset.remove(&dataset.id).unwrap_or_default();
+    }
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn get_dataset(state: State<'_, AppState>, id: i64) -> Result<Dataset, String> {
-    let pool = state.pool.clone();
+    fetch_dataset(&state.pool, id).await
+}
 
-    let dataset = sqlx::query_as::<_, Dataset>("SELECT * FROM datasets WHERE id = ?")
+pub(crate) async fn fetch_dataset(pool: &SqlitePool, id: i64) -> Result<Dataset, String> {
+    let mut dataset = sqlx::query_as::<_, Dataset>("SELECT * FROM datasets WHERE id = ?")
         .bind(id)
-        .fetch_one(&pool)
+        .fetch_one(pool)
         .await
         .map_err(|e| e.to_string())?;
+    attach_tags(pool, std::slice::from_mut(&mut dataset)).await?;
 
     Ok(dataset)
 }
 
 #[tauri::command]
 pub async fn delete_dataset(state: State<'_, AppState>, id: i64) -> Result<(), String> {
+    state.require_role(Role::Admin).await?;
     let pool = state.pool.clone();
 
     sqlx::query("DELETE FROM datasets WHERE id = ?")
@@ -65,13 +232,243 @@ pub async fn delete_dataset(state: State<'_, AppState>, id: i64) -> Result<(), S
     Ok(())
 }
 
+const ARCHIVE_DATASETS: &str = "UPDATE datasets SET archived_at = datetime('now'), updated_at = datetime('now') WHERE archived_at IS NULL AND id IN";
+const UNARCHIVE_DATASETS: &str = "UPDATE datasets SET archived_at = NULL, updated_at = datetime('now') WHERE archived_at IS NOT NULL AND id IN";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkDatasetResult {
+    pub requested: usize,
+    pub affected: u64,
+    /// Requested ids that did not match any dataset
+    pub missing_ids: Vec<i64>,
+}
+
+fn push_id_list(query: &mut QueryBuilder<'_, Sqlite>, ids: &[i64]) {
+    query.push(" (");
+    {
+        let mut separated = query.separated(", ");
+        for id in ids {
+            separated.push_bind(*id);
+        }
+    }
+    query.push(")");
+}
+
+/// Runs `statement` (an UPDATE or DELETE ending in `WHERE id IN`) against every existing
+/// dataset in `ids` inside one transaction, recording a single audit entry.
+async fn apply_bulk_dataset_change(
+    pool: &SqlitePool,
+    ids: Vec<i64>,
+    statement: &str,
+    action: &str,
+) -> Result<BulkDatasetResult, String> {
+    let mut ids = ids;
+    ids.sort_unstable();
+    ids.dedup();
+    if ids.is_empty() {
+        return Ok(BulkDatasetResult {
+            requested: 0,
+            affected: 0,
+            missing_ids: Vec::new(),
+        });
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    let mut lookup = QueryBuilder::<Sqlite>::new("SELECT id FROM datasets WHERE id IN");
+    push_id_list(&mut lookup, &ids);
+    let existing: Vec<i64> = lookup
+        .build_query_scalar()
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to load datasets: {}", e))?;
+    let missing_ids: Vec<i64> = ids
+        .iter()
+        .copied()
+        .filter(|id| !existing.contains(id))
+        .collect();
+
+    let mut affected = 0;
+    if !existing.is_empty() {
+        let mut change = QueryBuilder::<Sqlite>::new(statement);
+        push_id_list(&mut change, &existing);
+        affected = change
+            .build()
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to update datasets: {}", e))?
+            .rows_affected();
+
+        record_audit(
+            &mut *tx,
+            action,
+            None,
+            &serde_json::json!({ "dataset_ids": existing }),
+        )
+        .await
+        .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(BulkDatasetResult {
+        requested: ids.len(),
+        affected,
+        missing_ids,
+    })
+}
+
+#[tauri::command]
+pub async fn bulk_delete_datasets(
+    state: State<'_, AppState>,
+    ids: Vec<i64>,
+) -> Result<BulkDatasetResult, String> {
+    state.require_role(Role::Admin).await?;
+    apply_bulk_dataset_change(
+        &state.pool,
+        ids,
+        "DELETE FROM datasets WHERE id IN",
+        "datasets_deleted",
+    )
+    .await
+}
+
+/// Hides datasets from the default list without touching their data.
+#[tauri::command]
+pub async fn bulk_archive_datasets(
+    state: State<'_, AppState>,
+    ids: Vec<i64>,
+) -> Result<BulkDatasetResult, String> {
+    state.require_role(Role::Editor).await?;
+    apply_bulk_dataset_change(&state.pool, ids, ARCHIVE_DATASETS, "datasets_archived").await
+}
+
+/// Archives or restores a single dataset; archived datasets only show up in
+/// `list_datasets` with `include_archived`.
+#[tauri::command]
+pub async fn set_dataset_archived(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    archived: bool,
+) -> Result<Dataset, String> {
+    state.require_role(Role::Editor).await?;
+    let (statement, action) = if archived {
+        (ARCHIVE_DATASETS, "datasets_archived")
+    } else {
+        (UNARCHIVE_DATASETS, "datasets_unarchived")
+    };
+    let result =
+        apply_bulk_dataset_change(&state.pool, vec![dataset_id], statement, action).await?;
+    if !result.missing_ids.is_empty() {
+        return Err("Dataset not found".to_string());
+    }
+    fetch_dataset(&state.pool, dataset_id).await
+}
+
+fn normalize_tag(tag: &str) -> Result<String, String> {
+    let tag = tag.split_whitespace().collect::<Vec<_>>().join(" ");
+    if tag.is_empty() {
+        return Err("Tag cannot be empty".to_string());
+    }
+    if tag.chars().count() > MAX_TAG_LEN {
+        return Err(format!("Tag must be at most {} characters", MAX_TAG_LEN));
+    }
+    Ok(tag)
+}
+
+async fn dataset_tags(pool: &SqlitePool, dataset_id: i64) -> Result<Vec<String>, String> {
+    sqlx::query_scalar(
+        "SELECT tag FROM dataset_tags WHERE dataset_id = ? ORDER BY tag COLLATE NOCASE",
+    )
+    .bind(dataset_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to load dataset tags: {}", e))
+}
+
+/// Adds a tag (case-insensitively unique per dataset) and returns the dataset's tags.
+#[tauri::command]
+pub async fn add_dataset_tag(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    tag: String,
+) -> Result<Vec<String>, String> {
+    state.require_role(Role::Editor).await?;
+    let tag = normalize_tag(&tag)?;
+    change_dataset_tag(
+        &state.pool,
+        dataset_id,
+        &tag,
+        "INSERT OR IGNORE INTO dataset_tags (dataset_id, tag) VALUES (?, ?)",
+        "dataset_tag_added",
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn remove_dataset_tag(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    tag: String,
+) -> Result<Vec<String>, String> {
+    state.require_role(Role::Editor).await?;
+    change_dataset_tag(
+        &state.pool,
+        dataset_id,
+        tag.trim(),
+        "DELETE FROM dataset_tags WHERE dataset_id = ? AND tag = ?",
+        "dataset_tag_removed",
+    )
+    .await
+}
+
+/// Runs `statement` (binding the dataset id, then the tag), auditing it when it changed
+/// anything, and returns the dataset's tags.
+async fn change_dataset_tag(
+    pool: &SqlitePool,
+    dataset_id: i64,
+    tag: &str,
+    statement: &str,
+    action: &str,
+) -> Result<Vec<String>, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let changed = sqlx::query(statement)
+        .bind(dataset_id)
+        .bind(tag)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to update dataset tags: {}", e))?
+        .rows_affected();
+    if changed > 0 {
+        record_audit(
+            &mut *tx,
+            action,
+            Some(dataset_id),
+            &serde_json::json!({ "tag": tag }),
+        )
+        .await
+        .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    }
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    dataset_tags(pool, dataset_id).await
+}
+
 #[tauri::command]
 pub async fn update_dataset(
     state: State<'_, AppState>,
     id: i64,
     name: String,
     description: Option<String>,
+    period: Option<DatasetPeriod>,
 ) -> Result<Dataset, String> {
+    state.require_role(Role::Editor).await?;
     let pool = state.pool.clone();
 
     let trimmed_name = name.trim().to_string();
@@ -83,14 +480,27 @@ pub async fn update_dataset(
         .map(|value| value.trim().to_string())
         .filter(|value| !value.is_empty());
 
+    // An omitted period keeps the stored values; an explicit one replaces them (nulls clear)
+    let period = period
+        .map(|value| normalize_period(value.period_year, value.period_label.as_deref()))
+        .transpose()?;
+
     sqlx::query_as::<_, Dataset>(
         "UPDATE datasets
-         SET name = ?, description = ?, updated_at = datetime('now')
+         SET name = ?,
+             description = ?,
+             period_year = CASE WHEN ? THEN ? ELSE period_year END,
+             period_label = CASE WHEN ? THEN ? ELSE period_label END,
+             updated_at = datetime('now')
          WHERE id = ?
          RETURNING *",
     )
     .bind(trimmed_name)
     .bind(normalized_description)
+    .bind(period.is_some())
+    .bind(period.as_ref().and_then(|value| value.period_year))
+    .bind(period.is_some())
+    .bind(period.as_ref().and_then(|value| value.period_label.clone()))
     .bind(id)
     .fetch_one(&pool)
     .await
@@ -103,6 +513,628 @@ pub async fn update_dataset(
     })
 }
 
+/// An employee linked to a dataset and how many scores they have in it.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DatasetMember {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub employee: Employee,
+    pub score_count: i64,
+}
+
+#[tauri::command]
+pub async fn list_dataset_employees(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+) -> Result<Vec<DatasetMember>, String> {
+    fetch_dataset_members(&state.pool, dataset_id).await
+}
+
+pub(crate) async fn fetch_dataset_members(
+    pool: &SqlitePool,
+    dataset_id: i64,
+) -> Result<Vec<DatasetMember>, String> {
+    sqlx::query_as::<_, DatasetMember>(
+        "SELECT e.*,
+                (SELECT COUNT(*) FROM scores s
+                 WHERE s.dataset_id = de.dataset_id AND s.employee_id = e.id) AS score_count
+         FROM dataset_employees de
+         JOIN employees e ON e.id = de.employee_id
+         WHERE de.dataset_id = ?
+         ORDER BY LOWER(e.name), e.id",
+    )
+    .bind(dataset_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to load dataset employees: {}", e))
+}
+
+/// An employee linked to both datasets of a roster diff.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RosterMatch {
+    pub employee: Employee,
+    pub base_score_count: i64,
+    pub comparison_score_count: i64,
+}
+
+/// Who left (`only_in_base`), joined (`only_in_comparison`) and stayed between two datasets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RosterDiff {
+    pub only_in_base: Vec<DatasetMember>,
+    pub only_in_comparison: Vec<DatasetMember>,
+    pub in_both: Vec<RosterMatch>,
+}
+
+#[tauri::command]
+pub async fn diff_dataset_rosters(
+    state: State<'_, AppState>,
+    base_id: i64,
+    comparison_id: i64,
+) -> Result<RosterDiff, String> {
+    compute_roster_diff(&state.pool, base_id, comparison_id).await
+}
+
+async fn compute_roster_diff(
+    pool: &SqlitePool,
+    base_id: i64,
+    comparison_id: i64,
+) -> Result<RosterDiff, String> {
+    for id in [base_id, comparison_id] {
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM datasets WHERE id = ?)")
+                .bind(id)
+                .fetch_one(pool)
+                .await
+                .map_err(|e| format!("Failed to load dataset: {}", e))?;
+        if !exists {
+            return Err(format!("Dataset {} not found", id));
+        }
+    }
+
+    let base = fetch_dataset_members(pool, base_id).await?;
+    let mut comparison = fetch_dataset_members(pool, comparison_id).await?;
+    let mut only_in_base = Vec::new();
+    let mut in_both = Vec::new();
+    for member in base {
+        match comparison
+            .iter()
+            .position(|other| other.employee.id == member.employee.id)
+        {
+            Some(index) => {
+                let other = comparison.remove(index);
+                in_both.push(RosterMatch {
+                    employee: member.employee,
+                    base_score_count: member.score_count,
+                    comparison_score_count: other.score_count,
+                });
+            }
+            None => only_in_base.push(member),
+        }
+    }
+
+    Ok(RosterDiff {
+        only_in_base,
+        only_in_comparison: comparison,
+        in_both,
+    })
+}
+
+/// Removes an employee from a dataset together with their scores and summary in it; the
+/// employee record itself stays.
+#[tauri::command]
+pub async fn unlink_dataset_employee(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    employee_id: i64,
+) -> Result<u64, String> {
+    state.require_role(Role::Editor).await?;
+    let _lock = state
+        .dataset_locks
+        .acquire("unlink-employee", &[dataset_id])?;
+
+    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+    let unlinked =
+        sqlx::query("DELETE FROM dataset_employees WHERE dataset_id = ? AND employee_id = ?")
+            .bind(dataset_id)
+            .bind(employee_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to unlink employee: {}", e))?
+            .rows_affected();
+    if unlinked == 0 {
+        return Err(format!(
+            "Employee {} is not part of dataset {}",
+            employee_id, dataset_id
+        ));
+    }
+
+    let deleted_scores = sqlx::query("DELETE FROM scores WHERE dataset_id = ? AND employee_id = ?")
+        .bind(dataset_id)
+        .bind(employee_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to delete scores: {}", e))?
+        .rows_affected();
+    sqlx::query("DELETE FROM summaries WHERE dataset_id = ? AND employee_id = ?")
+        .bind(dataset_id)
+        .bind(employee_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to delete summary: {}", e))?;
+    sqlx::query("DELETE FROM summary_revisions WHERE dataset_id = ? AND employee_id = ?")
+        .bind(dataset_id)
+        .bind(employee_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to delete summary revisions: {}", e))?;
+    sqlx::query("DELETE FROM leadership_scores WHERE dataset_id = ? AND employee_id = ?")
+        .bind(dataset_id)
+        .bind(employee_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to delete leadership score: {}", e))?;
+
+    record_audit(
+        &mut *tx,
+        "dataset_employee_unlinked",
+        Some(dataset_id),
+        &serde_json::json!({ "employee_id": employee_id, "deleted_scores": deleted_scores }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(deleted_scores)
+}
+
+pub(crate) fn normalize_note_content(content: &str) -> Result<String, String> {
+    let trimmed = content.trim();
+    if trimmed.is_empty() {
+        return Err("Note content cannot be empty".to_string());
+    }
+    Ok(trimmed.to_string())
+}
+
+pub(crate) async fn fetch_dataset_notes(
+    pool: &sqlx::SqlitePool,
+    dataset_id: i64,
+) -> Result<Vec<DatasetNote>, sqlx::Error> {
+    sqlx::query_as::<_, DatasetNote>(
+        "SELECT * FROM dataset_notes WHERE dataset_id = ? ORDER BY created_at DESC, id DESC",
+    )
+    .bind(dataset_id)
+    .fetch_all(pool)
+    .await
+}
+
+#[tauri::command]
+pub async fn list_dataset_notes(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+) -> Result<Vec<DatasetNote>, String> {
+    fetch_dataset_notes(&state.pool, dataset_id)
+        .await
+        .map_err(|e| format!("Failed to load dataset notes: {}", e))
+}
+
+#[tauri::command]
+pub async fn add_dataset_note(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    content: String,
+) -> Result<DatasetNote, String> {
+    state.require_role(Role::Editor).await?;
+    let content = normalize_note_content(&content)?;
+
+    sqlx::query_as::<_, DatasetNote>(
+        r#"
+        INSERT INTO dataset_notes (dataset_id, content, created_at, updated_at)
+        VALUES (?, ?, datetime('now'), datetime('now'))
+        RETURNING *
+        "#,
+    )
+    .bind(dataset_id)
+    .bind(content)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|e| format!("Failed to add dataset note: {}", e))
+}
+
+#[tauri::command]
+pub async fn update_dataset_note(
+    state: State<'_, AppState>,
+    id: i64,
+    content: String,
+) -> Result<DatasetNote, String> {
+    state.require_role(Role::Editor).await?;
+    let content = normalize_note_content(&content)?;
+
+    sqlx::query_as::<_, DatasetNote>(
+        "UPDATE dataset_notes SET content = ?, updated_at = datetime('now') WHERE id = ? RETURNING *",
+    )
+    .bind(content)
+    .bind(id)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|e| {
+        if matches!(e, sqlx::Error::RowNotFound) {
+            "Dataset note not found".to_string()
+        } else {
+            format!("Failed to update dataset note: {}", e)
+        }
+    })
+}
+
+#[tauri::command]
+pub async fn delete_dataset_note(state: State<'_, AppState>, id: i64) -> Result<(), String> {
+    state.require_role(Role::Editor).await?;
+    sqlx::query("DELETE FROM dataset_notes WHERE id = ?")
+        .bind(id)
+        .execute(&state.pool)
+        .await
+        .map_err(|e| format!("Failed to delete dataset note: {}", e))?;
+
+    Ok(())
+}
+
+/// Issues recorded while importing into the dataset, open ones first.
+#[tauri::command]
+pub async fn list_validation_issues(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    include_resolved: Option<bool>,
+) -> Result<Vec<ValidationIssue>, String> {
+    sqlx::query_as::<_, ValidationIssue>(
+        "SELECT id, dataset_id, issue_type, severity, message, metadata,
+                IFNULL(resolved, 0) AS resolved, created_at
+         FROM validation_issues
+         WHERE dataset_id = ? AND (? OR NOT IFNULL(resolved, 0))
+         ORDER BY IFNULL(resolved, 0), CASE severity WHEN 'error' THEN 0 ELSE 1 END, id",
+    )
+    .bind(dataset_id)
+    .bind(include_resolved.unwrap_or(false))
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| format!("Failed to load validation issues: {}", e))
+}
+
+#[tauri::command]
+pub async fn resolve_validation_issue(
+    state: State<'_, AppState>,
+    id: i64,
+) -> Result<ValidationIssue, String> {
+    state.require_role(Role::Editor).await?;
+    let issue = sqlx::query_as::<_, ValidationIssue>(
+        "UPDATE validation_issues SET resolved = 1 WHERE id = ? RETURNING *",
+    )
+    .bind(id)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|e| {
+        if matches!(e, sqlx::Error::RowNotFound) {
+            "Validation issue not found".to_string()
+        } else {
+            format!("Failed to resolve validation issue: {}", e)
+        }
+    })?;
+
+    record_audit(
+        &state.pool,
+        "validation_issue_resolved",
+        Some(issue.dataset_id),
+        &serde_json::json!({ "issue_id": issue.id, "issue_type": issue.issue_type }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+
+    Ok(issue)
+}
+
+/// Sets the dataset's competency order to `competency_ids`; competencies left out keep
+/// their relative order after the listed ones.
+#[tauri::command]
+pub async fn reorder_dataset_competencies(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    competency_ids: Vec<i64>,
+) -> Result<(), String> {
+    state.require_role(Role::Editor).await?;
+    let mut tx = state
+        .pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    let current: Vec<i64> = sqlx::query_scalar(
+        "SELECT competency_id FROM dataset_competencies WHERE dataset_id = ? ORDER BY display_order",
+    )
+    .bind(dataset_id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to load competency order: {}", e))?;
+
+    if let Some(unknown) = competency_ids.iter().find(|id| !current.contains(id)) {
+        return Err(format!(
+            "Competency {} is not part of dataset {}",
+            unknown, dataset_id
+        ));
+    }
+
+    let remaining = current.iter().filter(|id| !competency_ids.contains(id));
+    for (position, competency_id) in competency_ids.iter().chain(remaining).enumerate() {
+        sqlx::query(
+            "UPDATE dataset_competencies SET display_order = ? WHERE dataset_id = ? AND competency_id = ?",
+        )
+        .bind(position as i64)
+        .bind(dataset_id)
+        .bind(competency_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to update competency order: {}", e))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LateSubmission {
+    pub employee_id: i64,
+    pub employee_name: String,
+    pub nip: Option<String>,
+    pub first_submitted_at: NaiveDateTime,
+    pub last_submitted_at: NaiveDateTime,
+    pub late_score_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LateSubmissionReport {
+    pub dataset_id: i64,
+    pub submission_deadline: NaiveDateTime,
+    pub late_submissions: Vec<LateSubmission>,
+    pub on_time_score_count: i64,
+    /// Scores imported from sources without response timestamps cannot be judged.
+    pub untimed_score_count: i64,
+}
+
+#[tauri::command]
+pub async fn set_dataset_deadline(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    submission_deadline: Option<String>,
+) -> Result<Dataset, String> {
+    state.require_role(Role::Editor).await?;
+    let deadline = submission_deadline
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| {
+            CsvParser::parse_timestamp(value)
+                .ok_or_else(|| format!("Invalid submission deadline: {}", value))
+        })
+        .transpose()?;
+
+    sqlx::query_as::<_, Dataset>(
+        "UPDATE datasets SET submission_deadline = ?, updated_at = datetime('now') WHERE id = ? RETURNING *",
+    )
+    .bind(deadline)
+    .bind(dataset_id)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|e| {
+        if matches!(e, sqlx::Error::RowNotFound) {
+            "Dataset not found".to_string()
+        } else {
+            format!("Failed to update submission deadline: {}", e)
+        }
+    })
+}
+
+/// Completeness of the dataset's scores and what, if anything, keeps its official documents
+/// (recaps, leaderboards, archives) from being exported.
+#[tauri::command]
+pub async fn get_dataset_quality(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+) -> Result<DatasetQuality, String> {
+    assess_dataset(&state.pool, dataset_id).await
+}
+
+/// Fixes the raw score that maps to 100 when normalising this dataset, e.g. 4 for a 1-4
+/// rating form; `None` goes back to detecting it from the highest score.
+#[tauri::command]
+pub async fn set_dataset_score_scale(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    score_scale: Option<f64>,
+) -> Result<Dataset, String> {
+    state.require_role(Role::Editor).await?;
+    update_score_scale(&state.pool, dataset_id, score_scale).await
+}
+
+async fn update_score_scale(
+    pool: &SqlitePool,
+    dataset_id: i64,
+    score_scale: Option<f64>,
+) -> Result<Dataset, String> {
+    if let Some(scale) = score_scale {
+        if !scale.is_finite() || scale <= 0.0 {
+            return Err("Score scale must be a positive number".to_string());
+        }
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let previous: Option<f64> =
+        sqlx::query_scalar::<_, Option<f64>>("SELECT score_scale FROM datasets WHERE id = ?")
+            .bind(dataset_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to load dataset: {}", e))?
+            .ok_or_else(|| "Dataset not found".to_string())?;
+    let dataset = sqlx::query_as::<_, Dataset>(
+        "UPDATE datasets SET score_scale = ?, updated_at = datetime('now') WHERE id = ? RETURNING *",
+    )
+    .bind(score_scale)
+    .bind(dataset_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to update score scale: {}", e))?;
+    record_audit(
+        &mut *tx,
+        "dataset_score_scale_set",
+        Some(dataset_id),
+        &serde_json::json!({ "old_score_scale": previous, "new_score_scale": score_scale }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    Ok(dataset)
+}
+
+/// Changes how the entries of several raters combine into a score (`mean`, `median` or
+/// `mode`) and rewrites the dataset's aggregated scores accordingly.
+#[tauri::command]
+pub async fn set_dataset_score_aggregation(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    aggregation: String,
+) -> Result<Dataset, String> {
+    state.require_role(Role::Editor).await?;
+    let aggregation = ScoreAggregation::from_str(&aggregation)?;
+    let _lock = state
+        .dataset_locks
+        .acquire("score-aggregation", &[dataset_id])?;
+
+    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+    let dataset = sqlx::query_as::<_, Dataset>(
+        "UPDATE datasets SET score_aggregation = ?, updated_at = datetime('now') WHERE id = ? RETURNING *",
+    )
+    .bind(aggregation.as_str())
+    .bind(dataset_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| {
+        if matches!(e, sqlx::Error::RowNotFound) {
+            "Dataset not found".to_string()
+        } else {
+            format!("Failed to update score aggregation: {}", e)
+        }
+    })?;
+    refresh_aggregated_scores(&mut tx, dataset_id, None)
+        .await
+        .map_err(|e| format!("Failed to aggregate scores: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(dataset)
+}
+
+/// Chooses how each rater's values are evened out (`none`, `z_score` or `min_max`) before
+/// analytics and the weighted score use them; the stored scores keep their values.
+#[tauri::command]
+pub async fn set_dataset_score_normalization(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    normalization: String,
+) -> Result<Dataset, String> {
+    state.require_role(Role::Editor).await?;
+    let normalization = ScoreNormalization::from_str(&normalization)?;
+
+    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+    let previous: Option<String> =
+        sqlx::query_scalar("SELECT score_normalization FROM datasets WHERE id = ?")
+            .bind(dataset_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to load dataset: {}", e))?
+            .ok_or_else(|| "Dataset not found".to_string())?;
+    let dataset = sqlx::query_as::<_, Dataset>(
+        "UPDATE datasets SET score_normalization = ?, updated_at = datetime('now') WHERE id = ? RETURNING *",
+    )
+    .bind(normalization.as_str())
+    .bind(dataset_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to update score normalization: {}", e))?;
+    record_audit(
+        &mut *tx,
+        "dataset_score_normalization_updated",
+        Some(dataset_id),
+        &serde_json::json!({
+            "old_normalization": previous,
+            "new_normalization": normalization.as_str(),
+        }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(dataset)
+}
+
+/// Employees whose scores were submitted after the dataset's deadline, for compliance reporting.
+#[tauri::command]
+pub async fn get_late_submissions(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+) -> Result<LateSubmissionReport, String> {
+    let pool = state.pool.clone();
+
+    let deadline: Option<NaiveDateTime> =
+        sqlx::query_scalar("SELECT submission_deadline FROM datasets WHERE id = ?")
+            .bind(dataset_id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| format!("Failed to load dataset: {}", e))?
+            .ok_or_else(|| "Dataset not found".to_string())?;
+    let deadline = deadline.ok_or_else(|| "Dataset has no submission deadline".to_string())?;
+
+    let late_submissions = sqlx::query_as::<_, LateSubmission>(
+        "SELECT e.id AS employee_id, e.name AS employee_name, e.nip,
+                MIN(s.submitted_at) AS first_submitted_at,
+                MAX(s.submitted_at) AS last_submitted_at,
+                COUNT(*) AS late_score_count
+         FROM scores s
+         JOIN employees e ON e.id = s.employee_id
+         WHERE s.dataset_id = ? AND s.submitted_at > ?
+         GROUP BY e.id, e.name, e.nip
+         ORDER BY last_submitted_at DESC, e.name",
+    )
+    .bind(dataset_id)
+    .bind(deadline)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to load late submissions: {}", e))?;
+
+    let (on_time_score_count, untimed_score_count): (i64, i64) = sqlx::query_as(
+        "SELECT
+            COALESCE(SUM(CASE WHEN submitted_at <= ? THEN 1 ELSE 0 END), 0),
+            COALESCE(SUM(CASE WHEN submitted_at IS NULL THEN 1 ELSE 0 END), 0)
+         FROM scores WHERE dataset_id = ?",
+    )
+    .bind(deadline)
+    .bind(dataset_id)
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| format!("Failed to count submissions: {}", e))?;
+
+    Ok(LateSubmissionReport {
+        dataset_id,
+        submission_deadline: deadline,
+        late_submissions,
+        on_time_score_count,
+        untimed_score_count,
+    })
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MergeDatasetsRequest {
     pub source_dataset_ids: Vec<i64>,
@@ -116,6 +1148,7 @@ pub struct MergeDatasetsResult {
     pub employee_count: i64,
     pub score_count: i64,
     pub rating_mapping_count: i64,
+    pub resolved_duplicates: i64,
     pub source_dataset_ids: Vec<i64>,
 }
 
@@ -124,6 +1157,7 @@ pub async fn merge_datasets(
     state: State<'_, AppState>,
     request: MergeDatasetsRequest,
 ) -> Result<MergeDatasetsResult, String> {
+    state.require_role(Role::Admin).await?;
     let pool = state.pool.clone();
 
     let mut unique_ids: Vec<i64> = Vec::new();
@@ -162,8 +1196,64 @@ pub async fn merge_datasets(
         }
     }
 
+    let _lock = state.dataset_locks.acquire("merge", &unique_ids)?;
     let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
 
+    // Sources may reference different records for the same person (e.g. restored databases),
+    // so resolve every participating employee to one canonical id before copying.
+    let mut employee_query = sqlx::QueryBuilder::<sqlx::Sqlite>::new(
+        "SELECT * FROM employees WHERE id IN (
+            SELECT employee_id FROM dataset_employees WHERE dataset_id IN (",
+    );
+    {
+        let mut sep = employee_query.separated(", ");
+        for id in &unique_ids {
+            sep.push_bind(id);
+        }
+    }
+    employee_query.push(") UNION SELECT employee_id FROM scores WHERE dataset_id IN (");
+    {
+        let mut sep = employee_query.separated(", ");
+        for id in &unique_ids {
+            sep.push_bind(id);
+        }
+    }
+    employee_query.push(")) ORDER BY id");
+    let employees: Vec<Employee> = employee_query
+        .build_query_as()
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    sqlx::query("DROP TABLE IF EXISTS temp.merge_identity")
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    sqlx::query(
+        "CREATE TEMP TABLE merge_identity (
+            employee_id INTEGER PRIMARY KEY,
+            canonical_id INTEGER NOT NULL
+        )",
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut resolver = EmployeeIdentityResolver::default();
+    let mut resolved_duplicates = 0i64;
+    for employee in &employees {
+        let canonical_id = resolver.resolve(employee.id, employee.nip.as_deref(), &employee.name);
+        if canonical_id != employee.id {
+            resolved_duplicates += 1;
+        }
+        sqlx::query("INSERT INTO merge_identity (employee_id, canonical_id) VALUES (?, ?)")
+            .bind(employee.id)
+            .bind(canonical_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
     let dataset = sqlx::query_as::<_, Dataset>(
         "INSERT INTO datasets (name, description, source_file, created_at, updated_at)
          VALUES (?, ?, ?, datetime('now'), datetime('now'))
@@ -179,9 +1269,10 @@ pub async fn merge_datasets(
     for source_id in &unique_ids {
         sqlx::query(
             "INSERT OR IGNORE INTO dataset_employees (dataset_id, employee_id, created_at, updated_at)
-             SELECT ?, employee_id, created_at, datetime('now')
-             FROM dataset_employees
-             WHERE dataset_id = ?",
+             SELECT ?, mi.canonical_id, de.created_at, datetime('now')
+             FROM dataset_employees de
+             JOIN temp.merge_identity mi ON mi.employee_id = de.employee_id
+             WHERE de.dataset_id = ?",
         )
         .bind(dataset.id)
         .bind(source_id)
@@ -190,10 +1281,24 @@ pub async fn merge_datasets(
         .map_err(|e| e.to_string())?;
 
         sqlx::query(
-            "INSERT OR IGNORE INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, created_at)
-             SELECT employee_id, ?, competency_id, raw_value, numeric_value, created_at
-             FROM scores
-             WHERE dataset_id = ?",
+            "INSERT OR IGNORE INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, respondent_count, created_at)
+             SELECT mi.canonical_id, ?, s.competency_id, s.raw_value, s.numeric_value, s.respondent_count, s.created_at
+             FROM scores s
+             JOIN temp.merge_identity mi ON mi.employee_id = s.employee_id
+             WHERE s.dataset_id = ?",
+        )
+        .bind(dataset.id)
+        .bind(source_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        sqlx::query(
+            "INSERT OR IGNORE INTO score_entries (dataset_id, employee_id, competency_id, rater, raw_value, numeric_value, created_at)
+             SELECT ?, mi.canonical_id, e.competency_id, e.rater, e.raw_value, e.numeric_value, e.created_at
+             FROM score_entries e
+             JOIN temp.merge_identity mi ON mi.employee_id = e.employee_id
+             WHERE e.dataset_id = ?",
         )
         .bind(dataset.id)
         .bind(source_id)
@@ -214,6 +1319,11 @@ pub async fn merge_datasets(
         .map_err(|e| e.to_string())?;
     }
 
+    // A cell several sources scored is combined from their entries
+    refresh_aggregated_scores(&mut tx, dataset.id, None)
+        .await
+        .map_err(|e| e.to_string())?;
+
     let employee_count: i64 =
         sqlx::query_scalar("SELECT COUNT(*) FROM dataset_employees WHERE dataset_id = ?")
             .bind(dataset.id)
@@ -234,13 +1344,275 @@ pub async fn merge_datasets(
             .await
             .map_err(|e| e.to_string())?;
 
+    sqlx::query("DROP TABLE temp.merge_identity")
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
     tx.commit().await.map_err(|e| e.to_string())?;
 
+    webhooks::notify(
+        &pool,
+        WebhookPayload::new(
+            MERGE_COMPLETED,
+            dataset.id,
+            serde_json::json!({
+                "employee_count": employee_count,
+                "score_count": score_count,
+                "rating_mapping_count": rating_mapping_count,
+                "resolved_duplicates": resolved_duplicates,
+                "source_dataset_ids": unique_ids,
+            }),
+        ),
+    );
+
     Ok(MergeDatasetsResult {
         dataset,
         employee_count,
         score_count,
         rating_mapping_count,
+        resolved_duplicates,
         source_dataset_ids: unique_ids,
     })
 }
+
+/// Starts a new dataset from an existing one: its employees, rating mappings, rating
+/// bands and competency order, plus the scores when `include_scores` is set. The period is left
+/// empty for the next evaluation round.
+#[tauri::command]
+pub async fn clone_dataset(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    new_name: String,
+    include_scores: bool,
+) -> Result<Dataset, String> {
+    state.require_role(Role::Editor).await?;
+    let trimmed_name = new_name.trim();
+    if trimmed_name.is_empty() {
+        return Err("Dataset name cannot be empty".to_string());
+    }
+    let _lock = state.dataset_locks.acquire("clone", &[dataset_id])?;
+
+    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+    let dataset = sqlx::query_as::<_, Dataset>(
+        "INSERT INTO datasets (name, description, score_scale, score_aggregation, score_normalization, created_at, updated_at)
+         SELECT ?, description, score_scale, score_aggregation, score_normalization, datetime('now'), datetime('now')
+         FROM datasets WHERE id = ?
+         RETURNING *",
+    )
+    .bind(trimmed_name)
+    .bind(dataset_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to create dataset: {}", e))?
+    .ok_or_else(|| "Dataset not found".to_string())?;
+
+    let mut copies = vec![
+        "INSERT INTO dataset_employees (dataset_id, employee_id, created_at, updated_at)
+         SELECT ?, employee_id, datetime('now'), datetime('now')
+         FROM dataset_employees WHERE dataset_id = ?",
+        "INSERT INTO rating_mappings (dataset_id, text_value, numeric_value)
+         SELECT ?, text_value, numeric_value FROM rating_mappings WHERE dataset_id = ?",
+        "INSERT INTO dataset_competencies (dataset_id, competency_id, display_order)
+         SELECT ?, competency_id, display_order FROM dataset_competencies WHERE dataset_id = ?",
+        "INSERT INTO rating_bands (dataset_id, label, min_score, max_score, color)
+         SELECT ?, label, min_score, max_score, color FROM rating_bands WHERE dataset_id = ?",
+    ];
+    if include_scores {
+        copies.push(
+            "INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, submitted_at, respondent_count, created_at)
+             SELECT employee_id, ?, competency_id, raw_value, numeric_value, submitted_at, respondent_count, datetime('now')
+             FROM scores WHERE dataset_id = ?",
+        );
+        copies.push(
+            "INSERT INTO score_entries (dataset_id, employee_id, competency_id, rater, raw_value, numeric_value, created_at)
+             SELECT ?, employee_id, competency_id, rater, raw_value, numeric_value, created_at
+             FROM score_entries WHERE dataset_id = ?",
+        );
+        copies.push(
+            "INSERT INTO leadership_scores (dataset_id, employee_id, score, created_at, updated_at)
+             SELECT ?, employee_id, score, datetime('now'), datetime('now')
+             FROM leadership_scores WHERE dataset_id = ?",
+        );
+    }
+    for copy in copies {
+        sqlx::query(copy)
+            .bind(dataset.id)
+            .bind(dataset_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to copy dataset: {}", e))?;
+    }
+
+    record_audit(
+        &mut *tx,
+        "dataset_cloned",
+        Some(dataset.id),
+        &serde_json::json!({ "source_dataset_id": dataset_id, "include_scores": include_scores }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(dataset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{seed_datasets, test_pool};
+
+    async fn dataset_pool() -> SqlitePool {
+        let pool = test_pool().await;
+        seed_datasets(&pool, &["Semester I", "Semester II"]).await;
+        pool
+    }
+
+    async fn audit_actions(pool: &SqlitePool) -> Vec<String> {
+        sqlx::query_scalar("SELECT action FROM audit_log ORDER BY id")
+            .fetch_all(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_archiving_is_audited_and_skips_archived_datasets() {
+        let pool = dataset_pool().await;
+
+        let archived = apply_bulk_dataset_change(
+            &pool,
+            vec![1, 2, 2, 9],
+            ARCHIVE_DATASETS,
+            "datasets_archived",
+        )
+        .await
+        .unwrap();
+        let again =
+            apply_bulk_dataset_change(&pool, vec![1], ARCHIVE_DATASETS, "datasets_archived")
+                .await
+                .unwrap();
+        let restored =
+            apply_bulk_dataset_change(&pool, vec![2], UNARCHIVE_DATASETS, "datasets_unarchived")
+                .await
+                .unwrap();
+
+        assert_eq!((archived.requested, archived.affected), (3, 2));
+        assert_eq!(archived.missing_ids, vec![9]);
+        assert_eq!(again.affected, 0);
+        assert_eq!(restored.affected, 1);
+        assert!(fetch_dataset(&pool, 1).await.unwrap().archived_at.is_some());
+        assert!(fetch_dataset(&pool, 2).await.unwrap().archived_at.is_none());
+        assert_eq!(
+            audit_actions(&pool).await,
+            vec![
+                "datasets_archived",
+                "datasets_archived",
+                "datasets_unarchived"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tag_changes_are_audited_and_attached_per_dataset() {
+        let pool = dataset_pool().await;
+        let add = "INSERT OR IGNORE INTO dataset_tags (dataset_id, tag) VALUES (?, ?)";
+
+        change_dataset_tag(&pool, 1, "pusat", add, "dataset_tag_added")
+            .await
+            .unwrap();
+        change_dataset_tag(&pool, 1, "pusat", add, "dataset_tag_added")
+            .await
+            .unwrap();
+        change_dataset_tag(&pool, 1, "Audit", add, "dataset_tag_added")
+            .await
+            .unwrap();
+        let tags = change_dataset_tag(
+            &pool,
+            2,
+            "daerah",
+            "DELETE FROM dataset_tags WHERE dataset_id = ? AND tag = ?",
+            "dataset_tag_removed",
+        )
+        .await
+        .unwrap();
+        let mut datasets = vec![
+            fetch_dataset(&pool, 1).await.unwrap(),
+            fetch_dataset(&pool, 2).await.unwrap(),
+        ];
+        attach_tags(&pool, &mut datasets).await.unwrap();
+
+        assert!(tags.is_empty());
+        assert_eq!(datasets[0].tags, vec!["Audit", "pusat"]);
+        assert!(datasets[1].tags.is_empty());
+        assert_eq!(
+            audit_actions(&pool).await,
+            vec!["dataset_tag_added", "dataset_tag_added"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_score_scale_changes_are_validated_and_audited() {
+        let pool = dataset_pool().await;
+
+        let dataset = update_score_scale(&pool, 1, Some(4.0)).await.unwrap();
+        update_score_scale(&pool, 1, None).await.unwrap();
+
+        assert_eq!(dataset.score_scale, Some(4.0));
+        assert!(update_score_scale(&pool, 1, Some(0.0)).await.is_err());
+        assert!(update_score_scale(&pool, 9, Some(4.0)).await.is_err());
+        let details: Vec<String> = sqlx::query_scalar(
+            "SELECT details FROM audit_log WHERE action = 'dataset_score_scale_set' ORDER BY id",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            details,
+            vec![
+                r#"{"new_score_scale":4.0,"old_score_scale":null}"#,
+                r#"{"new_score_scale":null,"old_score_scale":4.0}"#,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_roster_diff_splits_leavers_joiners_and_stayers() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO datasets (id, name, created_at, updated_at) VALUES
+                (1, 'Semester I', datetime('now'), datetime('now')),
+                (2, 'Semester II', datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, created_at, updated_at) VALUES
+                (1, 'Budi', datetime('now'), datetime('now')),
+                (2, 'Sari', datetime('now'), datetime('now')),
+                (3, 'Tono', datetime('now'), datetime('now'));
+             INSERT INTO dataset_employees (dataset_id, employee_id, created_at, updated_at) VALUES
+                (1, 1, datetime('now'), datetime('now')),
+                (1, 2, datetime('now'), datetime('now')),
+                (2, 2, datetime('now'), datetime('now')),
+                (2, 3, datetime('now'), datetime('now'));
+             INSERT INTO competencies (id, name, display_order) VALUES (1, 'Kerjasama', 0);
+             INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, created_at)
+             VALUES (2, 2, 1, 'Baik', 75.0, datetime('now'))",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let diff = compute_roster_diff(&pool, 1, 2).await.unwrap();
+        assert_eq!(diff.only_in_base[0].employee.name, "Budi");
+        assert_eq!(diff.only_in_comparison[0].employee.name, "Tono");
+        assert_eq!(diff.in_both.len(), 1);
+        assert_eq!(diff.in_both[0].employee.name, "Sari");
+        assert_eq!(
+            (
+                diff.in_both[0].base_score_count,
+                diff.in_both[0].comparison_score_count
+            ),
+            (0, 1)
+        );
+        assert!(compute_roster_diff(&pool, 1, 9).await.is_err());
+    }
+}
@@ -1,9 +1,102 @@
-use crate::db::models::Employee;
+use crate::attachments::remove_employee_files;
+use crate::auth::Role;
+use crate::commands::analytics::sanitize_text;
+use crate::commands::dataset::normalize_note_content;
+use crate::data_quality::{assess_employees, DataQualityIssue, EmployeeDataQuality};
+use crate::db::audit::record_audit;
+use crate::db::models::{Employee, EmployeeNote};
+use crate::photos::{photo_file, remove_employee_photos, set_photo};
+use crate::scoring::PositionType;
 use crate::AppState;
 use serde::{Deserialize, Serialize};
-use sqlx::{QueryBuilder, Sqlite, SqlitePool, Transaction};
+use sqlx::{QueryBuilder, Sqlite, SqliteConnection, SqlitePool, Transaction};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 use tauri::State;
 
+pub(crate) fn normalize_nip(nip: Option<&str>) -> Option<String> {
+    let digits: String = nip?.chars().filter(|ch| ch.is_ascii_digit()).collect();
+    if digits.is_empty() {
+        None
+    } else {
+        Some(digits)
+    }
+}
+
+/// Maps employee records that describe the same person onto one canonical id.
+/// NIP is authoritative; the normalized name is only used when NIPs do not conflict.
+#[derive(Default)]
+pub(crate) struct EmployeeIdentityResolver {
+    by_nip: HashMap<String, i64>,
+    by_name: HashMap<String, (i64, Option<String>)>,
+}
+
+impl EmployeeIdentityResolver {
+    pub(crate) fn resolve(&mut self, id: i64, nip: Option<&str>, name: &str) -> i64 {
+        let nip = normalize_nip(nip);
+        let name_key = sanitize_text(name);
+
+        let by_nip = nip
+            .as_ref()
+            .and_then(|value| self.by_nip.get(value).copied());
+        let by_name = self
+            .by_name
+            .get(&name_key)
+            .filter(|(_, known_nip)| known_nip.is_none() || nip.is_none() || *known_nip == nip)
+            .map(|(canonical, _)| *canonical);
+        let canonical = by_nip.or(by_name).unwrap_or(id);
+
+        if let Some(value) = nip.clone() {
+            self.by_nip.entry(value).or_insert(canonical);
+        }
+        if !name_key.is_empty() {
+            let entry = self.by_name.entry(name_key).or_insert((canonical, None));
+            if entry.0 == canonical && entry.1.is_none() {
+                entry.1 = nip;
+            }
+        }
+
+        canonical
+    }
+}
+
+/// Employee ids keyed by NIP. NIPs are stored normalized; records flagged as sharing another
+/// record's NIP are left out until the conflict is resolved.
+pub(crate) async fn load_nip_index(
+    conn: &mut SqliteConnection,
+) -> Result<HashMap<String, i64>, sqlx::Error> {
+    let employees: Vec<(i64, String)> =
+        sqlx::query_as("SELECT id, nip FROM employees WHERE nip IS NOT NULL AND nip_conflict = 0")
+            .fetch_all(conn)
+            .await?;
+    Ok(employees.into_iter().map(|(id, nip)| (nip, id)).collect())
+}
+
+/// Master record of an incoming employee. A known NIP decides, so someone whose name gained a
+/// title is still found; otherwise the lowercased name is used unless that record carries a
+/// different NIP.
+pub(crate) async fn find_master_employee(
+    conn: &mut SqliteConnection,
+    nip_index: &HashMap<String, i64>,
+    nip: Option<&str>,
+    normalized_name: &str,
+) -> Result<Option<Employee>, sqlx::Error> {
+    let nip = normalize_nip(nip);
+    if let Some(id) = nip.as_ref().and_then(|nip| nip_index.get(nip)) {
+        return sqlx::query_as::<_, Employee>("SELECT * FROM employees WHERE id = ?")
+            .bind(id)
+            .fetch_optional(conn)
+            .await;
+    }
+
+    let by_name =
+        sqlx::query_as::<_, Employee>("SELECT * FROM employees WHERE lower(name) = ? LIMIT 1")
+            .bind(normalized_name)
+            .fetch_optional(conn)
+            .await?;
+    Ok(by_name.filter(|employee| nip.is_none() || employee.nip.is_none() || employee.nip == nip))
+}
+
 #[tauri::command]
 pub async fn list_all_employees(state: State<'_, AppState>) -> Result<Vec<Employee>, String> {
     let pool = state.pool.clone();
@@ -14,6 +107,75 @@ pub async fn list_all_employees(state: State<'_, AppState>) -> Result<Vec<Employ
         .map_err(|e| format!("Failed to list employees: {}", e))
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmployeeDataQualityEntry {
+    pub employee: Employee,
+    pub data_quality: EmployeeDataQuality,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataQualityIssueCount {
+    pub issue: DataQualityIssue,
+    pub count: usize,
+}
+
+/// Workspace-wide data quality; `employees` only lists records with at least one issue,
+/// lowest score first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataQualityReport {
+    pub total_employees: usize,
+    pub average_score: f64,
+    pub issue_counts: Vec<DataQualityIssueCount>,
+    pub employees: Vec<EmployeeDataQualityEntry>,
+}
+
+#[tauri::command]
+pub async fn get_data_quality_report(
+    state: State<'_, AppState>,
+) -> Result<DataQualityReport, String> {
+    let assessed = assess_employees(&state.pool, None).await?;
+
+    let total_employees = assessed.len();
+    let average_score = if assessed.is_empty() {
+        0.0
+    } else {
+        assessed
+            .iter()
+            .map(|(_, quality)| quality.score)
+            .sum::<f64>()
+            / total_employees as f64
+    };
+
+    let mut issue_counts: Vec<DataQualityIssueCount> = Vec::new();
+    for issue in assessed
+        .iter()
+        .flat_map(|(_, quality)| quality.issues.iter().copied())
+    {
+        match issue_counts.iter_mut().find(|entry| entry.issue == issue) {
+            Some(entry) => entry.count += 1,
+            None => issue_counts.push(DataQualityIssueCount { issue, count: 1 }),
+        }
+    }
+    issue_counts.sort_by_key(|entry| std::cmp::Reverse(entry.count));
+
+    let mut employees: Vec<EmployeeDataQualityEntry> = assessed
+        .into_iter()
+        .filter(|(_, quality)| !quality.issues.is_empty())
+        .map(|(employee, data_quality)| EmployeeDataQualityEntry {
+            employee,
+            data_quality,
+        })
+        .collect();
+    employees.sort_by(|a, b| a.data_quality.score.total_cmp(&b.data_quality.score));
+
+    Ok(DataQualityReport {
+        total_employees,
+        average_score,
+        issue_counts,
+        employees,
+    })
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateEmployee {
     pub id: i64,
@@ -24,60 +186,69 @@ pub struct UpdateEmployee {
     pub sub_jabatan: Option<Option<String>>, // same semantics
 }
 
-async fn delete_employees_tx(tx: &mut Transaction<'_, Sqlite>, ids: &[i64]) -> Result<u64, String> {
-    if ids.is_empty() {
-        return Ok(0);
-    }
+/// Rows that deleting a set of employees removes; see `preview_bulk_delete_employees`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmployeeDeletionCounts {
+    pub employees: i64,
+    /// Rows going with them, per table holding an employee id; tables without any are left out
+    pub dependents: BTreeMap<String, i64>,
+}
 
-    // Delete dependent rows first to maintain referential integrity
-    let mut qb = QueryBuilder::<Sqlite>::new("DELETE FROM scores WHERE employee_id IN (");
+fn push_id_list(qb: &mut QueryBuilder<'_, Sqlite>, ids: &[i64]) {
+    qb.push(" IN (");
     {
         let mut sep = qb.separated(", ");
         for id in ids {
-            sep.push_bind(id);
+            sep.push_bind(*id);
         }
     }
     qb.push(")");
-    qb.build()
-        .execute(&mut **tx)
-        .await
-        .map_err(|e| format!("Failed to delete scores: {}", e))?;
+}
 
+async fn count_employee_rows(
+    conn: &mut SqliteConnection,
+    table: &str,
+    column: &str,
+    ids: &[i64],
+) -> Result<i64, String> {
     let mut qb =
-        QueryBuilder::<Sqlite>::new("DELETE FROM dataset_employees WHERE employee_id IN (");
-    {
-        let mut sep = qb.separated(", ");
-        for id in ids {
-            sep.push_bind(id);
-        }
-    }
-    qb.push(")");
-    qb.build()
-        .execute(&mut **tx)
+        QueryBuilder::<Sqlite>::new(format!("SELECT COUNT(*) FROM {} WHERE {}", table, column));
+    push_id_list(&mut qb, ids);
+    qb.build_query_scalar::<i64>()
+        .fetch_one(&mut *conn)
         .await
-        .map_err(|e| format!("Failed to delete dataset mappings: {}", e))?;
+        .map_err(|e| format!("Failed to count {}: {}", table, e))
+}
 
-    let mut qb = QueryBuilder::<Sqlite>::new("DELETE FROM summaries WHERE employee_id IN (");
-    {
-        let mut sep = qb.separated(", ");
-        for id in ids {
-            sep.push_bind(id);
+async fn count_employee_dependents(
+    conn: &mut SqliteConnection,
+    ids: &[i64],
+) -> Result<EmployeeDeletionCounts, String> {
+    if ids.is_empty() {
+        return Ok(EmployeeDeletionCounts::default());
+    }
+    let mut dependents = BTreeMap::new();
+    for (table, column) in employee_references(conn).await? {
+        let count = count_employee_rows(conn, &table, &column, ids).await?;
+        if count > 0 {
+            *dependents.entry(table).or_insert(0) += count;
         }
     }
-    qb.push(")");
-    qb.build()
-        .execute(&mut **tx)
-        .await
-        .map_err(|e| format!("Failed to delete summaries: {}", e))?;
+    Ok(EmployeeDeletionCounts {
+        employees: count_employee_rows(conn, "employees", "id", ids).await?,
+        dependents,
+    })
+}
 
-    let mut qb = QueryBuilder::<Sqlite>::new("DELETE FROM employees WHERE id IN (");
-    {
-        let mut sep = qb.separated(", ");
-        for id in ids {
-            sep.push_bind(id);
-        }
+/// Dependent rows (dataset links, scores, summaries, attachments, ...) go with the employees
+/// through their `ON DELETE CASCADE` foreign keys.
+async fn delete_employees_tx(tx: &mut Transaction<'_, Sqlite>, ids: &[i64]) -> Result<u64, String> {
+    if ids.is_empty() {
+        return Ok(0);
     }
-    qb.push(")");
+
+    let mut qb = QueryBuilder::<Sqlite>::new("DELETE FROM employees WHERE id");
+    push_id_list(&mut qb, ids);
     let result = qb
         .build()
         .execute(&mut **tx)
@@ -87,11 +258,27 @@ async fn delete_employees_tx(tx: &mut Transaction<'_, Sqlite>, ids: &[i64]) -> R
     Ok(result.rows_affected())
 }
 
+/// Dry run of `bulk_delete_employees`: what deleting `ids` would remove.
+#[tauri::command]
+pub async fn preview_bulk_delete_employees(
+    state: State<'_, AppState>,
+    ids: Vec<i64>,
+) -> Result<EmployeeDeletionCounts, String> {
+    state.require_role(Role::Admin).await?;
+    let mut conn = state
+        .pool
+        .acquire()
+        .await
+        .map_err(|e| format!("Failed to open connection: {}", e))?;
+    count_employee_dependents(&mut conn, &ids).await
+}
+
 #[tauri::command]
 pub async fn bulk_delete_employees(
     state: State<'_, AppState>,
     ids: Vec<i64>,
 ) -> Result<u64, String> {
+    state.require_role(Role::Admin).await?;
     let pool: SqlitePool = state.pool.clone();
     let mut tx = pool
         .begin()
@@ -101,6 +288,8 @@ pub async fn bulk_delete_employees(
     tx.commit()
         .await
         .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    remove_employee_files(&pool, &ids).await?;
+    remove_employee_photos(&pool, &ids).await?;
     Ok(affected)
 }
 
@@ -109,6 +298,7 @@ pub async fn bulk_update_employees(
     state: State<'_, AppState>,
     updates: Vec<UpdateEmployee>,
 ) -> Result<u64, String> {
+    state.require_role(Role::Editor).await?;
     if updates.is_empty() {
         return Ok(0);
     }
@@ -137,7 +327,10 @@ pub async fn bulk_update_employees(
                 qb.push(", ");
             }
             first = false;
-            qb.push("nip = ").push_bind(nip);
+            // A corrected NIP has to be unique again
+            qb.push("nip = ")
+                .push_bind(normalize_nip(nip.as_deref()))
+                .push(", nip_conflict = 0");
         }
         if let Some(gol) = u.gol {
             if !first {
@@ -172,7 +365,15 @@ pub async fn bulk_update_employees(
             .build()
             .execute(&mut *tx)
             .await
-            .map_err(|e| format!("Failed to update employee {}: {}", u.id, e))?;
+            .map_err(|e| match e.as_database_error() {
+                Some(db_error) if db_error.is_unique_violation() => {
+                    format!(
+                        "NIP of employee {} is already used by another employee",
+                        u.id
+                    )
+                }
+                _ => format!("Failed to update employee {}: {}", u.id, e),
+            })?;
         total_updated += res.rows_affected();
     }
 
@@ -181,3 +382,484 @@ pub async fn bulk_update_employees(
         .map_err(|e| format!("Failed to commit transaction: {}", e))?;
     Ok(total_updated)
 }
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelinkResult {
+    pub moved_scores: u64,
+    /// Scores left on the old record because the new one already has that competency scored
+    pub conflicting_scores: u64,
+}
+
+/// Tables and columns holding an employee id, read from the foreign keys in the schema so a
+/// table added later is covered without being listed here.
+pub(crate) async fn employee_references(
+    conn: &mut SqliteConnection,
+) -> Result<Vec<(String, String)>, String> {
+    sqlx::query_as(
+        "SELECT m.name, fk.\"from\"
+         FROM sqlite_master m
+         JOIN pragma_foreign_key_list(m.name) fk
+         WHERE m.type = 'table' AND fk.\"table\" = 'employees'
+         ORDER BY m.name, fk.\"from\"",
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to read employee references: {}", e))
+}
+
+/// Moves everything held about `old_id` (dataset links, scores with their rater entries,
+/// summaries, notes, attendance, attachments, ...) onto `new_id`, e.g. after the same person
+/// was imported twice under different names. Where `new_id` already has a value its own is
+/// kept and the old one stays behind with the old record.
+pub(crate) async fn relink_employee_scores_tx(
+    conn: &mut SqliteConnection,
+    old_id: i64,
+    new_id: i64,
+) -> Result<RelinkResult, String> {
+    if old_id == new_id {
+        return Err("Choose two different employees to relink".to_string());
+    }
+    let found = count_employee_rows(conn, "employees", "id", &[old_id, new_id]).await?;
+    if found < 2 {
+        return Err("Employee not found".to_string());
+    }
+
+    // Rater entries follow their score, so they are moved once the scores have been
+    let mut moved_scores = 0;
+    for (table, column) in employee_references(conn).await? {
+        if table == "score_entries" {
+            continue;
+        }
+        // OR IGNORE skips the rows that would collide with a value `new_id` already has
+        let moved = sqlx::query(&format!(
+            "UPDATE OR IGNORE {} SET {} = ? WHERE {} = ?",
+            table, column, column
+        ))
+        .bind(new_id)
+        .bind(old_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to relink {}: {}", table, e))?
+        .rows_affected();
+        if table == "scores" {
+            moved_scores = moved;
+        }
+    }
+    sqlx::query(
+        "UPDATE OR IGNORE score_entries SET employee_id = ?
+         WHERE employee_id = ?
+           AND NOT EXISTS (
+               SELECT 1 FROM scores s
+               WHERE s.dataset_id = score_entries.dataset_id
+                 AND s.employee_id = score_entries.employee_id
+                 AND s.competency_id = score_entries.competency_id
+           )",
+    )
+    .bind(new_id)
+    .bind(old_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to relink score entries: {}", e))?;
+    let conflicting_scores = count_employee_rows(conn, "scores", "employee_id", &[old_id]).await?;
+
+    Ok(RelinkResult {
+        moved_scores,
+        conflicting_scores: conflicting_scores as u64,
+    })
+}
+
+#[tauri::command]
+pub async fn relink_employee_scores(
+    state: State<'_, AppState>,
+    old_id: i64,
+    new_id: i64,
+) -> Result<RelinkResult, String> {
+    state.require_role(Role::Admin).await?;
+    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+    let result = relink_employee_scores_tx(&mut tx, old_id, new_id).await?;
+    record_audit(
+        &mut *tx,
+        "employee_scores_relinked",
+        None,
+        &serde_json::json!({
+            "old_id": old_id,
+            "new_id": new_id,
+            "moved_scores": result.moved_scores,
+            "conflicting_scores": result.conflicting_scores,
+        }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(result)
+}
+
+async fn store_position_type(
+    pool: &SqlitePool,
+    employee_id: i64,
+    position_type: Option<PositionType>,
+) -> Result<Employee, String> {
+    sqlx::query_as::<_, Employee>(
+        "UPDATE employees SET position_type = ?, updated_at = datetime('now') WHERE id = ? RETURNING *",
+    )
+    .bind(position_type.map(|value| value.label()))
+    .bind(employee_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to update position type: {}", e))?
+    .ok_or_else(|| format!("Employee {} not found", employee_id))
+}
+
+/// Pins an employee to `eselon` or `staff`, overriding the keyword heuristics.
+#[tauri::command]
+pub async fn set_employee_position_type(
+    state: State<'_, AppState>,
+    employee_id: i64,
+    position_type: String,
+) -> Result<Employee, String> {
+    state.require_role(Role::Editor).await?;
+    let position_type = PositionType::from_label(&position_type)
+        .ok_or_else(|| format!("Unknown position type: {}", position_type))?;
+    store_position_type(&state.pool, employee_id, Some(position_type)).await
+}
+
+/// Stores a resized copy of the image at `file_path` as the employee's photo; `None` removes it.
+#[tauri::command]
+pub async fn set_employee_photo(
+    state: State<'_, AppState>,
+    employee_id: i64,
+    file_path: Option<String>,
+) -> Result<Employee, String> {
+    state.require_role(Role::Editor).await?;
+    set_photo(
+        &state.pool,
+        employee_id,
+        file_path.as_deref().map(Path::new),
+    )
+    .await?;
+    sqlx::query_as::<_, Employee>("SELECT * FROM employees WHERE id = ?")
+        .bind(employee_id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| format!("Failed to load employee: {}", e))
+}
+
+/// JPEG bytes of the employee's photo, `None` when there is none.
+#[tauri::command]
+pub async fn get_employee_photo(
+    state: State<'_, AppState>,
+    employee_id: i64,
+) -> Result<Option<Vec<u8>>, String> {
+    match photo_file(&state.pool, employee_id).await? {
+        Some(path) => std::fs::read(path)
+            .map(Some)
+            .map_err(|e| format!("Failed to read photo: {}", e)),
+        None => Ok(None),
+    }
+}
+
+#[tauri::command]
+pub async fn clear_employee_position_type(
+    state: State<'_, AppState>,
+    employee_id: i64,
+) -> Result<Employee, String> {
+    state.require_role(Role::Editor).await?;
+    store_position_type(&state.pool, employee_id, None).await
+}
+
+/// Notes on every employee of a dataset, oldest first.
+pub(crate) async fn fetch_dataset_notes(
+    pool: &SqlitePool,
+    dataset_id: i64,
+) -> Result<Vec<EmployeeNote>, sqlx::Error> {
+    sqlx::query_as::<_, EmployeeNote>(
+        "SELECT * FROM employee_notes WHERE dataset_id = ? ORDER BY created_at, id",
+    )
+    .bind(dataset_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub(crate) async fn fetch_employee_notes(
+    pool: &SqlitePool,
+    dataset_id: i64,
+    employee_id: i64,
+) -> Result<Vec<EmployeeNote>, sqlx::Error> {
+    sqlx::query_as::<_, EmployeeNote>(
+        "SELECT * FROM employee_notes
+         WHERE dataset_id = ? AND employee_id = ?
+         ORDER BY created_at, id",
+    )
+    .bind(dataset_id)
+    .bind(employee_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Notes on an employee within one dataset, oldest first.
+#[tauri::command]
+pub async fn list_employee_notes(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    employee_id: i64,
+) -> Result<Vec<EmployeeNote>, String> {
+    fetch_employee_notes(&state.pool, dataset_id, employee_id)
+        .await
+        .map_err(|e| format!("Failed to load employee notes: {}", e))
+}
+
+#[tauri::command]
+pub async fn add_employee_note(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    employee_id: i64,
+    content: String,
+) -> Result<EmployeeNote, String> {
+    state.require_role(Role::Editor).await?;
+    let content = normalize_note_content(&content)?;
+
+    sqlx::query_as::<_, EmployeeNote>(
+        "INSERT INTO employee_notes (dataset_id, employee_id, content, created_at, updated_at)
+         SELECT dataset_id, employee_id, ?, datetime('now'), datetime('now')
+         FROM dataset_employees WHERE dataset_id = ? AND employee_id = ?
+         RETURNING *",
+    )
+    .bind(content)
+    .bind(dataset_id)
+    .bind(employee_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|e| format!("Failed to add employee note: {}", e))?
+    .ok_or_else(|| {
+        format!(
+            "Employee {} is not part of dataset {}",
+            employee_id, dataset_id
+        )
+    })
+}
+
+#[tauri::command]
+pub async fn update_employee_note(
+    state: State<'_, AppState>,
+    id: i64,
+    content: String,
+) -> Result<EmployeeNote, String> {
+    state.require_role(Role::Editor).await?;
+    let content = normalize_note_content(&content)?;
+
+    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+    let previous: String = sqlx::query_scalar("SELECT content FROM employee_notes WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to load employee note: {}", e))?
+        .ok_or_else(|| "Employee note not found".to_string())?;
+    let note = sqlx::query_as::<_, EmployeeNote>(
+        "UPDATE employee_notes SET content = ?, updated_at = datetime('now') WHERE id = ? RETURNING *",
+    )
+    .bind(content)
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to update employee note: {}", e))?;
+
+    record_audit(
+        &mut *tx,
+        "employee_note_updated",
+        Some(note.dataset_id),
+        &serde_json::json!({
+            "note_id": note.id,
+            "employee_id": note.employee_id,
+            "old_content": previous,
+            "new_content": note.content,
+        }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(note)
+}
+
+#[tauri::command]
+pub async fn delete_employee_note(state: State<'_, AppState>, id: i64) -> Result<(), String> {
+    state.require_role(Role::Editor).await?;
+    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+    let deleted =
+        sqlx::query_as::<_, EmployeeNote>("DELETE FROM employee_notes WHERE id = ? RETURNING *")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to delete employee note: {}", e))?;
+
+    if let Some(note) = deleted {
+        record_audit(
+            &mut *tx,
+            "employee_note_deleted",
+            Some(note.dataset_id),
+            &serde_json::json!({
+                "note_id": note.id,
+                "employee_id": note.employee_id,
+                "content": note.content,
+            }),
+        )
+        .await
+        .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    }
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    #[tokio::test]
+    async fn test_deleting_employees_cascades_to_dependent_rows() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO datasets (id, name, created_at, updated_at)
+             VALUES (1, 'Penilaian 2024', datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, created_at, updated_at)
+             VALUES (1, 'Budi', datetime('now'), datetime('now')),
+                    (2, 'Sari', datetime('now'), datetime('now'));
+             INSERT INTO dataset_employees (dataset_id, employee_id, created_at, updated_at)
+             VALUES (1, 1, datetime('now'), datetime('now')),
+                    (1, 2, datetime('now'), datetime('now'));
+             INSERT INTO competencies (id, name, display_order) VALUES (1, 'Kerjasama', 0);
+             INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, created_at)
+             VALUES (1, 1, 1, '80', 80.0, datetime('now')),
+                    (2, 1, 1, '70', 70.0, datetime('now'));
+             INSERT INTO summaries (employee_id, dataset_id, content) VALUES (1, 1, 'Ringkasan');
+             INSERT INTO employee_notes (dataset_id, employee_id, content) VALUES (1, 1, 'Rajin');
+             INSERT INTO attendance (employee_id, month, working_days, present_days)
+             VALUES (1, '2024-01', 22, 21);",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let mut conn = pool.acquire().await.unwrap();
+        let preview = count_employee_dependents(&mut conn, &[1]).await.unwrap();
+        drop(conn);
+        assert_eq!(preview.employees, 1);
+        assert_eq!(
+            preview.dependents,
+            BTreeMap::from([
+                ("attendance".to_string(), 1),
+                ("dataset_employees".to_string(), 1),
+                ("employee_notes".to_string(), 1),
+                ("scores".to_string(), 1),
+                ("summaries".to_string(), 1),
+            ])
+        );
+
+        let mut tx = pool.begin().await.unwrap();
+        assert_eq!(delete_employees_tx(&mut tx, &[1]).await.unwrap(), 1);
+        tx.commit().await.unwrap();
+
+        let mut conn = pool.acquire().await.unwrap();
+        let remaining = count_employee_dependents(&mut conn, &[1]).await.unwrap();
+        assert_eq!(remaining, EmployeeDeletionCounts::default());
+        let others = count_employee_dependents(&mut conn, &[2]).await.unwrap();
+        assert_eq!((others.employees, others.dependents["scores"]), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_nip_identifies_employees_and_relink_moves_scores() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO datasets (id, name, created_at, updated_at)
+             VALUES (1, 'Penilaian 2024', datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, nip, created_at, updated_at)
+             VALUES (1, 'Budi Santoso', '198501012010011001', datetime('now'), datetime('now')),
+                    (2, 'Dr. Budi Santoso', NULL, datetime('now'), datetime('now'));
+             INSERT INTO dataset_employees (dataset_id, employee_id, created_at, updated_at)
+             VALUES (1, 1, datetime('now'), datetime('now')),
+                    (1, 2, datetime('now'), datetime('now'));
+             INSERT INTO competencies (id, name, display_order)
+             VALUES (1, 'Kerjasama', 0), (2, 'Integritas', 1);
+             INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, created_at)
+             VALUES (1, 1, 1, '80', 80.0, datetime('now')),
+                    (2, 1, 1, '70', 70.0, datetime('now')),
+                    (2, 1, 2, '90', 90.0, datetime('now'));
+             INSERT INTO employee_notes (dataset_id, employee_id, content) VALUES (1, 2, 'Rajin');
+             INSERT INTO attendance (employee_id, month, working_days, present_days)
+             VALUES (2, '2024-01', 22, 21);
+             INSERT INTO summaries (employee_id, dataset_id, content) VALUES (2, 1, 'Baik');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let duplicate = sqlx::query("UPDATE employees SET nip = '198501012010011001' WHERE id = 2")
+            .execute(&pool)
+            .await;
+        assert!(duplicate.is_err());
+        // A record flagged as sharing the NIP keeps it but is never matched by it
+        sqlx::query(
+            "INSERT INTO employees (id, name, nip, nip_conflict, created_at, updated_at)
+             VALUES (3, 'Budi S.', '198501012010011001', 1, datetime('now'), datetime('now'))",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let mut conn = pool.acquire().await.unwrap();
+        let nip_index = load_nip_index(&mut conn).await.unwrap();
+        assert_eq!(nip_index.get("198501012010011001"), Some(&1));
+        let renamed = find_master_employee(
+            &mut conn,
+            &nip_index,
+            Some("19850101 201001 1 001"),
+            "prof. budi santoso",
+        )
+        .await
+        .unwrap();
+        assert_eq!(renamed.map(|e| e.id), Some(1));
+        let other_person =
+            find_master_employee(&mut conn, &nip_index, Some("199001"), "budi santoso")
+                .await
+                .unwrap();
+        assert!(other_person.is_none());
+        drop(conn);
+
+        let mut tx = pool.begin().await.unwrap();
+        assert!(relink_employee_scores_tx(&mut tx, 2, 2).await.is_err());
+        let result = relink_employee_scores_tx(&mut tx, 2, 1).await.unwrap();
+        tx.commit().await.unwrap();
+        assert_eq!(
+            result,
+            RelinkResult {
+                moved_scores: 1,
+                conflicting_scores: 1,
+            }
+        );
+
+        let scores: Vec<(i64, i64, f64)> = sqlx::query_as(
+            "SELECT employee_id, competency_id, numeric_value FROM scores ORDER BY employee_id, competency_id",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(scores, vec![(1, 1, 80.0), (1, 2, 90.0), (2, 1, 70.0)]);
+        let moved: (i64, i64, i64) = sqlx::query_as(
+            "SELECT (SELECT employee_id FROM employee_notes),
+                    (SELECT employee_id FROM attendance),
+                    (SELECT employee_id FROM summaries)",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(moved, (1, 1, 1));
+    }
+}
@@ -0,0 +1,71 @@
+use crate::auth::Role;
+use crate::db::Database;
+use crate::encryption::{self, DatabaseLocation};
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseLockStatus {
+    pub encrypted: bool,
+    /// False until `unlock_database` succeeds on an encrypted database
+    pub unlocked: bool,
+}
+
+#[tauri::command]
+pub async fn get_database_lock_status(
+    app: AppHandle,
+    location: State<'_, DatabaseLocation>,
+) -> Result<DatabaseLockStatus, String> {
+    Ok(DatabaseLockStatus {
+        encrypted: encryption::is_encrypted(&location.path)?,
+        unlocked: app.try_state::<AppState>().is_some(),
+    })
+}
+
+/// Opens an encrypted database; every other command is unavailable until this succeeds.
+#[tauri::command]
+pub async fn unlock_database(
+    app: AppHandle,
+    location: State<'_, DatabaseLocation>,
+    passphrase: String,
+) -> Result<(), String> {
+    if app.try_state::<AppState>().is_some() {
+        return Ok(());
+    }
+    encryption::verify_passphrase(&location.path, &passphrase).await?;
+    let database = Database::new(location.path.clone(), Some(&passphrase))
+        .await
+        .map_err(|e| format!("Failed to open database: {}", e))?;
+    crate::start_workspace(&app, database.pool);
+    Ok(())
+}
+
+/// Sets, changes or (with `new_passphrase` empty) removes the database passphrase, then
+/// restarts the app so the database is reopened with it. A failed swap restarts the app
+/// on the original database, with the failure in its audit log.
+#[tauri::command]
+pub async fn set_database_passphrase(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    location: State<'_, DatabaseLocation>,
+    current_passphrase: Option<String>,
+    new_passphrase: Option<String>,
+) -> Result<(), String> {
+    state.require_role(Role::Admin).await?;
+    let result = encryption::rekey_database(
+        &state.pool,
+        &location.path,
+        current_passphrase.as_deref(),
+        new_passphrase
+            .as_deref()
+            .filter(|passphrase| !passphrase.is_empty()),
+    )
+    .await;
+    // A swap that failed after the pool closed leaves the original file in place; the
+    // restart reopens it
+    if result.is_ok() || state.pool.is_closed() {
+        app.restart()
+    }
+    result
+}
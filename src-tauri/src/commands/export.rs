@@ -1,10 +1,206 @@
-use crate::commands::analytics::{compute_dataset_stats, ScoreWithCompetency};
-use crate::db::models::{Competency, Dataset, Employee};
+use crate::attachments::{attachment_path, list_attachments};
+use crate::auth::Role;
+use crate::commands::analytics::{
+    compute_dataset_comparison, compute_dataset_performances, compute_dataset_stats,
+    compute_rankings, load_anonymity_floor, DatasetComparison, DatasetStats, EmployeePerformance,
+    EmployeeRanking, ScoreWithCompetency, ANONYMITY_FLOOR_FILTER, DATASET_COMPETENCY_JOIN,
+    DATASET_COMPETENCY_ORDER,
+};
+use crate::commands::dataset::fetch_dataset_notes;
+use crate::commands::employee::{employee_references, fetch_employee_notes};
+use crate::commands::report::{
+    load_report_context, load_report_contexts, render_report_pdf, EmployeeReportContext,
+};
+use crate::commands::summaries::{
+    load_summary, load_summary_export, summary_export_contents, write_summary_pdf,
+};
+use crate::data_quality::assess_dataset;
+use crate::db::audit::record_audit;
+use crate::db::models::{Competency, Dataset, DatasetNote, Employee, EmployeeNote, Summary};
+use crate::i18n::Messages;
+use crate::pdf::batch::render_all;
+use crate::pdf::filename::{render_file_name, sanitize_file_name};
+use crate::pdf::letterhead::{draw_running_header, load_report_settings, ReportSettings};
+use crate::pdf::paper::{Orientation, PageLayout, PaperSize};
+use crate::pdf::{Canvas, Font, Pdf};
+use crate::photos::photo_file;
+use crate::scoring::PositionType;
 use crate::AppState;
-use pdf_canvas::{BuiltinFont, Canvas, Pdf};
-use rust_xlsxwriter::{Format, Workbook};
-use std::collections::HashMap;
-use tauri::State;
+use chrono::{DateTime, Utc};
+use rust_xlsxwriter::{Chart, ChartType, Format, Workbook, XlsxError};
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, State};
+use thiserror::Error;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Event emitted by `export_dataset_archive` after each employee's documents are written.
+const ARCHIVE_PROGRESS_EVENT: &str = "dataset-archive-progress";
+
+#[derive(Debug, Clone, Serialize)]
+struct ArchiveProgress {
+    dataset_id: i64,
+    completed: usize,
+    total: usize,
+    employee_name: String,
+}
+
+/// Event emitted by `export_dataset` after each chunk of employees is written.
+const EXPORT_PROGRESS_EVENT: &str = "export://progress";
+/// Employees written between two progress events.
+const EXPORT_PROGRESS_CHUNK: usize = 50;
+
+#[derive(Debug, Clone, Serialize)]
+struct ExportProgress {
+    export_id: String,
+    dataset_id: i64,
+    completed: usize,
+    total: usize,
+}
+
+#[derive(Debug, Error)]
+enum ExportError {
+    #[error("Export cancelled")]
+    Cancelled,
+    #[error("{0}")]
+    Failed(String),
+}
+
+impl From<String> for ExportError {
+    fn from(error: String) -> Self {
+        Self::Failed(error)
+    }
+}
+
+impl From<ExportError> for String {
+    fn from(error: ExportError) -> Self {
+        error.to_string()
+    }
+}
+
+/// Cancel tokens of the running dataset exports, keyed by the export id the caller chose.
+#[derive(Debug, Clone, Default)]
+pub struct ExportCancels {
+    tokens: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
+}
+
+/// Forgets the export's token when dropped, however the export ends.
+struct ExportRegistration {
+    cancels: ExportCancels,
+    export_id: String,
+    token: Arc<AtomicBool>,
+}
+
+impl ExportCancels {
+    fn token(&self, export_id: &str) -> Result<Arc<AtomicBool>, String> {
+        let mut tokens = self
+            .tokens
+            .lock()
+            .map_err(|_| "Export registry is unavailable".to_string())?;
+        Ok(tokens.entry(export_id.to_string()).or_default().clone())
+    }
+
+    /// A cancel that arrives before the export registers is kept and stops it on start.
+    fn register(&self, export_id: &str) -> Result<ExportRegistration, String> {
+        Ok(ExportRegistration {
+            cancels: self.clone(),
+            export_id: export_id.to_string(),
+            token: self.token(export_id)?,
+        })
+    }
+
+    fn cancel(&self, export_id: &str) -> Result<(), String> {
+        self.token(export_id)?.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+impl Drop for ExportRegistration {
+    fn drop(&mut self) {
+        if let Ok(mut tokens) = self.cancels.tokens.lock() {
+            tokens.remove(&self.export_id);
+        }
+    }
+}
+
+/// Reports how far a dataset export has come and stops it once `cancel_export` is called.
+struct ExportTracker {
+    app: Option<AppHandle>,
+    export_id: String,
+    cancel: Arc<AtomicBool>,
+    dataset_id: i64,
+    total: usize,
+    reported: Cell<usize>,
+}
+
+impl ExportTracker {
+    fn new(
+        app: Option<AppHandle>,
+        registration: &ExportRegistration,
+        dataset_id: i64,
+        total: usize,
+    ) -> Self {
+        Self {
+            app,
+            export_id: registration.export_id.clone(),
+            cancel: registration.token.clone(),
+            dataset_id,
+            total,
+            reported: Cell::new(0),
+        }
+    }
+
+    /// Neither reports nor cancels, for exports that are one step of a larger task.
+    fn silent(dataset_id: i64) -> Self {
+        Self {
+            app: None,
+            export_id: String::new(),
+            cancel: Arc::default(),
+            dataset_id,
+            total: 0,
+            reported: Cell::new(0),
+        }
+    }
+
+    /// Called once the first `completed` employees are written; errors when cancelled.
+    fn employees_written(&self, completed: usize) -> Result<(), ExportError> {
+        if self.cancel.load(Ordering::Relaxed) {
+            return Err(ExportError::Cancelled);
+        }
+        if completed == self.total || completed >= self.reported.get() + EXPORT_PROGRESS_CHUNK {
+            self.reported.set(completed);
+            if let Some(app) = &self.app {
+                let _ = app.emit(
+                    EXPORT_PROGRESS_EVENT,
+                    ExportProgress {
+                        export_id: self.export_id.clone(),
+                        dataset_id: self.dataset_id,
+                        completed,
+                        total: self.total,
+                    },
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs file writing off the async runtime so a large export does not stall other commands.
+async fn run_blocking<F>(task: F) -> Result<(), ExportError>
+where
+    F: FnOnce() -> Result<(), ExportError> + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(task)
+        .await
+        .map_err(|e| format!("Export task failed: {}", e))?
+}
 
 #[derive(Debug)]
 struct DatasetExportData {
@@ -14,25 +210,107 @@ struct DatasetExportData {
     scores_by_employee: HashMap<i64, Vec<ScoreWithCompetency>>,
 }
 
+/// Stable stand-ins for employees in shared exports: `E-001` onwards in order of employee id,
+/// so the same employee gets the same code each time a dataset is exported.
+pub(crate) fn employee_pseudonyms(
+    employee_ids: impl IntoIterator<Item = i64>,
+) -> HashMap<i64, String> {
+    let mut ids: Vec<i64> = employee_ids.into_iter().collect();
+    ids.sort_unstable();
+    ids.dedup();
+    ids.into_iter()
+        .enumerate()
+        .map(|(index, id)| (id, format!("E-{:03}", index + 1)))
+        .collect()
+}
+
+/// Replaces what identifies `employee` with their pseudonym; scores and position stay.
+pub(crate) fn pseudonymize(employee: &mut Employee, pseudonyms: &HashMap<i64, String>) {
+    employee.name = pseudonyms
+        .get(&employee.id)
+        .cloned()
+        .unwrap_or_else(|| format!("E-{}", employee.id));
+    employee.nip = None;
+    employee.photo_path = None;
+}
+
+/// Writes the dataset as CSV, XLSX or PDF, emitting `export://progress` as employees are
+/// written; `cancel_export` with the same `export_id` stops it. A failed or cancelled export
+/// leaves no partial file. With `anonymize`, names and NIPs are replaced by pseudonyms and
+/// notes are left out. Like every export of a whole dataset, and like the employee data
+/// package, it is for admins only.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn export_dataset(
+    app: AppHandle,
     state: State<'_, AppState>,
+    export_id: String,
     dataset_id: i64,
     format: String,
     file_path: String,
+    include_notes: Option<bool>,
+    anonymize: Option<bool>,
+    force: Option<bool>,
 ) -> Result<(), String> {
+    state.require_role(Role::Admin).await?;
+    let registration = state.export_cancels.register(&export_id)?;
     let pool = state.pool.clone();
+    assess_dataset(&pool, dataset_id)
+        .await?
+        .ensure_exportable(force.unwrap_or(false))?;
+    let anonymize = anonymize.unwrap_or(false);
 
-    let export_data = collect_dataset_data(&pool, dataset_id)
+    let mut export_data = collect_dataset_data(&pool, dataset_id)
         .await
         .map_err(|e| format!("Failed to collect dataset: {}", e))?;
+    if anonymize {
+        let pseudonyms = employee_pseudonyms(export_data.employees.iter().map(|e| e.id));
+        for employee in &mut export_data.employees {
+            pseudonymize(employee, &pseudonyms);
+        }
+        // Alphabetical order would hint at the names
+        export_data.employees.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+    let tracker = ExportTracker::new(
+        Some(app),
+        &registration,
+        dataset_id,
+        export_data.employees.len(),
+    );
 
-    match format.as_str() {
-        "csv" => export_csv(&export_data, &file_path),
-        "xlsx" => export_xlsx(&export_data, &file_path),
-        "pdf" => export_pdf(&pool, &export_data, &file_path).await,
-        other => Err(format!("Unsupported export format: {}", other)),
+    let path = file_path.clone();
+    let result = match format.as_str() {
+        "csv" => run_blocking(move || export_csv(&export_data, &path, &tracker)).await,
+        "xlsx" => run_blocking(move || export_xlsx(&export_data, &path, &tracker)).await,
+        "pdf" => {
+            let notes = if include_notes.unwrap_or(false) && !anonymize {
+                fetch_dataset_notes(&pool, dataset_id)
+                    .await
+                    .map_err(|e| format!("Failed to load dataset notes: {}", e))?
+            } else {
+                Vec::new()
+            };
+            let stats = compute_dataset_stats(&pool, dataset_id)
+                .await
+                .map_err(|e| format!("Failed to compute dataset stats: {}", e))?;
+            let settings = load_report_settings(&pool).await?;
+            run_blocking(move || {
+                export_pdf(&export_data, &notes, &stats, &settings, &path, &tracker)
+            })
+            .await
+        }
+        other => return Err(format!("Unsupported export format: {}", other)),
+    };
+    if result.is_err() {
+        let _ = std::fs::remove_file(&file_path);
     }
+    Ok(result?)
+}
+
+/// Stops the `export_dataset` started with `export_id` at its next employee.
+#[tauri::command]
+pub async fn cancel_export(state: State<'_, AppState>, export_id: String) -> Result<(), String> {
+    state.export_cancels.cancel(&export_id)
 }
 
 async fn collect_dataset_data(
@@ -54,12 +332,16 @@ async fn collect_dataset_data(
     .fetch_all(pool)
     .await?;
 
-    let competencies = sqlx::query_as::<_, Competency>(
-        "SELECT DISTINCT c.* FROM competencies c
+    let competencies = sqlx::query_as::<_, Competency>(&format!(
+        "SELECT DISTINCT c.id, c.name, c.description, {order} AS display_order, c.category_id
+         FROM competencies c
          JOIN scores s ON c.id = s.competency_id
+         {join}
          WHERE s.dataset_id = ?
-         ORDER BY c.display_order, c.name",
-    )
+         ORDER BY display_order, c.name",
+        join = DATASET_COMPETENCY_JOIN,
+        order = DATASET_COMPETENCY_ORDER,
+    ))
     .bind(dataset_id)
     .fetch_all(pool)
     .await?;
@@ -76,16 +358,22 @@ async fn collect_dataset_data(
         String,
         Option<String>,
         i32,
-    )> = sqlx::query_as(
+        Option<i64>,
+    )> = sqlx::query_as(&format!(
         "SELECT
                 s.id, s.employee_id, s.dataset_id, s.competency_id, s.raw_value, s.numeric_value, s.created_at,
-                c.id, c.name, c.description, c.display_order
+                c.id, c.name, c.description, {order}, c.category_id
             FROM scores s
             JOIN competencies c ON s.competency_id = c.id
-            WHERE s.dataset_id = ?
-            ORDER BY s.employee_id, c.display_order, c.name",
-    )
+            {join}
+            WHERE s.dataset_id = ? AND {floor}
+            ORDER BY s.employee_id, {order}, c.name",
+        join = DATASET_COMPETENCY_JOIN,
+        order = DATASET_COMPETENCY_ORDER,
+        floor = ANONYMITY_FLOOR_FILTER,
+    ))
     .bind(dataset_id)
+    .bind(load_anonymity_floor(pool).await?)
     .fetch_all(pool)
     .await?;
 
@@ -102,6 +390,7 @@ async fn collect_dataset_data(
         comp_name,
         comp_description,
         comp_order,
+        comp_category_id,
     ) in score_rows
     {
         let entry = scores_by_employee.entry(employee_id).or_default();
@@ -114,12 +403,16 @@ async fn collect_dataset_data(
                 raw_value,
                 numeric_value,
                 created_at: created_at.parse().unwrap_or_default(),
+                source_row: None,
+                source_column: None,
+                import_run_id: None,
             },
             competency: Competency {
                 id: comp_id,
                 name: comp_name,
                 description: comp_description,
                 display_order: comp_order,
+                category_id: comp_category_id,
             },
         });
     }
@@ -132,7 +425,11 @@ async fn collect_dataset_data(
     })
 }
 
-fn export_csv(data: &DatasetExportData, file_path: &str) -> Result<(), String> {
+fn export_csv(
+    data: &DatasetExportData,
+    file_path: &str,
+    tracker: &ExportTracker,
+) -> Result<(), ExportError> {
     let mut writer =
         csv::Writer::from_path(file_path).map_err(|e| format!("Failed to open CSV file: {}", e))?;
 
@@ -153,7 +450,7 @@ fn export_csv(data: &DatasetExportData, file_path: &str) -> Result<(), String> {
         .write_record(headers)
         .map_err(|e| format!("Failed to write CSV header: {}", e))?;
 
-    for employee in &data.employees {
+    for (idx, employee) in data.employees.iter().enumerate() {
         let scores = data
             .scores_by_employee
             .get(&employee.id)
@@ -202,14 +499,19 @@ fn export_csv(data: &DatasetExportData, file_path: &str) -> Result<(), String> {
         writer
             .write_record(row)
             .map_err(|e| format!("Failed to write CSV row: {}", e))?;
+        tracker.employees_written(idx + 1)?;
     }
 
     writer
         .flush()
-        .map_err(|e| format!("Failed to finish CSV export: {}", e))
+        .map_err(|e| ExportError::Failed(format!("Failed to finish CSV export: {}", e)))
 }
 
-fn export_xlsx(data: &DatasetExportData, file_path: &str) -> Result<(), String> {
+fn export_xlsx(
+    data: &DatasetExportData,
+    file_path: &str,
+    tracker: &ExportTracker,
+) -> Result<(), ExportError> {
     let mut workbook = Workbook::new();
     let worksheet = workbook.add_worksheet();
 
@@ -328,22 +630,22 @@ fn export_xlsx(data: &DatasetExportData, file_path: &str) -> Result<(), String>
             }
             col_idx += 1;
         }
+        tracker.employees_written(row_idx + 1)?;
     }
 
     workbook
         .save(file_path)
-        .map_err(|e| format!("Failed to save workbook: {}", e))
+        .map_err(|e| ExportError::Failed(format!("Failed to save workbook: {}", e)))
 }
 
-async fn export_pdf(
-    pool: &sqlx::SqlitePool,
+fn export_pdf(
     data: &DatasetExportData,
+    notes: &[DatasetNote],
+    stats: &DatasetStats,
+    settings: &ReportSettings,
     file_path: &str,
-) -> Result<(), String> {
-    let stats = compute_dataset_stats(pool, data.dataset.id)
-        .await
-        .map_err(|e| format!("Failed to compute dataset stats: {}", e))?;
-
+    tracker: &ExportTracker,
+) -> Result<(), ExportError> {
     let employee_lines: Vec<String> = data
         .employees
         .iter()
@@ -404,16 +706,19 @@ async fn export_pdf(
         .map(|dist| format!("Rentang {}: {} entri", dist.range, dist.count))
         .collect();
 
-    let first_capacity = dataset_first_page_capacity(score_distribution_lines.len());
-    let follow_capacity = dataset_followup_page_capacity();
+    let layout = settings.paper.layout(Orientation::Portrait);
+    let first_capacity = dataset_first_page_capacity(&layout, score_distribution_lines.len());
+    let follow_capacity = dataset_followup_page_capacity(&layout);
     let page_ranges =
         dataset_partition_employee_lines(employee_lines.len(), first_capacity, follow_capacity);
 
     let (first_start, first_end) = page_ranges[0];
     document
-        .render_page(595.0, 842.0, |canvas| {
+        .render_page(layout.width, layout.height, |canvas| {
+            draw_running_header(canvas, settings, layout.width, layout.height)?;
             render_dataset_first_page(
                 canvas,
+                &layout,
                 &title,
                 subtitle,
                 &stats_summary,
@@ -422,46 +727,52 @@ async fn export_pdf(
             )
         })
         .map_err(|e| format!("Failed to render PDF: {}", e))?;
+    tracker.employees_written(first_end)?;
 
     for (page_index, &(start, end)) in page_ranges.iter().enumerate().skip(1) {
         document
-            .render_page(595.0, 842.0, |canvas| {
+            .render_page(layout.width, layout.height, |canvas| {
+                draw_running_header(canvas, settings, layout.width, layout.height)?;
                 render_dataset_followup_page(
                     canvas,
+                    &layout,
                     &title,
                     page_index,
                     &employee_lines[start..end],
                 )
             })
             .map_err(|e| format!("Failed to render PDF: {}", e))?;
+        tracker.employees_written(end)?;
+    }
+
+    let note_lines = dataset_note_lines(&document, notes, layout.content_width());
+    for chunk in note_lines.chunks(follow_capacity.max(1)) {
+        document
+            .render_page(layout.width, layout.height, |canvas| {
+                draw_running_header(canvas, settings, layout.width, layout.height)?;
+                render_dataset_notes_page(canvas, &layout, &title, chunk)
+            })
+            .map_err(|e| format!("Failed to render PDF: {}", e))?;
     }
 
     document
         .finish()
-        .map_err(|e| format!("Failed to save PDF: {}", e))
+        .map_err(|e| ExportError::Failed(format!("Failed to save PDF: {}", e)))
 }
 
-fn dataset_first_page_capacity(score_distribution_count: usize) -> usize {
-    let mut cursor: f64 = 800.0;
+fn dataset_first_page_capacity(layout: &PageLayout, score_distribution_count: usize) -> usize {
+    let mut cursor = layout.top;
     cursor -= 24.0;
     cursor -= 40.0;
     cursor -= 20.0;
-    cursor -= score_distribution_count as f64 * 16.0;
+    cursor -= score_distribution_count as f32 * 16.0;
     cursor -= 20.0;
     cursor -= 24.0;
-    let available = cursor - 80.0;
-    if available <= 0.0 {
-        0
-    } else {
-        (available / 16.0).floor() as usize
-    }
+    layout.lines_below(cursor, 16.0)
 }
 
-fn dataset_followup_page_capacity() -> usize {
-    let mut cursor: f64 = 800.0;
-    cursor -= 24.0;
-    let available = cursor - 80.0;
-    (available / 16.0).floor() as usize
+fn dataset_followup_page_capacity(layout: &PageLayout) -> usize {
+    layout.lines_below(layout.top - 24.0, 16.0)
 }
 
 fn dataset_partition_employee_lines(
@@ -484,37 +795,32 @@ fn dataset_partition_employee_lines(
 
 fn render_dataset_first_page(
     canvas: &mut Canvas<'_>,
+    layout: &PageLayout,
     title: &str,
     subtitle: &str,
     stats_summary: &str,
     score_distribution_lines: &[String],
     employee_lines: &[String],
 ) -> std::io::Result<()> {
-    let mut cursor_y = 800.0;
-    canvas.left_text(50.0, cursor_y, BuiltinFont::Helvetica_Bold, 18.0, title)?;
+    let mut cursor_y = layout.top;
+    canvas.left_text(layout.left, cursor_y, Font::Bold, 18.0, title)?;
     cursor_y -= 24.0;
-    canvas.left_text(50.0, cursor_y, BuiltinFont::Helvetica, 12.0, subtitle)?;
+    canvas.left_text(layout.left, cursor_y, Font::Regular, 12.0, subtitle)?;
     cursor_y -= 40.0;
-    canvas.left_text(50.0, cursor_y, BuiltinFont::Helvetica, 12.0, stats_summary)?;
+    canvas.left_text(layout.left, cursor_y, Font::Regular, 12.0, stats_summary)?;
     cursor_y -= 20.0;
 
     for line in score_distribution_lines {
-        canvas.left_text(50.0, cursor_y, BuiltinFont::Helvetica, 12.0, line)?;
+        canvas.left_text(layout.left, cursor_y, Font::Regular, 12.0, line)?;
         cursor_y -= 16.0;
     }
 
     cursor_y -= 20.0;
-    canvas.left_text(
-        50.0,
-        cursor_y,
-        BuiltinFont::Helvetica_Bold,
-        14.0,
-        "Daftar Karyawan",
-    )?;
+    canvas.left_text(layout.left, cursor_y, Font::Bold, 14.0, "Daftar Karyawan")?;
     cursor_y -= 24.0;
 
     for line in employee_lines {
-        canvas.left_text(50.0, cursor_y, BuiltinFont::Helvetica, 11.0, line)?;
+        canvas.left_text(layout.left, cursor_y, Font::Regular, 11.0, line)?;
         cursor_y -= 16.0;
     }
 
@@ -523,23 +829,1376 @@ fn render_dataset_first_page(
 
 fn render_dataset_followup_page(
     canvas: &mut Canvas<'_>,
+    layout: &PageLayout,
     title: &str,
     page_index: usize,
     employee_lines: &[String],
 ) -> std::io::Result<()> {
-    let mut cursor_y = 800.0;
+    let mut cursor_y = layout.top;
     let header = if page_index == 1 {
         format!("{} (lanjutan)", title)
     } else {
         format!("{} (lanjutan {})", title, page_index)
     };
-    canvas.left_text(50.0, cursor_y, BuiltinFont::Helvetica_Bold, 14.0, &header)?;
+    canvas.left_text(layout.left, cursor_y, Font::Bold, 14.0, &header)?;
     cursor_y -= 24.0;
 
     for line in employee_lines {
-        canvas.left_text(50.0, cursor_y, BuiltinFont::Helvetica, 11.0, line)?;
+        canvas.left_text(layout.left, cursor_y, Font::Regular, 11.0, line)?;
+        cursor_y -= 16.0;
+    }
+
+    Ok(())
+}
+
+const NOTE_FONT_SIZE: f32 = 11.0;
+
+/// Dated notes wrapped to `width` in the notes page's font, one entry per printed line.
+fn dataset_note_lines(document: &Pdf, notes: &[DatasetNote], width: f32) -> Vec<String> {
+    notes
+        .iter()
+        .flat_map(|note| {
+            let text = format!(
+                "{} - {}",
+                note.created_at.format("%d/%m/%Y %H:%M"),
+                note.content
+            );
+            document.wrap_to_width(Font::Regular, NOTE_FONT_SIZE, &text, width)
+        })
+        .collect()
+}
+
+fn render_dataset_notes_page(
+    canvas: &mut Canvas<'_>,
+    layout: &PageLayout,
+    title: &str,
+    note_lines: &[String],
+) -> std::io::Result<()> {
+    let mut cursor_y = layout.top;
+    let header = format!("{} - Catatan Dataset", title);
+    canvas.left_text(layout.left, cursor_y, Font::Bold, 14.0, &header)?;
+    cursor_y -= 24.0;
+
+    for line in note_lines {
+        canvas.left_text(layout.left, cursor_y, Font::Regular, NOTE_FONT_SIZE, line)?;
+        cursor_y -= 16.0;
+    }
+
+    Ok(())
+}
+
+const DEFAULT_LEADERBOARD_SIZE: usize = 10;
+const MAX_LEADERBOARD_SIZE: usize = 50;
+
+/// Initials only, with academic titles after the comma dropped ("Budi Santoso, S.Pd" -> "B. S.")
+fn anonymized_name(name: &str) -> String {
+    name.split(',')
+        .next()
+        .unwrap_or_default()
+        .split_whitespace()
+        .filter_map(|part| part.chars().next())
+        .map(|initial| format!("{}.", initial.to_uppercase()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Shortens text with a trailing ellipsis until it fits the given width
+fn fit_text(canvas: &Canvas<'_>, size: f32, text: &str, max_width: f32) -> String {
+    if canvas.text_width(Font::Regular, size, text) <= max_width {
+        return text.to_string();
+    }
+    let mut chars: Vec<char> = text.chars().collect();
+    while chars.pop().is_some() {
+        let candidate = format!("{}…", chars.iter().collect::<String>().trim_end());
+        if canvas.text_width(Font::Regular, size, &candidate) <= max_width {
+            return candidate;
+        }
+    }
+    String::new()
+}
+
+/// Single-page leaderboard of the top ranked employees for the office notice board.
+/// Ties at the cut-off are all included.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn export_leaderboard_pdf(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    file_path: String,
+    top_n: Option<usize>,
+    paper_size: Option<String>,
+    anonymize: Option<bool>,
+    basis: Option<String>,
+    force: Option<bool>,
+) -> Result<(), String> {
+    state.require_role(Role::Admin).await?;
+    let pool = state.pool.clone();
+    let paper_size = paper_size
+        .as_deref()
+        .filter(|size| !size.trim().is_empty())
+        .map(PaperSize::parse)
+        .transpose()?;
+    assess_dataset(&pool, dataset_id)
+        .await?
+        .ensure_exportable(force.unwrap_or(false))?;
+    let top_n = top_n
+        .unwrap_or(DEFAULT_LEADERBOARD_SIZE)
+        .clamp(1, MAX_LEADERBOARD_SIZE);
+
+    let dataset = sqlx::query_as::<_, Dataset>("SELECT * FROM datasets WHERE id = ?")
+        .bind(dataset_id)
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| format!("Failed to load dataset: {}", e))?;
+
+    let rankings: Vec<EmployeeRanking> = compute_rankings(&pool, dataset_id, basis.as_deref())
+        .await?
+        .into_iter()
+        .filter(|ranking| ranking.rank as usize <= top_n)
+        .collect();
+    if rankings.is_empty() {
+        return Err("No ranked employees in this dataset".to_string());
+    }
+
+    let anonymize = anonymize.unwrap_or(false);
+    let rows: Vec<(String, String, String, String)> = rankings
+        .iter()
+        .map(|ranking| {
+            let name = if anonymize {
+                anonymized_name(&ranking.employee.name)
+            } else {
+                ranking.employee.name.clone()
+            };
+            (
+                ranking.rank.to_string(),
+                name,
+                format!("{:.2}", ranking.score),
+                ranking.rating.clone(),
+            )
+        })
+        .collect();
+
+    let period = [
+        dataset.period_label.clone(),
+        dataset.period_year.map(|year| year.to_string()),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(" ");
+    let subtitle = if period.is_empty() {
+        dataset.name.clone()
+    } else {
+        format!("{} - Periode {}", dataset.name, period)
+    };
+    let printed_at = format!("Dicetak {}", chrono::Local::now().format("%d/%m/%Y"));
+
+    let settings = load_report_settings(&pool).await?;
+    let layout = settings.paper.layout_for(
+        paper_size.unwrap_or(settings.paper.size),
+        Orientation::Portrait,
+    );
+    let mut document =
+        Pdf::create(&file_path).map_err(|e| format!("Failed to create PDF: {}", e))?;
+    document
+        .render_page(layout.width, layout.height, |canvas| {
+            draw_running_header(canvas, &settings, layout.width, layout.height)?;
+            render_leaderboard_page(canvas, &layout, &subtitle, &printed_at, &rows)
+        })
+        .map_err(|e| format!("Failed to render PDF: {}", e))?;
+    document
+        .finish()
+        .map_err(|e| format!("Failed to save PDF: {}", e))
+}
+
+fn render_leaderboard_page(
+    canvas: &mut Canvas<'_>,
+    layout: &PageLayout,
+    subtitle: &str,
+    printed_at: &str,
+    rows: &[(String, String, String, String)],
+) -> std::io::Result<()> {
+    // Type grows with the paper, measured along its short side
+    let scale = layout.width.min(layout.height) / 595.0;
+    let mut cursor_y = layout.top - 24.0 * scale;
+
+    canvas.center_text(
+        layout.center_x(),
+        cursor_y,
+        Font::Bold,
+        26.0 * scale,
+        "Papan Peringkat Kinerja Pegawai",
+    )?;
+    cursor_y -= 26.0 * scale;
+    canvas.center_text(
+        layout.center_x(),
+        cursor_y,
+        Font::Regular,
+        14.0 * scale,
+        subtitle,
+    )?;
+    cursor_y -= 40.0 * scale;
+
+    let rank_x = layout.left + 10.0 * scale;
+    let name_x = layout.left + 60.0 * scale;
+    let score_right_x = layout.right - 150.0 * scale;
+    let rating_x = layout.right - 130.0 * scale;
+    let header_size = 12.0 * scale;
+
+    canvas.left_text(rank_x, cursor_y, Font::Bold, header_size, "No")?;
+    canvas.left_text(name_x, cursor_y, Font::Bold, header_size, "Nama")?;
+    canvas.right_text(score_right_x, cursor_y, Font::Bold, header_size, "Nilai")?;
+    canvas.left_text(rating_x, cursor_y, Font::Bold, header_size, "Predikat")?;
+    cursor_y -= 8.0 * scale;
+    canvas.set_line_width(scale)?;
+    canvas.line(layout.left, cursor_y, layout.right, cursor_y)?;
+
+    // Rows share the remaining height, capped so short lists are not stretched
+    let available = cursor_y - layout.bottom - 30.0 * scale;
+    let row_height = (available / rows.len() as f32).min(40.0 * scale);
+    let font_size = (row_height * 0.5).min(18.0 * scale);
+    let name_width = rating_x - name_x - 80.0 * scale;
+
+    for (index, (rank, name, score, rating)) in rows.iter().enumerate() {
+        let row_top = cursor_y;
+        cursor_y -= row_height;
+        if index % 2 == 0 {
+            canvas.set_fill_gray(235)?;
+            canvas.fill_rectangle(layout.left, cursor_y, layout.content_width(), row_height)?;
+            canvas.set_fill_gray(0)?;
+        }
+
+        let label = fit_text(canvas, font_size, name, name_width);
+
+        let baseline = row_top - row_height * 0.5 - font_size * 0.35;
+        canvas.left_text(rank_x, baseline, Font::Bold, font_size, rank)?;
+        canvas.left_text(name_x, baseline, Font::Regular, font_size, &label)?;
+        canvas.right_text(score_right_x, baseline, Font::Bold, font_size, score)?;
+        canvas.left_text(rating_x, baseline, Font::Regular, font_size, rating)?;
+    }
+
+    canvas.left_text(
+        layout.left,
+        layout.bottom,
+        Font::Regular,
+        9.0 * scale,
+        printed_at,
+    )?;
+    Ok(())
+}
+
+/// Writes the competency deltas of `compare_datasets` as an XLSX sheet or a PDF table.
+#[tauri::command]
+pub async fn export_dataset_comparison(
+    state: State<'_, AppState>,
+    base_id: i64,
+    comparison_id: i64,
+    format: String,
+    file_path: String,
+) -> Result<(), String> {
+    state.require_role(Role::Admin).await?;
+    let pool = state.pool.clone();
+    let comparison = compute_dataset_comparison(&pool, base_id, comparison_id, false).await?;
+
+    match format.as_str() {
+        "xlsx" => export_comparison_xlsx(&comparison, &file_path),
+        "pdf" => {
+            let settings = load_report_settings(&pool).await?;
+            let mut document =
+                Pdf::create(&file_path).map_err(|e| format!("Failed to create PDF: {}", e))?;
+            let rows: Vec<[String; 4]> = comparison
+                .competency_deltas
+                .iter()
+                .map(|delta| {
+                    [
+                        delta.competency.name.clone(),
+                        format!("{:.2}", delta.base_average),
+                        format!("{:.2}", delta.comparison_average),
+                        format!("{:+.2}", delta.delta),
+                    ]
+                })
+                .collect();
+            let title = format!(
+                "Perbandingan {} dan {}",
+                comparison.base.dataset.name, comparison.comparison.dataset.name
+            );
+            let summary = [
+                format!(
+                    "Rata-rata {}: {:.2} ({} karyawan)",
+                    comparison.base.dataset.name,
+                    comparison.base.average_score,
+                    comparison.base.total_employees
+                ),
+                format!(
+                    "Rata-rata {}: {:.2} ({} karyawan)",
+                    comparison.comparison.dataset.name,
+                    comparison.comparison.average_score,
+                    comparison.comparison.total_employees
+                ),
+                format!("Selisih rata-rata: {:+.2}", comparison.average_delta),
+            ];
+
+            let layout = settings.paper.layout(Orientation::Portrait);
+            let rows_per_page = layout
+                .lines_below(layout.top - COMPARISON_HEADER_HEIGHT, 18.0)
+                .max(1);
+            let mut chunks: Vec<&[[String; 4]]> = rows.chunks(rows_per_page).collect();
+            if chunks.is_empty() {
+                chunks.push(&[]);
+            }
+            for (page_index, chunk) in chunks.into_iter().enumerate() {
+                let summary: &[String] = if page_index == 0 { &summary } else { &[] };
+                document
+                    .render_page(layout.width, layout.height, |canvas| {
+                        draw_running_header(canvas, &settings, layout.width, layout.height)?;
+                        render_comparison_page(canvas, &layout, &title, summary, chunk)
+                    })
+                    .map_err(|e| format!("Failed to render PDF: {}", e))?;
+            }
+            document
+                .finish()
+                .map_err(|e| format!("Failed to save PDF: {}", e))
+        }
+        other => Err(format!("Unsupported export format: {}", other)),
+    }
+}
+
+/// Space above the first row: title, summary lines and the column headings.
+const COMPARISON_HEADER_HEIGHT: f32 = 150.0;
+
+fn export_comparison_xlsx(comparison: &DatasetComparison, file_path: &str) -> Result<(), String> {
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    let header_format = Format::new().set_bold().set_background_color(0xDDDDDD);
+    let number_format = Format::new().set_num_format("0.00");
+    let cell_error = |e: rust_xlsxwriter::XlsxError| format!("Failed to write cell: {}", e);
+
+    let base_name = &comparison.base.dataset.name;
+    let comparison_name = &comparison.comparison.dataset.name;
+    let headers = [
+        "Competency",
+        base_name.as_str(),
+        comparison_name.as_str(),
+        "Delta",
+    ];
+    for (col, header) in headers.iter().enumerate() {
+        worksheet
+            .write_string_with_format(0, col as u16, *header, &header_format)
+            .map_err(|e| format!("Failed to write header: {}", e))?;
+    }
+
+    for (index, delta) in comparison.competency_deltas.iter().enumerate() {
+        let row = index as u32 + 1;
+        worksheet
+            .write_string(row, 0, &delta.competency.name)
+            .map_err(cell_error)?;
+        for (col, value) in [delta.base_average, delta.comparison_average, delta.delta]
+            .into_iter()
+            .enumerate()
+        {
+            worksheet
+                .write_number_with_format(row, col as u16 + 1, value, &number_format)
+                .map_err(cell_error)?;
+        }
+    }
+
+    let summary_row = comparison.competency_deltas.len() as u32 + 2;
+    worksheet
+        .write_string_with_format(summary_row, 0, "Average Score", &header_format)
+        .map_err(cell_error)?;
+    worksheet
+        .write_string_with_format(summary_row + 1, 0, "Employees", &header_format)
+        .map_err(cell_error)?;
+    for (col, value) in [
+        comparison.base.average_score,
+        comparison.comparison.average_score,
+        comparison.average_delta,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        worksheet
+            .write_number_with_format(summary_row, col as u16 + 1, value, &number_format)
+            .map_err(cell_error)?;
+    }
+    for (col, value) in [
+        comparison.base.total_employees,
+        comparison.comparison.total_employees,
+        comparison.comparison.total_employees - comparison.base.total_employees,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        worksheet
+            .write_number(summary_row + 1, col as u16 + 1, value as f64)
+            .map_err(cell_error)?;
+    }
+    worksheet
+        .set_column_width(0, 40)
+        .map_err(|e| format!("Failed to size column: {}", e))?;
+
+    workbook
+        .save(file_path)
+        .map_err(|e| format!("Failed to save workbook: {}", e))
+}
+
+fn render_comparison_page(
+    canvas: &mut Canvas<'_>,
+    layout: &PageLayout,
+    title: &str,
+    summary: &[String],
+    rows: &[[String; 4]],
+) -> std::io::Result<()> {
+    let mut cursor_y = layout.top - 40.0;
+    let title = fit_text(canvas, 16.0, title, layout.content_width());
+    canvas.left_text(layout.left, cursor_y, Font::Bold, 16.0, &title)?;
+    cursor_y -= 28.0;
+
+    for line in summary {
+        canvas.left_text(layout.left, cursor_y, Font::Regular, 11.0, line)?;
         cursor_y -= 16.0;
     }
+    cursor_y -= 12.0;
+
+    let right_edges = [layout.right - 165.0, layout.right - 80.0, layout.right];
+    canvas.left_text(layout.left, cursor_y, Font::Bold, 11.0, "Kompetensi")?;
+    for (x, label) in right_edges.iter().zip(["Dasar", "Pembanding", "Selisih"]) {
+        canvas.right_text(*x, cursor_y, Font::Bold, 11.0, label)?;
+    }
+    cursor_y -= 6.0;
+    canvas.line(layout.left, cursor_y, layout.right, cursor_y)?;
+    cursor_y -= 16.0;
+
+    for [name, base, compared, delta] in rows {
+        let label = fit_text(canvas, 10.0, name, layout.content_width() - 255.0);
+        canvas.left_text(layout.left, cursor_y, Font::Regular, 10.0, &label)?;
+        for (x, value) in right_edges.iter().zip([base, compared, delta]) {
+            canvas.right_text(*x, cursor_y, Font::Regular, 10.0, value)?;
+        }
+        cursor_y -= 18.0;
+    }
+
+    Ok(())
+}
+
+/// Per-position averages of the average score, the three section subtotals and the weighted
+/// total, with the number of employees; `None` when no employee has the position.
+fn position_metric_averages(
+    rankings: &[EmployeeRanking],
+    position: PositionType,
+) -> (usize, Option<[f64; 5]>) {
+    let members: Vec<&EmployeeRanking> = rankings
+        .iter()
+        .filter(|ranking| ranking.position_type == position)
+        .collect();
+    if members.is_empty() {
+        return (0, None);
+    }
+    let mut averages = [0.0; 5];
+    for member in &members {
+        let subtotal = |index: usize| member.section_subtotals.get(index).copied().unwrap_or(0.0);
+        let values = [
+            member.average_score,
+            subtotal(0),
+            subtotal(1),
+            subtotal(2),
+            member.weighted_score,
+        ];
+        for (average, value) in averages.iter_mut().zip(values) {
+            *average += value / members.len() as f64;
+        }
+    }
+    (members.len(), Some(averages))
+}
+
+fn analysis_header_format() -> Format {
+    Format::new().set_bold().set_background_color(0xDDDDDD)
+}
+
+/// Workbook for analysing a dataset in a spreadsheet: competency averages, the score
+/// distribution and Eselon against Staff, each sheet with a native chart of its table.
+#[tauri::command]
+pub async fn export_dataset_analysis_xlsx(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    file_path: String,
+) -> Result<(), String> {
+    state.require_role(Role::Admin).await?;
+    let pool = state.pool.clone();
+    let stats = compute_dataset_stats(&pool, dataset_id)
+        .await
+        .map_err(|e| format!("Failed to compute dataset stats: {}", e))?;
+    let rankings = compute_rankings(&pool, dataset_id, Some("weighted")).await?;
+    let text = load_report_settings(&pool).await?.locale.messages();
+    write_analysis_workbook(&stats, &rankings, text, &file_path)
+        .map_err(|e| format!("Failed to write workbook: {}", e))
+}
+
+fn write_analysis_workbook(
+    stats: &DatasetStats,
+    rankings: &[EmployeeRanking],
+    text: &Messages,
+    file_path: &str,
+) -> Result<(), XlsxError> {
+    let mut workbook = Workbook::new();
+    write_competency_sheet(&mut workbook, stats, text)?;
+    write_distribution_sheet(&mut workbook, stats, text)?;
+    write_position_sheet(&mut workbook, rankings, text)?;
+    workbook.save(file_path)
+}
+
+fn write_competency_sheet(
+    workbook: &mut Workbook,
+    stats: &DatasetStats,
+    text: &Messages,
+) -> Result<(), XlsxError> {
+    let sheet = text.analysis_sheets[0];
+    let header_format = analysis_header_format();
+    let number_format = Format::new().set_num_format("0.00");
+    let worksheet = workbook.add_worksheet().set_name(sheet)?;
+    for (col, header) in text.analysis_competency_headings.into_iter().enumerate() {
+        worksheet.write_string_with_format(0, col as u16, header, &header_format)?;
+    }
+    for (index, stat) in stats.competency_stats.iter().enumerate() {
+        let row = index as u32 + 1;
+        worksheet.write_string(row, 0, &stat.competency.name)?;
+        worksheet.write_number_with_format(row, 1, stat.average_score, &number_format)?;
+        worksheet.write_number_with_format(row, 2, stat.std_dev, &number_format)?;
+        worksheet.write_number(row, 3, stat.employee_count as f64)?;
+    }
+    worksheet.set_column_width(0, 40)?;
+    if !stats.competency_stats.is_empty() {
+        let last_row = stats.competency_stats.len() as u32;
+        let mut chart = Chart::new(ChartType::Bar);
+        chart
+            .add_series()
+            .set_name((sheet, 0, 1))
+            .set_categories((sheet, 1, 0, last_row, 0))
+            .set_values((sheet, 1, 1, last_row, 1));
+        chart.title().set_name(text.analysis_chart_titles[0]);
+        chart.legend().set_hidden();
+        chart.set_height(120 + 24 * last_row);
+        worksheet.insert_chart(1, 5, &chart)?;
+    }
+    Ok(())
+}
 
+fn write_distribution_sheet(
+    workbook: &mut Workbook,
+    stats: &DatasetStats,
+    text: &Messages,
+) -> Result<(), XlsxError> {
+    let sheet = text.analysis_sheets[1];
+    let header_format = analysis_header_format();
+    let worksheet = workbook.add_worksheet().set_name(sheet)?;
+    for (col, header) in text.analysis_distribution_headings.into_iter().enumerate() {
+        worksheet.write_string_with_format(0, col as u16, header, &header_format)?;
+    }
+    for (index, bucket) in stats.score_distribution.iter().enumerate() {
+        let row = index as u32 + 1;
+        worksheet.write_string(row, 0, &bucket.range)?;
+        worksheet.write_number(row, 1, bucket.count as f64)?;
+    }
+    if !stats.score_distribution.is_empty() {
+        let last_row = stats.score_distribution.len() as u32;
+        let mut chart = Chart::new(ChartType::Column);
+        chart
+            .add_series()
+            .set_name((sheet, 0, 1))
+            .set_categories((sheet, 1, 0, last_row, 0))
+            .set_values((sheet, 1, 1, last_row, 1));
+        chart.title().set_name(text.analysis_chart_titles[1]);
+        chart.legend().set_hidden();
+        worksheet.insert_chart(1, 3, &chart)?;
+    }
     Ok(())
 }
+
+/// Eselon against Staff: employee counts first, then one row per metric. A position
+/// without employees keeps its metric cells blank.
+fn write_position_sheet(
+    workbook: &mut Workbook,
+    rankings: &[EmployeeRanking],
+    text: &Messages,
+) -> Result<(), XlsxError> {
+    let sheet = text.analysis_sheets[2];
+    let header_format = analysis_header_format();
+    let number_format = Format::new().set_num_format("0.00");
+    let worksheet = workbook.add_worksheet().set_name(sheet)?;
+    let [metric_heading, employees_heading] = text.analysis_position_headings;
+    worksheet.write_string_with_format(0, 0, metric_heading, &header_format)?;
+    worksheet.write_string_with_format(1, 0, employees_heading, &header_format)?;
+    for (row, metric) in text.analysis_metrics.into_iter().enumerate() {
+        worksheet.write_string(row as u32 + 2, 0, metric)?;
+    }
+    for (index, (position, label)) in [PositionType::Eselon, PositionType::Staff]
+        .into_iter()
+        .zip(text.analysis_positions)
+        .enumerate()
+    {
+        let col = index as u16 + 1;
+        let (employees, averages) = position_metric_averages(rankings, position);
+        worksheet.write_string_with_format(0, col, label, &header_format)?;
+        worksheet.write_number(1, col, employees as f64)?;
+        for (row, value) in averages.into_iter().flatten().enumerate() {
+            worksheet.write_number_with_format(row as u32 + 2, col, value, &number_format)?;
+        }
+    }
+    worksheet.set_column_width(0, 24)?;
+    let last_row = text.analysis_metrics.len() as u32 + 1;
+    let mut chart = Chart::new(ChartType::Column);
+    for col in 1..=2 {
+        chart
+            .add_series()
+            .set_name((sheet, 0, col))
+            .set_categories((sheet, 2, 0, last_row, 0))
+            .set_values((sheet, 2, col, last_row, col));
+    }
+    chart.title().set_name(sheet);
+    worksheet.insert_chart(1, 4, &chart)?;
+    Ok(())
+}
+
+/// Writes one .zip with the dataset workbook plus every employee's report and summary PDF,
+/// for handing over a complete evaluation period.
+#[tauri::command]
+pub async fn export_dataset_archive(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    file_path: String,
+    force: Option<bool>,
+) -> Result<(), String> {
+    state.require_role(Role::Admin).await?;
+    let pool = state.pool.clone();
+    let _lock = state
+        .dataset_locks
+        .acquire("archive-export", &[dataset_id])?;
+    assess_dataset(&pool, dataset_id)
+        .await?
+        .ensure_exportable(force.unwrap_or(false))?;
+    write_dataset_archive(&pool, dataset_id, file_path, move |progress| {
+        let _ = app.emit(ARCHIVE_PROGRESS_EVENT, progress);
+    })
+    .await
+}
+
+async fn write_dataset_archive(
+    pool: &SqlitePool,
+    dataset_id: i64,
+    file_path: String,
+    on_progress: impl Fn(ArchiveProgress) + Send + Sync + 'static,
+) -> Result<(), String> {
+    let export_data = collect_dataset_data(pool, dataset_id)
+        .await
+        .map_err(|e| format!("Failed to collect dataset: {}", e))?;
+    let settings = load_report_settings(pool).await?;
+
+    // PDFs and the workbook are rendered to files first, then packed
+    let work_dir = std::env::temp_dir().join(format!(
+        "epa-archive-{}-{}",
+        dataset_id,
+        chrono::Utc::now().timestamp_millis()
+    ));
+    std::fs::create_dir_all(&work_dir)
+        .map_err(|e| format!("Failed to create working directory: {}", e))?;
+    let result =
+        match write_archive_parts(pool, export_data, settings, &work_dir, on_progress).await {
+            Ok(parts) => run_blocking(move || Ok(pack_archive(&parts, &file_path)?))
+                .await
+                .map_err(String::from),
+            Err(error) => Err(error),
+        };
+    let _ = std::fs::remove_dir_all(&work_dir);
+    result
+}
+
+/// One employee's report and summary, with everything needed to render them.
+struct ArchiveEmployee {
+    name: String,
+    report: EmployeeReportContext,
+    performance: EmployeePerformance,
+    summary: String,
+    report_path: PathBuf,
+    summary_path: PathBuf,
+}
+
+/// Renders the archive members into `work_dir`; returns (name in archive, file on disk).
+/// Employee documents are loaded up front, then the workbook and PDFs are rendered off the
+/// async runtime, the PDFs on several threads at once.
+async fn write_archive_parts(
+    pool: &SqlitePool,
+    data: DatasetExportData,
+    settings: ReportSettings,
+    work_dir: &Path,
+    on_progress: impl Fn(ArchiveProgress) + Send + Sync + 'static,
+) -> Result<Vec<(String, PathBuf)>, String> {
+    let dataset_name = &data.dataset.name;
+    let workbook_name = format!("{}.xlsx", sanitize_file_name(dataset_name));
+    let workbook_path = work_dir.join("dataset.xlsx");
+
+    let mut performances: HashMap<i64, EmployeePerformance> =
+        compute_dataset_performances(pool, data.dataset.id)
+            .await
+            .map_err(|e| format!("Failed to load employee performance: {}", e))?
+            .into_iter()
+            .map(|performance| (performance.employee.id, performance))
+            .collect();
+    let performances: Vec<EmployeePerformance> = data
+        .employees
+        .iter()
+        .filter_map(|employee| performances.remove(&employee.id))
+        .collect();
+    let reports = load_report_contexts(pool, &data.dataset, &performances).await?;
+    let summaries =
+        summary_export_contents(pool, &performances, data.dataset.id, settings.locale).await?;
+
+    let mut used_names = HashSet::new();
+    let mut employees = Vec::with_capacity(performances.len());
+    for ((performance, report), summary) in performances.into_iter().zip(reports).zip(summaries) {
+        let employee = &performance.employee;
+        let mut name = render_file_name(&settings.filename_template, employee, dataset_name, "pdf");
+        if !used_names.insert(name.clone()) {
+            name = format!("{}_{}.pdf", name.trim_end_matches(".pdf"), employee.id);
+            used_names.insert(name.clone());
+        }
+        employees.push(ArchiveEmployee {
+            name,
+            report_path: work_dir.join(format!("report-{}.pdf", employee.id)),
+            summary_path: work_dir.join(format!("summary-{}.pdf", employee.id)),
+            report,
+            performance,
+            summary,
+        });
+    }
+
+    let dataset_id = data.dataset.id;
+    let workbook_file = workbook_path.clone();
+    let employees = tauri::async_runtime::spawn_blocking(move || {
+        export_xlsx(
+            &data,
+            &workbook_file.to_string_lossy(),
+            &ExportTracker::silent(dataset_id),
+        )?;
+        let total = employees.len();
+        render_all(
+            &employees,
+            |employee| {
+                let employee_name = &employee.performance.employee.name;
+                render_report_pdf(
+                    &employee.report,
+                    &settings,
+                    &employee.report_path.to_string_lossy(),
+                )
+                .map_err(|e| format!("Failed to export report for {}: {}", employee_name, e))?;
+                write_summary_pdf(
+                    &employee.performance,
+                    &employee.summary,
+                    &settings,
+                    employee.summary_path.to_string_lossy().to_string(),
+                )
+                .map_err(|e| format!("Failed to export summary for {}: {}", employee_name, e))
+            },
+            |employee, completed| {
+                on_progress(ArchiveProgress {
+                    dataset_id,
+                    completed,
+                    total,
+                    employee_name: employee.performance.employee.name.clone(),
+                })
+            },
+        )
+        .map(|()| employees)
+    })
+    .await
+    .map_err(|e| format!("Archive rendering failed: {}", e))??;
+
+    let mut parts = vec![(workbook_name, workbook_path)];
+    for employee in employees {
+        parts.push((format!("laporan/{}", employee.name), employee.report_path));
+        parts.push((
+            format!("ringkasan/{}", employee.name),
+            employee.summary_path,
+        ));
+    }
+    Ok(parts)
+}
+
+/// A score as stored, without normalization or the anonymity floor.
+#[derive(Debug, Serialize, FromRow)]
+struct PackageScore {
+    competency: String,
+    raw_value: String,
+    numeric_value: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+struct PackageDataset {
+    dataset: Dataset,
+    scores: Vec<PackageScore>,
+    summary: Option<Summary>,
+    notes: Vec<EmployeeNote>,
+}
+
+/// `data.json` of an employee data package.
+#[derive(Debug, Serialize)]
+struct EmployeePackage {
+    exported_at: DateTime<Utc>,
+    employee: Employee,
+    datasets: Vec<PackageDataset>,
+    /// Every stored row that references the employee, by table: attendance, leadership
+    /// scores, rater entries, attachments, summary revisions and whatever is added later.
+    records: BTreeMap<String, Vec<serde_json::Value>>,
+}
+
+/// A dataset's report and summary, loaded so they can be rendered off the async runtime.
+struct PackageDocuments {
+    name: String,
+    dataset_id: i64,
+    report: EmployeeReportContext,
+    performance: EmployeePerformance,
+    summary: String,
+}
+
+/// Zips everything held about one employee: `data.json` with their master record and every
+/// row that references them, each dataset's report and summary PDF, their photo and their
+/// attachments. For personal data requests and transfers out.
+#[tauri::command]
+pub async fn export_employee_data_package(
+    state: State<'_, AppState>,
+    employee_id: i64,
+    file_path: String,
+) -> Result<(), String> {
+    state.require_role(Role::Admin).await?;
+    write_employee_data_package(&state.pool, employee_id, file_path).await
+}
+
+async fn write_employee_data_package(
+    pool: &SqlitePool,
+    employee_id: i64,
+    file_path: String,
+) -> Result<(), String> {
+    let employee = sqlx::query_as::<_, Employee>("SELECT * FROM employees WHERE id = ?")
+        .bind(employee_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to load employee: {}", e))?
+        .ok_or_else(|| "Employee not found".to_string())?;
+    let settings = load_report_settings(pool).await?;
+    let (package, documents, mut files) = load_package(pool, employee, &settings).await?;
+    if let Some(photo) = photo_file(pool, employee_id).await? {
+        files.push(("foto.jpg".to_string(), photo));
+    }
+
+    let work_dir = std::env::temp_dir().join(format!(
+        "epa-package-{}-{}",
+        employee_id,
+        chrono::Utc::now().timestamp_millis()
+    ));
+    std::fs::create_dir_all(&work_dir)
+        .map_err(|e| format!("Failed to create working directory: {}", e))?;
+    let archive_dir = work_dir.clone();
+    let result = run_blocking(move || {
+        let mut parts = render_package_parts(&package, &documents, &settings, &archive_dir)?;
+        parts.extend(files);
+        Ok(pack_archive(&parts, &file_path)?)
+    })
+    .await;
+    let _ = std::fs::remove_dir_all(&work_dir);
+    result?;
+
+    record_audit(
+        pool,
+        "employee_data_exported",
+        None,
+        &serde_json::json!({ "employee_id": employee_id }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))
+}
+
+/// Rows of every table with a foreign key to the employee, as JSON objects.
+async fn employee_records(
+    pool: &SqlitePool,
+    employee_id: i64,
+) -> Result<BTreeMap<String, Vec<serde_json::Value>>, String> {
+    let mut conn = pool
+        .acquire()
+        .await
+        .map_err(|e| format!("Failed to open connection: {}", e))?;
+    let mut references: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (table, column) in employee_references(&mut conn).await? {
+        references.entry(table).or_default().push(column);
+    }
+
+    let mut records = BTreeMap::new();
+    for (table, columns) in references {
+        let names: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_info(?)")
+            .bind(&table)
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to read columns of {}: {}", table, e))?;
+        // JSON cannot hold blobs, so they are written as hex
+        let fields: Vec<String> = names
+            .iter()
+            .map(|name| {
+                format!(
+                    "'{0}', CASE typeof(\"{0}\") WHEN 'blob' THEN hex(\"{0}\") ELSE \"{0}\" END",
+                    name
+                )
+            })
+            .collect();
+        let filter: Vec<String> = columns
+            .iter()
+            .map(|column| format!("\"{}\" = ?1", column))
+            .collect();
+        let rows: Vec<String> = sqlx::query_scalar(&format!(
+            "SELECT json_object({}) FROM \"{}\" WHERE {}",
+            fields.join(", "),
+            table,
+            filter.join(" OR ")
+        ))
+        .bind(employee_id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to load {}: {}", table, e))?;
+        if rows.is_empty() {
+            continue;
+        }
+        let rows = rows
+            .iter()
+            .map(|row| serde_json::from_str(row))
+            .collect::<Result<Vec<serde_json::Value>, _>>()
+            .map_err(|e| format!("Failed to read {}: {}", table, e))?;
+        records.insert(table, rows);
+    }
+    Ok(records)
+}
+
+/// The package data, the documents to render per dataset and the attachment files.
+async fn load_package(
+    pool: &SqlitePool,
+    employee: Employee,
+    settings: &ReportSettings,
+) -> Result<
+    (
+        EmployeePackage,
+        Vec<PackageDocuments>,
+        Vec<(String, PathBuf)>,
+    ),
+    String,
+> {
+    // Scores can outlive the dataset link, so datasets are found through either
+    let datasets = sqlx::query_as::<_, Dataset>(
+        "SELECT d.* FROM datasets d
+         WHERE d.id IN (SELECT dataset_id FROM dataset_employees WHERE employee_id = ?1
+                        UNION SELECT dataset_id FROM scores WHERE employee_id = ?1)
+         ORDER BY d.created_at, d.id",
+    )
+    .bind(employee.id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to load datasets: {}", e))?;
+    let linked: HashSet<i64> =
+        sqlx::query_scalar("SELECT dataset_id FROM dataset_employees WHERE employee_id = ?")
+            .bind(employee.id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Failed to load datasets: {}", e))?
+            .into_iter()
+            .collect();
+
+    let mut documents = Vec::with_capacity(datasets.len());
+    let mut package_datasets = Vec::with_capacity(datasets.len());
+    for dataset in datasets {
+        let scores = sqlx::query_as::<_, PackageScore>(
+            "SELECT c.name AS competency, s.raw_value, s.numeric_value
+             FROM scores s
+             JOIN competencies c ON c.id = s.competency_id
+             WHERE s.dataset_id = ? AND s.employee_id = ?
+             ORDER BY c.display_order, c.name",
+        )
+        .bind(dataset.id)
+        .bind(employee.id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to load scores: {}", e))?;
+        let summary = load_summary(pool, employee.id, dataset.id)
+            .await
+            .map_err(|e| format!("Failed to load summary: {}", e))?;
+        let notes = fetch_employee_notes(pool, dataset.id, employee.id)
+            .await
+            .map_err(|e| format!("Failed to load employee notes: {}", e))?;
+
+        // Reports are built from the dataset link; unlinked scores are in data.json only
+        if linked.contains(&dataset.id) {
+            let report = load_report_context(pool, dataset.id, employee.id).await?;
+            let (performance, summary_content) =
+                load_summary_export(pool, dataset.id, employee.id, settings.locale).await?;
+            documents.push(PackageDocuments {
+                name: dataset.name.clone(),
+                dataset_id: dataset.id,
+                report,
+                performance,
+                summary: summary_content,
+            });
+        }
+        package_datasets.push(PackageDataset {
+            dataset,
+            scores,
+            summary,
+            notes,
+        });
+    }
+
+    let mut files = Vec::new();
+    for attachment in list_attachments(pool, employee.id).await? {
+        files.push((
+            format!("lampiran/{}_{}", attachment.id, attachment.filename),
+            attachment_path(pool, attachment.id).await?,
+        ));
+    }
+
+    let package = EmployeePackage {
+        exported_at: Utc::now(),
+        records: employee_records(pool, employee.id).await?,
+        employee,
+        datasets: package_datasets,
+    };
+    Ok((package, documents, files))
+}
+
+/// Writes `data.json` and the PDFs into `work_dir`; returns (name in archive, file on disk).
+fn render_package_parts(
+    package: &EmployeePackage,
+    documents: &[PackageDocuments],
+    settings: &ReportSettings,
+    work_dir: &Path,
+) -> Result<Vec<(String, PathBuf)>, String> {
+    let data_path = work_dir.join("data.json");
+    let json = serde_json::to_vec_pretty(package)
+        .map_err(|e| format!("Failed to serialize employee data: {}", e))?;
+    std::fs::write(&data_path, json)
+        .map_err(|e| format!("Failed to write employee data: {}", e))?;
+    let mut parts = vec![("data.json".to_string(), data_path)];
+
+    for document in documents {
+        // Dataset names need not be unique, so the id keeps the file names apart
+        let name = format!(
+            "{}_{}.pdf",
+            document.dataset_id,
+            sanitize_file_name(&document.name)
+        );
+        let report_path = work_dir.join(format!("report-{}.pdf", document.dataset_id));
+        render_report_pdf(&document.report, settings, &report_path.to_string_lossy())
+            .map_err(|e| format!("Failed to export report for {}: {}", document.name, e))?;
+        parts.push((format!("laporan/{}", name), report_path));
+
+        let summary_path = work_dir.join(format!("summary-{}.pdf", document.dataset_id));
+        write_summary_pdf(
+            &document.performance,
+            &document.summary,
+            settings,
+            summary_path.to_string_lossy().to_string(),
+        )
+        .map_err(|e| format!("Failed to export summary for {}: {}", document.name, e))?;
+        parts.push((format!("ringkasan/{}", name), summary_path));
+    }
+    Ok(parts)
+}
+
+/// Zips `parts` into `file_path`; a partly written archive is removed again on failure.
+fn pack_archive(parts: &[(String, PathBuf)], file_path: &str) -> Result<(), String> {
+    let file =
+        std::fs::File::create(file_path).map_err(|e| format!("Failed to create archive: {}", e))?;
+    let result = write_archive(file, parts);
+    if result.is_err() {
+        let _ = std::fs::remove_file(file_path);
+    }
+    result
+}
+
+fn write_archive(file: std::fs::File, parts: &[(String, PathBuf)]) -> Result<(), String> {
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, path) in parts {
+        let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", name, e))?;
+        zip.start_file(name.as_str(), options)
+            .and_then(|_| zip.write_all(&bytes).map_err(Into::into))
+            .map_err(|e| format!("Failed to add {} to archive: {}", name, e))?;
+    }
+    zip.finish()
+        .map_err(|e| format!("Failed to write archive: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{seed_datasets, seed_employees, seed_members, test_pool};
+
+    #[test]
+    fn test_pseudonyms_follow_employee_ids() {
+        let pseudonyms = employee_pseudonyms([42, 7, 42, 19]);
+        assert_eq!(pseudonyms.len(), 3);
+        assert_eq!(pseudonyms[&7], "E-001");
+        assert_eq!(pseudonyms[&42], "E-003");
+
+        let mut employee: Employee = serde_json::from_value(serde_json::json!({
+            "id": 19,
+            "name": "Budi Santoso",
+            "nip": "198501012010011001",
+            "gol": "III/a",
+            "jabatan": "Pelaksana",
+            "sub_jabatan": null,
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z"
+        }))
+        .unwrap();
+        pseudonymize(&mut employee, &pseudonyms);
+        assert_eq!(employee.name, "E-002");
+        assert_eq!(employee.nip, None);
+        assert_eq!(employee.jabatan.as_deref(), Some("Pelaksana"));
+    }
+
+    #[test]
+    fn test_position_sheet_is_localized_and_leaves_empty_positions_blank() {
+        use calamine::{open_workbook_auto, Data, Reader};
+
+        let employee: Employee = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "name": "Budi Santoso",
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z"
+        }))
+        .unwrap();
+        let rankings = vec![EmployeeRanking {
+            rank: 1,
+            employee,
+            position_type: PositionType::Eselon,
+            score: 88.0,
+            average_score: 3.5,
+            weighted_score: 88.0,
+            section_subtotals: vec![20.0, 50.0, 18.0],
+            percentile: 100.0,
+            rating: "Sangat Baik".to_string(),
+        }];
+        let text = crate::i18n::Locale::Id.messages();
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("epa-analysis.xlsx");
+        let mut workbook = Workbook::new();
+        write_position_sheet(&mut workbook, &rankings, text).unwrap();
+        workbook.save(&path).unwrap();
+
+        let mut saved = open_workbook_auto(&path).unwrap();
+        let range = saved.worksheet_range(text.analysis_sheets[2]).unwrap();
+        assert_eq!(
+            range.get_value((2, 0)),
+            Some(&Data::String("Rata-rata Skor".into()))
+        );
+        assert_eq!(range.get_value((1, 1)), Some(&Data::Float(1.0)));
+        assert_eq!(range.get_value((6, 1)), Some(&Data::Float(88.0)));
+        // No Staff employee: counted as zero, metrics left blank
+        assert_eq!(range.get_value((1, 2)), Some(&Data::Float(0.0)));
+        assert!(matches!(range.get_value((2, 2)), None | Some(Data::Empty)));
+    }
+
+    #[test]
+    fn test_dataset_notes_wrap_to_the_page_width() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("epa-notes.pdf");
+        let document = Pdf::create(&path.to_string_lossy()).unwrap();
+        let note: DatasetNote = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "dataset_id": 1,
+            "content": "Penilaian semester ini diulang untuk bidang perlindungan sosial karena \
+                formulir yang pertama tidak memuat pertanyaan tentang ketepatan waktu pegawai",
+            "created_at": "2024-03-01T08:30:00Z",
+            "updated_at": "2024-03-01T08:30:00Z"
+        }))
+        .unwrap();
+        let lines = dataset_note_lines(&document, &[note], 200.0);
+
+        assert!(lines.len() > 1);
+        assert!(lines[0].starts_with("01/03/2024 08:30 - Penilaian"));
+        for line in &lines {
+            assert_eq!(
+                document.wrap_to_width(Font::Regular, NOTE_FONT_SIZE, line, 200.0),
+                vec![line.clone()]
+            );
+        }
+    }
+
+    #[test]
+    fn test_cancel_stops_only_its_own_export() {
+        let cancels = ExportCancels::default();
+        // Sent before the export registered, so it must still stop it
+        cancels.cancel("first").unwrap();
+        let first = cancels.register("first").unwrap();
+        let second = cancels.register("second").unwrap();
+
+        let first_tracker = ExportTracker::new(None, &first, 1, 10);
+        let second_tracker = ExportTracker::new(None, &second, 1, 10);
+        assert!(matches!(
+            first_tracker.employees_written(1),
+            Err(ExportError::Cancelled)
+        ));
+        assert!(second_tracker.employees_written(1).is_ok());
+
+        // A finished export forgets its token, so the id can be used again
+        drop(first);
+        let again = cancels.register("first").unwrap();
+        assert!(ExportTracker::new(None, &again, 1, 10)
+            .employees_written(1)
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_export_stops_at_the_next_employee() {
+        let pool = test_pool().await;
+        seed_datasets(&pool, &["Ganjil 2024"]).await;
+        seed_employees(&pool, &["Budi"]).await;
+        seed_members(&pool, 1, &[1]).await;
+        let data = collect_dataset_data(&pool, 1).await.unwrap();
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("epa-export.csv");
+        let path = path.to_string_lossy();
+
+        let cancels = ExportCancels::default();
+        let registration = cancels.register("export").unwrap();
+        let tracker = ExportTracker::new(None, &registration, 1, data.employees.len());
+        assert!(export_csv(&data, &path, &tracker).is_ok());
+        cancels.cancel("export").unwrap();
+        let result = export_csv(&data, &path, &tracker);
+        assert!(matches!(result, Err(ExportError::Cancelled)));
+    }
+
+    #[tokio::test]
+    async fn test_employee_package_holds_every_employee_table() {
+        let directory = tempfile::tempdir().unwrap();
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(&format!(
+                "sqlite:{}?mode=rwc",
+                directory.path().join("epa.db").display()
+            ))
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        // Also scored in dataset 1, which the employee is no longer linked to
+        sqlx::query(
+            "INSERT INTO datasets (id, name, created_at, updated_at) VALUES
+                (1, 'Ganjil 2024', datetime('now'), datetime('now')),
+                (2, 'Genap 2024', datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, created_at, updated_at)
+                VALUES (1, 'Budi', datetime('now'), datetime('now'));
+             INSERT INTO dataset_employees (dataset_id, employee_id, created_at, updated_at)
+                VALUES (2, 1, datetime('now'), datetime('now'));
+             INSERT INTO competencies (id, name, display_order) VALUES (1, 'Kerjasama', 0);
+             INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, created_at)
+                VALUES (1, 1, 1, 'Baik', 75, datetime('now')),
+                       (1, 2, 1, 'Sangat Baik', 85, datetime('now'));
+             INSERT INTO attendance (employee_id, month, working_days, present_days)
+                VALUES (1, '2024-01', 22, 20);
+             INSERT INTO leadership_scores (dataset_id, employee_id, score) VALUES (1, 1, 80);",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let source = directory.path().join("SK.txt");
+        std::fs::write(&source, "surat keputusan").unwrap();
+        crate::attachments::add_attachment(&pool, 1, &source)
+            .await
+            .unwrap();
+
+        let archive_path = directory.path().join("paket.zip");
+        write_employee_data_package(&pool, 1, archive_path.to_string_lossy().to_string())
+            .await
+            .unwrap();
+        let mut archive =
+            zip::ZipArchive::new(std::fs::File::open(&archive_path).unwrap()).unwrap();
+        let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        let mut json = String::new();
+        std::io::Read::read_to_string(&mut archive.by_name("data.json").unwrap(), &mut json)
+            .unwrap();
+        pool.close().await;
+
+        assert!(names.contains(&"laporan/2_Genap 2024.pdf".to_string()));
+        assert!(names.iter().any(|name| name.starts_with("lampiran/")));
+        let package: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(package["datasets"][0]["scores"][0]["raw_value"], "Baik");
+        assert_eq!(package["records"]["scores"].as_array().unwrap().len(), 2);
+        for table in ["attendance", "leadership_scores", "attachments"] {
+            assert_eq!(
+                package["records"][table].as_array().unwrap().len(),
+                1,
+                "{}",
+                table
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dataset_archive_holds_the_workbook_and_every_employee_document() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO datasets (id, name, created_at, updated_at)
+                VALUES (1, 'Ganjil 2024', datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, created_at, updated_at) VALUES
+                (1, 'Budi', datetime('now'), datetime('now')),
+                (2, 'Sari', datetime('now'), datetime('now'));
+             INSERT INTO dataset_employees (dataset_id, employee_id, created_at, updated_at) VALUES
+                (1, 1, datetime('now'), datetime('now')), (1, 2, datetime('now'), datetime('now'));
+             INSERT INTO competencies (id, name, display_order) VALUES (1, 'Kerjasama', 0);
+             INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, created_at)
+                VALUES (1, 1, 1, 'Baik', 75, datetime('now')),
+                       (2, 1, 1, 'Sangat Baik', 85, datetime('now'));",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let directory = tempfile::tempdir().unwrap();
+        let archive_path = directory.path().join("epa-dataset-archive.zip");
+        let progress = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = Arc::clone(&progress);
+
+        write_dataset_archive(
+            &pool,
+            1,
+            archive_path.to_string_lossy().to_string(),
+            move |_| {
+                counter.fetch_add(1, Ordering::Relaxed);
+            },
+        )
+        .await
+        .unwrap();
+        let archive = zip::ZipArchive::new(std::fs::File::open(&archive_path).unwrap()).unwrap();
+        let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+
+        assert_eq!(progress.load(Ordering::Relaxed), 2);
+        assert_eq!(names.len(), 5);
+        assert!(names.contains(&"Ganjil 2024.xlsx".to_string()));
+        assert_eq!(
+            names
+                .iter()
+                .filter(|name| name.starts_with("laporan/") || name.starts_with("ringkasan/"))
+                .count(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_failed_archive_is_removed() {
+        let directory = tempfile::tempdir().unwrap();
+        let archive_path = directory.path().join("epa-failed-archive.zip");
+        let missing = directory.path().join("epa-missing-part.pdf");
+
+        let result = pack_archive(
+            &[("laporan/Budi.pdf".to_string(), missing)],
+            &archive_path.to_string_lossy(),
+        );
+
+        assert!(result.is_err());
+        assert!(!archive_path.exists());
+    }
+}
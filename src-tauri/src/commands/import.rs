@@ -1,8 +1,32 @@
-use crate::csv_parser::{ParsedEmployee, ParsedScore};
-use crate::db::models::{Competency, CreateRatingMapping, Dataset, Employee};
+use crate::auth::Role;
+use crate::commands::csv::read_options;
+use crate::commands::dataset::normalize_period;
+use crate::commands::employee::{find_master_employee, load_nip_index, normalize_nip};
+use crate::commands::import_presets::{load_import_preset, FieldMapping};
+use crate::commands::metrics::timed_command;
+use crate::commands::org_structure::{
+    is_canonical_jabatan, load_canonical_jabatan, suggest_jabatan,
+};
+use crate::csv_parser::formats::{aggregate_respondents, SourceTable};
+use crate::csv_parser::{
+    rating_value, CsvParseError, CsvParser, ParsedEmployee, ParsedScore, ReadOptions,
+    RespondentAggregation,
+};
+use crate::db::competency_aliases::{apply_question_number, record_alias, resolve_competency};
+use crate::db::models::{CreateRatingMapping, Dataset, Employee, ImportRun};
+use crate::db::score_entries::{
+    combine_entries, load_score_aggregation, refresh_aggregated_scores,
+};
+use crate::db::validation::record_validation_issue;
+use crate::webhooks::{self, WebhookPayload, IMPORT_COMPLETED};
 use crate::AppState;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::{QueryBuilder, Sqlite, SqlitePool, Transaction};
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Instant;
 use tauri::State;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -22,9 +46,63 @@ pub struct PerformanceImportRequest {
     pub dataset_name: String,
     pub dataset_description: Option<String>,
     pub source_file: String,
+    pub period_year: Option<i32>,
+    pub period_label: Option<String>,
     pub employee_names: Vec<String>,
+    #[serde(default)]
     pub scores: Vec<ParsedScore>,
+    /// Score file parsed and staged on the backend instead of sending `scores`.
+    #[serde(default)]
+    pub scores_file: Option<String>,
+    /// `mean`, `median` or `latest`: combines the respondent rows of a Google Forms
+    /// `scores_file` per employee and question.
+    #[serde(default)]
+    pub aggregation: Option<String>,
+    /// Delimiter and encoding of `scores_file` chosen in the preview; detected when empty.
+    #[serde(default)]
+    pub delimiter: Option<String>,
+    #[serde(default)]
+    pub encoding: Option<String>,
+    #[serde(default)]
     pub rating_mappings: Vec<CreateRatingMapping>,
+    /// Who gave these scores; defaults to the source file name. Each rater keeps their own
+    /// entry per score, and the score becomes the dataset's aggregation of them.
+    #[serde(default)]
+    pub rater: Option<String>,
+    /// Run the import and roll it back, returning only what would change.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Result of `validate_import_data`, stored with the import run.
+    #[serde(default)]
+    pub validation_stats: Option<ValidationStats>,
+    /// Saved import preset; its delimiter and column mapping read `scores_file`, its rating
+    /// mappings are used when the request has none, and its score scale is set on the new
+    /// dataset.
+    #[serde(default)]
+    pub preset_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportChanges {
+    pub new_scores: usize,
+    pub updated_scores: usize,
+    pub new_competencies: usize,
+    /// Employees that were not linked to the dataset before this import.
+    pub newly_linked_employees: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportMetrics {
+    /// Time spent normalising and validating the payload before touching the database.
+    pub parse_ms: u64,
+    /// Time spent inside the database transaction, commit included.
+    pub db_ms: u64,
+    pub total_ms: u64,
+    pub rows_per_sec: f64,
+    /// Payload rows ignored because they carried no usable employee name.
+    pub skipped_rows: usize,
+    /// Score rows that replaced an existing score for the same employee and competency.
+    pub overwritten_rows: usize,
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -33,19 +111,42 @@ pub struct ImportResult {
     pub employee_count: usize,
     pub competency_count: usize,
     pub score_count: usize,
+    pub metrics: ImportMetrics,
+    pub changes: ImportChanges,
+    /// When true nothing was persisted; `dataset` describes the dataset that would be written.
+    pub dry_run: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PerformanceAppendRequest {
     pub dataset_id: i64,
+    #[serde(default)]
+    pub source_file: Option<String>,
     pub employee_names: Vec<String>,
+    #[serde(default)]
     pub scores: Vec<ParsedScore>,
+    #[serde(default)]
+    pub scores_file: Option<String>,
+    #[serde(default)]
+    pub aggregation: Option<String>,
+    #[serde(default)]
+    pub delimiter: Option<String>,
+    #[serde(default)]
+    pub encoding: Option<String>,
     pub rating_mappings: Vec<CreateRatingMapping>,
+    #[serde(default)]
+    pub rater: Option<String>,
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default)]
+    pub validation_stats: Option<ValidationStats>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DatasetEmployeeAppendRequest {
     pub dataset_id: i64,
+    #[serde(default)]
+    pub source_file: Option<String>,
     pub employees: Vec<ParsedEmployee>,
 }
 
@@ -87,6 +188,15 @@ pub struct BlankEmployeeNameIssue {
     pub employee_index: usize,
 }
 
+/// Jabatan not found in the imported SOTK reference list; reported as a warning.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UnknownJabatanIssue {
+    pub employee_index: usize,
+    pub employee_name: String,
+    pub jabatan: String,
+    pub suggestion: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ValidationStats {
     pub error_count: usize,
@@ -102,12 +212,23 @@ pub struct ImportValidationSummary {
     pub orphan_scores: Vec<OrphanScoreIssue>,
     pub unmapped_ratings: Vec<UnmappedRatingIssue>,
     pub blank_employee_names: Vec<BlankEmployeeNameIssue>,
+    pub unknown_jabatan: Vec<UnknownJabatanIssue>,
 }
 
 fn normalize_name(name: &str) -> String {
     name.trim().to_lowercase()
 }
 
+/// Key that groups rows describing the same person: the NIP when there is one, else the name.
+fn employee_identity_key(nip: Option<&str>, normalized_name: &str) -> String {
+    match normalize_nip(nip) {
+        Some(nip) => format!("nip:{}", nip),
+        None => format!("name:{}", normalized_name),
+    }
+}
+
+/// Numeric value of a raw rating: its mapping, or the value itself when it is a plain
+/// number such as an averaged form answer.
 fn sanitize_optional(value: &Option<String>) -> Option<String> {
     value
         .as_ref()
@@ -116,11 +237,790 @@ fn sanitize_optional(value: &Option<String>) -> Option<String> {
         .map(|v| v.to_string())
 }
 
+fn elapsed_ms(start: Instant) -> u64 {
+    start.elapsed().as_millis() as u64
+}
+
+/// Scores staged per insert; file imports never hold more than one batch in memory.
+const STAGING_BATCH_SIZE: usize = 2_000;
+/// Rater of imports with no rater, file name or preset to tell them apart.
+const UNNAMED_IMPORT_RATER: &str = "import";
+
+struct PreparedPerformance<'a> {
+    /// Normalised name paired with the first display spelling seen in the payload.
+    employees: Vec<(String, String)>,
+    scores: &'a [ParsedScore],
+}
+
+/// Where the scores of a performance import come from.
+enum ScoreSource<'a> {
+    Payload(&'a [ParsedScore]),
+    /// Source file parsed on the backend, row by row unless respondents are aggregated.
+    File {
+        path: &'a Path,
+        options: ReadOptions,
+        /// A preset's column mapping, applied to the file's headers
+        columns: &'a [FieldMapping],
+        aggregation: Option<RespondentAggregation>,
+    },
+    /// Table already read on the backend, e.g. one sheet of a workbook.
+    Table(&'a SourceTable),
+}
+
+fn score_source<'a>(
+    scores: &'a [ParsedScore],
+    scores_file: Option<&'a str>,
+    aggregation: Option<&str>,
+    options: ReadOptions,
+) -> Result<ScoreSource<'a>, String> {
+    match scores_file.map(str::trim).filter(|path| !path.is_empty()) {
+        Some(_) if !scores.is_empty() => {
+            Err("Send either scores or scores_file, not both".to_string())
+        }
+        Some(path) => Ok(ScoreSource::File {
+            path: Path::new(path),
+            options,
+            columns: &[],
+            aggregation: aggregation
+                .map(RespondentAggregation::from_str)
+                .transpose()?,
+        }),
+        None => Ok(ScoreSource::Payload(scores)),
+    }
+}
+
+struct PerformanceWriteStats {
+    employee_count: usize,
+    competency_count: usize,
+    score_count: usize,
+    overwritten_rows: usize,
+    new_competencies: usize,
+    newly_linked_employees: usize,
+}
+
+/// Unique listed employees, normalised name first, and the number of blank names skipped.
+fn listed_employees(employee_names: &[String]) -> (Vec<(String, String)>, usize) {
+    let mut employees: Vec<(String, String)> = Vec::new();
+    let mut seen_employees: HashSet<String> = HashSet::new();
+    let mut skipped_rows = 0usize;
+
+    for name in employee_names {
+        let trimmed = name.trim();
+        if trimmed.is_empty() {
+            skipped_rows += 1;
+            continue;
+        }
+        let normalized = normalize_name(trimmed);
+        if seen_employees.insert(normalized.clone()) {
+            employees.push((normalized, trimmed.to_string()));
+        }
+    }
+
+    (employees, skipped_rows)
+}
+
+fn prepare_performance_rows<'a>(
+    employee_names: &[String],
+    scores: &'a [ParsedScore],
+) -> Result<PreparedPerformance<'a>, String> {
+    let (mut employees, _) = listed_employees(employee_names);
+    let mut seen_employees: HashSet<String> = employees
+        .iter()
+        .map(|(normalized, _)| normalized.clone())
+        .collect();
+
+    for score in scores {
+        let trimmed = score.employee_name.trim();
+        if trimmed.is_empty() {
+            return Err("Score is associated with a blank employee name".to_string());
+        }
+        let normalized = normalize_name(trimmed);
+        if seen_employees.insert(normalized.clone()) {
+            employees.push((normalized, trimmed.to_string()));
+        }
+    }
+
+    Ok(PreparedPerformance { employees, scores })
+}
+
+async fn stage_scores(
+    tx: &mut Transaction<'_, Sqlite>,
+    rating_map: &HashMap<String, f64>,
+    scores: &[ParsedScore],
+) -> Result<(), String> {
+    for batch in scores.chunks(STAGING_BATCH_SIZE) {
+        if batch
+            .iter()
+            .any(|score| score.employee_name.trim().is_empty())
+        {
+            return Err("Score is associated with a blank employee name".to_string());
+        }
+
+        let mut query = QueryBuilder::<Sqlite>::new(
+            "INSERT INTO import_staging (employee_name, normalized_name, competency, ordinal, raw_value, numeric_value, submitted_at, respondents, source_row, source_column) ",
+        );
+        query.push_values(batch, |mut row, score| {
+            row.push_bind(score.employee_name.trim())
+                .push_bind(normalize_name(&score.employee_name))
+                .push_bind(&score.competency)
+                .push_bind(score.ordinal)
+                .push_bind(&score.value)
+                // Apply rating mapping if available
+                .push_bind(rating_value(rating_map, &score.value))
+                .push_bind(
+                    score
+                        .submitted_at
+                        .as_deref()
+                        .and_then(CsvParser::parse_timestamp),
+                )
+                .push_bind(
+                    score
+                        .respondent_count
+                        .unwrap_or(i64::from(score.submitted_at.is_some())),
+                )
+                .push_bind(score.source_row)
+                .push_bind(&score.source_column);
+        });
+        query
+            .build()
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| format!("Failed to stage scores: {}", e))?;
+    }
+    Ok(())
+}
+
+async fn stage_score_rows(
+    tx: &mut Transaction<'_, Sqlite>,
+    rating_map: &HashMap<String, f64>,
+    rows: impl Iterator<Item = Result<Vec<ParsedScore>, CsvParseError>>,
+) -> Result<(), String> {
+    let mut batch: Vec<ParsedScore> = Vec::with_capacity(STAGING_BATCH_SIZE);
+    for row in rows {
+        batch.extend(row.map_err(|e| format!("Failed to read score file: {}", e))?);
+        if batch.len() >= STAGING_BATCH_SIZE {
+            stage_scores(tx, rating_map, &batch).await?;
+            batch.clear();
+        }
+    }
+    stage_scores(tx, rating_map, &batch).await
+}
+
+async fn stage_table(
+    tx: &mut Transaction<'_, Sqlite>,
+    rating_map: &HashMap<String, f64>,
+    table: &SourceTable,
+) -> Result<(), String> {
+    let rows = table
+        .score_rows()
+        .map_err(|e| format!("Failed to read score file: {}", e))?;
+    stage_score_rows(tx, rating_map, rows).await
+}
+
+/// Keeps the problems visible in the staged rows as validation issues of the dataset.
+async fn record_staged_issues(
+    tx: &mut Transaction<'_, Sqlite>,
+    dataset_id: i64,
+) -> Result<(), String> {
+    let unmapped: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT raw_value, COUNT(*) FROM import_staging
+         WHERE numeric_value IS NULL GROUP BY raw_value ORDER BY raw_value",
+    )
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| format!("Failed to check staged ratings: {}", e))?;
+    for (value, occurrences) in unmapped {
+        record_validation_issue(
+            &mut **tx,
+            dataset_id,
+            "unmapped_rating",
+            "error",
+            &format!("Rating \"{}\" has no numeric mapping", value),
+            &serde_json::json!({ "value": value, "occurrences": occurrences }),
+        )
+        .await
+        .map_err(|e| format!("Failed to record validation issue: {}", e))?;
+    }
+
+    // Repeats without a timestamp are not separate responses; only the last one was kept
+    let repeated: Vec<(String, String, i64)> = sqlx::query_as(
+        "SELECT MIN(employee_name), competency, COUNT(*) FROM import_staging
+         GROUP BY employee_id, competency_id
+         HAVING COUNT(*) > 1 AND SUM(respondents) = 0
+         ORDER BY MIN(seq)",
+    )
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| format!("Failed to check staged duplicates: {}", e))?;
+    for (employee_name, competency, occurrences) in repeated {
+        record_validation_issue(
+            &mut **tx,
+            dataset_id,
+            "duplicate_score",
+            "warning",
+            &format!(
+                "{} has {} scores for {}; the last one was kept",
+                employee_name, occurrences, competency
+            ),
+            &serde_json::json!({
+                "employee_name": employee_name,
+                "competency": competency,
+                "occurrences": occurrences,
+            }),
+        )
+        .await
+        .map_err(|e| format!("Failed to record validation issue: {}", e))?;
+    }
+    Ok(())
+}
+
+async fn record_skipped_rows(
+    tx: &mut Transaction<'_, Sqlite>,
+    dataset_id: i64,
+    skipped_rows: usize,
+) -> Result<(), String> {
+    if skipped_rows == 0 {
+        return Ok(());
+    }
+    record_validation_issue(
+        &mut **tx,
+        dataset_id,
+        "blank_employee_name",
+        "warning",
+        &format!("{} employee rows without a name were skipped", skipped_rows),
+        &serde_json::json!({ "skipped_rows": skipped_rows }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record validation issue: {}", e))
+}
+
+/// Stages the scores, then resolves employees and competencies once per distinct name and
+/// writes every score with a single statement. Later rows win for repeated pairs. With a
+/// `run`, the import run is recorded and linked from every written score.
+async fn write_performance_rows(
+    tx: &mut Transaction<'_, Sqlite>,
+    dataset_id: i64,
+    rating_mappings: &[CreateRatingMapping],
+    listed: &[(String, String)],
+    source: ScoreSource<'_>,
+    run: Option<PerformanceRun<'_>>,
+) -> Result<PerformanceWriteStats, String> {
+    let mut rating_map: HashMap<String, f64> = HashMap::new();
+    for mapping in rating_mappings {
+        sqlx::query(
+            r#"
+            INSERT INTO rating_mappings (dataset_id, text_value, numeric_value)
+            VALUES (?, ?, ?)
+            ON CONFLICT(dataset_id, text_value) DO UPDATE SET numeric_value = excluded.numeric_value
+            "#,
+        )
+        .bind(dataset_id)
+        .bind(&mapping.text_value)
+        .bind(mapping.numeric_value)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| format!("Failed to upsert rating mapping: {}", e))?;
+
+        rating_map.insert(mapping.text_value.clone(), mapping.numeric_value);
+    }
+
+    match source {
+        ScoreSource::Payload(scores) => stage_scores(tx, &rating_map, scores).await?,
+        ScoreSource::File {
+            path,
+            options,
+            columns,
+            aggregation,
+        } => {
+            let mapping = columns
+                .iter()
+                .map(|mapping| (mapping.csv_column.as_str(), mapping.db_field.as_str()));
+            match aggregation {
+                None if !SourceTable::is_workbook(path) => {
+                    let rows = SourceTable::delimited_score_rows(path, options, mapping)
+                        .map_err(|e| format!("Failed to read score file: {}", e))?;
+                    stage_score_rows(tx, &rating_map, rows).await?
+                }
+                None => {
+                    let mut table = SourceTable::open(path, options)
+                        .map_err(|e| format!("Failed to read score file: {}", e))?;
+                    table.map_columns(mapping);
+                    stage_table(tx, &rating_map, &table).await?
+                }
+                // A respondent's answers can be anywhere in the file, so it is read whole
+                Some(aggregation) => {
+                    let mut table = SourceTable::open(path, options)
+                        .map_err(|e| format!("Failed to read score file: {}", e))?;
+                    table.map_columns(mapping);
+                    let scores = table
+                        .detected_scores()
+                        .map_err(|e| format!("Failed to read score file: {}", e))?;
+                    for batch in aggregate_respondents(scores, aggregation, &rating_map)
+                        .chunks(STAGING_BATCH_SIZE)
+                    {
+                        stage_scores(tx, &rating_map, batch).await?;
+                    }
+                }
+            }
+        }
+        ScoreSource::Table(table) => stage_table(tx, &rating_map, table).await?,
+    }
+
+    // Listed employees first, then the scored ones in order of first appearance
+    let mut employees = listed.to_vec();
+    let listed_names: HashSet<&str> = listed
+        .iter()
+        .map(|(normalized, _)| normalized.as_str())
+        .collect();
+    let scored: Vec<(String, String)> = sqlx::query_as(
+        "SELECT normalized_name, employee_name FROM import_staging
+         GROUP BY normalized_name ORDER BY MIN(seq)",
+    )
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| format!("Failed to load staged employees: {}", e))?;
+    employees.extend(
+        scored
+            .into_iter()
+            .filter(|(normalized, _)| !listed_names.contains(normalized.as_str())),
+    );
+
+    // Ensure employees exist as master data and associate with dataset
+    let mut unique_employee_ids: HashSet<i64> = HashSet::new();
+    let mut linked_ids: HashSet<i64> =
+        sqlx::query_scalar("SELECT employee_id FROM dataset_employees WHERE dataset_id = ?")
+            .bind(dataset_id)
+            .fetch_all(&mut **tx)
+            .await
+            .map_err(|e| format!("Failed to load dataset employees: {}", e))?
+            .into_iter()
+            .collect();
+    let mut newly_linked_employees = 0usize;
+
+    for (normalized, display_name) in &employees {
+        let employee = sqlx::query_as::<_, Employee>(
+            r#"
+            SELECT * FROM employees WHERE lower(name) = ? LIMIT 1
+            "#,
+        )
+        .bind(normalized)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| format!("Failed to lookup employee {}: {}", display_name, e))?
+        .ok_or_else(|| format!("Employee not found in master data: {}", display_name))?;
+
+        unique_employee_ids.insert(employee.id);
+        if linked_ids.insert(employee.id) {
+            newly_linked_employees += 1;
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO dataset_employees (dataset_id, employee_id, created_at, updated_at)
+            VALUES (?, ?, datetime('now'), datetime('now'))
+            ON CONFLICT(dataset_id, employee_id)
+            DO UPDATE SET updated_at = datetime('now')
+            "#,
+        )
+        .bind(dataset_id)
+        .bind(employee.id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| format!("Failed to link employee {}: {}", display_name, e))?;
+
+        sqlx::query("UPDATE import_staging SET employee_id = ? WHERE normalized_name = ?")
+            .bind(employee.id)
+            .bind(normalized)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| format!("Failed to stage employee {}: {}", display_name, e))?;
+    }
+
+    // Ensure competencies exist (globally) and get ids
+    let competencies: Vec<(String, Option<i32>)> = sqlx::query_as(
+        "SELECT competency, MIN(ordinal) FROM import_staging GROUP BY competency ORDER BY competency",
+    )
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| format!("Failed to load staged competencies: {}", e))?;
+    let mut new_competencies = 0usize;
+    for (idx, (comp_name, ordinal)) in competencies.iter().enumerate() {
+        // "1. Inisiatif & Fleksibilitas" joins an existing "Inisiatif dan fleksibilitas"
+        let existing_id = resolve_competency(tx, comp_name)
+            .await
+            .map_err(|e| format!("Failed to fetch competency: {}", e))?;
+        let competency_id = match existing_id {
+            Some(id) => {
+                apply_question_number(tx, id, *ordinal)
+                    .await
+                    .map_err(|e| format!("Failed to order competency {}: {}", comp_name, e))?;
+                id
+            }
+            None => {
+                new_competencies += 1;
+                sqlx::query_scalar::<_, i64>(
+                    r#"
+                    INSERT INTO competencies (name, display_order)
+                    VALUES (?, ?)
+                    RETURNING id
+                    "#,
+                )
+                .bind(comp_name)
+                // The header's question number when it had one
+                .bind(ordinal.unwrap_or(idx as i32))
+                .fetch_one(&mut **tx)
+                .await
+                .map_err(|e| format!("Failed to insert competency {}: {}", comp_name, e))?
+            }
+        };
+        record_alias(tx, comp_name, competency_id)
+            .await
+            .map_err(|e| format!("Failed to record competency alias {}: {}", comp_name, e))?;
+
+        sqlx::query("UPDATE import_staging SET competency_id = ? WHERE competency = ?")
+            .bind(competency_id)
+            .bind(comp_name)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| format!("Failed to stage competency {}: {}", comp_name, e))?;
+    }
+
+    // Keep the source form's question order for this dataset, by question number where the
+    // headers carry one; appended competencies go last
+    let source_order: Vec<i64> = sqlx::query_scalar(
+        "SELECT competency_id FROM import_staging GROUP BY competency_id
+         ORDER BY MIN(ordinal) IS NULL, MIN(ordinal), MIN(seq)",
+    )
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| format!("Failed to load competency order: {}", e))?;
+    for competency_id in source_order {
+        sqlx::query(
+            r#"
+            INSERT INTO dataset_competencies (dataset_id, competency_id, display_order)
+            SELECT ?, ?, COALESCE(MAX(display_order) + 1, 0)
+            FROM dataset_competencies WHERE dataset_id = ?
+            ON CONFLICT(dataset_id, competency_id) DO NOTHING
+            "#,
+        )
+        .bind(dataset_id)
+        .bind(competency_id)
+        .bind(dataset_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| format!("Failed to order competency {}: {}", competency_id, e))?;
+    }
+
+    let (score_count, new_pairs): (i64, i64) = sqlx::query_as(
+        r#"
+        SELECT
+            (SELECT COUNT(*) FROM import_staging),
+            (SELECT COUNT(*) FROM (SELECT DISTINCT employee_id, competency_id FROM import_staging) s
+             WHERE NOT EXISTS (
+                SELECT 1 FROM scores x
+                WHERE x.dataset_id = ? AND x.employee_id = s.employee_id
+                  AND x.competency_id = s.competency_id))
+        "#,
+    )
+    .bind(dataset_id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| format!("Failed to load existing scores: {}", e))?;
+    let stats = PerformanceWriteStats {
+        employee_count: unique_employee_ids.len(),
+        competency_count: competencies.len(),
+        score_count: score_count as usize,
+        overwritten_rows: (score_count - new_pairs) as usize,
+        new_competencies,
+        newly_linked_employees,
+    };
+
+    // Recorded before the scores are written so each one can point at its run
+    let rater = run.as_ref().map(PerformanceRun::rater);
+    let import_run_id = match run {
+        Some(run) => {
+            Some(record_import_run(tx, performance_import_run(dataset_id, run, &stats)).await?)
+        }
+        None => None,
+    };
+
+    // Timestamped rows come from form exports with one row per respondent and aggregated
+    // rows stand for the responses combined into them, so repeated cells add up to the
+    // responses behind the score that is kept
+    sqlx::query(
+        r#"
+        INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, submitted_at, respondent_count, source_row, source_column, import_run_id, created_at)
+        SELECT s.employee_id, ?, s.competency_id, s.raw_value, s.numeric_value, s.submitted_at, r.respondents, s.source_row, s.source_column, ?, datetime('now')
+        FROM import_staging s
+        JOIN (
+            SELECT employee_id, competency_id, NULLIF(SUM(respondents), 0) AS respondents
+            FROM import_staging
+            GROUP BY employee_id, competency_id
+        ) r ON r.employee_id = s.employee_id AND r.competency_id = s.competency_id
+        WHERE true
+        ORDER BY s.seq
+        ON CONFLICT(dataset_id, employee_id, competency_id) DO UPDATE
+        SET raw_value = excluded.raw_value,
+            numeric_value = excluded.numeric_value,
+            submitted_at = excluded.submitted_at,
+            respondent_count = excluded.respondent_count,
+            source_row = excluded.source_row,
+            source_column = excluded.source_column,
+            import_run_id = excluded.import_run_id
+        "#,
+    )
+    .bind(dataset_id)
+    .bind(import_run_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| format!("Failed to upsert scores: {}", e))?;
+
+    // Every written score gets an entry, so a later import is combined with it
+    sqlx::query(
+        r#"
+        INSERT INTO score_entries (dataset_id, employee_id, competency_id, rater, raw_value, numeric_value, import_run_id, created_at)
+        SELECT s.dataset_id, s.employee_id, s.competency_id, ?, s.raw_value, s.numeric_value, s.import_run_id, datetime('now')
+        FROM scores s
+        WHERE s.dataset_id = ?
+          AND (s.employee_id, s.competency_id) IN (SELECT employee_id, competency_id FROM import_staging)
+        ON CONFLICT(dataset_id, employee_id, competency_id, rater) DO UPDATE
+        SET raw_value = excluded.raw_value,
+            numeric_value = excluded.numeric_value,
+            import_run_id = excluded.import_run_id,
+            created_at = excluded.created_at
+        "#,
+    )
+    .bind(rater.as_deref().unwrap_or(UNNAMED_IMPORT_RATER))
+    .bind(dataset_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| format!("Failed to record score entries: {}", e))?;
+    refresh_aggregated_scores(&mut **tx, dataset_id, import_run_id)
+        .await
+        .map_err(|e| format!("Failed to aggregate scores: {}", e))?;
+
+    record_staged_issues(tx, dataset_id).await?;
+    sqlx::query("DELETE FROM import_staging")
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| format!("Failed to clear staged scores: {}", e))?;
+
+    Ok(stats)
+}
+
+/// Writes scores for employees that already exist in master data.
+pub(crate) async fn write_dataset_scores(
+    tx: &mut Transaction<'_, Sqlite>,
+    dataset_id: i64,
+    scores: &[ParsedScore],
+    rating_mappings: &[CreateRatingMapping],
+) -> Result<(), String> {
+    write_performance_rows(
+        tx,
+        dataset_id,
+        rating_mappings,
+        &[],
+        ScoreSource::Payload(scores),
+        None,
+    )
+    .await?;
+    Ok(())
+}
+
+struct ImportRunRecord<'a> {
+    dataset_id: i64,
+    kind: &'a str,
+    source_file: Option<&'a str>,
+    started_at: DateTime<Utc>,
+    row_count: usize,
+    inserted_count: usize,
+    updated_count: usize,
+    skipped_count: usize,
+    validation_stats: Option<&'a ValidationStats>,
+}
+
+/// Written inside the import transaction so dry runs leave no trace. Returns the run id.
+async fn record_import_run(
+    tx: &mut Transaction<'_, Sqlite>,
+    run: ImportRunRecord<'_>,
+) -> Result<i64, String> {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO import_runs (
+            dataset_id, kind, source_file, started_at, finished_at, row_count,
+            inserted_count, updated_count, skipped_count, validation_errors, validation_warnings
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        RETURNING id
+        "#,
+    )
+    .bind(run.dataset_id)
+    .bind(run.kind)
+    .bind(run.source_file)
+    .bind(run.started_at)
+    .bind(Utc::now())
+    .bind(run.row_count as i64)
+    .bind(run.inserted_count as i64)
+    .bind(run.updated_count as i64)
+    .bind(run.skipped_count as i64)
+    .bind(run.validation_stats.map(|stats| stats.error_count as i64))
+    .bind(run.validation_stats.map(|stats| stats.warning_count as i64))
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| format!("Failed to record import run: {}", e))
+}
+
+/// What a performance import knows about its run before the scores are written.
+struct PerformanceRun<'a> {
+    kind: &'a str,
+    source_file: Option<&'a str>,
+    rater: Option<&'a str>,
+    /// Name of the import preset used, if any
+    preset: Option<&'a str>,
+    started_at: DateTime<Utc>,
+    skipped_rows: usize,
+    validation_stats: Option<&'a ValidationStats>,
+}
+
+impl PerformanceRun<'_> {
+    /// Rater the run's score entries are kept under: the given one, else the file name,
+    /// else the preset. The key stays the same when the source is imported again, so the
+    /// new entries replace that source's old ones.
+    fn rater(&self) -> String {
+        entry_rater(self.rater, self.source_file, self.preset)
+    }
+}
+
+fn entry_rater(rater: Option<&str>, source_file: Option<&str>, preset: Option<&str>) -> String {
+    rater
+        .map(str::trim)
+        .filter(|rater| !rater.is_empty())
+        .map(str::to_string)
+        .or_else(|| {
+            source_file
+                .and_then(|file| Path::new(file).file_name())
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .or_else(|| preset.map(|preset| format!("preset:{}", preset)))
+        .unwrap_or_else(|| UNNAMED_IMPORT_RATER.to_string())
+}
+
+fn performance_import_run<'a>(
+    dataset_id: i64,
+    run: PerformanceRun<'a>,
+    stats: &PerformanceWriteStats,
+) -> ImportRunRecord<'a> {
+    ImportRunRecord {
+        dataset_id,
+        kind: run.kind,
+        source_file: run.source_file,
+        started_at: run.started_at,
+        row_count: stats.score_count + run.skipped_rows,
+        inserted_count: stats.score_count - stats.overwritten_rows,
+        updated_count: stats.overwritten_rows,
+        skipped_count: run.skipped_rows,
+        validation_stats: run.validation_stats,
+    }
+}
+
+/// Commits the import, or rolls it back when it only ran as a dry run.
+async fn finish_import(tx: Transaction<'_, Sqlite>, dry_run: bool) -> Result<(), String> {
+    if dry_run {
+        tx.rollback()
+            .await
+            .map_err(|e| format!("Failed to roll back dry run: {}", e))
+    } else {
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit transaction: {}", e))
+    }
+}
+
+fn build_import_result(
+    dataset: Dataset,
+    dry_run: bool,
+    metrics: ImportMetrics,
+    stats: &PerformanceWriteStats,
+) -> ImportResult {
+    ImportResult {
+        dataset,
+        employee_count: stats.employee_count,
+        competency_count: stats.competency_count,
+        score_count: stats.score_count,
+        metrics,
+        changes: ImportChanges {
+            new_scores: stats.score_count - stats.overwritten_rows,
+            updated_scores: stats.overwritten_rows,
+            new_competencies: stats.new_competencies,
+            newly_linked_employees: stats.newly_linked_employees,
+        },
+        dry_run,
+    }
+}
+
+/// Tells the registered webhooks about an import that was persisted.
+fn notify_import_completed(pool: &SqlitePool, result: &ImportResult) {
+    if result.dry_run {
+        return;
+    }
+    webhooks::notify(
+        pool,
+        WebhookPayload::new(
+            IMPORT_COMPLETED,
+            result.dataset.id,
+            serde_json::json!({
+                "employee_count": result.employee_count,
+                "competency_count": result.competency_count,
+                "score_count": result.score_count,
+                "new_scores": result.changes.new_scores,
+                "updated_scores": result.changes.updated_scores,
+            }),
+        ),
+    );
+}
+
+fn build_import_metrics(
+    parse_ms: u64,
+    db_ms: u64,
+    started: Instant,
+    skipped_rows: usize,
+    stats: &PerformanceWriteStats,
+) -> ImportMetrics {
+    let elapsed = started.elapsed().as_secs_f64();
+    ImportMetrics {
+        parse_ms,
+        db_ms,
+        total_ms: elapsed_ms(started),
+        rows_per_sec: if elapsed > 0.0 {
+            stats.score_count as f64 / elapsed
+        } else {
+            0.0
+        },
+        skipped_rows,
+        overwritten_rows: stats.overwritten_rows,
+    }
+}
+
 #[tauri::command]
 pub async fn import_employees(
     state: State<'_, AppState>,
     request: EmployeeImportRequest,
 ) -> Result<EmployeeImportResult, String> {
+    let run = run_import_employees(&state, request);
+    timed_command(
+        &state.pool,
+        "import_employees",
+        |result: &EmployeeImportResult| result.total,
+        run,
+    )
+    .await
+}
+
+async fn run_import_employees(
+    state: &AppState,
+    request: EmployeeImportRequest,
+) -> Result<EmployeeImportResult, String> {
+    state.require_role(Role::Editor).await?;
     let pool = state.pool.clone();
 
     if request.employees.is_empty() {
@@ -136,6 +1036,7 @@ pub async fn import_employees(
     #[derive(Clone)]
     struct EmployeeUpsertData {
         name: String,
+        normalized_name: String,
         nip: Option<String>,
         gol: Option<String>,
         jabatan: Option<String>,
@@ -152,25 +1053,34 @@ pub async fn import_employees(
 
         let entry = EmployeeUpsertData {
             name: emp.name.trim().to_string(),
-            nip: sanitize_optional(&emp.nip),
+            normalized_name: normalized,
+            nip: normalize_nip(emp.nip.as_deref()),
             gol: sanitize_optional(&emp.gol),
             jabatan: sanitize_optional(&emp.jabatan),
             sub_jabatan: sanitize_optional(&emp.sub_jabatan),
         };
 
-        unique_employees.insert(normalized, entry);
+        unique_employees.insert(
+            employee_identity_key(entry.nip.as_deref(), &entry.normalized_name),
+            entry,
+        );
     }
 
     let mut inserted = 0usize;
     let mut updated = 0usize;
-
-    for (normalized, data) in unique_employees {
-        let existing =
-            sqlx::query_as::<_, Employee>("SELECT * FROM employees WHERE lower(name) = ? LIMIT 1")
-                .bind(&normalized)
-                .fetch_optional(&mut *tx)
-                .await
-                .map_err(|e| format!("Failed to lookup employee {}: {}", data.name, e))?;
+    let mut nip_index = load_nip_index(&mut tx)
+        .await
+        .map_err(|e| format!("Failed to load employee NIPs: {}", e))?;
+
+    for data in unique_employees.into_values() {
+        let existing = find_master_employee(
+            &mut tx,
+            &nip_index,
+            data.nip.as_deref(),
+            &data.normalized_name,
+        )
+        .await
+        .map_err(|e| format!("Failed to lookup employee {}: {}", data.name, e))?;
 
         if let Some(employee) = existing {
             sqlx::query(
@@ -195,9 +1105,12 @@ pub async fn import_employees(
             .await
             .map_err(|e| format!("Failed to update employee {}: {}", data.name, e))?;
 
+            if let Some(nip) = normalize_nip(data.nip.as_deref()) {
+                nip_index.insert(nip, employee.id);
+            }
             updated += 1;
         } else {
-            sqlx::query_as::<_, Employee>(
+            let employee = sqlx::query_as::<_, Employee>(
                 r#"
                 INSERT INTO employees (name, nip, gol, jabatan, sub_jabatan, created_at, updated_at)
                 VALUES (?, ?, ?, ?, ?, datetime('now'), datetime('now'))
@@ -213,6 +1126,9 @@ pub async fn import_employees(
             .await
             .map_err(|e| format!("Failed to create employee {}: {}", data.name, e))?;
 
+            if let Some(nip) = normalize_nip(employee.nip.as_deref()) {
+                nip_index.insert(nip, employee.id);
+            }
             inserted += 1;
         }
     }
@@ -233,194 +1149,216 @@ pub async fn import_performance_dataset(
     state: State<'_, AppState>,
     request: PerformanceImportRequest,
 ) -> Result<ImportResult, String> {
-    let pool = state.pool.clone();
-
-    // Start transaction
-    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
-
-    // 1. Create dataset
-    let dataset = sqlx::query_as::<_, Dataset>(
-        r#"
-        INSERT INTO datasets (name, description, source_file, created_at, updated_at)
-        VALUES (?, ?, ?, datetime('now'), datetime('now'))
-        RETURNING *
-        "#,
+    let run = run_import_performance_dataset(&state, request);
+    timed_command(
+        &state.pool,
+        "import_performance_dataset",
+        |result: &ImportResult| result.score_count,
+        run,
     )
-    .bind(&request.dataset_name)
-    .bind(&request.dataset_description)
-    .bind(&request.source_file)
-    .fetch_one(&mut *tx)
     .await
-    .map_err(|e| format!("Failed to create dataset: {}", e))?;
-
-    // 2. Insert rating mappings for this dataset
-    let mut rating_map: HashMap<String, f64> = HashMap::new();
-    for mapping in &request.rating_mappings {
-        sqlx::query(
-            r#"
-            INSERT INTO rating_mappings (dataset_id, text_value, numeric_value)
-            VALUES (?, ?, ?)
-            ON CONFLICT(dataset_id, text_value) DO UPDATE SET numeric_value = excluded.numeric_value
-            "#,
-        )
-        .bind(dataset.id)
-        .bind(&mapping.text_value)
-        .bind(mapping.numeric_value)
-        .execute(&mut *tx)
-        .await
-        .map_err(|e| format!("Failed to insert rating mapping: {}", e))?;
-
-        rating_map.insert(mapping.text_value.clone(), mapping.numeric_value);
-    }
-
-    // 3. Ensure employees exist as master data and associate with dataset
-    let mut employee_lookup: HashMap<String, i64> = HashMap::new();
-    let mut unique_employee_ids: HashSet<i64> = HashSet::new();
-
-    let mut normalized_to_display: HashMap<String, String> = HashMap::new();
-
-    for name in &request.employee_names {
-        let trimmed = name.trim();
-        if trimmed.is_empty() {
-            continue;
-        }
-        let normalized = normalize_name(trimmed);
-        normalized_to_display
-            .entry(normalized.clone())
-            .or_insert_with(|| trimmed.to_string());
-    }
-
-    for score in &request.scores {
-        let trimmed = score.employee_name.trim();
-        if trimmed.is_empty() {
-            return Err("Score is associated with a blank employee name".to_string());
-        }
-        let normalized = normalize_name(trimmed);
-        normalized_to_display
-            .entry(normalized.clone())
-            .or_insert_with(|| trimmed.to_string());
-    }
-
-    for (normalized, display_name) in normalized_to_display.clone() {
-        let employee = sqlx::query_as::<_, Employee>(
-            r#"
-            SELECT * FROM employees WHERE lower(name) = ? LIMIT 1
-            "#,
-        )
-        .bind(&normalized)
-        .fetch_optional(&mut *tx)
-        .await
-        .map_err(|e| format!("Failed to lookup employee {}: {}", display_name, e))?
-        .ok_or_else(|| format!("Employee not found in master data: {}", display_name))?;
+}
 
-        employee_lookup.insert(normalized.clone(), employee.id);
-        employee_lookup.insert(display_name.to_lowercase(), employee.id);
-        unique_employee_ids.insert(employee.id);
+async fn run_import_performance_dataset(
+    state: &AppState,
+    request: PerformanceImportRequest,
+) -> Result<ImportResult, String> {
+    state.require_role(Role::Editor).await?;
+    let started = Instant::now();
+    let source = score_source(
+        &request.scores,
+        request.scores_file.as_deref(),
+        request.aggregation.as_deref(),
+        read_options(request.delimiter.clone(), request.encoding.clone())?,
+    )?;
+    create_performance_dataset(&state.pool, &request, source, started).await
+}
 
-        sqlx::query(
-            r#"
-            INSERT INTO dataset_employees (dataset_id, employee_id, created_at, updated_at)
-            VALUES (?, ?, datetime('now'), datetime('now'))
-            ON CONFLICT(dataset_id, employee_id)
-            DO UPDATE SET updated_at = datetime('now')
-            "#,
-        )
-        .bind(dataset.id)
-        .bind(employee.id)
-        .execute(&mut *tx)
-        .await
-        .map_err(|e| format!("Failed to link employee {}: {}", display_name, e))?;
-    }
+/// Options of `import_performance_dataset_xlsx`; everything the workbook itself does not say.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct XlsxImportOptions {
+    /// Defaults to the workbook's file name
+    pub dataset_name: Option<String>,
+    pub dataset_description: Option<String>,
+    pub period_year: Option<i32>,
+    pub period_label: Option<String>,
+    /// Defaults to `default_rating_mappings`
+    pub rating_mappings: Option<Vec<CreateRatingMapping>>,
+    pub rater: Option<String>,
+    pub dry_run: bool,
+    /// Saved import preset; its column mapping renames the sheet's headers
+    pub preset_id: Option<i64>,
+}
 
-    // 4. Extract unique competencies from scores and insert them
-    let mut competency_names: Vec<String> = request
-        .scores
-        .iter()
-        .map(|s| s.competency.clone())
-        .collect::<std::collections::HashSet<_>>()
-        .into_iter()
-        .collect();
-    competency_names.sort();
+/// Creates a dataset straight from one worksheet of a workbook. The score layout (wide or long)
+/// is detected from the sheet's headers; employees are taken from the scored rows.
+#[tauri::command]
+pub async fn import_performance_dataset_xlsx(
+    state: State<'_, AppState>,
+    file_path: String,
+    sheet: Option<String>,
+    options: Option<XlsxImportOptions>,
+) -> Result<ImportResult, String> {
+    let run = run_import_performance_dataset_xlsx(&state, file_path, sheet, options);
+    timed_command(
+        &state.pool,
+        "import_performance_dataset_xlsx",
+        |result: &ImportResult| result.score_count,
+        run,
+    )
+    .await
+}
 
-    let mut competency_map: HashMap<String, i64> = HashMap::new();
-    for (idx, comp_name) in competency_names.iter().enumerate() {
-        // Try to get existing competency first
-        let competency =
-            match sqlx::query_as::<_, Competency>("SELECT * FROM competencies WHERE name = ?")
-                .bind(comp_name)
-                .fetch_optional(&mut *tx)
-                .await
-                .map_err(|e| format!("Failed to fetch competency: {}", e))?
-            {
-                Some(comp) => comp,
-                None => {
-                    // Insert new competency
-                    sqlx::query_as::<_, Competency>(
-                        r#"
-                    INSERT INTO competencies (name, display_order)
-                    VALUES (?, ?)
-                    RETURNING *
-                    "#,
-                    )
-                    .bind(comp_name)
-                    .bind(idx as i32)
-                    .fetch_one(&mut *tx)
-                    .await
-                    .map_err(|e| format!("Failed to insert competency {}: {}", comp_name, e))?
-                }
-            };
+async fn run_import_performance_dataset_xlsx(
+    state: &AppState,
+    file_path: String,
+    sheet: Option<String>,
+    options: Option<XlsxImportOptions>,
+) -> Result<ImportResult, String> {
+    state.require_role(Role::Editor).await?;
+    let started = Instant::now();
+    let path = Path::new(&file_path);
+    let mut table = SourceTable::open_workbook(path, sheet.as_deref())
+        .map_err(|e| format!("Failed to read workbook: {}", e))?;
+
+    let request = xlsx_import_request(path, options.unwrap_or_default());
+    if let Some(id) = request.preset_id {
+        let mut conn = state.pool.acquire().await.map_err(|e| e.to_string())?;
+        let preset = load_import_preset(&mut conn, id).await?;
+        table.map_columns(
+            preset
+                .column_mapping
+                .iter()
+                .map(|mapping| (mapping.csv_column.as_str(), mapping.db_field.as_str())),
+        );
+    }
+    create_performance_dataset(&state.pool, &request, ScoreSource::Table(&table), started).await
+}
 
-        competency_map.insert(comp_name.clone(), competency.id);
+fn xlsx_import_request(path: &Path, options: XlsxImportOptions) -> PerformanceImportRequest {
+    let dataset_name = options
+        .dataset_name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default()
+        });
+    PerformanceImportRequest {
+        dataset_name,
+        dataset_description: options.dataset_description,
+        source_file: path.to_string_lossy().to_string(),
+        period_year: options.period_year,
+        period_label: options.period_label,
+        employee_names: Vec::new(),
+        scores: Vec::new(),
+        scores_file: None,
+        aggregation: None,
+        delimiter: None,
+        encoding: None,
+        rating_mappings: options
+            .rating_mappings
+            .unwrap_or_else(default_rating_mappings),
+        rater: options.rater,
+        dry_run: options.dry_run,
+        validation_stats: None,
+        preset_id: options.preset_id,
     }
+}
 
-    // 5. Insert scores
-    let mut score_count = 0;
-    for score in &request.scores {
-        let normalized = normalize_name(&score.employee_name);
-        let employee_id = employee_lookup
-            .get(&normalized)
-            .or_else(|| employee_lookup.get(&score.employee_name.to_lowercase()))
-            .ok_or_else(|| format!("Employee not found: {}", score.employee_name))?;
+/// Creates the dataset `request` describes and writes `source` into it in one transaction,
+/// rolled back again for a dry run.
+async fn create_performance_dataset(
+    pool: &SqlitePool,
+    request: &PerformanceImportRequest,
+    source: ScoreSource<'_>,
+    started: Instant,
+) -> Result<ImportResult, String> {
+    let started_at = Utc::now();
 
-        let competency_id = competency_map
-            .get(&score.competency)
-            .ok_or_else(|| format!("Competency not found: {}", score.competency))?;
+    let period = normalize_period(request.period_year, request.period_label.as_deref())?;
+    let (employees, skipped_rows) = listed_employees(&request.employee_names);
+    let parse_ms = elapsed_ms(started);
 
-        // Apply rating mapping if available
-        let numeric_value = rating_map.get(&score.value).copied();
+    let db_started = Instant::now();
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
 
-        sqlx::query(
-            r#"
-            INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, created_at)
-            VALUES (?, ?, ?, ?, ?, datetime('now'))
-            ON CONFLICT(dataset_id, employee_id, competency_id) DO UPDATE
-            SET raw_value = excluded.raw_value,
-                numeric_value = excluded.numeric_value
-            "#,
-        )
-        .bind(employee_id)
-        .bind(dataset.id)
-        .bind(competency_id)
-        .bind(&score.value)
-        .bind(numeric_value)
-        .execute(&mut *tx)
-        .await
-        .map_err(|e| format!("Failed to insert score: {}", e))?;
+    let preset = match request.preset_id {
+        Some(id) => Some(load_import_preset(&mut tx, id).await?),
+        None => None,
+    };
+    // A file read on the backend takes the preset's column names, and its delimiter unless
+    // the preview chose one
+    let source = match (source, &preset) {
+        (
+            ScoreSource::File {
+                path,
+                options,
+                aggregation,
+                ..
+            },
+            Some(preset),
+        ) => ScoreSource::File {
+            path,
+            options: ReadOptions {
+                delimiter: options.delimiter.or(preset.read_options()?.delimiter),
+                ..options
+            },
+            columns: &preset.column_mapping,
+            aggregation,
+        },
+        (source, _) => source,
+    };
+    let rating_mappings = match &preset {
+        Some(preset) if request.rating_mappings.is_empty() => &preset.rating_mappings,
+        _ => &request.rating_mappings,
+    };
 
-        score_count += 1;
-    }
+    let dataset = sqlx::query_as::<_, Dataset>(
+        r#"
+        INSERT INTO datasets (name, description, source_file, period_year, period_label, score_scale, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'))
+        RETURNING *
+        "#,
+    )
+    .bind(&request.dataset_name)
+    .bind(&request.dataset_description)
+    .bind(&request.source_file)
+    .bind(period.period_year)
+    .bind(&period.period_label)
+    .bind(preset.as_ref().and_then(|preset| preset.score_scale))
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to create dataset: {}", e))?;
 
-    // Commit transaction
-    tx.commit()
-        .await
-        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    let stats = write_performance_rows(
+        &mut tx,
+        dataset.id,
+        rating_mappings,
+        &employees,
+        source,
+        Some(PerformanceRun {
+            kind: "create",
+            source_file: Some(&request.source_file),
+            rater: request.rater.as_deref(),
+            preset: preset.as_ref().map(|preset| preset.name.as_str()),
+            started_at,
+            skipped_rows,
+            validation_stats: request.validation_stats.as_ref(),
+        }),
+    )
+    .await?;
+    record_skipped_rows(&mut tx, dataset.id, skipped_rows).await?;
 
-    Ok(ImportResult {
-        dataset,
-        employee_count: unique_employee_ids.len(),
-        competency_count: competency_map.len(),
-        score_count,
-    })
+    finish_import(tx, request.dry_run).await?;
+    let db_ms = elapsed_ms(db_started);
+
+    let metrics = build_import_metrics(parse_ms, db_ms, started, skipped_rows, &stats);
+    let result = build_import_result(dataset, request.dry_run, metrics, &stats);
+    notify_import_completed(pool, &result);
+    Ok(result)
 }
 
 /// Append scores/employees into an existing dataset (no dataset creation)
@@ -429,9 +1367,38 @@ pub async fn import_performance_into_dataset(
     state: State<'_, AppState>,
     request: PerformanceAppendRequest,
 ) -> Result<ImportResult, String> {
-    let pool = state.pool.clone();
+    let run = run_import_performance_into_dataset(&state, request);
+    timed_command(
+        &state.pool,
+        "import_performance_into_dataset",
+        |result: &ImportResult| result.score_count,
+        run,
+    )
+    .await
+}
 
-    // Start transaction
+async fn run_import_performance_into_dataset(
+    state: &AppState,
+    request: PerformanceAppendRequest,
+) -> Result<ImportResult, String> {
+    state.require_role(Role::Editor).await?;
+    let pool = state.pool.clone();
+    let started = Instant::now();
+    let started_at = Utc::now();
+
+    let (employees, skipped_rows) = listed_employees(&request.employee_names);
+    let source = score_source(
+        &request.scores,
+        request.scores_file.as_deref(),
+        request.aggregation.as_deref(),
+        read_options(request.delimiter.clone(), request.encoding.clone())?,
+    )?;
+    let parse_ms = elapsed_ms(started);
+
+    let _lock = state
+        .dataset_locks
+        .acquire("append-scores", &[request.dataset_id])?;
+    let db_started = Instant::now();
     let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
 
     // Ensure dataset exists
@@ -441,165 +1408,325 @@ pub async fn import_performance_into_dataset(
         .await
         .map_err(|e| format!("Failed to load target dataset: {}", e))?;
 
-    // Upsert rating mappings for this dataset
-    let mut rating_map: HashMap<String, f64> = HashMap::new();
-    for mapping in &request.rating_mappings {
-        sqlx::query(
-            r#"
-            INSERT INTO rating_mappings (dataset_id, text_value, numeric_value)
-            VALUES (?, ?, ?)
-            ON CONFLICT(dataset_id, text_value) DO UPDATE SET numeric_value = excluded.numeric_value
-            "#,
-        )
-        .bind(dataset.id)
-        .bind(&mapping.text_value)
-        .bind(mapping.numeric_value)
-        .execute(&mut *tx)
-        .await
-        .map_err(|e| format!("Failed to upsert rating mapping: {}", e))?;
+    let stats = write_performance_rows(
+        &mut tx,
+        dataset.id,
+        &request.rating_mappings,
+        &employees,
+        source,
+        Some(PerformanceRun {
+            kind: "append",
+            source_file: request.source_file.as_deref(),
+            rater: request.rater.as_deref(),
+            preset: None,
+            started_at,
+            skipped_rows,
+            validation_stats: request.validation_stats.as_ref(),
+        }),
+    )
+    .await?;
+    record_skipped_rows(&mut tx, dataset.id, skipped_rows).await?;
 
-        rating_map.insert(mapping.text_value.clone(), mapping.numeric_value);
-    }
+    finish_import(tx, request.dry_run).await?;
+    let db_ms = elapsed_ms(db_started);
 
-    // Build normalized employee name map and ensure links
-    let mut employee_lookup: HashMap<String, i64> = HashMap::new();
-    let mut unique_employee_ids: HashSet<i64> = HashSet::new();
-    let mut normalized_to_display: HashMap<String, String> = HashMap::new();
+    let metrics = build_import_metrics(parse_ms, db_ms, started, skipped_rows, &stats);
+    let result = build_import_result(dataset, request.dry_run, metrics, &stats);
+    notify_import_completed(&pool, &result);
+    Ok(result)
+}
 
-    for name in &request.employee_names {
-        let trimmed = name.trim();
-        if trimmed.is_empty() {
-            continue;
-        }
-        let normalized = normalize_name(trimmed);
-        normalized_to_display
-            .entry(normalized)
-            .or_insert_with(|| trimmed.to_string());
-    }
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AppendPreviewPayload {
+    #[serde(default)]
+    pub employee_names: Vec<String>,
+    pub scores: Vec<ParsedScore>,
+    #[serde(default)]
+    pub rating_mappings: Vec<CreateRatingMapping>,
+    /// Rater and source file of the append; they decide which rater entry it replaces
+    #[serde(default)]
+    pub rater: Option<String>,
+    #[serde(default)]
+    pub source_file: Option<String>,
+}
 
-    for score in &request.scores {
-        let trimmed = score.employee_name.trim();
-        if trimmed.is_empty() {
-            return Err("Score is associated with a blank employee name".to_string());
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScorePreviewEntry {
+    pub employee_name: String,
+    pub competency: String,
+    pub raw_value: String,
+    pub numeric_value: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScoreChangePreview {
+    pub employee_name: String,
+    pub competency: String,
+    /// Stored score before and after the import, once the rater entries are aggregated
+    pub old_raw_value: String,
+    pub new_raw_value: String,
+    pub old_numeric_value: Option<f64>,
+    pub new_numeric_value: Option<f64>,
+    /// Where the new value sits in the source file, when it was parsed from one
+    #[serde(default)]
+    pub source_row: Option<i64>,
+    #[serde(default)]
+    pub source_column: Option<String>,
+}
+
+/// What `import_performance_into_dataset` would do to a dataset, computed without writing.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AppendPreview {
+    pub new_scores: Vec<ScorePreviewEntry>,
+    pub changed_scores: Vec<ScoreChangePreview>,
+    pub unchanged_score_count: usize,
+    /// Existing employees that are not yet linked to the dataset
+    pub newly_linked_employees: Vec<String>,
+    /// Names missing from master data; the append would fail on these
+    pub unknown_employees: Vec<String>,
+}
+
+#[tauri::command]
+pub async fn preview_append(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    payload: AppendPreviewPayload,
+) -> Result<AppendPreview, String> {
+    build_append_preview(&state.pool, dataset_id, &payload).await
+}
+
+/// Cells of a corrected file whose stored value would change, for confirming the overwrites
+/// before `import_performance_into_dataset` runs.
+#[tauri::command]
+pub async fn preview_score_conflicts(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    payload: AppendPreviewPayload,
+) -> Result<Vec<ScoreChangePreview>, String> {
+    Ok(build_append_preview(&state.pool, dataset_id, &payload)
+        .await?
+        .changed_scores)
+}
+
+async fn build_append_preview(
+    pool: &SqlitePool,
+    dataset_id: i64,
+    payload: &AppendPreviewPayload,
+) -> Result<AppendPreview, String> {
+    let prepared = prepare_performance_rows(&payload.employee_names, &payload.scores)?;
+
+    ensure_dataset_exists(pool, dataset_id).await?;
+    let rating_map = dataset_rating_map(pool, dataset_id, &payload.rating_mappings).await?;
+    let master = master_employee_ids(pool).await?;
+    let linked_ids: HashSet<i64> =
+        sqlx::query_scalar("SELECT employee_id FROM dataset_employees WHERE dataset_id = ?")
+            .bind(dataset_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Failed to load dataset employees: {}", e))?
+            .into_iter()
+            .collect();
+
+    let mut newly_linked_employees = Vec::new();
+    let mut unknown_employees = Vec::new();
+    for (normalized, display_name) in &prepared.employees {
+        match master.get(normalized) {
+            Some(id) if !linked_ids.contains(id) => {
+                newly_linked_employees.push(display_name.clone())
+            }
+            Some(_) => {}
+            None => unknown_employees.push(display_name.clone()),
         }
-        let normalized = normalize_name(trimmed);
-        normalized_to_display
-            .entry(normalized)
-            .or_insert_with(|| trimmed.to_string());
     }
 
-    for (normalized, display_name) in normalized_to_display.clone() {
-        let employee = sqlx::query_as::<_, Employee>(
-            r#"
-            SELECT * FROM employees WHERE lower(name) = ? LIMIT 1
-            "#,
-        )
-        .bind(&normalized)
-        .fetch_optional(&mut *tx)
+    let rater = entry_rater(
+        payload.rater.as_deref(),
+        payload.source_file.as_deref(),
+        None,
+    );
+    let comparison = compare_with_stored_scores(
+        pool,
+        dataset_id,
+        prepared.scores,
+        &rater,
+        &rating_map,
+        &master,
+    )
+    .await?;
+    Ok(AppendPreview {
+        new_scores: comparison.new_scores,
+        changed_scores: comparison.changed_scores,
+        unchanged_score_count: comparison.unchanged_score_count,
+        newly_linked_employees,
+        unknown_employees,
+    })
+}
+
+async fn ensure_dataset_exists(pool: &SqlitePool, dataset_id: i64) -> Result<(), String> {
+    sqlx::query_scalar::<_, i64>("SELECT id FROM datasets WHERE id = ?")
+        .bind(dataset_id)
+        .fetch_one(pool)
         .await
-        .map_err(|e| format!("Failed to lookup employee {}: {}", display_name, e))?
-        .ok_or_else(|| format!("Employee not found in master data: {}", display_name))?;
+        .map_err(|e| format!("Failed to load target dataset: {}", e))?;
+    Ok(())
+}
 
-        employee_lookup.insert(normalized.clone(), employee.id);
-        employee_lookup.insert(display_name.to_lowercase(), employee.id);
-        unique_employee_ids.insert(employee.id);
+/// The dataset's rating mappings with `overrides` applied on top, as an import would store them.
+async fn dataset_rating_map(
+    pool: &SqlitePool,
+    dataset_id: i64,
+    overrides: &[CreateRatingMapping],
+) -> Result<HashMap<String, f64>, String> {
+    let mut rating_map: HashMap<String, f64> = sqlx::query_as::<_, (String, f64)>(
+        "SELECT text_value, numeric_value FROM rating_mappings WHERE dataset_id = ?",
+    )
+    .bind(dataset_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to load rating mappings: {}", e))?
+    .into_iter()
+    .collect();
+    for mapping in overrides {
+        rating_map.insert(mapping.text_value.clone(), mapping.numeric_value);
+    }
+    Ok(rating_map)
+}
 
-        sqlx::query(
-            r#"
-            INSERT INTO dataset_employees (dataset_id, employee_id, created_at, updated_at)
-            VALUES (?, ?, datetime('now'), datetime('now'))
-            ON CONFLICT(dataset_id, employee_id)
-            DO UPDATE SET updated_at = datetime('now')
-            "#,
+/// Employee ids keyed by lowercased name.
+async fn master_employee_ids(pool: &SqlitePool) -> Result<HashMap<String, i64>, String> {
+    Ok(
+        sqlx::query_as::<_, (String, i64)>("SELECT lower(name), id FROM employees")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Failed to load employees: {}", e))?
+            .into_iter()
+            .collect(),
+    )
+}
+
+/// Raw and numeric values of the rater entries behind one score.
+type EntryValues = Vec<(String, Option<f64>)>;
+
+struct ScoreComparison {
+    new_scores: Vec<ScorePreviewEntry>,
+    changed_scores: Vec<ScoreChangePreview>,
+    unchanged_score_count: usize,
+}
+
+/// Sorts incoming scores into new, changed and unchanged against the dataset's stored scores.
+/// An incoming score replaces `rater`'s entry and is aggregated with the other raters'
+/// entries, as `write_performance_rows` stores it.
+async fn compare_with_stored_scores(
+    pool: &SqlitePool,
+    dataset_id: i64,
+    scores: &[ParsedScore],
+    rater: &str,
+    rating_map: &HashMap<String, f64>,
+    master: &HashMap<String, i64>,
+) -> Result<ScoreComparison, String> {
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    let aggregation = load_score_aggregation(&mut conn, dataset_id)
+        .await
+        .map_err(|e| format!("Failed to load score aggregation: {}", e))?;
+    let mut other_entries: HashMap<(i64, String), EntryValues> = HashMap::new();
+    for (employee_id, competency, raw, numeric) in
+        sqlx::query_as::<_, (i64, String, String, Option<f64>)>(
+            "SELECT e.employee_id, c.name, e.raw_value, e.numeric_value
+             FROM score_entries e
+             JOIN competencies c ON c.id = e.competency_id
+             WHERE e.dataset_id = ? AND e.rater <> ?
+             ORDER BY e.created_at, e.id",
         )
-        .bind(dataset.id)
-        .bind(employee.id)
-        .execute(&mut *tx)
+        .bind(dataset_id)
+        .bind(rater)
+        .fetch_all(&mut *conn)
         .await
-        .map_err(|e| format!("Failed to link employee {}: {}", display_name, e))?;
+        .map_err(|e| format!("Failed to load score entries: {}", e))?
+    {
+        other_entries
+            .entry((employee_id, competency))
+            .or_default()
+            .push((raw, numeric));
     }
 
-    // Ensure competencies exist (globally) and get ids
-    let mut competency_names: Vec<String> = request
-        .scores
-        .iter()
-        .map(|s| s.competency.clone())
-        .collect::<std::collections::HashSet<_>>()
+    let existing: HashMap<(i64, String), (String, Option<f64>)> =
+        sqlx::query_as::<_, (i64, String, String, Option<f64>)>(
+            "SELECT s.employee_id, c.name, s.raw_value, s.numeric_value
+             FROM scores s
+             JOIN competencies c ON c.id = s.competency_id
+             WHERE s.dataset_id = ?",
+        )
+        .bind(dataset_id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to load existing scores: {}", e))?
         .into_iter()
+        .map(|(employee_id, competency, raw, numeric)| ((employee_id, competency), (raw, numeric)))
         .collect();
-    competency_names.sort();
 
-    let mut competency_map: HashMap<String, i64> = HashMap::new();
-    for (idx, comp_name) in competency_names.iter().enumerate() {
-        let competency =
-            match sqlx::query_as::<_, Competency>("SELECT * FROM competencies WHERE name = ?")
-                .bind(comp_name)
-                .fetch_optional(&mut *tx)
-                .await
-                .map_err(|e| format!("Failed to fetch competency: {}", e))?
-            {
-                Some(c) => c,
-                None => sqlx::query_as::<_, Competency>(
-                    r#"
-                        INSERT INTO competencies (name, display_order)
-                        VALUES (?, ?)
-                        RETURNING *
-                        "#,
-                )
-                .bind(comp_name)
-                .bind(idx as i32)
-                .fetch_one(&mut *tx)
-                .await
-                .map_err(|e| format!("Failed to insert competency {}: {}", comp_name, e))?,
-            };
-        competency_map.insert(comp_name.clone(), competency.id);
+    // Later rows win, exactly as the upsert in `write_performance_rows` behaves
+    let mut final_scores: Vec<&ParsedScore> = Vec::new();
+    let mut positions: HashMap<(String, &str), usize> = HashMap::new();
+    for score in scores {
+        let key = (
+            normalize_name(&score.employee_name),
+            score.competency.as_str(),
+        );
+        match positions.get(&key) {
+            Some(&index) => final_scores[index] = score,
+            None => {
+                positions.insert(key, final_scores.len());
+                final_scores.push(score);
+            }
+        }
     }
 
-    // Upsert scores for this dataset
-    let mut score_count = 0usize;
-    for score in &request.scores {
-        let normalized = normalize_name(&score.employee_name);
-        let employee_id = employee_lookup
-            .get(&normalized)
-            .or_else(|| employee_lookup.get(&score.employee_name.to_lowercase()))
-            .ok_or_else(|| format!("Employee not found: {}", score.employee_name))?;
-
-        let competency_id = competency_map
-            .get(&score.competency)
-            .ok_or_else(|| format!("Competency not found: {}", score.competency))?;
-
-        let numeric_value = rating_map.get(&score.value).copied();
+    let mut comparison = ScoreComparison {
+        new_scores: Vec::new(),
+        changed_scores: Vec::new(),
+        unchanged_score_count: 0,
+    };
+    for score in final_scores {
+        let employee_name = score.employee_name.trim().to_string();
+        let mut raw_value = score.value.clone();
+        let mut numeric_value = rating_value(rating_map, &score.value);
+        let key = master
+            .get(&normalize_name(&employee_name))
+            .map(|id| (*id, score.competency.clone()));
+        let previous = key.as_ref().and_then(|key| existing.get(key));
+        if let Some(others) = key.as_ref().and_then(|key| other_entries.get(key)) {
+            let mut entries = others.clone();
+            entries.push((raw_value.clone(), numeric_value));
+            if let Some((raw, numeric)) = combine_entries(aggregation, &entries) {
+                raw_value = raw;
+                numeric_value = Some(numeric);
+            }
+        }
 
-        sqlx::query(
-            r#"
-            INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, created_at)
-            VALUES (?, ?, ?, ?, ?, datetime('now'))
-            ON CONFLICT(dataset_id, employee_id, competency_id) DO UPDATE
-            SET raw_value = excluded.raw_value,
-                numeric_value = excluded.numeric_value
-            "#,
-        )
-        .bind(employee_id)
-        .bind(dataset.id)
-        .bind(competency_id)
-        .bind(&score.value)
-        .bind(numeric_value)
-        .execute(&mut *tx)
-        .await
-        .map_err(|e| format!("Failed to upsert score: {}", e))?;
-        score_count += 1;
+        match previous {
+            None => comparison.new_scores.push(ScorePreviewEntry {
+                employee_name,
+                competency: score.competency.clone(),
+                raw_value,
+                numeric_value,
+            }),
+            Some((raw, numeric)) if *raw == raw_value && *numeric == numeric_value => {
+                comparison.unchanged_score_count += 1;
+            }
+            Some((raw, numeric)) => comparison.changed_scores.push(ScoreChangePreview {
+                employee_name,
+                competency: score.competency.clone(),
+                old_raw_value: raw.clone(),
+                new_raw_value: raw_value,
+                old_numeric_value: *numeric,
+                new_numeric_value: numeric_value,
+                source_row: score.source_row,
+                source_column: score.source_column.clone(),
+            }),
+        }
     }
 
-    tx.commit()
-        .await
-        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
-
-    Ok(ImportResult {
-        dataset,
-        employee_count: unique_employee_ids.len(),
-        competency_count: competency_map.len(),
-        score_count,
-    })
+    Ok(comparison)
 }
 
 #[tauri::command]
@@ -607,6 +1734,7 @@ pub async fn append_dataset_employees(
     state: State<'_, AppState>,
     request: DatasetEmployeeAppendRequest,
 ) -> Result<DatasetEmployeeAppendResult, String> {
+    state.require_role(Role::Editor).await?;
     if request.employees.is_empty() {
         return Ok(DatasetEmployeeAppendResult {
             created: 0,
@@ -615,7 +1743,11 @@ pub async fn append_dataset_employees(
         });
     }
 
+    let started_at = Utc::now();
     let pool = state.pool.clone();
+    let _lock = state
+        .dataset_locks
+        .acquire("append-employees", &[request.dataset_id])?;
     let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
 
     sqlx::query_scalar::<_, i64>("SELECT id FROM datasets WHERE id = ? LIMIT 1")
@@ -627,6 +1759,7 @@ pub async fn append_dataset_employees(
     #[derive(Clone)]
     struct EmployeeUpsertData {
         name: String,
+        normalized_name: String,
         nip: Option<String>,
         gol: Option<String>,
         jabatan: Option<String>,
@@ -641,17 +1774,20 @@ pub async fn append_dataset_employees(
             return Err("Employee name cannot be blank".to_string());
         }
 
-        let normalized = normalize_name(trimmed);
         let data = EmployeeUpsertData {
             name: trimmed.to_string(),
-            nip: sanitize_optional(&employee.nip),
+            normalized_name: normalize_name(trimmed),
+            nip: normalize_nip(employee.nip.as_deref()),
             gol: sanitize_optional(&employee.gol),
             jabatan: sanitize_optional(&employee.jabatan),
             sub_jabatan: sanitize_optional(&employee.sub_jabatan),
         };
 
         unique_employees
-            .entry(normalized)
+            .entry(employee_identity_key(
+                data.nip.as_deref(),
+                &data.normalized_name,
+            ))
             .and_modify(|existing| {
                 if existing.nip.is_none() {
                     existing.nip = data.nip.clone();
@@ -672,19 +1808,23 @@ pub async fn append_dataset_employees(
     if unique_employees.is_empty() {
         return Err("At least one valid employee is required".to_string());
     }
+    let merged_rows = request.employees.len() - unique_employees.len();
 
     let mut created = 0usize;
     let mut updated = 0usize;
     let mut linked = 0usize;
 
-    for (normalized, data) in unique_employees {
-        let existing = sqlx::query_as::<_, Employee>(
-            r#"
-            SELECT * FROM employees WHERE lower(name) = ? LIMIT 1
-            "#,
+    let mut nip_index = load_nip_index(&mut tx)
+        .await
+        .map_err(|e| format!("Failed to load employee NIPs: {}", e))?;
+
+    for data in unique_employees.into_values() {
+        let existing = find_master_employee(
+            &mut tx,
+            &nip_index,
+            data.nip.as_deref(),
+            &data.normalized_name,
         )
-        .bind(&normalized)
-        .fetch_optional(&mut *tx)
         .await
         .map_err(|e| format!("Failed to lookup employee {}: {}", data.name, e))?;
 
@@ -749,6 +1889,9 @@ pub async fn append_dataset_employees(
             created += 1;
             created_employee
         };
+        if let Some(nip) = normalize_nip(employee.nip.as_deref()) {
+            nip_index.insert(nip, employee.id);
+        }
 
         let existing_link = sqlx::query_scalar::<_, i64>(
             r#"
@@ -807,6 +1950,22 @@ pub async fn append_dataset_employees(
     .await
     .map_err(|e| format!("Failed to update dataset timestamp: {}", e))?;
 
+    record_import_run(
+        &mut tx,
+        ImportRunRecord {
+            dataset_id: request.dataset_id,
+            kind: "employees",
+            source_file: request.source_file.as_deref(),
+            started_at,
+            row_count: request.employees.len(),
+            inserted_count: created,
+            updated_count: updated,
+            skipped_count: merged_rows,
+            validation_stats: None,
+        },
+    )
+    .await?;
+
     tx.commit()
         .await
         .map_err(|e| format!("Failed to commit transaction: {}", e))?;
@@ -818,9 +1977,27 @@ pub async fn append_dataset_employees(
     })
 }
 
+#[tauri::command]
+pub async fn list_import_runs(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+) -> Result<Vec<ImportRun>, String> {
+    sqlx::query_as::<_, ImportRun>(
+        "SELECT * FROM import_runs WHERE dataset_id = ? ORDER BY started_at DESC, id DESC",
+    )
+    .bind(dataset_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| format!("Failed to load import runs: {}", e))
+}
+
 #[tauri::command]
 pub async fn get_default_rating_mappings() -> Result<Vec<CreateRatingMapping>, String> {
-    Ok(vec![
+    Ok(default_rating_mappings())
+}
+
+pub(crate) fn default_rating_mappings() -> Vec<CreateRatingMapping> {
+    vec![
         CreateRatingMapping {
             dataset_id: 0, // Will be replaced when actually used
             text_value: "Sangat Baik".to_string(),
@@ -836,13 +2013,34 @@ pub async fn get_default_rating_mappings() -> Result<Vec<CreateRatingMapping>, S
             text_value: "Kurang Baik".to_string(),
             numeric_value: 65.0,
         },
-    ])
+    ]
 }
 
 #[tauri::command]
 pub async fn validate_import_data(
+    state: State<'_, AppState>,
     payload: ImportValidationPayload,
 ) -> Result<ImportValidationSummary, String> {
+    let canonical_jabatan = load_canonical_jabatan(&state.pool).await?;
+    let unknown_jabatan: Vec<UnknownJabatanIssue> = if canonical_jabatan.is_empty() {
+        Vec::new()
+    } else {
+        payload
+            .employees
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, employee)| {
+                let jabatan = sanitize_optional(&employee.jabatan)?;
+                (!is_canonical_jabatan(&canonical_jabatan, &jabatan)).then(|| UnknownJabatanIssue {
+                    employee_index: idx,
+                    employee_name: employee.name.clone(),
+                    suggestion: suggest_jabatan(&canonical_jabatan, &jabatan).map(str::to_string),
+                    jabatan,
+                })
+            })
+            .collect()
+    };
+
     let mut duplicate_employees: Vec<DuplicateEmployeeGroup> = Vec::new();
     let mut orphan_scores: Vec<OrphanScoreIssue> = Vec::new();
     let mut unmapped_ratings: Vec<UnmappedRatingIssue> = Vec::new();
@@ -902,7 +2100,7 @@ pub async fn validate_import_data(
         }
 
         let value_key = score.value.trim().to_lowercase();
-        if !value_key.is_empty() && !rating_map.contains_key(&value_key) {
+        if !value_key.is_empty() && rating_value(&rating_map, &value_key).is_none() {
             *unmapped_counts.entry(score.value.clone()).or_insert(0) += 1;
         }
     }
@@ -916,10 +2114,12 @@ pub async fn validate_import_data(
         + unmapped_ratings.len()
         + blank_employee_names.len();
 
+    let warning_count = unknown_jabatan.len();
+
     let validation_stats = ValidationStats {
         error_count,
-        warning_count: 0,
-        total_issues: error_count,
+        warning_count,
+        total_issues: error_count + warning_count,
         can_import: error_count == 0,
     };
 
@@ -929,5 +2129,338 @@ pub async fn validate_import_data(
         orphan_scores,
         unmapped_ratings,
         blank_employee_names,
+        unknown_jabatan,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{seed_datasets, seed_employees, test_pool};
+
+    #[tokio::test]
+    async fn test_score_conflicts_list_changed_cells_only() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO datasets (id, name, created_at, updated_at)
+             VALUES (1, 'Semester 1', datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, created_at, updated_at)
+             VALUES (1, 'Budi', datetime('now'), datetime('now'));
+             INSERT INTO competencies (id, name, display_order)
+             VALUES (1, 'Kerjasama', 0), (2, 'Integritas', 1);
+             INSERT INTO rating_mappings (dataset_id, text_value, numeric_value)
+             VALUES (1, 'Baik', 75.0), (1, 'Sangat Baik', 85.0);
+             INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, created_at)
+             VALUES (1, 1, 1, 'Baik', 75.0, datetime('now')),
+                    (1, 1, 2, 'Baik', 75.0, datetime('now'));
+             INSERT INTO score_entries (dataset_id, employee_id, competency_id, rater, raw_value, numeric_value)
+             VALUES (1, 1, 1, 'Atasan', 'Baik', 75.0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let score = |competency: &str, value: &str, row: i64| ParsedScore {
+            employee_name: "budi ".to_string(),
+            competency: competency.to_string(),
+            value: value.to_string(),
+            ordinal: None,
+            submitted_at: None,
+            source_row: Some(row),
+            source_column: Some("Nilai".to_string()),
+            respondent_count: None,
+        };
+        let scores = vec![
+            score("Kerjasama", "Sangat Baik", 2),
+            score("Integritas", "Baik", 3),
+            score("Inisiatif", "Baik", 4),
+        ];
+
+        let rating_map = dataset_rating_map(&pool, 1, &[]).await.unwrap();
+        let master = master_employee_ids(&pool).await.unwrap();
+        let comparison =
+            compare_with_stored_scores(&pool, 1, &scores, "Rekan", &rating_map, &master)
+                .await
+                .unwrap();
+
+        assert_eq!(comparison.unchanged_score_count, 1);
+        assert_eq!(comparison.new_scores.len(), 1);
+        let changed: Vec<(&str, &str, Option<f64>, Option<i64>)> = comparison
+            .changed_scores
+            .iter()
+            .map(|c| {
+                (
+                    c.competency.as_str(),
+                    c.old_raw_value.as_str(),
+                    c.new_numeric_value,
+                    c.source_row,
+                )
+            })
+            .collect();
+        // The other rater's "Baik" is averaged in, as the import would store it
+        assert_eq!(changed, vec![("Kerjasama", "Baik", Some(80.0), Some(2))]);
+    }
+
+    #[tokio::test]
+    async fn test_xlsx_import_reads_the_chosen_sheet() {
+        let pool = test_pool().await;
+        seed_employees(&pool, &["Budi", "Sari"]).await;
+
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("epa-import.xlsx");
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        workbook
+            .add_worksheet()
+            .set_name("Petunjuk")
+            .unwrap()
+            .write_string(0, 0, "Isi lembar Nilai")
+            .unwrap();
+        let sheet = workbook.add_worksheet().set_name("Nilai").unwrap();
+        for (row, values) in [
+            ["Nama", "Kompetensi", "Nilai"],
+            ["Budi", "Kerjasama", "Baik"],
+            ["Budi", "Integritas", "Sangat Baik"],
+            ["Sari", "Kerjasama", "Kurang Baik"],
+        ]
+        .iter()
+        .enumerate()
+        {
+            for (col, value) in values.iter().enumerate() {
+                sheet.write_string(row as u32, col as u16, *value).unwrap();
+            }
+        }
+        workbook.save(&path).unwrap();
+
+        let first_sheet = SourceTable::open_workbook(&path, None).unwrap();
+        assert!(first_sheet.score_rows().is_err());
+        assert!(SourceTable::open_workbook(&path, Some("Rekap")).is_err());
+
+        let table = SourceTable::open_workbook(&path, Some("Nilai")).unwrap();
+        let request = xlsx_import_request(&path, XlsxImportOptions::default());
+        let result =
+            create_performance_dataset(&pool, &request, ScoreSource::Table(&table), Instant::now())
+                .await
+                .unwrap();
+
+        assert_eq!(result.dataset.name, "epa-import");
+        assert_eq!((result.employee_count, result.score_count), (2, 3));
+        let values: Vec<(String, Option<f64>)> = sqlx::query_as(
+            "SELECT raw_value, numeric_value FROM scores WHERE dataset_id = ? ORDER BY numeric_value",
+        )
+        .bind(result.dataset.id)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            values,
+            vec![
+                ("Kurang Baik".to_string(), Some(65.0)),
+                ("Baik".to_string(), Some(75.0)),
+                ("Sangat Baik".to_string(), Some(85.0)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_preset_reads_the_score_file_with_its_delimiter_and_columns() {
+        let pool = test_pool().await;
+        sqlx::query(
+            r#"INSERT INTO employees (name, created_at, updated_at)
+                VALUES ('Budi', datetime('now'), datetime('now'));
+             INSERT INTO import_presets (id, name, delimiter, column_mapping, rating_mappings, created_at, updated_at)
+             VALUES (1, 'Rekap Biro', ';',
+                '[{"csv_column":"Nama Lengkap","db_field":"name"},
+                  {"csv_column":"Butir","db_field":"competency"},
+                  {"csv_column":"Hasil","db_field":"value"}]',
+                '[]', datetime('now'), datetime('now'))"#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("epa-preset.csv");
+        std::fs::write(
+            &path,
+            "Nama Lengkap;Butir;Hasil\nBudi;Kerjasama, Tim;Baik\nBudi;Integritas;Sangat Baik\n",
+        )
+        .unwrap();
+        let file = path.to_string_lossy().to_string();
+        let request = PerformanceImportRequest {
+            dataset_name: "Semester 1".to_string(),
+            dataset_description: None,
+            source_file: file.clone(),
+            period_year: None,
+            period_label: None,
+            employee_names: Vec::new(),
+            scores: Vec::new(),
+            scores_file: Some(file.clone()),
+            aggregation: None,
+            delimiter: None,
+            encoding: None,
+            rating_mappings: default_rating_mappings(),
+            rater: None,
+            dry_run: false,
+            validation_stats: None,
+            preset_id: Some(1),
+        };
+        let source = score_source(&[], Some(&file), None, ReadOptions::default()).unwrap();
+        let result = create_performance_dataset(&pool, &request, source, Instant::now())
+            .await
+            .unwrap();
+
+        let competencies: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM competencies ORDER BY name")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(competencies, vec!["Integritas", "Kerjasama, Tim"]);
+        assert_eq!((result.employee_count, result.score_count), (1, 2));
+    }
+
+    #[tokio::test]
+    async fn test_write_performance_rows_stages_score_file() {
+        let pool = test_pool().await;
+        seed_datasets(&pool, &["Semester 1"]).await;
+        seed_employees(&pool, &["Budi", "Sari"]).await;
+
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("epa-staging.csv");
+        std::fs::write(
+            &path,
+            "Timestamp,Nama,Kompetensi,Nilai\n\
+             01/02/2024 10:00:00,Budi,Kepemimpinan,Baik\n\
+             01/02/2024 11:00:00,budi ,Kepemimpinan,Sangat Baik\n\
+             01/02/2024 12:00:00,Sari,Komunikasi,Baik\n\
+             01/02/2024 12:00:00,Sari,Kepemimpinan,Cukup\n",
+        )
+        .unwrap();
+
+        let mut tx = pool.begin().await.unwrap();
+        let stats = write_performance_rows(
+            &mut tx,
+            1,
+            &default_rating_mappings(),
+            &[],
+            ScoreSource::File {
+                path: &path,
+                options: ReadOptions::default(),
+                columns: &[],
+                aggregation: None,
+            },
+            Some(PerformanceRun {
+                kind: "append",
+                source_file: None,
+                rater: None,
+                preset: None,
+                started_at: Utc::now(),
+                skipped_rows: 0,
+                validation_stats: None,
+            }),
+        )
+        .await
+        .unwrap();
+        tx.commit().await.unwrap();
+
+        assert_eq!(
+            (
+                stats.employee_count,
+                stats.score_count,
+                stats.overwritten_rows
+            ),
+            (2, 4, 1)
+        );
+        let kept: (String, Option<i64>) = sqlx::query_as(
+            "SELECT s.raw_value, s.respondent_count FROM scores s
+             JOIN employees e ON e.id = s.employee_id WHERE e.name = 'Budi'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(kept, ("Sangat Baik".to_string(), Some(2)));
+        let provenance: (Option<i64>, Option<String>, Option<i64>) = sqlx::query_as(
+            "SELECT s.source_row, s.source_column, s.import_run_id FROM scores s
+             JOIN employees e ON e.id = s.employee_id WHERE e.name = 'Budi'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let run_id: i64 = sqlx::query_scalar("SELECT id FROM import_runs")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(
+            provenance,
+            (Some(3), Some("Nilai".to_string()), Some(run_id))
+        );
+        let staged: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM import_staging")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(staged, 0);
+        let issues: Vec<String> =
+            sqlx::query_scalar("SELECT issue_type FROM validation_issues WHERE dataset_id = 1")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(issues, vec!["unmapped_rating".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_second_rater_is_aggregated_with_the_first() {
+        let pool = test_pool().await;
+        seed_datasets(&pool, &["Semester 1"]).await;
+        seed_employees(&pool, &["Budi"]).await;
+
+        // Atasan imports again, which replaces their first entry
+        for (rater, value) in [
+            ("Atasan", "Kurang Baik"),
+            ("Rekan", "Sangat Baik"),
+            ("Atasan", "Baik"),
+        ] {
+            let scores = vec![ParsedScore {
+                employee_name: "Budi".to_string(),
+                competency: "Integritas".to_string(),
+                value: value.to_string(),
+                ordinal: None,
+                submitted_at: None,
+                source_row: None,
+                source_column: None,
+                respondent_count: None,
+            }];
+            let mut tx = pool.begin().await.unwrap();
+            write_performance_rows(
+                &mut tx,
+                1,
+                &default_rating_mappings(),
+                &[],
+                ScoreSource::Payload(&scores),
+                Some(PerformanceRun {
+                    kind: "append",
+                    source_file: None,
+                    rater: Some(rater),
+                    preset: None,
+                    started_at: Utc::now(),
+                    skipped_rows: 0,
+                    validation_stats: None,
+                }),
+            )
+            .await
+            .unwrap();
+            tx.commit().await.unwrap();
+        }
+
+        let score: (String, Option<f64>) =
+            sqlx::query_as("SELECT raw_value, numeric_value FROM scores WHERE dataset_id = 1")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(score, ("80".to_string(), Some(80.0)));
+        let raters: Vec<String> =
+            sqlx::query_scalar("SELECT rater FROM score_entries ORDER BY rater")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(raters, vec!["Atasan".to_string(), "Rekan".to_string()]);
+    }
+}
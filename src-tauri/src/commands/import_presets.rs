@@ -0,0 +1,273 @@
+use crate::auth::Role;
+use crate::commands::csv::parse_delimiter;
+use crate::csv_parser::formats::mapped_field_header;
+use crate::csv_parser::ReadOptions;
+use crate::db::models::CreateRatingMapping;
+use crate::AppState;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqliteConnection};
+use tauri::State;
+
+/// Which database field a source column fills, as chosen on the mapping step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldMapping {
+    pub csv_column: String,
+    pub db_field: String,
+}
+
+/// Import choices saved under a name so the next semester's file can reuse them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportPreset {
+    pub id: i64,
+    pub name: String,
+    pub delimiter: Option<String>,
+    pub column_mapping: Vec<FieldMapping>,
+    pub rating_mappings: Vec<CreateRatingMapping>,
+    /// Raw score that normalises to 100 in datasets imported with the preset.
+    pub score_scale: Option<f64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveImportPresetRequest {
+    pub name: String,
+    #[serde(default)]
+    pub delimiter: Option<String>,
+    #[serde(default)]
+    pub column_mapping: Vec<FieldMapping>,
+    #[serde(default)]
+    pub rating_mappings: Vec<CreateRatingMapping>,
+    #[serde(default)]
+    pub score_scale: Option<f64>,
+}
+
+/// Stored row; both mappings are JSON text.
+#[derive(FromRow)]
+struct ImportPresetRow {
+    id: i64,
+    name: String,
+    delimiter: Option<String>,
+    column_mapping: String,
+    rating_mappings: String,
+    score_scale: Option<f64>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<ImportPresetRow> for ImportPreset {
+    type Error = String;
+
+    fn try_from(row: ImportPresetRow) -> Result<Self, String> {
+        let column_mapping = serde_json::from_str(&row.column_mapping)
+            .map_err(|e| format!("Column mapping of preset {} is invalid: {}", row.name, e))?;
+        let rating_mappings = serde_json::from_str(&row.rating_mappings)
+            .map_err(|e| format!("Rating mappings of preset {} are invalid: {}", row.name, e))?;
+        Ok(Self {
+            id: row.id,
+            name: row.name,
+            delimiter: row.delimiter,
+            column_mapping,
+            rating_mappings,
+            score_scale: row.score_scale,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+impl ImportPreset {
+    /// How files imported with the preset are read.
+    pub(crate) fn read_options(&self) -> Result<ReadOptions, String> {
+        Ok(ReadOptions {
+            delimiter: parse_delimiter(self.delimiter.clone())?,
+            ..ReadOptions::default()
+        })
+    }
+}
+
+pub(crate) async fn load_import_preset(
+    conn: &mut SqliteConnection,
+    id: i64,
+) -> Result<ImportPreset, String> {
+    sqlx::query_as::<_, ImportPresetRow>("SELECT * FROM import_presets WHERE id = ?")
+        .bind(id)
+        .fetch_optional(conn)
+        .await
+        .map_err(|e| format!("Failed to load import preset: {}", e))?
+        .ok_or_else(|| format!("Import preset {} not found", id))?
+        .try_into()
+}
+
+/// Checks and tidies a preset before it is stored.
+fn clean_preset(request: SaveImportPresetRequest) -> Result<SaveImportPresetRequest, String> {
+    let name = request.name.trim().to_string();
+    if name.is_empty() {
+        return Err("Preset name is required".to_string());
+    }
+    let delimiter = request.delimiter.filter(|value| !value.is_empty());
+    parse_delimiter(delimiter.clone())?;
+    if let Some(scale) = request.score_scale {
+        if !scale.is_finite() || scale <= 0.0 {
+            return Err("Score scale must be a positive number".to_string());
+        }
+    }
+
+    let column_mapping: Vec<FieldMapping> = request
+        .column_mapping
+        .into_iter()
+        .filter(|mapping| !mapping.csv_column.trim().is_empty())
+        .collect();
+    if let Some(mapping) = column_mapping
+        .iter()
+        .find(|mapping| mapped_field_header(&mapping.db_field).is_none())
+    {
+        return Err(format!(
+            "Unknown field in column mapping: {}",
+            mapping.db_field
+        ));
+    }
+    let rating_mappings = request
+        .rating_mappings
+        .into_iter()
+        .map(|mapping| CreateRatingMapping {
+            // Bound to the dataset when the preset is used
+            dataset_id: 0,
+            text_value: mapping.text_value.trim().to_string(),
+            numeric_value: mapping.numeric_value,
+        })
+        .filter(|mapping| !mapping.text_value.is_empty())
+        .collect();
+
+    Ok(SaveImportPresetRequest {
+        name,
+        delimiter,
+        column_mapping,
+        rating_mappings,
+        score_scale: request.score_scale,
+    })
+}
+
+async fn save_preset(
+    conn: &mut SqliteConnection,
+    request: SaveImportPresetRequest,
+) -> Result<ImportPreset, String> {
+    let preset = clean_preset(request)?;
+    let column_mapping = serde_json::to_string(&preset.column_mapping)
+        .map_err(|e| format!("Failed to serialize column mapping: {}", e))?;
+    let rating_mappings = serde_json::to_string(&preset.rating_mappings)
+        .map_err(|e| format!("Failed to serialize rating mappings: {}", e))?;
+
+    sqlx::query_as::<_, ImportPresetRow>(
+        "INSERT INTO import_presets
+            (name, delimiter, column_mapping, rating_mappings, score_scale, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, datetime('now'), datetime('now'))
+         ON CONFLICT(name) DO UPDATE SET
+            delimiter = excluded.delimiter,
+            column_mapping = excluded.column_mapping,
+            rating_mappings = excluded.rating_mappings,
+            score_scale = excluded.score_scale,
+            updated_at = excluded.updated_at
+         RETURNING *",
+    )
+    .bind(&preset.name)
+    .bind(&preset.delimiter)
+    .bind(column_mapping)
+    .bind(rating_mappings)
+    .bind(preset.score_scale)
+    .fetch_one(conn)
+    .await
+    .map_err(|e| format!("Failed to save import preset: {}", e))?
+    .try_into()
+}
+
+#[tauri::command]
+pub async fn list_import_presets(state: State<'_, AppState>) -> Result<Vec<ImportPreset>, String> {
+    sqlx::query_as::<_, ImportPresetRow>(
+        "SELECT * FROM import_presets ORDER BY name COLLATE NOCASE",
+    )
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| format!("Failed to load import presets: {}", e))?
+    .into_iter()
+    .map(ImportPreset::try_from)
+    .collect()
+}
+
+#[tauri::command]
+pub async fn get_import_preset(
+    state: State<'_, AppState>,
+    id: i64,
+) -> Result<ImportPreset, String> {
+    let mut conn = state.pool.acquire().await.map_err(|e| e.to_string())?;
+    load_import_preset(&mut conn, id).await
+}
+
+/// Saves the import choices under `name`; a preset with the same name is replaced.
+#[tauri::command]
+pub async fn save_import_preset(
+    state: State<'_, AppState>,
+    preset: SaveImportPresetRequest,
+) -> Result<ImportPreset, String> {
+    state.require_role(Role::Editor).await?;
+    let mut conn = state.pool.acquire().await.map_err(|e| e.to_string())?;
+    save_preset(&mut conn, preset).await
+}
+
+#[tauri::command]
+pub async fn delete_import_preset(state: State<'_, AppState>, id: i64) -> Result<(), String> {
+    state.require_role(Role::Editor).await?;
+    sqlx::query("DELETE FROM import_presets WHERE id = ?")
+        .bind(id)
+        .execute(&state.pool)
+        .await
+        .map_err(|e| format!("Failed to delete import preset: {}", e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    #[tokio::test]
+    async fn test_saving_a_preset_again_replaces_it() {
+        let pool = test_pool().await;
+        let mut conn = pool.acquire().await.unwrap();
+
+        let request = |scale: f64| SaveImportPresetRequest {
+            name: " Semester Ganjil ".to_string(),
+            delimiter: Some(";".to_string()),
+            column_mapping: vec![FieldMapping {
+                csv_column: "Nama Pegawai".to_string(),
+                db_field: "name".to_string(),
+            }],
+            rating_mappings: vec![CreateRatingMapping {
+                dataset_id: 9,
+                text_value: "Sangat Baik ".to_string(),
+                numeric_value: 90.0,
+            }],
+            score_scale: Some(scale),
+        };
+        let first = save_preset(&mut conn, request(4.0)).await.unwrap();
+        let second = save_preset(&mut conn, request(5.0)).await.unwrap();
+        assert_eq!(first.id, second.id);
+
+        let loaded = load_import_preset(&mut conn, first.id).await.unwrap();
+        assert_eq!(loaded.name, "Semester Ganjil");
+        assert_eq!(loaded.score_scale, Some(5.0));
+        assert_eq!(loaded.column_mapping[0].db_field, "name");
+        assert_eq!(loaded.rating_mappings[0].text_value, "Sangat Baik");
+        assert_eq!(loaded.rating_mappings[0].dataset_id, 0);
+
+        let mut invalid = request(5.0);
+        invalid.delimiter = Some(";;".to_string());
+        assert!(save_preset(&mut conn, invalid).await.is_err());
+        assert!(save_preset(&mut conn, request(-1.0)).await.is_err());
+        let mut unknown_field = request(5.0);
+        unknown_field.column_mapping[0].db_field = "alamat".to_string();
+        assert!(save_preset(&mut conn, unknown_field).await.is_err());
+    }
+}
@@ -0,0 +1,115 @@
+use crate::auth::Role;
+use crate::db::models::AuditLogEntry;
+use crate::db::{self, SchemaInfo};
+use crate::diagnostics::{self, DiagnosticsReport};
+use crate::maintenance::{self, MaintenanceReport, MaintenanceSettings};
+use crate::retention::{self, PurgeCandidate, PurgeResult, RetentionSettings};
+use crate::AppState;
+use sqlx::QueryBuilder;
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_maintenance_settings(
+    state: State<'_, AppState>,
+) -> Result<MaintenanceSettings, String> {
+    maintenance::load_settings(&state.pool)
+        .await
+        .map_err(|e| format!("Failed to load maintenance settings: {}", e))
+}
+
+#[tauri::command]
+pub async fn update_maintenance_settings(
+    state: State<'_, AppState>,
+    enabled: bool,
+    interval_hours: i64,
+) -> Result<MaintenanceSettings, String> {
+    state.require_role(Role::Admin).await?;
+    maintenance::save_settings(&state.pool, enabled, interval_hours).await
+}
+
+#[tauri::command]
+pub async fn run_database_maintenance(
+    state: State<'_, AppState>,
+) -> Result<MaintenanceReport, String> {
+    state.require_role(Role::Admin).await?;
+    maintenance::run_maintenance(&state.pool).await
+}
+
+/// Applied and pending migrations of the open database.
+#[tauri::command]
+pub async fn get_schema_info(state: State<'_, AppState>) -> Result<SchemaInfo, String> {
+    db::schema_info(&state.pool)
+        .await
+        .map_err(|e| format!("Failed to read schema version: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_retention_settings(
+    state: State<'_, AppState>,
+) -> Result<RetentionSettings, String> {
+    retention::load_settings(&state.pool)
+        .await
+        .map_err(|e| format!("Failed to load retention settings: {}", e))
+}
+
+#[tauri::command]
+pub async fn update_retention_settings(
+    state: State<'_, AppState>,
+    enabled: bool,
+    keep_periods: i64,
+) -> Result<RetentionSettings, String> {
+    state.require_role(Role::Admin).await?;
+    retention::save_settings(&state.pool, enabled, keep_periods).await
+}
+
+/// Datasets the retention policy would delete, without deleting anything.
+#[tauri::command]
+pub async fn preview_purge(state: State<'_, AppState>) -> Result<Vec<PurgeCandidate>, String> {
+    retention::preview_purge(&state.pool).await
+}
+
+/// Backs up the database, then deletes every dataset older than the retention policy that
+/// no import or merge is writing to.
+#[tauri::command]
+pub async fn apply_retention_policy(state: State<'_, AppState>) -> Result<PurgeResult, String> {
+    state.require_role(Role::Admin).await?;
+    retention::apply_retention_policy(&state.pool, &state.dataset_locks).await
+}
+
+/// Health check of the database; `repair` (admins only) first deletes orphaned rows.
+#[tauri::command]
+pub async fn run_diagnostics(
+    state: State<'_, AppState>,
+    repair: Option<bool>,
+) -> Result<DiagnosticsReport, String> {
+    let repair = repair.unwrap_or(false);
+    if repair {
+        state.require_role(Role::Admin).await?;
+    }
+    diagnostics::run_diagnostics(&state.pool, repair).await
+}
+
+#[tauri::command]
+pub async fn list_audit_log(
+    state: State<'_, AppState>,
+    action: Option<String>,
+    dataset_id: Option<i64>,
+    limit: Option<i64>,
+) -> Result<Vec<AuditLogEntry>, String> {
+    let mut query = QueryBuilder::new("SELECT * FROM audit_log WHERE 1 = 1");
+    if let Some(action) = action {
+        query.push(" AND action = ").push_bind(action);
+    }
+    if let Some(dataset_id) = dataset_id {
+        query.push(" AND dataset_id = ").push_bind(dataset_id);
+    }
+    query
+        .push(" ORDER BY created_at DESC, id DESC LIMIT ")
+        .push_bind(limit.unwrap_or(100).clamp(1, 1000));
+
+    query
+        .build_query_as::<AuditLogEntry>()
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| format!("Failed to load audit log: {}", e))
+}
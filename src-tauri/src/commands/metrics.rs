@@ -0,0 +1,217 @@
+use crate::auth::Role;
+use crate::db::audit::record_task_failure;
+use crate::AppState;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, QueryBuilder, SqlitePool};
+use std::future::Future;
+use std::time::Instant;
+use tauri::State;
+
+/// Older rows are dropped once the table grows past this many samples.
+const MAX_METRIC_ROWS: i64 = 20_000;
+/// Task name under which metrics that could not be stored are audited
+const METRICS_TASK: &str = "command_metrics";
+/// Largest batch the frontend may send in one call
+const MAX_METRIC_BATCH: usize = 100;
+const MAX_COMMAND_NAME_CHARS: usize = 64;
+/// Commands wrapped in `timed_command`; the frontend does not report them.
+const BACKEND_TIMED_COMMANDS: [&str; 4] = [
+    "import_employees",
+    "import_performance_dataset",
+    "import_performance_dataset_xlsx",
+    "import_performance_into_dataset",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandMetricEntry {
+    pub command: String,
+    pub duration_ms: f64,
+    /// Length of the result when the command returned a list
+    pub row_count: Option<i64>,
+    #[serde(default)]
+    pub failed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SlowCommand {
+    pub command: String,
+    pub call_count: i64,
+    pub average_ms: f64,
+    pub max_ms: f64,
+    pub average_rows: Option<f64>,
+    pub failure_count: i64,
+    pub last_called_at: DateTime<Utc>,
+}
+
+/// Stores a batch of timings collected by the frontend around each `invoke`.
+#[tauri::command]
+pub async fn record_command_metrics(
+    state: State<'_, AppState>,
+    entries: Vec<CommandMetricEntry>,
+) -> Result<(), String> {
+    state.require_role(Role::Viewer).await?;
+    if entries.is_empty() {
+        return Ok(());
+    }
+    validate_entries(&entries)?;
+    store_metrics(&state.pool, &entries).await
+}
+
+/// Rejects batches that could not come from the frontend's `invoke` wrapper.
+fn validate_entries(entries: &[CommandMetricEntry]) -> Result<(), String> {
+    if entries.len() > MAX_METRIC_BATCH {
+        return Err(format!(
+            "At most {} command metrics can be recorded at once",
+            MAX_METRIC_BATCH
+        ));
+    }
+    for entry in entries {
+        let name_valid = !entry.command.is_empty()
+            && entry.command.chars().count() <= MAX_COMMAND_NAME_CHARS
+            && entry
+                .command
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !name_valid || BACKEND_TIMED_COMMANDS.contains(&entry.command.as_str()) {
+            return Err(format!("Invalid command name: {}", entry.command));
+        }
+        if !entry.duration_ms.is_finite() || entry.duration_ms < 0.0 {
+            return Err(format!("Invalid duration for {}", entry.command));
+        }
+        if entry.row_count.is_some_and(|rows| rows < 0) {
+            return Err(format!("Invalid row count for {}", entry.command));
+        }
+    }
+    Ok(())
+}
+
+/// Runs a command body on the backend and records how long it took, failed runs included,
+/// for the `BACKEND_TIMED_COMMANDS`, whose timing the frontend does not record. Each of
+/// them is a thin `#[tauri::command]` that hands its body to this function.
+pub(crate) async fn timed_command<T>(
+    pool: &SqlitePool,
+    command: &str,
+    row_count: impl FnOnce(&T) -> usize,
+    run: impl Future<Output = Result<T, String>>,
+) -> Result<T, String> {
+    let started = Instant::now();
+    let result = run.await;
+    let entry = CommandMetricEntry {
+        command: command.to_string(),
+        duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+        row_count: result.as_ref().ok().map(|value| row_count(value) as i64),
+        failed: result.is_err(),
+    };
+    if let Err(error) = store_metrics(pool, &[entry]).await {
+        record_task_failure(pool, METRICS_TASK, &error).await;
+    }
+    result
+}
+
+async fn store_metrics(pool: &SqlitePool, entries: &[CommandMetricEntry]) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let mut insert =
+        QueryBuilder::new("INSERT INTO command_metrics (command, duration_ms, row_count, failed) ");
+    insert.push_values(entries, |mut row, entry| {
+        row.push_bind(&entry.command)
+            .push_bind(entry.duration_ms.max(0.0))
+            .push_bind(entry.row_count)
+            .push_bind(entry.failed);
+    });
+    insert
+        .build()
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to record command metrics: {}", e))?;
+
+    sqlx::query(
+        "DELETE FROM command_metrics WHERE id <= (SELECT MAX(id) FROM command_metrics) - ?",
+    )
+    .bind(MAX_METRIC_ROWS)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to prune command metrics: {}", e))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))
+}
+
+/// Commands ordered by average duration, slowest first.
+#[tauri::command]
+pub async fn get_slow_commands(
+    state: State<'_, AppState>,
+    limit: Option<i64>,
+) -> Result<Vec<SlowCommand>, String> {
+    sqlx::query_as::<_, SlowCommand>(
+        "SELECT command,
+                COUNT(*) AS call_count,
+                AVG(duration_ms) AS average_ms,
+                MAX(duration_ms) AS max_ms,
+                AVG(row_count) AS average_rows,
+                SUM(failed) AS failure_count,
+                MAX(recorded_at) AS last_called_at
+         FROM command_metrics
+         GROUP BY command
+         ORDER BY average_ms DESC
+         LIMIT ?",
+    )
+    .bind(limit.unwrap_or(20).clamp(1, 200))
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| format!("Failed to load command metrics: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    #[tokio::test]
+    async fn test_timed_command_records_failed_runs() {
+        let pool = test_pool().await;
+
+        let imported = timed_command(
+            &pool,
+            "import_employees",
+            |rows: &Vec<i64>| rows.len(),
+            async { Ok(vec![1, 2, 3]) },
+        )
+        .await;
+        let failed = timed_command(
+            &pool,
+            "import_employees",
+            |rows: &Vec<i64>| rows.len(),
+            async { Err("Failed to read score file".to_string()) },
+        )
+        .await;
+
+        assert_eq!(imported, Ok(vec![1, 2, 3]));
+        assert!(failed.is_err());
+        let recorded: Vec<(Option<i64>, bool)> =
+            sqlx::query_as("SELECT row_count, failed FROM command_metrics ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(recorded, vec![(Some(3), false), (None, true)]);
+    }
+
+    #[test]
+    fn test_validate_entries_rejects_forged_metrics() {
+        let entry = |command: &str, duration_ms: f64, row_count: Option<i64>| CommandMetricEntry {
+            command: command.to_string(),
+            duration_ms,
+            row_count,
+            failed: false,
+        };
+
+        assert!(validate_entries(&[entry("list_employees", 12.5, Some(40))]).is_ok());
+        assert!(validate_entries(&[entry("list_employees", 12.5, Some(-1))]).is_err());
+        assert!(validate_entries(&[entry("list_employees", f64::NAN, None)]).is_err());
+        assert!(validate_entries(&[entry("list employees; --", 1.0, None)]).is_err());
+        assert!(validate_entries(&[entry("import_employees", 1.0, Some(3))]).is_err());
+        let batch = vec![entry("list_employees", 1.0, None); MAX_METRIC_BATCH + 1];
+        assert!(validate_entries(&batch).is_err());
+    }
+}
@@ -1,8 +1,26 @@
 pub mod analytics;
+pub mod api_server;
+pub mod attachments;
+pub mod attendance;
+pub mod auth;
+pub mod backup;
+pub mod bundle;
+pub mod competency;
 pub mod csv;
 pub mod dataset;
 pub mod employee;
+pub mod encryption;
 pub mod export;
 pub mod import;
+pub mod import_presets;
+pub mod maintenance;
+pub mod metrics;
+pub mod org_structure;
 pub mod report;
+pub mod score;
+pub mod scoring;
+pub mod search;
 pub mod summaries;
+pub mod ui_config;
+pub mod webhooks;
+pub mod workspace;
@@ -0,0 +1,316 @@
+use crate::auth::Role;
+use crate::commands::csv::read_options;
+use crate::csv_parser::org_structure::parse_org_structure;
+use crate::db::audit::record_audit;
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::State;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrgStructureImportResult {
+    pub unit_count: usize,
+    pub jabatan_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct JabatanReference {
+    pub id: i64,
+    pub name: String,
+    pub unit_id: Option<i64>,
+    pub unit_name: Option<String>,
+    pub eselon: Option<String>,
+}
+
+/// Employee whose jabatan is not in the SOTK reference, with the closest canonical name
+/// when one is near enough to be a typo.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EmployeeJabatanIssue {
+    pub employee_id: i64,
+    pub employee_name: String,
+    pub jabatan: String,
+    #[sqlx(skip)]
+    pub suggestion: Option<String>,
+}
+
+/// Canonical jabatan names from the SOTK reference; empty when none has been imported.
+pub(crate) async fn load_canonical_jabatan(pool: &SqlitePool) -> Result<Vec<String>, String> {
+    sqlx::query_scalar("SELECT DISTINCT name FROM jabatan_reference ORDER BY name")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to load jabatan reference: {}", e))
+}
+
+fn normalize_jabatan(value: &str) -> String {
+    value
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Whether `jabatan` is one of the canonical names, ignoring case and spacing.
+pub(crate) fn is_canonical_jabatan(canonical: &[String], jabatan: &str) -> bool {
+    let normalized = normalize_jabatan(jabatan);
+    canonical
+        .iter()
+        .any(|name| normalize_jabatan(name) == normalized)
+}
+
+/// Closest canonical name to `jabatan`. Differences in case or spacing always match;
+/// otherwise up to one edit per five characters is treated as a typo.
+pub(crate) fn suggest_jabatan<'a>(canonical: &'a [String], jabatan: &str) -> Option<&'a str> {
+    let normalized = normalize_jabatan(jabatan);
+    canonical
+        .iter()
+        .map(|name| {
+            let candidate = normalize_jabatan(name);
+            (name, edit_distance(&normalized, &candidate), candidate)
+        })
+        .filter(|(_, distance, candidate)| distance * 5 <= candidate.chars().count())
+        .min_by_key(|(_, distance, _)| *distance)
+        .map(|(name, _, _)| name.as_str())
+}
+
+async fn find_jabatan_issues(pool: &SqlitePool) -> Result<Vec<EmployeeJabatanIssue>, String> {
+    let canonical = load_canonical_jabatan(pool).await?;
+    if canonical.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let employees = sqlx::query_as::<_, EmployeeJabatanIssue>(
+        "SELECT id AS employee_id, name AS employee_name, jabatan
+         FROM employees
+         WHERE jabatan IS NOT NULL AND TRIM(jabatan) <> ''
+         ORDER BY name",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to load employees: {}", e))?;
+
+    Ok(employees
+        .into_iter()
+        .filter(|employee| !is_canonical_jabatan(&canonical, &employee.jabatan))
+        .map(|employee| EmployeeJabatanIssue {
+            suggestion: suggest_jabatan(&canonical, &employee.jabatan).map(str::to_string),
+            ..employee
+        })
+        .collect())
+}
+
+/// Replaces the unit and jabatan reference tables with the contents of an SOTK file.
+#[tauri::command]
+pub async fn import_org_structure(
+    state: State<'_, AppState>,
+    file_path: String,
+    delimiter: Option<String>,
+    encoding: Option<String>,
+) -> Result<OrgStructureImportResult, String> {
+    state.require_role(Role::Editor).await?;
+    let options = read_options(delimiter, encoding)?;
+    let structure = parse_org_structure(Path::new(&file_path), options)
+        .map_err(|e| format!("Failed to parse organization structure: {}", e))?;
+    if structure.units.is_empty() && structure.positions.is_empty() {
+        return Err("Organization structure file has no units or jabatan".to_string());
+    }
+
+    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+
+    sqlx::query("DELETE FROM jabatan_reference")
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to clear jabatan reference: {}", e))?;
+    sqlx::query("DELETE FROM org_units")
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to clear units: {}", e))?;
+
+    let mut unit_ids: HashMap<String, i64> = HashMap::new();
+    for unit in &structure.units {
+        let id = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO org_units (name, code) VALUES (?, ?) RETURNING id",
+        )
+        .bind(&unit.name)
+        .bind(&unit.code)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to insert unit {}: {}", unit.name, e))?;
+        unit_ids.insert(unit.name.to_lowercase(), id);
+    }
+
+    for unit in &structure.units {
+        if let Some(parent_id) = unit
+            .parent
+            .as_ref()
+            .and_then(|parent| unit_ids.get(&parent.to_lowercase()))
+        {
+            sqlx::query("UPDATE org_units SET parent_id = ? WHERE id = ?")
+                .bind(parent_id)
+                .bind(unit_ids[&unit.name.to_lowercase()])
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to link unit {}: {}", unit.name, e))?;
+        }
+    }
+
+    for position in &structure.positions {
+        let unit_id = position
+            .unit
+            .as_ref()
+            .and_then(|unit| unit_ids.get(&unit.to_lowercase()));
+        sqlx::query("INSERT INTO jabatan_reference (name, unit_id, eselon) VALUES (?, ?, ?)")
+            .bind(&position.name)
+            .bind(unit_id)
+            .bind(&position.eselon)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to insert jabatan {}: {}", position.name, e))?;
+    }
+
+    let result = OrgStructureImportResult {
+        unit_count: structure.units.len(),
+        jabatan_count: structure.positions.len(),
+    };
+
+    record_audit(
+        &mut *tx,
+        "org_structure_imported",
+        None,
+        &serde_json::json!({
+            "file_path": file_path,
+            "unit_count": result.unit_count,
+            "jabatan_count": result.jabatan_count,
+        }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn list_jabatan_reference(
+    state: State<'_, AppState>,
+) -> Result<Vec<JabatanReference>, String> {
+    sqlx::query_as::<_, JabatanReference>(
+        "SELECT j.id, j.name, j.unit_id, u.name AS unit_name, j.eselon
+         FROM jabatan_reference j
+         LEFT JOIN org_units u ON u.id = j.unit_id
+         ORDER BY u.name, j.name",
+    )
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| format!("Failed to load jabatan reference: {}", e))
+}
+
+#[tauri::command]
+pub async fn list_jabatan_issues(
+    state: State<'_, AppState>,
+) -> Result<Vec<EmployeeJabatanIssue>, String> {
+    find_jabatan_issues(&state.pool).await
+}
+
+/// Rewrites employee jabatan to their suggested canonical names, limited to
+/// `employee_ids` when given. Returns the number of employees updated.
+#[tauri::command]
+pub async fn apply_jabatan_suggestions(
+    state: State<'_, AppState>,
+    employee_ids: Option<Vec<i64>>,
+) -> Result<u64, String> {
+    state.require_role(Role::Editor).await?;
+    let corrections: Vec<(EmployeeJabatanIssue, String)> = find_jabatan_issues(&state.pool)
+        .await?
+        .into_iter()
+        .filter(|issue| {
+            employee_ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&issue.employee_id))
+        })
+        .filter_map(|issue| {
+            let suggestion = issue.suggestion.clone()?;
+            Some((issue, suggestion))
+        })
+        .collect();
+    if corrections.is_empty() {
+        return Ok(0);
+    }
+
+    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+    for (issue, suggestion) in &corrections {
+        sqlx::query("UPDATE employees SET jabatan = ?, updated_at = datetime('now') WHERE id = ?")
+            .bind(suggestion)
+            .bind(issue.employee_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to update employee {}: {}", issue.employee_id, e))?;
+    }
+
+    record_audit(
+        &mut *tx,
+        "jabatan_corrected",
+        None,
+        &serde_json::json!({
+            "corrections": corrections
+                .iter()
+                .map(|(issue, suggestion)| serde_json::json!({
+                    "employee_id": issue.employee_id,
+                    "from": issue.jabatan,
+                    "to": suggestion,
+                }))
+                .collect::<Vec<_>>(),
+        }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(corrections.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggest_jabatan_matches_typos_only() {
+        let canonical = vec![
+            "Kepala Sub Bagian Umum".to_string(),
+            "Kepala Sub Bagian Keuangan".to_string(),
+            "Pekerja Sosial".to_string(),
+        ];
+        assert_eq!(
+            suggest_jabatan(&canonical, "Kepala Sub Bagia Umum"),
+            Some("Kepala Sub Bagian Umum")
+        );
+        assert_eq!(
+            suggest_jabatan(&canonical, "  pekerja   SOSIAL "),
+            Some("Pekerja Sosial")
+        );
+        assert_eq!(suggest_jabatan(&canonical, "Bendahara"), None);
+        assert!(is_canonical_jabatan(&canonical, " pekerja  sosial"));
+        assert!(!is_canonical_jabatan(&canonical, "Pekerja Sosia"));
+    }
+}
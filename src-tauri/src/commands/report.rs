@@ -1,265 +1,266 @@
-use crate::commands::analytics::compute_employee_performance;
-use crate::db::models::{Dataset, Employee};
+use crate::auth::Role;
+use crate::commands::analytics::{
+    compute_employee_performance, compute_rankings, EmployeePerformance, EmployeeRanking,
+};
+use crate::commands::dataset::dataset_period_year;
+use crate::commands::employee::fetch_dataset_notes;
+use crate::commands::export::{employee_pseudonyms, pseudonymize};
+use crate::data_quality::assess_dataset;
+use crate::db::data_directory;
+use crate::db::models::{Dataset, Employee, EmployeeNote};
+use crate::i18n::{fill, Messages};
+use crate::pdf::filename::resolve_export_path;
+use crate::pdf::letterhead::{
+    draw_letterhead, draw_running_header, draw_signature, load_report_settings,
+    save_report_settings, signature_height, store_logo, ReportSettings,
+};
+use crate::pdf::paper::{Orientation, PageLayout, PaperSettings};
+use crate::pdf::table::{Align, Column, RowStyle, Table};
+use crate::pdf::{Canvas, Font, Pdf};
+use crate::photos::open_photo;
+use crate::scoring::weighting::{
+    load_weighting_scheme, ParameterWeight, SectionWeights, WeightingScheme,
+};
+use crate::scoring::{
+    compute_weighted_score, CompetencyScore, ComponentSection, PositionType, WeightedScore,
+};
+use crate::ui_config::{load_dataset_ui_config, RatingBand, UiConfig};
 use crate::AppState;
-use chrono::Datelike;
-use pdf_canvas::{BuiltinFont, Canvas, Pdf};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use image::{DynamicImage, ImageFormat};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::path::Path;
 use tauri::State;
-use unicode_normalization::UnicodeNormalization;
 
-#[derive(Clone)]
-struct WeightedParameter {
-    parameter: &'static str,
-    weight: f64,
-    aliases: &'static [&'static str],
-}
-
-#[derive(Clone)]
-struct DualWeightedParameter {
-    parameter: &'static str,
-    eselon_weight: f64,
-    staff_weight: f64,
-    aliases: &'static [&'static str],
-}
-
-#[derive(Clone)]
-struct ScoreComponent {
-    parameter: String,
-    raw_score: f64,
-    weight_percentage: f64,
-    weighted_score: f64,
-}
-
-#[derive(Clone)]
-struct ComponentResult {
-    subtotal: f64,
-    breakdown: Vec<ScoreComponent>,
-}
-
-#[derive(Clone)]
-struct LeadershipScoreResult {
-    raw_score: f64,
-    weighted_score: f64,
-    applied: bool,
-}
-
-#[derive(Clone)]
-struct ComponentSection {
-    title: String,
-    cap: f64,
-    subtotal: f64,
-    breakdown: Vec<ScoreComponent>,
-}
-
-#[derive(Clone)]
-struct CompetencyScore {
-    name: String,
-    raw_score: f64,
-    original_score: f64,
-}
-
-#[derive(Clone)]
-struct EmployeeReportContext {
+/// Everything a report prints. Serialized for `get_employee_report_preview`.
+#[derive(Clone, Serialize)]
+pub struct EmployeeReportContext {
     dataset: Dataset,
     employee: Employee,
     position_type: PositionType,
     normalization_scale: f64,
     competencies: Vec<CompetencyScore>,
     component_sections: Vec<ComponentSection>,
+    /// Caps and shares of the weighting scheme the score was computed with
+    section_weights: SectionWeights,
+    /// Parameters the cover lists under each section, from the same weighting scheme; the
+    /// leadership section has none
+    section_parameters: [Vec<String>; 3],
     total_score: f64,
     rating: String,
     strengths: Vec<String>,
     gaps: Vec<String>,
     average_score: f64,
+    unmapped_competencies: Vec<String>,
+    /// The dataset's own bands; empty when the cover lists the standard predicates
+    rating_bands: Vec<RatingBand>,
+    /// Printed as an appendix when `ReportSettings::include_notes_appendix` is set
+    notes: Vec<EmployeeNote>,
+    #[serde(skip)]
+    photo: Option<DynamicImage>,
 }
 
-#[derive(Clone, Copy)]
-enum PositionType {
-    Eselon,
-    Staff,
+/// The figures `export_employee_report_pdf` would print, for showing the breakdown in the app.
+#[tauri::command]
+pub async fn get_employee_report_preview(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    employee_id: i64,
+) -> Result<EmployeeReportContext, String> {
+    load_report_context(&state.pool, dataset_id, employee_id).await
 }
 
-impl PositionType {
-    fn label(&self) -> &'static str {
-        match self {
-            PositionType::Eselon => "eselon",
-            PositionType::Staff => "staff",
-        }
-    }
-}
+#[tauri::command]
+pub async fn export_employee_report_pdf(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    employee_id: i64,
+    file_path: String,
+) -> Result<String, String> {
+    let pool = state.pool.clone();
 
-const PERILAKU_PARAMS: &[WeightedParameter] = &[
-    WeightedParameter {
-        parameter: "Inisiatif dan fleksibilitas",
-        weight: 5.0,
-        aliases: &["inisiatif", "initiative", "fleksibilitas", "flexibility"],
-    },
-    WeightedParameter {
-        parameter: "Kehadiran dan ketepatan waktu",
-        weight: 5.0,
-        aliases: &[
-            "kehadiran",
-            "ketepatan waktu",
-            "attendance",
-            "punctuality",
-            "absensi",
-        ],
-    },
-    WeightedParameter {
-        parameter: "Kerjasama dan team work",
-        weight: 5.0,
-        aliases: &["kerjasama", "team work", "teamwork", "kolaborasi", "team"],
-    },
-    WeightedParameter {
-        parameter: "Manajemen waktu kerja",
-        weight: 5.0,
-        aliases: &["manajemen waktu", "time management"],
-    },
-    WeightedParameter {
-        parameter: "Kepemimpinan",
-        weight: 10.0,
-        aliases: &["kepemimpinan", "leadership", "leader"],
-    },
-];
-
-const KUALITAS_PARAMS: &[DualWeightedParameter] = &[
-    DualWeightedParameter {
-        parameter: "Kualitas kinerja",
-        eselon_weight: 25.5,
-        staff_weight: 42.5,
-        aliases: &["kualitas kinerja", "kinerja", "quality of work", "quality"],
-    },
-    DualWeightedParameter {
-        parameter: "Kemampuan berkomunikasi",
-        eselon_weight: 8.5,
-        staff_weight: 8.5,
-        aliases: &["komunikasi", "communication"],
-    },
-    DualWeightedParameter {
-        parameter: "Pemahaman tentang permasalahan sosial",
-        eselon_weight: 8.5,
-        staff_weight: 8.5,
-        aliases: &[
-            "permasalahan sosial",
-            "social issues",
-            "social problem",
-            "pemahaman sosial",
-        ],
-    },
-];
-
-const PERILAKU_CAP: f64 = 25.5;
-const KUALITAS_CAP_ESELON: f64 = 42.5;
-const KUALITAS_CAP_STAFF: f64 = 70.0;
-const LEADERSHIP_CAP: f64 = 17.0;
-const TOTAL_CAP: f64 = 85.0;
-const LEADERSHIP_WEIGHT: f64 = 0.17;
-const DEFAULT_LEADERSHIP_SCORE: f64 = 80.0;
-
-const ESELON_KEYWORDS: &[&str] = &[
-    "eselon",
-    "kepala",
-    "sekretaris",
-    "kabid",
-    "kabag",
-    "kasubag",
-    "kepala seksi",
-    "kasi",
-    "koordinator",
-    "pengawas",
-    "sub bagian",
-    "subbagian",
-    "subbidang",
-    "sub bidang",
-];
-
-const STAFF_KEYWORDS: &[&str] = &["staff", "staf"];
+    let settings = load_report_settings(&pool).await?;
+    let report_context = load_report_context(&pool, dataset_id, employee_id).await?;
+    let file_path = resolve_export_path(
+        &file_path,
+        &settings.filename_template,
+        &report_context.employee,
+        &report_context.dataset.name,
+        "pdf",
+    );
+    render_report_pdf(&report_context, &settings, &file_path)?;
+    Ok(file_path)
+}
 
+/// Writes the same report as `export_employee_report_pdf` as one self-contained HTML page,
+/// for publishing on an intranet.
 #[tauri::command]
-pub async fn export_employee_report_pdf(
+pub async fn export_employee_report_html(
     state: State<'_, AppState>,
     dataset_id: i64,
     employee_id: i64,
     file_path: String,
-) -> Result<(), String> {
+) -> Result<String, String> {
     let pool = state.pool.clone();
 
+    let settings = load_report_settings(&pool).await?;
+    let report_context = load_report_context(&pool, dataset_id, employee_id).await?;
+    let file_path = resolve_export_path(
+        &file_path,
+        &settings.filename_template,
+        &report_context.employee,
+        &report_context.dataset.name,
+        "html",
+    );
+    std::fs::write(&file_path, render_report_html(&report_context, &settings)?)
+        .map_err(|e| format!("Failed to write HTML report: {}", e))?;
+    Ok(file_path)
+}
+
+/// Recap of every ranked employee in the dataset ("rekap nilai") with their component
+/// subtotals, continued over as many pages as needed and closed by the signature block.
+#[tauri::command]
+pub async fn export_dataset_ranking_pdf(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    file_path: String,
+    anonymize: Option<bool>,
+    force: Option<bool>,
+) -> Result<(), String> {
+    state.require_role(Role::Admin).await?;
+    let pool = state.pool.clone();
     let dataset = sqlx::query_as::<_, Dataset>("SELECT * FROM datasets WHERE id = ?")
         .bind(dataset_id)
         .fetch_one(&pool)
         .await
         .map_err(|e| format!("Failed to load dataset: {}", e))?;
+    assess_dataset(&pool, dataset_id)
+        .await?
+        .ensure_exportable(force.unwrap_or(false))?;
+    let mut rankings = compute_rankings(&pool, dataset_id, Some("weighted")).await?;
+    if rankings.is_empty() {
+        return Err("No ranked employees in this dataset".to_string());
+    }
+    if anonymize.unwrap_or(false) {
+        let pseudonyms = employee_pseudonyms(rankings.iter().map(|r| r.employee.id));
+        for ranking in &mut rankings {
+            pseudonymize(&mut ranking.employee, &pseudonyms);
+        }
+    }
+
+    let settings = load_report_settings(&pool).await?;
+    render_ranking_pdf(&dataset, &rankings, &settings, &file_path)
+}
+
+/// Everything the employee report shows, photo included.
+pub(crate) async fn load_report_context(
+    pool: &SqlitePool,
+    dataset_id: i64,
+    employee_id: i64,
+) -> Result<EmployeeReportContext, String> {
+    let dataset = sqlx::query_as::<_, Dataset>("SELECT * FROM datasets WHERE id = ?")
+        .bind(dataset_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to load dataset: {}", e))?;
 
-    let performance = compute_employee_performance(&pool, dataset_id, employee_id)
+    let performance = compute_employee_performance(pool, dataset_id, employee_id)
         .await
         .map_err(|e| format!("Failed to load employee performance: {}", e))?;
 
-    let report_context = build_report_context(dataset, performance);
-    render_report_pdf(&report_context, &file_path)
+    load_report_contexts(pool, &dataset, std::slice::from_ref(&performance))
+        .await?
+        .pop()
+        .ok_or_else(|| format!("Employee {} not found", employee_id))
 }
 
-fn build_report_context(
-    dataset: Dataset,
-    performance: crate::commands::analytics::EmployeePerformance,
-) -> EmployeeReportContext {
-    let (normalization_result, normalization_scale) = normalize_competencies(&performance.scores);
-    let position_type = determine_position_type(&performance.employee);
-
-    let perilaku = calculate_perilaku_kinerja(&normalization_result);
-    let kualitas = calculate_kualitas_kerja(&normalization_result, position_type);
-    let has_performance_data =
-        !normalization_result.is_empty() && (perilaku.subtotal > 0.0 || kualitas.subtotal > 0.0);
-    let leadership = compute_leadership_score(position_type, has_performance_data, None);
-    let total_score =
-        calculate_total_score(position_type, &perilaku, &kualitas, leadership.as_ref());
-    let rating = get_performance_rating(total_score).to_string();
-
-    let mut component_sections = Vec::new();
-    component_sections.push(ComponentSection {
-        title: "Perilaku Kerja (30%)".to_string(),
-        cap: PERILAKU_CAP,
-        subtotal: perilaku.subtotal,
-        breakdown: perilaku.breakdown.clone(),
-    });
-
-    component_sections.push(ComponentSection {
-        title: "Kualitas Kerja".to_string(),
-        cap: match position_type {
-            PositionType::Eselon => KUALITAS_CAP_ESELON,
-            PositionType::Staff => KUALITAS_CAP_STAFF,
-        },
-        subtotal: kualitas.subtotal,
-        breakdown: kualitas.breakdown.clone(),
-    });
-
-    if let Some(leader) = leadership.clone() {
-        component_sections.push(ComponentSection {
-            title: "Penilaian Pimpinan".to_string(),
-            cap: LEADERSHIP_CAP,
-            subtotal: leader.weighted_score,
-            breakdown: vec![ScoreComponent {
-                parameter: if leader.applied {
-                    "Nilai Pimpinan"
-                } else {
-                    "Tidak diaplikasikan"
-                }
-                .to_string(),
-                raw_score: leader.raw_score,
-                weight_percentage: 20.0,
-                weighted_score: leader.weighted_score,
-            }],
-        });
+/// As `load_report_context` for each of `performances`, loading what they share once.
+pub(crate) async fn load_report_contexts(
+    pool: &SqlitePool,
+    dataset: &Dataset,
+    performances: &[EmployeePerformance],
+) -> Result<Vec<EmployeeReportContext>, String> {
+    let config = load_dataset_ui_config(pool, dataset.id)
+        .await
+        .map_err(|e| format!("Failed to load UI config: {}", e))?;
+    let scheme = load_weighting_scheme(pool)
+        .await
+        .map_err(|e| format!("Failed to load weighting scheme: {}", e))?;
+    let mut notes: HashMap<i64, Vec<EmployeeNote>> = HashMap::new();
+    for note in fetch_dataset_notes(pool, dataset.id)
+        .await
+        .map_err(|e| format!("Failed to load employee notes: {}", e))?
+    {
+        notes.entry(note.employee_id).or_default().push(note);
     }
+    // Photos live next to the database file, which an in-memory database doesn't have
+    let has_photos = performances
+        .iter()
+        .any(|performance| performance.employee.photo_path.is_some());
+    let directory = if has_photos {
+        Some(data_directory(pool).await?)
+    } else {
+        None
+    };
 
-    let mut competencies: Vec<CompetencyScore> = performance
-        .scores
+    Ok(performances
         .iter()
-        .zip(normalization_result.iter())
-        .map(|(score, normalized)| CompetencyScore {
-            name: score.competency.name.clone(),
-            raw_score: normalized.raw_score,
-            original_score: normalized.original_score,
+        .map(|performance| {
+            let employee = &performance.employee;
+            let mut context =
+                build_report_context(dataset.clone(), performance.clone(), &config, &scheme);
+            context.notes = notes.remove(&employee.id).unwrap_or_default();
+            context.photo = directory
+                .as_deref()
+                .and_then(|directory| open_photo(directory, employee.photo_path.as_deref()));
+            context
         })
-        .collect();
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_report_settings(state: State<'_, AppState>) -> Result<ReportSettings, String> {
+    load_report_settings(&state.pool).await
+}
+
+#[tauri::command]
+pub async fn update_report_settings(
+    state: State<'_, AppState>,
+    settings: ReportSettings,
+) -> Result<ReportSettings, String> {
+    state.require_role(Role::Admin).await?;
+    save_report_settings(&state.pool, settings).await
+}
+
+/// Stores a resized copy of the image at `file_path` as the letterhead logo; `None` removes it.
+#[tauri::command]
+pub async fn set_report_logo(
+    state: State<'_, AppState>,
+    file_path: Option<String>,
+) -> Result<ReportSettings, String> {
+    state.require_role(Role::Admin).await?;
+    store_logo(&state.pool, file_path.as_deref().map(Path::new)).await
+}
+
+fn build_report_context(
+    dataset: Dataset,
+    performance: EmployeePerformance,
+    config: &UiConfig,
+    scheme: &WeightingScheme,
+) -> EmployeeReportContext {
+    let WeightedScore {
+        position_type,
+        normalization_scale,
+        mut competencies,
+        sections: component_sections,
+        total_score,
+        rating,
+        unmapped_competencies,
+        ..
+    } = compute_weighted_score(&performance, config, scheme);
 
     competencies.sort_by(|a, b| {
         b.raw_score
@@ -274,465 +275,681 @@ fn build_report_context(
         normalization_scale,
         competencies,
         component_sections,
+        section_weights: scheme.sections.clone(),
+        section_parameters: [
+            weighted_parameters(&scheme.perilaku, position_type),
+            weighted_parameters(&scheme.kualitas, position_type),
+            Vec::new(),
+        ],
         total_score,
         rating,
         strengths: performance.strengths.clone(),
         gaps: performance.gaps.clone(),
         average_score: performance.average_score,
+        unmapped_competencies,
+        rating_bands: config.rating_bands.clone(),
+        notes: Vec::new(),
+        photo: None,
     }
 }
 
-fn normalize_competencies(
-    scores: &[crate::commands::analytics::ScoreWithCompetency],
-) -> (Vec<CompetencyScore>, f64) {
-    let original_values: Vec<f64> = scores.iter().map(parse_numeric_score).collect();
-    let normalization_scale = determine_scale(&original_values);
-
-    let competencies = scores
+/// Names of the parameters that carry weight for `position_type`.
+fn weighted_parameters(parameters: &[ParameterWeight], position_type: PositionType) -> Vec<String> {
+    parameters
         .iter()
-        .zip(original_values.iter())
-        .map(|(entry, original)| {
-            let normalized = if normalization_scale <= 0.0 {
-                0.0
-            } else {
-                ((original / normalization_scale) * 100.0).clamp(0.0, 100.0)
-            };
+        .filter(|parameter| parameter.weight_for(position_type) > 0.0)
+        .map(|parameter| parameter.parameter.clone())
+        .collect()
+}
 
-            CompetencyScore {
-                name: entry.competency.name.clone(),
-                raw_score: normalized,
-                original_score: *original,
-            }
+pub(crate) fn render_report_pdf(
+    context: &EmployeeReportContext,
+    settings: &ReportSettings,
+    file_path: &str,
+) -> Result<(), String> {
+    let layout = settings.paper.layout(Orientation::Landscape);
+    let mut document =
+        Pdf::create(file_path).map_err(|e| format!("Failed to create PDF: {}", e))?;
+
+    // Page 1: Cover/criteria
+    document
+        .render_page(layout.width, layout.height, |canvas| {
+            draw_cover_page(canvas, context, settings, &layout)
         })
-        .collect();
+        .map_err(|e| format!("Failed to render cover page: {}", e))?;
 
-    (competencies, normalization_scale)
-}
+    // Page 2: Worksheet/evaluation
+    document
+        .render_page(layout.width, layout.height, |canvas| {
+            draw_worksheet_page(canvas, context, settings, &layout)
+        })
+        .map_err(|e| format!("Failed to render worksheet page: {}", e))?;
 
-fn parse_numeric_score(score: &crate::commands::analytics::ScoreWithCompetency) -> f64 {
-    if let Some(value) = score.score.numeric_value {
-        if value.is_finite() {
-            return value;
-        }
+    // Appendix: the employee's notes, continued over as many pages as they need
+    let mut remaining = appendix_notes(context, settings);
+    let mut first_line = 0;
+    let mut first_page = true;
+    while !remaining.is_empty() {
+        let mut next = (0, 0);
+        document
+            .render_page(layout.width, layout.height, |canvas| {
+                next = draw_notes_appendix(
+                    canvas, context, settings, &layout, remaining, first_line, first_page,
+                )?;
+                Ok(())
+            })
+            .map_err(|e| format!("Failed to render notes appendix: {}", e))?;
+        remaining = &remaining[next.0..];
+        first_line = next.1;
+        first_page = false;
     }
-    score
-        .score
-        .raw_value
-        .replace(',', ".")
-        .parse::<f64>()
-        .unwrap_or(0.0)
-}
 
-fn determine_scale(values: &[f64]) -> f64 {
-    let max = values
-        .iter()
-        .copied()
-        .fold(0.0_f64, |current, value| current.max(value));
-    if max <= 0.0 {
-        100.0
-    } else if max <= 5.0 {
-        4.0
-    } else if max <= 10.0 {
-        10.0
-    } else if max <= 20.0 {
-        20.0
-    } else if max <= 100.0 {
-        100.0
-    } else {
-        max
-    }
+    document
+        .finish()
+        .map_err(|e| format!("Failed to save PDF: {}", e))
 }
 
-fn calculate_perilaku_kinerja(scores: &[CompetencyScore]) -> ComponentResult {
-    let mut breakdown = Vec::new();
-
-    for param in PERILAKU_PARAMS {
-        let raw = find_competency_score(scores, param.parameter, param.aliases);
-        breakdown.push(to_component(param.parameter, raw, param.weight));
-    }
-
-    let subtotal = breakdown
+/// Cells under `Messages::ranking_headings`, one row per ranked employee.
+fn ranking_rows(rankings: &[EmployeeRanking], text: &Messages) -> Vec<[String; 9]> {
+    rankings
         .iter()
-        .map(|component| component.weighted_score)
-        .sum::<f64>()
-        .min(PERILAKU_CAP);
-
-    ComponentResult {
-        subtotal,
-        breakdown,
-    }
+        .map(|ranking| {
+            let subtotal = |index: usize| {
+                ranking
+                    .section_subtotals
+                    .get(index)
+                    .map(|value| text.number(*value))
+                    .unwrap_or_default()
+            };
+            [
+                ranking.rank.to_string(),
+                ranking.employee.name.clone(),
+                ranking
+                    .employee
+                    .nip
+                    .clone()
+                    .unwrap_or_else(|| "-".to_string()),
+                ranking
+                    .employee
+                    .jabatan
+                    .clone()
+                    .unwrap_or_else(|| "-".to_string()),
+                subtotal(0),
+                subtotal(1),
+                subtotal(2),
+                text.number(ranking.weighted_score),
+                text.predicate(&ranking.rating),
+            ]
+        })
+        .collect()
 }
 
-fn calculate_kualitas_kerja(
-    scores: &[CompetencyScore],
-    position_type: PositionType,
-) -> ComponentResult {
-    let mut breakdown = Vec::new();
-
-    for param in KUALITAS_PARAMS {
-        let raw = find_competency_score(scores, param.parameter, param.aliases);
-        let weight = match position_type {
-            PositionType::Eselon => param.eselon_weight,
-            PositionType::Staff => param.staff_weight,
-        };
-        breakdown.push(to_component(param.parameter, raw, weight));
+/// Name and jabatan share what the fixed-width columns leave of the content width.
+/// The nine ranking columns only fit across a landscape page, whatever orientation the
+/// paper settings choose for other documents.
+fn ranking_layout(paper: &PaperSettings) -> PageLayout {
+    PaperSettings {
+        orientation: None,
+        ..*paper
     }
+    .layout(Orientation::Landscape)
+}
 
-    let cap = match position_type {
-        PositionType::Eselon => KUALITAS_CAP_ESELON,
-        PositionType::Staff => KUALITAS_CAP_STAFF,
-    };
-
-    let subtotal = breakdown
-        .iter()
-        .map(|component| component.weighted_score)
-        .sum::<f64>()
-        .min(cap);
-
-    ComponentResult {
-        subtotal,
-        breakdown,
-    }
+fn ranking_table(layout: &PageLayout) -> Table {
+    let fixed = 30.0 + 115.0 + 4.0 * 62.0 + 80.0;
+    let flexible = (layout.content_width() - fixed).max(0.0);
+    let column = |width: f32, align: Align| Column { width, align };
+    Table::new(
+        layout.left,
+        vec![
+            column(30.0, Align::Center),
+            column(flexible * 0.55, Align::Left),
+            column(115.0, Align::Left),
+            column(flexible * 0.45, Align::Left),
+            column(62.0, Align::Right),
+            column(62.0, Align::Right),
+            column(62.0, Align::Right),
+            column(62.0, Align::Right),
+            column(80.0, Align::Center),
+        ],
+    )
 }
 
-fn compute_leadership_score(
-    position_type: PositionType,
-    has_performance_data: bool,
-    override_score: Option<f64>,
-) -> Option<LeadershipScoreResult> {
-    if !matches!(position_type, PositionType::Eselon) {
-        return None;
-    }
+fn render_ranking_pdf(
+    dataset: &Dataset,
+    rankings: &[EmployeeRanking],
+    settings: &ReportSettings,
+    file_path: &str,
+) -> Result<(), String> {
+    let text = settings.locale.messages();
+    let layout = ranking_layout(&settings.paper);
+    let table = ranking_table(&layout);
+    let rows = ranking_rows(rankings, text);
+    let title = fill(
+        text.ranking_title,
+        &[
+            &settings.organization_full_name.to_uppercase(),
+            &report_period_label(dataset, text).to_uppercase(),
+            &dataset_period_year(dataset),
+        ],
+    );
 
-    if !has_performance_data {
-        return Some(LeadershipScoreResult {
-            raw_score: 0.0,
-            weighted_score: 0.0,
-            applied: false,
-        });
+    let mut document =
+        Pdf::create(file_path).map_err(|e| format!("Failed to create PDF: {}", e))?;
+    let mut remaining = &rows[..];
+    let mut first_page = true;
+    let mut signed = false;
+    while !signed {
+        let mut drawn = 0;
+        document
+            .render_page(layout.width, layout.height, |canvas| {
+                (drawn, signed) = draw_ranking_page(
+                    canvas, settings, &layout, &table, &title, remaining, first_page,
+                )?;
+                Ok(())
+            })
+            .map_err(|e| format!("Failed to render ranking page: {}", e))?;
+        remaining = &remaining[drawn..];
+        first_page = false;
     }
 
-    let raw = clamp_score(override_score.unwrap_or(DEFAULT_LEADERSHIP_SCORE));
-    Some(LeadershipScoreResult {
-        raw_score: raw,
-        weighted_score: raw * LEADERSHIP_WEIGHT,
-        applied: true,
-    })
+    document
+        .finish()
+        .map_err(|e| format!("Failed to save PDF: {}", e))
 }
 
-fn calculate_total_score(
-    position_type: PositionType,
-    perilaku: &ComponentResult,
-    kualitas: &ComponentResult,
-    leadership: Option<&LeadershipScoreResult>,
-) -> f64 {
-    let leadership_contrib = if matches!(position_type, PositionType::Eselon) {
-        leadership.map(|s| s.weighted_score).unwrap_or(0.0)
+/// Draws as many of `rows` as fit under the repeated table header and, once every row is
+/// on paper and room is left, the signature block. Returns the rows drawn and whether the
+/// page was signed.
+fn draw_ranking_page(
+    canvas: &mut Canvas<'_>,
+    settings: &ReportSettings,
+    layout: &PageLayout,
+    table: &Table,
+    title: &str,
+    rows: &[[String; 9]],
+    first_page: bool,
+) -> std::io::Result<(usize, bool)> {
+    let mut y = if first_page {
+        draw_letterhead(canvas, settings, layout.left, layout.top)?
     } else {
-        0.0
+        draw_running_header(canvas, settings, layout.width, layout.height)?;
+        layout.top
     };
-
-    (perilaku.subtotal + kualitas.subtotal + leadership_contrib).min(TOTAL_CAP)
-}
-
-fn get_performance_rating(total_score: f64) -> &'static str {
-    if total_score >= 80.0 {
-        "Sangat Baik"
-    } else if total_score >= 70.0 {
-        "Baik"
-    } else if total_score >= 60.0 {
-        "Kurang Baik"
-    } else {
-        "Perlu Pembinaan"
-    }
-}
-
-fn to_component(parameter: &str, raw_score: f64, weight_percentage: f64) -> ScoreComponent {
-    ScoreComponent {
-        parameter: parameter.to_string(),
-        raw_score,
-        weight_percentage,
-        weighted_score: (raw_score * weight_percentage) / 100.0,
+    if first_page {
+        for line in canvas.wrap_to_width(Font::Bold, 12.0, title, layout.content_width() - 100.0) {
+            canvas.center_text(layout.center_x(), y, Font::Bold, 12.0, &line)?;
+            y -= 14.0;
+        }
+        y -= 6.0;
     }
-}
 
-fn find_competency_score(scores: &[CompetencyScore], parameter: &str, aliases: &[&str]) -> f64 {
-    if scores.is_empty() {
-        return 0.0;
+    let mut drawn = 0;
+    if !rows.is_empty() {
+        let headings = &settings.locale.messages().ranking_headings;
+        y = table.draw_row(canvas, y, headings, RowStyle::header(9.0))?;
+        let style = RowStyle::body(Font::Regular, 9.0);
+        for row in rows {
+            let cells: Vec<&str> = row.iter().map(String::as_str).collect();
+            // A row taller than the page is still drawn rather than never fitting
+            if drawn > 0 && y - table.row_height(canvas, &cells, style) < layout.bottom {
+                break;
+            }
+            y = table.draw_row(canvas, y, &cells, style)?;
+            drawn += 1;
+        }
     }
 
-    let mut targets: Vec<String> = Vec::with_capacity(1 + aliases.len());
-    targets.push(normalize_text(parameter));
-    for alias in aliases {
-        targets.push(normalize_text(alias));
+    // A page left with no rows is signed whatever its size, so the loop always ends
+    let signed = drawn == rows.len()
+        && (rows.is_empty() || y - 30.0 - signature_height(settings) >= layout.bottom);
+    if signed {
+        draw_signature(canvas, settings, layout.right, y - 30.0)?;
     }
+    Ok((drawn, signed))
+}
 
-    for score in scores {
-        let normalized_name = normalize_text(&score.name);
-        if targets.iter().any(|token| normalized_name.contains(token)) {
-            return clamp_score(score.raw_score);
+const HTML_STYLE: &str = "body{font-family:Arial,Helvetica,sans-serif;font-size:14px;color:#111;max-width:960px;margin:24px auto;padding:0 16px}
+.letterhead{display:flex;gap:16px;align-items:center;border-bottom:3px double #111;padding-bottom:8px}
+.letterhead img{max-width:72px;max-height:76px}
+.letterhead p{margin:2px 0;font-size:12px}
+h1{font-size:17px;text-align:center;margin:20px 0 12px}
+table{width:100%;border-collapse:collapse;margin:12px 0}
+th,td{border:1px solid #555;padding:4px 8px;text-align:left}
+td.num,th.num{text-align:right;white-space:nowrap}
+tr.section td{font-weight:bold;background:#f2f2f2}
+tr.total td{font-weight:bold}
+.photo{float:right;max-width:120px;max-height:150px;margin:0 0 8px 16px}
+.note{font-size:12px;font-style:italic}
+.signature{text-align:right;margin-top:40px}
+.signature .name{margin-top:64px;font-weight:bold}
+.notes li{margin-bottom:8px;white-space:pre-wrap}
+.notes time{display:block;font-size:12px;color:#555}
+section{page-break-after:always}";
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
         }
     }
-
-    0.0
+    escaped
 }
 
-fn normalize_text(value: &str) -> String {
-    value
-        .nfd()
-        .filter(|c| !unicode_normalization::char::is_combining_mark(*c))
-        .collect::<String>()
-        .to_lowercase()
-        .chars()
-        .filter(|ch| ch.is_ascii_alphabetic() || ch.is_whitespace())
-        .collect()
+/// PNG `data:` URI so images travel inside the HTML file.
+fn image_data_uri(image: &DynamicImage) -> Result<String, String> {
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut std::io::Cursor::new(&mut bytes), ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+    Ok(format!("data:image/png;base64,{}", BASE64.encode(bytes)))
 }
 
-fn clamp_score(value: f64) -> f64 {
-    if !value.is_finite() {
-        0.0
-    } else {
-        value.clamp(0.0, 100.0)
+fn html_letterhead(html: &mut String, settings: &ReportSettings) -> Result<(), String> {
+    html.push_str("<header class=\"letterhead\">");
+    if let Some(logo) = &settings.logo {
+        html.push_str(&format!("<img src=\"{}\" alt=\"\">", image_data_uri(logo)?));
+    }
+    html.push_str("<div>");
+    if !settings.government_name.is_empty() {
+        html.push_str(&format!(
+            "<p><strong>{}</strong></p>",
+            escape_html(&settings.government_name)
+        ));
     }
+    html.push_str(&format!(
+        "<p style=\"font-size:18px\"><strong>{}</strong></p>",
+        escape_html(&settings.organization_name)
+    ));
+    for line in &settings.address_lines {
+        html.push_str(&format!("<p>{}</p>", escape_html(line)));
+    }
+    html.push_str("</div></header>");
+    Ok(())
 }
 
-fn determine_position_type(employee: &Employee) -> PositionType {
-    let combined = format!(
-        "{} {}",
-        employee.jabatan.as_deref().unwrap_or_default(),
-        employee.sub_jabatan.as_deref().unwrap_or_default()
+/// Renders the cover and worksheet pages as a standalone HTML document with inline CSS.
+fn render_report_html(
+    context: &EmployeeReportContext,
+    settings: &ReportSettings,
+) -> Result<String, String> {
+    let text = settings.locale.messages();
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n<title>{} - {}</title>\n<style>{}</style>\n</head>\n<body>\n",
+        settings.locale.code(),
+        escape_html(&context.employee.name),
+        escape_html(&context.dataset.name),
+        HTML_STYLE
     );
-    let normalized = normalize_text(&combined);
-
-    if !normalized.is_empty() {
-        if STAFF_KEYWORDS
-            .iter()
-            .map(|keyword| normalize_text(keyword))
-            .any(|token| normalized.contains(&token))
-        {
-            return PositionType::Staff;
-        }
 
-        if ESELON_KEYWORDS
-            .iter()
-            .map(|keyword| normalize_text(keyword))
-            .any(|token| normalized.contains(&token))
-        {
-            return PositionType::Eselon;
+    // Cover: criteria, weights, rating bands and the conclusion
+    html.push_str("<section>");
+    html_letterhead(&mut html, settings)?;
+    html.push_str(&format!(
+        "<h1>{}</h1>",
+        escape_html(&cover_title(context, settings))
+    ));
+    if let Some(photo) = &context.photo {
+        html.push_str(&format!(
+            "<img class=\"photo\" src=\"{}\" alt=\"{}\">",
+            image_data_uri(photo)?,
+            escape_html(&context.employee.name)
+        ));
+    }
+    html.push_str(&format!(
+        "<p>{}</p>",
+        escape_html(&cover_intro(context, settings))
+    ));
+    let [no, criteria, weight] = text.criteria_headings;
+    html.push_str(&format!(
+        "<table><tr><th>{}</th><th>{}</th><th class=\"num\">{}</th></tr>",
+        no, criteria, weight
+    ));
+    for (idx, parameters) in context.section_parameters.iter().enumerate() {
+        html.push_str(&format!(
+            "<tr class=\"section\"><td>{}</td><td>{}</td><td class=\"num\">{}</td></tr>",
+            COVER_NUMERALS[idx],
+            text.section_names[idx],
+            percent(context.section_weights.shares()[idx])
+        ));
+        for (i, parameter) in parameters.iter().enumerate() {
+            html.push_str(&format!(
+                "<tr><td></td><td>{}. {}</td><td></td></tr>",
+                i + 1,
+                escape_html(parameter)
+            ));
+        }
+    }
+    html.push_str(&format!(
+        "<tr class=\"total\"><td></td><td>{}</td><td class=\"num\">{}</td></tr></table>",
+        text.total,
+        percent(context.section_weights.shares().iter().sum())
+    ));
+    html.push_str(&format!(
+        "<p>{}</p><ol type=\"a\">",
+        escape_html(text.rating_bands_intro)
+    ));
+    for (label, threshold) in cover_rating_bands(context, text) {
+        html.push_str(&format!(
+            "<li>{} : {}</li>",
+            escape_html(&label),
+            escape_html(&threshold)
+        ));
+    }
+    let (conclusion, conclusion2) = cover_conclusion(context, text);
+    html.push_str(&format!(
+        "</ol><p>{} {}</p></section>\n",
+        escape_html(&conclusion),
+        escape_html(&conclusion2)
+    ));
+
+    // Worksheet: weighted score per component
+    html.push_str("<section>");
+    html_letterhead(&mut html, settings)?;
+    html.push_str(&format!(
+        "<h1>{}<br>{} {}<br>{}</h1>",
+        fill(
+            text.worksheet_title,
+            &[&worksheet_position_title(context.position_type, text)]
+        ),
+        escape_html(&settings.organization_full_name.to_uppercase()),
+        escape_html(&report_period_label(&context.dataset, text).to_uppercase()),
+        fill(text.year_line, &[&dataset_period_year(&context.dataset)])
+    ));
+    let [no, component, weight, score] = text.worksheet_headings;
+    html.push_str(&format!(
+        "<table><tr><th>{}</th><th>{}</th><th class=\"num\">{}</th><th class=\"num\">{}</th></tr>",
+        no, component, weight, score
+    ));
+    for (idx, ((numeral, heading), cap)) in worksheet_headings(context, text)
+        .iter()
+        .zip(section_caps(context))
+        .enumerate()
+    {
+        let section = context.component_sections.get(idx);
+        html.push_str(&format!(
+            "<tr class=\"section\"><td>{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+            numeral,
+            heading,
+            text.number(cap),
+            text.number(section.map(|s| s.subtotal).unwrap_or(0.0))
+        ));
+        // The leadership section is a single score without parameters, as in the PDF
+        if idx == 2 {
+            continue;
         }
+        for (i, component) in section.iter().flat_map(|s| &s.breakdown).enumerate() {
+            html.push_str(&format!(
+                "<tr><td></td><td>{}. {}</td><td></td><td class=\"num\">{}</td></tr>",
+                i + 1,
+                escape_html(&component.parameter),
+                text.number(component.weighted_score)
+            ));
+        }
+    }
+    html.push_str(&format!(
+        "<tr class=\"total\"><td></td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr></table>",
+        text.final_score,
+        text.number(context.section_weights.total_cap),
+        text.number(context.total_score)
+    ));
+    if let Some(note) = unmapped_note(context, text) {
+        html.push_str(&format!("<p class=\"note\">{}</p>", escape_html(&note)));
     }
 
-    if let Some(gol) = employee.gol.as_deref() {
-        let gol_upper = gol.trim().to_uppercase();
-        if gol_upper.starts_with("IV") {
-            return PositionType::Eselon;
+    html.push_str("<div class=\"signature\">");
+    for line in &settings.signer_title_lines {
+        html.push_str(&format!("<div>{}</div>", escape_html(line)));
+    }
+    html.push_str(&format!(
+        "<div class=\"name\">{}</div>",
+        escape_html(&settings.signer_name)
+    ));
+    if let Some(nip) = &settings.signer_nip {
+        html.push_str(&format!("<div>NIP. {}</div>", escape_html(nip)));
+    }
+    html.push_str("</div></section>\n");
+
+    let notes = appendix_notes(context, settings);
+    if !notes.is_empty() {
+        html.push_str(&format!("<section><h1>{}</h1>", text.appendix_title));
+        html.push_str(&format!(
+            "<p>{}</p><ol class=\"notes\">",
+            escape_html(&context.employee.name)
+        ));
+        for note in notes {
+            html.push_str(&format!(
+                "<li><time>{}</time>{}</li>",
+                note.created_at.format("%d/%m/%Y %H:%M"),
+                escape_html(&note.content)
+            ));
         }
+        html.push_str("</ol></section>\n");
     }
+    html.push_str("</body>\n</html>\n");
+    Ok(html)
+}
 
-    PositionType::Staff
+/// Warns that some scored competencies are left out of the total, see `competency_weights`.
+fn unmapped_note(context: &EmployeeReportContext, text: &Messages) -> Option<String> {
+    if context.unmapped_competencies.is_empty() {
+        return None;
+    }
+    Some(fill(
+        text.unmapped_note,
+        &[&context.unmapped_competencies.join(", ")],
+    ))
 }
 
-fn render_report_pdf(context: &EmployeeReportContext, file_path: &str) -> Result<(), String> {
-    let mut document =
-        Pdf::create(file_path).map_err(|e| format!("Failed to create PDF: {}", e))?;
+fn report_period_label(dataset: &Dataset, text: &Messages) -> String {
+    dataset
+        .period_label
+        .clone()
+        .unwrap_or_else(|| text.default_period.to_string())
+}
 
-    // Page 1: Cover/criteria (landscape A4)
-    document
-        .render_page(842.0, 595.0, |canvas| {
-            draw_cover_page_landscape(canvas, context)
-        })
-        .map_err(|e| format!("Failed to render cover page: {}", e))?;
+/// Cover numerals of the three sections, in `component_sections` order.
+const COVER_NUMERALS: [&str; 3] = ["A.", "B.", "C."];
 
-    // Page 2: Worksheet/evaluation (landscape A4)
-    document
-        .render_page(842.0, 595.0, |canvas| {
-            draw_worksheet_page_landscape(canvas, context)
-        })
-        .map_err(|e| format!("Failed to render worksheet page: {}", e))?;
+fn percent(share: f64) -> String {
+    format!("{}%", share)
+}
 
-    document
-        .finish()
-        .map_err(|e| format!("Failed to save PDF: {}", e))
+/// Caps of the worksheet sections, in `component_sections` order.
+fn section_caps(context: &EmployeeReportContext) -> [f64; 3] {
+    let weights = &context.section_weights;
+    [
+        weights.perilaku_cap,
+        weights.kualitas_cap(context.position_type),
+        weights.leadership_cap,
+    ]
 }
 
-fn fmt_id(value: f64) -> String {
-    format!("{:.2}", value).replace('.', ",")
+/// Predicates listed on the cover with their score ranges.
+fn cover_rating_bands(context: &EmployeeReportContext, text: &Messages) -> Vec<(String, String)> {
+    context
+        .rating_bands
+        .iter()
+        .map(|band| {
+            let threshold = match band.max_score {
+                Some(max_score) => {
+                    format!(
+                        "{} - {}",
+                        text.number(band.min_score),
+                        text.number(max_score)
+                    )
+                }
+                None => format!(">= {}", text.number(band.min_score)),
+            };
+            (text.predicate(&band.label).to_uppercase(), threshold)
+        })
+        .collect()
 }
 
-fn draw_cover_page_landscape(
-    canvas: &mut Canvas<'_>,
+/// Numeral and heading of the worksheet sections, in `component_sections` order.
+fn worksheet_headings(
     context: &EmployeeReportContext,
-) -> std::io::Result<()> {
-    let mut y = 555.0;
+    text: &Messages,
+) -> [(&'static str, String); 3] {
+    let shares = context.section_weights.shares();
+    let heading = |idx: usize| format!("{} ({})", text.section_names[idx], percent(shares[idx]));
+    [
+        ("I.", heading(0)),
+        ("II.", heading(1)),
+        ("III.", heading(2)),
+    ]
+}
 
-    // Header with logo placeholder and agency info
-    canvas.left_text(
-        50.0,
-        y,
-        BuiltinFont::Helvetica_Bold,
-        11.0,
-        "PEMERINTAH PROVINSI KALIMANTAN SELATAN",
-    )?;
-    y -= 16.0;
-    canvas.left_text(50.0, y, BuiltinFont::Helvetica_Bold, 14.0, "DINAS SOSIAL")?;
-    y -= 20.0;
+fn cover_title(context: &EmployeeReportContext, settings: &ReportSettings) -> String {
+    let text = settings.locale.messages();
+    fill(
+        text.cover_title,
+        &[
+            &settings.organization_full_name.to_uppercase(),
+            &report_period_label(&context.dataset, text).to_uppercase(),
+            &dataset_period_year(&context.dataset),
+        ],
+    )
+}
 
-    // Contact information
-    canvas.left_text(
-        50.0,
-        y,
-        BuiltinFont::Helvetica,
-        9.0,
-        "Jalan Letjen R. Soeprapto No. 8 Banjarmasin Kode Pos 70114",
-    )?;
-    y -= 11.0;
-    canvas.left_text(
-        50.0,
-        y,
-        BuiltinFont::Helvetica,
-        9.0,
-        "Telepon : (0511) 335 0825, Fax. (0511) 335 4193",
-    )?;
-    y -= 11.0;
-    canvas.left_text(
-        50.0,
-        y,
-        BuiltinFont::Helvetica,
-        9.0,
-        "Email: dinsosialselprov@gmail.com Website: dinsoss.kalselprov.go.id",
-    )?;
-    y -= 25.0;
+fn cover_intro(context: &EmployeeReportContext, settings: &ReportSettings) -> String {
+    let text = settings.locale.messages();
+    fill(
+        text.cover_intro,
+        &[
+            &settings.organization_full_name,
+            &report_period_label(&context.dataset, text),
+            &dataset_period_year(&context.dataset),
+        ],
+    )
+}
 
-    // Title
-    let year = context
-        .dataset
-        .created_at
-        .with_timezone(&chrono::Local)
-        .year();
-    canvas.center_text(
-        421.0,
-        y,
-        BuiltinFont::Helvetica_Bold,
-        12.0,
-        "HASIL PENILAIAN KINERJA PEGAWAI DINAS SOSIAL PROVINSI",
-    )?;
-    y -= 14.0;
-    canvas.center_text(
-        421.0,
-        y,
-        BuiltinFont::Helvetica_Bold,
-        12.0,
-        &format!("KALIMANTAN SELATAN SEMESTER I TAHUN {}", year),
-    )?;
-    y -= 25.0;
+/// The two lines closing the cover page: who was assessed, then the rating and score.
+fn cover_conclusion(context: &EmployeeReportContext, text: &Messages) -> (String, String) {
+    let position_title = match (context.position_type, &context.employee.jabatan) {
+        (PositionType::Eselon, Some(jabatan)) => jabatan.clone(),
+        _ => text.conclusion_subject.to_string(),
+    };
+    (
+        fill(text.conclusion, &[&position_title, &context.employee.name]),
+        fill(
+            text.conclusion_rating,
+            &[
+                &text.predicate(&context.rating).to_uppercase(),
+                &text.number(context.total_score),
+            ],
+        ),
+    )
+}
 
-    // Official intro paragraph
-    canvas.left_text(50.0, y, BuiltinFont::Helvetica, 10.0, "       Penilaian Kinerja oleh seluruh pegawai Dinas Sosial Provinsi Kalimantan Selatan sampai dengan")?;
-    y -= 12.0;
-    let year_line = format!("Semester I Tahun {} berdasarkan dari Kualitas Kinerja dengan melalui form yang disebarkan tiap akhir", year);
-    canvas.left_text(50.0, y, BuiltinFont::Helvetica, 10.0, &year_line)?;
-    y -= 12.0;
-    canvas.left_text(
-        50.0,
-        y,
-        BuiltinFont::Helvetica,
-        10.0,
-        "semester, dengan kriteria penilaian sebagai berikut :",
-    )?;
-    y -= 20.0;
+fn worksheet_position_title(position_type: PositionType, text: &Messages) -> &'static str {
+    match position_type {
+        PositionType::Eselon => text.worksheet_eselon,
+        PositionType::Staff => text.worksheet_staff,
+    }
+}
 
-    // Table: Kriteria dan Bobot
-    canvas.left_text(50.0, y, BuiltinFont::Helvetica_Bold, 10.5, "NO.")?;
-    canvas.left_text(120.0, y, BuiltinFont::Helvetica_Bold, 10.5, "KRITERIA")?;
-    canvas.left_text(720.0, y, BuiltinFont::Helvetica_Bold, 10.5, "BOBOT")?;
-    y -= 14.0;
+fn draw_cover_page(
+    canvas: &mut Canvas<'_>,
+    context: &EmployeeReportContext,
+    settings: &ReportSettings,
+    layout: &PageLayout,
+) -> std::io::Result<()> {
+    let text = settings.locale.messages();
+    let mut y = draw_letterhead(canvas, settings, layout.left, layout.top)?;
 
-    // A. Perilaku Kerja (30%)
-    canvas.left_text(50.0, y, BuiltinFont::Helvetica_Bold, 10.0, "A.")?;
-    canvas.left_text(
-        120.0,
-        y,
-        BuiltinFont::Helvetica_Bold,
-        10.0,
-        "PERILAKU KERJA",
-    )?;
-    canvas.left_text(720.0, y, BuiltinFont::Helvetica_Bold, 10.0, "30%")?;
-    y -= 12.0;
+    // Title
+    let title = cover_title(context, settings);
+    let title_width = layout.content_width() - 102.0;
+    for line in canvas.wrap_to_width(Font::Bold, 12.0, &title, title_width) {
+        canvas.center_text(layout.center_x(), y, Font::Bold, 12.0, &line)?;
+        y -= 14.0;
+    }
+    y -= 11.0;
 
-    let perilaku_items = [
-        "1. Kehadiran dan Tepat Waktu",
-        "2. Management Waktu Kerja",
-        "3. Kerjasama dan Teamwork",
-        "4. Inisiatif dan Flexibilitas",
-        "5. Kepemimpinan",
-    ];
-    for item in perilaku_items.iter() {
-        canvas.left_text(140.0, y, BuiltinFont::Helvetica, 9.5, item)?;
-        y -= 11.0;
+    // Official intro paragraph, first line indented
+    let intro = cover_intro(context, settings);
+    for (index, line) in canvas
+        .wrap_to_width(Font::Regular, 10.0, &intro, layout.content_width() - 32.0)
+        .iter()
+        .enumerate()
+    {
+        let x = if index == 0 {
+            layout.left + 25.0
+        } else {
+            layout.left
+        };
+        canvas.left_text(x, y, Font::Regular, 10.0, line)?;
+        y -= 12.0;
     }
+    y -= 8.0;
 
-    // B. Kualitas Kinerja (50%)
-    y -= 4.0;
-    canvas.left_text(50.0, y, BuiltinFont::Helvetica_Bold, 10.0, "B.")?;
-    canvas.left_text(
-        120.0,
-        y,
-        BuiltinFont::Helvetica_Bold,
-        10.0,
-        "KUALITAS KINERJA",
-    )?;
-    canvas.left_text(720.0, y, BuiltinFont::Helvetica_Bold, 10.0, "50%")?;
-    y -= 12.0;
+    // Table: Kriteria dan Bobot
+    let label_x = layout.left + 70.0;
+    let item_x = layout.left + 90.0;
+    let weight_x = layout.right - 72.0;
+    let [no, criteria, weight] = text.criteria_headings;
+    canvas.left_text(layout.left, y, Font::Bold, 10.5, no)?;
+    canvas.left_text(label_x, y, Font::Bold, 10.5, criteria)?;
+    canvas.left_text(weight_x, y, Font::Bold, 10.5, weight)?;
+    y -= 14.0;
 
-    let kualitas_items = [
-        "1. Kualitas Kinerja",
-        "2. Kemampuan Berkomunikasi",
-        "3. Pemahaman Urusan Sosial",
-    ];
-    for item in kualitas_items.iter() {
-        canvas.left_text(140.0, y, BuiltinFont::Helvetica, 9.5, item)?;
-        y -= 11.0;
+    // A. Perilaku Kerja, B. Kualitas Kinerja and C. Penilaian Pimpinan with their shares
+    for (idx, parameters) in context.section_parameters.iter().enumerate() {
+        if idx > 0 {
+            y -= 4.0;
+        }
+        canvas.left_text(layout.left, y, Font::Bold, 10.0, COVER_NUMERALS[idx])?;
+        canvas.left_text(label_x, y, Font::Bold, 10.0, text.section_names[idx])?;
+        canvas.left_text(
+            weight_x,
+            y,
+            Font::Bold,
+            10.0,
+            &percent(context.section_weights.shares()[idx]),
+        )?;
+        y -= 12.0;
+
+        for (i, parameter) in parameters.iter().enumerate() {
+            canvas.left_text(
+                item_x,
+                y,
+                Font::Regular,
+                9.5,
+                &format!("{}. {}", i + 1, parameter),
+            )?;
+            y -= 11.0;
+        }
     }
+    y -= 2.0;
 
-    // C. Penilaian Pimpinan (20%)
+    // Total row
     y -= 4.0;
-    canvas.left_text(50.0, y, BuiltinFont::Helvetica_Bold, 10.0, "C.")?;
+    canvas.center_text(layout.center_x(), y, Font::Bold, 10.0, text.total)?;
     canvas.left_text(
-        120.0,
+        weight_x,
         y,
-        BuiltinFont::Helvetica_Bold,
+        Font::Bold,
         10.0,
-        "PENILAIAN PIMPINAN",
+        &percent(context.section_weights.shares().iter().sum()),
     )?;
-    canvas.left_text(720.0, y, BuiltinFont::Helvetica_Bold, 10.0, "20%")?;
-    y -= 14.0;
-
-    // Total row
-    y -= 4.0;
-    canvas.center_text(420.0, y, BuiltinFont::Helvetica_Bold, 10.0, "TOTAL")?;
-    canvas.left_text(720.0, y, BuiltinFont::Helvetica_Bold, 10.0, "100%")?;
     y -= 18.0;
 
     // Rating bands
-    canvas.left_text(
-        50.0,
-        y,
-        BuiltinFont::Helvetica,
-        9.5,
-        "Predikat skor akhir penilaian Penilaian Pegawai dengan kinerja terbaik sebagai berikut :",
-    )?;
+    canvas.left_text(layout.left, y, Font::Regular, 9.5, text.rating_bands_intro)?;
     y -= 12.0;
 
-    let bands = [
-        ("SANGAT BAIK", ">= 80,00"),
-        ("BAIK", "70,00 - 79,99"),
-        ("KURANG BAIK", "65,00 - 69,99"),
-    ];
-    for (i, (label, thr)) in bands.iter().enumerate() {
+    for (i, (label, thr)) in cover_rating_bands(context, text).iter().enumerate() {
         canvas.left_text(
-            70.0,
+            layout.left + 20.0,
             y,
-            BuiltinFont::Helvetica,
+            Font::Regular,
             9.5,
             &format!("{}. {} : {}", (b'a' + i as u8) as char, label, thr),
         )?;
@@ -741,308 +958,405 @@ fn draw_cover_page_landscape(
     y -= 12.0;
 
     // Conclusion line
-    let position_title = match context.position_type {
-        PositionType::Eselon => {
-            if let Some(jabatan) = &context.employee.jabatan {
-                jabatan.clone()
-            } else {
-                "Pegawai".to_string()
-            }
-        }
-        PositionType::Staff => "Pegawai".to_string(),
-    };
-
-    let conclusion = format!(
-        "       Berdasarkan hasil penilaian, dapat disampaikan bahwa capaian kinerja {} {} memperoleh",
-        position_title, context.employee.name
-    );
-    canvas.left_text(50.0, y, BuiltinFont::Helvetica, 10.0, &conclusion)?;
+    let (conclusion, conclusion2) = cover_conclusion(context, text);
+    canvas.left_text(
+        layout.left,
+        y,
+        Font::Regular,
+        10.0,
+        &format!("       {}", conclusion),
+    )?;
     y -= 12.0;
-    let conclusion2 = format!(
-        "predikat \"{}\" dengan nilai {}.",
-        context.rating.to_uppercase(),
-        fmt_id(context.total_score)
-    );
-    canvas.left_text(50.0, y, BuiltinFont::Helvetica, 10.0, &conclusion2)?;
+    canvas.left_text(layout.left, y, Font::Regular, 10.0, &conclusion2)?;
+
+    // Photo sits in the empty bottom-right corner below the conclusion
+    if let Some(photo) = &context.photo {
+        canvas.image(photo, layout.right - 90.0, layout.bottom, 90.0, 110.0)?;
+    }
 
     Ok(())
 }
 
-fn draw_worksheet_page_landscape(
+fn draw_worksheet_page(
     canvas: &mut Canvas<'_>,
     context: &EmployeeReportContext,
+    settings: &ReportSettings,
+    layout: &PageLayout,
 ) -> std::io::Result<()> {
-    let mut y = 555.0;
-
-    // Header with logo placeholder and contact info
-    canvas.left_text(
-        50.0,
-        y,
-        BuiltinFont::Helvetica_Bold,
-        11.0,
-        "PEMERINTAH PROVINSI KALIMANTAN SELATAN",
-    )?;
-    y -= 16.0;
-    canvas.left_text(50.0, y, BuiltinFont::Helvetica_Bold, 14.0, "DINAS SOSIAL")?;
-    y -= 20.0;
-
-    // Contact information
-    canvas.left_text(
-        50.0,
-        y,
-        BuiltinFont::Helvetica,
-        9.0,
-        "Jalan Letjen R. Soeprapto No. 8 Banjarmasin Kode Pos 70114",
-    )?;
-    y -= 11.0;
-    canvas.left_text(
-        50.0,
-        y,
-        BuiltinFont::Helvetica,
-        9.0,
-        "Telepon : (0511) 335 0825, Fax. (0511) 335 4193",
-    )?;
-    y -= 11.0;
-    canvas.left_text(
-        50.0,
-        y,
-        BuiltinFont::Helvetica,
-        9.0,
-        "Email: dinsosialselprov@gmail.com Website: dinsoss.kalselprov.go.id",
-    )?;
-    y -= 25.0;
+    let text = settings.locale.messages();
+    let mut y = draw_letterhead(canvas, settings, layout.left, layout.top)?;
 
     // Title
-    let year = context
-        .dataset
-        .created_at
-        .with_timezone(&chrono::Local)
-        .year();
-
-    let position_title = match context.position_type {
-        PositionType::Eselon => "ESELON III",
-        PositionType::Staff => "STAFF",
-    };
+    let year = dataset_period_year(&context.dataset);
+
+    let position_title = worksheet_position_title(context.position_type, text);
 
     canvas.center_text(
-        421.0,
+        layout.center_x(),
         y,
-        BuiltinFont::Helvetica_Bold,
+        Font::Bold,
         12.0,
-        &format!(
-            "KERTAS KERJA EVALUASI PENGUKURAN KINERJA {}",
-            position_title
-        ),
+        &fill(text.worksheet_title, &[&position_title]),
     )?;
     y -= 14.0;
     canvas.center_text(
-        421.0,
+        layout.center_x(),
         y,
-        BuiltinFont::Helvetica_Bold,
+        Font::Bold,
         12.0,
-        "DINAS SOSIAL PROVINSI KALIMANTAN SELATAN SEMESTER I",
+        &format!(
+            "{} {}",
+            settings.organization_full_name.to_uppercase(),
+            report_period_label(&context.dataset, text).to_uppercase()
+        ),
     )?;
     y -= 14.0;
     canvas.center_text(
-        421.0,
+        layout.center_x(),
         y,
-        BuiltinFont::Helvetica_Bold,
+        Font::Bold,
         12.0,
-        &format!("TAHUN {}", year),
+        &fill(text.year_line, &[&year]),
     )?;
-    y -= 25.0;
+    y -= 10.0;
+
+    // Bordered grid in the layout of the official form, numbered columns under the headings
+    let table = Table::new(
+        layout.left,
+        vec![
+            Column {
+                width: 50.0,
+                align: Align::Center,
+            },
+            Column {
+                width: layout.content_width() - 230.0,
+                align: Align::Left,
+            },
+            Column {
+                width: 90.0,
+                align: Align::Right,
+            },
+            Column {
+                width: 90.0,
+                align: Align::Right,
+            },
+        ],
+    );
+    let header = RowStyle::header(10.0);
+    let section_row = RowStyle::body(Font::Bold, 10.0);
+    let item_row = RowStyle::body(Font::Regular, 9.5);
+    y = table.draw_row(canvas, y, &text.worksheet_headings, header)?;
+    y = table.draw_row(canvas, y, &["1", "2", "3", "4"], header)?;
 
-    // Table header
-    canvas.left_text(50.0, y, BuiltinFont::Helvetica_Bold, 10.5, "NO.")?;
-    canvas.left_text(
-        120.0,
+    for (idx, ((numeral, heading), cap)) in worksheet_headings(context, text)
+        .iter()
+        .zip(section_caps(context))
+        .enumerate()
+    {
+        let section = context.component_sections.get(idx);
+        let subtotal = text.number(section.map(|s| s.subtotal).unwrap_or(0.0));
+        y = table.draw_row(
+            canvas,
+            y,
+            &[numeral, heading, &text.number(cap), &subtotal],
+            section_row,
+        )?;
+        // The leadership section is a single score without parameters
+        if idx == 2 {
+            continue;
+        }
+        for (i, component) in section.iter().flat_map(|s| &s.breakdown).enumerate() {
+            let label = format!("{}. {}", i + 1, component.parameter);
+            let score = text.number(component.weighted_score);
+            y = table.draw_row(canvas, y, &["", &label, "", &score], item_row)?;
+        }
+    }
+    y = table.draw_row(
+        canvas,
         y,
-        BuiltinFont::Helvetica_Bold,
-        10.5,
-        "KOMPONEN / KRITERIA",
+        &[
+            "",
+            text.final_score,
+            &text.number(context.section_weights.total_cap),
+            &text.number(context.total_score),
+        ],
+        RowStyle {
+            align: Some(Align::Right),
+            ..RowStyle::body(Font::Bold, 10.5)
+        },
     )?;
-    canvas.left_text(660.0, y, BuiltinFont::Helvetica_Bold, 10.5, "BOBOT")?;
-    canvas.left_text(750.0, y, BuiltinFont::Helvetica_Bold, 10.5, "NILAI")?;
-    y -= 4.0;
-    canvas.left_text(50.0, y, BuiltinFont::Helvetica_Bold, 10.5, "1")?;
-    canvas.left_text(120.0, y, BuiltinFont::Helvetica_Bold, 10.5, "2")?;
-    canvas.left_text(660.0, y, BuiltinFont::Helvetica_Bold, 10.5, "3")?;
-    canvas.left_text(750.0, y, BuiltinFont::Helvetica_Bold, 10.5, "3")?;
-    y -= 14.0;
+    if let Some(note) = unmapped_note(context, text) {
+        y -= 4.0;
+        for line in canvas.wrap_to_width(Font::Regular, 8.5, &note, layout.content_width()) {
+            y -= 10.0;
+            canvas.left_text(layout.left, y, Font::Regular, 8.5, &line)?;
+        }
+    }
+    y -= 40.0;
 
-    // I. PERILAKU KERJA (30%)
-    canvas.left_text(50.0, y, BuiltinFont::Helvetica_Bold, 10.0, "I.")?;
-    canvas.left_text(
-        120.0,
-        y,
-        BuiltinFont::Helvetica_Bold,
-        10.0,
-        "PERILAKU KERJA (30%)",
-    )?;
-    canvas.left_text(
-        660.0,
-        y,
-        BuiltinFont::Helvetica_Bold,
-        10.0,
-        &fmt_id(PERILAKU_CAP),
-    )?;
-    canvas.left_text(
-        750.0,
-        y,
-        BuiltinFont::Helvetica_Bold,
-        10.0,
-        &fmt_id(
-            context
-                .component_sections
-                .get(0)
-                .map(|s| s.subtotal)
-                .unwrap_or(0.0),
-        ),
-    )?;
-    y -= 12.0;
+    // Official signature section
+    draw_signature(canvas, settings, layout.right, y)
+}
 
-    // Perilaku kerja breakdown
-    let perilaku_section = &context.component_sections[0];
-    for (i, component) in perilaku_section.breakdown.iter().enumerate() {
-        let num = format!("{}", i + 1);
-        canvas.left_text(120.0, y, BuiltinFont::Helvetica, 9.5, &num)?;
-        canvas.left_text(140.0, y, BuiltinFont::Helvetica, 9.5, &component.parameter)?;
-        canvas.left_text(
-            750.0,
-            y,
-            BuiltinFont::Helvetica,
-            9.5,
-            &fmt_id(component.weighted_score),
-        )?;
-        y -= 11.0;
+/// Notes the report appends, empty unless the appendix is switched on.
+fn appendix_notes<'a>(
+    context: &'a EmployeeReportContext,
+    settings: &ReportSettings,
+) -> &'a [EmployeeNote] {
+    if settings.include_notes_appendix {
+        &context.notes
+    } else {
+        &[]
     }
-    y -= 4.0;
+}
 
-    // II. KUALITAS KINERJA (50%)
-    canvas.left_text(50.0, y, BuiltinFont::Helvetica_Bold, 10.0, "II.")?;
-    canvas.left_text(
-        120.0,
-        y,
-        BuiltinFont::Helvetica_Bold,
-        10.0,
-        "KUALITAS KINERJA (50%)",
-    )?;
-    let kualitas_cap = match context.position_type {
-        PositionType::Eselon => KUALITAS_CAP_ESELON,
-        PositionType::Staff => KUALITAS_CAP_STAFF,
+/// Draws `notes` from line `first_line` of the first one until the page is full and returns
+/// where the next page starts: the index into `notes` and the line within that note. The
+/// first appendix page carries the letterhead; a note longer than a page continues on the next.
+fn draw_notes_appendix(
+    canvas: &mut Canvas<'_>,
+    context: &EmployeeReportContext,
+    settings: &ReportSettings,
+    layout: &PageLayout,
+    notes: &[EmployeeNote],
+    first_line: usize,
+    first_page: bool,
+) -> std::io::Result<(usize, usize)> {
+    const LINE_HEIGHT: f32 = 12.0;
+
+    let mut y = if first_page {
+        draw_letterhead(canvas, settings, layout.left, layout.top)?
+    } else {
+        layout.top
     };
-    canvas.left_text(
-        660.0,
-        y,
-        BuiltinFont::Helvetica_Bold,
-        10.0,
-        &fmt_id(kualitas_cap),
-    )?;
-    canvas.left_text(
-        750.0,
+    let text = settings.locale.messages();
+    let title = if first_page {
+        text.appendix_title
+    } else {
+        text.appendix_title_continued
+    };
+    canvas.center_text(layout.center_x(), y, Font::Bold, 12.0, title)?;
+    y -= 14.0;
+    canvas.center_text(
+        layout.center_x(),
         y,
-        BuiltinFont::Helvetica_Bold,
-        10.0,
-        &fmt_id(
-            context
-                .component_sections
-                .get(1)
-                .map(|s| s.subtotal)
-                .unwrap_or(0.0),
-        ),
+        Font::Bold,
+        11.0,
+        &context.employee.name,
     )?;
-    y -= 12.0;
+    y -= 25.0;
 
-    // Kualitas kinerja breakdown
-    let kualitas_section = &context.component_sections[1];
-    for (i, component) in kualitas_section.breakdown.iter().enumerate() {
-        let num = format!("{}", i + 1);
-        canvas.left_text(120.0, y, BuiltinFont::Helvetica, 9.5, &num)?;
-        canvas.left_text(140.0, y, BuiltinFont::Helvetica, 9.5, &component.parameter)?;
-        canvas.left_text(
-            750.0,
-            y,
-            BuiltinFont::Helvetica,
-            9.5,
-            &fmt_id(component.weighted_score),
-        )?;
-        y -= 11.0;
+    for (index, note) in notes.iter().enumerate() {
+        let lines =
+            canvas.wrap_to_width(Font::Regular, 10.0, &note.content, layout.content_width());
+        let start = if index == 0 { first_line } else { 0 };
+        if start == 0 {
+            // Notes that fit on one page are not split, unless they start the page
+            let needed = LINE_HEIGHT * (lines.len() as f32 + 1.0);
+            if index > 0 && y - needed < layout.bottom {
+                return Ok((index, 0));
+            }
+            canvas.left_text(
+                layout.left,
+                y,
+                Font::Bold,
+                9.5,
+                &note.created_at.format("%d/%m/%Y %H:%M").to_string(),
+            )?;
+            y -= LINE_HEIGHT;
+        }
+        for (line_index, line) in lines.iter().enumerate().skip(start) {
+            if y < layout.bottom {
+                return Ok((index, line_index));
+            }
+            canvas.left_text(layout.left, y, Font::Regular, 10.0, line)?;
+            y -= LINE_HEIGHT;
+        }
+        y -= 8.0;
     }
-    y -= 4.0;
 
-    // III. PENILAIAN PIMPINAN (20%)
-    canvas.left_text(50.0, y, BuiltinFont::Helvetica_Bold, 10.0, "III.")?;
-    canvas.left_text(
-        120.0,
-        y,
-        BuiltinFont::Helvetica_Bold,
-        10.0,
-        "PENILAIAN PIMPINAN (20%)",
-    )?;
-    canvas.left_text(
-        660.0,
-        y,
-        BuiltinFont::Helvetica_Bold,
-        10.0,
-        &fmt_id(LEADERSHIP_CAP),
-    )?;
-    canvas.left_text(
-        750.0,
-        y,
-        BuiltinFont::Helvetica_Bold,
-        10.0,
-        &fmt_id(
-            context
-                .component_sections
-                .get(2)
-                .map(|s| s.subtotal)
-                .unwrap_or(0.0),
-        ),
-    )?;
-    y -= 16.0;
+    Ok((notes.len(), 0))
+}
 
-    // Final total row
-    canvas.left_text(350.0, y, BuiltinFont::Helvetica_Bold, 10.5, "NILAI AKHIR")?;
-    canvas.left_text(
-        660.0,
-        y,
-        BuiltinFont::Helvetica_Bold,
-        10.5,
-        &fmt_id(TOTAL_CAP),
-    )?;
-    canvas.left_text(
-        750.0,
-        y,
-        BuiltinFont::Helvetica_Bold,
-        10.5,
-        &fmt_id(context.total_score),
-    )?;
-    y -= 40.0;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{seed_datasets, seed_employees, seed_members, test_pool};
+    use crate::i18n::Locale;
+    use crate::scoring::weighting::save_weighting_scheme;
+
+    #[test]
+    fn test_escape_html_neutralises_markup() {
+        assert_eq!(
+            escape_html("<b>Budi & \"Sari\"</b>"),
+            "&lt;b&gt;Budi &amp; &quot;Sari&quot;&lt;/b&gt;"
+        );
+    }
 
-    // Official signature section
-    canvas.right_text(
-        792.0,
-        y,
-        BuiltinFont::Helvetica,
-        10.0,
-        "Plt. KEPALA DINAS SOSIAL",
-    )?;
-    y -= 11.0;
-    canvas.right_text(
-        792.0,
-        y,
-        BuiltinFont::Helvetica,
-        10.0,
-        "PROVINSI KALIMANTAN SELATAN",
-    )?;
-    y -= 55.0;
-    canvas.right_text(
-        792.0,
-        y,
-        BuiltinFont::Helvetica_Bold,
-        10.0,
-        "MUHAMMADUN, A.KS, M.I.Kom",
-    )?;
+    #[tokio::test]
+    async fn test_cover_lists_the_weighting_scheme_parameters() {
+        let pool = test_pool().await;
+        seed_datasets(&pool, &["Penilaian 2024"]).await;
+        seed_employees(&pool, &["Budi"]).await;
+        seed_members(&pool, 1, &[1]).await;
+        let mut scheme = load_weighting_scheme(&pool).await.unwrap();
+        scheme.perilaku[0].parameter = "Disiplin & kehadiran".to_string();
+        scheme.kualitas[0].staff_weight = 0.0;
+        let dropped = scheme.kualitas[0].parameter.clone();
+        save_weighting_scheme(&pool, scheme.clone()).await.unwrap();
+
+        let context = load_report_context(&pool, 1, 1).await.unwrap();
+        let html = render_report_html(&context, &ReportSettings::default()).unwrap();
+
+        assert_eq!(context.position_type, PositionType::Staff);
+        assert!(html.contains("1. Disiplin &amp; kehadiran"));
+        assert_eq!(context.section_parameters[0].len(), scheme.perilaku.len());
+        assert!(!context.section_parameters[1].contains(&dropped));
+        assert!(context.section_parameters[2].is_empty());
+    }
 
-    Ok(())
+    #[tokio::test]
+    async fn test_notes_appendix_follows_report_setting() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO datasets (id, name, created_at, updated_at)
+             VALUES (1, 'Penilaian 2024', datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, created_at, updated_at)
+             VALUES (1, 'Budi', datetime('now'), datetime('now'));
+             INSERT INTO dataset_employees (dataset_id, employee_id, created_at, updated_at)
+             VALUES (1, 1, datetime('now'), datetime('now'));
+             INSERT INTO competencies (id, name, display_order) VALUES (1, 'Kerjasama', 0);
+             INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, created_at)
+             VALUES (1, 1, 1, '80', 80.0, datetime('now'));
+             INSERT INTO employee_notes (dataset_id, employee_id, content, created_at, updated_at)
+             VALUES (1, 1, 'Perlu pelatihan <Excel>', '2024-03-01 09:00:00', '2024-03-01 09:00:00'),
+                    (1, 1, 'Sudah ikut pelatihan', '2024-06-01 09:00:00', '2024-06-01 09:00:00');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let context = load_report_context(&pool, 1, 1).await.unwrap();
+        assert_eq!(context.notes.len(), 2);
+
+        let mut settings = ReportSettings::default();
+        let html = render_report_html(&context, &settings).unwrap();
+        assert!(!html.contains("LAMPIRAN"));
+
+        settings.include_notes_appendix = true;
+        let html = render_report_html(&context, &settings).unwrap();
+        let first = html.find("Perlu pelatihan &lt;Excel&gt;").unwrap();
+        let second = html.find("Sudah ikut pelatihan").unwrap();
+        assert!(html.contains("LAMPIRAN: CATATAN PENILAIAN"));
+        assert!(first < second);
+
+        settings.locale = Locale::En;
+        let html = render_report_html(&context, &settings).unwrap();
+        assert!(html.contains("<html lang=\"en\">"));
+        assert!(html.contains("APPENDIX: ASSESSMENT NOTES"));
+        assert!(html.contains("FINAL SCORE"));
+    }
+
+    #[tokio::test]
+    async fn test_long_note_continues_on_the_next_appendix_page() {
+        let pool = test_pool().await;
+        seed_datasets(&pool, &["Penilaian 2024"]).await;
+        seed_employees(&pool, &["Budi"]).await;
+        seed_members(&pool, 1, &[1]).await;
+        sqlx::query(
+            "INSERT INTO employee_notes (dataset_id, employee_id, content) VALUES (1, 1, ?)",
+        )
+        .bind("Perlu pelatihan lanjutan. ".repeat(600))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let context = load_report_context(&pool, 1, 1).await.unwrap();
+        let settings = ReportSettings::default();
+        let layout = settings.paper.layout(Orientation::Landscape);
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("epa-appendix.pdf");
+        let mut document = Pdf::create(&path.to_string_lossy()).unwrap();
+        let mut pages = Vec::new();
+        let mut next = (0, 0);
+        while next.0 < context.notes.len() {
+            document
+                .render_page(layout.width, layout.height, |canvas| {
+                    next = draw_notes_appendix(
+                        canvas,
+                        &context,
+                        &settings,
+                        &layout,
+                        &context.notes,
+                        next.1,
+                        pages.is_empty(),
+                    )?;
+                    Ok(())
+                })
+                .unwrap();
+            pages.push(next);
+        }
+        document.finish().unwrap();
+
+        // The note breaks mid-way on the first page and ends on a later one
+        assert!(pages.len() > 1);
+        assert_eq!(pages[0].0, 0);
+        assert!(pages[0].1 > 0);
+        assert_eq!(pages.last(), Some(&(1, 0)));
+    }
+
+    #[tokio::test]
+    async fn test_ranking_recap_lists_every_ranked_employee() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO datasets (id, name, created_at, updated_at)
+             VALUES (1, 'Penilaian 2024', datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, nip, jabatan, created_at, updated_at)
+             VALUES (1, 'Budi', '198501012010011001', 'Analis', datetime('now'), datetime('now')),
+                    (2, 'Sari', NULL, NULL, datetime('now'), datetime('now'));
+             INSERT INTO dataset_employees (dataset_id, employee_id, created_at, updated_at)
+             VALUES (1, 1, datetime('now'), datetime('now')),
+                    (1, 2, datetime('now'), datetime('now'));
+             INSERT INTO competencies (id, name, display_order) VALUES (1, 'Kerjasama', 0);
+             INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, created_at)
+             VALUES (1, 1, 1, '70', 70.0, datetime('now')),
+                    (2, 1, 1, '90', 90.0, datetime('now'));",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let dataset = sqlx::query_as::<_, Dataset>("SELECT * FROM datasets WHERE id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let rankings = compute_rankings(&pool, 1, Some("weighted")).await.unwrap();
+        let text = Locale::Id.messages();
+        let rows = ranking_rows(&rankings, text);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0][0], "1");
+        assert_eq!(rows[0][1], "Sari");
+        assert_eq!((rows[0][2].as_str(), rows[0][3].as_str()), ("-", "-"));
+        assert_eq!(rows[1][2], "198501012010011001");
+        assert_eq!(rows[1][7], text.number(rankings[1].weighted_score));
+
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("epa-ranking.pdf");
+        render_ranking_pdf(
+            &dataset,
+            &rankings,
+            &ReportSettings::default(),
+            &path.to_string_lossy(),
+        )
+        .unwrap();
+        let written = std::fs::metadata(&path).unwrap().len();
+        assert!(written > 0);
+
+        let portrait = PaperSettings {
+            orientation: Some(Orientation::Portrait),
+            ..PaperSettings::default()
+        };
+        let layout = ranking_layout(&portrait);
+        assert!(layout.width > layout.height);
+    }
 }
@@ -0,0 +1,700 @@
+use crate::auth::Role;
+use crate::csv_parser::rating_value;
+use crate::db::audit::record_audit;
+use crate::db::models::{Competency, Employee, Score, ScoreEntry};
+use crate::db::score_entries::MANUAL_RATER;
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use sqlx::{SqliteConnection, SqlitePool};
+use std::collections::HashMap;
+use tauri::State;
+
+/// Everything an editable score grid needs: rows, columns in the dataset's order and the
+/// filled cells. Cells without a score are simply absent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreMatrix {
+    pub employees: Vec<Employee>,
+    pub competencies: Vec<Competency>,
+    pub scores: Vec<Score>,
+}
+
+/// One edited grid cell; an empty or missing `raw_value` clears it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreCellChange {
+    pub employee_id: i64,
+    pub competency_id: i64,
+    #[serde(default)]
+    pub raw_value: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreCellError {
+    pub employee_id: i64,
+    pub competency_id: i64,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreMatrixSaveResult {
+    pub saved: usize,
+    pub cleared: usize,
+    /// Cells left unchanged because they failed validation
+    pub failed: Vec<ScoreCellError>,
+}
+
+/// A saved or cleared grid cell as the audit log records it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct AppliedCell {
+    employee_id: i64,
+    competency_id: i64,
+    old_raw_value: Option<String>,
+    old_numeric_value: Option<f64>,
+    new_raw_value: Option<String>,
+    new_numeric_value: Option<f64>,
+}
+
+/// Numeric value of a manually entered rating, read the way an import reads it: a rating
+/// mapping of the dataset, else a number. The value has to lie between 0 and the dataset's
+/// score scale, or its highest rating mapping when no scale is fixed; `numeric_value`, when
+/// given, has to agree with the result so the UI cannot store a value the import would not
+/// produce.
+async fn resolve_numeric_value(
+    conn: &mut SqliteConnection,
+    dataset_id: i64,
+    raw_value: &str,
+    numeric_value: Option<f64>,
+) -> Result<f64, String> {
+    let rating_map: HashMap<String, f64> = sqlx::query_as::<_, (String, f64)>(
+        "SELECT text_value, numeric_value FROM rating_mappings WHERE dataset_id = ?",
+    )
+    .bind(dataset_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to load rating mappings: {}", e))?
+    .into_iter()
+    .collect();
+    let score_scale: Option<f64> =
+        sqlx::query_scalar::<_, Option<f64>>("SELECT score_scale FROM datasets WHERE id = ?")
+            .bind(dataset_id)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to load dataset: {}", e))?
+            .flatten();
+
+    let resolved = rating_value(&rating_map, raw_value).ok_or_else(|| {
+        format!(
+            "\"{}\" is neither a rating of this dataset nor a number",
+            raw_value
+        )
+    })?;
+    let highest = score_scale.or_else(|| rating_map.values().copied().reduce(f64::max));
+    if resolved < 0.0 || highest.is_some_and(|highest| resolved > highest) {
+        return Err(match highest {
+            Some(highest) => format!("Rating {} is outside 0 to {}", resolved, highest),
+            None => format!("Rating {} cannot be negative", resolved),
+        });
+    }
+    if numeric_value.is_some_and(|value| (value - resolved).abs() > f64::EPSILON) {
+        return Err(format!(
+            "Rating \"{}\" has the value {} in this dataset",
+            raw_value, resolved
+        ));
+    }
+    Ok(resolved)
+}
+
+/// Errors unless the employee belongs to the dataset and the competency exists.
+async fn ensure_cell(
+    conn: &mut SqliteConnection,
+    dataset_id: i64,
+    employee_id: i64,
+    competency_id: i64,
+) -> Result<(), String> {
+    let (in_dataset, competency_exists): (bool, bool) = sqlx::query_as(
+        "SELECT EXISTS (SELECT 1 FROM dataset_employees WHERE dataset_id = ? AND employee_id = ?),
+                EXISTS (SELECT 1 FROM competencies WHERE id = ?)",
+    )
+    .bind(dataset_id)
+    .bind(employee_id)
+    .bind(competency_id)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to load dataset: {}", e))?;
+    if !in_dataset {
+        return Err(format!(
+            "Employee {} is not part of dataset {}",
+            employee_id, dataset_id
+        ));
+    }
+    if !competency_exists {
+        return Err(format!("Competency {} not found", competency_id));
+    }
+    Ok(())
+}
+
+/// Adds a competency new to the dataset after the existing ones.
+async fn link_competency(
+    conn: &mut SqliteConnection,
+    dataset_id: i64,
+    competency_id: i64,
+) -> Result<(), String> {
+    sqlx::query(
+        "INSERT OR IGNORE INTO dataset_competencies (dataset_id, competency_id, display_order)
+         SELECT ?, ?, IFNULL(MAX(display_order), -1) + 1
+         FROM dataset_competencies WHERE dataset_id = ?",
+    )
+    .bind(dataset_id)
+    .bind(competency_id)
+    .bind(dataset_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to update competency order: {}", e))?;
+    Ok(())
+}
+
+/// Keeps an edited value as the score's `manual` entry, so a later import aggregates it as
+/// one more rater instead of dropping it.
+async fn record_manual_entry(
+    conn: &mut SqliteConnection,
+    dataset_id: i64,
+    employee_id: i64,
+    competency_id: i64,
+    raw_value: &str,
+    numeric_value: f64,
+) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO score_entries (dataset_id, employee_id, competency_id, rater, raw_value, numeric_value, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, datetime('now'))
+         ON CONFLICT(dataset_id, employee_id, competency_id, rater) DO UPDATE
+         SET raw_value = excluded.raw_value,
+             numeric_value = excluded.numeric_value,
+             import_run_id = NULL,
+             created_at = excluded.created_at",
+    )
+    .bind(dataset_id)
+    .bind(employee_id)
+    .bind(competency_id)
+    .bind(MANUAL_RATER)
+    .bind(raw_value)
+    .bind(numeric_value)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to record score entry: {}", e))?;
+    Ok(())
+}
+
+fn clean_raw_value(raw_value: &str) -> Result<&str, String> {
+    let raw_value = raw_value.trim();
+    if raw_value.is_empty() {
+        return Err("Score value cannot be empty".to_string());
+    }
+    Ok(raw_value)
+}
+
+async fn load_score(pool: &SqlitePool, score_id: i64) -> Result<Score, String> {
+    sqlx::query_as::<_, Score>("SELECT * FROM scores WHERE id = ?")
+        .bind(score_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to load score: {}", e))?
+        .ok_or_else(|| format!("Score {} not found", score_id))
+}
+
+#[tauri::command]
+pub async fn create_score(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    employee_id: i64,
+    competency_id: i64,
+    raw_value: String,
+    numeric_value: Option<f64>,
+) -> Result<Score, String> {
+    state.require_role(Role::Editor).await?;
+    let raw_value = clean_raw_value(&raw_value)?;
+    let _lock = state.dataset_locks.acquire("score-edit", &[dataset_id])?;
+
+    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+    ensure_cell(&mut tx, dataset_id, employee_id, competency_id).await?;
+    let numeric_value =
+        resolve_numeric_value(&mut tx, dataset_id, raw_value, numeric_value).await?;
+    let score = sqlx::query_as::<_, Score>(
+        "INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, created_at)
+         VALUES (?, ?, ?, ?, ?, datetime('now'))
+         RETURNING *",
+    )
+    .bind(employee_id)
+    .bind(dataset_id)
+    .bind(competency_id)
+    .bind(raw_value)
+    .bind(numeric_value)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| match e.as_database_error() {
+        Some(db_error) if db_error.is_unique_violation() => {
+            "The employee already has a score for this competency; edit it instead".to_string()
+        }
+        _ => format!("Failed to create score: {}", e),
+    })?;
+    record_manual_entry(
+        &mut tx,
+        dataset_id,
+        employee_id,
+        competency_id,
+        raw_value,
+        numeric_value,
+    )
+    .await?;
+    link_competency(&mut tx, dataset_id, competency_id).await?;
+
+    record_audit(
+        &mut *tx,
+        "score_created",
+        Some(dataset_id),
+        &serde_json::json!({
+            "score_id": score.id,
+            "employee_id": employee_id,
+            "competency_id": competency_id,
+            "raw_value": score.raw_value,
+            "numeric_value": score.numeric_value,
+        }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(score)
+}
+
+#[tauri::command]
+pub async fn update_score(
+    state: State<'_, AppState>,
+    score_id: i64,
+    raw_value: String,
+    numeric_value: Option<f64>,
+) -> Result<Score, String> {
+    state.require_role(Role::Editor).await?;
+    let raw_value = clean_raw_value(&raw_value)?;
+    let previous = load_score(&state.pool, score_id).await?;
+    let _lock = state
+        .dataset_locks
+        .acquire("score-edit", &[previous.dataset_id])?;
+
+    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+    let numeric_value =
+        resolve_numeric_value(&mut tx, previous.dataset_id, raw_value, numeric_value).await?;
+    let score = sqlx::query_as::<_, Score>(
+        "UPDATE scores SET raw_value = ?, numeric_value = ?,
+            source_row = NULL, source_column = NULL, import_run_id = NULL
+         WHERE id = ? RETURNING *",
+    )
+    .bind(raw_value)
+    .bind(numeric_value)
+    .bind(score_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to update score: {}", e))?;
+    record_manual_entry(
+        &mut tx,
+        score.dataset_id,
+        score.employee_id,
+        score.competency_id,
+        raw_value,
+        numeric_value,
+    )
+    .await?;
+
+    record_audit(
+        &mut *tx,
+        "score_updated",
+        Some(score.dataset_id),
+        &serde_json::json!({
+            "score_id": score.id,
+            "employee_id": score.employee_id,
+            "competency_id": score.competency_id,
+            "old_raw_value": previous.raw_value,
+            "old_numeric_value": previous.numeric_value,
+            "new_raw_value": score.raw_value,
+            "new_numeric_value": score.numeric_value,
+        }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(score)
+}
+
+#[tauri::command]
+pub async fn delete_score(state: State<'_, AppState>, score_id: i64) -> Result<(), String> {
+    state.require_role(Role::Editor).await?;
+    let score = load_score(&state.pool, score_id).await?;
+    let _lock = state
+        .dataset_locks
+        .acquire("score-edit", &[score.dataset_id])?;
+
+    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query("DELETE FROM scores WHERE id = ?")
+        .bind(score_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to delete score: {}", e))?;
+    record_audit(
+        &mut *tx,
+        "score_deleted",
+        Some(score.dataset_id),
+        &serde_json::json!({
+            "score_id": score.id,
+            "employee_id": score.employee_id,
+            "competency_id": score.competency_id,
+            "raw_value": score.raw_value,
+            "numeric_value": score.numeric_value,
+        }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_score_matrix(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+) -> Result<ScoreMatrix, String> {
+    let pool = &state.pool;
+    let employees = sqlx::query_as::<_, Employee>(
+        "SELECT e.* FROM employees e
+         JOIN dataset_employees de ON de.employee_id = e.id
+         WHERE de.dataset_id = ?
+         ORDER BY LOWER(e.name), e.id",
+    )
+    .bind(dataset_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to load employees: {}", e))?;
+    let competencies = sqlx::query_as::<_, Competency>(
+        "SELECT c.* FROM competencies c
+         JOIN dataset_competencies dc ON dc.competency_id = c.id
+         WHERE dc.dataset_id = ?
+         ORDER BY dc.display_order, c.id",
+    )
+    .bind(dataset_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to load competencies: {}", e))?;
+    let scores = sqlx::query_as::<_, Score>(
+        "SELECT * FROM scores WHERE dataset_id = ? ORDER BY employee_id, competency_id",
+    )
+    .bind(dataset_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to load scores: {}", e))?;
+
+    Ok(ScoreMatrix {
+        employees,
+        competencies,
+        scores,
+    })
+}
+
+/// The individual rater entries behind an employee's scores, optionally for one competency.
+#[tauri::command]
+pub async fn list_score_entries(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    employee_id: i64,
+    competency_id: Option<i64>,
+) -> Result<Vec<ScoreEntry>, String> {
+    sqlx::query_as::<_, ScoreEntry>(
+        "SELECT * FROM score_entries
+         WHERE dataset_id = ? AND employee_id = ? AND (? IS NULL OR competency_id = ?)
+         ORDER BY competency_id, created_at, id",
+    )
+    .bind(dataset_id)
+    .bind(employee_id)
+    .bind(competency_id)
+    .bind(competency_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| format!("Failed to load score entries: {}", e))
+}
+
+/// Applies one grid cell, returning its values before and after.
+async fn apply_cell_change(
+    conn: &mut SqliteConnection,
+    dataset_id: i64,
+    change: &ScoreCellChange,
+) -> Result<AppliedCell, String> {
+    ensure_cell(conn, dataset_id, change.employee_id, change.competency_id).await?;
+    let previous: Option<(String, Option<f64>)> = sqlx::query_as(
+        "SELECT raw_value, numeric_value FROM scores
+         WHERE dataset_id = ? AND employee_id = ? AND competency_id = ?",
+    )
+    .bind(dataset_id)
+    .bind(change.employee_id)
+    .bind(change.competency_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to load score: {}", e))?;
+    let (old_raw_value, old_numeric_value) = previous.unzip();
+    let mut applied = AppliedCell {
+        employee_id: change.employee_id,
+        competency_id: change.competency_id,
+        old_raw_value,
+        old_numeric_value: old_numeric_value.flatten(),
+        new_raw_value: None,
+        new_numeric_value: None,
+    };
+
+    let raw_value = change
+        .raw_value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    let Some(raw_value) = raw_value else {
+        sqlx::query(
+            "DELETE FROM scores WHERE dataset_id = ? AND employee_id = ? AND competency_id = ?",
+        )
+        .bind(dataset_id)
+        .bind(change.employee_id)
+        .bind(change.competency_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to clear score: {}", e))?;
+        return Ok(applied);
+    };
+
+    let numeric_value = resolve_numeric_value(conn, dataset_id, raw_value, None).await?;
+    sqlx::query(
+        "INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, created_at)
+         VALUES (?, ?, ?, ?, ?, datetime('now'))
+         ON CONFLICT(dataset_id, employee_id, competency_id) DO UPDATE SET
+            raw_value = excluded.raw_value,
+            numeric_value = excluded.numeric_value,
+            source_row = NULL,
+            source_column = NULL,
+            import_run_id = NULL",
+    )
+    .bind(change.employee_id)
+    .bind(dataset_id)
+    .bind(change.competency_id)
+    .bind(raw_value)
+    .bind(numeric_value)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to save score: {}", e))?;
+    record_manual_entry(
+        conn,
+        dataset_id,
+        change.employee_id,
+        change.competency_id,
+        raw_value,
+        numeric_value,
+    )
+    .await?;
+    link_competency(conn, dataset_id, change.competency_id).await?;
+    applied.new_raw_value = Some(raw_value.to_string());
+    applied.new_numeric_value = Some(numeric_value);
+    Ok(applied)
+}
+
+/// Applies every valid change; cells that fail validation are reported instead of
+/// aborting the batch. The applied cells are returned alongside for the audit log.
+async fn apply_score_changes(
+    conn: &mut SqliteConnection,
+    dataset_id: i64,
+    changes: &[ScoreCellChange],
+) -> (ScoreMatrixSaveResult, Vec<AppliedCell>) {
+    let mut result = ScoreMatrixSaveResult {
+        saved: 0,
+        cleared: 0,
+        failed: Vec::new(),
+    };
+    let mut applied = Vec::new();
+    for change in changes {
+        match apply_cell_change(conn, dataset_id, change).await {
+            Ok(cell) => {
+                if cell.new_raw_value.is_some() {
+                    result.saved += 1;
+                } else {
+                    result.cleared += 1;
+                }
+                applied.push(cell);
+            }
+            Err(message) => result.failed.push(ScoreCellError {
+                employee_id: change.employee_id,
+                competency_id: change.competency_id,
+                message,
+            }),
+        }
+    }
+    (result, applied)
+}
+
+/// Saves a batch of grid edits in one transaction.
+#[tauri::command]
+pub async fn save_score_matrix(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    changes: Vec<ScoreCellChange>,
+) -> Result<ScoreMatrixSaveResult, String> {
+    state.require_role(Role::Editor).await?;
+    let _lock = state.dataset_locks.acquire("score-edit", &[dataset_id])?;
+
+    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+    let (result, applied) = apply_score_changes(&mut tx, dataset_id, &changes).await;
+    record_audit(
+        &mut *tx,
+        "score_matrix_saved",
+        Some(dataset_id),
+        &serde_json::json!({
+            "saved": result.saved,
+            "cleared": result.cleared,
+            "failed": result.failed.len(),
+            "cells": applied,
+        }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    #[tokio::test]
+    async fn test_resolve_numeric_value_checks_rating_mappings() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO datasets (id, name, created_at, updated_at)
+             VALUES (1, 'Penilaian 2024', datetime('now'), datetime('now'));
+             INSERT INTO rating_mappings (dataset_id, text_value, numeric_value)
+             VALUES (1, 'Baik', 75.0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let mut conn = pool.acquire().await.unwrap();
+        assert_eq!(
+            resolve_numeric_value(&mut conn, 1, "Baik", None).await,
+            Ok(75.0)
+        );
+        assert_eq!(
+            resolve_numeric_value(&mut conn, 1, "62.5", None).await,
+            Ok(62.5)
+        );
+        assert!(resolve_numeric_value(&mut conn, 1, "Baik", Some(80.0))
+            .await
+            .is_err());
+        assert!(resolve_numeric_value(&mut conn, 1, "Cukup", None)
+            .await
+            .is_err());
+        // Without a fixed scale the highest rating mapping bounds plain numbers
+        assert!(resolve_numeric_value(&mut conn, 1, "-5", None)
+            .await
+            .is_err());
+        assert!(resolve_numeric_value(&mut conn, 1, "120", None)
+            .await
+            .is_err());
+
+        sqlx::query("UPDATE datasets SET score_scale = 150 WHERE id = 1")
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+        assert_eq!(
+            resolve_numeric_value(&mut conn, 1, "120", None).await,
+            Ok(120.0)
+        );
+        assert!(resolve_numeric_value(&mut conn, 1, "151", None)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_apply_score_changes_reports_invalid_cells() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO datasets (id, name, created_at, updated_at)
+             VALUES (1, 'Penilaian 2024', datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, created_at, updated_at)
+             VALUES (1, 'Budi', datetime('now'), datetime('now'));
+             INSERT INTO dataset_employees (dataset_id, employee_id, created_at, updated_at)
+             VALUES (1, 1, datetime('now'), datetime('now'));
+             INSERT INTO competencies (id, name, display_order) VALUES (1, 'Kerjasama', 0);
+             INSERT INTO rating_mappings (dataset_id, text_value, numeric_value)
+             VALUES (1, 'Baik', 75.0);
+             INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, source_row, source_column, created_at)
+             VALUES (1, 1, 1, '60', 60.0, 4, 'C', datetime('now'))",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let change = |employee_id, raw_value: Option<&str>| ScoreCellChange {
+            employee_id,
+            competency_id: 1,
+            raw_value: raw_value.map(str::to_string),
+        };
+        let mut conn = pool.acquire().await.unwrap();
+        let (result, applied) = apply_score_changes(
+            &mut conn,
+            1,
+            &[
+                change(1, Some("Baik")),
+                change(2, Some("Baik")),
+                change(1, Some("Cukup")),
+            ],
+        )
+        .await;
+        assert_eq!(result.saved, 1);
+        assert_eq!(result.failed.len(), 2);
+        assert_eq!(
+            applied,
+            vec![AppliedCell {
+                employee_id: 1,
+                competency_id: 1,
+                old_raw_value: Some("60".to_string()),
+                old_numeric_value: Some(60.0),
+                new_raw_value: Some("Baik".to_string()),
+                new_numeric_value: Some(75.0),
+            }]
+        );
+        let entries: Vec<(String, String)> =
+            sqlx::query_as("SELECT rater, raw_value FROM score_entries")
+                .fetch_all(&mut *conn)
+                .await
+                .unwrap();
+        assert_eq!(
+            entries,
+            vec![(MANUAL_RATER.to_string(), "Baik".to_string())]
+        );
+        let provenance: (Option<i64>, Option<String>) =
+            sqlx::query_as("SELECT source_row, source_column FROM scores")
+                .fetch_one(&mut *conn)
+                .await
+                .unwrap();
+        assert_eq!(provenance, (None, None));
+
+        let (result, applied) = apply_score_changes(&mut conn, 1, &[change(1, None)]).await;
+        assert_eq!(result.cleared, 1);
+        assert_eq!(applied[0].old_raw_value.as_deref(), Some("Baik"));
+        assert_eq!(applied[0].new_raw_value, None);
+        let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM scores")
+            .fetch_one(&mut *conn)
+            .await
+            .unwrap();
+        assert_eq!(remaining, 0);
+    }
+}
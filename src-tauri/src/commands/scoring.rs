@@ -0,0 +1,499 @@
+use crate::auth::Role;
+use crate::commands::analytics::{
+    compute_employee_performance, EmployeePerformance, ScoreWithCompetency,
+};
+use crate::csv_parser::leadership::ParsedLeadershipScore;
+use crate::csv_parser::CsvParser;
+use crate::db::audit::record_audit;
+use crate::db::models::{Competency, Score};
+use crate::scoring::position::{load_position_keywords, save_position_keywords, PositionKeywords};
+use crate::scoring::weighting::{
+    self, load_competency_weights, load_weighting_scheme, save_competency_weight,
+    save_weighting_scheme, CompetencyWeight, WeightingConfigFile, WeightingScheme,
+    WEIGHTING_CONFIG_FORMAT,
+};
+use crate::scoring::{self, PositionType, WeightedScore};
+use crate::ui_config::{load_dataset_ui_config, load_ui_config, save_ui_config};
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::State;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeadershipImportResult {
+    pub imported: usize,
+    /// Names that match no employee of the dataset; their scores are skipped.
+    pub unmatched_names: Vec<String>,
+}
+
+#[tauri::command]
+pub async fn compute_weighted_score(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    employee_id: i64,
+) -> Result<WeightedScore, String> {
+    let pool = state.pool.clone();
+
+    let performance = compute_employee_performance(&pool, dataset_id, employee_id)
+        .await
+        .map_err(|e| format!("Failed to load employee performance: {}", e))?;
+
+    let config = load_dataset_ui_config(&pool, dataset_id)
+        .await
+        .map_err(|e| format!("Failed to load UI config: {}", e))?;
+    let scheme = load_weighting_scheme(&pool)
+        .await
+        .map_err(|e| format!("Failed to load weighting scheme: {}", e))?;
+    Ok(scoring::compute_weighted_score(
+        &performance,
+        &config,
+        &scheme,
+    ))
+}
+
+/// Hypothetical values for `simulate_score`; anything left out keeps the recorded value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScoreOverrides {
+    /// Numeric value per competency id, on the dataset's own scale
+    #[serde(default)]
+    pub competencies: HashMap<i64, f64>,
+    /// Penilaian Pimpinan on a 0-100 scale
+    #[serde(default)]
+    pub leadership_score: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreSimulation {
+    pub current: WeightedScore,
+    pub simulated: WeightedScore,
+    /// Simulated total minus the current total
+    pub delta: f64,
+}
+
+/// Replaces the scores named in `overrides`; a competency the employee has no score for
+/// is added from `competencies`.
+fn apply_score_overrides(
+    performance: &mut EmployeePerformance,
+    dataset_id: i64,
+    overrides: &ScoreOverrides,
+    competencies: &[Competency],
+) -> Result<(), String> {
+    for (&competency_id, &value) in &overrides.competencies {
+        if !value.is_finite() || value < 0.0 {
+            return Err(format!(
+                "Simulated value of competency {} must be a non-negative number",
+                competency_id
+            ));
+        }
+        let existing = performance
+            .scores
+            .iter_mut()
+            .find(|entry| entry.competency.id == competency_id);
+        match existing {
+            Some(entry) => {
+                entry.score.raw_value = value.to_string();
+                entry.score.numeric_value = Some(value);
+            }
+            None => {
+                let competency = competencies
+                    .iter()
+                    .find(|competency| competency.id == competency_id)
+                    .ok_or_else(|| format!("Competency {} not found", competency_id))?;
+                performance.scores.push(ScoreWithCompetency {
+                    score: Score {
+                        id: 0,
+                        employee_id: performance.employee.id,
+                        dataset_id,
+                        competency_id,
+                        raw_value: value.to_string(),
+                        numeric_value: Some(value),
+                        created_at: chrono::Utc::now(),
+                        source_row: None,
+                        source_column: None,
+                        import_run_id: None,
+                    },
+                    competency: competency.clone(),
+                });
+            }
+        }
+    }
+
+    if let Some(leadership_score) = overrides.leadership_score {
+        if !(0.0..=100.0).contains(&leadership_score) {
+            return Err("Simulated leadership score must be between 0 and 100".to_string());
+        }
+        performance.leadership_score = Some(leadership_score);
+    }
+    Ok(())
+}
+
+/// Re-runs the weighted score with hypothetical competency or leadership values, e.g. to
+/// see what an employee needs to reach the next rating. Nothing is saved.
+#[tauri::command]
+pub async fn simulate_score(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    employee_id: i64,
+    overrides: ScoreOverrides,
+) -> Result<ScoreSimulation, String> {
+    let pool = state.pool.clone();
+
+    let mut performance = compute_employee_performance(&pool, dataset_id, employee_id)
+        .await
+        .map_err(|e| format!("Failed to load employee performance: {}", e))?;
+    let competencies = sqlx::query_as::<_, Competency>("SELECT * FROM competencies")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| format!("Failed to load competencies: {}", e))?;
+    let config = load_dataset_ui_config(&pool, dataset_id)
+        .await
+        .map_err(|e| format!("Failed to load UI config: {}", e))?;
+    let scheme = load_weighting_scheme(&pool)
+        .await
+        .map_err(|e| format!("Failed to load weighting scheme: {}", e))?;
+
+    let current = scoring::compute_weighted_score(&performance, &config, &scheme);
+    apply_score_overrides(&mut performance, dataset_id, &overrides, &competencies)?;
+    let simulated = scoring::compute_weighted_score(&performance, &config, &scheme);
+    Ok(ScoreSimulation {
+        delta: simulated.total_score - current.total_score,
+        current,
+        simulated,
+    })
+}
+
+#[tauri::command]
+pub async fn get_position_keywords(state: State<'_, AppState>) -> Result<PositionKeywords, String> {
+    load_position_keywords(&state.pool)
+        .await
+        .map_err(|e| format!("Failed to load position keywords: {}", e))
+}
+
+#[tauri::command]
+pub async fn update_position_keywords(
+    state: State<'_, AppState>,
+    keywords: PositionKeywords,
+) -> Result<PositionKeywords, String> {
+    state.require_role(Role::Editor).await?;
+    save_position_keywords(&state.pool, keywords).await
+}
+
+#[tauri::command]
+pub async fn list_competency_weights(
+    state: State<'_, AppState>,
+) -> Result<Vec<CompetencyWeight>, String> {
+    load_competency_weights(&state.pool)
+        .await
+        .map_err(|e| format!("Failed to load competency weights: {}", e))
+}
+
+/// Counts a competency towards `parameter` (its id or name) for one position type with
+/// `weight` percent of the 0-100 score, see `CompetencyWeight::weight`. Returns every mapping
+/// so the settings screen can refresh in one call.
+#[tauri::command]
+pub async fn set_competency_weight(
+    state: State<'_, AppState>,
+    competency_id: i64,
+    position_type: PositionType,
+    parameter: String,
+    weight: f64,
+) -> Result<Vec<CompetencyWeight>, String> {
+    state.require_role(Role::Editor).await?;
+    let weights = save_competency_weight(
+        &state.pool,
+        competency_id,
+        position_type,
+        &parameter,
+        weight,
+    )
+    .await?;
+    let saved = weights
+        .iter()
+        .find(|saved| saved.competency_id == competency_id && saved.position_type == position_type);
+    record_audit(
+        &state.pool,
+        "competency_weight_set",
+        None,
+        &serde_json::json!({
+            "competency_id": competency_id,
+            "position_type": position_type.label(),
+            "parameter_id": saved.map(|saved| saved.parameter_id.as_str()),
+            "weight": weight,
+        }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    Ok(weights)
+}
+
+#[tauri::command]
+pub async fn delete_competency_weight(
+    state: State<'_, AppState>,
+    competency_id: i64,
+    position_type: PositionType,
+) -> Result<Vec<CompetencyWeight>, String> {
+    state.require_role(Role::Editor).await?;
+    let weights =
+        weighting::delete_competency_weight(&state.pool, competency_id, position_type).await?;
+    record_audit(
+        &state.pool,
+        "competency_weight_deleted",
+        None,
+        &serde_json::json!({
+            "competency_id": competency_id,
+            "position_type": position_type.label(),
+        }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    Ok(weights)
+}
+
+/// Scores `parameter` (e.g. "Kehadiran dan ketepatan waktu") from imported attendance;
+/// `None` goes back to the survey answers.
+#[tauri::command]
+pub async fn set_attendance_parameter(
+    state: State<'_, AppState>,
+    parameter: Option<String>,
+) -> Result<WeightingScheme, String> {
+    state.require_role(Role::Editor).await?;
+    let scheme = load_weighting_scheme(&state.pool)
+        .await
+        .map_err(|e| format!("Failed to load weighting scheme: {}", e))?;
+    let previous = scheme.attendance_parameter.clone();
+    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+    // Saving checks that the parameter is one of the Perilaku or Kualitas parameters
+    let scheme = save_weighting_scheme(
+        &mut *tx,
+        WeightingScheme {
+            attendance_parameter: parameter,
+            ..scheme
+        },
+    )
+    .await?;
+    record_audit(
+        &mut *tx,
+        "attendance_parameter_set",
+        None,
+        &serde_json::json!({
+            "old_parameter": previous,
+            "new_parameter": scheme.attendance_parameter,
+        }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    Ok(scheme)
+}
+
+/// Writes the weighting scheme, parameter aliases and rating bands to a JSON file that
+/// another office can load with `import_weighting_config`.
+#[tauri::command]
+pub async fn export_weighting_config(
+    state: State<'_, AppState>,
+    file_path: String,
+) -> Result<(), String> {
+    let pool = state.pool.clone();
+
+    let weighting = load_weighting_scheme(&pool)
+        .await
+        .map_err(|e| format!("Failed to load weighting scheme: {}", e))?;
+    let config = load_ui_config(&pool)
+        .await
+        .map_err(|e| format!("Failed to load UI config: {}", e))?;
+    let file = WeightingConfigFile {
+        format: WEIGHTING_CONFIG_FORMAT.to_string(),
+        weighting,
+        rating_bands: config.rating_bands,
+    };
+
+    let json = serde_json::to_string_pretty(&file)
+        .map_err(|e| format!("Failed to serialize weighting config: {}", e))?;
+    std::fs::write(&file_path, json).map_err(|e| format!("Failed to write weighting config: {}", e))
+}
+
+/// Replaces the weighting scheme and rating bands with the ones in an exported file.
+#[tauri::command]
+pub async fn import_weighting_config(
+    state: State<'_, AppState>,
+    file_path: String,
+) -> Result<WeightingConfigFile, String> {
+    state.require_role(Role::Editor).await?;
+    let pool = state.pool.clone();
+
+    let content = std::fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read weighting config: {}", e))?;
+    let file: WeightingConfigFile = serde_json::from_str(&content)
+        .map_err(|e| format!("File is not a valid weighting config: {}", e))?;
+    if file.format != WEIGHTING_CONFIG_FORMAT {
+        return Err(format!(
+            "Unsupported weighting config format: {}",
+            file.format
+        ));
+    }
+
+    let mut config = load_ui_config(&pool)
+        .await
+        .map_err(|e| format!("Failed to load UI config: {}", e))?;
+    config.rating_bands = file.rating_bands;
+
+    // The bands and the scheme are replaced together or not at all
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let config = save_ui_config(&mut *tx, config).await?;
+    let weighting = save_weighting_scheme(&mut *tx, file.weighting).await?;
+    record_audit(
+        &mut *tx,
+        "weighting_config_imported",
+        None,
+        &serde_json::json!({
+            "file_path": file_path,
+            "parameters": weighting.parameter_names().collect::<Vec<_>>(),
+            "rating_bands": config.rating_bands.len(),
+        }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(WeightingConfigFile {
+        format: file.format,
+        weighting,
+        rating_bands: config.rating_bands,
+    })
+}
+
+/// Stores Penilaian Pimpinan scores for the dataset's employees, matched by name; a later
+/// import replaces an employee's earlier score.
+#[tauri::command]
+pub async fn import_leadership_scores(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    entries: Vec<ParsedLeadershipScore>,
+) -> Result<LeadershipImportResult, String> {
+    state.require_role(Role::Editor).await?;
+    if let Some(entry) = entries
+        .iter()
+        .find(|entry| !(0.0..=100.0).contains(&entry.score))
+    {
+        return Err(format!(
+            "Leadership score for {} must be between 0 and 100",
+            entry.employee_name
+        ));
+    }
+    let _lock = state
+        .dataset_locks
+        .acquire("leadership-import", &[dataset_id])?;
+
+    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+    let employees: HashMap<String, i64> = sqlx::query_as::<_, (i64, String)>(
+        "SELECT e.id, e.name FROM employees e
+         JOIN dataset_employees de ON de.employee_id = e.id
+         WHERE de.dataset_id = ?",
+    )
+    .bind(dataset_id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to load employees: {}", e))?
+    .into_iter()
+    .map(|(id, name)| (CsvParser::clean_field(&name).to_lowercase(), id))
+    .collect();
+
+    let mut imported = 0;
+    let mut unmatched_names = Vec::new();
+    for entry in &entries {
+        let key = CsvParser::clean_field(&entry.employee_name).to_lowercase();
+        let Some(employee_id) = employees.get(&key) else {
+            unmatched_names.push(entry.employee_name.clone());
+            continue;
+        };
+        sqlx::query(
+            "INSERT INTO leadership_scores (dataset_id, employee_id, score, created_at, updated_at)
+             VALUES (?, ?, ?, datetime('now'), datetime('now'))
+             ON CONFLICT(dataset_id, employee_id) DO UPDATE SET
+                score = excluded.score,
+                updated_at = excluded.updated_at",
+        )
+        .bind(dataset_id)
+        .bind(employee_id)
+        .bind(entry.score)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to save leadership score: {}", e))?;
+        imported += 1;
+    }
+
+    record_audit(
+        &mut *tx,
+        "leadership_scores_imported",
+        Some(dataset_id),
+        &serde_json::json!({
+            "imported": imported,
+            "unmatched_names": unmatched_names,
+        }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(LeadershipImportResult {
+        imported,
+        unmatched_names,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    #[tokio::test]
+    async fn test_overrides_replace_and_add_scores() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO datasets (id, name, created_at, updated_at)
+             VALUES (1, 'Semester 1', datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, created_at, updated_at)
+             VALUES (1, 'Budi', datetime('now'), datetime('now'));
+             INSERT INTO dataset_employees (dataset_id, employee_id, created_at, updated_at)
+             VALUES (1, 1, datetime('now'), datetime('now'));
+             INSERT INTO competencies (id, name, display_order)
+             VALUES (1, 'Kerjasama', 0), (2, 'Integritas', 1);
+             INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, created_at)
+             VALUES (1, 1, 1, 'Baik', 75.0, datetime('now'))",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let mut performance = compute_employee_performance(&pool, 1, 1).await.unwrap();
+        let competencies = sqlx::query_as::<_, Competency>("SELECT * FROM competencies")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+
+        let overrides = ScoreOverrides {
+            competencies: HashMap::from([(1, 90.0), (2, 80.0)]),
+            leadership_score: Some(95.0),
+        };
+        apply_score_overrides(&mut performance, 1, &overrides, &competencies).unwrap();
+        let mut values: Vec<(i64, Option<f64>)> = performance
+            .scores
+            .iter()
+            .map(|entry| (entry.competency.id, entry.score.numeric_value))
+            .collect();
+        values.sort_by_key(|(id, _)| *id);
+        assert_eq!(values, vec![(1, Some(90.0)), (2, Some(80.0))]);
+        assert_eq!(performance.leadership_score, Some(95.0));
+
+        let invalid = ScoreOverrides {
+            leadership_score: Some(120.0),
+            ..ScoreOverrides::default()
+        };
+        assert!(apply_score_overrides(&mut performance, 1, &invalid, &competencies).is_err());
+    }
+}
@@ -0,0 +1,112 @@
+use crate::AppState;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use tauri::State;
+
+const DEFAULT_SEARCH_LIMIT: i64 = 50;
+const MAX_SEARCH_LIMIT: i64 = 200;
+
+/// One hit from the global search index. `kind` is `employee`, `dataset` or `summary`;
+/// `id` is the row id in the matching source table.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct SearchHit {
+    pub kind: String,
+    pub id: i64,
+    pub title: String,
+    pub employee_id: Option<i64>,
+    pub snippet: String,
+    pub rank: f64,
+}
+
+/// Turns free user input into an FTS5 query: every word becomes a quoted prefix term,
+/// so operators and punctuation typed by the user are matched literally.
+fn build_match_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"*", term.replace('"', "\"\"")))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+pub async fn search(pool: &SqlitePool, query: &str, limit: i64) -> Result<Vec<SearchHit>, String> {
+    let Some(expression) = build_match_expression(query) else {
+        return Ok(Vec::new());
+    };
+
+    sqlx::query_as::<_, SearchHit>(
+        r#"
+        SELECT si.kind AS kind,
+               CAST(si.ref_id AS INTEGER) AS id,
+               CASE WHEN si.kind = 'summary' THEN COALESCE(e.name, '') ELSE si.title END AS title,
+               CASE
+                   WHEN si.kind = 'summary' THEN sm.employee_id
+                   WHEN si.kind = 'employee' THEN CAST(si.ref_id AS INTEGER)
+               END AS employee_id,
+               snippet(search_index, -1, '<mark>', '</mark>', '…', 12) AS snippet,
+               bm25(search_index) AS rank
+        FROM search_index si
+        LEFT JOIN summaries sm ON si.kind = 'summary' AND sm.id = si.ref_id
+        LEFT JOIN employees e ON e.id = sm.employee_id
+        WHERE search_index MATCH ?
+        ORDER BY rank
+        LIMIT ?
+        "#,
+    )
+    .bind(expression)
+    .bind(limit.clamp(1, MAX_SEARCH_LIMIT))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to search: {}", e))
+}
+
+#[tauri::command]
+pub async fn global_search(
+    state: State<'_, AppState>,
+    query: String,
+    limit: Option<i64>,
+) -> Result<Vec<SearchHit>, String> {
+    search(&state.pool, &query, limit.unwrap_or(DEFAULT_SEARCH_LIMIT)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    #[tokio::test]
+    async fn test_search_tracks_source_tables() {
+        let pool = test_pool().await;
+
+        let employee_id: i64 = sqlx::query_scalar(
+            "INSERT INTO employees (name, nip, jabatan) VALUES ('Budi Santoso', '198701', 'Kepala Seksi') RETURNING id",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO datasets (name, description) VALUES ('Penilaian 2024', 'Evaluasi Budi dan tim')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let hits = search(&pool, "bud", 10).await.unwrap();
+        assert_eq!(hits.len(), 2);
+        let employee_hit = hits.iter().find(|hit| hit.kind == "employee").unwrap();
+        assert_eq!(employee_hit.employee_id, Some(employee_id));
+        let dataset_hit = hits.iter().find(|hit| hit.kind == "dataset").unwrap();
+        assert_eq!(dataset_hit.title, "Penilaian 2024");
+        assert!(dataset_hit.snippet.contains("<mark>Budi</mark>"));
+
+        sqlx::query("UPDATE employees SET jabatan = 'Analis' WHERE id = ?")
+            .bind(employee_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(search(&pool, "kepala", 10).await.unwrap().is_empty());
+        assert_eq!(search(&pool, "analis \"", 10).await.unwrap().len(), 1);
+    }
+}
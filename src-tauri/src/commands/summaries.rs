@@ -1,8 +1,22 @@
-use crate::commands::analytics::{compute_employee_performance, EmployeePerformance};
-use crate::db::models::Summary;
+use crate::auth::Role;
+use crate::commands::analytics::{
+    compute_dataset_performances, compute_employee_performance, sanitize_text, EmployeePerformance,
+};
+use crate::data_quality::assess_dataset;
+use crate::db::data_directory;
+use crate::db::models::{Summary, SummaryRevision};
+use crate::db::settings::{get_setting, set_setting};
+use crate::i18n::{fill, Locale, Messages};
+use crate::pdf::filename::resolve_export_path;
+use crate::pdf::letterhead::{draw_running_header, load_report_settings, ReportSettings};
+use crate::pdf::paper::{Orientation, PageLayout};
+use crate::pdf::{Canvas, Font, Pdf};
+use crate::secrets;
 use crate::AppState;
-use pdf_canvas::{BuiltinFont, Canvas, Pdf};
 use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::time::Duration;
 use tauri::State;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,6 +24,131 @@ pub struct GeneratedSummary {
     pub content: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SummaryLintIssue {
+    pub code: String,
+    pub severity: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummarySaveResult {
+    pub summary: Summary,
+    pub issues: Vec<SummaryLintIssue>,
+}
+
+/// Where drafts come from when the AI provider is used; JSON in app_settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AiProviderSettings {
+    pub enabled: bool,
+    /// Base URL of an OpenAI-compatible API, e.g. `https://api.openai.com/v1` or a local
+    /// Ollama at `http://localhost:11434/v1`
+    pub endpoint: String,
+    /// The API key sealed with `secrets::seal`; only `request_ai_summary` opens it
+    pub sealed_api_key: Option<String>,
+    pub model: String,
+}
+
+/// The settings as shown to the settings screen, without the key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiProviderSettingsView {
+    pub enabled: bool,
+    pub endpoint: String,
+    pub has_api_key: bool,
+    pub model: String,
+}
+
+impl From<AiProviderSettings> for AiProviderSettingsView {
+    fn from(settings: AiProviderSettings) -> Self {
+        Self {
+            enabled: settings.enabled,
+            endpoint: settings.endpoint,
+            has_api_key: settings.sealed_api_key.is_some(),
+            model: settings.model,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiProviderSettingsUpdate {
+    pub enabled: bool,
+    pub endpoint: String,
+    /// `None` keeps the saved key and an empty string removes it
+    pub api_key: Option<String>,
+    pub model: String,
+}
+
+impl Default for AiProviderSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: "http://localhost:11434/v1".to_string(),
+            sealed_api_key: None,
+            model: "llama3.1".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiSummaryDraft {
+    pub content: String,
+    /// `ai` or `rule_based`
+    pub generated_by: String,
+    /// Why the rule-based generator was used instead of the provider
+    pub fallback_reason: Option<String>,
+}
+
+const AI_PROVIDER_KEY: &str = "summaries.ai_provider";
+const AI_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+/// The provider never sees the name; it writes this token and the draft is filled in locally.
+/// Every locale's `ai_system_prompt` asks for this token.
+const AI_EMPLOYEE_PLACEHOLDER: &str = "[PEGAWAI]";
+
+const PLACEHOLDER_PATTERNS: &[&str] = &[
+    "lorem ipsum",
+    "todo",
+    "tbd",
+    "xxx",
+    "[nama",
+    "{nama",
+    "<nama",
+    "{{",
+    "isi di sini",
+    "placeholder",
+];
+
+const PRAISE_KEYWORDS: &[&str] = &[
+    "kekuatan",
+    "unggul",
+    "sangat baik",
+    "menonjol",
+    "terbaik",
+    "tertinggi",
+    "istimewa",
+    "strength",
+    "excellent",
+];
+
+const CONCERN_KEYWORDS: &[&str] = &[
+    "perlu perbaikan",
+    "perlu ditingkatkan",
+    "kelemahan",
+    "area pengembangan",
+    "memerlukan perhatian",
+    "terendah",
+    "kurang",
+    "weakness",
+];
+
+const CLAUSE_BREAKS: &[&str] = &[
+    " sementara ",
+    " namun ",
+    " tetapi ",
+    " tapi ",
+    " sedangkan ",
+];
+
 #[tauri::command]
 pub async fn generate_employee_summary(
     state: State<'_, AppState>,
@@ -21,13 +160,183 @@ pub async fn generate_employee_summary(
     let performance = compute_employee_performance(&pool, dataset_id, employee_id)
         .await
         .map_err(|e| format!("Failed to generate summary: {}", e))?;
+    let locale = load_report_settings(&pool).await?.locale;
 
-    let content = build_summary(&performance);
+    let content = build_summary(&performance, locale.messages());
 
     Ok(GeneratedSummary { content })
 }
 
-fn build_summary(performance: &EmployeePerformance) -> String {
+pub async fn load_ai_provider_settings(pool: &SqlitePool) -> Result<AiProviderSettings, String> {
+    let stored = get_setting(pool, AI_PROVIDER_KEY)
+        .await
+        .map_err(|e| format!("Failed to load AI provider settings: {}", e))?;
+    Ok(stored
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn get_ai_provider_settings(
+    state: State<'_, AppState>,
+) -> Result<AiProviderSettingsView, String> {
+    state.require_role(Role::Admin).await?;
+    load_ai_provider_settings(&state.pool).await.map(Into::into)
+}
+
+#[tauri::command]
+pub async fn update_ai_provider_settings(
+    state: State<'_, AppState>,
+    settings: AiProviderSettingsUpdate,
+) -> Result<AiProviderSettingsView, String> {
+    state.require_role(Role::Admin).await?;
+    let sealed_api_key = match settings.api_key.as_deref().map(str::trim) {
+        None => load_ai_provider_settings(&state.pool).await?.sealed_api_key,
+        Some("") => None,
+        Some(key) => Some(secrets::seal(&data_directory(&state.pool).await?, key)?),
+    };
+    let settings = AiProviderSettings {
+        enabled: settings.enabled,
+        endpoint: settings.endpoint.trim().trim_end_matches('/').to_string(),
+        sealed_api_key,
+        model: settings.model.trim().to_string(),
+    };
+    if !settings.endpoint.starts_with("http://") && !settings.endpoint.starts_with("https://") {
+        return Err("AI endpoint must be an http:// or https:// URL".to_string());
+    }
+    if settings.model.is_empty() {
+        return Err("AI model cannot be empty".to_string());
+    }
+
+    let value = serde_json::to_string(&settings)
+        .map_err(|e| format!("Failed to serialize AI provider settings: {}", e))?;
+    set_setting(&state.pool, AI_PROVIDER_KEY, &value)
+        .await
+        .map_err(|e| format!("Failed to save AI provider settings: {}", e))?;
+    Ok(settings.into())
+}
+
+/// Drafts a narrative with the configured AI provider, falling back to the rule-based
+/// generator when the provider is disabled, unreachable or returns nothing usable.
+#[tauri::command]
+pub async fn generate_employee_summary_ai(
+    state: State<'_, AppState>,
+    employee_id: i64,
+    dataset_id: i64,
+) -> Result<AiSummaryDraft, String> {
+    state.require_role(Role::Editor).await?;
+    let pool = state.pool.clone();
+
+    let performance = compute_employee_performance(&pool, dataset_id, employee_id)
+        .await
+        .map_err(|e| format!("Failed to generate summary: {}", e))?;
+    let settings = load_ai_provider_settings(&pool).await?;
+    let text = load_report_settings(&pool).await?.locale.messages();
+
+    let drafted = if settings.enabled {
+        request_ai_summary(
+            &pool,
+            &settings,
+            text.ai_system_prompt,
+            &build_ai_prompt(&performance, text),
+        )
+        .await
+    } else {
+        Err("AI provider is disabled".to_string())
+    };
+
+    Ok(match drafted {
+        Ok(content) => AiSummaryDraft {
+            content: content.replace(AI_EMPLOYEE_PLACEHOLDER, &performance.employee.name),
+            generated_by: "ai".to_string(),
+            fallback_reason: None,
+        },
+        Err(reason) => AiSummaryDraft {
+            content: build_summary(&performance, text),
+            generated_by: "rule_based".to_string(),
+            fallback_reason: Some(reason),
+        },
+    })
+}
+
+/// Scores and competency names only: no name, NIP or jabatan leaves the machine.
+fn build_ai_prompt(performance: &EmployeePerformance, text: &Messages) -> String {
+    let mut lines = vec![
+        fill(text.ai_position, &[&performance.position_type.label()]),
+        fill(
+            text.ai_average,
+            &[
+                &format!("{:.2}", performance.average_score),
+                &performance.scores.len(),
+            ],
+        ),
+        text.ai_scores.to_string(),
+    ];
+    lines.extend(
+        performance
+            .scores
+            .iter()
+            .map(|score| match score.score.numeric_value {
+                Some(value) => format!("- {}: {:.2}", score.competency.name, value),
+                None => format!("- {}: {}", score.competency.name, score.score.raw_value),
+            }),
+    );
+    if !performance.strengths.is_empty() {
+        lines.push(fill(
+            text.ai_strengths,
+            &[&performance.strengths.join(", ")],
+        ));
+    }
+    if !performance.gaps.is_empty() {
+        lines.push(fill(text.ai_gaps, &[&performance.gaps.join(", ")]));
+    }
+    lines.join("\n")
+}
+
+async fn request_ai_summary(
+    pool: &SqlitePool,
+    settings: &AiProviderSettings,
+    system_prompt: &str,
+    prompt: &str,
+) -> Result<String, String> {
+    let client = reqwest::Client::builder()
+        .timeout(AI_REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let mut request = client
+        .post(format!("{}/chat/completions", settings.endpoint))
+        .json(&serde_json::json!({
+            "model": settings.model,
+            "temperature": 0.3,
+            "messages": [
+                { "role": "system", "content": system_prompt },
+                { "role": "user", "content": prompt },
+            ],
+        }));
+    if let Some(sealed) = &settings.sealed_api_key {
+        let api_key = secrets::open(&data_directory(pool).await?, sealed)?;
+        request = request.bearer_auth(api_key);
+    }
+
+    let response: serde_json::Value = request
+        .send()
+        .await
+        .map_err(|e| format!("AI provider is unreachable: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("AI provider rejected the request: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("AI provider returned an invalid response: {}", e))?;
+
+    response["choices"][0]["message"]["content"]
+        .as_str()
+        .map(str::trim)
+        .filter(|content| !content.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| "AI provider returned an empty summary".to_string())
+}
+
+fn build_summary(performance: &EmployeePerformance, text: &Messages) -> String {
     let employee = &performance.employee;
     let total_competencies = performance.scores.len();
     let average = performance.average_score;
@@ -48,183 +357,556 @@ fn build_summary(performance: &EmployeePerformance) -> String {
     let lowest_competency = numeric_scores.last();
 
     let strengths_text = if performance.strengths.is_empty() {
-        "Belum ada kompetensi dengan skor numerik tercatat sebagai kekuatan utama.".to_string()
+        text.summary_no_strengths.to_string()
     } else {
-        format!(
-            "Kekuatan utama saat ini mencakup {}.",
-            performance
-                .strengths
-                .iter()
-                .map(|s| format!("{}", s))
-                .collect::<Vec<_>>()
-                .join(", ")
-        )
+        fill(text.summary_strengths, &[&performance.strengths.join(", ")])
     };
 
     let gaps_text = if performance.gaps.is_empty() {
-        "Tidak ada area pengembangan yang tercatat karena nilai numerik belum lengkap.".to_string()
+        text.summary_no_gaps.to_string()
     } else {
-        format!(
-            "Area yang memerlukan perhatian lanjutan meliputi {}.",
-            performance
-                .gaps
-                .iter()
-                .map(|s| format!("{}", s))
-                .collect::<Vec<_>>()
-                .join(", ")
-        )
+        fill(text.summary_gaps, &[&performance.gaps.join(", ")])
     };
 
     let highlight_text = match (top_competency, lowest_competency) {
-        (Some(top), Some(low)) if top.0 != low.0 => format!(
-            "Skor tertinggi berada pada kompetensi {} dengan nilai {:.2}, sementara skor terendah tercatat pada {} dengan nilai {:.2}.",
-            top.0, top.1, low.0, low.1
+        (Some(top), Some(low)) if top.0 != low.0 => fill(
+            text.summary_highlight_range,
+            &[
+                &top.0,
+                &format!("{:.2}", top.1),
+                &low.0,
+                &format!("{:.2}", low.1),
+            ],
         ),
-        (Some(top), _) => format!(
-            "Kompetensi dengan capaian tertinggi adalah {} dengan nilai {:.2}.",
-            top.0, top.1
+        (Some(top), _) => fill(
+            text.summary_highlight_top,
+            &[&top.0, &format!("{:.2}", top.1)],
         ),
-        _ => "Belum tersedia skor numerik untuk mendeskripsikan capaian kompetensi secara detail.".to_string(),
+        _ => text.summary_no_highlight.to_string(),
     };
 
     let role_text = match (&employee.jabatan, &employee.sub_jabatan) {
         (Some(jabatan), Some(sub)) if !jabatan.is_empty() && !sub.is_empty() => {
-            format!("berperan sebagai {} ({})", jabatan, sub)
+            fill(text.summary_role_with_sub, &[jabatan, sub])
         }
-        (Some(jabatan), _) if !jabatan.is_empty() => format!("berperan sebagai {}", jabatan),
-        _ => "berperan sebagai karyawan".to_string(),
+        (Some(jabatan), _) if !jabatan.is_empty() => fill(text.summary_role, &[jabatan]),
+        _ => text.summary_role_default.to_string(),
     };
 
     let nip_text = employee
         .nip
         .as_deref()
         .filter(|nip| !nip.is_empty())
-        .map(|nip| format!(" dengan NIP {}", nip))
+        .map(|nip| fill(text.summary_nip, &[&nip]))
         .unwrap_or_default();
 
-    let intro = format!(
-        "{} saat ini {}{}. Rata-rata pencapaian dari {} kompetensi yang dinilai adalah {:.2}.",
-        employee.name, role_text, nip_text, total_competencies, average
+    let intro = fill(
+        text.summary_intro,
+        &[
+            &employee.name,
+            &role_text,
+            &nip_text,
+            &total_competencies,
+            &format!("{:.2}", average),
+        ],
     );
 
-    let supportive = if average >= 3.5 {
-        "Secara keseluruhan performa berada pada kategori sangat baik dan konsisten di atas ekspektasi organisasi.".to_string()
+    let level = if average >= 3.5 {
+        0
     } else if average >= 3.0 {
-        "Secara keseluruhan performa berada pada kategori baik dengan hasil yang stabil dan memenuhi target utama.".to_string()
+        1
     } else if average >= 2.5 {
-        "Rata-rata skor menunjukkan performa cukup dengan beberapa area yang masih memerlukan peningkatan.".to_string()
+        2
     } else {
-        "Performa saat ini berada di bawah target organisasi sehingga dibutuhkan rencana pengembangan terstruktur.".to_string()
+        3
     };
 
-    let closing = "Rekomendasikan tindak lanjut berupa sesi umpan balik terjadwal, pemantauan target triwulanan, serta dukungan pelatihan yang relevan agar progres dapat diakselerasi.";
-
     vec![
         intro,
-        supportive,
+        text.summary_levels[level].to_string(),
         strengths_text,
         gaps_text,
         highlight_text,
-        closing.to_string(),
+        text.summary_closing.to_string(),
     ]
-    .into_iter()
-    .collect::<Vec<_>>()
     .join("\n\n")
 }
 
+/// Strip academic titles ("GUSNANDA EFFENDI, S.Pd, MM") so only the personal name is matched.
+fn name_core(name: &str) -> String {
+    sanitize_text(name.split(',').next().unwrap_or(name))
+}
+
+fn split_clauses(content: &str) -> Vec<String> {
+    content
+        .split(['.', '!', '?', ';', '\n'])
+        .flat_map(|sentence| {
+            let mut clauses = vec![sentence.to_lowercase()];
+            for marker in CLAUSE_BREAKS {
+                clauses = clauses
+                    .iter()
+                    .flat_map(|clause| clause.split(marker).map(str::to_string).collect::<Vec<_>>())
+                    .collect();
+            }
+            clauses
+        })
+        .filter(|clause| !clause.trim().is_empty())
+        .collect()
+}
+
+fn lint_content(
+    content: &str,
+    employee_name: &str,
+    other_employee_names: &[String],
+    strengths: &[String],
+    gaps: &[String],
+) -> Vec<SummaryLintIssue> {
+    let mut issues = Vec::new();
+    let normalized_content = format!(" {} ", sanitize_text(content));
+    let own_name = name_core(employee_name);
+
+    for other in other_employee_names {
+        let core = name_core(other);
+        if core.len() < 6 || core == own_name || own_name.contains(&core) {
+            continue;
+        }
+        if normalized_content.contains(&format!(" {} ", core)) {
+            issues.push(SummaryLintIssue {
+                code: "wrong_employee_name".to_string(),
+                severity: "error".to_string(),
+                message: format!("Ringkasan menyebut pegawai lain: {}", other),
+            });
+        }
+    }
+
+    let lowered = content.to_lowercase();
+    for pattern in PLACEHOLDER_PATTERNS {
+        let found = if pattern.chars().all(|ch| ch.is_ascii_alphabetic()) {
+            normalized_content.contains(&format!(" {} ", pattern))
+        } else {
+            lowered.contains(pattern)
+        };
+        if found {
+            issues.push(SummaryLintIssue {
+                code: "placeholder_text".to_string(),
+                severity: "error".to_string(),
+                message: format!("Ringkasan masih memuat teks placeholder \"{}\"", pattern),
+            });
+        }
+    }
+
+    let clauses: Vec<String> = split_clauses(content)
+        .iter()
+        .map(|clause| format!(" {} ", sanitize_text(clause)))
+        .collect();
+    let mentions = |competency: &str, keywords: &[&str]| {
+        let target = sanitize_text(competency);
+        !target.is_empty()
+            && clauses.iter().any(|clause| {
+                clause.contains(&format!(" {} ", target))
+                    && keywords
+                        .iter()
+                        .any(|keyword| clause.contains(&format!(" {} ", keyword)))
+            })
+    };
+
+    for gap in gaps.iter().filter(|gap| !strengths.contains(gap)) {
+        if mentions(gap, PRAISE_KEYWORDS) {
+            issues.push(SummaryLintIssue {
+                code: "praises_gap".to_string(),
+                severity: "warning".to_string(),
+                message: format!(
+                    "Kompetensi {} dipuji padahal termasuk area dengan skor terendah",
+                    gap
+                ),
+            });
+        }
+    }
+
+    for strength in strengths.iter().filter(|strength| !gaps.contains(strength)) {
+        if mentions(strength, CONCERN_KEYWORDS) {
+            issues.push(SummaryLintIssue {
+                code: "criticizes_strength".to_string(),
+                severity: "warning".to_string(),
+                message: format!(
+                    "Kompetensi {} disebut lemah padahal termasuk skor tertinggi",
+                    strength
+                ),
+            });
+        }
+    }
+
+    issues
+}
+
+async fn run_summary_lint(
+    pool: &SqlitePool,
+    employee_id: i64,
+    dataset_id: Option<i64>,
+    content: &str,
+) -> Result<Vec<SummaryLintIssue>, String> {
+    let employee_name: String = sqlx::query_scalar("SELECT name FROM employees WHERE id = ?")
+        .bind(employee_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to load employee: {}", e))?;
+
+    let other_employee_names: Vec<String> =
+        sqlx::query_scalar("SELECT name FROM employees WHERE id <> ?")
+            .bind(employee_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Failed to load employees: {}", e))?;
+
+    // Without an explicit dataset, compare against the most recent dataset the employee belongs to
+    let dataset_id = match dataset_id {
+        Some(id) => Some(id),
+        None => sqlx::query_scalar(
+            "SELECT d.id FROM datasets d
+             JOIN dataset_employees de ON de.dataset_id = d.id
+             WHERE de.employee_id = ?
+             ORDER BY d.created_at DESC, d.id DESC
+             LIMIT 1",
+        )
+        .bind(employee_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to resolve dataset: {}", e))?,
+    };
+
+    let (strengths, gaps) = match dataset_id {
+        Some(dataset_id) => {
+            let performance = compute_employee_performance(pool, dataset_id, employee_id)
+                .await
+                .map_err(|e| format!("Failed to load employee performance: {}", e))?;
+            (performance.strengths, performance.gaps)
+        }
+        None => (Vec::new(), Vec::new()),
+    };
+
+    Ok(lint_content(
+        content,
+        &employee_name,
+        &other_employee_names,
+        &strengths,
+        &gaps,
+    ))
+}
+
+#[tauri::command]
+pub async fn lint_summary(
+    state: State<'_, AppState>,
+    employee_id: i64,
+    content: String,
+    dataset_id: Option<i64>,
+) -> Result<Vec<SummaryLintIssue>, String> {
+    let pool = state.pool.clone();
+    run_summary_lint(&pool, employee_id, dataset_id, &content).await
+}
+
 #[tauri::command]
 pub async fn get_employee_summary(
     state: State<'_, AppState>,
     employee_id: i64,
+    dataset_id: i64,
 ) -> Result<Option<Summary>, String> {
     let pool = state.pool.clone();
 
-    let summary = sqlx::query_as::<_, Summary>("SELECT * FROM summaries WHERE employee_id = ?")
-        .bind(employee_id)
-        .fetch_optional(&pool)
+    let summary = load_summary(&pool, employee_id, dataset_id)
         .await
         .map_err(|e| format!("Failed to load summary: {}", e))?;
 
     Ok(summary)
 }
 
+pub(crate) async fn load_summary(
+    pool: &SqlitePool,
+    employee_id: i64,
+    dataset_id: i64,
+) -> Result<Option<Summary>, sqlx::Error> {
+    sqlx::query_as::<_, Summary>("SELECT * FROM summaries WHERE employee_id = ? AND dataset_id = ?")
+        .bind(employee_id)
+        .bind(dataset_id)
+        .fetch_optional(pool)
+        .await
+}
+
 #[tauri::command]
 pub async fn save_employee_summary(
     state: State<'_, AppState>,
     employee_id: i64,
+    dataset_id: i64,
     content: String,
-) -> Result<Summary, String> {
+) -> Result<SummarySaveResult, String> {
+    state.require_role(Role::Editor).await?;
     let pool = state.pool.clone();
 
+    let issues = run_summary_lint(&pool, employee_id, Some(dataset_id), &content).await?;
+    let summary = write_summary(&pool, employee_id, dataset_id, &content).await?;
+
+    Ok(SummarySaveResult { summary, issues })
+}
+
+/// Upserts the summary and appends the content to its revision history.
+async fn write_summary(
+    pool: &SqlitePool,
+    employee_id: i64,
+    dataset_id: i64,
+    content: &str,
+) -> Result<Summary, String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
     let summary = sqlx::query_as::<_, Summary>(
         r#"
-        INSERT INTO summaries (employee_id, content, created_at, updated_at)
-        VALUES (?, ?, datetime('now'), datetime('now'))
-        ON CONFLICT(employee_id) DO UPDATE
+        INSERT INTO summaries (employee_id, dataset_id, content, created_at, updated_at)
+        VALUES (?, ?, ?, datetime('now'), datetime('now'))
+        ON CONFLICT(employee_id, dataset_id) DO UPDATE
         SET content = excluded.content,
             updated_at = datetime('now')
         RETURNING *
         "#,
     )
     .bind(employee_id)
+    .bind(dataset_id)
     .bind(content)
-    .fetch_one(&pool)
+    .fetch_one(&mut *tx)
     .await
     .map_err(|e| format!("Failed to save summary: {}", e))?;
 
+    sqlx::query(
+        "INSERT INTO summary_revisions (employee_id, dataset_id, content, created_at)
+         VALUES (?, ?, ?, datetime('now'))",
+    )
+    .bind(employee_id)
+    .bind(dataset_id)
+    .bind(content)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to record summary revision: {}", e))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
     Ok(summary)
 }
 
+#[tauri::command]
+pub async fn list_summary_revisions(
+    state: State<'_, AppState>,
+    employee_id: i64,
+    dataset_id: i64,
+) -> Result<Vec<SummaryRevision>, String> {
+    sqlx::query_as::<_, SummaryRevision>(
+        "SELECT * FROM summary_revisions
+         WHERE employee_id = ? AND dataset_id = ?
+         ORDER BY created_at DESC, id DESC",
+    )
+    .bind(employee_id)
+    .bind(dataset_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| format!("Failed to load summary revisions: {}", e))
+}
+
+/// Makes an earlier revision the current summary; the restore is itself recorded as a
+/// new revision so the history stays append-only.
+#[tauri::command]
+pub async fn restore_summary_revision(
+    state: State<'_, AppState>,
+    revision_id: i64,
+) -> Result<Summary, String> {
+    state.require_role(Role::Editor).await?;
+    let pool = state.pool.clone();
+
+    let revision =
+        sqlx::query_as::<_, SummaryRevision>("SELECT * FROM summary_revisions WHERE id = ?")
+            .bind(revision_id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| format!("Failed to load summary revision: {}", e))?
+            .ok_or_else(|| format!("Summary revision {} not found", revision_id))?;
+
+    write_summary(
+        &pool,
+        revision.employee_id,
+        revision.dataset_id,
+        &revision.content,
+    )
+    .await
+}
+
 #[tauri::command]
 pub async fn export_employee_summary_pdf(
     state: State<'_, AppState>,
     dataset_id: i64,
     employee_id: i64,
     file_path: String,
-) -> Result<(), String> {
+) -> Result<String, String> {
     let pool = state.pool.clone();
 
-    let performance = compute_employee_performance(&pool, dataset_id, employee_id)
+    let settings = load_report_settings(&pool).await?;
+    let (performance, content) =
+        load_summary_export(&pool, dataset_id, employee_id, settings.locale).await?;
+    let dataset_name: String = sqlx::query_scalar("SELECT name FROM datasets WHERE id = ?")
+        .bind(dataset_id)
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| format!("Failed to load dataset: {}", e))?;
+
+    let file_path = resolve_export_path(
+        &file_path,
+        &settings.filename_template,
+        &performance.employee,
+        &dataset_name,
+        "pdf",
+    );
+    write_summary_pdf(&performance, &content, &settings, file_path.clone())?;
+    Ok(file_path)
+}
+
+/// The employee's performance and their saved summary, or one generated in `locale` when none
+/// is saved.
+pub(crate) async fn load_summary_export(
+    pool: &SqlitePool,
+    dataset_id: i64,
+    employee_id: i64,
+    locale: Locale,
+) -> Result<(EmployeePerformance, String), String> {
+    let performance = compute_employee_performance(pool, dataset_id, employee_id)
         .await
         .map_err(|e| format!("Failed to prepare export: {}", e))?;
+    let content = summary_export_content(pool, &performance, dataset_id, locale).await?;
+    Ok((performance, content))
+}
 
-    let content = if let Some(existing) =
-        sqlx::query_as::<_, Summary>("SELECT * FROM summaries WHERE employee_id = ?")
-            .bind(employee_id)
-            .fetch_optional(&pool)
+/// The saved summary for `performance`, or one generated in `locale` when none is saved.
+pub(crate) async fn summary_export_content(
+    pool: &SqlitePool,
+    performance: &EmployeePerformance,
+    dataset_id: i64,
+    locale: Locale,
+) -> Result<String, String> {
+    Ok(
+        match load_summary(pool, performance.employee.id, dataset_id)
             .await
             .map_err(|e| format!("Failed to load summary for export: {}", e))?
-    {
-        existing.content
-    } else {
-        build_summary(&performance)
-    };
+        {
+            Some(existing) => existing.content,
+            None => build_summary(performance, locale.messages()),
+        },
+    )
+}
 
-    write_summary_pdf(&performance, &content, file_path)
+/// `summary_export_content` for each of `performances`, with the saved summaries loaded in
+/// one query.
+pub(crate) async fn summary_export_contents(
+    pool: &SqlitePool,
+    performances: &[EmployeePerformance],
+    dataset_id: i64,
+    locale: Locale,
+) -> Result<Vec<String>, String> {
+    let mut saved: HashMap<i64, String> = sqlx::query_as::<_, (i64, String)>(
+        "SELECT employee_id, content FROM summaries WHERE dataset_id = ?",
+    )
+    .bind(dataset_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to load summaries for export: {}", e))?
+    .into_iter()
+    .collect();
+    Ok(performances
+        .iter()
+        .map(|performance| {
+            saved
+                .remove(&performance.employee.id)
+                .unwrap_or_else(|| build_summary(performance, locale.messages()))
+        })
+        .collect())
 }
 
-fn write_summary_pdf(
+/// `load_summary_export` for every employee of the dataset in name order, loaded with a
+/// fixed number of queries.
+async fn load_dataset_summary_exports(
+    pool: &SqlitePool,
+    dataset_id: i64,
+    locale: Locale,
+) -> Result<Vec<(EmployeePerformance, String)>, String> {
+    let performances = compute_dataset_performances(pool, dataset_id)
+        .await
+        .map_err(|e| format!("Failed to prepare export: {}", e))?;
+    let contents = summary_export_contents(pool, &performances, dataset_id, locale).await?;
+    Ok(performances.into_iter().zip(contents).collect())
+}
+
+/// Every employee's summary in one PDF, each starting on a new page, so a department's
+/// narratives can be printed as one document.
+#[tauri::command]
+pub async fn export_dataset_summaries_pdf(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    file_path: String,
+    force: Option<bool>,
+) -> Result<(), String> {
+    let pool = state.pool.clone();
+    let settings = load_report_settings(&pool).await?;
+    let sections = load_dataset_summary_exports(&pool, dataset_id, settings.locale).await?;
+    if sections.is_empty() {
+        return Err("The dataset has no employees to export".to_string());
+    }
+    assess_dataset(&pool, dataset_id)
+        .await?
+        .ensure_exportable(force.unwrap_or(false))?;
+
+    let mut document =
+        Pdf::create(&file_path).map_err(|e| format!("Failed to create PDF: {}", e))?;
+    for (performance, content) in &sections {
+        render_summary_pages(&mut document, performance, content, &settings)?;
+    }
+    document
+        .finish()
+        .map_err(|e| format!("Failed to save PDF: {}", e))
+}
+
+pub(crate) fn write_summary_pdf(
     performance: &EmployeePerformance,
     content: &str,
+    settings: &ReportSettings,
     file_path: String,
 ) -> Result<(), String> {
     let mut document =
         Pdf::create(&file_path).map_err(|e| format!("Failed to create PDF: {}", e))?;
-    let title = format!("Ringkasan Kinerja - {}", performance.employee.name);
+    render_summary_pages(&mut document, performance, content, settings)?;
+    document
+        .finish()
+        .map_err(|e| format!("Failed to save PDF: {}", e))
+}
+
+/// Adds the pages of one employee's summary to `document`.
+fn render_summary_pages(
+    document: &mut Pdf,
+    performance: &EmployeePerformance,
+    content: &str,
+    settings: &ReportSettings,
+) -> Result<(), String> {
+    let text = settings.locale.messages();
+    let title = fill(text.summary_title, &[&performance.employee.name]);
 
-    let body_lines = wrap_text(content, 90);
-    let metadata_lines = collect_metadata_lines(performance);
+    let layout = settings.paper.layout(Orientation::Portrait);
+    let body_lines = wrap_text(content, (layout.content_width() / BODY_CHAR_WIDTH) as usize);
+    let metadata_lines = collect_metadata_lines(performance, text);
 
-    let first_capacity = summary_first_page_capacity(metadata_lines.len());
-    let follow_capacity = summary_followup_page_capacity();
+    let first_capacity = summary_first_page_capacity(&layout, metadata_lines.len());
+    let follow_capacity = summary_followup_page_capacity(&layout);
     let page_ranges =
         summary_partition_body_lines(body_lines.len(), first_capacity, follow_capacity);
 
     let (first_start, first_end) = page_ranges[0];
     document
-        .render_page(595.0, 842.0, |canvas| {
+        .render_page(layout.width, layout.height, |canvas| {
+            draw_running_header(canvas, settings, layout.width, layout.height)?;
             render_summary_first_page(
                 canvas,
+                &layout,
                 &title,
                 &metadata_lines,
                 &body_lines[first_start..first_end],
@@ -234,35 +916,35 @@ fn write_summary_pdf(
 
     for (page_index, &(start, end)) in page_ranges.iter().enumerate().skip(1) {
         document
-            .render_page(595.0, 842.0, |canvas| {
-                render_summary_followup_page(canvas, &title, page_index, &body_lines[start..end])
+            .render_page(layout.width, layout.height, |canvas| {
+                draw_running_header(canvas, settings, layout.width, layout.height)?;
+                render_summary_followup_page(
+                    canvas,
+                    &layout,
+                    text,
+                    &title,
+                    page_index,
+                    &body_lines[start..end],
+                )
             })
             .map_err(|e| format!("Failed to render PDF: {}", e))?;
     }
-
-    document
-        .finish()
-        .map_err(|e| format!("Failed to save PDF: {}", e))
+    Ok(())
 }
 
-fn summary_first_page_capacity(metadata_count: usize) -> usize {
-    let mut cursor: f64 = 800.0;
+/// Average width of a 12 pt body character, for wrapping the summary to the column.
+const BODY_CHAR_WIDTH: f32 = 5.5;
+
+fn summary_first_page_capacity(layout: &PageLayout, metadata_count: usize) -> usize {
+    let mut cursor = layout.top;
     cursor -= 40.0;
-    cursor -= metadata_count as f64 * 16.0;
+    cursor -= metadata_count as f32 * 16.0;
     cursor -= 16.0;
-    let available = cursor - 80.0;
-    if available <= 0.0 {
-        0
-    } else {
-        (available / 16.0).floor() as usize
-    }
+    layout.lines_below(cursor, 16.0)
 }
 
-fn summary_followup_page_capacity() -> usize {
-    let mut cursor: f64 = 800.0;
-    cursor -= 24.0;
-    let available = cursor - 80.0;
-    (available / 16.0).floor() as usize
+fn summary_followup_page_capacity(layout: &PageLayout) -> usize {
+    layout.lines_below(layout.top - 24.0, 16.0)
 }
 
 fn summary_partition_body_lines(
@@ -285,23 +967,24 @@ fn summary_partition_body_lines(
 
 fn render_summary_first_page(
     canvas: &mut Canvas<'_>,
+    layout: &PageLayout,
     title: &str,
     metadata_lines: &[String],
     body_lines: &[String],
 ) -> std::io::Result<()> {
-    let mut cursor_y = 800.0;
-    canvas.left_text(50.0, cursor_y, BuiltinFont::Helvetica_Bold, 18.0, title)?;
+    let mut cursor_y = layout.top;
+    canvas.left_text(layout.left, cursor_y, Font::Bold, 18.0, title)?;
     cursor_y -= 40.0;
 
     for line in metadata_lines {
-        canvas.left_text(50.0, cursor_y, BuiltinFont::Helvetica, 12.0, line)?;
+        canvas.left_text(layout.left, cursor_y, Font::Regular, 12.0, line)?;
         cursor_y -= 16.0;
     }
 
     cursor_y -= 16.0;
 
     for line in body_lines {
-        canvas.left_text(50.0, cursor_y, BuiltinFont::Helvetica, 12.0, line)?;
+        canvas.left_text(layout.left, cursor_y, Font::Regular, 12.0, line)?;
         cursor_y -= 16.0;
     }
 
@@ -310,45 +993,50 @@ fn render_summary_first_page(
 
 fn render_summary_followup_page(
     canvas: &mut Canvas<'_>,
+    layout: &PageLayout,
+    text: &Messages,
     title: &str,
     page_index: usize,
     body_lines: &[String],
 ) -> std::io::Result<()> {
-    let mut cursor_y = 800.0;
+    let mut cursor_y = layout.top;
     let header = if page_index == 1 {
-        format!("{} (lanjutan)", title)
+        fill(text.continued, &[&title])
     } else {
-        format!("{} (lanjutan {})", title, page_index)
+        fill(text.continued_page, &[&title, &page_index])
     };
-    canvas.left_text(50.0, cursor_y, BuiltinFont::Helvetica_Bold, 16.0, &header)?;
+    canvas.left_text(layout.left, cursor_y, Font::Bold, 16.0, &header)?;
     cursor_y -= 24.0;
 
     for line in body_lines {
-        canvas.left_text(50.0, cursor_y, BuiltinFont::Helvetica, 12.0, line)?;
+        canvas.left_text(layout.left, cursor_y, Font::Regular, 12.0, line)?;
         cursor_y -= 16.0;
     }
 
     Ok(())
 }
 
-fn collect_metadata_lines(performance: &EmployeePerformance) -> Vec<String> {
+fn collect_metadata_lines(performance: &EmployeePerformance, text: &Messages) -> Vec<String> {
     let mut lines = Vec::new();
     if let Some(nip) = performance.employee.nip.as_deref() {
         if !nip.is_empty() {
-            lines.push(format!("NIP: {}", nip));
+            lines.push(fill(text.metadata_nip, &[&nip]));
         }
     }
     if let Some(jabatan) = performance.employee.jabatan.as_deref() {
         if !jabatan.is_empty() {
-            lines.push(format!("Jabatan: {}", jabatan));
+            lines.push(fill(text.metadata_jabatan, &[&jabatan]));
         }
     }
     if let Some(gol) = performance.employee.gol.as_deref() {
         if !gol.is_empty() {
-            lines.push(format!("Golongan: {}", gol));
+            lines.push(fill(text.metadata_golongan, &[&gol]));
         }
     }
-    lines.push(format!("Rata-rata skor: {:.2}", performance.average_score));
+    lines.push(fill(
+        text.metadata_average,
+        &[&format!("{:.2}", performance.average_score)],
+    ));
     lines
 }
 
@@ -380,3 +1068,102 @@ fn wrap_text(text: &str, max_chars: usize) -> Vec<String> {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{seed_datasets, seed_employees, seed_members, test_pool};
+
+    fn codes(issues: &[SummaryLintIssue]) -> Vec<&str> {
+        issues.iter().map(|issue| issue.code.as_str()).collect()
+    }
+
+    #[test]
+    fn test_lint_flags_other_employee_and_placeholder() {
+        let others = vec!["SITI AMINAH, S.Sos".to_string()];
+        let issues = lint_content(
+            "Siti Aminah menunjukkan kinerja baik. TODO lengkapi.",
+            "BUDI SANTOSO, SE",
+            &others,
+            &[],
+            &[],
+        );
+        assert_eq!(
+            codes(&issues),
+            vec!["wrong_employee_name", "placeholder_text"]
+        );
+    }
+
+    #[test]
+    fn test_lint_flags_praised_gap() {
+        let strengths = vec!["1. Kualitas Kinerja".to_string()];
+        let gaps = vec!["3. Kerjasama".to_string()];
+        let issues = lint_content(
+            "Kekuatan utama adalah kerjasama. Kualitas kinerja memerlukan perhatian.",
+            "BUDI SANTOSO",
+            &[],
+            &strengths,
+            &gaps,
+        );
+        assert_eq!(codes(&issues), vec!["praises_gap", "criticizes_strength"]);
+    }
+
+    #[test]
+    fn test_lint_accepts_generated_contrast_sentence() {
+        let strengths = vec!["Komunikasi".to_string()];
+        let gaps = vec!["Kerjasama".to_string()];
+        let issues = lint_content(
+            "Skor tertinggi berada pada kompetensi Komunikasi dengan nilai 85,00, sementara skor terendah tercatat pada Kerjasama dengan nilai 65,00.",
+            "BUDI SANTOSO",
+            &[],
+            &strengths,
+            &gaps,
+        );
+        assert!(issues.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_summaries_are_scoped_to_dataset_with_revisions() {
+        let pool = test_pool().await;
+        seed_datasets(&pool, &["Semester I", "Semester II"]).await;
+        seed_employees(&pool, &["Budi"]).await;
+
+        write_summary(&pool, 1, 1, "Draf pertama").await.unwrap();
+        write_summary(&pool, 1, 1, "Draf kedua").await.unwrap();
+        write_summary(&pool, 1, 2, "Semester kedua").await.unwrap();
+
+        let first = load_summary(&pool, 1, 1).await.unwrap().unwrap();
+        let second = load_summary(&pool, 1, 2).await.unwrap().unwrap();
+        assert_eq!(first.content, "Draf kedua");
+        assert_eq!(second.content, "Semester kedua");
+        let revisions: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM summary_revisions WHERE employee_id = 1 AND dataset_id = 1",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(revisions, 2);
+    }
+
+    #[tokio::test]
+    async fn test_dataset_summary_exports_are_in_name_order_ignoring_case() {
+        let pool = test_pool().await;
+        seed_datasets(&pool, &["Semester I"]).await;
+        seed_employees(&pool, &["budi", "Citra", "Andi"]).await;
+        seed_members(&pool, 1, &[1, 2, 3]).await;
+        write_summary(&pool, 2, 1, "Ringkasan tersimpan")
+            .await
+            .unwrap();
+
+        let sections = load_dataset_summary_exports(&pool, 1, Locale::Id)
+            .await
+            .unwrap();
+        let names: Vec<&str> = sections
+            .iter()
+            .map(|(performance, _)| performance.employee.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Andi", "budi", "Citra"]);
+        assert_eq!(sections[2].1, "Ringkasan tersimpan");
+        assert!(!sections[0].1.is_empty());
+    }
+}
@@ -0,0 +1,42 @@
+use crate::auth::Role;
+use crate::ui_config::{self, RatingBand, UiConfig};
+use crate::AppState;
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_ui_config(state: State<'_, AppState>) -> Result<UiConfig, String> {
+    ui_config::load_ui_config(&state.pool)
+        .await
+        .map_err(|e| format!("Failed to load UI config: {}", e))
+}
+
+#[tauri::command]
+pub async fn update_ui_config(
+    state: State<'_, AppState>,
+    config: UiConfig,
+) -> Result<UiConfig, String> {
+    state.require_role(Role::Admin).await?;
+    ui_config::save_ui_config(&state.pool, config).await
+}
+
+/// The dataset's own rating bands; empty when it uses the ones in the UI config.
+#[tauri::command]
+pub async fn get_dataset_rating_bands(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+) -> Result<Vec<RatingBand>, String> {
+    ui_config::load_dataset_rating_bands(&state.pool, dataset_id)
+        .await
+        .map_err(|e| format!("Failed to load rating bands: {}", e))
+}
+
+/// Replaces the dataset's rating bands; an empty list goes back to the UI config ones.
+#[tauri::command]
+pub async fn update_dataset_rating_bands(
+    state: State<'_, AppState>,
+    dataset_id: i64,
+    bands: Vec<RatingBand>,
+) -> Result<Vec<RatingBand>, String> {
+    state.require_role(Role::Editor).await?;
+    ui_config::save_dataset_rating_bands(&state.pool, dataset_id, bands).await
+}
@@ -0,0 +1,71 @@
+use crate::auth::Role;
+use crate::db::models::Webhook;
+use crate::webhooks::{load_webhooks, normalize_url};
+use crate::AppState;
+use tauri::State;
+
+#[tauri::command]
+pub async fn list_webhooks(state: State<'_, AppState>) -> Result<Vec<Webhook>, String> {
+    // The list carries the signing secrets
+    state.require_role(Role::Admin).await?;
+    load_webhooks(&state.pool)
+        .await
+        .map_err(|e| format!("Failed to load webhooks: {}", e))
+}
+
+/// Registers an endpoint that is sent a JSON payload whenever an import or merge finishes,
+/// with a new secret to verify the payload's signature.
+#[tauri::command]
+pub async fn register_webhook(state: State<'_, AppState>, url: String) -> Result<Webhook, String> {
+    state.require_role(Role::Admin).await?;
+    let url = normalize_url(&url)?;
+
+    sqlx::query_as::<_, Webhook>(
+        "INSERT INTO webhooks (url, secret, created_at)
+         VALUES (?, lower(hex(randomblob(32))), datetime('now'))
+         RETURNING *",
+    )
+    .bind(url)
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|e| match e.as_database_error() {
+        Some(db_error) if db_error.is_unique_violation() => {
+            "This URL is already registered".to_string()
+        }
+        _ => format!("Failed to register webhook: {}", e),
+    })
+}
+
+/// Pauses or resumes deliveries to a webhook without forgetting it.
+#[tauri::command]
+pub async fn set_webhook_enabled(
+    state: State<'_, AppState>,
+    id: i64,
+    enabled: bool,
+) -> Result<Webhook, String> {
+    state.require_role(Role::Admin).await?;
+    sqlx::query_as::<_, Webhook>("UPDATE webhooks SET enabled = ? WHERE id = ? RETURNING *")
+        .bind(enabled)
+        .bind(id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| {
+            if matches!(e, sqlx::Error::RowNotFound) {
+                "Webhook not found".to_string()
+            } else {
+                format!("Failed to update webhook: {}", e)
+            }
+        })
+}
+
+#[tauri::command]
+pub async fn delete_webhook(state: State<'_, AppState>, id: i64) -> Result<(), String> {
+    state.require_role(Role::Admin).await?;
+    sqlx::query("DELETE FROM webhooks WHERE id = ?")
+        .bind(id)
+        .execute(&state.pool)
+        .await
+        .map_err(|e| format!("Failed to delete webhook: {}", e))?;
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use crate::auth::Role;
+use crate::workspace::{
+    self, DemoDataSummary, WorkspaceReset, DEFAULT_DEMO_DATASETS, DEFAULT_DEMO_EMPLOYEES,
+};
+use crate::AppState;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::State;
+
+#[tauri::command]
+pub async fn reset_workspace(
+    state: State<'_, AppState>,
+    confirm_token: String,
+) -> Result<WorkspaceReset, String> {
+    state.require_role(Role::Admin).await?;
+    workspace::reset_workspace(&state.pool, &confirm_token).await
+}
+
+/// Counts default to `DEFAULT_DEMO_EMPLOYEES` employees over `DEFAULT_DEMO_DATASETS`
+/// semesters.
+#[tauri::command]
+pub async fn seed_demo_data(
+    state: State<'_, AppState>,
+    employee_count: Option<i64>,
+    dataset_count: Option<i64>,
+) -> Result<DemoDataSummary, String> {
+    state.require_role(Role::Admin).await?;
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos() as u64)
+        .unwrap_or_default();
+    workspace::seed_demo_data(
+        &state.pool,
+        seed,
+        employee_count.unwrap_or(DEFAULT_DEMO_EMPLOYEES),
+        dataset_count.unwrap_or(DEFAULT_DEMO_DATASETS),
+    )
+    .await
+}
+
+/// Demo data for trainings and screenshots; the same generator as `seed_demo_data`.
+#[tauri::command]
+pub async fn generate_demo_data(
+    state: State<'_, AppState>,
+    employee_count: Option<i64>,
+    dataset_count: Option<i64>,
+) -> Result<DemoDataSummary, String> {
+    seed_demo_data(state, employee_count, dataset_count).await
+}
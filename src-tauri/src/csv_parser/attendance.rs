@@ -0,0 +1,208 @@
+use super::formats::SourceTable;
+use super::{CsvParseError, CsvParser, ReadOptions};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const NAME_HEADERS: &[&str] = &["Nama", "Nama Pegawai", "Name"];
+const NIP_HEADERS: &[&str] = &["NIP"];
+const MONTH_HEADERS: &[&str] = &["Bulan", "Periode", "Month"];
+const WORKING_DAYS_HEADERS: &[&str] = &["Hari Kerja", "Jumlah Hari Kerja", "Working Days"];
+const PRESENT_DAYS_HEADERS: &[&str] = &["Hadir", "Jumlah Hadir", "Kehadiran", "Present"];
+const LATE_DAYS_HEADERS: &[&str] = &["Terlambat", "Jumlah Terlambat", "Late"];
+
+const MONTH_NAMES: &[(&str, u32)] = &[
+    ("januari", 1),
+    ("january", 1),
+    ("jan", 1),
+    ("februari", 2),
+    ("february", 2),
+    ("feb", 2),
+    ("maret", 3),
+    ("march", 3),
+    ("mar", 3),
+    ("april", 4),
+    ("apr", 4),
+    ("mei", 5),
+    ("may", 5),
+    ("juni", 6),
+    ("june", 6),
+    ("jun", 6),
+    ("juli", 7),
+    ("july", 7),
+    ("jul", 7),
+    ("agustus", 8),
+    ("august", 8),
+    ("agu", 8),
+    ("aug", 8),
+    ("september", 9),
+    ("sep", 9),
+    ("oktober", 10),
+    ("october", 10),
+    ("okt", 10),
+    ("oct", 10),
+    ("november", 11),
+    ("nov", 11),
+    ("desember", 12),
+    ("december", 12),
+    ("des", 12),
+    ("dec", 12),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParsedAttendance {
+    pub employee_name: Option<String>,
+    pub nip: Option<String>,
+    /// `YYYY-MM`
+    pub month: String,
+    pub working_days: i64,
+    pub present_days: i64,
+    pub late_days: i64,
+    /// Line of the source file (sheet row for workbooks) the record was read from.
+    pub source_row: Option<i64>,
+}
+
+/// `YYYY-MM` from `2024-01`, `2024/1`, `01/2024` or a month name such as `Januari 2024`.
+pub fn parse_month(value: &str) -> Option<String> {
+    let parts: Vec<&str> = value
+        .split(|ch: char| ch == '-' || ch == '/' || ch.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .collect();
+    let [first, second] = parts.as_slice() else {
+        return None;
+    };
+    let month_number = |part: &str| {
+        part.parse::<u32>().ok().or_else(|| {
+            let lower = part.to_lowercase();
+            MONTH_NAMES
+                .iter()
+                .find(|(name, _)| *name == lower)
+                .map(|(_, month)| *month)
+        })
+    };
+    let (year, month) = if first.len() == 4 {
+        (first.parse::<i32>().ok()?, month_number(second)?)
+    } else {
+        (second.parse::<i32>().ok()?, month_number(first)?)
+    };
+    ((1..=12).contains(&month) && (1900..=9999).contains(&year))
+        .then(|| format!("{:04}-{:02}", year, month))
+}
+
+fn day_count(
+    record: &csv::StringRecord,
+    headers: &csv::StringRecord,
+    names: &[&str],
+) -> Result<Option<i64>, CsvParseError> {
+    CsvParser::get_field_opt(record, headers, names)
+        .map(|value| {
+            value
+                .parse::<i64>()
+                .ok()
+                .filter(|days| *days >= 0)
+                .ok_or_else(|| {
+                    CsvParseError::InvalidFormat(format!(
+                        "Invalid day count in {} (row {}): {}",
+                        names[0],
+                        CsvParser::record_line(record).unwrap_or_default(),
+                        value
+                    ))
+                })
+        })
+        .transpose()
+}
+
+/// Reads an attendance export with one row per employee and month. Employees are
+/// identified by NIP when the file has one, otherwise by name.
+pub fn parse_attendance(
+    path: &Path,
+    options: ReadOptions,
+) -> Result<Vec<ParsedAttendance>, CsvParseError> {
+    let table = SourceTable::open(path, options)?;
+    let headers = &table.headers;
+    for (label, names) in [
+        ("month", MONTH_HEADERS),
+        ("working days", WORKING_DAYS_HEADERS),
+        ("present days", PRESENT_DAYS_HEADERS),
+    ] {
+        if CsvParser::find_header_pos(headers, names).is_none() {
+            return Err(CsvParseError::InvalidFormat(format!(
+                "Attendance file has no {} column",
+                label
+            )));
+        }
+    }
+    if CsvParser::find_header_pos(headers, NAME_HEADERS).is_none()
+        && CsvParser::find_header_pos(headers, NIP_HEADERS).is_none()
+    {
+        return Err(CsvParseError::InvalidFormat(
+            "Attendance file needs a name or NIP column".to_string(),
+        ));
+    }
+
+    let mut records = Vec::new();
+    for record in &table.records {
+        let employee_name = CsvParser::get_field_opt(record, headers, NAME_HEADERS);
+        let nip = CsvParser::get_field_opt(record, headers, NIP_HEADERS);
+        if employee_name.is_none() && nip.is_none() {
+            continue;
+        }
+        let source_row = CsvParser::record_line(record);
+        let invalid = |message: String| {
+            CsvParseError::InvalidFormat(format!(
+                "{} (row {})",
+                message,
+                source_row.unwrap_or_default()
+            ))
+        };
+
+        let raw_month = CsvParser::get_field_opt(record, headers, MONTH_HEADERS)
+            .ok_or_else(|| invalid("Missing month".to_string()))?;
+        let month = parse_month(&raw_month)
+            .ok_or_else(|| invalid(format!("Invalid month: {}", raw_month)))?;
+        let working_days = day_count(record, headers, WORKING_DAYS_HEADERS)?
+            .ok_or_else(|| invalid("Missing working days".to_string()))?;
+        let present_days = day_count(record, headers, PRESENT_DAYS_HEADERS)?
+            .ok_or_else(|| invalid("Missing present days".to_string()))?;
+        let late_days = day_count(record, headers, LATE_DAYS_HEADERS)?.unwrap_or(0);
+        if present_days > working_days {
+            return Err(invalid(format!(
+                "Present days ({}) exceed working days ({})",
+                present_days, working_days
+            )));
+        }
+        if late_days > present_days {
+            return Err(invalid(format!(
+                "Late days ({}) exceed present days ({})",
+                late_days, present_days
+            )));
+        }
+
+        records.push(ParsedAttendance {
+            employee_name,
+            nip,
+            month,
+            working_days,
+            present_days,
+            late_days,
+            source_row,
+        });
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_month_accepts_common_layouts() {
+        assert_eq!(parse_month("2024-01").as_deref(), Some("2024-01"));
+        assert_eq!(parse_month("2024/3").as_deref(), Some("2024-03"));
+        assert_eq!(parse_month("07/2024").as_deref(), Some("2024-07"));
+        assert_eq!(parse_month("Agustus 2024").as_deref(), Some("2024-08"));
+        assert_eq!(parse_month("Dec 2023").as_deref(), Some("2023-12"));
+        assert_eq!(parse_month("2024-13"), None);
+        assert_eq!(parse_month("Semester I"), None);
+    }
+}
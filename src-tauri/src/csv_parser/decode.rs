@@ -0,0 +1,105 @@
+use encoding_rs::{CoderResult, Decoder, Encoding};
+use std::io::{self, Read};
+
+const INPUT_BUFFER_BYTES: usize = 64 * 1024;
+/// Decoded bytes are staged here because the decoder needs room for whole characters,
+/// more than a caller's `read` buffer may offer.
+const OUTPUT_BUFFER_BYTES: usize = 64 * 1024;
+
+/// Decodes `inner` to UTF-8 while it is read, so large files never sit in memory whole.
+/// Malformed input becomes U+FFFD and sets `had_errors`.
+pub(crate) struct DecodingReader<R> {
+    inner: R,
+    decoder: Decoder,
+    input: Vec<u8>,
+    input_start: usize,
+    input_end: usize,
+    output: Vec<u8>,
+    output_start: usize,
+    output_end: usize,
+    eof: bool,
+    finished: bool,
+    pub had_errors: bool,
+    /// Source bytes decoded so far
+    pub bytes_in: u64,
+    /// UTF-8 bytes produced so far
+    pub bytes_out: u64,
+}
+
+impl<R: Read> DecodingReader<R> {
+    pub fn new(inner: R, encoding: &'static Encoding) -> Self {
+        Self {
+            inner,
+            decoder: encoding.new_decoder(),
+            input: vec![0; INPUT_BUFFER_BYTES],
+            input_start: 0,
+            input_end: 0,
+            output: vec![0; OUTPUT_BUFFER_BYTES],
+            output_start: 0,
+            output_end: 0,
+            eof: false,
+            finished: false,
+            had_errors: false,
+            bytes_in: 0,
+            bytes_out: 0,
+        }
+    }
+}
+
+impl<R: Read> Read for DecodingReader<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if self.output_start < self.output_end {
+                let len = out.len().min(self.output_end - self.output_start);
+                out[..len]
+                    .copy_from_slice(&self.output[self.output_start..self.output_start + len]);
+                self.output_start += len;
+                return Ok(len);
+            }
+            if self.finished {
+                return Ok(0);
+            }
+            if self.input_start == self.input_end && !self.eof {
+                self.input_end = self.inner.read(&mut self.input)?;
+                self.input_start = 0;
+                self.eof = self.input_end == 0;
+            }
+
+            let (result, read, written, had_errors) = self.decoder.decode_to_utf8(
+                &self.input[self.input_start..self.input_end],
+                &mut self.output,
+                self.eof,
+            );
+            self.input_start += read;
+            self.output_start = 0;
+            self.output_end = written;
+            self.bytes_in += read as u64;
+            self.bytes_out += written as u64;
+            self.had_errors |= had_errors;
+            // The decoder must not be called again once it has flushed the last input
+            self.finished = self.eof && result == CoderResult::InputEmpty;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encoding_rs::UTF_16LE;
+
+    #[test]
+    fn test_decoding_reader_streams_utf16() {
+        let text = "Nama\tNilai\r\nSéno\tBaik\r\n".repeat(5_000);
+        let bytes: Vec<u8> = text
+            .encode_utf16()
+            .flat_map(|unit| unit.to_le_bytes())
+            .collect();
+
+        let mut reader = DecodingReader::new(bytes.as_slice(), UTF_16LE);
+        let mut decoded = String::new();
+        reader.read_to_string(&mut decoded).unwrap();
+        assert_eq!(decoded, text);
+        assert!(!reader.had_errors);
+        assert_eq!(reader.bytes_in, bytes.len() as u64);
+    }
+}
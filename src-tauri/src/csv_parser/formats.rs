@@ -0,0 +1,850 @@
+use super::{
+    rating_value, CsvParseError, CsvParser, ParsedEmployee, ParsedScore, ReadOptions,
+    RespondentAggregation, TIMESTAMP_HEADERS,
+};
+use calamine::{open_workbook_auto, Reader};
+use csv::{Position, StringRecord};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::path::Path;
+
+const LONG_NAME_HEADERS: &[&str] = &["NAMA", "Name", "Nama Pegawai", "Pegawai", "Employee"];
+const LONG_COMPETENCY_HEADERS: &[&str] = &[
+    "KOMPETENSI",
+    "Competency",
+    "Aspek",
+    "Indikator",
+    "Parameter",
+];
+const LONG_VALUE_HEADERS: &[&str] = &["NILAI", "Value", "Score", "Skor", "Penilaian"];
+
+const WORKBOOK_EXTENSIONS: &[&str] = &["xlsx", "xlsm", "xls", "ods"];
+
+/// Fields a preset's column mapping can fill, with the header the parsers read each under.
+const MAPPED_FIELDS: &[(&str, &str)] = &[
+    ("name", "NAMA"),
+    ("nip", "NIP"),
+    ("gol", "GOL"),
+    ("jabatan", "JABATAN"),
+    ("sub_jabatan", "SUB JABATAN"),
+    ("competency", "KOMPETENSI"),
+    ("value", "NILAI"),
+    ("submitted_at", "Timestamp"),
+];
+
+/// Header a mapped `db_field` is read under; `None` for fields no parser reads.
+pub fn mapped_field_header(db_field: &str) -> Option<&'static str> {
+    MAPPED_FIELDS
+        .iter()
+        .find(|(field, _)| *field == db_field)
+        .map(|(_, header)| *header)
+}
+
+/// Header and data rows of a source file, independent of its container format
+pub struct SourceTable {
+    pub headers: StringRecord,
+    pub records: Vec<StringRecord>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScoreLayout {
+    Wide,
+    Long,
+}
+
+impl SourceTable {
+    /// Whether `open` reads `path` as a workbook rather than a delimited file
+    pub fn is_workbook(path: &Path) -> bool {
+        has_extension(path, WORKBOOK_EXTENSIONS)
+    }
+
+    /// Reads a workbook or a delimited file depending on the extension
+    pub fn open(path: &Path, options: ReadOptions) -> Result<Self, CsvParseError> {
+        if Self::is_workbook(path) {
+            Self::from_workbook(path, None)
+        } else {
+            Self::from_delimited(path, options)
+        }
+    }
+
+    /// Renames each `(source column, db field)` pair's column to the header its field is
+    /// read under, so a preset's mapping applies whatever the file calls the column.
+    pub fn map_columns<'a>(&mut self, mapping: impl IntoIterator<Item = (&'a str, &'a str)>) {
+        for (column, field) in mapping {
+            let Some(header) = mapped_field_header(field) else {
+                continue;
+            };
+            self.headers = self
+                .headers
+                .iter()
+                .map(|existing| {
+                    if existing.trim() == column.trim() {
+                        header
+                    } else {
+                        existing
+                    }
+                })
+                .collect();
+        }
+    }
+
+    fn from_delimited(path: &Path, options: ReadOptions) -> Result<Self, CsvParseError> {
+        let (headers, records) = CsvParser::read_records(path, options)?;
+        Ok(Self { headers, records })
+    }
+
+    /// Delimited text passed in directly, e.g. rows pasted from a spreadsheet
+    pub fn from_text(content: &str, delimiter: Option<char>) -> Result<Self, CsvParseError> {
+        let (headers, records) = CsvParser::records_from_text(content, delimiter)?;
+        Ok(Self { headers, records })
+    }
+
+    pub fn employees(&self) -> Result<Vec<ParsedEmployee>, CsvParseError> {
+        CsvParser::employees_from_records(&self.headers, &self.records)
+    }
+
+    /// Scores in whichever layout (wide or long) the headers describe
+    pub fn detected_scores(&self) -> Result<Vec<ParsedScore>, CsvParseError> {
+        self.scores(self.detected_layout()?)
+    }
+
+    /// Scores of a delimited file one source row at a time, read from the file as they are
+    /// needed, after renaming its columns like `map_columns`
+    pub fn delimited_score_rows<'a>(
+        path: &Path,
+        options: ReadOptions,
+        mapping: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<impl Iterator<Item = Result<Vec<ParsedScore>, CsvParseError>>, CsvParseError> {
+        let (mut reader, _, _) = CsvParser::open_delimited(path, options)?;
+        let mut table = Self {
+            headers: reader.headers()?.clone(),
+            records: Vec::new(),
+        };
+        table.map_columns(mapping);
+        let layout = table.detected_layout()?;
+
+        let mut record = StringRecord::new();
+        Ok(std::iter::from_fn(move || loop {
+            match reader.read_record(&mut record) {
+                Err(error) => return Some(Err(error.into())),
+                Ok(false) => {
+                    return reader
+                        .get_ref()
+                        .had_errors
+                        .then_some(Err(CsvParseError::Encoding))
+                }
+                Ok(true) if record.iter().all(|field| field.trim().is_empty()) => continue,
+                Ok(true) => return Some(table.record_scores(layout, &record)),
+            }
+        }))
+    }
+
+    /// Reads the named worksheet of a workbook, or its first one when `sheet` is `None`
+    pub fn open_workbook(path: &Path, sheet: Option<&str>) -> Result<Self, CsvParseError> {
+        if !has_extension(path, WORKBOOK_EXTENSIONS) {
+            return Err(CsvParseError::InvalidFormat(format!(
+                "{} is not a spreadsheet workbook",
+                path.display()
+            )));
+        }
+        Self::from_workbook(path, sheet)
+    }
+
+    /// Reads one worksheet of an xlsx/xls/ods workbook, the first unless `sheet` names another
+    fn from_workbook(path: &Path, sheet: Option<&str>) -> Result<Self, CsvParseError> {
+        let mut workbook =
+            open_workbook_auto(path).map_err(|e| CsvParseError::Spreadsheet(e.to_string()))?;
+        let range = match sheet {
+            Some(name) => {
+                let sheet_names = workbook.sheet_names();
+                if !sheet_names.iter().any(|sheet_name| sheet_name == name) {
+                    return Err(CsvParseError::InvalidFormat(format!(
+                        "Worksheet \"{}\" not found; the workbook has: {}",
+                        name,
+                        sheet_names.join(", ")
+                    )));
+                }
+                workbook
+                    .worksheet_range(name)
+                    .map_err(|e| CsvParseError::Spreadsheet(e.to_string()))?
+            }
+            None => workbook
+                .worksheet_range_at(0)
+                .ok_or_else(|| CsvParseError::InvalidFormat("Workbook has no sheets".to_string()))?
+                .map_err(|e| CsvParseError::Spreadsheet(e.to_string()))?,
+        };
+
+        // Positions carry the sheet row so parsed scores can point back to their cell
+        let first_row = range.start().map_or(0, |(row, _)| row as u64);
+        let mut rows = range.rows().enumerate().map(|(idx, row)| {
+            let mut record = row
+                .iter()
+                .map(|cell| cell.to_string())
+                .collect::<StringRecord>();
+            let mut position = Position::new();
+            position.set_line(first_row + idx as u64 + 1);
+            record.set_position(Some(position));
+            record
+        });
+        let headers = rows
+            .next()
+            .ok_or_else(|| CsvParseError::InvalidFormat("Worksheet is empty".to_string()))?;
+        let records = rows
+            .filter(|record| record.iter().any(|field| !field.trim().is_empty()))
+            .collect();
+
+        Ok(Self { headers, records })
+    }
+
+    fn has_header(&self, names: &[&str]) -> bool {
+        CsvParser::find_header_pos(&self.headers, names).is_some()
+    }
+
+    fn has_bracketed_headers(&self) -> bool {
+        self.headers
+            .iter()
+            .any(|header| CsvParser::extract_employee_name(header).is_some())
+    }
+
+    fn score_layout(&self) -> Option<ScoreLayout> {
+        if self.has_bracketed_headers() {
+            Some(ScoreLayout::Wide)
+        } else if self.has_header(LONG_NAME_HEADERS)
+            && self.has_header(LONG_COMPETENCY_HEADERS)
+            && self.has_header(LONG_VALUE_HEADERS)
+        {
+            Some(ScoreLayout::Long)
+        } else {
+            None
+        }
+    }
+
+    fn detected_layout(&self) -> Result<ScoreLayout, CsvParseError> {
+        self.score_layout().ok_or_else(|| {
+            CsvParseError::InvalidFormat(
+                "Unable to detect score columns from the headers".to_string(),
+            )
+        })
+    }
+
+    fn scores(&self, layout: ScoreLayout) -> Result<Vec<ParsedScore>, CsvParseError> {
+        match layout {
+            ScoreLayout::Wide => Ok(CsvParser::scores_from_wide_records(
+                &self.headers,
+                &self.records,
+            )),
+            ScoreLayout::Long => self
+                .records
+                .iter()
+                .filter_map(|record| self.long_record_score(record).transpose())
+                .collect(),
+        }
+    }
+
+    /// Scores one source row at a time, so large files can be staged without holding
+    /// every parsed score in memory
+    pub fn score_rows(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<Vec<ParsedScore>, CsvParseError>> + '_, CsvParseError>
+    {
+        let layout = self.detected_layout()?;
+        Ok(self
+            .records
+            .iter()
+            .map(move |record| self.record_scores(layout, record)))
+    }
+
+    fn record_scores(
+        &self,
+        layout: ScoreLayout,
+        record: &StringRecord,
+    ) -> Result<Vec<ParsedScore>, CsvParseError> {
+        match layout {
+            ScoreLayout::Wide => Ok(CsvParser::wide_record_scores(&self.headers, record)),
+            ScoreLayout::Long => Ok(self.long_record_score(record)?.into_iter().collect()),
+        }
+    }
+
+    /// Score from one row per employee/competency pair
+    fn long_record_score(
+        &self,
+        record: &StringRecord,
+    ) -> Result<Option<ParsedScore>, CsvParseError> {
+        let employee_name = CsvParser::clean_field(&CsvParser::get_field(
+            record,
+            &self.headers,
+            LONG_NAME_HEADERS,
+        )?);
+        let competency = CsvParser::clean_field(&CsvParser::get_field(
+            record,
+            &self.headers,
+            LONG_COMPETENCY_HEADERS,
+        )?);
+        let (ordinal, competency) = CsvParser::split_ordinal(&competency);
+        let competency = competency.to_string();
+        let value = CsvParser::get_field_opt(record, &self.headers, LONG_VALUE_HEADERS);
+
+        Ok(value
+            .filter(|_| !employee_name.is_empty() && !competency.is_empty())
+            .map(|value| ParsedScore {
+                employee_name,
+                competency,
+                value,
+                ordinal,
+                submitted_at: CsvParser::record_timestamp(record, &self.headers),
+                source_row: CsvParser::record_line(record),
+                source_column: CsvParser::find_header_pos(&self.headers, LONG_VALUE_HEADERS)
+                    .and_then(|pos| self.headers.get(pos))
+                    .map(str::to_string),
+                respondent_count: None,
+            }))
+    }
+}
+
+/// A source format the import pipeline understands.
+///
+/// `detect` returns a confidence from 0 (cannot handle the file) to 100.
+pub trait FormatParser: Send + Sync {
+    fn id(&self) -> &'static str;
+    fn label(&self) -> &'static str;
+    fn detect(&self, path: &Path, table: &SourceTable) -> u8;
+    fn read_table(&self, path: &Path, options: ReadOptions) -> Result<SourceTable, CsvParseError>;
+    fn parse_scores(
+        &self,
+        path: &Path,
+        options: ReadOptions,
+    ) -> Result<Vec<ParsedScore>, CsvParseError>;
+
+    fn parse_employees(
+        &self,
+        path: &Path,
+        options: ReadOptions,
+    ) -> Result<Vec<ParsedEmployee>, CsvParseError> {
+        let table = self.read_table(path, options)?;
+        CsvParser::employees_from_records(&table.headers, &table.records)
+    }
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| extensions.iter().any(|e| ext.eq_ignore_ascii_case(e)))
+        .unwrap_or(false)
+}
+
+fn is_delimited(path: &Path) -> bool {
+    has_extension(path, &["csv", "tsv", "txt"])
+}
+
+struct CsvWideParser;
+
+impl FormatParser for CsvWideParser {
+    fn id(&self) -> &'static str {
+        "csv-wide"
+    }
+
+    fn label(&self) -> &'static str {
+        "CSV (kolom \"Kompetensi [Nama Pegawai]\")"
+    }
+
+    fn detect(&self, path: &Path, table: &SourceTable) -> u8 {
+        if is_delimited(path) && table.score_layout() == Some(ScoreLayout::Wide) {
+            80
+        } else {
+            0
+        }
+    }
+
+    fn read_table(&self, path: &Path, options: ReadOptions) -> Result<SourceTable, CsvParseError> {
+        SourceTable::from_delimited(path, options)
+    }
+
+    fn parse_scores(
+        &self,
+        path: &Path,
+        options: ReadOptions,
+    ) -> Result<Vec<ParsedScore>, CsvParseError> {
+        CsvParser::parse_scores_csv(path, options)
+    }
+
+    fn parse_employees(
+        &self,
+        path: &Path,
+        options: ReadOptions,
+    ) -> Result<Vec<ParsedEmployee>, CsvParseError> {
+        CsvParser::parse_employee_csv(path, options)
+    }
+}
+
+struct CsvLongParser;
+
+impl FormatParser for CsvLongParser {
+    fn id(&self) -> &'static str {
+        "csv-long"
+    }
+
+    fn label(&self) -> &'static str {
+        "CSV (baris Nama / Kompetensi / Nilai)"
+    }
+
+    fn detect(&self, path: &Path, table: &SourceTable) -> u8 {
+        if is_delimited(path) && table.score_layout() == Some(ScoreLayout::Long) {
+            80
+        } else {
+            0
+        }
+    }
+
+    fn read_table(&self, path: &Path, options: ReadOptions) -> Result<SourceTable, CsvParseError> {
+        SourceTable::from_delimited(path, options)
+    }
+
+    fn parse_scores(
+        &self,
+        path: &Path,
+        options: ReadOptions,
+    ) -> Result<Vec<ParsedScore>, CsvParseError> {
+        self.read_table(path, options)?.scores(ScoreLayout::Long)
+    }
+}
+
+/// Google Forms CSV exports: a timestamp column followed by bracketed question columns,
+/// one row per respondent. `aggregate_respondents` combines the respondents of each
+/// employee and question into one score.
+struct GoogleFormsParser;
+
+impl FormatParser for GoogleFormsParser {
+    fn id(&self) -> &'static str {
+        "google-forms"
+    }
+
+    fn label(&self) -> &'static str {
+        "Google Forms (ekspor CSV respons)"
+    }
+
+    fn detect(&self, path: &Path, table: &SourceTable) -> u8 {
+        if is_delimited(path)
+            && table.has_header(TIMESTAMP_HEADERS)
+            && table.score_layout() == Some(ScoreLayout::Wide)
+        {
+            90
+        } else {
+            0
+        }
+    }
+
+    fn read_table(&self, path: &Path, options: ReadOptions) -> Result<SourceTable, CsvParseError> {
+        SourceTable::from_delimited(path, options)
+    }
+
+    fn parse_scores(
+        &self,
+        path: &Path,
+        options: ReadOptions,
+    ) -> Result<Vec<ParsedScore>, CsvParseError> {
+        CsvParser::parse_scores_csv(path, options)
+    }
+}
+
+/// One score per employee and question, in the order the pairs first appear. Answers are
+/// combined through `rating_map`, so labels such as "Baik" count by their rating value.
+pub fn aggregate_respondents(
+    scores: Vec<ParsedScore>,
+    aggregation: RespondentAggregation,
+    rating_map: &HashMap<String, f64>,
+) -> Vec<ParsedScore> {
+    let mut positions: HashMap<(String, String), usize> = HashMap::new();
+    let mut groups: Vec<Vec<ParsedScore>> = Vec::new();
+    for score in scores {
+        let key = (score.employee_name.to_lowercase(), score.competency.clone());
+        match positions.get(&key) {
+            Some(&position) => groups[position].push(score),
+            None => {
+                positions.insert(key, groups.len());
+                groups.push(vec![score]);
+            }
+        }
+    }
+    groups
+        .into_iter()
+        .map(|responses| combine_responses(responses, aggregation, rating_map))
+        .collect()
+}
+
+fn combine_responses(
+    mut responses: Vec<ParsedScore>,
+    aggregation: RespondentAggregation,
+    rating_map: &HashMap<String, f64>,
+) -> ParsedScore {
+    let respondent_count = responses.len() as i64;
+    // Stable, so responses without a timestamp stay in file order
+    responses.sort_by(|a, b| a.submitted_at.cmp(&b.submitted_at));
+    let numbers: Option<Vec<f64>> = responses
+        .iter()
+        .map(|response| rating_value(rating_map, &response.value))
+        .collect();
+
+    let combined = match (aggregation, numbers) {
+        (RespondentAggregation::Latest, _) => None,
+        (RespondentAggregation::Mean, Some(numbers)) => Some(format_answer(
+            numbers.iter().sum::<f64>() / numbers.len() as f64,
+        )),
+        (RespondentAggregation::Median, Some(mut numbers)) => {
+            numbers.sort_by(f64::total_cmp);
+            let middle = numbers.len() / 2;
+            Some(format_answer(if numbers.len() % 2 == 0 {
+                (numbers[middle - 1] + numbers[middle]) / 2.0
+            } else {
+                numbers[middle]
+            }))
+        }
+        (_, None) => Some(most_frequent_answer(&responses)),
+    };
+
+    let mut score = responses.pop().expect("respondent groups are never empty");
+    if let Some(value) = combined {
+        score.value = value;
+        score.source_row = None;
+    }
+    score.respondent_count = Some(respondent_count);
+    score
+}
+
+/// The answer given most often; the later one wins a tie.
+fn most_frequent_answer(responses: &[ParsedScore]) -> String {
+    let mut counts: HashMap<&str, (usize, usize)> = HashMap::new();
+    for (position, response) in responses.iter().enumerate() {
+        let entry = counts.entry(response.value.as_str()).or_default();
+        entry.0 += 1;
+        entry.1 = position;
+    }
+    counts
+        .into_iter()
+        .max_by_key(|(_, count)| *count)
+        .map(|(answer, _)| answer.to_string())
+        .unwrap_or_default()
+}
+
+/// Rounds a combined answer to two decimals without trailing zeros.
+fn format_answer(value: f64) -> String {
+    ((value * 100.0).round() / 100.0).to_string()
+}
+
+/// Spreadsheet workbooks; the score layout (wide or long) is detected from the first sheet.
+struct WorkbookParser {
+    id: &'static str,
+    label: &'static str,
+    extensions: &'static [&'static str],
+}
+
+impl FormatParser for WorkbookParser {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn label(&self) -> &'static str {
+        self.label
+    }
+
+    fn detect(&self, path: &Path, _table: &SourceTable) -> u8 {
+        if has_extension(path, self.extensions) {
+            90
+        } else {
+            0
+        }
+    }
+
+    // The delimited-text options do not apply to workbooks
+    fn read_table(&self, path: &Path, _options: ReadOptions) -> Result<SourceTable, CsvParseError> {
+        SourceTable::from_workbook(path, None)
+    }
+
+    fn parse_scores(
+        &self,
+        path: &Path,
+        options: ReadOptions,
+    ) -> Result<Vec<ParsedScore>, CsvParseError> {
+        let table = self.read_table(path, options)?;
+        let layout = table.score_layout().ok_or_else(|| {
+            CsvParseError::InvalidFormat(
+                "Unable to detect score columns in the first worksheet".to_string(),
+            )
+        })?;
+        table.scores(layout)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormatCandidate {
+    pub id: String,
+    pub label: String,
+    pub confidence: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormatDetection {
+    pub format: Option<FormatCandidate>,
+    pub candidates: Vec<FormatCandidate>,
+}
+
+pub struct FormatRegistry {
+    parsers: Vec<Box<dyn FormatParser>>,
+}
+
+impl Default for FormatRegistry {
+    fn default() -> Self {
+        let mut registry = Self {
+            parsers: Vec::new(),
+        };
+        registry.register(Box::new(GoogleFormsParser));
+        registry.register(Box::new(CsvWideParser));
+        registry.register(Box::new(CsvLongParser));
+        registry.register(Box::new(WorkbookParser {
+            id: "xlsx",
+            label: "Microsoft Excel (.xlsx / .xls)",
+            extensions: &["xlsx", "xlsm", "xls"],
+        }));
+        registry.register(Box::new(WorkbookParser {
+            id: "ods",
+            label: "OpenDocument Spreadsheet (.ods)",
+            extensions: &["ods"],
+        }));
+        registry
+    }
+}
+
+impl FormatRegistry {
+    pub fn register(&mut self, parser: Box<dyn FormatParser>) {
+        self.parsers.push(parser);
+    }
+
+    pub fn formats(&self) -> Vec<FormatCandidate> {
+        self.parsers
+            .iter()
+            .map(|parser| FormatCandidate {
+                id: parser.id().to_string(),
+                label: parser.label().to_string(),
+                confidence: 0,
+            })
+            .collect()
+    }
+
+    fn get(&self, id: &str) -> Result<&dyn FormatParser, CsvParseError> {
+        self.parsers
+            .iter()
+            .find(|parser| parser.id() == id)
+            .map(|parser| parser.as_ref())
+            .ok_or_else(|| CsvParseError::InvalidFormat(format!("Unknown import format: {}", id)))
+    }
+
+    fn read_any_table(path: &Path, options: ReadOptions) -> Result<SourceTable, CsvParseError> {
+        if is_delimited(path) {
+            SourceTable::from_delimited(path, options)
+        } else {
+            SourceTable::from_workbook(path, None)
+        }
+    }
+
+    pub fn detect(
+        &self,
+        path: &Path,
+        options: ReadOptions,
+    ) -> Result<FormatDetection, CsvParseError> {
+        let table = Self::read_any_table(path, options)?;
+
+        let mut candidates: Vec<FormatCandidate> = self
+            .parsers
+            .iter()
+            .map(|parser| FormatCandidate {
+                id: parser.id().to_string(),
+                label: parser.label().to_string(),
+                confidence: parser.detect(path, &table),
+            })
+            .filter(|candidate| candidate.confidence > 0)
+            .collect();
+        candidates.sort_by_key(|candidate| Reverse(candidate.confidence));
+
+        Ok(FormatDetection {
+            format: candidates.first().cloned(),
+            candidates,
+        })
+    }
+
+    /// Resolves an explicit format id, or the best detected parser when none is given.
+    fn resolve(
+        &self,
+        path: &Path,
+        format: Option<&str>,
+        options: ReadOptions,
+    ) -> Result<&dyn FormatParser, CsvParseError> {
+        match format {
+            Some(id) => self.get(id),
+            None => {
+                let detection = self.detect(path, options)?;
+                let best = detection.format.ok_or_else(|| {
+                    CsvParseError::InvalidFormat("Unable to detect the file format".to_string())
+                })?;
+                self.get(&best.id)
+            }
+        }
+    }
+
+    pub fn parse_scores(
+        &self,
+        path: &Path,
+        format: Option<&str>,
+        options: ReadOptions,
+    ) -> Result<Vec<ParsedScore>, CsvParseError> {
+        self.resolve(path, format, options)?
+            .parse_scores(path, options)
+    }
+
+    pub fn parse_employees(
+        &self,
+        path: &Path,
+        format: Option<&str>,
+        options: ReadOptions,
+    ) -> Result<Vec<ParsedEmployee>, CsvParseError> {
+        // Employee master files carry no score layout, so fall back to reading the
+        // container directly when detection finds no score format.
+        let parser = match format {
+            Some(id) => self.get(id)?,
+            None => match self.detect(path, options)?.format {
+                Some(best) => self.get(&best.id)?,
+                None => {
+                    let table = Self::read_any_table(path, options)?;
+                    return CsvParser::employees_from_records(&table.headers, &table.records);
+                }
+            },
+        };
+        parser.parse_employees(path, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_wide_csv_sample() {
+        let registry = FormatRegistry::default();
+        let detection = registry
+            .detect(
+                Path::new("../docs/contoh_data_penilaian.csv"),
+                ReadOptions::default(),
+            )
+            .expect("Failed to detect format");
+
+        assert_eq!(detection.format.map(|f| f.id), Some("csv-wide".to_string()));
+    }
+
+    #[test]
+    fn test_employee_master_csv_falls_back_to_structured_columns() {
+        let registry = FormatRegistry::default();
+        let employees = registry
+            .parse_employees(
+                Path::new("../docs/datapegawai-gabung.csv"),
+                None,
+                ReadOptions::default(),
+            )
+            .expect("Failed to parse employees");
+
+        assert!(!employees.is_empty());
+        assert!(employees.iter().all(|employee| !employee.name.is_empty()));
+    }
+
+    #[test]
+    fn test_pasted_tab_separated_employees() {
+        let table = SourceTable::from_text(
+            "NAMA\tNIP\tJABATAN\nBudi Santoso\t1987\tKepala Seksi\n\t\t\n",
+            None,
+        )
+        .expect("Failed to read pasted text");
+        let employees = table.employees().expect("Failed to parse employees");
+
+        assert_eq!(employees.len(), 1);
+        assert_eq!(employees[0].name, "Budi Santoso");
+        assert_eq!(employees[0].jabatan.as_deref(), Some("Kepala Seksi"));
+    }
+
+    #[test]
+    fn test_long_rows_split_the_question_number() {
+        let table = SourceTable::from_text(
+            "Nama\tKompetensi\tNilai\nBudi Santoso\t1. Inisiatif & Fleksibilitas\tBaik\n",
+            None,
+        )
+        .expect("Failed to read pasted text");
+        let rows: Vec<Vec<ParsedScore>> = table
+            .score_rows()
+            .expect("Failed to detect score columns")
+            .collect::<Result<_, _>>()
+            .expect("Failed to read score rows");
+
+        assert_eq!(rows[0][0].competency, "Inisiatif & Fleksibilitas");
+        assert_eq!(rows[0][0].ordinal, Some(1));
+    }
+
+    #[test]
+    fn test_delimited_score_rows_are_read_with_the_column_mapping() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("epa-streamed.csv");
+        std::fs::write(
+            &path,
+            "Nama Lengkap;Kompetensi;Jawaban\nBudi;Integritas;Baik\n;;\nSari;Disiplin;Cukup\n",
+        )
+        .unwrap();
+        let rows: Vec<Vec<ParsedScore>> = SourceTable::delimited_score_rows(
+            &path,
+            ReadOptions::default(),
+            [("Nama Lengkap", "name"), ("Jawaban", "value")],
+        )
+        .expect("Failed to open score file")
+        .collect::<Result<_, _>>()
+        .expect("Failed to read score rows");
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0][0].employee_name, "Budi");
+        assert_eq!(rows[1][0].competency, "Disiplin");
+        assert_eq!(rows[1][0].value, "Cukup");
+        assert_eq!(rows[1][0].source_row, Some(4));
+    }
+
+    #[test]
+    fn test_google_forms_respondents_are_aggregated() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("epa-forms.csv");
+        std::fs::write(
+            &path,
+            "Timestamp,Email Address,1. Integritas [Budi],2. Disiplin [Budi]\n\
+             01/02/2024 10:00:00,a@example.com,4,Baik\n\
+             01/02/2024 09:00:00,b@example.com,3,Sangat Baik\n\
+             01/02/2024 11:00:00,c@example.com,2,Sangat Baik\n",
+        )
+        .unwrap();
+        let scores = FormatRegistry::default()
+            .parse_scores(&path, Some("google-forms"), ReadOptions::default())
+            .expect("Failed to parse scores");
+        let rating_map =
+            HashMap::from([("Baik".to_string(), 3.0), ("Sangat Baik".to_string(), 4.0)]);
+        let aggregate =
+            |aggregation| aggregate_respondents(scores.clone(), aggregation, &rating_map);
+
+        let mean = aggregate(RespondentAggregation::Mean);
+        let median = aggregate(RespondentAggregation::Median);
+        let latest = aggregate(RespondentAggregation::Latest);
+
+        assert_eq!(mean.len(), 2);
+        assert_eq!(mean[0].value, "3");
+        assert_eq!(mean[0].respondent_count, Some(3));
+        assert_eq!(mean[0].submitted_at.as_deref(), Some("2024-02-01 11:00:00"));
+        assert_eq!(mean[1].value, "3.67");
+        assert_eq!(median[0].value, "3");
+        assert_eq!(median[1].value, "4");
+        assert_eq!(
+            (latest[0].value.as_str(), latest[0].source_row),
+            ("2", Some(4))
+        );
+        assert_eq!(latest[1].value, "Sangat Baik");
+    }
+}
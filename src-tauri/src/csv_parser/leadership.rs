@@ -0,0 +1,101 @@
+use super::formats::SourceTable;
+use super::{CsvParseError, CsvParser, ReadOptions};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const NAME_HEADERS: &[&str] = &["Nama", "Nama Pegawai", "Name"];
+const SCORE_HEADERS: &[&str] = &[
+    "Nilai Pimpinan",
+    "Penilaian Pimpinan",
+    "Nilai",
+    "Skor",
+    "Score",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParsedLeadershipScore {
+    pub employee_name: String,
+    pub score: f64,
+    /// Line of the source file (sheet row for workbooks) the score was read from.
+    #[serde(default)]
+    pub source_row: Option<i64>,
+}
+
+/// Reads the Penilaian Pimpinan sheet: one row per employee with a name and a 0-100 score.
+/// Without recognised headers the first column is the name and the second the score.
+pub fn parse_leadership_scores(
+    path: &Path,
+    options: ReadOptions,
+) -> Result<Vec<ParsedLeadershipScore>, CsvParseError> {
+    let table = SourceTable::open(path, options)?;
+    let headers = &table.headers;
+    let (name_pos, score_pos) = match (
+        CsvParser::find_header_pos(headers, NAME_HEADERS),
+        CsvParser::find_header_pos(headers, SCORE_HEADERS),
+    ) {
+        (Some(name), Some(score)) => (name, score),
+        _ if headers.len() == 2 => (0, 1),
+        _ => {
+            return Err(CsvParseError::InvalidFormat(
+                "Leadership scores need a name and a score column".to_string(),
+            ))
+        }
+    };
+
+    let mut scores = Vec::new();
+    for record in &table.records {
+        let name = CsvParser::clean_field(record.get(name_pos).unwrap_or_default());
+        let value = CsvParser::clean_field(record.get(score_pos).unwrap_or_default());
+        if name.is_empty() || value.is_empty() {
+            continue;
+        }
+        let source_row = CsvParser::record_line(record);
+        let score = value
+            .replace(',', ".")
+            .parse::<f64>()
+            .ok()
+            .filter(|score| (0.0..=100.0).contains(score))
+            .ok_or_else(|| {
+                CsvParseError::InvalidFormat(format!(
+                    "Invalid leadership score for {} (row {}): {}",
+                    name,
+                    source_row.unwrap_or_default(),
+                    value
+                ))
+            })?;
+        scores.push(ParsedLeadershipScore {
+            employee_name: name,
+            score,
+            source_row,
+        });
+    }
+
+    Ok(scores)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_leadership_scores_reads_named_columns() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("epa-leadership.csv");
+        std::fs::write(
+            &path,
+            "No;Nama;Nilai Pimpinan\n1;Budi  Santoso;85,5\n2;;90\n3;Sari;78\n",
+        )
+        .unwrap();
+
+        let scores = parse_leadership_scores(&path, ReadOptions::default()).unwrap();
+
+        let parsed: Vec<(&str, f64, Option<i64>)> = scores
+            .iter()
+            .map(|s| (s.employee_name.as_str(), s.score, s.source_row))
+            .collect();
+        assert_eq!(
+            parsed,
+            vec![("Budi Santoso", 85.5, Some(2)), ("Sari", 78.0, Some(4))]
+        );
+    }
+}
@@ -1,12 +1,49 @@
+use chardetng::EncodingDetector;
+use chrono::NaiveDateTime;
 use csv::{ReaderBuilder, StringRecord};
-use encoding_rs::{Encoding, UTF_8, WINDOWS_1252};
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{BufReader, Read};
 use std::path::Path;
+use std::str::FromStr;
 use thiserror::Error;
 
+pub mod attendance;
+mod decode;
+pub mod formats;
+pub mod leadership;
+pub mod org_structure;
+
+use decode::DecodingReader;
+
+pub(crate) type DelimitedReader = csv::Reader<DecodingReader<BufReader<File>>>;
+
+pub(crate) const TIMESTAMP_HEADERS: &[&str] = &["Timestamp", "Cap waktu", "Stempel waktu"];
+
+/// Delimiters `detect_delimiter` chooses from; earlier ones win ties.
+const DELIMITER_CANDIDATES: [char; 4] = [',', '\t', ';', '|'];
+/// Records sampled when detecting the delimiter.
+const DELIMITER_SAMPLE_RECORDS: usize = 50;
+/// Leading bytes inspected to choose the encoding and delimiter of a streamed file.
+const PREVIEW_SAMPLE_BYTES: u64 = 1024 * 1024;
+/// Records a preview reads past its rows; the total is estimated from them when the file
+/// is longer.
+const PREVIEW_COUNT_RECORDS: usize = 5_000;
+
+/// Formats seen in form exports; day-first is tried before month-first.
+const TIMESTAMP_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y/%m/%d %I:%M:%S %p",
+    "%Y/%m/%d %H:%M:%S",
+    "%d/%m/%Y %H:%M:%S",
+    "%d/%m/%Y %H.%M.%S",
+    "%m/%d/%Y %H:%M:%S",
+    "%d-%m-%Y %H:%M:%S",
+];
+
 #[derive(Error, Debug)]
 pub enum CsvParseError {
     #[error("IO error: {0}")]
@@ -20,6 +57,9 @@ pub enum CsvParseError {
 
     #[error("Invalid format: {0}")]
     InvalidFormat(String),
+
+    #[error("Spreadsheet error: {0}")]
+    Spreadsheet(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +69,10 @@ pub struct CsvPreview {
     pub detected_delimiter: char,
     pub employee_count: usize,
     pub encoding: String,
+    pub file_size: u64,
+    /// Data rows in the file; an estimate when `rows_estimated` is set
+    pub total_rows: usize,
+    pub rows_estimated: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,67 +89,223 @@ pub struct ParsedScore {
     pub employee_name: String,
     pub competency: String,
     pub value: String,
+    /// Question number the header carried ("3. Integritas [..]"), kept apart from the name.
+    #[serde(default)]
+    pub ordinal: Option<i32>,
+    /// Response timestamp normalised to `YYYY-MM-DD HH:MM:SS`, when the source has one.
+    #[serde(default)]
+    pub submitted_at: Option<String>,
+    /// Line of the source file (sheet row for workbooks) the value was read from.
+    #[serde(default)]
+    pub source_row: Option<i64>,
+    /// Header of the column the value was read from.
+    #[serde(default)]
+    pub source_column: Option<String>,
+    /// Responses combined into `value` when respondent rows were aggregated.
+    #[serde(default)]
+    pub respondent_count: Option<i64>,
 }
 
-pub struct CsvParser;
-
-impl CsvParser {
-    /// Detect the encoding of a file
-    pub fn detect_encoding(file_path: &Path) -> Result<&'static Encoding, CsvParseError> {
-        let mut file = File::open(file_path)?;
-        let mut buffer = vec![0u8; 8192];
-        let bytes_read = file.read(&mut buffer)?;
+/// How the respondent rows of a form export are combined into one score per employee and
+/// question. Mean and median combine the answers' rating values; when an answer has neither a
+/// rating mapping nor a numeric value the most frequent answer is kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RespondentAggregation {
+    Mean,
+    Median,
+    Latest,
+}
 
-        let (_encoding, _) = Encoding::for_bom(&buffer[..bytes_read]).unwrap_or((UTF_8, 0));
+impl FromStr for RespondentAggregation {
+    type Err = String;
 
-        // Check if it's valid UTF-8
-        if std::str::from_utf8(&buffer[..bytes_read]).is_ok() {
-            return Ok(UTF_8);
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "mean" => Ok(Self::Mean),
+            "median" => Ok(Self::Median),
+            "latest" => Ok(Self::Latest),
+            other => Err(format!("Unknown respondent aggregation: {}", other)),
         }
-
-        // Default to Windows-1252 for Indonesian data
-        Ok(WINDOWS_1252)
     }
+}
 
-    /// Detect the delimiter used in the CSV file
-    pub fn detect_delimiter(content: &str) -> char {
-        let first_line = content.lines().next().unwrap_or("");
+/// Manual overrides for reading delimited files; `None` fields are detected.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReadOptions {
+    pub delimiter: Option<char>,
+    pub encoding: Option<&'static Encoding>,
+}
 
-        let delimiters = [',', '\t', ';', '|'];
-        let mut counts: Vec<(char, usize)> = delimiters
-            .iter()
-            .map(|&d| (d, first_line.matches(d).count()))
-            .collect();
+/// Numeric value of an answer: its rating mapping, else the answer itself when numeric.
+pub fn rating_value(rating_map: &HashMap<String, f64>, value: &str) -> Option<f64> {
+    rating_map.get(value).copied().or_else(|| {
+        value
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|number| number.is_finite())
+    })
+}
 
-        counts.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+pub struct CsvParser;
 
-        counts.first().map(|&(d, _)| d).unwrap_or(',')
+impl CsvParser {
+    /// Detect the encoding of file contents: a BOM wins, then BOM-less UTF-16 is recognised
+    /// by its zero bytes, then UTF-8, and anything else is guessed statistically.
+    pub fn detect_encoding(bytes: &[u8]) -> &'static Encoding {
+        if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+            return encoding;
+        }
+        if let Some(encoding) = Self::detect_utf16(bytes) {
+            return encoding;
+        }
+        // A sample may end inside a multi-byte character, which is still UTF-8
+        match std::str::from_utf8(bytes) {
+            Ok(_) => return UTF_8,
+            Err(error) if error.error_len().is_none() => return UTF_8,
+            Err(_) => {}
+        }
+
+        let mut detector = EncodingDetector::new();
+        detector.feed(bytes, true);
+        detector.guess(None, false)
     }
 
-    /// Parse CSV and return a preview
-    pub fn preview(file_path: &Path, max_rows: usize) -> Result<CsvPreview, CsvParseError> {
-        // Detect encoding
-        let encoding = Self::detect_encoding(file_path)?;
+    /// ASCII text in UTF-16 has a zero in every other byte; more than a third of the even
+    /// or odd bytes being zero is never the case for 8-bit encodings.
+    fn detect_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+        let pairs = bytes.len() / 2;
+        if pairs == 0 {
+            return None;
+        }
+        let zero_high = bytes.chunks_exact(2).filter(|pair| pair[1] == 0).count();
+        let zero_low = bytes.chunks_exact(2).filter(|pair| pair[0] == 0).count();
+        if zero_high * 3 > pairs && zero_low * 10 < pairs {
+            Some(UTF_16LE)
+        } else if zero_low * 3 > pairs && zero_high * 10 < pairs {
+            Some(UTF_16BE)
+        } else {
+            None
+        }
+    }
 
-        // Read file with detected encoding
-        let file = File::open(file_path)?;
-        let mut reader = BufReader::new(file);
-        let mut bytes = Vec::new();
-        reader.read_to_end(&mut bytes)?;
+    /// Reads and decodes a text file with the given or detected encoding, returning the
+    /// encoding actually used.
+    pub fn read_text(
+        file_path: &Path,
+        encoding: Option<&'static Encoding>,
+    ) -> Result<(String, &'static Encoding), CsvParseError> {
+        let bytes = std::fs::read(file_path)?;
+        let encoding = encoding.unwrap_or_else(|| Self::detect_encoding(&bytes));
 
-        let (content, _, had_errors) = encoding.decode(&bytes);
+        let (content, used, had_errors) = encoding.decode(&bytes);
         if had_errors {
             return Err(CsvParseError::Encoding);
         }
+        Ok((content.into_owned(), used))
+    }
 
-        // Detect delimiter
-        let delimiter = Self::detect_delimiter(&content);
+    /// Detect the delimiter used in the CSV file.
+    ///
+    /// Each candidate is scored over the first records by how many of them split into the
+    /// same number of columns as the header; delimiters inside quoted fields are ignored.
+    pub fn detect_delimiter(content: &str) -> char {
+        let mut best = (',', 0, 0);
+        for delimiter in DELIMITER_CANDIDATES {
+            let counts = Self::delimiter_counts(content, delimiter);
+            let header_count = counts.first().copied().unwrap_or(0);
+            if header_count == 0 {
+                continue;
+            }
+            let consistent = counts
+                .iter()
+                .filter(|&&count| count == header_count)
+                .count();
+            if (consistent, header_count) > (best.1, best.2) {
+                best = (delimiter, consistent, header_count);
+            }
+        }
+        best.0
+    }
 
-        // Parse CSV
-        let mut csv_reader = ReaderBuilder::new()
+    /// Unquoted occurrences of `delimiter` per non-empty record, for the sampled records.
+    fn delimiter_counts(content: &str, delimiter: char) -> Vec<usize> {
+        let mut counts = Vec::new();
+        let mut in_quotes = false;
+        let mut count = 0;
+        let mut has_content = false;
+        for ch in content.chars() {
+            match ch {
+                // An escaped quote ("") toggles twice and leaves the state unchanged
+                '"' => in_quotes = !in_quotes,
+                '\n' if !in_quotes => {
+                    if has_content {
+                        counts.push(count);
+                        if counts.len() == DELIMITER_SAMPLE_RECORDS {
+                            return counts;
+                        }
+                    }
+                    count = 0;
+                    has_content = false;
+                    continue;
+                }
+                ch if ch == delimiter && !in_quotes => count += 1,
+                _ => {}
+            }
+            if !ch.is_whitespace() || ch == delimiter {
+                has_content = true;
+            }
+        }
+        if has_content {
+            counts.push(count);
+        }
+        counts
+    }
+
+    /// Reader decoding the file as it is read, with the encoding and delimiter it uses;
+    /// those not given are detected from the file's first megabyte.
+    pub(crate) fn open_delimited(
+        file_path: &Path,
+        options: ReadOptions,
+    ) -> Result<(DelimitedReader, &'static Encoding, char), CsvParseError> {
+        let mut sample = Vec::new();
+        File::open(file_path)?
+            .take(PREVIEW_SAMPLE_BYTES)
+            .read_to_end(&mut sample)?;
+        let encoding = options
+            .encoding
+            .unwrap_or_else(|| Self::detect_encoding(&sample));
+
+        let delimiter = options
+            .delimiter
+            .unwrap_or_else(|| Self::detect_delimiter(&encoding.decode(&sample).0));
+        if !delimiter.is_ascii() {
+            return Err(CsvParseError::InvalidFormat(format!(
+                "Unsupported delimiter: {}",
+                delimiter
+            )));
+        }
+
+        let csv_reader = ReaderBuilder::new()
             .delimiter(delimiter as u8)
             .flexible(true)
-            .from_reader(content.as_bytes());
+            .from_reader(DecodingReader::new(
+                BufReader::new(File::open(file_path)?),
+                encoding,
+            ));
+        Ok((csv_reader, encoding, delimiter))
+    }
+
+    /// Preview of the first `max_rows` rows. The file is streamed: encoding and delimiter are
+    /// detected from its first megabyte and at most `PREVIEW_COUNT_RECORDS` further records
+    /// are read to count or estimate the rest.
+    pub fn preview(
+        file_path: &Path,
+        max_rows: usize,
+        options: ReadOptions,
+    ) -> Result<CsvPreview, CsvParseError> {
+        let file_size = std::fs::metadata(file_path)?.len();
+        let (mut csv_reader, encoding, delimiter) = Self::open_delimited(file_path, options)?;
 
         let header_record = csv_reader.headers()?.clone();
         let headers: Vec<String> = header_record.iter().map(|h| Self::clean_field(h)).collect();
@@ -113,22 +313,36 @@ impl CsvParser {
 
         let mut rows = Vec::new();
         let mut record_count = 0;
-
-        for (idx, result) in csv_reader.records().enumerate() {
-            let record = result?;
-
-            if idx < max_rows {
-                let row: Vec<String> = record.iter().map(|f| Self::clean_field(f)).collect();
-                rows.push(row);
+        let mut record = StringRecord::new();
+        let mut reached_end = false;
+        while record_count < max_rows + PREVIEW_COUNT_RECORDS {
+            if !csv_reader.read_record(&mut record)? {
+                reached_end = true;
+                break;
+            }
+            if record_count < max_rows {
+                rows.push(record.iter().map(Self::clean_field).collect());
             }
-
             record_count += 1;
         }
+        if csv_reader.get_ref().had_errors {
+            return Err(CsvParseError::Encoding);
+        }
+
+        let total_rows = if reached_end {
+            record_count
+        } else {
+            // Scale by the share of the file behind the records read so far
+            let decoder = csv_reader.get_ref();
+            let utf8_read = csv_reader.position().byte() as f64;
+            let source_read = utf8_read * decoder.bytes_in as f64 / decoder.bytes_out.max(1) as f64;
+            (record_count as f64 * file_size as f64 / source_read.max(1.0)).round() as usize
+        };
 
         let employee_count = if !unique_employee_names.is_empty() {
             unique_employee_names.len()
         } else {
-            record_count
+            total_rows
         };
 
         Ok(CsvPreview {
@@ -137,6 +351,9 @@ impl CsvParser {
             detected_delimiter: delimiter,
             employee_count,
             encoding: encoding.name().to_string(),
+            file_size,
+            total_rows,
+            rows_estimated: !reached_end,
         })
     }
 
@@ -157,6 +374,23 @@ impl CsvParser {
         }
     }
 
+    /// Leading question number of a header ("1.", "2)", "3 -", "4:") and the rest of it.
+    pub fn split_ordinal(text: &str) -> (Option<i32>, &str) {
+        let trimmed = text.trim_start();
+        let digits = trimmed.len()
+            - trimmed
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .len();
+        let rest = trimmed[digits..]
+            .trim_start()
+            .strip_prefix(['.', ')', '-', ':'])
+            .filter(|rest| !rest.trim().is_empty());
+        match (trimmed[..digits].parse().ok(), rest) {
+            (Some(ordinal), Some(rest)) => (Some(ordinal), rest.trim_start()),
+            _ => (None, text),
+        }
+    }
+
     /// Extract employee name from bracketed format: "1. Competency [Employee Name]"
     pub fn extract_employee_name(field: &str) -> Option<String> {
         let start = field.find('[')?;
@@ -169,47 +403,81 @@ impl CsvParser {
         }
     }
 
-    /// Parse employee data CSV (like data_pegawai_all.csv)
-    pub fn parse_employee_csv(file_path: &Path) -> Result<Vec<ParsedEmployee>, CsvParseError> {
-        let encoding = Self::detect_encoding(file_path)?;
-
-        let file = File::open(file_path)?;
-        let mut reader = BufReader::new(file);
-        let mut bytes = Vec::new();
-        reader.read_to_end(&mut bytes)?;
+    /// Decode a delimited text file and return its header and data records
+    pub fn read_records(
+        file_path: &Path,
+        options: ReadOptions,
+    ) -> Result<(StringRecord, Vec<StringRecord>), CsvParseError> {
+        let (content, _) = Self::read_text(file_path, options.encoding)?;
+        Self::records_from_text(&content, options.delimiter)
+    }
 
-        let (content, _, had_errors) = encoding.decode(&bytes);
-        if had_errors {
-            return Err(CsvParseError::Encoding);
+    /// Header and data records of delimited text such as rows pasted from a spreadsheet;
+    /// the delimiter is detected unless given.
+    pub fn records_from_text(
+        content: &str,
+        delimiter: Option<char>,
+    ) -> Result<(StringRecord, Vec<StringRecord>), CsvParseError> {
+        let delimiter = delimiter.unwrap_or_else(|| Self::detect_delimiter(content));
+        if !delimiter.is_ascii() {
+            return Err(CsvParseError::InvalidFormat(format!(
+                "Unsupported delimiter: {}",
+                delimiter
+            )));
         }
 
-        let delimiter = Self::detect_delimiter(&content);
-
         let mut csv_reader = ReaderBuilder::new()
             .delimiter(delimiter as u8)
+            .flexible(true)
             .from_reader(content.as_bytes());
 
         let headers = csv_reader.headers()?.clone();
-        let has_structured_employee_columns = headers.iter().any(|h| {
-            let normalized = Self::clean_field(h);
-            normalized.eq_ignore_ascii_case("NAMA")
-                || normalized.eq_ignore_ascii_case("NAME")
-                || normalized.eq_ignore_ascii_case("NAMA PEGAWAI")
-        });
+        let records = csv_reader
+            .records()
+            .filter(|record| {
+                record.as_ref().map_or(true, |record| {
+                    record.iter().any(|field| !field.trim().is_empty())
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
 
-        if has_structured_employee_columns {
-            let mut employees = Vec::new();
+        Ok((headers, records))
+    }
 
-            for result in csv_reader.records() {
-                let record = result?;
+    /// Parse employee data CSV (like data_pegawai_all.csv)
+    pub fn parse_employee_csv(
+        file_path: &Path,
+        options: ReadOptions,
+    ) -> Result<Vec<ParsedEmployee>, CsvParseError> {
+        let (headers, records) = Self::read_records(file_path, options)?;
+        Self::employees_from_records(&headers, &records)
+    }
 
-                let name = Self::get_field(&record, &headers, &["NAMA", "Name", "Nama"])?;
-                let nip = Self::get_field_opt(&record, &headers, &["NIP", "Nip"]);
-                let gol = Self::get_field_opt(&record, &headers, &["GOL", "Gol", "Golongan"]);
-                let jabatan = Self::get_field_opt(&record, &headers, &["JABATAN", "Jabatan"]);
+    /// Parse performance scores CSV (like contoh_data_penilaian.csv)
+    pub fn parse_scores_csv(
+        file_path: &Path,
+        options: ReadOptions,
+    ) -> Result<Vec<ParsedScore>, CsvParseError> {
+        let (headers, records) = Self::read_records(file_path, options)?;
+        Ok(Self::scores_from_wide_records(&headers, &records))
+    }
+
+    /// Employees from either structured columns (NAMA, NIP, ...) or bracketed score headers
+    pub fn employees_from_records(
+        headers: &StringRecord,
+        records: &[StringRecord],
+    ) -> Result<Vec<ParsedEmployee>, CsvParseError> {
+        if Self::has_employee_name_column(headers) {
+            let mut employees = Vec::new();
+
+            for record in records {
+                let name = Self::get_field(record, headers, &["NAMA", "Name", "Nama"])?;
+                let nip = Self::get_field_opt(record, headers, &["NIP", "Nip"]);
+                let gol = Self::get_field_opt(record, headers, &["GOL", "Gol", "Golongan"]);
+                let jabatan = Self::get_field_opt(record, headers, &["JABATAN", "Jabatan"]);
                 let sub_jabatan = Self::get_field_opt(
-                    &record,
-                    &headers,
+                    record,
+                    headers,
                     &["SUB JABATAN", "Sub Jabatan", "Sub_Jabatan"],
                 );
 
@@ -225,7 +493,7 @@ impl CsvParser {
             return Ok(employees);
         }
 
-        let employee_names = Self::extract_employee_names(&headers);
+        let employee_names = Self::extract_employee_names(headers);
         if employee_names.is_empty() {
             return Err(CsvParseError::InvalidFormat(
                 "Unable to detect employee names from CSV headers".to_string(),
@@ -246,60 +514,83 @@ impl CsvParser {
         Ok(employees)
     }
 
-    /// Parse performance scores CSV (like contoh_data_penilaian.csv)
-    pub fn parse_scores_csv(file_path: &Path) -> Result<Vec<ParsedScore>, CsvParseError> {
-        let encoding = Self::detect_encoding(file_path)?;
-
-        let file = File::open(file_path)?;
-        let mut reader = BufReader::new(file);
-        let mut bytes = Vec::new();
-        reader.read_to_end(&mut bytes)?;
+    /// Scores from wide headers shaped like "1. Competency [Employee Name]"
+    pub fn scores_from_wide_records(
+        headers: &StringRecord,
+        records: &[StringRecord],
+    ) -> Vec<ParsedScore> {
+        records
+            .iter()
+            .flat_map(|record| Self::wide_record_scores(headers, record))
+            .collect()
+    }
 
-        let (content, _, had_errors) = encoding.decode(&bytes);
-        if had_errors {
-            return Err(CsvParseError::Encoding);
+    /// Scores of a single wide row, one per non-empty bracketed column
+    pub fn wide_record_scores(headers: &StringRecord, record: &StringRecord) -> Vec<ParsedScore> {
+        let mut scores = Vec::new();
+        let submitted_at = Self::record_timestamp(record, headers);
+        let source_row = Self::record_line(record);
+        // Parse each column header to extract competency and employee
+        for (idx, header) in headers.iter().enumerate() {
+            if let Some(raw_employee_name) = Self::extract_employee_name(header) {
+                let employee_name = Self::clean_field(&raw_employee_name);
+                let header_name = header.split('[').next().unwrap_or_default();
+                let (ordinal, competency) = Self::split_ordinal(header_name);
+                let competency = Self::clean_field(competency);
+
+                let value = record.get(idx).map(Self::clean_field).unwrap_or_default();
+
+                if !value.is_empty() {
+                    scores.push(ParsedScore {
+                        employee_name,
+                        competency,
+                        value,
+                        ordinal,
+                        submitted_at: submitted_at.clone(),
+                        source_row,
+                        source_column: Some(header.to_string()),
+                        respondent_count: None,
+                    });
+                }
+            }
         }
+        scores
+    }
 
-        let delimiter = Self::detect_delimiter(&content);
+    /// Parse a response timestamp, ignoring a trailing time zone label such as "GMT+7".
+    pub fn parse_timestamp(value: &str) -> Option<NaiveDateTime> {
+        let trimmed = value.trim();
+        let without_zone = trimmed
+            .rsplit_once(' ')
+            .filter(|(_, zone)| zone.starts_with("GMT") || zone.starts_with("UTC"))
+            .map_or(trimmed, |(rest, _)| rest.trim());
 
-        let mut csv_reader = ReaderBuilder::new()
-            .delimiter(delimiter as u8)
-            .flexible(true)
-            .from_reader(content.as_bytes());
+        TIMESTAMP_FORMATS
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(without_zone, format).ok())
+    }
 
-        let headers = csv_reader.headers()?.clone();
-        let mut scores = Vec::new();
+    /// Source line of a record, as set by the reader that produced it.
+    pub(crate) fn record_line(record: &StringRecord) -> Option<i64> {
+        record.position().map(|position| position.line() as i64)
+    }
 
-        for result in csv_reader.records() {
-            let record = result?;
-
-            // Parse each column header to extract competency and employee
-            for (idx, header) in headers.iter().enumerate() {
-                if let Some(raw_employee_name) = Self::extract_employee_name(header) {
-                    let employee_name = Self::clean_field(&raw_employee_name);
-                    let competency = header
-                        .split('[')
-                        .next()
-                        .map(|s| Self::clean_field(s))
-                        .unwrap_or_default();
-
-                    let value = record
-                        .get(idx)
-                        .map(|v| Self::clean_field(v))
-                        .unwrap_or_default();
-
-                    if !value.is_empty() {
-                        scores.push(ParsedScore {
-                            employee_name,
-                            competency,
-                            value,
-                        });
-                    }
-                }
-            }
-        }
+    pub(crate) fn record_timestamp(
+        record: &StringRecord,
+        headers: &StringRecord,
+    ) -> Option<String> {
+        Self::get_field_opt(record, headers, TIMESTAMP_HEADERS)
+            .and_then(|value| Self::parse_timestamp(&value))
+            .map(|timestamp| timestamp.format("%Y-%m-%d %H:%M:%S").to_string())
+    }
 
-        Ok(scores)
+    fn has_employee_name_column(headers: &StringRecord) -> bool {
+        headers.iter().any(|h| {
+            let normalized = Self::clean_field(h);
+            normalized.eq_ignore_ascii_case("NAMA")
+                || normalized.eq_ignore_ascii_case("NAME")
+                || normalized.eq_ignore_ascii_case("NAMA PEGAWAI")
+        })
     }
 
     fn extract_employee_names(headers: &StringRecord) -> Vec<String> {
@@ -390,6 +681,23 @@ mod tests {
         assert_eq!(name, Some("GUSNANDA EFFENDI, S.Pd, MM".to_string()));
     }
 
+    #[test]
+    fn test_parse_timestamp_handles_form_exports() {
+        let expected = chrono::NaiveDate::from_ymd_opt(2024, 1, 15)
+            .unwrap()
+            .and_hms_opt(14, 5, 9)
+            .unwrap();
+        assert_eq!(
+            CsvParser::parse_timestamp("2024/01/15 2:05:09 PM GMT+7"),
+            Some(expected)
+        );
+        assert_eq!(
+            CsvParser::parse_timestamp("15/01/2024 14:05:09"),
+            Some(expected)
+        );
+        assert_eq!(CsvParser::parse_timestamp("kemarin"), None);
+    }
+
     #[test]
     fn test_detect_delimiter() {
         assert_eq!(CsvParser::detect_delimiter("a,b,c"), ',');
@@ -397,6 +705,49 @@ mod tests {
         assert_eq!(CsvParser::detect_delimiter("a;b;c"), ';');
     }
 
+    #[test]
+    fn test_detect_delimiter_ignores_quoted_fields() {
+        let content = "\"Nama, Gelar\";NIP\n\"Budi, S.Pd\";1987\n\"Sari, \"\"SE\"\"\";1990\n";
+        assert_eq!(CsvParser::detect_delimiter(content), ';');
+        // One stray comma in a row does not beat a consistent tab layout
+        assert_eq!(
+            CsvParser::detect_delimiter("a\tb\tc\n1\t2\t3,5\n4\t5\t6\n"),
+            '\t'
+        );
+    }
+
+    #[test]
+    fn test_detect_encoding_handles_utf16_and_legacy_bytes() {
+        let utf16: Vec<u8> = "NAMA\tNIP\r\nBudi\t1987\r\n"
+            .encode_utf16()
+            .flat_map(|unit| unit.to_le_bytes())
+            .collect();
+        assert_eq!(CsvParser::detect_encoding(&utf16), UTF_16LE);
+        assert_eq!(CsvParser::detect_encoding("Nama;Nilai".as_bytes()), UTF_8);
+        assert_ne!(
+            CsvParser::detect_encoding(b"Nama;Jabatan\nS\xe9kretaris;Kasubbag"),
+            UTF_8
+        );
+    }
+
+    #[test]
+    fn test_preview_estimates_rows_of_long_files() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("epa-preview.csv");
+        let mut content = String::from("NAMA;NIP\n");
+        for idx in 0..20_000 {
+            content.push_str(&format!("Pegawai {};{}\n", idx, 198_000 + idx));
+        }
+        std::fs::write(&path, &content).unwrap();
+
+        let preview = CsvParser::preview(&path, 10, ReadOptions::default()).unwrap();
+        assert_eq!(preview.rows.len(), 10);
+        assert_eq!(preview.detected_delimiter, ';');
+        assert!(preview.rows_estimated);
+        assert!((19_000..=21_000).contains(&preview.total_rows));
+        assert_eq!(preview.file_size, content.len() as u64);
+    }
+
     #[test]
     fn test_clean_field_normalizes_whitespace() {
         assert_eq!(CsvParser::clean_field("  Kurang  Baik  "), "Kurang Baik");
@@ -406,7 +757,8 @@ mod tests {
     #[test]
     fn test_parse_employee_csv_supports_wide_format() {
         let path = Path::new("../docs/contoh_data_penilaian.csv");
-        let employees = CsvParser::parse_employee_csv(path).expect("Failed to parse employees");
+        let employees = CsvParser::parse_employee_csv(path, ReadOptions::default())
+            .expect("Failed to parse employees");
 
         assert_eq!(employees.len(), 19);
         assert_eq!(employees[0].name, "GUSNANDA EFFENDI, S.Pd, MM");
@@ -416,12 +768,31 @@ mod tests {
     #[test]
     fn test_parse_scores_csv_supports_wide_format() {
         let path = Path::new("../docs/contoh_data_penilaian.csv");
-        let scores = CsvParser::parse_scores_csv(path).expect("Failed to parse scores");
+        let scores = CsvParser::parse_scores_csv(path, ReadOptions::default())
+            .expect("Failed to parse scores");
 
         assert_eq!(scores.len(), 604);
         let first = &scores[0];
         assert_eq!(first.employee_name, "GUSNANDA EFFENDI, S.Pd, MM");
-        assert_eq!(first.competency, "1. Inisiatif & Fleksibilitas");
+        assert_eq!(first.competency, "Inisiatif & Fleksibilitas");
+        assert_eq!(first.ordinal, Some(1));
         assert_eq!(first.value, "Baik");
     }
+
+    #[test]
+    fn test_split_ordinal_separates_question_numbers() {
+        assert_eq!(
+            CsvParser::split_ordinal("12) Kerjasama dan Teamwork"),
+            (Some(12), "Kerjasama dan Teamwork")
+        );
+        assert_eq!(
+            CsvParser::split_ordinal("3 - Integritas"),
+            (Some(3), "Integritas")
+        );
+        assert_eq!(
+            CsvParser::split_ordinal("360 Feedback"),
+            (None, "360 Feedback")
+        );
+        assert_eq!(CsvParser::split_ordinal("2."), (None, "2."));
+    }
 }
@@ -0,0 +1,118 @@
+use super::formats::SourceTable;
+use super::{CsvParseError, CsvParser, ReadOptions};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+const UNIT_HEADERS: &[&str] = &[
+    "Unit Kerja",
+    "Unit Organisasi",
+    "Nama Unit",
+    "Unit",
+    "Satuan Kerja",
+];
+const PARENT_HEADERS: &[&str] = &["Unit Induk", "Induk", "Unit Atasan", "Parent"];
+const CODE_HEADERS: &[&str] = &["Kode Unit", "Kode"];
+const JABATAN_HEADERS: &[&str] = &["Nama Jabatan", "Jabatan"];
+const ESELON_HEADERS: &[&str] = &["Eselon", "Kelas Jabatan"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParsedOrgUnit {
+    pub name: String,
+    pub code: Option<String>,
+    pub parent: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParsedJabatan {
+    pub name: String,
+    pub unit: Option<String>,
+    pub eselon: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParsedOrgStructure {
+    pub units: Vec<ParsedOrgUnit>,
+    pub positions: Vec<ParsedJabatan>,
+}
+
+impl ParsedOrgStructure {
+    fn add_unit(&mut self, index: &mut HashMap<String, usize>, unit: ParsedOrgUnit) {
+        match index.get(&unit.name.to_lowercase()) {
+            Some(&existing) => {
+                let existing = &mut self.units[existing];
+                existing.code = existing.code.take().or(unit.code);
+                existing.parent = existing.parent.take().or(unit.parent);
+            }
+            None => {
+                index.insert(unit.name.to_lowercase(), self.units.len());
+                self.units.push(unit);
+            }
+        }
+    }
+}
+
+/// Reads an SOTK sheet with one row per jabatan. The unit column may be left blank on
+/// follow-up rows (merged cells in the official layout), so the last unit carries forward.
+pub fn parse_org_structure(
+    path: &Path,
+    options: ReadOptions,
+) -> Result<ParsedOrgStructure, CsvParseError> {
+    let table = SourceTable::open(path, options)?;
+    let headers = &table.headers;
+
+    if CsvParser::find_header_pos(headers, UNIT_HEADERS).is_none()
+        && CsvParser::find_header_pos(headers, JABATAN_HEADERS).is_none()
+    {
+        return Err(CsvParseError::InvalidFormat(
+            "Organization structure needs a unit or jabatan column".to_string(),
+        ));
+    }
+
+    let mut structure = ParsedOrgStructure::default();
+    let mut unit_index: HashMap<String, usize> = HashMap::new();
+    let mut seen_positions: HashSet<(String, String)> = HashSet::new();
+    let mut current_unit: Option<String> = None;
+
+    for record in &table.records {
+        if let Some(unit) = CsvParser::get_field_opt(record, headers, UNIT_HEADERS) {
+            let parent = CsvParser::get_field_opt(record, headers, PARENT_HEADERS)
+                .filter(|parent| !parent.eq_ignore_ascii_case(&unit));
+            if let Some(parent) = &parent {
+                structure.add_unit(
+                    &mut unit_index,
+                    ParsedOrgUnit {
+                        name: parent.clone(),
+                        code: None,
+                        parent: None,
+                    },
+                );
+            }
+            structure.add_unit(
+                &mut unit_index,
+                ParsedOrgUnit {
+                    name: unit.clone(),
+                    code: CsvParser::get_field_opt(record, headers, CODE_HEADERS),
+                    parent,
+                },
+            );
+            current_unit = Some(unit);
+        }
+
+        if let Some(jabatan) = CsvParser::get_field_opt(record, headers, JABATAN_HEADERS) {
+            let key = (
+                jabatan.to_lowercase(),
+                current_unit.as_deref().unwrap_or_default().to_lowercase(),
+            );
+            if seen_positions.insert(key) {
+                structure.positions.push(ParsedJabatan {
+                    name: jabatan,
+                    unit: current_unit.clone(),
+                    eselon: CsvParser::get_field_opt(record, headers, ESELON_HEADERS),
+                });
+            }
+        }
+    }
+
+    Ok(structure)
+}
@@ -0,0 +1,409 @@
+use crate::db::models::{Competency, Employee};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, QueryBuilder, Row, Sqlite, SqlitePool};
+use std::str::FromStr;
+
+/// A master-data check an employee record can fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataQualityIssue {
+    MissingNip,
+    UnparsedGol,
+    NonCanonicalJabatan,
+    MissingPhoto,
+    DuplicateSuspect,
+}
+
+impl DataQualityIssue {
+    const ALL: [Self; 5] = [
+        Self::MissingNip,
+        Self::UnparsedGol,
+        Self::NonCanonicalJabatan,
+        Self::MissingPhoto,
+        Self::DuplicateSuspect,
+    ];
+
+    /// SQL condition over `employees e` that holds when the employee fails the check.
+    fn condition(self) -> &'static str {
+        match self {
+            Self::MissingNip => "TRIM(IFNULL(e.nip, '')) = ''",
+            Self::UnparsedGol => {
+                "NOT (REPLACE(UPPER(IFNULL(e.gol, '')), ' ', '') GLOB 'I/[A-D]'
+                    OR REPLACE(UPPER(IFNULL(e.gol, '')), ' ', '') GLOB 'II/[A-D]'
+                    OR REPLACE(UPPER(IFNULL(e.gol, '')), ' ', '') GLOB 'III/[A-D]'
+                    OR REPLACE(UPPER(IFNULL(e.gol, '')), ' ', '') GLOB 'IV/[A-E]')"
+            }
+            Self::NonCanonicalJabatan => {
+                "LOWER(TRIM(IFNULL(e.jabatan, '')))
+                    NOT IN (SELECT LOWER(TRIM(name)) FROM jabatan_reference)"
+            }
+            Self::MissingPhoto => "e.photo_path IS NULL",
+            Self::DuplicateSuspect => {
+                "EXISTS (SELECT 1 FROM employees d WHERE d.id <> e.id
+                    AND (LOWER(TRIM(d.name)) = LOWER(TRIM(e.name))
+                        OR (TRIM(IFNULL(e.nip, '')) <> '' AND TRIM(d.nip) = TRIM(e.nip))))"
+            }
+        }
+    }
+}
+
+impl FromStr for DataQualityIssue {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "missing_nip" => Ok(Self::MissingNip),
+            "unparsed_gol" => Ok(Self::UnparsedGol),
+            "non_canonical_jabatan" => Ok(Self::NonCanonicalJabatan),
+            "missing_photo" => Ok(Self::MissingPhoto),
+            "duplicate_suspect" => Ok(Self::DuplicateSuspect),
+            other => Err(format!("Unknown data quality issue: {}", other)),
+        }
+    }
+}
+
+/// Share of applicable checks the employee passes (0-100) and the ones it fails.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmployeeDataQuality {
+    pub score: f64,
+    pub issues: Vec<DataQualityIssue>,
+}
+
+/// Checks that apply to this workspace; the jabatan check needs an imported SOTK reference
+/// and the photo check only counts once at least one photo has been uploaded.
+pub(crate) async fn applicable_checks(pool: &SqlitePool) -> Result<Vec<DataQualityIssue>, String> {
+    let (has_reference, has_photos): (bool, bool) = sqlx::query_as(
+        "SELECT EXISTS (SELECT 1 FROM jabatan_reference),
+                EXISTS (SELECT 1 FROM employees WHERE photo_path IS NOT NULL)",
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Failed to load data quality checks: {}", e))?;
+
+    Ok(DataQualityIssue::ALL
+        .into_iter()
+        .filter(|issue| match issue {
+            DataQualityIssue::NonCanonicalJabatan => has_reference,
+            DataQualityIssue::MissingPhoto => has_photos,
+            _ => true,
+        })
+        .collect())
+}
+
+/// SQL condition matching employees that fail `issue`, or any applicable check when `None`.
+pub(crate) fn failing_condition(
+    checks: &[DataQualityIssue],
+    issue: Option<DataQualityIssue>,
+) -> String {
+    let conditions: Vec<String> = checks
+        .iter()
+        .filter(|check| issue.is_none_or(|issue| issue == **check))
+        .map(|check| format!("({})", check.condition()))
+        .collect();
+    if conditions.is_empty() {
+        "0".to_string()
+    } else {
+        format!("({})", conditions.join(" OR "))
+    }
+}
+
+/// Runs the checks for the given employees, or for everyone when `employee_ids` is `None`.
+pub(crate) async fn assess_employees(
+    pool: &SqlitePool,
+    employee_ids: Option<&[i64]>,
+) -> Result<Vec<(Employee, EmployeeDataQuality)>, String> {
+    let checks = applicable_checks(pool).await?;
+
+    let mut query = QueryBuilder::<Sqlite>::new("SELECT e.*");
+    for (idx, check) in checks.iter().enumerate() {
+        query.push(format!(", ({}) AS check_{}", check.condition(), idx));
+    }
+    query.push(" FROM employees e");
+    if let Some(ids) = employee_ids {
+        query.push(" WHERE e.id IN (");
+        let mut sep = query.separated(", ");
+        for id in ids {
+            sep.push_bind(id);
+        }
+        query.push(")");
+    }
+    query.push(" ORDER BY LOWER(e.name)");
+
+    let rows = query
+        .build()
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to assess employee data quality: {}", e))?;
+
+    rows.iter()
+        .map(|row| {
+            let employee = Employee::from_row(row).map_err(|e| e.to_string())?;
+            let mut issues = Vec::new();
+            for (idx, check) in checks.iter().enumerate() {
+                let failed: bool = row
+                    .try_get(format!("check_{}", idx).as_str())
+                    .map_err(|e| e.to_string())?;
+                if failed {
+                    issues.push(*check);
+                }
+            }
+            let score = (checks.len() - issues.len()) as f64 / checks.len() as f64 * 100.0;
+            Ok((employee, EmployeeDataQuality { score, issues }))
+        })
+        .collect()
+}
+
+/// Share of the dataset's employees a competency may be missing for before it is reported.
+const MISSING_COMPETENCY_THRESHOLD: f64 = 0.2;
+
+/// A competency left unscored for more than `MISSING_COMPETENCY_THRESHOLD` of the employees.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompetencyGap {
+    pub competency: Competency,
+    pub missing_count: i64,
+    pub missing_percent: f64,
+}
+
+/// A competency with the number of linked employees scored on it.
+#[derive(FromRow)]
+struct ScoredCompetency {
+    #[sqlx(flatten)]
+    competency: Competency,
+    scored_count: i64,
+}
+
+/// How complete a dataset's scores are. Exports of official documents are refused while
+/// `export_blockers` is not empty, unless the user forces them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetQuality {
+    pub dataset_id: i64,
+    pub employee_count: i64,
+    /// Competencies scored for at least one employee of the dataset
+    pub competency_count: i64,
+    /// Employees scored on every one of those competencies, 0-100
+    pub complete_employee_percent: f64,
+    /// Scores whose value maps to a number, 0-100
+    pub numeric_score_percent: f64,
+    pub unmapped_score_count: i64,
+    pub employees_without_scores: Vec<Employee>,
+    pub competency_gaps: Vec<CompetencyGap>,
+    pub export_blockers: Vec<String>,
+}
+
+impl DatasetQuality {
+    /// Errors with the blockers; `force` exports anyway once the user has seen them.
+    pub fn ensure_exportable(&self, force: bool) -> Result<(), String> {
+        if force || self.export_blockers.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Dataset is not ready for export: {}",
+                self.export_blockers.join("; ")
+            ))
+        }
+    }
+}
+
+fn percent(part: i64, total: i64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64 * 100.0
+    }
+}
+
+pub(crate) async fn assess_dataset(
+    pool: &SqlitePool,
+    dataset_id: i64,
+) -> Result<DatasetQuality, String> {
+    let map_err = |e: sqlx::Error| format!("Failed to assess dataset quality: {}", e);
+
+    // Scores of employees no longer linked to the dataset are left out everywhere
+    let (employee_count, competency_count, score_count, numeric_score_count): (i64, i64, i64, i64) =
+        sqlx::query_as(
+            "SELECT
+            (SELECT COUNT(*) FROM dataset_employees WHERE dataset_id = ?1),
+            COUNT(DISTINCT s.competency_id),
+            COUNT(s.id),
+            COUNT(s.numeric_value)
+            FROM scores s
+            JOIN dataset_employees de ON de.dataset_id = s.dataset_id AND de.employee_id = s.employee_id
+            WHERE s.dataset_id = ?1",
+        )
+        .bind(dataset_id)
+        .fetch_one(pool)
+        .await
+        .map_err(map_err)?;
+
+    let complete_employees: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM (
+            SELECT de.employee_id FROM dataset_employees de
+            JOIN scores s ON s.dataset_id = de.dataset_id AND s.employee_id = de.employee_id
+            WHERE de.dataset_id = ?1
+            GROUP BY de.employee_id
+            HAVING COUNT(DISTINCT s.competency_id) = ?2
+        )",
+    )
+    .bind(dataset_id)
+    .bind(competency_count)
+    .fetch_one(pool)
+    .await
+    .map_err(map_err)?;
+
+    let employees_without_scores = sqlx::query_as::<_, Employee>(
+        "SELECT e.* FROM dataset_employees de
+         JOIN employees e ON e.id = de.employee_id
+         WHERE de.dataset_id = ?1
+           AND NOT EXISTS (SELECT 1 FROM scores s
+                           WHERE s.dataset_id = ?1 AND s.employee_id = e.id)
+         ORDER BY LOWER(e.name)",
+    )
+    .bind(dataset_id)
+    .fetch_all(pool)
+    .await
+    .map_err(map_err)?;
+
+    let scored: Vec<ScoredCompetency> = sqlx::query_as(
+        "SELECT c.*, COUNT(DISTINCT s.employee_id) AS scored_count
+         FROM scores s
+         JOIN dataset_employees de ON de.dataset_id = s.dataset_id AND de.employee_id = s.employee_id
+         JOIN competencies c ON c.id = s.competency_id
+         WHERE s.dataset_id = ?
+         GROUP BY c.id",
+    )
+    .bind(dataset_id)
+    .fetch_all(pool)
+    .await
+    .map_err(map_err)?;
+    let mut competency_gaps: Vec<CompetencyGap> = scored
+        .into_iter()
+        .filter_map(|scored| {
+            let missing_count = employee_count - scored.scored_count;
+            ((missing_count as f64) > employee_count as f64 * MISSING_COMPETENCY_THRESHOLD).then(
+                || CompetencyGap {
+                    competency: scored.competency,
+                    missing_count,
+                    missing_percent: percent(missing_count, employee_count),
+                },
+            )
+        })
+        .collect();
+    competency_gaps.sort_by_key(|gap| std::cmp::Reverse(gap.missing_count));
+
+    let unmapped_score_count = score_count - numeric_score_count;
+    let mut export_blockers = Vec::new();
+    if score_count == 0 {
+        export_blockers.push("the dataset has no scores".to_string());
+    }
+    if unmapped_score_count > 0 {
+        export_blockers.push(format!(
+            "{} scores have no numeric rating mapping",
+            unmapped_score_count
+        ));
+    }
+    if !employees_without_scores.is_empty() {
+        export_blockers.push(format!(
+            "{} employees have no scores",
+            employees_without_scores.len()
+        ));
+    }
+
+    Ok(DatasetQuality {
+        dataset_id,
+        employee_count,
+        competency_count,
+        complete_employee_percent: percent(complete_employees, employee_count),
+        numeric_score_percent: percent(numeric_score_count, score_count),
+        unmapped_score_count,
+        employees_without_scores,
+        competency_gaps,
+        export_blockers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    #[tokio::test]
+    async fn test_assess_employees_flags_failed_checks() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO employees (name, nip, gol, jabatan, created_at, updated_at) VALUES
+                ('Budi', '198001012005011001', 'III / c', 'Analis', datetime('now'), datetime('now')),
+                ('Sari', NULL, 'Golongan 3', 'Analis', datetime('now'), datetime('now')),
+                ('sari ', NULL, 'IV/e', 'Analis', datetime('now'), datetime('now'))",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let assessed = assess_employees(&pool, None).await.unwrap();
+        let issues: Vec<Vec<DataQualityIssue>> = assessed
+            .iter()
+            .map(|(_, quality)| quality.issues.clone())
+            .collect();
+        assert_eq!(issues[0], Vec::<DataQualityIssue>::new());
+        assert_eq!(
+            issues[1],
+            vec![
+                DataQualityIssue::MissingNip,
+                DataQualityIssue::UnparsedGol,
+                DataQualityIssue::DuplicateSuspect
+            ]
+        );
+        assert_eq!(
+            issues[2],
+            vec![
+                DataQualityIssue::MissingNip,
+                DataQualityIssue::DuplicateSuspect
+            ]
+        );
+        assert_eq!(assessed[0].1.score, 100.0);
+    }
+
+    #[tokio::test]
+    async fn test_assess_dataset_reports_gaps_and_blockers() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO datasets (id, name, created_at, updated_at)
+             VALUES (1, 'Semester 1', datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, created_at, updated_at) VALUES
+                (1, 'Budi', datetime('now'), datetime('now')),
+                (2, 'Sari', datetime('now'), datetime('now')),
+                (3, 'Tono', datetime('now'), datetime('now')),
+                (4, 'Dewi', datetime('now'), datetime('now'));
+             INSERT INTO dataset_employees (dataset_id, employee_id, created_at, updated_at) VALUES
+                (1, 1, datetime('now'), datetime('now')),
+                (1, 2, datetime('now'), datetime('now')),
+                (1, 3, datetime('now'), datetime('now'));
+             INSERT INTO competencies (id, name, display_order)
+             VALUES (1, 'Kerjasama', 0), (2, 'Integritas', 1);
+             INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, created_at)
+             VALUES (1, 1, 1, 'Baik', 75.0, datetime('now')),
+                    (1, 1, 2, 'Baik', 75.0, datetime('now')),
+                    (2, 1, 1, 'Luar Biasa', NULL, datetime('now')),
+                    (4, 1, 2, 'Baik', 75.0, datetime('now'))",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Dewi is no longer linked, so her score does not count
+        let quality = assess_dataset(&pool, 1).await.unwrap();
+        assert_eq!(quality.competency_count, 2);
+        assert!((quality.complete_employee_percent - 100.0 / 3.0).abs() < 1e-9);
+        assert_eq!(quality.unmapped_score_count, 1);
+        assert_eq!(quality.employees_without_scores[0].name, "Tono");
+        let gaps: Vec<(&str, i64)> = quality
+            .competency_gaps
+            .iter()
+            .map(|gap| (gap.competency.name.as_str(), gap.missing_count))
+            .collect();
+        assert_eq!(gaps, vec![("Integritas", 2), ("Kerjasama", 1)]);
+        assert_eq!(quality.export_blockers.len(), 2);
+        assert!(quality.ensure_exportable(false).is_err());
+        assert!(quality.ensure_exportable(true).is_ok());
+    }
+}
@@ -0,0 +1,59 @@
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+/// Days present and on time (present days less late days) as a percentage of working days
+/// over the months of the dataset's reporting year (its period year, or the year it was created). Employees without attendance in that
+/// year are absent from the map; pass `employee_id` to load a single employee.
+pub async fn load_attendance_percentages(
+    pool: &SqlitePool,
+    dataset_id: i64,
+    employee_id: Option<i64>,
+) -> Result<HashMap<i64, f64>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, (i64, f64)>(
+        "SELECT a.employee_id, SUM(MAX(a.present_days - a.late_days, 0)) * 100.0 / SUM(a.working_days)
+         FROM attendance a
+         JOIN datasets d ON d.id = ?
+         WHERE substr(a.month, 1, 4)
+               = CAST(COALESCE(d.period_year, strftime('%Y', d.created_at, 'localtime')) AS TEXT)
+           AND (? IS NULL OR a.employee_id = ?)
+         GROUP BY a.employee_id
+         HAVING SUM(a.working_days) > 0",
+    )
+    .bind(dataset_id)
+    .bind(employee_id)
+    .bind(employee_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    #[tokio::test]
+    async fn test_attendance_percentage_covers_the_dataset_year() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO datasets (id, name, period_year, created_at, updated_at)
+             VALUES (1, 'Penilaian 2024', 2024, datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, created_at, updated_at)
+             VALUES (1, 'Budi', datetime('now'), datetime('now')),
+                    (2, 'Sari', datetime('now'), datetime('now'));
+             INSERT INTO attendance (employee_id, month, working_days, present_days, late_days)
+             VALUES (1, '2024-01', 20, 19, 1), (1, '2024-02', 20, 17, 2),
+                    (1, '2023-12', 20, 5, 0), (2, '2023-11', 20, 20, 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let all = load_attendance_percentages(&pool, 1, None).await.unwrap();
+        assert_eq!(all, HashMap::from([(1, 82.5)]));
+        let single = load_attendance_percentages(&pool, 1, Some(2))
+            .await
+            .unwrap();
+        assert!(single.is_empty());
+    }
+}
@@ -0,0 +1,35 @@
+use sqlx::{Executor, Sqlite, SqlitePool};
+
+/// Audit action of errors raised by background tasks.
+pub const TASK_FAILED_AUDIT_ACTION: &str = "background_task_failed";
+
+/// Appends an entry to the audit log. Accepts a pool or an open transaction so
+/// the entry can commit (or roll back) together with the change it describes.
+pub async fn record_audit<'e, E>(
+    executor: E,
+    action: &str,
+    dataset_id: Option<i64>,
+    details: &serde_json::Value,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    sqlx::query(
+        "INSERT INTO audit_log (action, dataset_id, details, created_at) VALUES (?, ?, ?, datetime('now'))",
+    )
+    .bind(action)
+    .bind(dataset_id)
+    .bind(details.to_string())
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Background tasks have no caller to hand errors to, so their failures go to the audit
+/// log; stderr is only the last resort when the log itself cannot be written.
+pub async fn record_task_failure(pool: &SqlitePool, task: &str, error: &str) {
+    let details = serde_json::json!({ "task": task, "error": error });
+    if let Err(audit_error) = record_audit(pool, TASK_FAILED_AUDIT_ACTION, None, &details).await {
+        eprintln!("{}: {} (not recorded: {})", task, error, audit_error);
+    }
+}
@@ -0,0 +1,345 @@
+use crate::csv_parser::CsvParser;
+use crate::db::score_entries::refresh_aggregated_scores;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqliteConnection, SqlitePool};
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CompetencyAlias {
+    pub alias_key: String,
+    pub competency_id: i64,
+}
+
+/// Identity of a competency name: leading question numbering ("1.", "2)", "3 -") removed,
+/// "&" read as "dan", punctuation dropped and case folded. "1. Inisiatif & Fleksibilitas"
+/// and "Inisiatif dan fleksibilitas" share a key.
+pub fn competency_key(name: &str) -> String {
+    let (_, unnumbered) = CsvParser::split_ordinal(name);
+    unnumbered
+        .to_lowercase()
+        .replace('&', " dan ")
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Rows a merge could not carry over to the target competency.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MergeOutcome {
+    /// Rater entries whose rater had already scored the target
+    pub dropped_entries: i64,
+    /// Source scores without rater entries where the target already had a score
+    pub dropped_scores: i64,
+}
+
+/// Existing competency `name` stands for: an alias with its key or the exact name.
+pub async fn resolve_competency(
+    conn: &mut SqliteConnection,
+    name: &str,
+) -> Result<Option<i64>, sqlx::Error> {
+    let key = competency_key(name);
+    if let Some(id) =
+        sqlx::query_scalar("SELECT competency_id FROM competency_aliases WHERE alias_key = ?")
+            .bind(&key)
+            .fetch_optional(&mut *conn)
+            .await?
+    {
+        return Ok(Some(id));
+    }
+    sqlx::query_scalar("SELECT id FROM competencies WHERE name = ?")
+        .bind(name)
+        .fetch_optional(conn)
+        .await
+}
+
+/// Gives every competency an alias of its own name, lowest id first, so names created
+/// before aliases existed are matched too. Existing aliases are kept.
+pub async fn backfill_aliases(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let competencies: Vec<(i64, String)> =
+        sqlx::query_as("SELECT id, name FROM competencies ORDER BY id")
+            .fetch_all(&mut *tx)
+            .await?;
+    for (id, name) in competencies {
+        record_alias(&mut tx, &name, id).await?;
+    }
+    tx.commit().await
+}
+
+/// Moves competency `competency_id` to its question number: `ordinal` when the source
+/// carries one, else the number its stored name starts with. A numbered name
+/// ("1. Inisiatif & Fleksibilitas") is stored without the number unless another
+/// competency already has that name.
+pub async fn apply_question_number(
+    conn: &mut SqliteConnection,
+    competency_id: i64,
+    ordinal: Option<i32>,
+) -> Result<(), sqlx::Error> {
+    let name: String = sqlx::query_scalar("SELECT name FROM competencies WHERE id = ?")
+        .bind(competency_id)
+        .fetch_one(&mut *conn)
+        .await?;
+    let (stored_ordinal, unnumbered) = CsvParser::split_ordinal(&name);
+    let Some(ordinal) = ordinal.or(stored_ordinal) else {
+        return Ok(());
+    };
+    sqlx::query(
+        "UPDATE competencies
+         SET display_order = ?1,
+             name = CASE WHEN EXISTS (SELECT 1 FROM competencies WHERE name = ?2 AND id <> ?3)
+                         THEN name ELSE ?2 END
+         WHERE id = ?3",
+    )
+    .bind(ordinal)
+    .bind(unnumbered)
+    .bind(competency_id)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Applies the question number of every competency stored with a numbered name.
+pub async fn backfill_question_numbers(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let competencies: Vec<(i64, String)> =
+        sqlx::query_as("SELECT id, name FROM competencies ORDER BY id")
+            .fetch_all(&mut *tx)
+            .await?;
+    for (id, name) in competencies {
+        if CsvParser::split_ordinal(&name).0.is_some() {
+            apply_question_number(&mut tx, id, None).await?;
+        }
+    }
+    tx.commit().await
+}
+
+/// Remembers that `name` stands for `competency_id`; an existing alias of the key is kept.
+pub async fn record_alias(
+    conn: &mut SqliteConnection,
+    name: &str,
+    competency_id: i64,
+) -> Result<(), sqlx::Error> {
+    let key = competency_key(name);
+    if key.is_empty() {
+        return Ok(());
+    }
+    sqlx::query(
+        "INSERT INTO competency_aliases (alias_key, competency_id, created_at)
+         VALUES (?, ?, datetime('now'))
+         ON CONFLICT(alias_key) DO NOTHING",
+    )
+    .bind(key)
+    .bind(competency_id)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Re-points everything of competency `source_id` onto `target_id` and deletes `source_id`,
+/// leaving its name as an alias of the target. Rater entries move to the target and each
+/// affected dataset is re-aggregated; entries of a rater who already scored the target, and
+/// clashing source scores without entries, are dropped and counted.
+pub async fn merge_competencies(
+    conn: &mut SqliteConnection,
+    source_id: i64,
+    target_id: i64,
+) -> Result<MergeOutcome, sqlx::Error> {
+    let source_name: String = sqlx::query_scalar("SELECT name FROM competencies WHERE id = ?")
+        .bind(source_id)
+        .fetch_one(&mut *conn)
+        .await?;
+    let dataset_ids: Vec<i64> =
+        sqlx::query_scalar("SELECT DISTINCT dataset_id FROM scores WHERE competency_id = ?")
+            .bind(source_id)
+            .fetch_all(&mut *conn)
+            .await?;
+
+    let clash = "competency_id = ?1 AND EXISTS (
+            SELECT 1 FROM scores t
+            WHERE t.competency_id = ?2 AND t.dataset_id = scores.dataset_id
+              AND t.employee_id = scores.employee_id)";
+    let dropped_scores: i64 = sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM scores WHERE {} AND NOT EXISTS (
+            SELECT 1 FROM score_entries e
+            WHERE e.competency_id = ?1 AND e.dataset_id = scores.dataset_id
+              AND e.employee_id = scores.employee_id)",
+        clash
+    ))
+    .bind(source_id)
+    .bind(target_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    // Entries move before their scores go: deleting a score removes its entries through a
+    // trigger, taking the entries a rater had already given the target with it
+    sqlx::query("UPDATE OR IGNORE score_entries SET competency_id = ?2 WHERE competency_id = ?1")
+        .bind(source_id)
+        .bind(target_id)
+        .execute(&mut *conn)
+        .await?;
+    let dropped_entries: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM score_entries WHERE competency_id = ?")
+            .bind(source_id)
+            .fetch_one(&mut *conn)
+            .await?;
+    sqlx::query(&format!("DELETE FROM scores WHERE {}", clash))
+        .bind(source_id)
+        .bind(target_id)
+        .execute(&mut *conn)
+        .await?;
+
+    for statement in [
+        "UPDATE scores SET competency_id = ?2 WHERE competency_id = ?1",
+        "UPDATE OR IGNORE dataset_competencies SET competency_id = ?2 WHERE competency_id = ?1",
+        "UPDATE OR IGNORE competency_weights SET competency_id = ?2 WHERE competency_id = ?1",
+        "UPDATE competency_aliases SET competency_id = ?2 WHERE competency_id = ?1",
+    ] {
+        sqlx::query(statement)
+            .bind(source_id)
+            .bind(target_id)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    // Rows that clashed with the target go with the source
+    sqlx::query("DELETE FROM competencies WHERE id = ?")
+        .bind(source_id)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query(
+        "INSERT INTO competency_aliases (alias_key, competency_id, created_at)
+         VALUES (?, ?, datetime('now'))
+         ON CONFLICT(alias_key) DO UPDATE SET competency_id = excluded.competency_id",
+    )
+    .bind(competency_key(&source_name))
+    .bind(target_id)
+    .execute(&mut *conn)
+    .await?;
+
+    for dataset_id in dataset_ids {
+        refresh_aggregated_scores(&mut *conn, dataset_id, None).await?;
+    }
+    Ok(MergeOutcome {
+        dropped_entries,
+        dropped_scores,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    #[test]
+    fn test_competency_key_ignores_numbering_and_spelling() {
+        let key = competency_key("Inisiatif dan fleksibilitas");
+        assert_eq!(key, "inisiatif dan fleksibilitas");
+        assert_eq!(competency_key("1. Inisiatif & Fleksibilitas"), key);
+        assert_eq!(competency_key(" 12) Inisiatif  &Fleksibilitas "), key);
+        assert_eq!(competency_key("360 Feedback"), "360 feedback");
+    }
+
+    #[tokio::test]
+    async fn test_question_numbers_move_into_the_display_order() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO competencies (id, name, display_order)
+             VALUES (1, '1. Inisiatif & Fleksibilitas', 5), (2, 'Kerjasama', 0),
+                    (3, '2. Kerjasama', 0), (4, 'Integritas', 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        backfill_question_numbers(&pool).await.unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        apply_question_number(&mut conn, 4, Some(7)).await.unwrap();
+        let competencies: Vec<(String, i64)> =
+            sqlx::query_as("SELECT name, display_order FROM competencies ORDER BY id")
+                .fetch_all(&mut *conn)
+                .await
+                .unwrap();
+        assert_eq!(
+            competencies,
+            vec![
+                ("Inisiatif & Fleksibilitas".to_string(), 1),
+                ("Kerjasama".to_string(), 0),
+                ("2. Kerjasama".to_string(), 2),
+                ("Integritas".to_string(), 7),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_moves_scores_onto_the_target() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO datasets (id, name, created_at, updated_at)
+             VALUES (1, 'Semester 1', datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, created_at, updated_at)
+             VALUES (1, 'Budi', datetime('now'), datetime('now')),
+                    (2, 'Sari', datetime('now'), datetime('now')),
+                    (3, 'Dewi', datetime('now'), datetime('now')),
+                    (4, 'Rudi', datetime('now'), datetime('now'));
+             INSERT INTO competencies (id, name, display_order)
+             VALUES (1, 'Inisiatif dan fleksibilitas', 0), (2, '1. Inisiatif & Fleksibilitas', 1);
+             INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, created_at)
+             VALUES (1, 1, 1, 'Baik', 75, datetime('now')),
+                    (1, 1, 2, 'Sangat Baik', 90, datetime('now')),
+                    (2, 1, 2, 'Baik', 75, datetime('now')),
+                    (3, 1, 1, '80', 80, datetime('now')),
+                    (3, 1, 2, '60', 60, datetime('now')),
+                    (4, 1, 1, '70', 70, datetime('now')),
+                    (4, 1, 2, '50', 50, datetime('now'));
+             INSERT INTO score_entries (dataset_id, employee_id, competency_id, rater, raw_value, numeric_value)
+             VALUES (1, 1, 1, 'legacy', 'Baik', 75),
+                    (1, 1, 2, 'atasan', 'Sangat Baik', 90),
+                    (1, 2, 2, 'legacy', 'Baik', 75),
+                    (1, 3, 1, 'legacy', '80', 80),
+                    (1, 3, 2, 'legacy', '60', 60),
+                    (1, 4, 1, 'legacy', '70', 70);",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        backfill_aliases(&pool).await.unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        assert_eq!(
+            resolve_competency(&mut conn, "2. inisiatif dan Fleksibilitas")
+                .await
+                .unwrap(),
+            Some(1)
+        );
+
+        let outcome = merge_competencies(&mut conn, 2, 1).await.unwrap();
+        assert_eq!(
+            outcome,
+            MergeOutcome {
+                dropped_entries: 1,
+                dropped_scores: 1,
+            }
+        );
+        let scores: Vec<(i64, i64, f64)> = sqlx::query_as(
+            "SELECT employee_id, competency_id, numeric_value FROM scores ORDER BY employee_id",
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .unwrap();
+        assert_eq!(
+            scores,
+            vec![(1, 1, 82.5), (2, 1, 75.0), (3, 1, 80.0), (4, 1, 70.0)]
+        );
+        let entries: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM score_entries")
+            .fetch_one(&mut *conn)
+            .await
+            .unwrap();
+        assert_eq!(entries, 5);
+        assert_eq!(
+            resolve_competency(&mut conn, "1. Inisiatif & Fleksibilitas")
+                .await
+                .unwrap(),
+            Some(1)
+        );
+    }
+}
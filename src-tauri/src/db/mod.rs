@@ -1,29 +1,255 @@
-use sqlx::{migrate::MigrateDatabase, sqlite::SqlitePool};
-use std::path::PathBuf;
+use crate::backup;
+use crate::encryption::key_pragma;
+use serde::{Deserialize, Serialize};
+use sqlx::migrate::Migrator;
+use sqlx::sqlite::{
+    SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions, SqliteSynchronous,
+};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
+pub mod attendance;
+pub mod audit;
+pub mod competency_aliases;
 pub mod models;
+pub mod score_entries;
+pub mod settings;
+pub mod validation;
+
+/// SQLite serialises writers, so a handful of connections covers concurrent reads
+/// alongside the one running import.
+const MAX_CONNECTIONS: u32 = 8;
+/// How long a statement waits on another connection's lock before failing.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(10);
+/// Backup label of the snapshot taken before pending migrations run.
+const PRE_MIGRATION_LABEL: &str = "pre-migration";
+
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// A migration this build carries that the database has not run yet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingMigration {
+    pub version: i64,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaInfo {
+    /// Newest migration applied to the database; `None` when it is empty
+    pub current_version: Option<i64>,
+    /// Newest migration this build carries
+    pub latest_version: i64,
+    pub pending: Vec<PendingMigration>,
+    /// Applied migrations this build does not carry, left behind by a newer version of the
+    /// app; the database cannot be opened until that version is back
+    pub unknown_versions: Vec<i64>,
+}
 
 pub struct Database {
     pub pool: SqlitePool,
 }
 
 impl Database {
-    pub async fn new(db_path: PathBuf) -> Result<Self, sqlx::Error> {
-        let db_url = format!("sqlite:{}", db_path.display());
-
-        // Create database if it doesn't exist
-        if !sqlx::Sqlite::database_exists(&db_url)
-            .await
-            .unwrap_or(false)
-        {
-            sqlx::Sqlite::create_database(&db_url).await?;
+    /// Opens (creating if needed) the database, keyed with `passphrase` when encrypted.
+    pub async fn new(db_path: PathBuf, passphrase: Option<&str>) -> Result<Self, sqlx::Error> {
+        // WAL lets readers continue while an import writes; NORMAL sync is durable in WAL
+        // mode except across power loss. Deletes rely on cascading foreign keys, so they are
+        // enforced on every connection. The key pragma always runs first.
+        let mut options = SqliteConnectOptions::new()
+            .filename(db_path)
+            .create_if_missing(true)
+            .foreign_keys(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .synchronous(SqliteSynchronous::Normal)
+            .busy_timeout(BUSY_TIMEOUT)
+            .optimize_on_close(true, None);
+        if let Some(passphrase) = passphrase {
+            options = options.pragma("key", key_pragma(passphrase));
         }
 
-        let pool = SqlitePool::connect(&db_url).await?;
+        let pool = SqlitePoolOptions::new()
+            .max_connections(MAX_CONNECTIONS)
+            .connect_with(options)
+            .await?;
 
-        // Run migrations
-        sqlx::migrate!("./migrations").run(&pool).await?;
+        // A failed migration can leave the schema half changed, so an existing database is
+        // copied first; an empty one has nothing to lose
+        let schema = schema_info(&pool).await?;
+        if schema.current_version.is_some() && !schema.pending.is_empty() {
+            backup::create_backup(&pool, PRE_MIGRATION_LABEL)
+                .await
+                .map_err(|e| sqlx::Error::Io(std::io::Error::other(e)))?;
+        }
+        MIGRATOR.run(&pool).await?;
+        competency_aliases::backfill_aliases(&pool).await?;
+        competency_aliases::backfill_question_numbers(&pool).await?;
 
         Ok(Self { pool })
     }
 }
+
+async fn applied_versions(pool: &SqlitePool) -> Result<Vec<i64>, sqlx::Error> {
+    let tracked: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations')",
+    )
+    .fetch_one(pool)
+    .await?;
+    if !tracked {
+        return Ok(Vec::new());
+    }
+    sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success = 1 ORDER BY version")
+        .fetch_all(pool)
+        .await
+}
+
+/// Where the database's schema stands against the migrations in this build.
+pub async fn schema_info(pool: &SqlitePool) -> Result<SchemaInfo, sqlx::Error> {
+    let applied = applied_versions(pool).await?;
+    let migrations: Vec<_> = MIGRATOR
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .collect();
+
+    Ok(SchemaInfo {
+        current_version: applied.last().copied(),
+        latest_version: migrations
+            .iter()
+            .map(|migration| migration.version)
+            .max()
+            .unwrap_or_default(),
+        pending: migrations
+            .iter()
+            .filter(|migration| !applied.contains(&migration.version))
+            .map(|migration| PendingMigration {
+                version: migration.version,
+                description: migration.description.to_string(),
+            })
+            .collect(),
+        unknown_versions: applied
+            .iter()
+            .copied()
+            .filter(|version| {
+                !migrations
+                    .iter()
+                    .any(|migration| migration.version == *version)
+            })
+            .collect(),
+    })
+}
+
+/// Directory holding the database file; backups and attachments live next to it.
+pub(crate) async fn data_directory(pool: &SqlitePool) -> Result<PathBuf, String> {
+    let (_, _, file): (i64, String, String) = sqlx::query_as("PRAGMA database_list")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to locate database file: {}", e))?;
+    Path::new(&file)
+        .parent()
+        .filter(|_| !file.is_empty())
+        .map(Path::to_path_buf)
+        .ok_or_else(|| "The database is not stored in a file".to_string())
+}
+
+/// In-memory database with every migration applied, for tests. One connection, since each
+/// connection to `sqlite::memory:` opens a database of its own.
+#[cfg(test)]
+pub(crate) async fn test_pool() -> SqlitePool {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    MIGRATOR.run(&pool).await.unwrap();
+    pool
+}
+
+/// Adds datasets with these names, numbered from 1 in a fresh `test_pool`.
+#[cfg(test)]
+pub(crate) async fn seed_datasets(pool: &SqlitePool, names: &[&str]) {
+    let mut insert =
+        sqlx::QueryBuilder::new("INSERT INTO datasets (name, created_at, updated_at) ");
+    insert.push_values(names, |mut row, name| {
+        row.push_bind(*name)
+            .push("datetime('now')")
+            .push("datetime('now')");
+    });
+    insert.build().execute(pool).await.unwrap();
+}
+
+/// Adds employees with these names, numbered from 1 in a fresh `test_pool`.
+#[cfg(test)]
+pub(crate) async fn seed_employees(pool: &SqlitePool, names: &[&str]) {
+    let mut insert =
+        sqlx::QueryBuilder::new("INSERT INTO employees (name, created_at, updated_at) ");
+    insert.push_values(names, |mut row, name| {
+        row.push_bind(*name)
+            .push("datetime('now')")
+            .push("datetime('now')");
+    });
+    insert.build().execute(pool).await.unwrap();
+}
+
+/// Adds the employees to the dataset's roster.
+#[cfg(test)]
+pub(crate) async fn seed_members(pool: &SqlitePool, dataset_id: i64, employee_ids: &[i64]) {
+    let mut insert = sqlx::QueryBuilder::new(
+        "INSERT INTO dataset_employees (dataset_id, employee_id, created_at, updated_at) ",
+    );
+    insert.push_values(employee_ids, |mut row, employee_id| {
+        row.push_bind(dataset_id)
+            .push_bind(*employee_id)
+            .push("datetime('now')")
+            .push("datetime('now')");
+    });
+    insert.build().execute(pool).await.unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_existing_database_is_backed_up_before_migrating() {
+        let directory = tempfile::tempdir().unwrap();
+
+        // A new database has nothing to back up
+        let database = Database::new(directory.path().join("new.db"), None)
+            .await
+            .unwrap();
+        database.pool.close().await;
+        assert!(!directory.path().join("backups").exists());
+
+        // A database one migration behind this build
+        let path = directory.path().join("epa.db");
+        let mut older = sqlx::migrate!("./migrations");
+        older.migrations = older.migrations[..older.migrations.len() - 1]
+            .to_vec()
+            .into();
+        let pool = SqlitePoolOptions::new()
+            .connect_with(
+                SqliteConnectOptions::new()
+                    .filename(&path)
+                    .create_if_missing(true),
+            )
+            .await
+            .unwrap();
+        older.run(&pool).await.unwrap();
+        let info = schema_info(&pool).await.unwrap();
+        assert_eq!(info.pending.len(), 1);
+        assert_eq!(info.pending[0].version, info.latest_version);
+        pool.close().await;
+
+        let database = Database::new(path, None).await.unwrap();
+        let info = schema_info(&database.pool).await.unwrap();
+        assert_eq!(info.current_version, Some(info.latest_version));
+        assert!(info.pending.is_empty());
+        database.pool.close().await;
+
+        let backups: Vec<String> = std::fs::read_dir(directory.path().join("backups"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(backups.len(), 1);
+        assert!(backups[0].starts_with("epa-pre-migration-"));
+    }
+}
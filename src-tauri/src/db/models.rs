@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
@@ -8,8 +8,30 @@ pub struct Dataset {
     pub name: String,
     pub description: Option<String>,
     pub source_file: Option<String>,
+    pub period_year: Option<i32>,
+    pub period_label: Option<String>,
+    /// Local wall-clock time after which submitted responses count as late.
+    #[serde(default)]
+    pub submission_deadline: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub archived_at: Option<DateTime<Utc>>,
+    /// Raw score that normalises to 100; `None` detects it from the scores.
+    #[serde(default)]
+    pub score_scale: Option<f64>,
+    /// How entries of several raters combine into a score: `mean`, `median` or `mode`;
+    /// `None` means mean.
+    #[serde(default)]
+    pub score_aggregation: Option<String>,
+    /// How each rater's values are evened out for analytics and weighting: `none`, `z_score`
+    /// or `min_max`; `None` means none.
+    #[serde(default)]
+    pub score_normalization: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Filled in by `list_datasets` and `get_dataset`; empty elsewhere.
+    #[serde(default)]
+    #[sqlx(skip)]
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -20,6 +42,12 @@ pub struct Employee {
     pub gol: Option<String>,
     pub jabatan: Option<String>,
     pub sub_jabatan: Option<String>,
+    /// Manual `eselon`/`staff` override; when unset the type is guessed from jabatan and golongan.
+    #[serde(default)]
+    pub position_type: Option<String>,
+    /// Stored photo relative to the database directory; read it with `get_employee_photo`.
+    #[serde(default)]
+    pub photo_path: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -30,6 +58,16 @@ pub struct Competency {
     pub name: String,
     pub description: Option<String>,
     pub display_order: i32,
+    #[serde(default)]
+    pub category_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CompetencyCategory {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub display_order: i32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -41,6 +79,13 @@ pub struct Score {
     pub raw_value: String,
     pub numeric_value: Option<f64>,
     pub created_at: DateTime<Utc>,
+    /// Source line and column header the score was imported from, if it was imported.
+    #[serde(default)]
+    pub source_row: Option<i64>,
+    #[serde(default)]
+    pub source_column: Option<String>,
+    #[serde(default)]
+    pub import_run_id: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -51,15 +96,127 @@ pub struct RatingMapping {
     pub numeric_value: f64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DatasetNote {
+    pub id: i64,
+    pub dataset_id: i64,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EmployeeNote {
+    pub id: i64,
+    pub dataset_id: i64,
+    pub employee_id: i64,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Endpoint that receives a POST when an import or merge finishes; see `webhooks`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Webhook {
+    pub id: i64,
+    pub url: String,
+    pub enabled: bool,
+    /// Key of the `X-Webhook-Signature` HMAC the receiver verifies payloads with
+    pub secret: String,
+    pub last_status: Option<String>,
+    pub last_error: Option<String>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Attendance of one employee in one month.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Attendance {
+    pub id: i64,
+    pub employee_id: i64,
+    /// `YYYY-MM`
+    pub month: String,
+    pub working_days: i64,
+    pub present_days: i64,
+    pub late_days: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One rater's value for a score; the score holds the aggregation of its entries.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ScoreEntry {
+    pub id: i64,
+    pub dataset_id: i64,
+    pub employee_id: i64,
+    pub competency_id: i64,
+    /// Rater name, or the import source when none was given.
+    pub rater: String,
+    pub raw_value: String,
+    pub numeric_value: Option<f64>,
+    pub import_run_id: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// One committed import or append. `kind` is `create`, `append` or `employees`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ImportRun {
+    pub id: i64,
+    pub dataset_id: i64,
+    pub kind: String,
+    pub source_file: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub row_count: i64,
+    pub inserted_count: i64,
+    pub updated_count: i64,
+    pub skipped_count: i64,
+    /// Issue counts from the validation pass the user ran before importing, if any.
+    pub validation_errors: Option<i64>,
+    pub validation_warnings: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AuditLogEntry {
+    pub id: i64,
+    pub action: String,
+    pub dataset_id: Option<i64>,
+    pub details: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Summary {
     pub id: i64,
     pub employee_id: i64,
+    pub dataset_id: i64,
     pub content: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Attachment {
+    pub id: i64,
+    pub employee_id: i64,
+    /// Original file name as chosen by the user
+    pub filename: String,
+    pub mime: String,
+    /// Relative to the attachments directory
+    pub stored_path: String,
+    pub size_bytes: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SummaryRevision {
+    pub id: i64,
+    pub employee_id: i64,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub dataset_id: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct ValidationIssue {
     pub id: i64,
@@ -78,6 +235,14 @@ pub struct CreateDataset {
     pub name: String,
     pub description: Option<String>,
     pub source_file: Option<String>,
+    pub period_year: Option<i32>,
+    pub period_label: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetPeriod {
+    pub period_year: Option<i32>,
+    pub period_label: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -112,3 +277,14 @@ pub struct CreateRatingMapping {
     pub text_value: String,
     pub numeric_value: f64,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct User {
+    pub id: i64,
+    pub username: String,
+    #[serde(skip)]
+    pub password_hash: String,
+    /// `admin`, `editor` or `viewer`
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+}
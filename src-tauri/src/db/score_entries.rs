@@ -0,0 +1,370 @@
+use sqlx::SqliteConnection;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Rater of values typed into the app rather than imported.
+pub const MANUAL_RATER: &str = "manual";
+/// Rater of values brought in with a dataset bundle.
+pub const BUNDLE_RATER: &str = "bundle";
+
+/// How the entries of several raters for one score are combined.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScoreAggregation {
+    #[default]
+    Mean,
+    Median,
+    Mode,
+}
+
+impl ScoreAggregation {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Mean => "mean",
+            Self::Median => "median",
+            Self::Mode => "mode",
+        }
+    }
+}
+
+impl FromStr for ScoreAggregation {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "mean" => Ok(Self::Mean),
+            "median" => Ok(Self::Median),
+            "mode" => Ok(Self::Mode),
+            other => Err(format!("Unknown score aggregation: {}", other)),
+        }
+    }
+}
+
+/// How each rater's numeric values are evened out before analytics and weighting. Imports
+/// rate under the source file name by default, so this also evens out source files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScoreNormalization {
+    #[default]
+    None,
+    /// Each rater's values keep their distance from the rater's mean in standard deviations,
+    /// measured against the mean and deviation of the whole dataset
+    ZScore,
+    /// Each rater's lowest and highest value are stretched onto the dataset's range
+    MinMax,
+}
+
+impl ScoreNormalization {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::ZScore => "z_score",
+            Self::MinMax => "min_max",
+        }
+    }
+}
+
+impl FromStr for ScoreNormalization {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "none" => Ok(Self::None),
+            "z_score" => Ok(Self::ZScore),
+            "min_max" => Ok(Self::MinMax),
+            other => Err(format!("Unknown score normalization: {}", other)),
+        }
+    }
+}
+
+/// Raw and numeric value of a score rated more than once. Entries without a numeric value
+/// are left out; `None` when none has one.
+pub(crate) fn combine_entries(
+    aggregation: ScoreAggregation,
+    entries: &[(String, Option<f64>)],
+) -> Option<(String, f64)> {
+    let rated: Vec<(&str, f64)> = entries
+        .iter()
+        .filter_map(|(raw, numeric)| numeric.map(|value| (raw.as_str(), value)))
+        .collect();
+    if rated.is_empty() {
+        return None;
+    }
+
+    let value = match aggregation {
+        ScoreAggregation::Mean => {
+            rated.iter().map(|(_, value)| value).sum::<f64>() / rated.len() as f64
+        }
+        ScoreAggregation::Median => {
+            let mut values: Vec<f64> = rated.iter().map(|(_, value)| *value).collect();
+            values.sort_by(f64::total_cmp);
+            let middle = values.len() / 2;
+            if values.len().is_multiple_of(2) {
+                (values[middle - 1] + values[middle]) / 2.0
+            } else {
+                values[middle]
+            }
+        }
+        // The raw value given most often keeps its label; the later one wins a tie
+        ScoreAggregation::Mode => {
+            let mut best: Option<(usize, usize)> = None;
+            for (position, (raw, _)) in rated.iter().enumerate() {
+                let count = rated.iter().filter(|(other, _)| other == raw).count();
+                if best.is_none_or(|(best_count, _)| count >= best_count) {
+                    best = Some((count, position));
+                }
+            }
+            let (raw, value) = rated[best.map_or(0, |(_, position)| position)];
+            return Some((raw.to_string(), value));
+        }
+    };
+    let rounded = (value * 100.0).round() / 100.0;
+    Some((rounded.to_string(), rounded))
+}
+
+/// The dataset's aggregation; unknown or missing values fall back to the mean.
+pub async fn load_score_aggregation(
+    conn: &mut SqliteConnection,
+    dataset_id: i64,
+) -> Result<ScoreAggregation, sqlx::Error> {
+    Ok(sqlx::query_scalar::<_, Option<String>>(
+        "SELECT score_aggregation FROM datasets WHERE id = ?",
+    )
+    .bind(dataset_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .flatten()
+    .and_then(|value| value.parse().ok())
+    .unwrap_or_default())
+}
+
+/// The dataset's normalization; unknown or missing values leave scores as they are.
+pub async fn load_score_normalization(
+    conn: &mut SqliteConnection,
+    dataset_id: i64,
+) -> Result<ScoreNormalization, sqlx::Error> {
+    Ok(sqlx::query_scalar::<_, Option<String>>(
+        "SELECT score_normalization FROM datasets WHERE id = ?",
+    )
+    .bind(dataset_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .flatten()
+    .and_then(|value| value.parse().ok())
+    .unwrap_or_default())
+}
+
+/// Mean, standard deviation, lowest and highest of `values`, which must not be empty.
+fn spread(values: &[f64]) -> (f64, f64, f64, f64) {
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let variance = values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / values.len() as f64;
+    let low = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let high = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    (mean, variance.sqrt(), low, high)
+}
+
+/// Numeric values of `entries` (rater and value) evened out per rater. Entries without a
+/// numeric value stay `None`.
+fn normalize_by_rater(
+    normalization: ScoreNormalization,
+    entries: &[(&str, Option<f64>)],
+) -> Vec<Option<f64>> {
+    let all: Vec<f64> = entries.iter().filter_map(|(_, value)| *value).collect();
+    if all.is_empty() || normalization == ScoreNormalization::None {
+        return entries.iter().map(|(_, value)| *value).collect();
+    }
+    let mut by_rater: HashMap<&str, Vec<f64>> = HashMap::new();
+    for (rater, value) in entries {
+        if let Some(value) = value {
+            by_rater.entry(*rater).or_default().push(*value);
+        }
+    }
+    let rater_spreads: HashMap<&str, (f64, f64, f64, f64)> = by_rater
+        .into_iter()
+        .map(|(rater, values)| (rater, spread(&values)))
+        .collect();
+
+    let (mean, deviation, low, high) = spread(&all);
+    entries
+        .iter()
+        .map(|(rater, value)| {
+            let value = (*value)?;
+            let (own_mean, own_deviation, own_low, own_high) = rater_spreads[rater];
+            Some(match normalization {
+                ScoreNormalization::None => value,
+                ScoreNormalization::ZScore if own_deviation > 0.0 => {
+                    mean + (value - own_mean) / own_deviation * deviation
+                }
+                ScoreNormalization::ZScore => mean,
+                ScoreNormalization::MinMax if own_high > own_low => {
+                    low + (value - own_low) / (own_high - own_low) * (high - low)
+                }
+                ScoreNormalization::MinMax => (low + high) / 2.0,
+            })
+        })
+        .collect()
+}
+
+/// Numeric value of every score in the dataset after its normalization, keyed by employee
+/// and competency and combined with the dataset's aggregation. Scores last edited by hand
+/// keep their stored value and are left out. Empty when the dataset is not normalized; the
+/// stored scores are left untouched either way.
+pub async fn load_normalized_values(
+    conn: &mut SqliteConnection,
+    dataset_id: i64,
+) -> Result<HashMap<(i64, i64), f64>, sqlx::Error> {
+    let normalization = load_score_normalization(conn, dataset_id).await?;
+    if normalization == ScoreNormalization::None {
+        return Ok(HashMap::new());
+    }
+    let aggregation = load_score_aggregation(conn, dataset_id).await?;
+    let entries: Vec<(i64, i64, String, String, Option<f64>)> = sqlx::query_as(
+        "SELECT employee_id, competency_id, rater, raw_value, numeric_value
+         FROM score_entries
+         WHERE dataset_id = ?
+         ORDER BY employee_id, competency_id, created_at, id",
+    )
+    .bind(dataset_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let rated: Vec<(&str, Option<f64>)> = entries
+        .iter()
+        .map(|(_, _, rater, _, numeric)| (rater.as_str(), *numeric))
+        .collect();
+    let normalized = normalize_by_rater(normalization, &rated);
+
+    let mut values = HashMap::new();
+    let mut start = 0;
+    for pair in entries.chunk_by(|a, b| (a.0, a.1) == (b.0, b.1)) {
+        let combined: Vec<(String, Option<f64>)> = pair
+            .iter()
+            .zip(&normalized[start..start + pair.len()])
+            .map(|((_, _, _, raw, _), value)| (raw.clone(), *value))
+            .collect();
+        start += pair.len();
+        if pair
+            .last()
+            .is_some_and(|(_, _, rater, _, _)| rater == MANUAL_RATER)
+        {
+            continue;
+        }
+        let value = if combined.len() == 1 {
+            combined[0].1
+        } else {
+            combine_entries(aggregation, &combined).map(|(_, value)| value)
+        };
+        if let Some(value) = value {
+            values.insert((pair[0].0, pair[0].1), value);
+        }
+    }
+    Ok(values)
+}
+
+/// Rewrites the scores rated more than once from their entries; with `import_run_id`, only
+/// the scores that run wrote. Returns the number of scores rewritten.
+pub async fn refresh_aggregated_scores(
+    conn: &mut SqliteConnection,
+    dataset_id: i64,
+    import_run_id: Option<i64>,
+) -> Result<usize, sqlx::Error> {
+    let aggregation = load_score_aggregation(conn, dataset_id).await?;
+    let entries: Vec<(i64, i64, String, Option<f64>)> = sqlx::query_as(
+        "SELECT e.employee_id, e.competency_id, e.raw_value, e.numeric_value
+         FROM score_entries e
+         JOIN scores s ON s.dataset_id = e.dataset_id AND s.employee_id = e.employee_id
+          AND s.competency_id = e.competency_id
+         WHERE e.dataset_id = ? AND (? IS NULL OR s.import_run_id = ?)
+         ORDER BY e.employee_id, e.competency_id, e.created_at, e.id",
+    )
+    .bind(dataset_id)
+    .bind(import_run_id)
+    .bind(import_run_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut rewritten = 0usize;
+    for pair in entries.chunk_by(|a, b| (a.0, a.1) == (b.0, b.1)) {
+        if pair.len() < 2 {
+            continue;
+        }
+        let values: Vec<(String, Option<f64>)> = pair
+            .iter()
+            .map(|(_, _, raw, numeric)| (raw.clone(), *numeric))
+            .collect();
+        let Some((raw_value, numeric_value)) = combine_entries(aggregation, &values) else {
+            continue;
+        };
+        sqlx::query(
+            "UPDATE scores SET raw_value = ?, numeric_value = ?
+             WHERE dataset_id = ? AND employee_id = ? AND competency_id = ?",
+        )
+        .bind(raw_value)
+        .bind(numeric_value)
+        .bind(dataset_id)
+        .bind(pair[0].0)
+        .bind(pair[0].1)
+        .execute(&mut *conn)
+        .await?;
+        rewritten += 1;
+    }
+    Ok(rewritten)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combine_entries_per_aggregation() {
+        let entries = vec![
+            ("Baik".to_string(), Some(75.0)),
+            ("Sangat Baik".to_string(), Some(85.0)),
+            ("Baik".to_string(), Some(75.0)),
+            ("Cukup".to_string(), None),
+        ];
+
+        assert_eq!(
+            combine_entries(ScoreAggregation::Mean, &entries),
+            Some(("78.33".to_string(), 78.33))
+        );
+        assert_eq!(
+            combine_entries(ScoreAggregation::Median, &entries),
+            Some(("75".to_string(), 75.0))
+        );
+        assert_eq!(
+            combine_entries(ScoreAggregation::Mode, &entries),
+            Some(("Baik".to_string(), 75.0))
+        );
+        assert_eq!(combine_entries(ScoreAggregation::Mean, &entries[3..]), None);
+    }
+
+    #[test]
+    fn test_normalize_by_rater_evens_out_a_lenient_rater() {
+        let entries = vec![
+            ("Atasan", Some(60.0)),
+            ("Atasan", Some(80.0)),
+            ("Rekan", Some(80.0)),
+            ("Rekan", Some(100.0)),
+            ("Rekan", None),
+        ];
+
+        assert_eq!(
+            normalize_by_rater(ScoreNormalization::None, &entries),
+            vec![Some(60.0), Some(80.0), Some(80.0), Some(100.0), None]
+        );
+        assert_eq!(
+            normalize_by_rater(ScoreNormalization::MinMax, &entries),
+            vec![Some(60.0), Some(100.0), Some(60.0), Some(100.0), None]
+        );
+        // Pooled mean 80 and deviation sqrt(200); each rater is one deviation either side
+        let z_scores = normalize_by_rater(ScoreNormalization::ZScore, &entries);
+        let deviation = 200f64.sqrt();
+        assert!((z_scores[0].unwrap() - (80.0 - deviation)).abs() < 1e-9);
+        assert_eq!(z_scores[0], z_scores[2]);
+        assert_eq!(z_scores[1], z_scores[3]);
+        assert_eq!(z_scores[4], None);
+    }
+}
@@ -0,0 +1,39 @@
+use sqlx::{Executor, Sqlite, SqlitePool};
+use std::str::FromStr;
+
+pub async fn get_setting(pool: &SqlitePool, key: &str) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar("SELECT value FROM app_settings WHERE key = ?")
+        .bind(key)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Reads a typed setting, falling back to `default` when it is missing or unparsable.
+pub async fn get_setting_or<T: FromStr>(
+    pool: &SqlitePool,
+    key: &str,
+    default: T,
+) -> Result<T, sqlx::Error> {
+    Ok(get_setting(pool, key)
+        .await?
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default))
+}
+
+pub async fn set_setting<'e, E>(executor: E, key: &str, value: &str) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO app_settings (key, value, updated_at)
+        VALUES (?, ?, datetime('now'))
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(key)
+    .bind(value)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use sqlx::{Executor, Sqlite};
+
+/// Stores a data quality problem found while importing into a dataset. An unresolved issue
+/// with the same type and message is not duplicated when the same file is appended again.
+pub async fn record_validation_issue<'e, E>(
+    executor: E,
+    dataset_id: i64,
+    issue_type: &str,
+    severity: &str,
+    message: &str,
+    metadata: &serde_json::Value,
+) -> Result<(), sqlx::Error>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    sqlx::query(
+        "INSERT INTO validation_issues (dataset_id, issue_type, severity, message, metadata, resolved, created_at)
+         SELECT ?, ?, ?, ?, ?, 0, datetime('now')
+         WHERE NOT EXISTS (
+             SELECT 1 FROM validation_issues
+             WHERE dataset_id = ? AND issue_type = ? AND message = ? AND NOT IFNULL(resolved, 0)
+         )",
+    )
+    .bind(dataset_id)
+    .bind(issue_type)
+    .bind(severity)
+    .bind(message)
+    .bind(metadata.to_string())
+    .bind(dataset_id)
+    .bind(issue_type)
+    .bind(message)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
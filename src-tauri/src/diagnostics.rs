@@ -0,0 +1,231 @@
+use crate::db::audit::record_audit;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+pub const REPAIR_AUDIT_ACTION: &str = "database_repair";
+
+/// Scores whose employee is not linked to the score's dataset.
+const UNLINKED_SCORES: &str = "FROM scores s
+     WHERE NOT EXISTS (
+         SELECT 1 FROM dataset_employees de
+         WHERE de.dataset_id = s.dataset_id AND de.employee_id = s.employee_id
+     )";
+/// Summaries left behind by a deleted employee.
+const ORPHANED_SUMMARIES: &str = "FROM summaries su
+     WHERE NOT EXISTS (SELECT 1 FROM employees e WHERE e.id = su.employee_id)";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrphanCounts {
+    pub unlinked_scores: i64,
+    pub orphaned_summaries: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableRowCount {
+    pub table: String,
+    pub rows: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticsReport {
+    pub checked_at: DateTime<Utc>,
+    /// Database file plus its write-ahead log; `None` for in-memory databases.
+    pub file_size_bytes: Option<u64>,
+    /// Latest applied migration.
+    pub schema_version: Option<i64>,
+    pub table_row_counts: Vec<TableRowCount>,
+    pub orphans: OrphanCounts,
+    /// Output of `PRAGMA integrity_check`; a single `ok` means no problems were found.
+    pub integrity_check: Vec<String>,
+    /// No orphans and a clean integrity check.
+    pub healthy: bool,
+    /// Rows removed by the repair step when it was requested; the other fields are measured after it.
+    pub repaired: Option<OrphanCounts>,
+}
+
+async fn database_file_size(pool: &SqlitePool) -> Result<Option<u64>, sqlx::Error> {
+    let (_, _, file): (i64, String, String) = sqlx::query_as("PRAGMA database_list")
+        .fetch_one(pool)
+        .await?;
+    if file.is_empty() {
+        return Ok(None);
+    }
+    let size = |path: &str| std::fs::metadata(path).map(|m| m.len()).ok();
+    Ok(size(&file).map(|main| main + size(&format!("{}-wal", file)).unwrap_or(0)))
+}
+
+async fn table_row_counts(pool: &SqlitePool) -> Result<Vec<TableRowCount>, sqlx::Error> {
+    let tables: Vec<String> = sqlx::query_scalar(
+        "SELECT name FROM sqlite_master
+         WHERE type = 'table'
+           AND name NOT LIKE 'sqlite_%'
+           AND name NOT LIKE 'search_index_%'
+           AND name <> '_sqlx_migrations'
+         ORDER BY name",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut counts = Vec::with_capacity(tables.len());
+    for table in tables {
+        let rows: i64 = sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM \"{}\"",
+            table.replace('"', "\"\"")
+        ))
+        .fetch_one(pool)
+        .await?;
+        counts.push(TableRowCount { table, rows });
+    }
+    Ok(counts)
+}
+
+async fn orphan_counts(pool: &SqlitePool) -> Result<OrphanCounts, sqlx::Error> {
+    Ok(OrphanCounts {
+        unlinked_scores: sqlx::query_scalar(&format!("SELECT COUNT(*) {}", UNLINKED_SCORES))
+            .fetch_one(pool)
+            .await?,
+        orphaned_summaries: sqlx::query_scalar(&format!("SELECT COUNT(*) {}", ORPHANED_SUMMARIES))
+            .fetch_one(pool)
+            .await?,
+    })
+}
+
+/// Deletes unlinked scores (their rater entries follow) and orphaned summaries in one
+/// transaction, recording what was removed in the audit log.
+async fn remove_orphans(pool: &SqlitePool) -> Result<OrphanCounts, String> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start repair: {}", e))?;
+
+    let unlinked_scores = sqlx::query(&format!(
+        "DELETE FROM scores WHERE id IN (SELECT s.id {})",
+        UNLINKED_SCORES
+    ))
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to remove unlinked scores: {}", e))?
+    .rows_affected() as i64;
+    let orphaned_summaries = sqlx::query(&format!(
+        "DELETE FROM summaries WHERE id IN (SELECT su.id {})",
+        ORPHANED_SUMMARIES
+    ))
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| format!("Failed to remove orphaned summaries: {}", e))?
+    .rows_affected() as i64;
+
+    let removed = OrphanCounts {
+        unlinked_scores,
+        orphaned_summaries,
+    };
+    if removed != OrphanCounts::default() {
+        let details = serde_json::to_value(&removed)
+            .map_err(|e| format!("Failed to serialize repair result: {}", e))?;
+        record_audit(&mut *tx, REPAIR_AUDIT_ACTION, None, &details)
+            .await
+            .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    }
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit repair: {}", e))?;
+
+    Ok(removed)
+}
+
+/// Inspects the database, first removing orphaned rows when `repair` is set.
+pub async fn run_diagnostics(pool: &SqlitePool, repair: bool) -> Result<DiagnosticsReport, String> {
+    let repaired = if repair {
+        Some(remove_orphans(pool).await?)
+    } else {
+        None
+    };
+
+    let file_size_bytes = database_file_size(pool)
+        .await
+        .map_err(|e| format!("Failed to locate database file: {}", e))?;
+    let schema_version: Option<i64> =
+        sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations")
+            .fetch_one(pool)
+            .await
+            .map_err(|e| format!("Failed to read schema version: {}", e))?;
+    let table_row_counts = table_row_counts(pool)
+        .await
+        .map_err(|e| format!("Failed to count table rows: {}", e))?;
+    let orphans = orphan_counts(pool)
+        .await
+        .map_err(|e| format!("Failed to count orphaned rows: {}", e))?;
+    let integrity_check: Vec<String> = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Integrity check failed: {}", e))?;
+
+    let healthy = orphans == OrphanCounts::default() && integrity_check == ["ok"];
+
+    Ok(DiagnosticsReport {
+        checked_at: Utc::now(),
+        file_size_bytes,
+        schema_version,
+        table_row_counts,
+        orphans,
+        integrity_check,
+        healthy,
+        repaired,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    #[tokio::test]
+    async fn test_diagnostics_reports_and_repairs_orphans() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO datasets (id, name, created_at, updated_at)
+             VALUES (1, 'Penilaian 2024', datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, created_at, updated_at)
+             VALUES (1, 'Budi', datetime('now'), datetime('now')),
+                    (2, 'Sari', datetime('now'), datetime('now'));
+             INSERT INTO dataset_employees (dataset_id, employee_id, created_at, updated_at)
+             VALUES (1, 1, datetime('now'), datetime('now'));
+             INSERT INTO competencies (id, name, display_order) VALUES (1, 'Kerjasama', 0);
+             INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, created_at)
+             VALUES (1, 1, 1, '80', 80.0, datetime('now')),
+                    (2, 1, 1, '70', 70.0, datetime('now'));
+             PRAGMA foreign_keys = OFF;
+             INSERT INTO summaries (employee_id, dataset_id, content) VALUES (99, 1, 'Ringkasan');
+             PRAGMA foreign_keys = ON;",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let report = run_diagnostics(&pool, false).await.unwrap();
+        assert_eq!(
+            report.orphans,
+            OrphanCounts {
+                unlinked_scores: 1,
+                orphaned_summaries: 1,
+            }
+        );
+        assert_eq!(report.integrity_check, vec!["ok".to_string()]);
+        assert!(report.schema_version.is_some());
+        assert!(report
+            .table_row_counts
+            .iter()
+            .any(|t| t.table == "scores" && t.rows == 2));
+        assert!(!report.healthy);
+
+        let repaired = run_diagnostics(&pool, true).await.unwrap();
+        assert_eq!(repaired.repaired, Some(report.orphans));
+        assert!(repaired.healthy);
+        let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM scores")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, 1);
+    }
+}
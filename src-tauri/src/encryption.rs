@@ -0,0 +1,330 @@
+use crate::backup::{backup_directory, list_backups};
+use crate::db::audit::{record_audit, record_task_failure};
+use crate::db::Database;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection, SqlitePool};
+use sqlx::{ConnectOptions, Connection};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// First bytes of every unencrypted SQLite file; SQLCipher files start with random salt.
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+const MIN_PASSPHRASE_CHARS: usize = 8;
+/// Attached name of the re-encrypted copy while it is being written.
+const REKEY_SCHEMA: &str = "rekeyed";
+/// Attached name of a backup while its re-encrypted copy is written.
+const BACKUP_SCHEMA: &str = "previous";
+/// Extension of a re-encrypted copy until it replaces its original.
+const REKEY_EXTENSION: &str = "db.rekey";
+const REKEY_TASK: &str = "database_rekey";
+
+/// Where the database lives; managed from startup so the unlock commands work before
+/// `AppState` exists.
+pub struct DatabaseLocation {
+    pub path: PathBuf,
+}
+
+/// Whether the file at `path` is SQLCipher-encrypted. A missing file is not.
+pub fn is_encrypted(path: &Path) -> Result<bool, String> {
+    if !path.exists() {
+        return Ok(false);
+    }
+    let mut header = [0u8; 16];
+    let read = std::fs::File::open(path)
+        .and_then(|mut file| file.read(&mut header))
+        .map_err(|e| format!("Failed to read database file: {}", e))?;
+    Ok(read > 0 && &header != SQLITE_HEADER)
+}
+
+/// `PRAGMA key` value for `passphrase`; pragmas take no bound parameters, so quote it.
+pub(crate) fn key_pragma(passphrase: &str) -> String {
+    format!("'{}'", passphrase.replace('\'', "''"))
+}
+
+/// Opens the file with `passphrase` and reads the schema, which fails on a wrong key.
+pub async fn verify_passphrase(path: &Path, passphrase: &str) -> Result<(), String> {
+    let mut conn = SqliteConnectOptions::new()
+        .filename(path)
+        .pragma("key", key_pragma(passphrase))
+        .connect()
+        .await
+        .map_err(|e| format!("Failed to open database: {}", e))?;
+    let result = sqlx::query("SELECT COUNT(*) FROM sqlite_master")
+        .execute(&mut conn)
+        .await;
+    let _ = conn.close().await;
+    result
+        .map(|_| ())
+        .map_err(|_| "Incorrect passphrase".to_string())
+}
+
+/// Rewrites the database and its backups encrypted with `new_passphrase`, or as plain
+/// SQLite when `None`, and swaps them in for the originals. The pool is closed afterwards, so the caller
+/// has to reopen the database, also when the swap itself fails: the original file is then
+/// left in place and the failure is recorded in it.
+pub async fn rekey_database(
+    pool: &SqlitePool,
+    path: &Path,
+    current_passphrase: Option<&str>,
+    new_passphrase: Option<&str>,
+) -> Result<(), String> {
+    let current_key = if is_encrypted(path)? {
+        let current =
+            current_passphrase.ok_or_else(|| "Enter the current passphrase".to_string())?;
+        verify_passphrase(path, current).await?;
+        Some(current)
+    } else {
+        None
+    };
+    if new_passphrase.is_some_and(|passphrase| passphrase.chars().count() < MIN_PASSPHRASE_CHARS) {
+        return Err(format!(
+            "The passphrase needs at least {} characters",
+            MIN_PASSPHRASE_CHARS
+        ));
+    }
+
+    // Recorded first so the entry is part of the exported copy
+    record_audit(
+        pool,
+        "database_passphrase_changed",
+        None,
+        &serde_json::json!({ "encrypted": new_passphrase.is_some() }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+
+    let target = path.with_extension(REKEY_EXTENSION);
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    export_rekeyed(&mut conn, &target, new_passphrase).await?;
+    // Fold the write-ahead log back in so no -wal file outlives the file it belongs to
+    let _ = sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+        .execute(&mut *conn)
+        .await;
+    drop(conn);
+    // Backups follow the database, so none stays readable with an old key or none
+    let backups = match rekey_backups(pool, &target, current_key, new_passphrase).await {
+        Ok(backups) => backups,
+        Err(error) => {
+            let _ = std::fs::remove_file(&target);
+            return Err(error);
+        }
+    };
+
+    pool.close().await;
+    if let Err(e) = std::fs::rename(&target, path) {
+        let _ = std::fs::remove_file(&target);
+        for (copy, _) in &backups {
+            let _ = std::fs::remove_file(copy);
+        }
+        let error = format!("Failed to replace database: {}", e);
+        if let Ok(original) = Database::new(path.to_path_buf(), current_key).await {
+            record_task_failure(&original.pool, REKEY_TASK, &error).await;
+            original.pool.close().await;
+        }
+        return Err(error);
+    }
+    for (copy, backup) in &backups {
+        std::fs::rename(copy, backup).map_err(|e| {
+            let _ = std::fs::remove_file(copy);
+            format!("Failed to replace backup {}: {}", backup.display(), e)
+        })?;
+    }
+    Ok(())
+}
+
+/// Exports the main database of `conn` to `target`, encrypted with `new_passphrase` or
+/// plain when `None`. A failed export leaves no file behind.
+async fn export_rekeyed(
+    conn: &mut SqliteConnection,
+    target: &Path,
+    new_passphrase: Option<&str>,
+) -> Result<(), String> {
+    let _ = std::fs::remove_file(target);
+    sqlx::query(&format!("ATTACH DATABASE ? AS {} KEY ?", REKEY_SCHEMA))
+        .bind(target.to_string_lossy().to_string())
+        .bind(new_passphrase.unwrap_or_default())
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to create encrypted copy: {}", e))?;
+    let exported = sqlx::query(&format!("SELECT sqlcipher_export('{}')", REKEY_SCHEMA))
+        .execute(&mut *conn)
+        .await;
+    sqlx::query(&format!("DETACH DATABASE {}", REKEY_SCHEMA))
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to close encrypted copy: {}", e))?;
+    if let Err(e) = exported {
+        let _ = std::fs::remove_file(target);
+        return Err(format!("Failed to encrypt database: {}", e));
+    }
+    Ok(())
+}
+
+/// Writes `backup`, attached with `current_key` when it is encrypted, to `copy` through
+/// `conn`, which is open on the re-encrypted database. Without a key clause the copy takes
+/// that database's key and salt, as a `VACUUM INTO` backup does, so a restore can attach it.
+async fn rekey_backup(
+    conn: &mut SqliteConnection,
+    backup: &Path,
+    copy: &Path,
+    current_key: Option<&str>,
+    encrypted: bool,
+) -> Result<(), String> {
+    let _ = std::fs::remove_file(copy);
+    let backup_key = match is_encrypted(backup)? {
+        true => current_key.unwrap_or_default(),
+        false => "",
+    };
+    sqlx::query(&format!("ATTACH DATABASE ? AS {} KEY ?", BACKUP_SCHEMA))
+        .bind(backup.to_string_lossy().to_string())
+        .bind(backup_key)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to open backup: {}", e))?;
+    let copy_key = if encrypted { "" } else { " KEY ''" };
+    let exported = match sqlx::query(&format!(
+        "ATTACH DATABASE ? AS {}{}",
+        REKEY_SCHEMA, copy_key
+    ))
+    .bind(copy.to_string_lossy().to_string())
+    .execute(&mut *conn)
+    .await
+    {
+        Ok(_) => {
+            let exported = sqlx::query(&format!(
+                "SELECT sqlcipher_export('{}', '{}')",
+                REKEY_SCHEMA, BACKUP_SCHEMA
+            ))
+            .execute(&mut *conn)
+            .await;
+            let _ = sqlx::query(&format!("DETACH DATABASE {}", REKEY_SCHEMA))
+                .execute(&mut *conn)
+                .await;
+            exported
+        }
+        Err(e) => Err(e),
+    };
+    let _ = sqlx::query(&format!("DETACH DATABASE {}", BACKUP_SCHEMA))
+        .execute(&mut *conn)
+        .await;
+    exported.map(|_| ()).map_err(|e| {
+        let _ = std::fs::remove_file(copy);
+        format!("Failed to encrypt backup: {}", e)
+    })
+}
+
+/// Writes a copy of every backup keyed like the re-encrypted database at `target` next
+/// to it and returns each copy with the backup it replaces once the database is swapped.
+async fn rekey_backups(
+    pool: &SqlitePool,
+    target: &Path,
+    current_key: Option<&str>,
+    new_passphrase: Option<&str>,
+) -> Result<Vec<(PathBuf, PathBuf)>, String> {
+    let directory = backup_directory(pool).await?;
+    let backups = list_backups(pool).await?;
+    // Attached files open with the connection's flags, so it must be allowed to create one
+    let mut options = SqliteConnectOptions::new()
+        .filename(target)
+        .create_if_missing(true);
+    if let Some(passphrase) = new_passphrase {
+        options = options.pragma("key", key_pragma(passphrase));
+    }
+    let mut conn = options
+        .connect()
+        .await
+        .map_err(|e| format!("Failed to open encrypted copy: {}", e))?;
+    let mut copies: Vec<(PathBuf, PathBuf)> = Vec::new();
+    for backup in backups {
+        let path = directory.join(&backup.file_name);
+        let copy = path.with_extension(REKEY_EXTENSION);
+        let rekeyed = rekey_backup(
+            &mut conn,
+            &path,
+            &copy,
+            current_key,
+            new_passphrase.is_some(),
+        )
+        .await;
+        if let Err(error) = rekeyed {
+            for (copy, _) in &copies {
+                let _ = std::fs::remove_file(copy);
+            }
+            let _ = conn.close().await;
+            return Err(format!("{}: {}", backup.file_name, error));
+        }
+        copies.push((copy, path));
+    }
+    let _ = conn.close().await;
+    Ok(copies)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backup::{create_backup, restore_backup};
+
+    #[tokio::test]
+    async fn test_is_encrypted_reads_file_header() {
+        let directory = tempfile::tempdir().unwrap();
+        let plain = directory.path().join("epa.db");
+        assert!(!is_encrypted(&plain).unwrap());
+
+        let database = Database::new(plain.clone(), None).await.unwrap();
+        database.pool.close().await;
+        assert!(!is_encrypted(&plain).unwrap());
+
+        let scrambled = directory.path().join("scrambled.db");
+        std::fs::write(&scrambled, [0x5au8; 64]).unwrap();
+        assert!(is_encrypted(&scrambled).unwrap());
+        assert_eq!(key_pragma("it's"), "'it''s'");
+    }
+
+    #[tokio::test]
+    async fn test_backup_taken_before_a_rekey_still_restores() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("epa.db");
+        let database = Database::new(path.clone(), None).await.unwrap();
+        sqlx::query("INSERT INTO employees (name) VALUES ('Budi Santoso')")
+            .execute(&database.pool)
+            .await
+            .unwrap();
+        let backup = create_backup(&database.pool, "manual").await.unwrap();
+        let file_name = backup.file_name().unwrap().to_string_lossy().to_string();
+        database.pool.close().await;
+
+        // Encrypted, then encrypted again under a new passphrase
+        let mut current = None;
+        for passphrase in ["rahasia-pertama", "rahasia-kedua"] {
+            rekey_database(
+                &database_pool(&path, current).await,
+                &path,
+                current,
+                Some(passphrase),
+            )
+            .await
+            .unwrap();
+            assert!(is_encrypted(&backup).unwrap());
+            current = Some(passphrase);
+        }
+
+        let pool = database_pool(&path, current).await;
+        sqlx::query("DELETE FROM employees")
+            .execute(&pool)
+            .await
+            .unwrap();
+        restore_backup(&pool, &file_name).await.unwrap();
+        let names: Vec<String> = sqlx::query_scalar("SELECT name FROM employees")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(names, vec!["Budi Santoso".to_string()]);
+        pool.close().await;
+    }
+
+    async fn database_pool(path: &Path, passphrase: Option<&str>) -> SqlitePool {
+        Database::new(path.to_path_buf(), passphrase)
+            .await
+            .unwrap()
+            .pool
+    }
+}
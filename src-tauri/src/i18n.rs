@@ -0,0 +1,383 @@
+//! Text of generated documents (summaries, report and recap PDFs) in each supported language.
+//!
+//! Templates mark their arguments with `{}`, filled in order by `fill`.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Write};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    Id,
+    /// For provincial reporting
+    En,
+}
+
+impl Locale {
+    pub fn messages(self) -> &'static Messages {
+        match self {
+            Self::Id => &INDONESIAN,
+            Self::En => &ENGLISH,
+        }
+    }
+
+    /// Language tag, for the `lang` attribute of HTML exports
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::En => "en",
+        }
+    }
+}
+
+/// Replaces each `{}` of `template` with the next of `args`.
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut text = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut parts = template.split("{}");
+    if let Some(first) = parts.next() {
+        text.push_str(first);
+    }
+    for part in parts {
+        if let Some(arg) = args.next() {
+            let _ = write!(text, "{}", arg);
+        }
+        text.push_str(part);
+    }
+    text
+}
+
+pub struct Messages {
+    /// Scores are printed as `80,00` in Indonesian and `80.00` in English
+    pub decimal_comma: bool,
+    /// Standard predicates and their translation; configured labels not listed here are
+    /// printed as they are
+    pub predicates: &'static [(&'static str, &'static str)],
+
+    // Summaries
+    pub summary_title: &'static str,
+    pub continued: &'static str,
+    pub continued_page: &'static str,
+    pub metadata_nip: &'static str,
+    pub metadata_jabatan: &'static str,
+    pub metadata_golongan: &'static str,
+    pub metadata_average: &'static str,
+    pub summary_intro: &'static str,
+    pub summary_role: &'static str,
+    pub summary_role_with_sub: &'static str,
+    pub summary_role_default: &'static str,
+    pub summary_nip: &'static str,
+    /// Overall verdicts for averages of at least 3.5, 3.0, 2.5 and below
+    pub summary_levels: [&'static str; 4],
+    pub summary_strengths: &'static str,
+    pub summary_no_strengths: &'static str,
+    pub summary_gaps: &'static str,
+    pub summary_no_gaps: &'static str,
+    pub summary_highlight_range: &'static str,
+    pub summary_highlight_top: &'static str,
+    pub summary_no_highlight: &'static str,
+    pub summary_closing: &'static str,
+    pub ai_system_prompt: &'static str,
+    pub ai_position: &'static str,
+    pub ai_average: &'static str,
+    pub ai_scores: &'static str,
+    pub ai_strengths: &'static str,
+    pub ai_gaps: &'static str,
+
+    // Employee report and ranking recap
+    pub default_period: &'static str,
+    pub cover_title: &'static str,
+    pub cover_intro: &'static str,
+    pub criteria_headings: [&'static str; 3],
+    /// Perilaku kerja, kualitas kinerja and penilaian pimpinan
+    pub section_names: [&'static str; 3],
+    pub total: &'static str,
+    pub rating_bands_intro: &'static str,
+    pub conclusion_subject: &'static str,
+    pub conclusion: &'static str,
+    pub conclusion_rating: &'static str,
+    pub worksheet_title: &'static str,
+    pub worksheet_eselon: &'static str,
+    pub worksheet_staff: &'static str,
+    pub year_line: &'static str,
+    pub worksheet_headings: [&'static str; 4],
+    pub final_score: &'static str,
+    pub unmapped_note: &'static str,
+    pub appendix_title: &'static str,
+    pub appendix_title_continued: &'static str,
+    pub ranking_title: &'static str,
+    pub ranking_headings: [&'static str; 9],
+
+    // Dataset analysis workbook
+    /// Competency averages, score distribution and Eselon against Staff
+    pub analysis_sheets: [&'static str; 3],
+    pub analysis_competency_headings: [&'static str; 4],
+    pub analysis_distribution_headings: [&'static str; 2],
+    /// Metric column and employee count row of the position sheet
+    pub analysis_position_headings: [&'static str; 2],
+    pub analysis_positions: [&'static str; 2],
+    /// Average score, the three section subtotals and the weighted total
+    pub analysis_metrics: [&'static str; 5],
+    /// Charts of the competency and distribution sheets; the position chart takes its
+    /// sheet's name
+    pub analysis_chart_titles: [&'static str; 2],
+}
+
+impl Messages {
+    /// Score with two decimals and the language's decimal separator.
+    pub fn number(&self, value: f64) -> String {
+        let text = format!("{:.2}", value);
+        if self.decimal_comma {
+            text.replace('.', ",")
+        } else {
+            text
+        }
+    }
+
+    /// `label` translated when it is one of the standard predicates.
+    pub fn predicate(&self, label: &str) -> String {
+        self.predicates
+            .iter()
+            .find(|(source, _)| source.eq_ignore_ascii_case(label.trim()))
+            .map(|(_, translated)| translated.to_string())
+            .unwrap_or_else(|| label.to_string())
+    }
+}
+
+static INDONESIAN: Messages = Messages {
+    decimal_comma: true,
+    predicates: &[],
+
+    summary_title: "Ringkasan Kinerja - {}",
+    continued: "{} (lanjutan)",
+    continued_page: "{} (lanjutan {})",
+    metadata_nip: "NIP: {}",
+    metadata_jabatan: "Jabatan: {}",
+    metadata_golongan: "Golongan: {}",
+    metadata_average: "Rata-rata skor: {}",
+    summary_intro: "{} saat ini {}{}. Rata-rata pencapaian dari {} kompetensi yang dinilai adalah {}.",
+    summary_role: "berperan sebagai {}",
+    summary_role_with_sub: "berperan sebagai {} ({})",
+    summary_role_default: "berperan sebagai karyawan",
+    summary_nip: " dengan NIP {}",
+    summary_levels: [
+        "Secara keseluruhan performa berada pada kategori sangat baik dan konsisten di atas ekspektasi organisasi.",
+        "Secara keseluruhan performa berada pada kategori baik dengan hasil yang stabil dan memenuhi target utama.",
+        "Rata-rata skor menunjukkan performa cukup dengan beberapa area yang masih memerlukan peningkatan.",
+        "Performa saat ini berada di bawah target organisasi sehingga dibutuhkan rencana pengembangan terstruktur.",
+    ],
+    summary_strengths: "Kekuatan utama saat ini mencakup {}.",
+    summary_no_strengths: "Belum ada kompetensi dengan skor numerik tercatat sebagai kekuatan utama.",
+    summary_gaps: "Area yang memerlukan perhatian lanjutan meliputi {}.",
+    summary_no_gaps: "Tidak ada area pengembangan yang tercatat karena nilai numerik belum lengkap.",
+    summary_highlight_range: "Skor tertinggi berada pada kompetensi {} dengan nilai {}, sementara skor terendah tercatat pada {} dengan nilai {}.",
+    summary_highlight_top: "Kompetensi dengan capaian tertinggi adalah {} dengan nilai {}.",
+    summary_no_highlight: "Belum tersedia skor numerik untuk mendeskripsikan capaian kompetensi secara detail.",
+    summary_closing: "Rekomendasikan tindak lanjut berupa sesi umpan balik terjadwal, pemantauan target triwulanan, serta dukungan pelatihan yang relevan agar progres dapat diakselerasi.",
+    ai_system_prompt: "Anda membantu bagian kepegawaian menulis ringkasan kinerja \
+pegawai dalam Bahasa Indonesia formal, tiga sampai lima paragraf singkat. Sebut pegawai hanya \
+sebagai [PEGAWAI]. Gunakan hanya data yang diberikan dan jangan mengarang angka, nama, atau jabatan.",
+    ai_position: "Jenis jabatan: {}",
+    ai_average: "Rata-rata skor: {} dari {} kompetensi",
+    ai_scores: "Skor per kompetensi:",
+    ai_strengths: "Kekuatan: {}",
+    ai_gaps: "Area pengembangan: {}",
+
+    default_period: "Semester I",
+    cover_title: "HASIL PENILAIAN KINERJA PEGAWAI {} {} TAHUN {}",
+    cover_intro: "Penilaian Kinerja oleh seluruh pegawai {} sampai dengan {} Tahun {} berdasarkan dari Kualitas Kinerja dengan melalui form yang disebarkan tiap akhir semester, dengan kriteria penilaian sebagai berikut :",
+    criteria_headings: ["NO.", "KRITERIA", "BOBOT"],
+    section_names: ["PERILAKU KERJA", "KUALITAS KINERJA", "PENILAIAN PIMPINAN"],
+    total: "TOTAL",
+    rating_bands_intro: "Predikat skor akhir penilaian Penilaian Pegawai dengan kinerja terbaik sebagai berikut :",
+    conclusion_subject: "Pegawai",
+    conclusion: "Berdasarkan hasil penilaian, dapat disampaikan bahwa capaian kinerja {} {} memperoleh",
+    conclusion_rating: "predikat \"{}\" dengan nilai {}.",
+    worksheet_title: "KERTAS KERJA EVALUASI PENGUKURAN KINERJA {}",
+    worksheet_eselon: "ESELON III",
+    worksheet_staff: "STAFF",
+    year_line: "TAHUN {}",
+    worksheet_headings: ["NO.", "KOMPONEN / KRITERIA", "BOBOT", "NILAI"],
+    final_score: "NILAI AKHIR",
+    unmapped_note: "Catatan: kompetensi berikut tidak dipetakan ke parameter penilaian dan tidak dihitung: {}",
+    appendix_title: "LAMPIRAN: CATATAN PENILAIAN",
+    appendix_title_continued: "LAMPIRAN: CATATAN PENILAIAN (LANJUTAN)",
+    ranking_title: "REKAPITULASI NILAI EVALUASI KINERJA PEGAWAI {} {} TAHUN {}",
+    ranking_headings: [
+        "NO.",
+        "NAMA",
+        "NIP",
+        "JABATAN",
+        "PERILAKU KERJA",
+        "KUALITAS KINERJA",
+        "PENILAIAN PIMPINAN",
+        "NILAI AKHIR",
+        "PREDIKAT",
+    ],
+
+    analysis_sheets: ["Kompetensi", "Distribusi", "Eselon vs Staff"],
+    analysis_competency_headings: ["Kompetensi", "Rata-rata", "Simpangan Baku", "Pegawai"],
+    analysis_distribution_headings: ["Rentang", "Jumlah Skor"],
+    analysis_position_headings: ["Ukuran", "Pegawai"],
+    analysis_positions: ["Eselon", "Staff"],
+    analysis_metrics: [
+        "Rata-rata Skor",
+        "Perilaku Kerja",
+        "Kualitas Kinerja",
+        "Penilaian Pimpinan",
+        "Nilai Akhir",
+    ],
+    analysis_chart_titles: ["Rata-rata Skor per Kompetensi", "Distribusi Skor"],
+};
+
+static ENGLISH: Messages = Messages {
+    decimal_comma: false,
+    predicates: &[
+        ("Sangat Baik", "Very Good"),
+        ("Baik", "Good"),
+        ("Cukup", "Fair"),
+        ("Kurang Baik", "Needs Improvement"),
+        ("Kurang", "Poor"),
+    ],
+
+    summary_title: "Performance Summary - {}",
+    continued: "{} (continued)",
+    continued_page: "{} (continued {})",
+    metadata_nip: "NIP: {}",
+    metadata_jabatan: "Position: {}",
+    metadata_golongan: "Grade: {}",
+    metadata_average: "Average score: {}",
+    summary_intro: "{} currently {}{}. The average achievement across the {} assessed competencies is {}.",
+    summary_role: "serves as {}",
+    summary_role_with_sub: "serves as {} ({})",
+    summary_role_default: "serves as an employee",
+    summary_nip: " with NIP {}",
+    summary_levels: [
+        "Overall performance is in the very good category and consistently above the organization's expectations.",
+        "Overall performance is in the good category, with stable results that meet the main targets.",
+        "The average score shows adequate performance, with some areas that still need improvement.",
+        "Performance is currently below the organization's targets, so a structured development plan is needed.",
+    ],
+    summary_strengths: "Current key strengths include {}.",
+    summary_no_strengths: "No competency with a numeric score has been recorded as a key strength yet.",
+    summary_gaps: "Areas that need further attention include {}.",
+    summary_no_gaps: "No development areas are recorded because the numeric scores are incomplete.",
+    summary_highlight_range: "The highest score is in {} at {}, while the lowest is recorded in {} at {}.",
+    summary_highlight_top: "The competency with the highest achievement is {} at {}.",
+    summary_no_highlight: "No numeric scores are available yet to describe competency achievement in detail.",
+    summary_closing: "Recommended follow-up: scheduled feedback sessions, quarterly target monitoring and relevant training support to accelerate progress.",
+    ai_system_prompt: "You help the personnel department write employee performance \
+summaries in formal English, three to five short paragraphs. Refer to the employee only as \
+[PEGAWAI]. Use only the data provided and do not invent figures, names or positions.",
+    ai_position: "Position type: {}",
+    ai_average: "Average score: {} across {} competencies",
+    ai_scores: "Score per competency:",
+    ai_strengths: "Strengths: {}",
+    ai_gaps: "Development areas: {}",
+
+    default_period: "Semester I",
+    cover_title: "EMPLOYEE PERFORMANCE ASSESSMENT RESULTS {} {} YEAR {}",
+    cover_intro: "Performance assessment by all employees of {} up to {} of {}, based on work quality through forms distributed at the end of each semester, with the following assessment criteria:",
+    criteria_headings: ["NO.", "CRITERIA", "WEIGHT"],
+    section_names: ["WORK BEHAVIOUR", "WORK QUALITY", "LEADERSHIP ASSESSMENT"],
+    total: "TOTAL",
+    rating_bands_intro: "Predicates of the final assessment score, from the best performance down:",
+    conclusion_subject: "Employee",
+    conclusion: "Based on the assessment results, the performance of {} {} has earned",
+    conclusion_rating: "the predicate \"{}\" with a score of {}.",
+    worksheet_title: "PERFORMANCE MEASUREMENT EVALUATION WORKSHEET {}",
+    worksheet_eselon: "ECHELON III",
+    worksheet_staff: "STAFF",
+    year_line: "YEAR {}",
+    worksheet_headings: ["NO.", "COMPONENT / CRITERIA", "WEIGHT", "SCORE"],
+    final_score: "FINAL SCORE",
+    unmapped_note: "Note: the following competencies are not mapped to an assessment parameter and are not counted: {}",
+    appendix_title: "APPENDIX: ASSESSMENT NOTES",
+    appendix_title_continued: "APPENDIX: ASSESSMENT NOTES (CONTINUED)",
+    ranking_title: "EMPLOYEE PERFORMANCE EVALUATION RECAP {} {} YEAR {}",
+    ranking_headings: [
+        "NO.",
+        "NAME",
+        "NIP",
+        "POSITION",
+        "WORK BEHAVIOUR",
+        "WORK QUALITY",
+        "LEADERSHIP ASSESSMENT",
+        "FINAL SCORE",
+        "PREDICATE",
+    ],
+
+    analysis_sheets: ["Competencies", "Distribution", "Echelon vs Staff"],
+    analysis_competency_headings: ["Competency", "Average", "Std Dev", "Employees"],
+    analysis_distribution_headings: ["Range", "Scores"],
+    analysis_position_headings: ["Metric", "Employees"],
+    analysis_positions: ["Echelon", "Staff"],
+    analysis_metrics: [
+        "Average Score",
+        "Work Behaviour",
+        "Work Quality",
+        "Leadership Assessment",
+        "Weighted Total",
+    ],
+    analysis_chart_titles: ["Average Score per Competency", "Score Distribution"],
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every template of a message, so both catalogs can be compared placeholder by placeholder.
+    fn templates(messages: &Messages) -> Vec<&'static str> {
+        let mut templates = vec![
+            messages.summary_title,
+            messages.continued,
+            messages.continued_page,
+            messages.metadata_nip,
+            messages.metadata_jabatan,
+            messages.metadata_golongan,
+            messages.metadata_average,
+            messages.summary_intro,
+            messages.summary_role,
+            messages.summary_role_with_sub,
+            messages.summary_nip,
+            messages.summary_strengths,
+            messages.summary_gaps,
+            messages.summary_highlight_range,
+            messages.summary_highlight_top,
+            messages.ai_position,
+            messages.ai_average,
+            messages.ai_strengths,
+            messages.ai_gaps,
+            messages.cover_title,
+            messages.cover_intro,
+            messages.conclusion,
+            messages.conclusion_rating,
+            messages.worksheet_title,
+            messages.year_line,
+            messages.unmapped_note,
+            messages.ranking_title,
+        ];
+        templates.extend(messages.summary_levels);
+        templates
+    }
+
+    #[test]
+    fn test_catalogs_take_the_same_arguments() {
+        let indonesian = templates(Locale::Id.messages());
+        let english = templates(Locale::En.messages());
+        for (id, en) in indonesian.iter().zip(&english) {
+            assert_eq!(id.matches("{}").count(), en.matches("{}").count(), "{}", en);
+        }
+
+        assert_eq!(
+            fill(Locale::En.messages().summary_title, &[&"Budi"]),
+            "Performance Summary - Budi"
+        );
+        assert_eq!(Locale::Id.messages().number(79.5), "79,50");
+        assert_eq!(Locale::En.messages().number(79.5), "79.50");
+        assert_eq!(Locale::En.messages().predicate("sangat baik"), "Very Good");
+        assert_eq!(Locale::En.messages().predicate("Istimewa"), "Istimewa");
+        assert_eq!(Locale::Id.messages().predicate("Baik"), "Baik");
+    }
+}
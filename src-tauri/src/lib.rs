@@ -1,11 +1,56 @@
+mod api_server;
+mod attachments;
+mod auth;
+mod backup;
 mod commands;
 mod csv_parser;
+mod data_quality;
+mod dataset_lock;
 mod db;
+mod diagnostics;
+mod encryption;
+mod i18n;
+mod maintenance;
+mod pdf;
+mod photos;
+mod retention;
+mod scoring;
+mod secrets;
+mod ui_config;
+mod webhooks;
+mod workspace;
 
 use tauri::Manager;
 
 pub struct AppState {
     pub pool: sqlx::SqlitePool,
+    pub dataset_locks: dataset_lock::DatasetLocks,
+    pub session: auth::Session,
+    /// Cancel tokens of the running dataset exports; see `cancel_export`.
+    pub export_cancels: commands::export::ExportCancels,
+}
+
+impl AppState {
+    /// Errors unless the signed-in user has at least `role`; see `auth::require_role`.
+    pub async fn require_role(&self, role: auth::Role) -> Result<(), String> {
+        auth::require_role(&self.pool, &self.session, role).await
+    }
+}
+
+/// Starts the background tasks and makes the database available to commands.
+pub(crate) fn start_workspace(app: &tauri::AppHandle, pool: sqlx::SqlitePool) {
+    let dataset_locks = dataset_lock::DatasetLocks::default();
+    maintenance::spawn_maintenance_task(pool.clone(), dataset_locks.clone());
+    backup::spawn_backup_task(pool.clone());
+    api_server::spawn_api_server(pool.clone());
+    webhooks::resume_deliveries(pool.clone());
+
+    app.manage(AppState {
+        pool,
+        dataset_locks,
+        session: Default::default(),
+        export_cancels: Default::default(),
+    });
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -20,15 +65,17 @@ pub fn run() {
                 .map_err(|e| -> Box<dyn std::error::Error> { Box::new(e) })?;
 
             let db_path = app_dir.join("epa.db");
+            app.manage(encryption::DatabaseLocation {
+                path: db_path.clone(),
+            });
 
-            let database = tauri::async_runtime::block_on(db::Database::new(db_path))
+            // An encrypted database stays closed until the user unlocks it
+            if encryption::is_encrypted(&db_path)? {
+                return Ok(());
+            }
+            let database = tauri::async_runtime::block_on(db::Database::new(db_path, None))
                 .map_err(|e| -> Box<dyn std::error::Error> { Box::new(e) })?;
-
-            let db::Database { pool } = database;
-
-            let state = AppState { pool };
-
-            app.manage(state);
+            start_workspace(app.handle(), database.pool);
 
             Ok(())
         })
@@ -36,32 +83,178 @@ pub fn run() {
             commands::csv::preview_csv,
             commands::csv::parse_employee_csv,
             commands::csv::parse_scores_csv,
+            commands::csv::parse_leadership_csv,
+            commands::csv::parse_employee_text,
+            commands::csv::parse_scores_text,
+            commands::csv::detect_format,
+            commands::csv::list_import_formats,
             commands::dataset::create_dataset,
             commands::dataset::list_datasets,
             commands::dataset::get_dataset,
             commands::dataset::delete_dataset,
+            commands::dataset::bulk_delete_datasets,
+            commands::dataset::bulk_archive_datasets,
+            commands::dataset::set_dataset_archived,
+            commands::dataset::add_dataset_tag,
+            commands::dataset::remove_dataset_tag,
             commands::dataset::update_dataset,
             commands::dataset::merge_datasets,
+            commands::dataset::clone_dataset,
+            commands::dataset::list_dataset_employees,
+            commands::dataset::diff_dataset_rosters,
+            commands::dataset::unlink_dataset_employee,
+            commands::dataset::list_dataset_notes,
+            commands::dataset::add_dataset_note,
+            commands::dataset::update_dataset_note,
+            commands::dataset::delete_dataset_note,
+            commands::employee::list_employee_notes,
+            commands::employee::add_employee_note,
+            commands::employee::update_employee_note,
+            commands::employee::delete_employee_note,
+            commands::dataset::list_validation_issues,
+            commands::dataset::resolve_validation_issue,
+            commands::dataset::reorder_dataset_competencies,
+            commands::dataset::set_dataset_deadline,
+            commands::dataset::get_dataset_quality,
+            commands::dataset::set_dataset_score_scale,
+            commands::dataset::set_dataset_score_aggregation,
+            commands::dataset::set_dataset_score_normalization,
+            commands::dataset::get_late_submissions,
+            commands::bundle::export_dataset_bundle,
+            commands::bundle::import_dataset_bundle,
+            commands::bundle::export_audit_snapshot,
+            commands::employee::get_data_quality_report,
+            commands::attachments::add_employee_attachment,
+            commands::attachments::list_employee_attachments,
+            commands::attachments::open_attachment,
+            commands::attachments::delete_attachment,
             commands::employee::list_all_employees,
             commands::employee::bulk_delete_employees,
+            commands::employee::preview_bulk_delete_employees,
             commands::employee::bulk_update_employees,
+            commands::employee::relink_employee_scores,
+            commands::employee::set_employee_position_type,
+            commands::employee::clear_employee_position_type,
+            commands::employee::set_employee_photo,
+            commands::employee::get_employee_photo,
             commands::import::import_employees,
             commands::import::import_performance_dataset,
+            commands::import::import_performance_dataset_xlsx,
             commands::import::import_performance_into_dataset,
+            commands::import::preview_append,
+            commands::import::preview_score_conflicts,
+            commands::import::list_import_runs,
             commands::import::append_dataset_employees,
             commands::import::get_default_rating_mappings,
             commands::import::validate_import_data,
+            commands::import_presets::list_import_presets,
+            commands::import_presets::get_import_preset,
+            commands::import_presets::save_import_preset,
+            commands::import_presets::delete_import_preset,
+            commands::score::create_score,
+            commands::score::update_score,
+            commands::score::delete_score,
+            commands::score::get_score_matrix,
+            commands::score::save_score_matrix,
+            commands::score::list_score_entries,
+            commands::competency::list_competency_categories,
+            commands::competency::create_competency_category,
+            commands::competency::update_competency_category,
+            commands::competency::delete_competency_category,
+            commands::competency::set_competency_category,
+            commands::competency::list_competency_aliases,
+            commands::competency::merge_competencies,
             commands::analytics::get_overview_stats,
             commands::analytics::get_dataset_stats,
             commands::analytics::list_employees,
+            commands::analytics::get_anonymity_floor,
+            commands::analytics::update_anonymity_floor,
             commands::analytics::get_employee_performance,
+            commands::analytics::rank_employees,
+            commands::analytics::get_group_stats,
             commands::analytics::compare_datasets,
+            commands::analytics::get_competency_correlations,
             commands::summaries::generate_employee_summary,
+            commands::summaries::generate_employee_summary_ai,
+            commands::summaries::get_ai_provider_settings,
+            commands::summaries::update_ai_provider_settings,
             commands::summaries::get_employee_summary,
             commands::summaries::save_employee_summary,
+            commands::summaries::list_summary_revisions,
+            commands::summaries::restore_summary_revision,
+            commands::summaries::lint_summary,
             commands::summaries::export_employee_summary_pdf,
+            commands::summaries::export_dataset_summaries_pdf,
             commands::export::export_dataset,
+            commands::export::cancel_export,
+            commands::export::export_employee_data_package,
+            commands::export::export_leaderboard_pdf,
+            commands::export::export_dataset_analysis_xlsx,
+            commands::export::export_dataset_comparison,
+            commands::export::export_dataset_archive,
             commands::report::export_employee_report_pdf,
+            commands::report::get_employee_report_preview,
+            commands::report::export_employee_report_html,
+            commands::report::export_dataset_ranking_pdf,
+            commands::report::get_report_settings,
+            commands::report::update_report_settings,
+            commands::report::set_report_logo,
+            commands::scoring::compute_weighted_score,
+            commands::scoring::simulate_score,
+            commands::scoring::get_position_keywords,
+            commands::scoring::update_position_keywords,
+            commands::scoring::export_weighting_config,
+            commands::scoring::import_weighting_config,
+            commands::scoring::list_competency_weights,
+            commands::scoring::set_competency_weight,
+            commands::scoring::delete_competency_weight,
+            commands::scoring::import_leadership_scores,
+            commands::scoring::set_attendance_parameter,
+            commands::attendance::import_attendance,
+            commands::attendance::list_employee_attendance,
+            commands::ui_config::get_ui_config,
+            commands::ui_config::update_ui_config,
+            commands::ui_config::get_dataset_rating_bands,
+            commands::ui_config::update_dataset_rating_bands,
+            commands::maintenance::get_maintenance_settings,
+            commands::maintenance::update_maintenance_settings,
+            commands::maintenance::run_database_maintenance,
+            commands::maintenance::run_diagnostics,
+            commands::maintenance::get_schema_info,
+            commands::maintenance::get_retention_settings,
+            commands::maintenance::update_retention_settings,
+            commands::maintenance::preview_purge,
+            commands::maintenance::apply_retention_policy,
+            commands::api_server::get_api_server_settings,
+            commands::api_server::update_api_server_settings,
+            commands::webhooks::list_webhooks,
+            commands::webhooks::register_webhook,
+            commands::webhooks::set_webhook_enabled,
+            commands::webhooks::delete_webhook,
+            commands::maintenance::list_audit_log,
+            commands::metrics::record_command_metrics,
+            commands::metrics::get_slow_commands,
+            commands::workspace::reset_workspace,
+            commands::workspace::seed_demo_data,
+            commands::workspace::generate_demo_data,
+            commands::encryption::get_database_lock_status,
+            commands::encryption::unlock_database,
+            commands::encryption::set_database_passphrase,
+            commands::auth::login,
+            commands::auth::logout,
+            commands::auth::get_current_user,
+            commands::auth::list_users,
+            commands::auth::create_user,
+            commands::auth::delete_user,
+            commands::backup::get_backup_settings,
+            commands::backup::update_backup_settings,
+            commands::backup::list_backups,
+            commands::backup::restore_backup,
+            commands::search::global_search,
+            commands::org_structure::import_org_structure,
+            commands::org_structure::list_jabatan_reference,
+            commands::org_structure::list_jabatan_issues,
+            commands::org_structure::apply_jabatan_suggestions,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
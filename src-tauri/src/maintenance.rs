@@ -0,0 +1,214 @@
+use crate::dataset_lock::DatasetLocks;
+use crate::db::audit::{record_audit, record_task_failure};
+use crate::db::settings::{get_setting_or, set_setting};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqliteConnection, SqlitePool};
+use std::time::{Duration, Instant};
+
+pub const MAINTENANCE_AUDIT_ACTION: &str = "database_maintenance";
+
+const ENABLED_KEY: &str = "maintenance.enabled";
+const INTERVAL_KEY: &str = "maintenance.interval_hours";
+const DEFAULT_INTERVAL_HOURS: i64 = 24;
+const MIN_INTERVAL_HOURS: i64 = 1;
+const MAX_INTERVAL_HOURS: i64 = 24 * 90;
+/// How often the background task wakes up to check whether a run is due.
+const CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+/// SQLite's `PRAGMA auto_vacuum` value for incremental mode.
+const AUTO_VACUUM_INCREMENTAL: i64 = 2;
+const RETENTION_TASK: &str = "retention_purge";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceSettings {
+    pub enabled: bool,
+    pub interval_hours: i64,
+    pub last_run_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceReport {
+    pub ran_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub page_count_before: i64,
+    pub page_count_after: i64,
+    pub freelist_before: i64,
+    /// True when this run switched the database to incremental auto-vacuum (one full VACUUM).
+    pub enabled_incremental_vacuum: bool,
+}
+
+pub async fn load_settings(pool: &SqlitePool) -> Result<MaintenanceSettings, sqlx::Error> {
+    Ok(MaintenanceSettings {
+        enabled: get_setting_or(pool, ENABLED_KEY, true).await?,
+        interval_hours: get_setting_or(pool, INTERVAL_KEY, DEFAULT_INTERVAL_HOURS)
+            .await?
+            .clamp(MIN_INTERVAL_HOURS, MAX_INTERVAL_HOURS),
+        last_run_at: last_run_at(pool).await?,
+    })
+}
+
+pub async fn save_settings(
+    pool: &SqlitePool,
+    enabled: bool,
+    interval_hours: i64,
+) -> Result<MaintenanceSettings, String> {
+    if !(MIN_INTERVAL_HOURS..=MAX_INTERVAL_HOURS).contains(&interval_hours) {
+        return Err(format!(
+            "Maintenance interval must be between {} and {} hours",
+            MIN_INTERVAL_HOURS, MAX_INTERVAL_HOURS
+        ));
+    }
+
+    set_setting(pool, ENABLED_KEY, &enabled.to_string())
+        .await
+        .map_err(|e| format!("Failed to save maintenance settings: {}", e))?;
+    set_setting(pool, INTERVAL_KEY, &interval_hours.to_string())
+        .await
+        .map_err(|e| format!("Failed to save maintenance settings: {}", e))?;
+
+    load_settings(pool)
+        .await
+        .map_err(|e| format!("Failed to load maintenance settings: {}", e))
+}
+
+async fn last_run_at(pool: &SqlitePool) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT created_at FROM audit_log WHERE action = ? ORDER BY created_at DESC, id DESC LIMIT 1",
+    )
+    .bind(MAINTENANCE_AUDIT_ACTION)
+    .fetch_optional(pool)
+    .await
+}
+
+async fn pragma_value(conn: &mut SqliteConnection, pragma: &str) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(&format!("PRAGMA {}", pragma))
+        .fetch_one(conn)
+        .await
+}
+
+/// Runs `PRAGMA optimize`, `ANALYZE` and an incremental vacuum, then records the run in the audit log.
+/// Everything runs on one connection: `PRAGMA auto_vacuum` only applies to the connection
+/// that runs the following `VACUUM`.
+pub async fn run_maintenance(pool: &SqlitePool) -> Result<MaintenanceReport, String> {
+    let started = Instant::now();
+    let ran_at = Utc::now();
+    let mut conn = pool
+        .acquire()
+        .await
+        .map_err(|e| format!("Failed to acquire connection: {}", e))?;
+
+    let page_count_before = pragma_value(&mut conn, "page_count")
+        .await
+        .map_err(|e| format!("Failed to read page count: {}", e))?;
+    let freelist_before = pragma_value(&mut conn, "freelist_count")
+        .await
+        .map_err(|e| format!("Failed to read freelist count: {}", e))?;
+
+    sqlx::query("PRAGMA optimize")
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("PRAGMA optimize failed: {}", e))?;
+    sqlx::query("ANALYZE")
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("ANALYZE failed: {}", e))?;
+
+    // Databases created before maintenance existed use auto_vacuum = NONE; switching
+    // modes only takes effect after one full VACUUM.
+    let auto_vacuum = pragma_value(&mut conn, "auto_vacuum")
+        .await
+        .map_err(|e| format!("Failed to read auto_vacuum mode: {}", e))?;
+    let enabled_incremental_vacuum = auto_vacuum != AUTO_VACUUM_INCREMENTAL;
+    if enabled_incremental_vacuum {
+        sqlx::query("PRAGMA auto_vacuum = INCREMENTAL")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to enable incremental vacuum: {}", e))?;
+        sqlx::query("VACUUM")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("VACUUM failed: {}", e))?;
+    } else {
+        sqlx::query("PRAGMA incremental_vacuum")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Incremental vacuum failed: {}", e))?;
+    }
+
+    let page_count_after = pragma_value(&mut conn, "page_count")
+        .await
+        .map_err(|e| format!("Failed to read page count: {}", e))?;
+
+    let report = MaintenanceReport {
+        ran_at,
+        duration_ms: started.elapsed().as_millis() as u64,
+        page_count_before,
+        page_count_after,
+        freelist_before,
+        enabled_incremental_vacuum,
+    };
+
+    let details = serde_json::to_value(&report)
+        .map_err(|e| format!("Failed to serialize maintenance report: {}", e))?;
+    record_audit(&mut *conn, MAINTENANCE_AUDIT_ACTION, None, &details)
+        .await
+        .map_err(|e| format!("Failed to record maintenance run: {}", e))?;
+
+    Ok(report)
+}
+
+async fn run_if_due(pool: &SqlitePool) -> Result<(), String> {
+    let settings = load_settings(pool)
+        .await
+        .map_err(|e| format!("Failed to load maintenance settings: {}", e))?;
+    if !settings.enabled {
+        return Ok(());
+    }
+
+    let due = settings.last_run_at.is_none_or(|last_run| {
+        Utc::now() - last_run >= ChronoDuration::hours(settings.interval_hours)
+    });
+    if due {
+        run_maintenance(pool).await?;
+    }
+    Ok(())
+}
+
+/// Starts the background task that periodically runs maintenance when it is due.
+pub fn spawn_maintenance_task(pool: SqlitePool, dataset_locks: DatasetLocks) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(error) = run_if_due(&pool).await {
+                record_task_failure(&pool, MAINTENANCE_AUDIT_ACTION, &error).await;
+            }
+            if let Err(error) = crate::retention::purge_if_enabled(&pool, &dataset_locks).await {
+                record_task_failure(&pool, RETENTION_TASK, &error).await;
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    #[tokio::test]
+    async fn test_maintenance_switches_to_incremental_and_records_run() {
+        let pool = test_pool().await;
+
+        let first = run_maintenance(&pool).await.unwrap();
+        assert!(first.enabled_incremental_vacuum);
+        let second = run_maintenance(&pool).await.unwrap();
+        assert!(!second.enabled_incremental_vacuum);
+
+        let runs: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM audit_log WHERE action = ?")
+            .bind(MAINTENANCE_AUDIT_ACTION)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(runs, 2);
+        assert!(load_settings(&pool).await.unwrap().last_run_at.is_some());
+    }
+}
@@ -0,0 +1,94 @@
+//! Renders independent documents side by side on a bounded set of threads.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Most rendering threads a batch uses, so the app stays responsive while it runs.
+const MAX_WORKERS: usize = 8;
+
+/// Calls `render` for every item on up to `MAX_WORKERS` threads, then `on_done` with the
+/// item and the number finished so far. After the first error no further items are
+/// started and that error is returned.
+pub fn render_all<T, R, D>(items: &[T], render: R, on_done: D) -> Result<(), String>
+where
+    T: Sync,
+    R: Fn(&T) -> Result<(), String> + Sync,
+    D: Fn(&T, usize) + Sync,
+{
+    let workers = std::thread::available_parallelism()
+        .map_or(1, |count| count.get())
+        .min(MAX_WORKERS)
+        .min(items.len())
+        .max(1);
+    let next = AtomicUsize::new(0);
+    let completed = AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+    let first_error: Mutex<Option<String>> = Mutex::new(None);
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                while !failed.load(Ordering::Relaxed) {
+                    let Some(item) = items.get(next.fetch_add(1, Ordering::Relaxed)) else {
+                        break;
+                    };
+                    match render(item) {
+                        Ok(()) => on_done(item, completed.fetch_add(1, Ordering::Relaxed) + 1),
+                        Err(error) => {
+                            failed.store(true, Ordering::Relaxed);
+                            if let Ok(mut first) = first_error.lock() {
+                                first.get_or_insert(error);
+                            }
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    match first_error.into_inner().ok().flatten() {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_all_visits_every_item_once() {
+        let items: Vec<usize> = (0..40).collect();
+        let seen = Mutex::new(Vec::new());
+        let highest = AtomicUsize::new(0);
+        render_all(
+            &items,
+            |item| {
+                seen.lock().unwrap().push(*item);
+                Ok(())
+            },
+            |_, completed| {
+                highest.fetch_max(completed, Ordering::Relaxed);
+            },
+        )
+        .unwrap();
+
+        let mut seen = seen.into_inner().unwrap();
+        seen.sort_unstable();
+        assert_eq!(seen, items);
+        assert_eq!(highest.into_inner(), 40);
+
+        let result = render_all(
+            &items,
+            |item| {
+                if *item == 3 {
+                    Err("Failed to render 3".to_string())
+                } else {
+                    Ok(())
+                }
+            },
+            |_, _| {},
+        );
+        assert_eq!(result, Err("Failed to render 3".to_string()));
+    }
+}
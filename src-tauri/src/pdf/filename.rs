@@ -0,0 +1,122 @@
+//! Export file names built from the `filename_template` report setting.
+
+use crate::db::models::Employee;
+use std::path::Path;
+
+/// Placeholders: `{nip}`, `{name}`, `{dataset}` and `{date}` (the export date).
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "{nip}_{name}_{dataset}_{date}";
+
+/// Replaces characters Windows or Unix reject in file names (and path separators) with `_`,
+/// collapses repeated separators left by empty placeholders and trims the edges.
+pub(crate) fn sanitize_file_name(name: &str) -> String {
+    let replaced: String = name
+        .chars()
+        .map(|ch| {
+            if ch.is_control() || matches!(ch, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|')
+            {
+                '_'
+            } else {
+                ch
+            }
+        })
+        .collect();
+    let mut cleaned = String::with_capacity(replaced.len());
+    for word in replaced.split_whitespace() {
+        if !cleaned.is_empty() {
+            cleaned.push(' ');
+        }
+        cleaned.push_str(word);
+    }
+    while cleaned.contains("__") {
+        cleaned = cleaned.replace("__", "_");
+    }
+    cleaned
+        .trim_matches(|ch: char| matches!(ch, '_' | '-' | '.' | ' '))
+        .to_string()
+}
+
+pub fn render_file_name(
+    template: &str,
+    employee: &Employee,
+    dataset_name: &str,
+    extension: &str,
+) -> String {
+    let rendered = template
+        .replace("{nip}", employee.nip.as_deref().unwrap_or_default())
+        .replace("{name}", &employee.name)
+        .replace("{dataset}", dataset_name)
+        .replace(
+            "{date}",
+            &chrono::Local::now().format("%Y-%m-%d").to_string(),
+        );
+    let stem = sanitize_file_name(&rendered);
+    let stem = if stem.is_empty() {
+        sanitize_file_name(&employee.name)
+    } else {
+        stem
+    };
+    format!("{}.{}", stem, extension)
+}
+
+/// `file_path` itself, or a templated file name inside it when it is a directory, so
+/// batch exports only need to pick a folder.
+pub fn resolve_export_path(
+    file_path: &str,
+    template: &str,
+    employee: &Employee,
+    dataset_name: &str,
+    extension: &str,
+) -> String {
+    let path = Path::new(file_path);
+    if !path.is_dir() {
+        return file_path.to_string();
+    }
+    path.join(render_file_name(
+        template,
+        employee,
+        dataset_name,
+        extension,
+    ))
+    .to_string_lossy()
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_render_file_name_sanitizes_values() {
+        let mut employee = Employee {
+            id: 1,
+            name: "Siti Rahmawati, S.E./M.M.".to_string(),
+            nip: None,
+            gol: None,
+            jabatan: None,
+            sub_jabatan: None,
+            position_type: None,
+            photo_path: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        assert_eq!(
+            render_file_name(
+                "{nip}_{name}_{dataset}",
+                &employee,
+                "Semester 1: 2024",
+                "pdf"
+            ),
+            "Siti Rahmawati, S.E._M.M._Semester 1_ 2024.pdf"
+        );
+        employee.nip = Some("198001012005011001".to_string());
+        assert_eq!(
+            render_file_name("{nip}", &employee, "", "pdf"),
+            "198001012005011001.pdf"
+        );
+        assert_eq!(
+            render_file_name("{unknown}?", &employee, "", "pdf"),
+            "{unknown}.pdf"
+        );
+    }
+}
@@ -0,0 +1,305 @@
+use super::filename::DEFAULT_FILENAME_TEMPLATE;
+use super::paper::PaperSettings;
+use super::{Canvas, Font};
+use crate::db::data_directory;
+use crate::db::settings::{get_setting, set_setting};
+use crate::i18n::Locale;
+use image::{DynamicImage, ImageFormat};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::SystemTime;
+
+const REPORT_SETTINGS_KEY: &str = "report.letterhead";
+/// Stored copy of the uploaded logo, relative to the app data directory.
+const LOGO_FILE: &str = "logo.png";
+/// Longest side of the stored logo in pixels; the letterhead prints it at under an inch.
+const LOGO_MAX_SIZE: u32 = 300;
+
+/// Agency identity printed on generated PDFs: the letterhead, document titles and the
+/// signature block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportSettings {
+    /// Top letterhead line, e.g. the provincial government
+    pub government_name: String,
+    /// Short agency name shown large in the letterhead
+    pub organization_name: String,
+    /// Full agency name used inside titles and sentences
+    pub organization_full_name: String,
+    pub address_lines: Vec<String>,
+    /// Logo file, relative to the app data directory unless absolute
+    pub logo_path: Option<String>,
+    /// Decoded `logo_path`, filled in by `load_report_settings` for the renderers
+    #[serde(skip)]
+    pub logo: Option<Arc<DynamicImage>>,
+    pub signer_title_lines: Vec<String>,
+    pub signer_name: String,
+    pub signer_nip: Option<String>,
+    /// Name given to exports saved into a folder; see `pdf::filename`
+    pub filename_template: String,
+    /// Appends the employee's notes for the dataset as a last page of the report
+    pub include_notes_appendix: bool,
+    /// Paper size, orientation and margins of every generated PDF
+    pub paper: PaperSettings,
+    /// Language of generated documents: titles, form labels and rule-based summaries
+    pub locale: Locale,
+}
+
+impl Default for ReportSettings {
+    fn default() -> Self {
+        Self {
+            government_name: "PEMERINTAH PROVINSI KALIMANTAN SELATAN".to_string(),
+            organization_name: "DINAS SOSIAL".to_string(),
+            organization_full_name: "Dinas Sosial Provinsi Kalimantan Selatan".to_string(),
+            address_lines: vec![
+                "Jalan Letjen R. Soeprapto No. 8 Banjarmasin Kode Pos 70114".to_string(),
+                "Telepon : (0511) 335 0825, Fax. (0511) 335 4193".to_string(),
+                "Email: dinsosialselprov@gmail.com Website: dinsoss.kalselprov.go.id".to_string(),
+            ],
+            logo_path: None,
+            logo: None,
+            signer_title_lines: vec![
+                "Plt. KEPALA DINAS SOSIAL".to_string(),
+                "PROVINSI KALIMANTAN SELATAN".to_string(),
+            ],
+            signer_name: "MUHAMMADUN, A.KS, M.I.Kom".to_string(),
+            signer_nip: None,
+            filename_template: DEFAULT_FILENAME_TEMPLATE.to_string(),
+            include_notes_appendix: false,
+            paper: PaperSettings::default(),
+            locale: Locale::default(),
+        }
+    }
+}
+
+fn clean_lines(lines: Vec<String>) -> Vec<String> {
+    lines
+        .into_iter()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+fn clean_optional(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+pub async fn load_report_settings(pool: &SqlitePool) -> Result<ReportSettings, String> {
+    let stored = get_setting(pool, REPORT_SETTINGS_KEY)
+        .await
+        .map_err(|e| format!("Failed to load report settings: {}", e))?;
+    let mut settings: ReportSettings = stored
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default();
+    // A logo that went missing or cannot be decoded is left out rather than failing exports
+    if let Some(logo_path) = &settings.logo_path {
+        settings.logo = decoded_logo(&data_directory(pool).await?.join(logo_path));
+    }
+    Ok(settings)
+}
+
+/// Decodes the logo at `path` once and hands out the same image until the file changes.
+fn decoded_logo(path: &Path) -> Option<Arc<DynamicImage>> {
+    static LOGO: Mutex<Option<(PathBuf, SystemTime, Arc<DynamicImage>)>> = Mutex::new(None);
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    let mut cached = LOGO.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some((cached_path, cached_modified, logo)) = cached.as_ref() {
+        if cached_path == path && *cached_modified == modified {
+            return Some(logo.clone());
+        }
+    }
+    let logo = Arc::new(image::open(path).ok()?);
+    *cached = Some((path.to_path_buf(), modified, logo.clone()));
+    Some(logo)
+}
+
+pub async fn save_report_settings(
+    pool: &SqlitePool,
+    settings: ReportSettings,
+) -> Result<ReportSettings, String> {
+    let settings = ReportSettings {
+        government_name: settings.government_name.trim().to_string(),
+        organization_name: settings.organization_name.trim().to_string(),
+        organization_full_name: settings.organization_full_name.trim().to_string(),
+        address_lines: clean_lines(settings.address_lines),
+        logo_path: clean_optional(settings.logo_path),
+        logo: None,
+        signer_title_lines: clean_lines(settings.signer_title_lines),
+        signer_name: settings.signer_name.trim().to_string(),
+        signer_nip: clean_optional(settings.signer_nip),
+        filename_template: clean_optional(Some(settings.filename_template))
+            .unwrap_or_else(|| DEFAULT_FILENAME_TEMPLATE.to_string()),
+        include_notes_appendix: settings.include_notes_appendix,
+        paper: settings.paper,
+        locale: settings.locale,
+    };
+    if settings.organization_name.is_empty() || settings.organization_full_name.is_empty() {
+        return Err("Organization name cannot be empty".to_string());
+    }
+    settings.paper.validate()?;
+
+    let value = serde_json::to_string(&settings)
+        .map_err(|e| format!("Failed to serialize report settings: {}", e))?;
+    set_setting(pool, REPORT_SETTINGS_KEY, &value)
+        .await
+        .map_err(|e| format!("Failed to save report settings: {}", e))?;
+    Ok(settings)
+}
+
+/// Stores a resized copy of the image at `source` as the letterhead logo; `None` removes it.
+pub async fn store_logo(
+    pool: &SqlitePool,
+    source: Option<&Path>,
+) -> Result<ReportSettings, String> {
+    let target = data_directory(pool).await?.join(LOGO_FILE);
+    let mut settings = load_report_settings(pool).await?;
+    settings.logo_path = match source {
+        Some(source) => {
+            let image = image::open(source).map_err(|e| format!("Failed to read logo: {}", e))?;
+            // PNG keeps the transparency most logos rely on
+            image
+                .thumbnail(LOGO_MAX_SIZE, LOGO_MAX_SIZE)
+                .save_with_format(&target, ImageFormat::Png)
+                .map_err(|e| format!("Failed to store logo: {}", e))?;
+            Some(LOGO_FILE.to_string())
+        }
+        None => {
+            match std::fs::remove_file(&target) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(format!("Failed to remove logo: {}", e))
+                }
+                _ => {}
+            }
+            None
+        }
+    };
+    save_report_settings(pool, settings).await?;
+    load_report_settings(pool).await
+}
+
+/// Draws the letterhead starting at `y` and returns the baseline below it.
+pub fn draw_letterhead(
+    canvas: &mut Canvas<'_>,
+    settings: &ReportSettings,
+    x: f32,
+    mut y: f32,
+) -> std::io::Result<f32> {
+    let top = y;
+    let x = match &settings.logo {
+        Some(logo) => {
+            canvas.image(logo, x, y - 58.0, 64.0, 68.0)?;
+            x + 76.0
+        }
+        None => x,
+    };
+
+    if !settings.government_name.is_empty() {
+        canvas.left_text(x, y, Font::Bold, 11.0, &settings.government_name)?;
+        y -= 16.0;
+    }
+    canvas.left_text(x, y, Font::Bold, 14.0, &settings.organization_name)?;
+    y -= 20.0;
+
+    for line in &settings.address_lines {
+        canvas.left_text(x, y, Font::Regular, 9.0, line)?;
+        y -= 11.0;
+    }
+
+    // Keep the content below the logo when there are few text lines
+    let y = if settings.logo.is_some() {
+        y.min(top - 62.0)
+    } else {
+        y
+    };
+    Ok(y - 14.0)
+}
+
+/// Right-aligned signature block whose right edge sits at `right_x`.
+pub fn draw_signature(
+    canvas: &mut Canvas<'_>,
+    settings: &ReportSettings,
+    right_x: f32,
+    mut y: f32,
+) -> std::io::Result<()> {
+    for line in &settings.signer_title_lines {
+        canvas.right_text(right_x, y, Font::Regular, 10.0, line)?;
+        y -= 11.0;
+    }
+    y -= 44.0;
+    canvas.right_text(right_x, y, Font::Bold, 10.0, &settings.signer_name)?;
+    if let Some(nip) = &settings.signer_nip {
+        canvas.right_text(
+            right_x,
+            y - 12.0,
+            Font::Regular,
+            10.0,
+            &format!("NIP. {}", nip),
+        )?;
+    }
+    Ok(())
+}
+
+/// Vertical space `draw_signature` takes below its starting baseline.
+pub fn signature_height(settings: &ReportSettings) -> f32 {
+    settings.signer_title_lines.len() as f32 * 11.0 + 56.0
+}
+
+/// Small centered agency name along the top edge, for documents without a full letterhead.
+pub fn draw_running_header(
+    canvas: &mut Canvas<'_>,
+    settings: &ReportSettings,
+    page_width: f32,
+    page_height: f32,
+) -> std::io::Result<()> {
+    if let Some(logo) = &settings.logo {
+        let text_width = canvas.text_width(Font::Regular, 9.0, &settings.organization_full_name);
+        canvas.image(
+            logo,
+            (page_width - text_width) / 2.0 - 20.0,
+            page_height - 26.0,
+            14.0,
+            14.0,
+        )?;
+    }
+    canvas.center_text(
+        page_width / 2.0,
+        page_height - 22.0,
+        Font::Regular,
+        9.0,
+        &settings.organization_full_name,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbImage;
+
+    #[test]
+    fn test_logo_is_decoded_once_until_the_file_changes() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("epa-logo.png");
+        let logo = RgbImage::from_pixel(4, 4, image::Rgb([0, 0, 200]));
+        logo.save(&path).unwrap();
+
+        let first = decoded_logo(&path).unwrap();
+        let second = decoded_logo(&path).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        RgbImage::from_pixel(6, 6, image::Rgb([0, 200, 0]))
+            .save(&path)
+            .unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::now() + std::time::Duration::from_secs(1))
+            .unwrap();
+        let replaced = decoded_logo(&path).unwrap();
+        assert_eq!(replaced.width(), 6);
+    }
+}
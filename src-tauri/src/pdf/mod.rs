@@ -0,0 +1,343 @@
+//! Shared PDF backend. Every document embeds the bundled DejaVu Sans TrueType fonts so
+//! names with diacritics and non-Latin scripts render as typed.
+//!
+//! Coordinates and sizes are in points with the origin at the bottom-left corner.
+
+use image::DynamicImage;
+use printpdf::lopdf::{self, Object, ObjectId};
+use printpdf::path::PaintMode;
+use printpdf::{
+    Color, Greyscale, Image, ImageTransform, IndirectFontRef, Line, Mm, PdfDocument,
+    PdfDocumentReference, PdfLayerReference, Point, Pt, Rect,
+};
+use std::cell::Cell;
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::collections::HashMap;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufWriter, Cursor};
+use ttf_parser::Face;
+
+pub mod batch;
+pub mod filename;
+pub mod letterhead;
+pub mod paper;
+pub mod table;
+
+static REGULAR_FONT: &[u8] = include_bytes!("../../fonts/DejaVuSans.ttf");
+static BOLD_FONT: &[u8] = include_bytes!("../../fonts/DejaVuSans-Bold.ttf");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+    Regular,
+    Bold,
+}
+
+fn to_io_error(error: impl std::fmt::Display) -> io::Error {
+    io::Error::other(error.to_string())
+}
+
+fn mm(value: f32) -> Mm {
+    Mm::from(Pt(value))
+}
+
+struct EmbeddedFont {
+    reference: IndirectFontRef,
+    face: Face<'static>,
+}
+
+impl EmbeddedFont {
+    fn load(document: &PdfDocumentReference, data: &'static [u8]) -> io::Result<Self> {
+        Ok(Self {
+            reference: document
+                .add_external_font(Cursor::new(data))
+                .map_err(to_io_error)?,
+            face: Face::parse(data, 0).map_err(to_io_error)?,
+        })
+    }
+
+    fn text_width(&self, size: f32, text: &str) -> f32 {
+        let units: u32 = text
+            .chars()
+            .filter_map(|ch| self.face.glyph_index(ch))
+            .filter_map(|glyph| self.face.glyph_hor_advance(glyph))
+            .map(u32::from)
+            .sum();
+        units as f32 * size / f32::from(self.face.units_per_em())
+    }
+}
+
+/// A PDF file being written page by page; saved to disk by `finish`.
+pub struct Pdf {
+    document: PdfDocumentReference,
+    writer: BufWriter<File>,
+    regular: EmbeddedFont,
+    bold: EmbeddedFont,
+    /// Images placed so far; more than one means copies may need sharing on `finish`
+    images: Cell<usize>,
+}
+
+impl Pdf {
+    pub fn create(file_path: &str) -> io::Result<Self> {
+        let writer = BufWriter::new(File::create(file_path)?);
+        let document = PdfDocument::empty("");
+        let regular = EmbeddedFont::load(&document, REGULAR_FONT)?;
+        let bold = EmbeddedFont::load(&document, BOLD_FONT)?;
+        Ok(Self {
+            document,
+            writer,
+            regular,
+            bold,
+            images: Cell::new(0),
+        })
+    }
+
+    pub fn render_page<F>(&mut self, width: f32, height: f32, render: F) -> io::Result<()>
+    where
+        F: FnOnce(&mut Canvas<'_>) -> io::Result<()>,
+    {
+        let (page, layer) = self.document.add_page(mm(width), mm(height), "Layer 1");
+        let mut canvas = Canvas {
+            layer: self.document.get_page(page).get_layer(layer),
+            pdf: self,
+        };
+        render(&mut canvas)
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        if self.images.get() < 2 {
+            return self.document.save(&mut self.writer).map_err(to_io_error);
+        }
+        let bytes = self.document.save_to_bytes().map_err(to_io_error)?;
+        let mut document = lopdf::Document::load_mem(&bytes).map_err(to_io_error)?;
+        share_repeated_images(&mut document);
+        document.save_to(&mut self.writer)
+    }
+
+    /// Greedy word wrap so each line fits within `max_width`; for laying out text before
+    /// its page is drawn.
+    pub fn wrap_to_width(&self, font: Font, size: f32, text: &str, max_width: f32) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut current = String::new();
+        for word in text.split_whitespace() {
+            let candidate = if current.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", current, word)
+            };
+            if !current.is_empty() && self.font(font).text_width(size, &candidate) > max_width {
+                lines.push(std::mem::replace(&mut current, word.to_string()));
+            } else {
+                current = candidate;
+            }
+        }
+        if !current.is_empty() {
+            lines.push(current);
+        }
+        lines
+    }
+
+    fn font(&self, font: Font) -> &EmbeddedFont {
+        match font {
+            Font::Regular => &self.regular,
+            Font::Bold => &self.bold,
+        }
+    }
+}
+
+/// printpdf embeds a separate copy of an image on every page it is drawn on; this points
+/// every page at the first copy of each identical image, so a logo repeated in every
+/// header is stored once.
+fn share_repeated_images(document: &mut lopdf::Document) {
+    let mut first_copies: HashMap<(String, u64), ObjectId> = HashMap::new();
+    let mut duplicates = HashMap::new();
+    for (&id, object) in &document.objects {
+        let Object::Stream(stream) = object else {
+            continue;
+        };
+        if !matches!(stream.dict.get(b"Subtype"), Ok(Object::Name(name)) if name == b"Image") {
+            continue;
+        }
+        let mut hasher = DefaultHasher::new();
+        stream.content.hash(&mut hasher);
+        match first_copies.entry((format!("{:?}", stream.dict), hasher.finish())) {
+            Entry::Vacant(entry) => {
+                entry.insert(id);
+            }
+            Entry::Occupied(entry) => {
+                if let Ok(Object::Stream(first)) = document.get_object(*entry.get()) {
+                    if first.content == stream.content {
+                        duplicates.insert(id, *entry.get());
+                    }
+                }
+            }
+        }
+    }
+    if duplicates.is_empty() {
+        return;
+    }
+    document.traverse_objects(|object| {
+        if let Object::Reference(id) = object {
+            if let Some(first) = duplicates.get(id) {
+                *id = *first;
+            }
+        }
+    });
+    document.prune_objects();
+}
+
+/// Drawing surface for a single page.
+pub struct Canvas<'a> {
+    layer: PdfLayerReference,
+    pdf: &'a Pdf,
+}
+
+impl Canvas<'_> {
+    pub fn text_width(&self, font: Font, size: f32, text: &str) -> f32 {
+        self.pdf.font(font).text_width(size, text)
+    }
+
+    /// Greedy word wrap so each line fits within `max_width`.
+    pub fn wrap_to_width(&self, font: Font, size: f32, text: &str, max_width: f32) -> Vec<String> {
+        self.pdf.wrap_to_width(font, size, text, max_width)
+    }
+
+    pub fn left_text(
+        &mut self,
+        x: f32,
+        y: f32,
+        font: Font,
+        size: f32,
+        text: &str,
+    ) -> io::Result<()> {
+        self.layer
+            .use_text(text, size, mm(x), mm(y), &self.pdf.font(font).reference);
+        Ok(())
+    }
+
+    pub fn right_text(
+        &mut self,
+        x: f32,
+        y: f32,
+        font: Font,
+        size: f32,
+        text: &str,
+    ) -> io::Result<()> {
+        let width = self.text_width(font, size, text);
+        self.left_text(x - width, y, font, size, text)
+    }
+
+    pub fn center_text(
+        &mut self,
+        x: f32,
+        y: f32,
+        font: Font,
+        size: f32,
+        text: &str,
+    ) -> io::Result<()> {
+        let width = self.text_width(font, size, text);
+        self.left_text(x - width / 2.0, y, font, size, text)
+    }
+
+    /// Gray level from 0 (black) to 255 (white), used for fills and text.
+    pub fn set_fill_gray(&mut self, gray: u8) -> io::Result<()> {
+        self.layer.set_fill_color(Color::Greyscale(Greyscale::new(
+            f32::from(gray) / 255.0,
+            None,
+        )));
+        Ok(())
+    }
+
+    pub fn set_line_width(&mut self, width: f32) -> io::Result<()> {
+        self.layer.set_outline_thickness(width);
+        Ok(())
+    }
+
+    pub fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32) -> io::Result<()> {
+        self.layer.add_line(Line {
+            points: vec![
+                (Point::new(mm(x1), mm(y1)), false),
+                (Point::new(mm(x2), mm(y2)), false),
+            ],
+            is_closed: false,
+        });
+        Ok(())
+    }
+
+    pub fn fill_rectangle(&mut self, x: f32, y: f32, width: f32, height: f32) -> io::Result<()> {
+        self.layer.add_rect(
+            Rect::new(mm(x), mm(y), mm(x + width), mm(y + height)).with_mode(PaintMode::Fill),
+        );
+        Ok(())
+    }
+
+    /// Outline of a rectangle in the current line width.
+    pub fn stroke_rectangle(&mut self, x: f32, y: f32, width: f32, height: f32) -> io::Result<()> {
+        self.layer.add_rect(
+            Rect::new(mm(x), mm(y), mm(x + width), mm(y + height)).with_mode(PaintMode::Stroke),
+        );
+        Ok(())
+    }
+
+    /// Draws `image` as large as fits in the box at (`x`, `y`), keeping its aspect ratio
+    /// and centring it.
+    pub fn image(
+        &mut self,
+        image: &DynamicImage,
+        x: f32,
+        y: f32,
+        max_width: f32,
+        max_height: f32,
+    ) -> io::Result<()> {
+        let (width, height) = (image.width() as f32, image.height() as f32);
+        let scale = (max_width / width).min(max_height / height);
+        self.pdf.images.set(self.pdf.images.get() + 1);
+        // At 72 dpi one pixel is one point, so `scale` maps pixels straight onto the box
+        Image::from_dynamic_image(image).add_to_layer(
+            self.layer.clone(),
+            ImageTransform {
+                translate_x: Some(mm(x + (max_width - width * scale) / 2.0)),
+                translate_y: Some(mm(y + (max_height - height * scale) / 2.0)),
+                scale_x: Some(scale),
+                scale_y: Some(scale),
+                dpi: Some(72.0),
+                ..Default::default()
+            },
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbImage;
+
+    #[test]
+    fn test_image_drawn_on_every_page_is_stored_once() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("epa-image.pdf");
+        let logo = DynamicImage::ImageRgb8(RgbImage::from_pixel(8, 8, image::Rgb([200, 0, 0])));
+        let mut document = Pdf::create(&path.to_string_lossy()).unwrap();
+        for _ in 0..3 {
+            document
+                .render_page(595.0, 842.0, |canvas| {
+                    canvas.image(&logo, 50.0, 780.0, 40.0, 40.0)
+                })
+                .unwrap();
+        }
+        document.finish().unwrap();
+        let saved = lopdf::Document::load(&path).unwrap();
+
+        let images = saved
+            .objects
+            .values()
+            .filter(|object| {
+                matches!(object, Object::Stream(stream)
+                    if matches!(stream.dict.get(b"Subtype"), Ok(Object::Name(name)) if name == b"Image"))
+            })
+            .count();
+        assert_eq!(saved.get_pages().len(), 3);
+        assert_eq!(images, 1);
+    }
+}
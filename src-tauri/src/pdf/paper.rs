@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+
+const POINTS_PER_MM: f32 = 72.0 / 25.4;
+/// Widest margin accepted on any side, so every paper keeps a usable content area.
+const MAX_MARGIN_MM: f32 = 60.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PaperSize {
+    #[default]
+    A4,
+    /// Folio, 215 x 330 mm, the common office stock here
+    F4,
+    Letter,
+    A3,
+}
+
+impl PaperSize {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "a4" => Ok(Self::A4),
+            "f4" | "folio" => Ok(Self::F4),
+            "letter" => Ok(Self::Letter),
+            "a3" => Ok(Self::A3),
+            other => Err(format!("Unsupported paper size: {}", other)),
+        }
+    }
+
+    /// Portrait width and height in points
+    pub fn dimensions(self) -> (f32, f32) {
+        match self {
+            Self::A4 => (595.0, 842.0),
+            Self::F4 => (609.0, 935.0),
+            Self::Letter => (612.0, 792.0),
+            Self::A3 => (842.0, 1191.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Orientation {
+    Portrait,
+    Landscape,
+}
+
+/// Page margins in millimetres.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PageMargins {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+impl Default for PageMargins {
+    fn default() -> Self {
+        Self {
+            top: 14.0,
+            right: 18.0,
+            bottom: 14.0,
+            left: 18.0,
+        }
+    }
+}
+
+/// Paper the generated PDFs are laid out for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PaperSettings {
+    pub size: PaperSize,
+    /// `None` keeps each document's own orientation: landscape for the employee report,
+    /// portrait for summaries and dataset exports
+    pub orientation: Option<Orientation>,
+    pub margins: PageMargins,
+}
+
+impl PaperSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        let PageMargins {
+            top,
+            right,
+            bottom,
+            left,
+        } = self.margins;
+        if [top, right, bottom, left]
+            .iter()
+            .any(|margin| !(0.0..=MAX_MARGIN_MM).contains(margin))
+        {
+            return Err(format!(
+                "Page margins must be between 0 and {} mm",
+                MAX_MARGIN_MM
+            ));
+        }
+        Ok(())
+    }
+
+    /// Page and content area for a document that is `default_orientation` unless the
+    /// settings choose one.
+    pub fn layout(&self, default_orientation: Orientation) -> PageLayout {
+        self.layout_for(self.size, default_orientation)
+    }
+
+    /// As `layout`, on `size` instead of the configured paper.
+    pub fn layout_for(&self, size: PaperSize, default_orientation: Orientation) -> PageLayout {
+        let (short, long) = size.dimensions();
+        let (width, height) = match self.orientation.unwrap_or(default_orientation) {
+            Orientation::Portrait => (short, long),
+            Orientation::Landscape => (long, short),
+        };
+        PageLayout {
+            width,
+            height,
+            left: self.margins.left * POINTS_PER_MM,
+            right: width - self.margins.right * POINTS_PER_MM,
+            top: height - self.margins.top * POINTS_PER_MM,
+            bottom: self.margins.bottom * POINTS_PER_MM,
+        }
+    }
+}
+
+/// Page size and the edges of its content area, in points from the bottom-left corner.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageLayout {
+    pub width: f32,
+    pub height: f32,
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+impl PageLayout {
+    pub fn content_width(&self) -> f32 {
+        self.right - self.left
+    }
+
+    pub fn center_x(&self) -> f32 {
+        (self.left + self.right) / 2.0
+    }
+
+    /// Lines of `line_height` that fit between `from_y` and the bottom margin.
+    pub fn lines_below(&self, from_y: f32, line_height: f32) -> usize {
+        let available = from_y - self.bottom;
+        if available <= 0.0 {
+            0
+        } else {
+            (available / line_height).floor() as usize
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_follows_paper_orientation_and_margins() {
+        let mut paper = PaperSettings {
+            size: PaperSize::F4,
+            ..Default::default()
+        };
+        let report = paper.layout(Orientation::Landscape);
+        assert_eq!((report.width, report.height), (935.0, 609.0));
+        let summary = paper.layout(Orientation::Portrait);
+        assert_eq!((summary.width, summary.height), (609.0, 935.0));
+
+        paper.orientation = Some(Orientation::Portrait);
+        paper.margins.left = 25.4;
+        let layout = paper.layout(Orientation::Landscape);
+        assert_eq!(layout.width, 609.0);
+        assert!((layout.left - 72.0).abs() < 0.01);
+        assert_eq!(PaperSize::parse(" Letter ").unwrap(), PaperSize::Letter);
+        assert!(PaperSize::parse("b5").is_err());
+
+        paper.margins.top = 80.0;
+        assert!(paper.validate().is_err());
+    }
+}
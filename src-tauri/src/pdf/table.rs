@@ -0,0 +1,201 @@
+//! Bordered tables drawn row by row, as on the printed evaluation forms.
+
+use super::{Canvas, Font};
+use std::io;
+
+/// Space between a cell's border and its text.
+const CELL_PADDING: f32 = 3.0;
+/// Baseline-to-baseline distance as a multiple of the font size.
+const LINE_SPACING: f32 = 1.2;
+/// Gray level of shaded header rows.
+const HEADER_SHADE: u8 = 225;
+const BORDER_WIDTH: f32 = 0.6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Column {
+    pub width: f32,
+    pub align: Align,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RowStyle {
+    pub font: Font,
+    pub size: f32,
+    /// Gray background, for header rows
+    pub shaded: bool,
+    /// Overrides the column alignment for every cell of the row
+    pub align: Option<Align>,
+}
+
+impl RowStyle {
+    pub fn body(font: Font, size: f32) -> Self {
+        Self {
+            font,
+            size,
+            shaded: false,
+            align: None,
+        }
+    }
+
+    pub fn header(size: f32) -> Self {
+        Self {
+            font: Font::Bold,
+            size,
+            shaded: true,
+            align: Some(Align::Center),
+        }
+    }
+}
+
+/// Columns laid out from `x`; every cell gets a border, so neighbouring borders form the
+/// grid lines and column dividers.
+pub struct Table {
+    x: f32,
+    columns: Vec<Column>,
+}
+
+impl Table {
+    pub fn new(x: f32, columns: Vec<Column>) -> Self {
+        Self { x, columns }
+    }
+
+    pub fn width(&self) -> f32 {
+        self.columns.iter().map(|column| column.width).sum()
+    }
+
+    fn wrap_cells(&self, canvas: &Canvas<'_>, cells: &[&str], style: RowStyle) -> Vec<Vec<String>> {
+        self.columns
+            .iter()
+            .zip(cells)
+            .map(|(column, text)| {
+                canvas.wrap_to_width(
+                    style.font,
+                    style.size,
+                    text,
+                    column.width - 2.0 * CELL_PADDING,
+                )
+            })
+            .collect()
+    }
+
+    /// Height the row needs so its longest cell fits without overflowing.
+    pub fn row_height(&self, canvas: &Canvas<'_>, cells: &[&str], style: RowStyle) -> f32 {
+        let lines = self
+            .wrap_cells(canvas, cells, style)
+            .iter()
+            .map(Vec::len)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        lines as f32 * style.size * LINE_SPACING + 2.0 * CELL_PADDING
+    }
+
+    /// Draws a row whose top edge is at `top` and returns the y of its bottom edge.
+    pub fn draw_row(
+        &self,
+        canvas: &mut Canvas<'_>,
+        top: f32,
+        cells: &[&str],
+        style: RowStyle,
+    ) -> io::Result<f32> {
+        let height = self.row_height(canvas, cells, style);
+        let bottom = top - height;
+        if style.shaded {
+            canvas.set_fill_gray(HEADER_SHADE)?;
+            canvas.fill_rectangle(self.x, bottom, self.width(), height)?;
+            canvas.set_fill_gray(0)?;
+        }
+
+        canvas.set_line_width(BORDER_WIDTH)?;
+        let mut x = self.x;
+        for (column, lines) in self
+            .columns
+            .iter()
+            .zip(self.wrap_cells(canvas, cells, style))
+        {
+            canvas.stroke_rectangle(x, bottom, column.width, height)?;
+            // Baseline of the first line sits one cap height below the padding
+            let mut y = top - CELL_PADDING - style.size * 0.85;
+            for line in &lines {
+                match style.align.unwrap_or(column.align) {
+                    Align::Left => {
+                        canvas.left_text(x + CELL_PADDING, y, style.font, style.size, line)?
+                    }
+                    Align::Center => canvas.center_text(
+                        x + column.width / 2.0,
+                        y,
+                        style.font,
+                        style.size,
+                        line,
+                    )?,
+                    Align::Right => canvas.right_text(
+                        x + column.width - CELL_PADDING,
+                        y,
+                        style.font,
+                        style.size,
+                        line,
+                    )?,
+                }
+                y -= style.size * LINE_SPACING;
+            }
+            x += column.width;
+        }
+        Ok(bottom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::Pdf;
+
+    #[test]
+    fn test_rows_grow_to_fit_wrapped_cells() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("epa-table.pdf");
+        let mut document = Pdf::create(&path.to_string_lossy()).unwrap();
+        let table = Table::new(
+            50.0,
+            vec![
+                Column {
+                    width: 40.0,
+                    align: Align::Left,
+                },
+                Column {
+                    width: 120.0,
+                    align: Align::Right,
+                },
+            ],
+        );
+        assert_eq!(table.width(), 160.0);
+
+        let style = RowStyle::body(Font::Regular, 10.0);
+        let mut heights = (0.0, 0.0);
+        document
+            .render_page(595.0, 842.0, |canvas| {
+                let bottom = table.draw_row(canvas, 800.0, &["1", "80,00"], style)?;
+                heights.0 = 800.0 - bottom;
+                heights.1 = table.row_height(
+                    canvas,
+                    &[
+                        "2",
+                        "Kehadiran dan tepat waktu dalam setiap kegiatan kantor",
+                    ],
+                    style,
+                );
+                Ok(())
+            })
+            .unwrap();
+        document.finish().unwrap();
+
+        assert_eq!(heights.0, 10.0 * LINE_SPACING + 2.0 * CELL_PADDING);
+        assert!(heights.1 >= 2.0 * heights.0 - 2.0 * CELL_PADDING);
+    }
+}
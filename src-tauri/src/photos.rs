@@ -0,0 +1,92 @@
+use crate::db::data_directory;
+use image::{DynamicImage, ImageFormat};
+use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
+
+const PHOTO_DIR: &str = "photos";
+/// Longest side of a stored photo in pixels; enough for the report cover and list avatars.
+const PHOTO_MAX_SIZE: u32 = 400;
+
+async fn stored_photo_path(pool: &SqlitePool, employee_id: i64) -> Result<Option<String>, String> {
+    sqlx::query_scalar::<_, Option<String>>("SELECT photo_path FROM employees WHERE id = ?")
+        .bind(employee_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to load employee: {}", e))?
+        .ok_or_else(|| format!("Employee {} not found", employee_id))
+}
+
+/// Resizes `source` and stores it as the employee's photo, or removes the photo when
+/// `source` is `None`.
+pub async fn set_photo(
+    pool: &SqlitePool,
+    employee_id: i64,
+    source: Option<&Path>,
+) -> Result<(), String> {
+    let previous = stored_photo_path(pool, employee_id).await?;
+    let directory = data_directory(pool).await?;
+
+    let photo_path = match source {
+        Some(source) => {
+            let image = image::open(source).map_err(|e| format!("Failed to read photo: {}", e))?;
+            let relative = format!("{}/{}.jpg", PHOTO_DIR, employee_id);
+            let target = directory.join(&relative);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create photo directory: {}", e))?;
+            }
+            // JPEG has no alpha channel, so flatten to RGB first
+            image
+                .thumbnail(PHOTO_MAX_SIZE, PHOTO_MAX_SIZE)
+                .to_rgb8()
+                .save_with_format(&target, ImageFormat::Jpeg)
+                .map_err(|e| format!("Failed to store photo: {}", e))?;
+            Some(relative)
+        }
+        None => None,
+    };
+
+    sqlx::query("UPDATE employees SET photo_path = ?, updated_at = datetime('now') WHERE id = ?")
+        .bind(&photo_path)
+        .bind(employee_id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to save photo: {}", e))?;
+
+    if let Some(previous) = previous.filter(|previous| Some(previous) != photo_path.as_ref()) {
+        let _ = std::fs::remove_file(directory.join(previous));
+    }
+    Ok(())
+}
+
+/// Absolute path of the stored photo, `None` when the employee has none or it went missing.
+pub async fn photo_file(pool: &SqlitePool, employee_id: i64) -> Result<Option<PathBuf>, String> {
+    let Some(relative) = stored_photo_path(pool, employee_id).await? else {
+        return Ok(None);
+    };
+    let path = data_directory(pool).await?.join(relative);
+    Ok(path.is_file().then_some(path))
+}
+
+/// Decoded photo at `photo_path` under the database `directory`, for PDF rendering; a
+/// missing or unreadable file is treated as no photo.
+pub fn open_photo(directory: &Path, photo_path: Option<&str>) -> Option<DynamicImage> {
+    photo_path.and_then(|relative| image::open(directory.join(relative)).ok())
+}
+
+/// Removes the stored photos of deleted employees.
+pub(crate) async fn remove_employee_photos(
+    pool: &SqlitePool,
+    employee_ids: &[i64],
+) -> Result<(), String> {
+    let directory = data_directory(pool).await?.join(PHOTO_DIR);
+    for id in employee_ids {
+        match std::fs::remove_file(directory.join(format!("{}.jpg", id))) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                return Err(format!("Failed to remove photo: {}", e))
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,345 @@
+use crate::backup::create_backup;
+use crate::commands::dataset::dataset_period_key;
+use crate::dataset_lock::DatasetLocks;
+use crate::db::audit::record_audit;
+use crate::db::models::Dataset;
+use crate::db::settings::{get_setting_or, set_setting};
+use serde::{Deserialize, Serialize};
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+
+pub const RETENTION_AUDIT_ACTION: &str = "retention_purge";
+/// Holder named in the busy error of a dataset the purge has locked.
+const PURGE_TASK: &str = "retention-purge";
+
+const ENABLED_KEY: &str = "retention.enabled";
+const KEEP_PERIODS_KEY: &str = "retention.keep_periods";
+/// Three years of semesters.
+const DEFAULT_KEEP_PERIODS: i64 = 6;
+const MIN_KEEP_PERIODS: i64 = 1;
+const MAX_KEEP_PERIODS: i64 = 100;
+/// Backup label of the snapshot taken before datasets are purged.
+const PRE_PURGE_LABEL: &str = "pre-purge";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionSettings {
+    /// Purges automatically from the maintenance task; off by default
+    pub enabled: bool,
+    /// Newest evaluation periods kept; older datasets are purged
+    pub keep_periods: i64,
+}
+
+/// A dataset the policy would remove, with what goes with it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurgeCandidate {
+    pub dataset: Dataset,
+    pub employee_count: i64,
+    pub score_count: i64,
+    pub summary_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurgeResult {
+    pub deleted_dataset_ids: Vec<i64>,
+    /// Due but busy with an import or merge; purged on a later run
+    pub skipped_dataset_ids: Vec<i64>,
+    /// Snapshot taken before deleting; `None` when nothing was deleted
+    pub backup_file: Option<String>,
+}
+
+pub async fn load_settings(pool: &SqlitePool) -> Result<RetentionSettings, sqlx::Error> {
+    Ok(RetentionSettings {
+        enabled: get_setting_or(pool, ENABLED_KEY, false).await?,
+        keep_periods: get_setting_or(pool, KEEP_PERIODS_KEY, DEFAULT_KEEP_PERIODS)
+            .await?
+            .clamp(MIN_KEEP_PERIODS, MAX_KEEP_PERIODS),
+    })
+}
+
+pub async fn save_settings(
+    pool: &SqlitePool,
+    enabled: bool,
+    keep_periods: i64,
+) -> Result<RetentionSettings, String> {
+    if !(MIN_KEEP_PERIODS..=MAX_KEEP_PERIODS).contains(&keep_periods) {
+        return Err(format!(
+            "Periods to keep must be between {} and {}",
+            MIN_KEEP_PERIODS, MAX_KEEP_PERIODS
+        ));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    set_setting(&mut *tx, ENABLED_KEY, &enabled.to_string())
+        .await
+        .map_err(|e| format!("Failed to save retention settings: {}", e))?;
+    set_setting(&mut *tx, KEEP_PERIODS_KEY, &keep_periods.to_string())
+        .await
+        .map_err(|e| format!("Failed to save retention settings: {}", e))?;
+    record_audit(
+        &mut *tx,
+        "retention_settings_updated",
+        None,
+        &serde_json::json!({ "enabled": enabled, "keep_periods": keep_periods }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    load_settings(pool)
+        .await
+        .map_err(|e| format!("Failed to load retention settings: {}", e))
+}
+
+/// Datasets outside the newest `keep_periods` evaluation periods. Datasets without a period
+/// label share one period per year.
+fn datasets_beyond_retention(datasets: Vec<Dataset>, keep_periods: usize) -> Vec<Dataset> {
+    let period = |dataset: &Dataset| {
+        let (year, label, _) = dataset_period_key(dataset);
+        (year, label)
+    };
+    let mut periods: Vec<(i32, String)> = datasets.iter().map(period).collect();
+    periods.sort_unstable_by(|a, b| b.cmp(a));
+    periods.dedup();
+    periods.truncate(keep_periods);
+
+    datasets
+        .into_iter()
+        .filter(|dataset| !periods.contains(&period(dataset)))
+        .collect()
+}
+
+/// What `apply_retention_policy` would delete under the saved settings, oldest first.
+pub async fn preview_purge(pool: &SqlitePool) -> Result<Vec<PurgeCandidate>, String> {
+    let settings = load_settings(pool)
+        .await
+        .map_err(|e| format!("Failed to load retention settings: {}", e))?;
+    let datasets = sqlx::query_as::<_, Dataset>("SELECT * FROM datasets")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to load datasets: {}", e))?;
+    let mut expired = datasets_beyond_retention(datasets, settings.keep_periods as usize);
+    expired.sort_by_key(dataset_period_key);
+
+    let mut candidates = Vec::with_capacity(expired.len());
+    for dataset in expired {
+        let (employee_count, score_count, summary_count): (i64, i64, i64) = sqlx::query_as(
+            "SELECT (SELECT COUNT(*) FROM dataset_employees WHERE dataset_id = ?1),
+                    (SELECT COUNT(*) FROM scores WHERE dataset_id = ?1),
+                    (SELECT COUNT(*) FROM summaries WHERE dataset_id = ?1)",
+        )
+        .bind(dataset.id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to count dataset contents: {}", e))?;
+        candidates.push(PurgeCandidate {
+            dataset,
+            employee_count,
+            score_count,
+            summary_count,
+        });
+    }
+    Ok(candidates)
+}
+
+fn push_ids(query: &mut QueryBuilder<'_, Sqlite>, ids: &[i64]) {
+    query.push(" IN (");
+    let mut separated = query.separated(", ");
+    for id in ids {
+        separated.push_bind(*id);
+    }
+    query.push(")");
+}
+
+/// Backs up the database, then deletes the datasets beyond the retention policy; their
+/// scores, summaries and notes follow through cascading foreign keys. Master employee
+/// records and their files stay, since the backup holds only the database. Datasets an
+/// import or merge holds locked are skipped until a later run.
+pub async fn apply_retention_policy(
+    pool: &SqlitePool,
+    locks: &DatasetLocks,
+) -> Result<PurgeResult, String> {
+    let mut guards = Vec::new();
+    let mut ids = Vec::new();
+    let mut skipped_dataset_ids = Vec::new();
+    for candidate in preview_purge(pool).await? {
+        match locks.acquire(PURGE_TASK, &[candidate.dataset.id]) {
+            Ok(guard) => {
+                guards.push(guard);
+                ids.push(candidate.dataset.id);
+            }
+            Err(_) => skipped_dataset_ids.push(candidate.dataset.id),
+        }
+    }
+    if ids.is_empty() {
+        return Ok(PurgeResult {
+            deleted_dataset_ids: Vec::new(),
+            skipped_dataset_ids,
+            backup_file: None,
+        });
+    }
+
+    let backup = create_backup(pool, PRE_PURGE_LABEL).await?;
+    let backup_file = backup
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string());
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let mut delete = QueryBuilder::<Sqlite>::new("DELETE FROM datasets WHERE id");
+    push_ids(&mut delete, &ids);
+    delete
+        .build()
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to delete datasets: {}", e))?;
+
+    record_audit(
+        &mut *tx,
+        RETENTION_AUDIT_ACTION,
+        None,
+        &serde_json::json!({
+            "dataset_ids": ids,
+            "skipped_dataset_ids": skipped_dataset_ids,
+            "backup_file": backup_file,
+        }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    drop(guards);
+
+    Ok(PurgeResult {
+        deleted_dataset_ids: ids,
+        skipped_dataset_ids,
+        backup_file,
+    })
+}
+
+/// Applies the policy when automatic purging is enabled; run by the maintenance task.
+pub async fn purge_if_enabled(pool: &SqlitePool, locks: &DatasetLocks) -> Result<(), String> {
+    let settings = load_settings(pool)
+        .await
+        .map_err(|e| format!("Failed to load retention settings: {}", e))?;
+    if settings.enabled {
+        apply_retention_policy(pool, locks).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_preview_lists_datasets_beyond_the_kept_periods() {
+        let pool = test_pool().await;
+        sqlx::query(
+            "INSERT INTO datasets (id, name, period_year, period_label, created_at, updated_at) VALUES
+                (1, 'Ganjil 2023', 2023, 'Semester 1', datetime('now'), datetime('now')),
+                (2, 'Genap 2023', 2023, 'Semester 2', datetime('now'), datetime('now')),
+                (3, 'Ganjil 2024', 2024, 'Semester 1', datetime('now'), datetime('now')),
+                (4, 'Ganjil 2024 ulang', 2024, 'Semester 1', datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, created_at, updated_at)
+                VALUES (1, 'Budi', datetime('now'), datetime('now'));
+             INSERT INTO competencies (id, name, display_order) VALUES (1, 'Kerjasama', 0);
+             INSERT INTO scores (employee_id, dataset_id, competency_id, raw_value, numeric_value, created_at)
+                VALUES (1, 1, 1, 'Baik', 75, datetime('now'));",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert!(save_settings(&pool, false, 0).await.is_err());
+        save_settings(&pool, false, 2).await.unwrap();
+        let candidates = preview_purge(&pool).await.unwrap();
+        let ids: Vec<i64> = candidates.iter().map(|c| c.dataset.id).collect();
+        assert_eq!(ids, vec![1]);
+        assert_eq!(candidates[0].score_count, 1);
+
+        // Disabled, so the maintenance task leaves everything in place
+        purge_if_enabled(&pool, &DatasetLocks::default())
+            .await
+            .unwrap();
+        let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM datasets")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, 4);
+    }
+
+    #[tokio::test]
+    async fn test_purge_skips_locked_datasets_and_keeps_employees() {
+        let directory = tempfile::tempdir().unwrap();
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(&format!(
+                "sqlite:{}?mode=rwc",
+                directory.path().join("epa.db").display()
+            ))
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        // Budi was only assessed in 2022, Sari in 2022 and 2024
+        sqlx::query(
+            "INSERT INTO datasets (id, name, period_year, period_label, created_at, updated_at) VALUES
+                (1, 'Ganjil 2022', 2022, 'Semester 1', datetime('now'), datetime('now')),
+                (2, 'Genap 2022', 2022, 'Semester 2', datetime('now'), datetime('now')),
+                (3, 'Ganjil 2024', 2024, 'Semester 1', datetime('now'), datetime('now'));
+             INSERT INTO employees (id, name, created_at, updated_at) VALUES
+                (1, 'Budi', datetime('now'), datetime('now')),
+                (2, 'Sari', datetime('now'), datetime('now'));
+             INSERT INTO dataset_employees (dataset_id, employee_id, created_at, updated_at) VALUES
+                (1, 1, datetime('now'), datetime('now')),
+                (1, 2, datetime('now'), datetime('now')),
+                (3, 2, datetime('now'), datetime('now'));",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let source = directory.path().join("SK.txt");
+        std::fs::write(&source, "surat keputusan").unwrap();
+        let attachment = crate::attachments::add_attachment(&pool, 1, &source)
+            .await
+            .unwrap();
+        let attachment_file = directory
+            .path()
+            .join("attachments")
+            .join(&attachment.stored_path);
+        assert!(attachment_file.is_file());
+        save_settings(&pool, true, 1).await.unwrap();
+
+        let locks = DatasetLocks::default();
+        let import = locks.acquire("append", &[2]).unwrap();
+        let result = apply_retention_policy(&pool, &locks).await.unwrap();
+        drop(import);
+        let remaining: Vec<i64> = sqlx::query_scalar("SELECT id FROM datasets ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        let employees: Vec<i64> = sqlx::query_scalar("SELECT id FROM employees ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        let audited: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM audit_log WHERE action IN (?, 'retention_settings_updated')",
+        )
+        .bind(RETENTION_AUDIT_ACTION)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        pool.close().await;
+        let attachment_kept = attachment_file.exists();
+
+        assert_eq!(result.deleted_dataset_ids, vec![1]);
+        assert_eq!(result.skipped_dataset_ids, vec![2]);
+        assert!(result.backup_file.is_some());
+        assert_eq!(remaining, vec![2, 3]);
+        assert_eq!(employees, vec![1, 2]);
+        assert!(attachment_kept);
+        assert_eq!(audited, 2);
+    }
+}
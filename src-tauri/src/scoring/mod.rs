@@ -0,0 +1,426 @@
+use crate::commands::analytics::{EmployeePerformance, ScoreWithCompetency};
+use crate::ui_config::UiConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use unicode_normalization::UnicodeNormalization;
+use weighting::{CompetencyWeight, ParameterWeight, WeightingScheme};
+
+pub mod position;
+pub mod weighting;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreComponent {
+    pub parameter: String,
+    pub raw_score: f64,
+    pub weight_percentage: f64,
+    pub weighted_score: f64,
+}
+
+#[derive(Clone)]
+struct ComponentResult {
+    subtotal: f64,
+    breakdown: Vec<ScoreComponent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeadershipScoreResult {
+    pub raw_score: f64,
+    pub weighted_score: f64,
+    pub applied: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentSection {
+    pub title: String,
+    pub cap: f64,
+    pub subtotal: f64,
+    pub breakdown: Vec<ScoreComponent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompetencyScore {
+    pub competency_id: i64,
+    pub name: String,
+    pub raw_score: f64,
+    pub original_score: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PositionType {
+    Eselon,
+    Staff,
+}
+
+impl PositionType {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PositionType::Eselon => "eselon",
+            PositionType::Staff => "staff",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label.trim().to_lowercase().as_str() {
+            "eselon" => Some(PositionType::Eselon),
+            "staff" => Some(PositionType::Staff),
+            _ => None,
+        }
+    }
+}
+
+const DEFAULT_LEADERSHIP_SCORE: f64 = 80.0;
+
+/// Full weighted score of one employee: the same figures the PDF report prints.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeightedScore {
+    pub position_type: PositionType,
+    pub normalization_scale: f64,
+    pub competencies: Vec<CompetencyScore>,
+    pub sections: Vec<ComponentSection>,
+    pub leadership: Option<LeadershipScoreResult>,
+    pub total_score: f64,
+    pub total_cap: f64,
+    pub rating: String,
+    /// Scored competencies that no parameter picked up, so they do not count towards the total
+    pub unmapped_competencies: Vec<String>,
+}
+
+pub fn compute_weighted_score(
+    performance: &EmployeePerformance,
+    config: &UiConfig,
+    scheme: &WeightingScheme,
+) -> WeightedScore {
+    let (competencies, normalization_scale) =
+        normalize_competencies(&performance.scores, performance.score_scale);
+    let position_type = performance.position_type;
+    let competency_weights: Vec<&CompetencyWeight> = scheme
+        .competency_weights
+        .iter()
+        .filter(|weight| weight.position_type == position_type)
+        .collect();
+    let attendance = scheme
+        .attendance_parameter
+        .as_deref()
+        .zip(performance.attendance_percentage);
+    let mut used = HashSet::new();
+
+    let perilaku = calculate_section(
+        &competencies,
+        &scheme.perilaku,
+        &competency_weights,
+        attendance,
+        position_type,
+        scheme.sections.perilaku_cap,
+        &mut used,
+    );
+    let kualitas = calculate_section(
+        &competencies,
+        &scheme.kualitas,
+        &competency_weights,
+        attendance,
+        position_type,
+        scheme.sections.kualitas_cap(position_type),
+        &mut used,
+    );
+    let unmapped_competencies = competencies
+        .iter()
+        .filter(|score| !used.contains(&score.competency_id))
+        .map(|score| score.name.clone())
+        .collect();
+    let has_performance_data =
+        !competencies.is_empty() && (perilaku.subtotal > 0.0 || kualitas.subtotal > 0.0);
+    let leadership = compute_leadership_score(
+        position_type,
+        has_performance_data,
+        performance.leadership_score,
+        scheme.sections.leadership_cap,
+    );
+    let total_score = calculate_total_score(
+        position_type,
+        &perilaku,
+        &kualitas,
+        leadership.as_ref(),
+        scheme.sections.total_cap,
+    );
+
+    let mut sections = vec![
+        ComponentSection {
+            title: format!("Perilaku Kerja ({}%)", scheme.sections.perilaku_share),
+            cap: scheme.sections.perilaku_cap,
+            subtotal: perilaku.subtotal,
+            breakdown: perilaku.breakdown,
+        },
+        ComponentSection {
+            title: "Kualitas Kerja".to_string(),
+            cap: scheme.sections.kualitas_cap(position_type),
+            subtotal: kualitas.subtotal,
+            breakdown: kualitas.breakdown,
+        },
+    ];
+
+    if let Some(leader) = &leadership {
+        sections.push(ComponentSection {
+            title: "Penilaian Pimpinan".to_string(),
+            cap: scheme.sections.leadership_cap,
+            subtotal: leader.weighted_score,
+            breakdown: vec![ScoreComponent {
+                parameter: if leader.applied {
+                    "Nilai Pimpinan"
+                } else {
+                    "Tidak diaplikasikan"
+                }
+                .to_string(),
+                raw_score: leader.raw_score,
+                weight_percentage: scheme.sections.leadership_share,
+                weighted_score: leader.weighted_score,
+            }],
+        });
+    }
+
+    WeightedScore {
+        position_type,
+        normalization_scale,
+        competencies,
+        sections,
+        leadership,
+        total_score,
+        total_cap: scheme.sections.total_cap,
+        rating: config.rating_for(total_score),
+        unmapped_competencies,
+    }
+}
+
+/// Scores as 0-100 against `score_scale`, or against a scale guessed from the highest score.
+fn normalize_competencies(
+    scores: &[ScoreWithCompetency],
+    score_scale: Option<f64>,
+) -> (Vec<CompetencyScore>, f64) {
+    let original_values: Vec<f64> = scores.iter().map(parse_numeric_score).collect();
+    let normalization_scale = score_scale.unwrap_or_else(|| determine_scale(&original_values));
+
+    let competencies = scores
+        .iter()
+        .zip(original_values.iter())
+        .map(|(entry, original)| {
+            let normalized = if normalization_scale <= 0.0 {
+                0.0
+            } else {
+                ((original / normalization_scale) * 100.0).clamp(0.0, 100.0)
+            };
+
+            CompetencyScore {
+                competency_id: entry.competency.id,
+                name: entry.competency.name.clone(),
+                raw_score: normalized,
+                original_score: *original,
+            }
+        })
+        .collect();
+
+    (competencies, normalization_scale)
+}
+
+fn parse_numeric_score(score: &ScoreWithCompetency) -> f64 {
+    if let Some(value) = score.score.numeric_value {
+        if value.is_finite() {
+            return value;
+        }
+    }
+    score
+        .score
+        .raw_value
+        .replace(',', ".")
+        .parse::<f64>()
+        .unwrap_or(0.0)
+}
+
+fn determine_scale(values: &[f64]) -> f64 {
+    let max = values
+        .iter()
+        .copied()
+        .fold(0.0_f64, |current, value| current.max(value));
+    if max <= 0.0 {
+        100.0
+    } else if max <= 5.0 {
+        4.0
+    } else if max <= 10.0 {
+        10.0
+    } else if max <= 20.0 {
+        20.0
+    } else if max <= 100.0 {
+        100.0
+    } else {
+        max
+    }
+}
+
+/// Scores each parameter from its mapped competencies, or from the first competency its
+/// aliases match when nothing is mapped to it. Competencies that count are added to `used`.
+/// `attendance` names the parameter scored from the attendance percentage instead.
+fn calculate_section(
+    scores: &[CompetencyScore],
+    parameters: &[ParameterWeight],
+    competency_weights: &[&CompetencyWeight],
+    attendance: Option<(&str, f64)>,
+    position_type: PositionType,
+    cap: f64,
+    used: &mut HashSet<i64>,
+) -> ComponentResult {
+    let breakdown: Vec<ScoreComponent> = parameters
+        .iter()
+        .map(|param| {
+            if let Some((_, percentage)) =
+                attendance.filter(|(parameter, _)| *parameter == param.parameter)
+            {
+                return to_component(
+                    &param.parameter,
+                    clamp_score(percentage),
+                    param.weight_for(position_type),
+                );
+            }
+            let mapped: Vec<&CompetencyWeight> = competency_weights
+                .iter()
+                .copied()
+                .filter(|weight| weight.parameter_id == param.id)
+                .collect();
+            if mapped.is_empty() {
+                let unmapped = scores.iter().filter(|score| {
+                    competency_weights
+                        .iter()
+                        .all(|weight| weight.competency_id != score.competency_id)
+                });
+                let raw = match find_competency_score(unmapped, &param.parameter, &param.aliases) {
+                    Some(score) => {
+                        used.insert(score.competency_id);
+                        clamp_score(score.raw_score)
+                    }
+                    None => 0.0,
+                };
+                return to_component(&param.parameter, raw, param.weight_for(position_type));
+            }
+
+            // The parameter weighs as much as its competencies together; its score is their
+            // weighted mean, with missing scores counting as zero
+            let total_weight: f64 = mapped.iter().map(|weight| weight.weight).sum();
+            let weighted_sum: f64 = mapped
+                .iter()
+                .filter_map(|weight| {
+                    let score = scores
+                        .iter()
+                        .find(|score| score.competency_id == weight.competency_id)?;
+                    used.insert(score.competency_id);
+                    Some(clamp_score(score.raw_score) * weight.weight)
+                })
+                .sum();
+            let raw = if total_weight > 0.0 {
+                weighted_sum / total_weight
+            } else {
+                0.0
+            };
+            to_component(&param.parameter, raw, total_weight)
+        })
+        .collect();
+
+    let subtotal = breakdown
+        .iter()
+        .map(|component| component.weighted_score)
+        .sum::<f64>()
+        .min(cap);
+
+    ComponentResult {
+        subtotal,
+        breakdown,
+    }
+}
+
+fn compute_leadership_score(
+    position_type: PositionType,
+    has_performance_data: bool,
+    override_score: Option<f64>,
+    leadership_cap: f64,
+) -> Option<LeadershipScoreResult> {
+    if !matches!(position_type, PositionType::Eselon) {
+        return None;
+    }
+
+    if !has_performance_data {
+        return Some(LeadershipScoreResult {
+            raw_score: 0.0,
+            weighted_score: 0.0,
+            applied: false,
+        });
+    }
+
+    let raw = clamp_score(override_score.unwrap_or(DEFAULT_LEADERSHIP_SCORE));
+    Some(LeadershipScoreResult {
+        raw_score: raw,
+        weighted_score: raw * leadership_cap / 100.0,
+        applied: true,
+    })
+}
+
+fn calculate_total_score(
+    position_type: PositionType,
+    perilaku: &ComponentResult,
+    kualitas: &ComponentResult,
+    leadership: Option<&LeadershipScoreResult>,
+    total_cap: f64,
+) -> f64 {
+    let leadership_contrib = if matches!(position_type, PositionType::Eselon) {
+        leadership.map(|s| s.weighted_score).unwrap_or(0.0)
+    } else {
+        0.0
+    };
+
+    (perilaku.subtotal + kualitas.subtotal + leadership_contrib).min(total_cap)
+}
+
+fn to_component(parameter: &str, raw_score: f64, weight_percentage: f64) -> ScoreComponent {
+    ScoreComponent {
+        parameter: parameter.to_string(),
+        raw_score,
+        weight_percentage,
+        weighted_score: (raw_score * weight_percentage) / 100.0,
+    }
+}
+
+fn find_competency_score<'a>(
+    scores: impl Iterator<Item = &'a CompetencyScore>,
+    parameter: &str,
+    aliases: &[String],
+) -> Option<&'a CompetencyScore> {
+    let mut targets: Vec<String> = Vec::with_capacity(1 + aliases.len());
+    targets.push(normalize_text(parameter));
+    for alias in aliases {
+        targets.push(normalize_text(alias));
+    }
+
+    for score in scores {
+        let normalized_name = normalize_text(&score.name);
+        if targets.iter().any(|token| normalized_name.contains(token)) {
+            return Some(score);
+        }
+    }
+
+    None
+}
+
+fn normalize_text(value: &str) -> String {
+    value
+        .nfd()
+        .filter(|c| !unicode_normalization::char::is_combining_mark(*c))
+        .collect::<String>()
+        .to_lowercase()
+        .chars()
+        .filter(|ch| ch.is_ascii_alphabetic() || ch.is_whitespace())
+        .collect()
+}
+
+fn clamp_score(value: f64) -> f64 {
+    if !value.is_finite() {
+        0.0
+    } else {
+        value.clamp(0.0, 100.0)
+    }
+}
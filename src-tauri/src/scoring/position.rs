@@ -0,0 +1,184 @@
+use super::PositionType;
+use crate::commands::analytics::sanitize_text;
+use crate::db::models::Employee;
+use crate::db::settings::{get_setting, set_setting};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+const POSITION_KEYWORDS_KEY: &str = "classification.position_keywords";
+
+const DEFAULT_ESELON_KEYWORDS: &[&str] = &[
+    "eselon",
+    "kepala",
+    "sekretaris",
+    "kabid",
+    "kabag",
+    "kasubag",
+    "kepala seksi",
+    "kasi",
+    "koordinator",
+    "pengawas",
+    "sub bagian",
+    "subbagian",
+    "subbidang",
+    "sub bidang",
+];
+
+const DEFAULT_STAFF_KEYWORDS: &[&str] = &["staff", "staf"];
+
+/// Keywords matched against jabatan and sub jabatan to tell Eselon from Staff. Staff
+/// keywords win when both match; golongan IV is the fallback for Eselon.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PositionKeywords {
+    pub eselon: Vec<String>,
+    pub staff: Vec<String>,
+}
+
+impl Default for PositionKeywords {
+    fn default() -> Self {
+        Self {
+            eselon: DEFAULT_ESELON_KEYWORDS
+                .iter()
+                .map(|k| k.to_string())
+                .collect(),
+            staff: DEFAULT_STAFF_KEYWORDS
+                .iter()
+                .map(|k| k.to_string())
+                .collect(),
+        }
+    }
+}
+
+fn clean_keywords(keywords: Vec<String>) -> Vec<String> {
+    let mut cleaned: Vec<String> = keywords
+        .iter()
+        .map(|keyword| sanitize_text(keyword))
+        .filter(|keyword| !keyword.is_empty())
+        .collect();
+    cleaned.sort();
+    cleaned.dedup();
+    cleaned
+}
+
+impl PositionKeywords {
+    /// Honors the employee's manual override before falling back to the keywords.
+    pub fn classify(&self, employee: &Employee) -> PositionType {
+        employee
+            .position_type
+            .as_deref()
+            .and_then(PositionType::from_label)
+            .unwrap_or_else(|| {
+                self.classify_role(
+                    employee.jabatan.as_deref(),
+                    employee.sub_jabatan.as_deref(),
+                    employee.gol.as_deref(),
+                )
+            })
+    }
+
+    pub fn classify_role(
+        &self,
+        jabatan: Option<&str>,
+        sub_jabatan: Option<&str>,
+        gol: Option<&str>,
+    ) -> PositionType {
+        let normalized = sanitize_text(&format!(
+            "{} {}",
+            jabatan.unwrap_or_default(),
+            sub_jabatan.unwrap_or_default()
+        ));
+
+        if !normalized.is_empty() {
+            let matches = |keywords: &[String]| {
+                keywords
+                    .iter()
+                    .any(|keyword| normalized.contains(&sanitize_text(keyword)))
+            };
+            if matches(&self.staff) {
+                return PositionType::Staff;
+            }
+            if matches(&self.eselon) {
+                return PositionType::Eselon;
+            }
+        }
+
+        if gol
+            .unwrap_or_default()
+            .trim()
+            .to_uppercase()
+            .starts_with("IV")
+        {
+            PositionType::Eselon
+        } else {
+            PositionType::Staff
+        }
+    }
+}
+
+pub async fn load_position_keywords(pool: &SqlitePool) -> Result<PositionKeywords, sqlx::Error> {
+    Ok(get_setting(pool, POSITION_KEYWORDS_KEY)
+        .await?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default())
+}
+
+/// Stores the keyword lists normalized the same way jabatan text is before matching.
+pub async fn save_position_keywords(
+    pool: &SqlitePool,
+    keywords: PositionKeywords,
+) -> Result<PositionKeywords, String> {
+    let keywords = PositionKeywords {
+        eselon: clean_keywords(keywords.eselon),
+        staff: clean_keywords(keywords.staff),
+    };
+    if keywords.eselon.is_empty() {
+        return Err("At least one Eselon keyword is required".to_string());
+    }
+
+    let value = serde_json::to_string(&keywords)
+        .map_err(|e| format!("Failed to serialize position keywords: {}", e))?;
+    set_setting(pool, POSITION_KEYWORDS_KEY, &value)
+        .await
+        .map_err(|e| format!("Failed to save position keywords: {}", e))?;
+    Ok(keywords)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_prefers_override_then_keywords() {
+        let keywords = PositionKeywords {
+            eselon: vec!["kepala".to_string(), "penata".to_string()],
+            staff: vec!["staf".to_string()],
+        };
+        assert_eq!(
+            keywords.classify_role(Some("Penata Laporan"), None, Some("III/c")),
+            PositionType::Eselon
+        );
+        assert_eq!(
+            keywords.classify_role(Some("Staf Kepala Seksi"), None, None),
+            PositionType::Staff
+        );
+        assert_eq!(
+            keywords.classify_role(Some("Analis"), None, Some("IV/a")),
+            PositionType::Eselon
+        );
+
+        let employee = Employee {
+            id: 1,
+            name: "Budi".to_string(),
+            nip: None,
+            gol: None,
+            jabatan: Some("Kepala Seksi".to_string()),
+            sub_jabatan: None,
+            position_type: Some("staff".to_string()),
+            photo_path: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+        };
+        assert_eq!(keywords.classify(&employee), PositionType::Staff);
+    }
+}
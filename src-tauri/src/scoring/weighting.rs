@@ -0,0 +1,550 @@
+use super::PositionType;
+use crate::db::settings::{get_setting, set_setting};
+use crate::ui_config::RatingBand;
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, Sqlite, SqlitePool};
+
+const WEIGHTING_KEY: &str = "scoring.weighting";
+/// Marks files written by `export_weighting_config`.
+pub const WEIGHTING_CONFIG_FORMAT: &str = "employee-performance-weighting";
+
+/// Parameter, Eselon weight, Staff weight and the competency name fragments it matches.
+type DefaultParameter = (&'static str, f64, f64, &'static [&'static str]);
+
+const DEFAULT_PERILAKU: &[DefaultParameter] = &[
+    (
+        "Inisiatif dan fleksibilitas",
+        5.0,
+        5.0,
+        &["inisiatif", "initiative", "fleksibilitas", "flexibility"],
+    ),
+    (
+        "Kehadiran dan ketepatan waktu",
+        5.0,
+        5.0,
+        &[
+            "kehadiran",
+            "ketepatan waktu",
+            "attendance",
+            "punctuality",
+            "absensi",
+        ],
+    ),
+    (
+        "Kerjasama dan team work",
+        5.0,
+        5.0,
+        &["kerjasama", "team work", "teamwork", "kolaborasi", "team"],
+    ),
+    (
+        "Manajemen waktu kerja",
+        5.0,
+        5.0,
+        &["manajemen waktu", "time management"],
+    ),
+    (
+        "Kepemimpinan",
+        10.0,
+        10.0,
+        &["kepemimpinan", "leadership", "leader"],
+    ),
+];
+
+const DEFAULT_KUALITAS: &[DefaultParameter] = &[
+    (
+        "Kualitas kinerja",
+        25.5,
+        42.5,
+        &["kualitas kinerja", "kinerja", "quality of work", "quality"],
+    ),
+    (
+        "Kemampuan berkomunikasi",
+        8.5,
+        8.5,
+        &["komunikasi", "communication"],
+    ),
+    (
+        "Pemahaman tentang permasalahan sosial",
+        8.5,
+        8.5,
+        &[
+            "permasalahan sosial",
+            "social issues",
+            "social problem",
+            "pemahaman sosial",
+        ],
+    ),
+];
+
+/// A report parameter: its weight (percent of the normalized 0-100 score) per position type
+/// and the fragments matched against competency names.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParameterWeight {
+    /// Stable key `competency_weights` refer to, so renaming the parameter keeps its mapped
+    /// competencies; it starts out as the lowercased name.
+    #[serde(default)]
+    pub id: String,
+    pub parameter: String,
+    pub eselon_weight: f64,
+    pub staff_weight: f64,
+    pub aliases: Vec<String>,
+}
+
+impl ParameterWeight {
+    pub fn weight_for(&self, position_type: PositionType) -> f64 {
+        match position_type {
+            PositionType::Eselon => self.eselon_weight,
+            PositionType::Staff => self.staff_weight,
+        }
+    }
+}
+
+/// Caps of the weighted score's sections and total, in points of the 0-100 score, and the
+/// share of each section printed on the report form.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SectionWeights {
+    pub perilaku_cap: f64,
+    pub kualitas_cap_eselon: f64,
+    pub kualitas_cap_staff: f64,
+    /// Also the weight of the leadership score in an Eselon total
+    pub leadership_cap: f64,
+    pub total_cap: f64,
+    pub perilaku_share: f64,
+    pub kualitas_share: f64,
+    pub leadership_share: f64,
+}
+
+impl Default for SectionWeights {
+    fn default() -> Self {
+        Self {
+            perilaku_cap: 25.5,
+            kualitas_cap_eselon: 42.5,
+            kualitas_cap_staff: 70.0,
+            leadership_cap: 17.0,
+            total_cap: 85.0,
+            perilaku_share: 30.0,
+            kualitas_share: 50.0,
+            leadership_share: 20.0,
+        }
+    }
+}
+
+impl SectionWeights {
+    pub fn kualitas_cap(&self, position_type: PositionType) -> f64 {
+        match position_type {
+            PositionType::Eselon => self.kualitas_cap_eselon,
+            PositionType::Staff => self.kualitas_cap_staff,
+        }
+    }
+
+    /// Perilaku, Kualitas and leadership shares, in report order.
+    pub fn shares(&self) -> [f64; 3] {
+        [
+            self.perilaku_share,
+            self.kualitas_share,
+            self.leadership_share,
+        ]
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let values = [
+            self.perilaku_cap,
+            self.kualitas_cap_eselon,
+            self.kualitas_cap_staff,
+            self.leadership_cap,
+            self.total_cap,
+            self.perilaku_share,
+            self.kualitas_share,
+            self.leadership_share,
+        ];
+        if values
+            .iter()
+            .any(|value| !value.is_finite() || !(0.0..=100.0).contains(value))
+        {
+            return Err("Section caps and shares must be between 0 and 100".to_string());
+        }
+        if (self.shares().iter().sum::<f64>() - 100.0).abs() > 1e-9 {
+            return Err("Section shares must add up to 100%".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Parameters of the Perilaku Kerja and Kualitas Kerja sections of the weighted score.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WeightingScheme {
+    pub perilaku: Vec<ParameterWeight>,
+    pub kualitas: Vec<ParameterWeight>,
+    pub sections: SectionWeights,
+    /// Perilaku or Kualitas parameter scored from attendance data instead of survey answers,
+    /// e.g. "Kehadiran dan ketepatan waktu". Employees without attendance keep the survey score.
+    pub attendance_parameter: Option<String>,
+    /// Rows of `competency_weights`. Competency ids only mean something in this database,
+    /// so they stay out of exported weighting configs.
+    #[serde(skip)]
+    pub competency_weights: Vec<CompetencyWeight>,
+}
+
+/// A competency counted towards a report parameter with its own weight, instead of being
+/// matched by the parameter's aliases.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompetencyWeight {
+    pub competency_id: i64,
+    pub competency_name: String,
+    pub position_type: PositionType,
+    /// `ParameterWeight::id` of the parameter the competency counts towards
+    pub parameter_id: String,
+    /// Percent of the normalized 0-100 score the competency contributes. A parameter with
+    /// mapped competencies weighs their sum in place of its own weight and scores their
+    /// weighted mean.
+    pub weight: f64,
+}
+
+fn parameter_id(parameter: &str) -> String {
+    parameter.trim().to_lowercase()
+}
+
+fn default_parameters(parameters: &[DefaultParameter]) -> Vec<ParameterWeight> {
+    parameters
+        .iter()
+        .map(
+            |(parameter, eselon_weight, staff_weight, aliases)| ParameterWeight {
+                id: parameter_id(parameter),
+                parameter: parameter.to_string(),
+                eselon_weight: *eselon_weight,
+                staff_weight: *staff_weight,
+                aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+            },
+        )
+        .collect()
+}
+
+impl Default for WeightingScheme {
+    fn default() -> Self {
+        Self {
+            perilaku: default_parameters(DEFAULT_PERILAKU),
+            kualitas: default_parameters(DEFAULT_KUALITAS),
+            sections: SectionWeights::default(),
+            attendance_parameter: None,
+            competency_weights: Vec::new(),
+        }
+    }
+}
+
+impl WeightingScheme {
+    /// Parameter names, Perilaku first.
+    pub fn parameter_names(&self) -> impl Iterator<Item = &str> {
+        self.perilaku
+            .iter()
+            .chain(&self.kualitas)
+            .map(|param| param.parameter.as_str())
+    }
+
+    /// The parameter with id `key`, or named `key` ignoring case.
+    pub fn find_parameter(&self, key: &str) -> Option<&ParameterWeight> {
+        let key = key.trim();
+        self.perilaku
+            .iter()
+            .chain(&self.kualitas)
+            .find(|param| param.id == key || param.parameter.eq_ignore_ascii_case(key))
+    }
+}
+
+/// Shareable weighting file: the scheme plus the rating bands applied to its totals.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeightingConfigFile {
+    pub format: String,
+    pub weighting: WeightingScheme,
+    pub rating_bands: Vec<RatingBand>,
+}
+
+pub async fn load_weighting_scheme(pool: &SqlitePool) -> Result<WeightingScheme, sqlx::Error> {
+    let mut scheme: WeightingScheme = get_setting(pool, WEIGHTING_KEY)
+        .await?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default();
+    // Schemes saved before parameters had ids
+    for param in scheme.perilaku.iter_mut().chain(&mut scheme.kualitas) {
+        if param.id.is_empty() {
+            param.id = parameter_id(&param.parameter);
+        }
+    }
+    Ok(WeightingScheme {
+        competency_weights: load_competency_weights(pool).await?,
+        ..scheme
+    })
+}
+
+pub async fn load_competency_weights(
+    pool: &SqlitePool,
+) -> Result<Vec<CompetencyWeight>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, (i64, String, String, String, f64)>(
+        "SELECT cw.competency_id, c.name, cw.position_type, cw.parameter_id, cw.weight
+         FROM competency_weights cw
+         JOIN competencies c ON c.id = cw.competency_id
+         ORDER BY cw.position_type, c.display_order, c.name",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(
+            |(competency_id, competency_name, position_type, parameter_id, weight)| {
+                Some(CompetencyWeight {
+                    competency_id,
+                    competency_name,
+                    position_type: PositionType::from_label(&position_type)?,
+                    parameter_id,
+                    weight,
+                })
+            },
+        )
+        .collect())
+}
+
+/// Maps a competency onto one of the scheme's parameters, given by id or name, for a position
+/// type, replacing any earlier mapping of that competency.
+pub async fn save_competency_weight(
+    pool: &SqlitePool,
+    competency_id: i64,
+    position_type: PositionType,
+    parameter: &str,
+    weight: f64,
+) -> Result<Vec<CompetencyWeight>, String> {
+    if !weight.is_finite() || !(0.0..=100.0).contains(&weight) {
+        return Err("Weight must be between 0 and 100".to_string());
+    }
+    let scheme = load_weighting_scheme(pool)
+        .await
+        .map_err(|e| format!("Failed to load weighting scheme: {}", e))?;
+    let parameter = scheme
+        .find_parameter(parameter)
+        .ok_or_else(|| format!("Unknown report parameter: {}", parameter))?;
+
+    sqlx::query(
+        "INSERT INTO competency_weights (competency_id, position_type, parameter_id, weight)
+         VALUES (?, ?, ?, ?)
+         ON CONFLICT(competency_id, position_type)
+         DO UPDATE SET parameter_id = excluded.parameter_id, weight = excluded.weight",
+    )
+    .bind(competency_id)
+    .bind(position_type.label())
+    .bind(&parameter.id)
+    .bind(weight)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to save competency weight: {}", e))?;
+
+    load_competency_weights(pool)
+        .await
+        .map_err(|e| format!("Failed to load competency weights: {}", e))
+}
+
+pub async fn delete_competency_weight(
+    pool: &SqlitePool,
+    competency_id: i64,
+    position_type: PositionType,
+) -> Result<Vec<CompetencyWeight>, String> {
+    sqlx::query("DELETE FROM competency_weights WHERE competency_id = ? AND position_type = ?")
+        .bind(competency_id)
+        .bind(position_type.label())
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to delete competency weight: {}", e))?;
+
+    load_competency_weights(pool)
+        .await
+        .map_err(|e| format!("Failed to load competency weights: {}", e))
+}
+
+fn clean_parameters(
+    section: &str,
+    parameters: Vec<ParameterWeight>,
+) -> Result<Vec<ParameterWeight>, String> {
+    if parameters.is_empty() {
+        return Err(format!(
+            "The {} section needs at least one parameter",
+            section
+        ));
+    }
+    parameters
+        .into_iter()
+        .map(|param| {
+            let parameter = param.parameter.trim().to_string();
+            if parameter.is_empty() {
+                return Err(format!("A {} parameter has no name", section));
+            }
+            if [param.eselon_weight, param.staff_weight]
+                .iter()
+                .any(|weight| !weight.is_finite() || !(0.0..=100.0).contains(weight))
+            {
+                return Err(format!(
+                    "Weights of {} must be between 0 and 100",
+                    parameter
+                ));
+            }
+            let mut aliases: Vec<String> = param
+                .aliases
+                .iter()
+                .map(|alias| alias.trim().to_lowercase())
+                .filter(|alias| !alias.is_empty())
+                .collect();
+            aliases.sort();
+            aliases.dedup();
+            let id = match param.id.trim() {
+                "" => parameter_id(&parameter),
+                id => id.to_string(),
+            };
+            Ok(ParameterWeight {
+                id,
+                parameter,
+                eselon_weight: param.eselon_weight,
+                staff_weight: param.staff_weight,
+                aliases,
+            })
+        })
+        .collect()
+}
+
+pub async fn save_weighting_scheme<'e, E>(
+    executor: E,
+    scheme: WeightingScheme,
+) -> Result<WeightingScheme, String>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    scheme.sections.validate()?;
+    let mut cleaned = WeightingScheme {
+        perilaku: clean_parameters("Perilaku Kerja", scheme.perilaku)?,
+        kualitas: clean_parameters("Kualitas Kerja", scheme.kualitas)?,
+        sections: scheme.sections,
+        attendance_parameter: None,
+        competency_weights: scheme.competency_weights,
+    };
+    let mut ids: Vec<&str> = cleaned
+        .perilaku
+        .iter()
+        .chain(&cleaned.kualitas)
+        .map(|param| param.id.as_str())
+        .collect();
+    ids.sort_unstable();
+    if let Some(pair) = ids.windows(2).find(|pair| pair[0] == pair[1]) {
+        return Err(format!("Two report parameters share the id {}", pair[0]));
+    }
+    if let Some(parameter) = scheme
+        .attendance_parameter
+        .as_deref()
+        .map(str::trim)
+        .filter(|parameter| !parameter.is_empty())
+    {
+        let known = cleaned
+            .parameter_names()
+            .find(|name| name.eq_ignore_ascii_case(parameter))
+            .ok_or_else(|| format!("Unknown report parameter: {}", parameter))?
+            .to_string();
+        cleaned.attendance_parameter = Some(known);
+    }
+    let scheme = cleaned;
+
+    let value = serde_json::to_string(&scheme)
+        .map_err(|e| format!("Failed to serialize weighting scheme: {}", e))?;
+    set_setting(executor, WEIGHTING_KEY, &value)
+        .await
+        .map_err(|e| format!("Failed to save weighting scheme: {}", e))?;
+    Ok(scheme)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    #[tokio::test]
+    async fn test_save_weighting_scheme_cleans_aliases() {
+        let pool = test_pool().await;
+
+        let mut scheme = WeightingScheme::default();
+        scheme.kualitas[0].aliases = vec![" Output ".to_string(), "output".to_string()];
+        save_weighting_scheme(&pool, scheme).await.unwrap();
+        let loaded = load_weighting_scheme(&pool).await.unwrap();
+        assert_eq!(loaded.kualitas[0].aliases, vec!["output".to_string()]);
+
+        let empty = WeightingScheme {
+            perilaku: Vec::new(),
+            ..WeightingScheme::default()
+        };
+        assert!(save_weighting_scheme(&pool, empty).await.is_err());
+
+        let mut uneven = WeightingScheme::default();
+        uneven.sections.perilaku_share = 40.0;
+        assert!(save_weighting_scheme(&pool, uneven).await.is_err());
+        let mut capped = WeightingScheme::default();
+        capped.sections.total_cap = 90.0;
+        save_weighting_scheme(&pool, capped).await.unwrap();
+        let loaded = load_weighting_scheme(&pool).await.unwrap();
+        assert_eq!(loaded.sections.total_cap, 90.0);
+
+        // Schemes saved before the caps were configurable get the standard ones
+        set_setting(&pool, WEIGHTING_KEY, r#"{"perilaku": [], "kualitas": []}"#)
+            .await
+            .unwrap();
+        let loaded = load_weighting_scheme(&pool).await.unwrap();
+        assert_eq!(loaded.sections.kualitas_cap(PositionType::Staff), 70.0);
+    }
+
+    #[tokio::test]
+    async fn test_save_competency_weight_upserts_known_parameters() {
+        let pool = test_pool().await;
+        let competency_id: i64 =
+            sqlx::query_scalar("INSERT INTO competencies (name) VALUES ('Output') RETURNING id")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+
+        let unknown =
+            save_competency_weight(&pool, competency_id, PositionType::Staff, "Output", 10.0);
+        assert!(unknown.await.is_err());
+
+        save_competency_weight(
+            &pool,
+            competency_id,
+            PositionType::Staff,
+            "kualitas kinerja",
+            20.0,
+        )
+        .await
+        .unwrap();
+        let weights = save_competency_weight(
+            &pool,
+            competency_id,
+            PositionType::Staff,
+            "Kualitas kinerja",
+            30.0,
+        )
+        .await
+        .unwrap();
+        assert_eq!(weights.len(), 1);
+        assert_eq!(weights[0].parameter_id, "kualitas kinerja");
+        assert_eq!(weights[0].weight, 30.0);
+
+        // Renaming the parameter keeps the competency mapped to it
+        let mut scheme = load_weighting_scheme(&pool).await.unwrap();
+        scheme.kualitas[0].parameter = "Mutu kinerja".to_string();
+        let renamed = save_weighting_scheme(&pool, scheme).await.unwrap();
+        assert_eq!(
+            renamed.find_parameter("Mutu kinerja").unwrap().id,
+            weights[0].parameter_id
+        );
+        assert_eq!(
+            load_weighting_scheme(&pool)
+                .await
+                .unwrap()
+                .competency_weights
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::path::Path;
+
+/// Key file next to the database. It stays out of the database so a copied database or
+/// backup does not carry the key that opens its secrets.
+const KEY_FILE: &str = "secrets.key";
+const KEY_LEN: usize = 32;
+
+fn random_bytes<const N: usize>() -> Result<[u8; N], String> {
+    let mut bytes = [0u8; N];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| "Failed to generate random bytes".to_string())?;
+    Ok(bytes)
+}
+
+/// Loads the key from `directory`, creating it on first use.
+fn load_key(directory: &Path) -> Result<LessSafeKey, String> {
+    let path = directory.join(KEY_FILE);
+    let bytes = if path.exists() {
+        std::fs::read(&path).map_err(|e| format!("Failed to read secret key: {}", e))?
+    } else {
+        let bytes = random_bytes::<KEY_LEN>()?.to_vec();
+        std::fs::write(&path, &bytes).map_err(|e| format!("Failed to write secret key: {}", e))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+                .map_err(|e| format!("Failed to protect secret key: {}", e))?;
+        }
+        bytes
+    };
+    UnboundKey::new(&AES_256_GCM, &bytes)
+        .map(LessSafeKey::new)
+        .map_err(|_| "The secret key file is corrupt".to_string())
+}
+
+/// Encrypts `plaintext` with the key in `directory`; the result is base64 of nonce and
+/// ciphertext, safe to store in app_settings.
+pub fn seal(directory: &Path, plaintext: &str) -> Result<String, String> {
+    let key = load_key(directory)?;
+    let nonce = random_bytes::<NONCE_LEN>()?;
+    let mut sealed = plaintext.as_bytes().to_vec();
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce),
+        Aad::empty(),
+        &mut sealed,
+    )
+    .map_err(|_| "Failed to encrypt secret".to_string())?;
+    Ok(BASE64.encode([nonce.as_slice(), &sealed].concat()))
+}
+
+/// Decrypts a value written by `seal`.
+pub fn open(directory: &Path, sealed: &str) -> Result<String, String> {
+    let key = load_key(directory)?;
+    let bytes = BASE64
+        .decode(sealed)
+        .map_err(|_| "The stored secret is corrupt".to_string())?;
+    if bytes.len() < NONCE_LEN {
+        return Err("The stored secret is corrupt".to_string());
+    }
+    let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce)
+        .map_err(|_| "The stored secret is corrupt".to_string())?;
+    let mut ciphertext = ciphertext.to_vec();
+    let plaintext = key
+        .open_in_place(nonce, Aad::empty(), &mut ciphertext)
+        .map_err(|_| "The stored secret cannot be decrypted with this key".to_string())?;
+    String::from_utf8(plaintext.to_vec()).map_err(|_| "The stored secret is corrupt".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sealed_secret_opens_only_with_its_key() {
+        let directory = tempfile::tempdir().unwrap();
+
+        let sealed = seal(directory.path(), "sk-rahasia").unwrap();
+        assert!(!sealed.contains("sk-rahasia"));
+        assert_eq!(open(directory.path(), &sealed).unwrap(), "sk-rahasia");
+
+        std::fs::remove_file(directory.path().join(KEY_FILE)).unwrap();
+        let result = open(directory.path(), &sealed);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,308 @@
+use crate::commands::analytics::{EmployeeSortField, RankingBasis};
+use crate::db::settings::{get_setting, set_setting};
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
+use std::str::FromStr;
+
+const UI_CONFIG_KEY: &str = "ui.config";
+
+/// Total weighted scores at or above `min_score` (and at most `max_score`, when set) get
+/// `label`; `color` is a `#rrggbb` hex.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RatingBand {
+    pub label: String,
+    pub min_score: f64,
+    #[serde(default)]
+    pub max_score: Option<f64>,
+    pub color: String,
+}
+
+/// Per-competency average thresholds used to color score cells (scores are on a 1-4 scale).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreThresholds {
+    pub good: f64,
+    pub fair: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SortPreference {
+    pub column: String,
+    pub direction: String,
+}
+
+/// How scores are categorized, colored and sorted, shared by the frontend and PDF exports.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiConfig {
+    /// Ordered from the highest `min_score` down
+    pub rating_bands: Vec<RatingBand>,
+    pub score_thresholds: ScoreThresholds,
+    pub default_employee_sort: SortPreference,
+    /// `weighted` or `average`
+    pub default_ranking_basis: String,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        let band = |label: &str, min_score: f64, color: &str| RatingBand {
+            label: label.to_string(),
+            min_score,
+            max_score: None,
+            color: color.to_string(),
+        };
+        Self {
+            rating_bands: vec![
+                band("Sangat Baik", 80.0, "#16a34a"),
+                band("Baik", 70.0, "#2563eb"),
+                band("Kurang Baik", 60.0, "#ca8a04"),
+                band("Perlu Pembinaan", 0.0, "#dc2626"),
+            ],
+            score_thresholds: ScoreThresholds {
+                good: 3.0,
+                fair: 2.0,
+            },
+            default_employee_sort: SortPreference {
+                column: "name".to_string(),
+                direction: "asc".to_string(),
+            },
+            default_ranking_basis: "weighted".to_string(),
+        }
+    }
+}
+
+impl UiConfig {
+    /// Label of the highest band whose minimum the score reaches, so a score between one
+    /// band's maximum and the next band's minimum still gets the lower band; scores below
+    /// every band get the lowest one. `max_score` is only shown, never matched on.
+    pub fn rating_for(&self, total_score: f64) -> String {
+        let by_min = |a: &&RatingBand, b: &&RatingBand| a.min_score.total_cmp(&b.min_score);
+        self.rating_bands
+            .iter()
+            .filter(|band| total_score >= band.min_score)
+            .max_by(by_min)
+            .or_else(|| self.rating_bands.iter().min_by(by_min))
+            .map(|band| band.label.clone())
+            .unwrap_or_default()
+    }
+}
+
+fn is_hex_color(value: &str) -> bool {
+    value.len() == 7
+        && value.starts_with('#')
+        && value[1..].chars().all(|ch| ch.is_ascii_hexdigit())
+}
+
+pub async fn load_ui_config(pool: &SqlitePool) -> Result<UiConfig, sqlx::Error> {
+    Ok(get_setting(pool, UI_CONFIG_KEY)
+        .await?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default())
+}
+
+/// The UI config with the dataset's own rating bands, when it has any, in place of the
+/// global ones.
+pub async fn load_dataset_ui_config(
+    pool: &SqlitePool,
+    dataset_id: i64,
+) -> Result<UiConfig, sqlx::Error> {
+    let mut config = load_ui_config(pool).await?;
+    let bands = load_dataset_rating_bands(pool, dataset_id).await?;
+    if !bands.is_empty() {
+        config.rating_bands = bands;
+    }
+    Ok(config)
+}
+
+/// Bands stored for the dataset, highest first; empty when it uses the global ones.
+pub async fn load_dataset_rating_bands(
+    pool: &SqlitePool,
+    dataset_id: i64,
+) -> Result<Vec<RatingBand>, sqlx::Error> {
+    sqlx::query_as::<_, RatingBand>(
+        "SELECT label, min_score, max_score, color FROM rating_bands
+         WHERE dataset_id = ?
+         ORDER BY min_score DESC",
+    )
+    .bind(dataset_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Replaces the dataset's bands; an empty list makes it use the global bands again.
+pub async fn save_dataset_rating_bands(
+    pool: &SqlitePool,
+    dataset_id: i64,
+    bands: Vec<RatingBand>,
+) -> Result<Vec<RatingBand>, String> {
+    let bands = clean_rating_bands(bands)?;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+    sqlx::query("DELETE FROM rating_bands WHERE dataset_id = ?")
+        .bind(dataset_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to clear rating bands: {}", e))?;
+    for band in &bands {
+        sqlx::query(
+            "INSERT INTO rating_bands (dataset_id, label, min_score, max_score, color)
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(dataset_id)
+        .bind(&band.label)
+        .bind(band.min_score)
+        .bind(band.max_score)
+        .bind(&band.color)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to save rating band: {}", e))?;
+    }
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit rating bands: {}", e))?;
+    Ok(bands)
+}
+
+/// Trims and validates the bands and orders them from the highest minimum down.
+fn clean_rating_bands(mut bands: Vec<RatingBand>) -> Result<Vec<RatingBand>, String> {
+    for band in &mut bands {
+        band.label = band.label.trim().to_string();
+        band.color = band.color.trim().to_lowercase();
+        if band.label.is_empty() {
+            return Err("Rating band label cannot be empty".to_string());
+        }
+        if !band.min_score.is_finite() {
+            return Err(format!(
+                "Rating band {} needs a numeric minimum",
+                band.label
+            ));
+        }
+        if let Some(max_score) = band.max_score {
+            if !max_score.is_finite() || max_score < band.min_score {
+                return Err(format!(
+                    "The maximum of {} cannot be below its minimum",
+                    band.label
+                ));
+            }
+        }
+        if !is_hex_color(&band.color) {
+            return Err(format!("Invalid color for {}: {}", band.label, band.color));
+        }
+    }
+    bands.sort_by(|a, b| b.min_score.total_cmp(&a.min_score));
+    Ok(bands)
+}
+
+pub async fn save_ui_config<'e, E>(executor: E, config: UiConfig) -> Result<UiConfig, String>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let mut config = config;
+    config.rating_bands = clean_rating_bands(config.rating_bands)?;
+    if config.rating_bands.is_empty() {
+        return Err("At least one rating band is required".to_string());
+    }
+
+    if config.score_thresholds.fair > config.score_thresholds.good {
+        return Err("The fair score threshold cannot exceed the good threshold".to_string());
+    }
+
+    let sort = &mut config.default_employee_sort;
+    sort.direction = sort.direction.to_lowercase();
+    if EmployeeSortField::from_str(&sort.column).is_err() {
+        return Err(format!("Unknown sort column: {}", sort.column));
+    }
+    if sort.direction != "asc" && sort.direction != "desc" {
+        return Err(format!("Unknown sort direction: {}", sort.direction));
+    }
+    RankingBasis::from_str(&config.default_ranking_basis)?;
+
+    let value = serde_json::to_string(&config)
+        .map_err(|e| format!("Failed to serialize UI config: {}", e))?;
+    set_setting(executor, UI_CONFIG_KEY, &value)
+        .await
+        .map_err(|e| format!("Failed to save UI config: {}", e))?;
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    #[test]
+    fn test_rating_for_uses_first_band_reached() {
+        let config = UiConfig::default();
+        assert_eq!(config.rating_for(85.0), "Sangat Baik");
+        assert_eq!(config.rating_for(70.0), "Baik");
+        assert_eq!(config.rating_for(-5.0), "Perlu Pembinaan");
+    }
+
+    #[test]
+    fn test_rating_for_leaves_no_gap_between_bands() {
+        let band = |label: &str, min_score: f64, max_score: Option<f64>| RatingBand {
+            label: label.to_string(),
+            min_score,
+            max_score,
+            color: "#000000".to_string(),
+        };
+        let config = UiConfig {
+            rating_bands: vec![
+                band("Kurang Baik", 65.0, Some(69.99)),
+                band("Sangat Baik", 80.0, None),
+                band("Baik", 70.0, Some(79.99)),
+            ],
+            ..UiConfig::default()
+        };
+        assert_eq!(config.rating_for(79.995), "Baik");
+        assert_eq!(config.rating_for(80.0), "Sangat Baik");
+        assert_eq!(config.rating_for(69.999), "Kurang Baik");
+        assert_eq!(config.rating_for(10.0), "Kurang Baik");
+    }
+
+    #[tokio::test]
+    async fn test_dataset_rating_bands_override_global_ones() {
+        let pool = test_pool().await;
+        let dataset_id: i64 =
+            sqlx::query_scalar("INSERT INTO datasets (name) VALUES ('2025') RETURNING id")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+
+        let band = |label: &str, min_score: f64, max_score: Option<f64>| RatingBand {
+            label: label.to_string(),
+            min_score,
+            max_score,
+            color: "#16A34A".to_string(),
+        };
+        let saved = save_dataset_rating_bands(
+            &pool,
+            dataset_id,
+            vec![
+                band("Cukup", 0.0, Some(74.99)),
+                band(" Istimewa ", 75.0, None),
+            ],
+        )
+        .await
+        .unwrap();
+        assert_eq!(saved[0].label, "Istimewa");
+        assert_eq!(saved[0].color, "#16a34a");
+
+        let config = load_dataset_ui_config(&pool, dataset_id).await.unwrap();
+        assert_eq!(config.rating_for(80.0), "Istimewa");
+        assert_eq!(config.rating_for(74.0), "Cukup");
+
+        let invalid = vec![band("Cukup", 50.0, Some(40.0))];
+        assert!(save_dataset_rating_bands(&pool, dataset_id, invalid)
+            .await
+            .is_err());
+
+        save_dataset_rating_bands(&pool, dataset_id, Vec::new())
+            .await
+            .unwrap();
+        let config = load_dataset_ui_config(&pool, dataset_id).await.unwrap();
+        assert_eq!(config.rating_for(80.0), "Sangat Baik");
+    }
+}
@@ -0,0 +1,351 @@
+use crate::db::audit::record_task_failure;
+use crate::db::models::Webhook;
+use chrono::{DateTime, Utc};
+use reqwest::header::CONTENT_TYPE;
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::time::Duration;
+
+pub const IMPORT_COMPLETED: &str = "import_completed";
+pub const MERGE_COMPLETED: &str = "merge_completed";
+
+/// Attempts after the first failed delivery; the wait doubles before each one.
+const MAX_RETRIES: u32 = 4;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+const WEBHOOK_TASK: &str = "webhook_delivery";
+
+/// JSON body posted to every enabled webhook, signed in `SIGNATURE_HEADER`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    pub event: String,
+    pub dataset_id: i64,
+    /// Row counts of the finished operation, e.g. `employee_count` and `score_count`
+    pub counts: serde_json::Value,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl WebhookPayload {
+    pub fn new(event: &str, dataset_id: i64, counts: serde_json::Value) -> Self {
+        Self {
+            event: event.to_string(),
+            dataset_id,
+            counts,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+/// Trimmed `url` when it is an absolute http(s) URL.
+pub fn normalize_url(url: &str) -> Result<String, String> {
+    let trimmed = url.trim();
+    let parsed = reqwest::Url::parse(trimmed)
+        .map_err(|e| format!("{} is not a valid URL: {}", trimmed, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("Webhook URLs must start with http:// or https://".to_string());
+    }
+    Ok(trimmed.to_string())
+}
+
+pub async fn load_webhooks(pool: &SqlitePool) -> Result<Vec<Webhook>, sqlx::Error> {
+    sqlx::query_as::<_, Webhook>("SELECT * FROM webhooks ORDER BY id")
+        .fetch_all(pool)
+        .await
+}
+
+/// Why a delivery failed and whether trying again could help: the endpoint rejecting the
+/// payload with a 4xx answer won't change its mind.
+struct DeliveryError {
+    message: String,
+    retryable: bool,
+}
+
+/// `sha256=` and the hex HMAC-SHA256 of `body` under the webhook's secret.
+fn signature(secret: &str, body: &str) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let tag = hmac::sign(&key, body.as_bytes());
+    let hex: String = tag
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!("sha256={}", hex)
+}
+
+async fn post_payload(
+    client: &reqwest::Client,
+    webhook: &Webhook,
+    body: &str,
+) -> Result<(), DeliveryError> {
+    let response = client
+        .post(&webhook.url)
+        .header(CONTENT_TYPE, "application/json")
+        .header(SIGNATURE_HEADER, signature(&webhook.secret, body))
+        .body(body.to_string())
+        .send()
+        .await
+        .map_err(|e| DeliveryError {
+            message: format!("Webhook endpoint is unreachable: {}", e),
+            retryable: true,
+        })?;
+    let retryable = !response.status().is_client_error();
+    response.error_for_status().map_err(|e| DeliveryError {
+        message: format!("Webhook endpoint rejected the payload: {}", e),
+        retryable,
+    })?;
+    Ok(())
+}
+
+/// Posts `body` to the webhook, retrying with exponential backoff until `MAX_RETRIES` is spent
+/// or the endpoint refuses it outright.
+async fn deliver(webhook: &Webhook, body: &str, initial_backoff: Duration) -> Result<(), String> {
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let mut backoff = initial_backoff;
+    let mut retries = 0;
+    loop {
+        match post_payload(&client, webhook, body).await {
+            Ok(()) => return Ok(()),
+            Err(error) if !error.retryable || retries >= MAX_RETRIES => return Err(error.message),
+            Err(_) => {
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+                retries += 1;
+            }
+        }
+    }
+}
+
+/// Delivers one queued payload, stores the outcome on the webhook and takes the payload off
+/// the queue.
+async fn deliver_and_record(
+    pool: &SqlitePool,
+    webhook: &Webhook,
+    delivery_id: i64,
+    body: &str,
+    initial_backoff: Duration,
+) -> Result<(), sqlx::Error> {
+    let result = deliver(webhook, body, initial_backoff).await;
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        "UPDATE webhooks
+         SET last_status = ?, last_error = ?, last_attempt_at = datetime('now')
+         WHERE id = ?",
+    )
+    .bind(if result.is_ok() {
+        "delivered"
+    } else {
+        "failed"
+    })
+    .bind(result.err())
+    .bind(webhook.id)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query("DELETE FROM webhook_deliveries WHERE id = ?")
+        .bind(delivery_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await
+}
+
+/// Delivers in the background, so a slow or failing endpoint does not hold up the others or
+/// the caller.
+fn spawn_delivery(pool: SqlitePool, webhook: Webhook, delivery_id: i64, body: String) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(error) =
+            deliver_and_record(&pool, &webhook, delivery_id, &body, INITIAL_BACKOFF).await
+        {
+            let error = format!("Failed to record delivery to {}: {}", webhook.url, error);
+            record_task_failure(&pool, WEBHOOK_TASK, &error).await;
+        }
+    });
+}
+
+/// Queues `payload` for every enabled webhook and sends it in the background.
+pub fn notify(pool: &SqlitePool, payload: WebhookPayload) {
+    let pool = pool.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(error) = queue_deliveries(&pool, &payload).await {
+            record_task_failure(&pool, WEBHOOK_TASK, &error).await;
+        }
+    });
+}
+
+async fn queue_deliveries(pool: &SqlitePool, payload: &WebhookPayload) -> Result<(), String> {
+    let body = serde_json::to_string(payload)
+        .map_err(|e| format!("Failed to serialize webhook payload: {}", e))?;
+    let webhooks = load_webhooks(pool)
+        .await
+        .map_err(|e| format!("Failed to load webhooks: {}", e))?;
+    for webhook in webhooks.into_iter().filter(|webhook| webhook.enabled) {
+        let delivery_id: i64 = sqlx::query_scalar(
+            "INSERT INTO webhook_deliveries (webhook_id, payload) VALUES (?, ?) RETURNING id",
+        )
+        .bind(webhook.id)
+        .bind(&body)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to queue webhook delivery: {}", e))?;
+        spawn_delivery(pool.clone(), webhook, delivery_id, body.clone());
+    }
+    Ok(())
+}
+
+/// Sends the payloads still queued when the app last exited.
+pub fn resume_deliveries(pool: SqlitePool) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(error) = resume_queued(&pool).await {
+            record_task_failure(&pool, WEBHOOK_TASK, &error).await;
+        }
+    });
+}
+
+async fn resume_queued(pool: &SqlitePool) -> Result<(), String> {
+    let webhooks: HashMap<i64, Webhook> = load_webhooks(pool)
+        .await
+        .map_err(|e| format!("Failed to load webhooks: {}", e))?
+        .into_iter()
+        .filter(|webhook| webhook.enabled)
+        .map(|webhook| (webhook.id, webhook))
+        .collect();
+    let queued = sqlx::query_as::<_, (i64, i64, String)>(
+        "SELECT id, webhook_id, payload FROM webhook_deliveries ORDER BY id",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to load queued webhook deliveries: {}", e))?;
+    for (delivery_id, webhook_id, body) in queued {
+        if let Some(webhook) = webhooks.get(&webhook_id) {
+            spawn_delivery(pool.clone(), webhook.clone(), delivery_id, body);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::Router;
+    use std::sync::{Arc, Mutex};
+
+    async fn register(pool: &SqlitePool, url: &str) -> Webhook {
+        sqlx::query_as::<_, Webhook>(
+            "INSERT INTO webhooks (url, secret) VALUES (?, 'rahasia') RETURNING *",
+        )
+        .bind(url)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    async fn queue(pool: &SqlitePool, webhook: &Webhook, body: &str) -> i64 {
+        sqlx::query_scalar(
+            "INSERT INTO webhook_deliveries (webhook_id, payload) VALUES (?, ?) RETURNING id",
+        )
+        .bind(webhook.id)
+        .bind(body)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_delivery_retries_until_the_endpoint_accepts() {
+        let pool = test_pool().await;
+
+        // `/hook` rejects the first request, then records the signed payloads it accepts;
+        // `/gone` refuses every request
+        let received: Arc<Mutex<Vec<WebhookPayload>>> = Arc::default();
+        let attempts = Arc::new(Mutex::new(0));
+        let app = Router::new()
+            .route(
+                "/hook",
+                post({
+                    let received = received.clone();
+                    let attempts = attempts.clone();
+                    move |headers: HeaderMap, body: String| async move {
+                        let mut attempts = attempts.lock().unwrap();
+                        *attempts += 1;
+                        if *attempts == 1 {
+                            return StatusCode::SERVICE_UNAVAILABLE;
+                        }
+                        if headers[SIGNATURE_HEADER] != signature("rahasia", &body).as_str() {
+                            return StatusCode::UNAUTHORIZED;
+                        }
+                        received
+                            .lock()
+                            .unwrap()
+                            .push(serde_json::from_str(&body).unwrap());
+                        StatusCode::OK
+                    }
+                }),
+            )
+            .route(
+                "/gone",
+                post({
+                    let attempts = attempts.clone();
+                    move || async move {
+                        *attempts.lock().unwrap() += 1;
+                        StatusCode::GONE
+                    }
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let url = normalize_url(&format!(" http://{}/hook ", address)).unwrap();
+        assert!(normalize_url("ftp://intranet/hook").is_err());
+        let webhook = register(&pool, &url).await;
+
+        let payload = WebhookPayload::new(
+            IMPORT_COMPLETED,
+            7,
+            serde_json::json!({ "employee_count": 3, "score_count": 12 }),
+        );
+        let body = serde_json::to_string(&payload).unwrap();
+        let delivery = queue(&pool, &webhook, &body).await;
+        deliver_and_record(&pool, &webhook, delivery, &body, Duration::from_millis(10))
+            .await
+            .unwrap();
+
+        assert_eq!(*attempts.lock().unwrap(), 2);
+        {
+            let received = received.lock().unwrap();
+            assert_eq!(received.len(), 1);
+            assert_eq!(received[0].dataset_id, 7);
+            assert_eq!(received[0].counts["score_count"], 12);
+        }
+
+        let stored = load_webhooks(&pool).await.unwrap();
+        assert_eq!(stored[0].last_status.as_deref(), Some("delivered"));
+        assert!(stored[0].last_error.is_none());
+        assert!(stored[0].last_attempt_at.is_some());
+
+        // A 4xx answer is final
+        *attempts.lock().unwrap() = 0;
+        let gone = register(&pool, &format!("http://{}/gone", address)).await;
+        let delivery = queue(&pool, &gone, &body).await;
+        deliver_and_record(&pool, &gone, delivery, &body, Duration::from_millis(10))
+            .await
+            .unwrap();
+        assert_eq!(*attempts.lock().unwrap(), 1);
+        let stored = load_webhooks(&pool).await.unwrap();
+        assert_eq!(stored[1].last_status.as_deref(), Some("failed"));
+
+        let queued: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM webhook_deliveries")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(queued, 0);
+    }
+}
@@ -0,0 +1,347 @@
+use crate::backup::{create_backup, replace_all_data};
+use crate::commands::import::{default_rating_mappings, write_dataset_scores};
+use crate::csv_parser::ParsedScore;
+use crate::db::audit::record_audit;
+use crate::scoring::weighting::load_weighting_scheme;
+use chrono::{Datelike, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+/// Phrase the user has to type before `reset_workspace` wipes anything.
+pub const RESET_CONFIRMATION: &str = "RESET WORKSPACE";
+
+/// Employees and semesters `seed_demo_data` creates when no count is given.
+pub const DEFAULT_DEMO_EMPLOYEES: i64 = 8;
+pub const DEFAULT_DEMO_DATASETS: i64 = 1;
+const MAX_DEMO_DATASETS: i64 = 12;
+
+const FIRST_NAMES: &[&str] = &[
+    "Agus", "Budi", "Dewi", "Siti", "Rina", "Hendra", "Wahyu", "Fitri", "Rudi", "Sri", "Eko",
+    "Nur", "Yusuf", "Ratna", "Ahmad", "Indah", "Bambang", "Lestari", "Dian", "Fajar",
+];
+const LAST_NAMES: &[&str] = &[
+    "Santoso",
+    "Wijaya",
+    "Rahmawati",
+    "Saputra",
+    "Hidayat",
+    "Kurniawan",
+    "Pratama",
+    "Nugroho",
+    "Setiawan",
+    "Susanti",
+    "Hakim",
+    "Wibowo",
+    "Purnomo",
+    "Maharani",
+    "Sitompul",
+    "Siregar",
+];
+const GOLONGAN: &[&str] = &[
+    "II/c", "II/d", "III/a", "III/b", "III/c", "III/d", "IV/a", "IV/b",
+];
+const UNITS: &[&str] = &[
+    "Subbagian Umum",
+    "Seksi Pelayanan",
+    "Seksi Pengawasan",
+    "Seksi Penagihan",
+    "Seksi Pemeriksaan",
+];
+const STAFF_POSITIONS: &[&str] = &[
+    "Pelaksana",
+    "Analis Kepegawaian",
+    "Pranata Komputer",
+    "Penelaah Teknis Kebijakan",
+    "Pengadministrasi Umum",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceReset {
+    pub backup_path: String,
+    pub cleared_tables: Vec<String>,
+}
+
+/// Empties every table after taking a backup; the schema and migration history stay.
+pub async fn reset_workspace(
+    pool: &SqlitePool,
+    confirm_token: &str,
+) -> Result<WorkspaceReset, String> {
+    if confirm_token.trim() != RESET_CONFIRMATION {
+        return Err(format!(
+            "Type \"{}\" to confirm the reset",
+            RESET_CONFIRMATION
+        ));
+    }
+
+    let backup_path = create_backup(pool, "before-reset")
+        .await?
+        .to_string_lossy()
+        .to_string();
+    let cleared_tables = replace_all_data(
+        pool,
+        None,
+        "workspace_reset",
+        &serde_json::json!({ "backup_path": backup_path }),
+    )
+    .await?;
+
+    Ok(WorkspaceReset {
+        backup_path,
+        cleared_tables,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DemoDataSummary {
+    pub dataset_ids: Vec<i64>,
+    pub employee_count: usize,
+    pub competency_count: usize,
+    pub score_count: usize,
+}
+
+/// SplitMix64; demo data only needs to look varied, not be unpredictable.
+struct DemoRng(u64);
+
+impl DemoRng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0.0..1.0`.
+    fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.below(items.len() as u64) as usize]
+    }
+}
+
+/// An 18-digit NIP laid out like the real ones: birth date, appointment month, sex and a
+/// sequence number.
+fn demo_nip(rng: &mut DemoRng, current_year: u64) -> String {
+    let birth_year = current_year - 60 + rng.below(35);
+    let appointed_year = (birth_year + 22 + rng.below(8)).min(current_year);
+    format!(
+        "{}{:02}{:02}{}{:02}{}{:03}",
+        birth_year,
+        1 + rng.below(12),
+        1 + rng.below(28),
+        appointed_year,
+        1 + rng.below(12),
+        1 + rng.below(2),
+        1 + rng.below(999),
+    )
+}
+
+/// Loads made-up employees and `dataset_count` semesters of their scores for training and
+/// screenshots, ending with the current semester. Only allowed on an empty workspace so demo
+/// rows never mix with real assessments. Each employee keeps an ability across semesters, so
+/// trends and rankings look plausible.
+pub async fn seed_demo_data(
+    pool: &SqlitePool,
+    seed: u64,
+    employee_count: i64,
+    dataset_count: i64,
+) -> Result<DemoDataSummary, String> {
+    // Every name is used at most once, since scores are matched to employees by name
+    let mut names: Vec<String> = FIRST_NAMES
+        .iter()
+        .flat_map(|first| {
+            LAST_NAMES
+                .iter()
+                .map(move |last| format!("{} {}", first, last))
+        })
+        .collect();
+    if !(1..=names.len() as i64).contains(&employee_count) {
+        return Err(format!(
+            "Employee count must be between 1 and {}",
+            names.len()
+        ));
+    }
+    if !(1..=MAX_DEMO_DATASETS).contains(&dataset_count) {
+        return Err(format!(
+            "Dataset count must be between 1 and {}",
+            MAX_DEMO_DATASETS
+        ));
+    }
+
+    let existing: i64 = sqlx::query_scalar(
+        "SELECT (SELECT COUNT(*) FROM employees) + (SELECT COUNT(*) FROM datasets)",
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Failed to inspect workspace: {}", e))?;
+    if existing > 0 {
+        return Err("Demo data can only be loaded into an empty workspace".to_string());
+    }
+
+    let scheme = load_weighting_scheme(pool)
+        .await
+        .map_err(|e| format!("Failed to load weighting scheme: {}", e))?;
+    let today = Utc::now();
+    let mut rng = DemoRng(seed);
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let mut employees = Vec::with_capacity(employee_count as usize);
+    for index in 0..employee_count as usize {
+        let unit = UNITS[index % UNITS.len()];
+        // The first employee of every unit heads it
+        let jabatan = if index < UNITS.len() {
+            format!("Kepala {}", unit)
+        } else {
+            rng.pick(STAFF_POSITIONS).to_string()
+        };
+        let name = names.swap_remove(rng.below(names.len() as u64) as usize);
+        sqlx::query(
+            "INSERT INTO employees (name, nip, gol, jabatan, sub_jabatan, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, datetime('now'), datetime('now'))",
+        )
+        .bind(&name)
+        .bind(demo_nip(&mut rng, today.year() as u64))
+        .bind(rng.pick(GOLONGAN))
+        .bind(&jabatan)
+        .bind(unit)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to create employee {}: {}", name, e))?;
+        employees.push((name, rng.unit()));
+    }
+
+    // Lowest mapping first, so a higher draw earns a better label
+    let mut mappings = default_rating_mappings();
+    mappings.sort_by(|a, b| a.numeric_value.total_cmp(&b.numeric_value));
+    let current_semester = if today.month() <= 6 { 1 } else { 2 };
+    let mut dataset_ids = Vec::with_capacity(dataset_count as usize);
+    let mut score_count = 0;
+    for semesters_back in (0..dataset_count).rev() {
+        let semester_index = today.year() as i64 * 2 + current_semester - 1 - semesters_back;
+        let (year, semester) = (semester_index / 2, semester_index % 2 + 1);
+        let dataset_id: i64 = sqlx::query_scalar(
+            "INSERT INTO datasets (name, description, period_year, period_label, created_at, updated_at)
+             VALUES (?, 'Data contoh untuk pelatihan', ?, ?, datetime('now'), datetime('now'))
+             RETURNING id",
+        )
+        .bind(format!("Demo Penilaian Semester {} {}", semester, year))
+        .bind(year)
+        .bind(format!("Semester {}", semester))
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to create dataset: {}", e))?;
+
+        let scores: Vec<ParsedScore> = employees
+            .iter()
+            .flat_map(|(name, ability)| {
+                scheme
+                    .parameter_names()
+                    .map(|competency| {
+                        let draw = ability * 0.7 + rng.unit() * 0.3;
+                        let band = (draw * mappings.len() as f64) as usize;
+                        ParsedScore {
+                            employee_name: name.clone(),
+                            competency: competency.to_string(),
+                            value: mappings[band.min(mappings.len() - 1)].text_value.clone(),
+                            ordinal: None,
+                            submitted_at: None,
+                            source_row: None,
+                            source_column: None,
+                            respondent_count: None,
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        write_dataset_scores(&mut tx, dataset_id, &scores, &mappings).await?;
+        score_count += scores.len();
+        dataset_ids.push(dataset_id);
+    }
+
+    let summary = DemoDataSummary {
+        dataset_ids,
+        employee_count: employees.len(),
+        competency_count: scheme.parameter_names().count(),
+        score_count,
+    };
+    record_audit(
+        &mut *tx,
+        "demo_data_seeded",
+        None,
+        &serde_json::json!({
+            "dataset_ids": summary.dataset_ids,
+            "employees": summary.employee_count,
+            "scores": summary.score_count,
+        }),
+    )
+    .await
+    .map_err(|e| format!("Failed to record audit entry: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scoring::weighting::WeightingScheme;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_reset_and_seed_demo_data() {
+        let directory = tempfile::tempdir().unwrap();
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(&format!(
+                "sqlite:{}?mode=rwc",
+                directory.path().join("epa.db").display()
+            ))
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        assert!(seed_demo_data(&pool, 7, 0, 1).await.is_err());
+        let summary = seed_demo_data(&pool, 7, 12, 3).await.unwrap();
+        assert!(seed_demo_data(&pool, 7, 1, 1).await.is_err());
+        let score_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM scores")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(score_count as usize, summary.score_count);
+        assert_eq!(
+            summary.score_count,
+            12 * 3 * WeightingScheme::default().parameter_names().count()
+        );
+        let nips: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM employees WHERE LENGTH(nip) = 18")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(nips, 12);
+        // The newest semester is the current one
+        let newest_year: i64 = sqlx::query_scalar("SELECT MAX(period_year) FROM datasets")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(newest_year, Utc::now().year() as i64);
+
+        assert!(reset_workspace(&pool, "reset").await.is_err());
+        let reset = reset_workspace(&pool, RESET_CONFIRMATION).await.unwrap();
+        assert!(std::path::Path::new(&reset.backup_path).exists());
+        let remaining: i64 = sqlx::query_scalar(
+            "SELECT (SELECT COUNT(*) FROM employees) + (SELECT COUNT(*) FROM scores)",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(remaining, 0);
+
+        pool.close().await;
+    }
+}
@@ -1,20 +1,27 @@
 import type {
   Dataset,
+  DatasetPeriod,
   CreateDataset,
   CSVPreview,
   ParsedEmployee,
   ParsedScore,
+  RespondentAggregation,
   ImportResult,
   CreateRatingMapping,
   Employee,
+  EmployeeDeletionCounts,
   DatasetStats,
   DashboardOverview,
+  EmployeeListColumn,
+  DataQualityIssue,
   EmployeeListResult,
   EmployeePerformance,
   ImportValidationPayload,
   ImportValidationSummary,
   Summary,
   GeneratedSummary,
+  SummaryLintIssue,
+  SummarySaveResult,
   DatasetComparison,
   UpdateDatasetRequest,
   MergeDatasetsRequest,
@@ -23,6 +30,7 @@ import type {
   EmployeeImportResult,
   PerformanceImportRequest,
   PerformanceAppendRequest,
+  XlsxImportOptions,
   UpdateEmployee,
   DatasetEmployeeAppendResult,
   SortState,
@@ -35,33 +43,113 @@ export function isTauri(): boolean {
   return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
 }
 
+interface CommandMetricEntry {
+  command: string;
+  duration_ms: number;
+  row_count: number | null;
+  failed: boolean;
+}
+
+const METRICS_COMMAND = 'record_command_metrics';
+// Timed by the backend itself, failed runs included; keep in sync with metrics.rs
+const BACKEND_TIMED_COMMANDS = new Set([
+  'import_employees',
+  'import_performance_dataset',
+  'import_performance_dataset_xlsx',
+  'import_performance_into_dataset',
+]);
+const METRICS_BATCH_SIZE = 20;
+const METRICS_FLUSH_DELAY_MS = 5000;
+let pendingMetrics: CommandMetricEntry[] = [];
+let metricsFlushTimer: ReturnType<typeof setTimeout> | undefined;
+
+async function flushCommandMetrics(): Promise<void> {
+  clearTimeout(metricsFlushTimer);
+  metricsFlushTimer = undefined;
+  const entries = pendingMetrics;
+  pendingMetrics = [];
+  const { invoke: tauriInvoke } = await import('@tauri-apps/api/core');
+  // Timings are best effort and must never surface as UI errors
+  await tauriInvoke(METRICS_COMMAND, { entries }).catch(() => undefined);
+}
+
+function queueCommandMetric(entry: CommandMetricEntry): void {
+  pendingMetrics.push(entry);
+  if (pendingMetrics.length >= METRICS_BATCH_SIZE) {
+    void flushCommandMetrics();
+  } else if (metricsFlushTimer === undefined) {
+    metricsFlushTimer = setTimeout(() => void flushCommandMetrics(), METRICS_FLUSH_DELAY_MS);
+  }
+}
+
 async function invoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
   const { invoke: tauriInvoke } = await import('@tauri-apps/api/core');
-  return tauriInvoke<T>(cmd, args);
+  if (BACKEND_TIMED_COMMANDS.has(cmd)) {
+    return tauriInvoke<T>(cmd, args);
+  }
+  const started = performance.now();
+  let result: T | undefined;
+  let failed = true;
+  try {
+    result = await tauriInvoke<T>(cmd, args);
+    failed = false;
+    return result;
+  } finally {
+    queueCommandMetric({
+      command: cmd,
+      duration_ms: performance.now() - started,
+      row_count: Array.isArray(result) ? result.length : null,
+      failed,
+    });
+  }
 }
 
 // CSV Commands
-export async function previewCSV(filePathOrFile: string | File, maxRows: number = 10): Promise<CSVPreview> {
+export async function previewCSV(
+  filePathOrFile: string | File,
+  maxRows: number = 10,
+  delimiter?: string,
+  encoding?: string,
+): Promise<CSVPreview> {
   if (isTauri() && typeof filePathOrFile === 'string') {
-    return invoke('preview_csv', { filePath: filePathOrFile, maxRows });
+    return invoke('preview_csv', { filePath: filePathOrFile, maxRows, delimiter, encoding });
   } else if (filePathOrFile instanceof File) {
     return BrowserCSVParser.preview(filePathOrFile, maxRows);
   }
   throw new Error('Invalid file input');
 }
 
-export async function parseEmployeeCSV(filePathOrFile: string | File): Promise<ParsedEmployee[]> {
+export async function parseEmployeeCSV(
+  filePathOrFile: string | File,
+  format?: string,
+  delimiter?: string,
+  encoding?: string,
+): Promise<ParsedEmployee[]> {
   if (isTauri() && typeof filePathOrFile === 'string') {
-    return invoke('parse_employee_csv', { filePath: filePathOrFile });
+    return invoke('parse_employee_csv', { filePath: filePathOrFile, format, delimiter, encoding });
   } else if (filePathOrFile instanceof File) {
     return BrowserCSVParser.parseEmployeeCSV(filePathOrFile);
   }
   throw new Error('Invalid file input');
 }
 
-export async function parseScoresCSV(filePathOrFile: string | File): Promise<ParsedScore[]> {
+export async function parseScoresCSV(
+  filePathOrFile: string | File,
+  format?: string,
+  delimiter?: string,
+  encoding?: string,
+  aggregation?: RespondentAggregation,
+  ratingMappings?: CreateRatingMapping[],
+): Promise<ParsedScore[]> {
   if (isTauri() && typeof filePathOrFile === 'string') {
-    return invoke('parse_scores_csv', { filePath: filePathOrFile });
+    return invoke('parse_scores_csv', {
+      filePath: filePathOrFile,
+      format,
+      delimiter,
+      encoding,
+      aggregation,
+      ratingMappings,
+    });
   } else if (filePathOrFile instanceof File) {
     return BrowserCSVParser.parseScoresCSV(filePathOrFile);
   }
@@ -76,9 +164,16 @@ export async function createDataset(dataset: CreateDataset): Promise<Dataset> {
   return browserStorage.createDataset(dataset);
 }
 
-export async function listDatasets(): Promise<Dataset[]> {
+export async function listDatasets(
+  period?: Partial<DatasetPeriod>,
+  tag?: string,
+): Promise<Dataset[]> {
   if (isTauri()) {
-    return invoke('list_datasets');
+    return invoke('list_datasets', {
+      periodYear: period?.period_year ?? null,
+      periodLabel: period?.period_label ?? null,
+      tag: tag ?? null,
+    });
   }
   return browserStorage.listDatasets();
 }
@@ -99,6 +194,14 @@ export async function bulkDeleteEmployees(ids: number[]): Promise<number> {
   return 0;
 }
 
+export async function previewBulkDeleteEmployees(ids: number[]): Promise<EmployeeDeletionCounts | null> {
+  if (isTauri()) {
+    return invoke('preview_bulk_delete_employees', { ids });
+  }
+  // Browser fallback not implemented
+  return null;
+}
+
 export async function bulkUpdateEmployees(updates: UpdateEmployee[]): Promise<number> {
   if (!Array.isArray(updates) || updates.length === 0) return 0;
   if (isTauri()) {
@@ -210,6 +313,7 @@ export async function updateDataset(id: number, payload: UpdateDatasetRequest):
       id,
       name,
       description: description ?? null,
+      period: payload.period,
     });
   }
 
@@ -277,14 +381,24 @@ export async function importPerformanceDataset(
     dataset_name: datasetName,
     dataset_description: description,
     source_file: request.source_file,
+    period_year: request.period_year ?? null,
+    period_label: request.period_label?.trim() || null,
     employee_names: request.employee_names.map((name) => name.trim()).filter((name) => name.length > 0),
     scores: sanitizedScores,
+    scores_file: request.scores_file ?? null,
+    aggregation: request.aggregation ?? null,
     rating_mappings: sanitizedMappings,
+    dry_run: request.dry_run ?? false,
+    validation_stats: request.validation_stats ?? null,
+    preset_id: request.preset_id ?? null,
   };
 
   if (isTauri()) {
     return invoke('import_performance_dataset', { request: payload });
   }
+  if (payload.dry_run) {
+    throw new Error('Dry-run imports are only available in the desktop application.');
+  }
   return browserStorage.importPerformanceDataset(payload);
 }
 
@@ -305,9 +419,14 @@ export async function importPerformanceIntoDataset(
 
   const payload: PerformanceAppendRequest = {
     dataset_id: request.dataset_id,
+    source_file: request.source_file ?? null,
     employee_names: request.employee_names.map((n) => n.trim()).filter(Boolean),
     scores: sanitizedScores,
+    scores_file: request.scores_file ?? null,
+    aggregation: request.aggregation ?? null,
     rating_mappings: sanitizedMappings,
+    dry_run: request.dry_run ?? false,
+    validation_stats: request.validation_stats ?? null,
   };
 
   if (isTauri()) {
@@ -317,6 +436,21 @@ export async function importPerformanceIntoDataset(
   throw new Error('Appending into existing dataset is only available in the desktop application.');
 }
 
+export async function importPerformanceDatasetXlsx(
+  filePath: string,
+  sheet?: string | null,
+  options?: XlsxImportOptions,
+): Promise<ImportResult> {
+  if (isTauri()) {
+    return invoke('import_performance_dataset_xlsx', {
+      filePath,
+      sheet: sheet?.trim() || null,
+      options: options ?? null,
+    });
+  }
+  throw new Error('Importing workbooks directly is only available in the desktop application.');
+}
+
 export async function getDefaultRatingMappings(): Promise<CreateRatingMapping[]> {
   if (isTauri()) {
     return invoke('get_default_rating_mappings');
@@ -345,6 +479,8 @@ export async function listEmployees(
   limit?: number,
   offset?: number,
   sort?: SortState,
+  columns?: EmployeeListColumn[],
+  dataQualityIssue?: DataQualityIssue | 'any',
 ): Promise<EmployeeListResult> {
   if (isTauri()) {
     return invoke('list_employees', {
@@ -354,6 +490,8 @@ export async function listEmployees(
       offset,
       sortBy: sort?.column,
       sortDirection: sort?.direction,
+      columns,
+      dataQualityIssue,
     });
   }
   return browserStorage.listEmployees(datasetId, search, limit, offset, sort);
@@ -388,25 +526,44 @@ export async function generateEmployeeSummary(
   return browserStorage.generateEmployeeSummary(datasetId, employeeId);
 }
 
-export async function getEmployeeSummary(employeeId: number): Promise<Summary | null> {
+export async function getEmployeeSummary(
+  employeeId: number,
+  datasetId: number
+): Promise<Summary | null> {
   if (isTauri()) {
-    return invoke('get_employee_summary', { employeeId });
+    return invoke('get_employee_summary', { employeeId, datasetId });
   }
-  return browserStorage.getEmployeeSummary(employeeId);
+  return browserStorage.getEmployeeSummary(employeeId, datasetId);
 }
 
-export async function saveEmployeeSummary(employeeId: number, content: string): Promise<Summary> {
+export async function saveEmployeeSummary(
+  employeeId: number,
+  datasetId: number,
+  content: string
+): Promise<SummarySaveResult> {
   if (isTauri()) {
-    return invoke('save_employee_summary', { employeeId, content });
+    return invoke('save_employee_summary', { employeeId, datasetId, content });
   }
-  return browserStorage.saveEmployeeSummary(employeeId, content);
+  const summary = await browserStorage.saveEmployeeSummary(employeeId, datasetId, content);
+  return { summary, issues: [] };
+}
+
+export async function lintSummary(
+  employeeId: number,
+  content: string,
+  datasetId?: number
+): Promise<SummaryLintIssue[]> {
+  if (isTauri()) {
+    return invoke('lint_summary', { employeeId, content, datasetId });
+  }
+  return [];
 }
 
 export async function exportEmployeeSummary(
   datasetId: number,
   employeeId: number,
   filePath: string
-): Promise<void> {
+): Promise<string> {
   if (isTauri()) {
     return invoke('export_employee_summary_pdf', { datasetId, employeeId, filePath });
   }
@@ -417,7 +574,7 @@ export async function exportEmployeeReport(
   datasetId: number,
   employeeId: number,
   filePath: string
-): Promise<void> {
+): Promise<string> {
   if (isTauri()) {
     return invoke('export_employee_report_pdf', { datasetId, employeeId, filePath });
   }
@@ -427,20 +584,30 @@ export async function exportEmployeeReport(
 export async function exportDataset(
   datasetId: number,
   format: 'csv' | 'xlsx' | 'pdf',
-  filePath: string
+  filePath: string,
+  includeNotes = false,
+  anonymize = false,
+  exportId: string = crypto.randomUUID() // pass to cancelExport to stop this export
 ): Promise<void> {
   if (isTauri()) {
-    return invoke('export_dataset', { datasetId, format, filePath });
+    return invoke('export_dataset', { exportId, datasetId, format, filePath, includeNotes, anonymize });
   }
   throw new Error('Dataset export is only available in the desktop application.');
 }
 
+export async function cancelExport(exportId: string): Promise<void> {
+  if (isTauri()) {
+    return invoke('cancel_export', { exportId });
+  }
+}
+
 export async function compareDatasets(
   baseDatasetId: number,
-  comparisonDatasetId: number
+  comparisonDatasetId: number,
+  orderByPeriod = false
 ): Promise<DatasetComparison> {
   if (isTauri()) {
-    return invoke('compare_datasets', { baseDatasetId, comparisonDatasetId });
+    return invoke('compare_datasets', { baseDatasetId, comparisonDatasetId, orderByPeriod });
   }
   return browserStorage.compareDatasets(baseDatasetId, comparisonDatasetId);
 }
@@ -47,7 +47,7 @@ import {
 } from './employee-utils';
 
 const DB_NAME = 'employee_monitoring';
-const DB_VERSION = 3;
+const DB_VERSION = 4;
 
 interface DBSchema {
   datasets: Dataset;
@@ -180,7 +180,14 @@ class BrowserStorage {
         // Summaries store
         if (!db.objectStoreNames.contains('summaries')) {
           const summaryStore = db.createObjectStore('summaries', { keyPath: 'id', autoIncrement: true });
-          summaryStore.createIndex('employee_id', 'employee_id', { unique: true });
+          summaryStore.createIndex('employee_id', 'employee_id', { unique: false });
+        } else {
+          // Summaries are per dataset since version 4, so an employee can have several
+          const summaryStore = transaction.objectStore('summaries');
+          if (summaryStore.index('employee_id').unique) {
+            summaryStore.deleteIndex('employee_id');
+            summaryStore.createIndex('employee_id', 'employee_id', { unique: false });
+          }
         }
 
         if (oldVersion > 0 && oldVersion < 3) {
@@ -1391,19 +1398,26 @@ class BrowserStorage {
     });
   }
 
-  async getEmployeeSummary(employeeId: number): Promise<Summary | null> {
+  async getEmployeeSummary(employeeId: number, datasetId: number): Promise<Summary | null> {
     const store = await this.getObjectStore('summaries');
     return new Promise((resolve, reject) => {
       const index = store.index('employee_id');
-      const request = index.get(employeeId);
-      request.onsuccess = () => resolve((request.result as Summary | undefined) ?? null);
+      const request = index.getAll(employeeId);
+      request.onsuccess = () =>
+        resolve(
+          (request.result as Summary[]).find((summary) => summary.dataset_id === datasetId) ?? null
+        );
       request.onerror = () => reject(new Error(request.error?.message ?? 'Failed to load summary'));
     });
   }
 
-  async saveEmployeeSummary(employeeId: number, content: string): Promise<Summary> {
+  async saveEmployeeSummary(
+    employeeId: number,
+    datasetId: number,
+    content: string
+  ): Promise<Summary> {
+    const existing = await this.getEmployeeSummary(employeeId, datasetId);
     const store = await this.getObjectStore('summaries', 'readwrite');
-    const existing = await this.getEmployeeSummary(employeeId);
     const now = new Date().toISOString();
 
     const summary: Summary = existing
@@ -1411,6 +1425,7 @@ class BrowserStorage {
       : {
           id: 0,
           employee_id: employeeId,
+          dataset_id: datasetId,
           content,
           created_at: now,
           updated_at: now,
@@ -38,7 +38,8 @@ export class BrowserCSVParser {
    */
   static async preview(file: File, maxRows: number = 10): Promise<CSVPreview> {
     return new Promise((resolve, reject) => {
-      const text = file.slice(0, Math.min(file.size, 1024 * 1024)); // Read first 1MB for preview
+      const sampleSize = Math.min(file.size, 1024 * 1024);
+      const text = file.slice(0, sampleSize); // Read first 1MB for preview
 
       const reader = new FileReader();
       reader.onload = (e) => {
@@ -54,12 +55,22 @@ export class BrowserCSVParser {
               (row as string[]).map(f => this.cleanField(f))
             );
 
+            // Larger files are estimated from the rows found in the sample
+            const sampleRows = results.data.length - 1;
+            const rowsEstimated = file.size > sampleSize;
+            const totalRows = rowsEstimated
+              ? Math.round((sampleRows * file.size) / sampleSize)
+              : sampleRows;
+
             resolve({
               headers,
               rows,
               detected_delimiter: delimiter,
-              employee_count: results.data.length - 1,
-              encoding: 'UTF-8'
+              employee_count: totalRows,
+              encoding: 'UTF-8',
+              file_size: file.size,
+              total_rows: totalRows,
+              rows_estimated: rowsEstimated
             });
           },
           error: (error: Error) => {
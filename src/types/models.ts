@@ -3,8 +3,21 @@ export interface Dataset {
   name: string;
   description: string | null;
   source_file: string | null;
+  period_year?: number | null;
+  period_label?: string | null;
+  submission_deadline?: string | null;
+  archived_at?: string | null;
+  score_scale?: number | null; // null detects the scale from the scores
+  score_aggregation?: ScoreAggregation | null; // null means mean
+  score_normalization?: ScoreNormalization | null; // null means none
   created_at: string;
   updated_at: string;
+  tags?: string[];
+}
+
+export interface DatasetPeriod {
+  period_year: number | null;
+  period_label: string | null;
 }
 
 export interface Employee {
@@ -14,6 +27,8 @@ export interface Employee {
   gol: string | null;
   jabatan: string | null;
   sub_jabatan: string | null;
+  position_type?: 'eselon' | 'staff' | null;
+  photo_path?: string | null;
   created_at: string;
   updated_at: string;
 }
@@ -23,6 +38,14 @@ export interface Competency {
   name: string;
   description: string | null;
   display_order: number;
+  category_id?: number | null;
+}
+
+export interface CompetencyCategory {
+  id: number;
+  name: string;
+  description: string | null;
+  display_order: number;
 }
 
 export interface Score {
@@ -45,6 +68,7 @@ export interface RatingMapping {
 export interface Summary {
   id: number;
   employee_id: number;
+  dataset_id: number;
   content: string;
   created_at: string;
   updated_at: string;
@@ -54,6 +78,17 @@ export interface GeneratedSummary {
   content: string;
 }
 
+export interface SummaryLintIssue {
+  code: string;
+  severity: 'error' | 'warning';
+  message: string;
+}
+
+export interface SummarySaveResult {
+  summary: Summary;
+  issues: SummaryLintIssue[];
+}
+
 export interface ValidationIssue {
   id: number;
   dataset_id: number;
@@ -70,11 +105,14 @@ export interface CreateDataset {
   name: string;
   description?: string;
   source_file?: string;
+  period_year?: number | null;
+  period_label?: string | null;
 }
 
 export interface UpdateDatasetRequest {
   name: string;
   description?: string | null;
+  period?: DatasetPeriod;
 }
 
 export interface MergeDatasetsRequest {
@@ -83,6 +121,21 @@ export interface MergeDatasetsRequest {
   source_dataset_ids: number[];
 }
 
+// An employee linked to a dataset, as returned by list_dataset_employees
+export type DatasetMember = Employee & { score_count: number };
+
+export interface RosterMatch {
+  employee: Employee;
+  base_score_count: number;
+  comparison_score_count: number;
+}
+
+export interface RosterDiff {
+  only_in_base: DatasetMember[];
+  only_in_comparison: DatasetMember[];
+  in_both: RosterMatch[];
+}
+
 export interface MergeDatasetsResult {
   dataset: Dataset;
   employee_count: number;
@@ -120,6 +173,9 @@ export interface CSVPreview {
   detected_delimiter: string;
   employee_count: number;
   encoding: string;
+  file_size: number;
+  total_rows: number;
+  rows_estimated: boolean;
 }
 
 export interface FieldMapping {
@@ -127,6 +183,25 @@ export interface FieldMapping {
   db_field: string;
 }
 
+export interface ImportPreset {
+  id: number;
+  name: string;
+  delimiter: string | null;
+  column_mapping: FieldMapping[];
+  rating_mappings: CreateRatingMapping[];
+  score_scale: number | null;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface SaveImportPresetRequest {
+  name: string;
+  delimiter?: string | null;
+  column_mapping?: FieldMapping[];
+  rating_mappings?: CreateRatingMapping[];
+  score_scale?: number | null;
+}
+
 export interface ImportConfig {
   file_path: string;
   delimiter: string;
@@ -148,6 +223,27 @@ export interface ParsedScore {
   employee_name: string;
   competency: string;
   value: string;
+  ordinal?: number | null; // question number split off the header
+  submitted_at?: string | null;
+  respondent_count?: number | null;
+}
+
+export type RespondentAggregation = 'mean' | 'median' | 'latest';
+
+export type ScoreAggregation = 'mean' | 'median' | 'mode';
+
+export type ScoreNormalization = 'none' | 'z_score' | 'min_max';
+
+export interface ScoreEntry {
+  id: number;
+  dataset_id: number;
+  employee_id: number;
+  competency_id: number;
+  rater: string;
+  raw_value: string;
+  numeric_value?: number | null;
+  import_run_id?: number | null;
+  created_at: string;
 }
 
 export interface EmployeeImportRequest {
@@ -164,16 +260,45 @@ export interface PerformanceImportRequest {
   dataset_name: string;
   dataset_description: string | null;
   source_file: string;
+  period_year?: number | null;
+  period_label?: string | null;
   employee_names: string[];
   scores: ParsedScore[];
+  scores_file?: string | null;
+  aggregation?: RespondentAggregation | null; // combines Google Forms respondents in scores_file
+  delimiter?: string | null; // the delimiter chosen in the preview for scores_file
+  encoding?: string | null; // the encoding chosen in the preview for scores_file
   rating_mappings: CreateRatingMapping[];
+  rater?: string | null; // defaults to the source file name
+  dry_run?: boolean;
+  validation_stats?: ValidationStats | null;
+  preset_id?: number | null; // delimiter, column mapping, rating mappings and score scale of a saved preset
 }
 
 export interface PerformanceAppendRequest {
   dataset_id: number;
+  source_file?: string | null;
   employee_names: string[];
   scores: ParsedScore[];
+  scores_file?: string | null;
+  aggregation?: RespondentAggregation | null; // combines Google Forms respondents in scores_file
+  delimiter?: string | null; // the delimiter chosen in the preview for scores_file
+  encoding?: string | null; // the encoding chosen in the preview for scores_file
   rating_mappings: CreateRatingMapping[];
+  rater?: string | null; // defaults to the source file name
+  dry_run?: boolean;
+  validation_stats?: ValidationStats | null;
+}
+
+export interface XlsxImportOptions {
+  dataset_name?: string | null; // defaults to the workbook's file name
+  dataset_description?: string | null;
+  period_year?: number | null;
+  period_label?: string | null;
+  rating_mappings?: CreateRatingMapping[] | null; // defaults to the standard mappings
+  rater?: string | null;
+  dry_run?: boolean;
+  preset_id?: number | null; // its column mapping renames the sheet's headers
 }
 
 export interface DatasetEmployeeAppendResult {
@@ -182,23 +307,68 @@ export interface DatasetEmployeeAppendResult {
   linked: number;
 }
 
+export interface ImportMetrics {
+  parse_ms: number;
+  db_ms: number;
+  total_ms: number;
+  rows_per_sec: number;
+  skipped_rows: number;
+  overwritten_rows: number;
+}
+
+export interface ImportChanges {
+  new_scores: number;
+  updated_scores: number;
+  new_competencies: number;
+  newly_linked_employees: number;
+}
+
 export interface ImportResult {
   dataset: Dataset;
   employee_count: number;
   competency_count: number;
   score_count: number;
+  metrics?: ImportMetrics;
+  changes?: ImportChanges;
+  dry_run?: boolean;
 }
 
 // Analytics types
 export interface ScoreDistribution {
   range: string;
   count: number;
+  lower_bound?: number | null;
+  upper_bound?: number | null;
+}
+
+export interface RatingLabelCount {
+  label: string;
+  numeric_value: number;
+  count: number;
 }
 
 export interface CompetencyStats {
   competency: Competency;
   average_score: number;
+  std_dev?: number;
+  employee_count: number;
+}
+
+export interface CategoryStats {
+  category: CompetencyCategory;
+  average_score: number;
+  std_dev: number;
   employee_count: number;
+  competency_count: number;
+}
+
+export interface ScoreSpread {
+  min: number;
+  q1: number;
+  median: number;
+  q3: number;
+  max: number;
+  std_dev: number;
 }
 
 export interface DatasetStats {
@@ -207,8 +377,11 @@ export interface DatasetStats {
   total_competencies: number;
   total_scores: number;
   average_score: number;
+  score_spread?: ScoreSpread | null;
   score_distribution: ScoreDistribution[];
+  rating_distribution?: RatingLabelCount[];
   competency_stats: CompetencyStats[];
+  category_stats?: CategoryStats[];
 }
 
 export interface DatasetSummary {
@@ -252,6 +425,13 @@ export interface DatasetComparison {
   average_delta: number;
 }
 
+export interface CompetencyCorrelations {
+  competencies: Competency[];
+  // matrix[i][j] correlates competencies[i] with competencies[j]; null when too few employees
+  matrix: (number | null)[][];
+  pair_counts: number[][];
+}
+
 export type PositionStatus = 'Staff' | 'Eselon';
 
 export interface EmployeeWithStats {
@@ -266,11 +446,52 @@ export interface EmployeeWithStats {
   position_status: PositionStatus;
   average_score: number;
   score_count: number;
+  percentile?: number | null;
+  weighted_score?: number | null;
+  completeness?: number | null;
+  data_quality?: EmployeeDataQuality | null;
+}
+
+export type DataQualityIssue =
+  | 'missing_nip'
+  | 'unparsed_gol'
+  | 'non_canonical_jabatan'
+  | 'missing_photo'
+  | 'duplicate_suspect';
+
+export interface EmployeeDataQuality {
+  score: number;
+  issues: DataQualityIssue[];
 }
 
+export interface CompetencyGap {
+  competency: Competency;
+  missing_count: number;
+  missing_percent: number;
+}
+
+// Official exports are refused while export_blockers is not empty
+export interface DatasetQuality {
+  dataset_id: number;
+  employee_count: number;
+  competency_count: number;
+  complete_employee_percent: number;
+  numeric_score_percent: number;
+  unmapped_score_count: number;
+  employees_without_scores: Employee[];
+  competency_gaps: CompetencyGap[];
+  export_blockers: string[];
+}
+
+export type EmployeeListColumn = 'percentile' | 'weighted_score' | 'completeness' | 'data_quality';
+
 export interface EmployeeListResult {
   employees: EmployeeWithStats[];
   total_count: number;
+  page_size?: number;
+  default_page_size?: number;
+  max_page_size?: number;
+  columns?: EmployeeListColumn[];
 }
 
 export interface ScoreWithCompetency {
@@ -280,10 +501,78 @@ export interface ScoreWithCompetency {
 
 export interface EmployeePerformance {
   employee: Employee;
+  position_type?: 'eselon' | 'staff';
   scores: ScoreWithCompetency[];
   average_score: number;
   strengths: string[];
   gaps: string[];
+  score_scale?: number | null;
+  category_averages?: CategoryAverage[];
+  leadership_score?: number | null;
+  attendance_percentage?: number | null;
+}
+
+export interface CategoryAverage {
+  category: CompetencyCategory;
+  average_score: number;
+  competency_count: number;
+}
+
+export interface EmployeeDeletionCounts {
+  employees: number;
+  dependents: Record<string, number>;
+}
+
+export interface RelinkResult {
+  moved_scores: number;
+  conflicting_scores: number;
+}
+
+export interface ParsedLeadershipScore {
+  employee_name: string;
+  score: number;
+  source_row?: number | null;
+}
+
+export interface LeadershipImportResult {
+  imported: number;
+  unmatched_names: string[];
+}
+
+export interface EmployeeNote {
+  id: number;
+  dataset_id: number;
+  employee_id: number;
+  content: string;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface Webhook {
+  id: number;
+  url: string;
+  enabled: boolean;
+  secret: string; // HMAC-SHA256 key of the X-Webhook-Signature header
+  last_status: 'delivered' | 'failed' | null;
+  last_error: string | null;
+  last_attempt_at: string | null;
+  created_at: string;
+}
+
+export interface Attendance {
+  id: number;
+  employee_id: number;
+  month: string;
+  working_days: number;
+  present_days: number;
+  late_days: number;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface AttendanceImportResult {
+  imported: number;
+  unmatched: string[];
 }
 
 export interface UpdateEmployee {
@@ -328,12 +617,20 @@ export interface ValidationStats {
   can_import: boolean;
 }
 
+export interface UnknownJabatanIssue {
+  employee_index: number;
+  employee_name: string;
+  jabatan: string;
+  suggestion?: string | null;
+}
+
 export interface ImportValidationSummary {
   stats: ValidationStats;
   duplicate_employees: DuplicateEmployeeGroup[];
   orphan_scores: OrphanScoreIssue[];
   unmapped_ratings: UnmappedRatingIssue[];
   blank_employee_names: BlankEmployeeNameIssue[];
+  unknown_jabatan?: UnknownJabatanIssue[];
 }
 
 export type SortDirection = 'asc' | 'desc';
@@ -342,3 +639,83 @@ export interface SortState<TColumn extends string = string> {
   column: TColumn;
   direction: SortDirection;
 }
+
+export interface DemoDataSummary {
+  dataset_ids: number[];
+  employee_count: number;
+  competency_count: number;
+  score_count: number;
+}
+
+export interface PendingMigration {
+  version: number;
+  description: string;
+}
+
+export interface SchemaInfo {
+  current_version: number | null;
+  latest_version: number;
+  pending: PendingMigration[];
+  unknown_versions: number[]; // applied by a newer version of the app
+}
+
+// Payload of the `export://progress` event
+export interface ExportProgress {
+  export_id: string;
+  dataset_id: number;
+  completed: number;
+  total: number;
+}
+
+export interface RetentionSettings {
+  enabled: boolean; // purge automatically during maintenance
+  keep_periods: number;
+}
+
+export interface PurgeCandidate {
+  dataset: Dataset;
+  employee_count: number;
+  score_count: number;
+  summary_count: number;
+}
+
+export interface PurgeResult {
+  deleted_dataset_ids: number[];
+  skipped_dataset_ids: number[]; // busy with an import or merge, purged later
+  backup_file: string | null;
+}
+
+export interface CompetencyAlias {
+  alias_key: string; // lowercase name without numbering or punctuation
+  competency_id: number;
+}
+
+export interface ScoreComponent {
+  parameter: string;
+  raw_score: number;
+  weight_percentage: number;
+  weighted_score: number;
+}
+
+export interface WeightedScore {
+  position_type: 'eselon' | 'staff';
+  normalization_scale: number;
+  competencies: { competency_id: number; name: string; raw_score: number; original_score: number }[];
+  sections: { title: string; cap: number; subtotal: number; breakdown: ScoreComponent[] }[];
+  leadership: { raw_score: number; weighted_score: number; applied: boolean } | null;
+  total_score: number;
+  total_cap: number;
+  rating: string;
+  unmapped_competencies: string[];
+}
+
+export interface ScoreOverrides {
+  competencies?: Record<number, number>; // competency id -> value on the dataset's scale
+  leadership_score?: number | null;
+}
+
+export interface ScoreSimulation {
+  current: WeightedScore;
+  simulated: WeightedScore;
+  delta: number;
+}